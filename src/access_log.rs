@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, HeaderName, Request, Response};
+use axum::Router;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{MakeSpan, OnResponse, TraceLayer};
+use tracing::Span;
+
+use crate::api::auth::hash_token;
+
+const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// Hex characters of the token hash kept in the log; enough to tell callers apart without
+/// making the log a token oracle.
+const IDENTITY_HASH_LEN: usize = 12;
+
+/// Wraps `router` so every request logs one `request completed` line at `info`.
+///
+/// Requests keep a caller-supplied `X-Request-Id` or get a fresh UUID, and the id is echoed
+/// back on the response so clients can quote it in bug reports.
+pub fn layer<S>(router: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(AccessSpan)
+                .on_request(())
+                .on_response(AccessLine)
+                .on_failure(()),
+        )
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+}
+
+#[derive(Clone, Copy, Debug)]
+struct AccessSpan;
+
+impl<B> MakeSpan<B> for AccessSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        let request_id = request
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            path = %loggable_path(request.uri().path()),
+            request_id,
+            identity = %identity_hash(request.headers()),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct AccessLine;
+
+impl<B> OnResponse<B> for AccessLine {
+    fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX),
+            "request completed"
+        );
+    }
+}
+
+/// The request path with share link tokens, which grant access on their own, replaced by `…`.
+fn loggable_path(path: &str) -> String {
+    let mut segments: Vec<&str> = path.split('/').collect();
+    for index in 1..segments.len() {
+        if segments[index - 1] == "share" {
+            segments[index] = "…";
+        }
+    }
+    segments.join("/")
+}
+
+/// A short hash of the bearer token, or `anonymous` when the request carries none.
+fn identity_hash(headers: &HeaderMap) -> String {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            let (scheme, token) = value.split_once(' ')?;
+            let token = token.trim();
+            (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+        })
+        .map(|token| hash_token(token)[..IDENTITY_HASH_LEN].to_string())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::header::AUTHORIZATION;
+    use axum::http::{HeaderMap, HeaderValue, Request};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use super::{identity_hash, layer, loggable_path};
+
+    #[tokio::test]
+    async fn requests_get_an_echoed_request_id() {
+        let app = layer(Router::new().route("/ping", get(|| async { "pong" })));
+
+        let generated = app
+            .clone()
+            .oneshot(Request::get("/ping").body(Body::empty()).unwrap())
+            .await
+            .expect("request should succeed");
+        let generated_id = generated
+            .headers()
+            .get("x-request-id")
+            .expect("request id should be set");
+        assert_eq!(generated_id.len(), 36);
+
+        let supplied = app
+            .oneshot(
+                Request::get("/ping")
+                    .header("x-request-id", "trace-me")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .expect("request should succeed");
+        assert_eq!(supplied.headers()["x-request-id"], "trace-me");
+    }
+
+    #[test]
+    fn identity_is_a_short_token_hash() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(identity_hash(&headers), "anonymous");
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        let identity = identity_hash(&headers);
+        assert_eq!(identity.len(), 12);
+        assert!(!identity.contains("secret"));
+    }
+
+    #[test]
+    fn share_tokens_are_left_out_of_logged_paths() {
+        assert_eq!(
+            loggable_path("/api/v1/share/abc123/tasks/HOOKS-1"),
+            "/api/v1/share/…/tasks/HOOKS-1"
+        );
+        assert_eq!(
+            loggable_path("/api/v1/projects/HOOKS/share-links"),
+            "/api/v1/projects/HOOKS/share-links"
+        );
+    }
+}
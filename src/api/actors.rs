@@ -0,0 +1,103 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::api::auth::AccessScope;
+use crate::api::events::{map_task_event, TaskEventPayload};
+use crate::api::json::ApiJson;
+use crate::api::ListQuery;
+use crate::db::models::ActorRecord;
+use crate::db::queries;
+use crate::db::queries::UpdateActorInput;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/actors", get(list_actors))
+        .route("/actors/{actor}", get(get_actor).patch(update_actor))
+        .route("/actors/{actor}/activity", get(get_actor_activity))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateActorRequest {
+    display_name: Option<String>,
+    kind: Option<String>,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ActorActivityResponse {
+    actor: ActorRecord,
+    events: Vec<TaskEventPayload>,
+    limit: i64,
+    offset: i64,
+}
+
+async fn list_actors(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Query(query): Query<ListQuery>,
+) -> AppResult<Json<Vec<ActorRecord>>> {
+    let (limit, offset) = query.normalize()?;
+    let actors = queries::list_actors(&state.db, scope.workspace_id(), limit, offset).await?;
+    Ok(Json(actors))
+}
+
+async fn get_actor(
+    State(state): State<AppState>,
+    Path(actor): Path<String>,
+) -> AppResult<Json<ActorRecord>> {
+    let actor = queries::get_actor(&state.db, &actor).await?;
+    Ok(Json(actor))
+}
+
+async fn get_actor_activity(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(actor): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> AppResult<Json<ActorActivityResponse>> {
+    let (limit, offset) = query.normalize()?;
+    let actor = queries::get_actor(&state.db, &actor).await?;
+    let events =
+        queries::list_actor_activity(&state.db, scope.workspace_id(), &actor.id, limit, offset)
+            .await?;
+
+    Ok(Json(ActorActivityResponse {
+        actor,
+        events: events.into_iter().map(map_task_event).collect(),
+        limit,
+        offset,
+    }))
+}
+
+async fn update_actor(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(actor): Path<String>,
+    ApiJson(request): ApiJson<UpdateActorRequest>,
+) -> AppResult<Json<ActorRecord>> {
+    // Actors are shared across workspaces, so only instance tokens may rename them.
+    scope.require_instance()?;
+
+    if request.display_name.is_none() && request.kind.is_none() && request.avatar_url.is_none() {
+        return Err(AppError::BadRequest(
+            "at least one field must be provided".to_string(),
+        ));
+    }
+
+    let updated = queries::update_actor(
+        &state.db,
+        &actor,
+        UpdateActorInput {
+            display_name: request.display_name,
+            kind: request.kind,
+            avatar_url: request.avatar_url,
+        },
+    )
+    .await?;
+
+    Ok(Json(updated))
+}
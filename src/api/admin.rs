@@ -0,0 +1,814 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+
+use crate::api::auth::AccessScope;
+use crate::client_ip::ClientIp;
+use crate::db::queries;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+use crate::webhooks;
+use crate::webhooks::health::DispatcherReport;
+
+const BACKUP_FORMAT: &str = "lattice-backup";
+const BACKUP_VERSION: i64 = 1;
+const MANIFEST_PATH: &str = "manifest.json";
+const TABLE_PREFIX: &str = "db/";
+const ATTACHMENT_PREFIX: &str = "attachments/";
+
+type TableRows = Vec<serde_json::Map<String, Value>>;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/admin/export", get(export_instance))
+        .route("/admin/import", post(import_instance))
+        .route("/admin/stats", get(get_instance_stats))
+        .route("/admin/sse", get(list_sse_streams))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupManifest {
+    format: String,
+    version: i64,
+    schema_version: i64,
+    created_at: String,
+    tables: BTreeMap<String, usize>,
+    attachments: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportQuery {
+    replace: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub schema_version: i64,
+    pub tables: BTreeMap<String, usize>,
+    pub attachments: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct InstanceStatsResponse {
+    counts: BTreeMap<&'static str, i64>,
+    webhook_dispatcher: DispatcherReport,
+}
+
+#[derive(Debug, Serialize)]
+struct SseStreamsResponse {
+    active: usize,
+    max_global: u32,
+    max_per_identity: u32,
+    streams: Vec<SseStreamResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct SseStreamResponse {
+    id: u64,
+    identity: String,
+    workspace_id: Option<String>,
+    projects: Vec<String>,
+    connected_at: String,
+    connected_secs: u64,
+    events_sent: u64,
+    /// The stream's `connection` label in `GET /metrics`.
+    connection: Option<u64>,
+}
+
+struct BackupContents {
+    manifest: Option<BackupManifest>,
+    tables: HashMap<String, TableRows>,
+    attachments: Vec<String>,
+}
+
+/// A scratch file or directory under the storage dir that is removed once dropped.
+struct ScratchPath(PathBuf);
+
+impl Drop for ScratchPath {
+    fn drop(&mut self) {
+        let result = if self.0.is_dir() {
+            std::fs::remove_dir_all(&self.0)
+        } else {
+            std::fs::remove_file(&self.0)
+        };
+        if let Err(error) = result {
+            if error.kind() != ErrorKind::NotFound {
+                tracing::warn!(error = ?error, path = %self.0.display(), "failed to remove scratch path");
+            }
+        }
+    }
+}
+
+/// Row counts for every table plus the webhook dispatcher's liveness and lag.
+async fn get_instance_stats(
+    State(state): State<AppState>,
+    scope: AccessScope,
+) -> AppResult<Json<InstanceStatsResponse>> {
+    scope.require_instance()?;
+
+    Ok(Json(InstanceStatsResponse {
+        counts: queries::instance_row_counts(&state.db).await?,
+        webhook_dispatcher: webhooks::dispatcher_report(&state).await?,
+    }))
+}
+
+async fn list_sse_streams(
+    State(state): State<AppState>,
+    scope: AccessScope,
+) -> AppResult<Json<SseStreamsResponse>> {
+    scope.require_instance()?;
+
+    let (max_global, max_per_identity) = state.rate_limiter.sse_limits();
+    let streams: Vec<SseStreamResponse> = state
+        .rate_limiter
+        .sse_streams()
+        .into_iter()
+        .map(|stream| SseStreamResponse {
+            id: stream.id,
+            identity: stream.identity,
+            workspace_id: stream.workspace_id,
+            projects: stream.projects,
+            connected_at: stream
+                .connected_at
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            connected_secs: stream.connected_for.as_secs(),
+            events_sent: stream
+                .metrics_connection
+                .and_then(|connection| state.metrics.sse_events_sent(connection))
+                .unwrap_or(0),
+            connection: stream.metrics_connection,
+        })
+        .collect();
+    Ok(Json(SseStreamsResponse {
+        active: streams.len(),
+        max_global,
+        max_per_identity,
+        streams,
+    }))
+}
+
+/// Streams a tarball with every table as JSON plus the attachment blobs.
+async fn export_instance(State(state): State<AppState>, scope: AccessScope) -> AppResult<Response> {
+    scope.require_instance()?;
+
+    let tables = queries::export_backup_tables(&state.db).await?;
+    let storage_paths: Vec<String> = tables
+        .iter()
+        .filter(|(table, _)| *table == "attachments")
+        .flat_map(|(_, rows)| rows.iter())
+        .filter_map(|row| row.get("storage_path").and_then(Value::as_str))
+        .map(ToOwned::to_owned)
+        .collect();
+    let created_at = Utc::now();
+    let manifest = BackupManifest {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        schema_version: queries::schema_version(&state.db).await?,
+        created_at: created_at.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        tables: tables
+            .iter()
+            .map(|(table, rows)| (table.to_string(), rows.len()))
+            .collect(),
+        attachments: storage_paths.len(),
+    };
+
+    let storage_dir = state.config.storage_dir.clone();
+    let scratch = ScratchPath(storage_dir.join(format!(".export-{}.tar", Uuid::new_v4())));
+    let archive_path = scratch.0.clone();
+    let file = tokio::task::spawn_blocking(move || {
+        write_archive(
+            &archive_path,
+            &manifest,
+            &tables,
+            &storage_dir,
+            &storage_paths,
+        )
+    })
+    .await
+    .map_err(|error| {
+        tracing::error!(error = ?error, "backup export task failed");
+        AppError::Internal
+    })?
+    .map_err(|error| {
+        tracing::error!(error = ?error, "failed to write backup archive");
+        AppError::Internal
+    })?;
+
+    let stream = ReaderStream::new(tokio::fs::File::from_std(file)).map(move |chunk| {
+        let _keep_until_streamed = &scratch;
+        chunk
+    });
+    let mut response = Response::new(Body::from_stream(stream));
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-tar"));
+    if let Ok(disposition) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"lattice-backup-{}.tar\"",
+        created_at.format("%Y%m%dT%H%M%SZ")
+    )) {
+        headers.insert(CONTENT_DISPOSITION, disposition);
+    }
+
+    Ok(response)
+}
+
+/// Restores a tarball produced by `export_instance`, replacing every table.
+///
+/// Refuses to touch an instance that already has projects unless `?replace=true` is passed.
+async fn import_instance(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    client_ip: ClientIp,
+    Query(query): Query<ImportQuery>,
+    headers: HeaderMap,
+    body: Body,
+) -> AppResult<(StatusCode, Json<ImportResponse>)> {
+    scope.require_instance()?;
+
+    if !query.replace.unwrap_or(false) && queries::count_projects(&state.db, None, None).await? > 0
+    {
+        return Err(AppError::Conflict(
+            "instance already has projects; pass replace=true to overwrite them".to_string(),
+        ));
+    }
+
+    let storage_dir = state.config.storage_dir.clone();
+    tokio::fs::create_dir_all(&storage_dir)
+        .await
+        .map_err(storage_error)?;
+    let import_id = Uuid::new_v4();
+    let spool = ScratchPath(storage_dir.join(format!(".import-{import_id}.tar")));
+    let staging = ScratchPath(storage_dir.join(format!(".import-{import_id}")));
+
+    let mut spool_file = tokio::fs::File::create(&spool.0)
+        .await
+        .map_err(storage_error)?;
+    let mut chunks = body.into_data_stream();
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|error| {
+            tracing::warn!(error = ?error, "failed to read backup upload");
+            AppError::BadRequest("failed to read backup upload".to_string())
+        })?;
+        spool_file.write_all(&chunk).await.map_err(storage_error)?;
+    }
+    spool_file.flush().await.map_err(storage_error)?;
+    drop(spool_file);
+
+    let spool_path = spool.0.clone();
+    let staging_path = staging.0.clone();
+    let contents = tokio::task::spawn_blocking(move || read_archive(&spool_path, &staging_path))
+        .await
+        .map_err(|error| {
+            tracing::error!(error = ?error, "backup import task failed");
+            AppError::Internal
+        })??;
+
+    let manifest = contents
+        .manifest
+        .ok_or_else(|| AppError::BadRequest(format!("backup is missing {MANIFEST_PATH}")))?;
+    if manifest.format != BACKUP_FORMAT || manifest.version != BACKUP_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "unsupported backup format '{}' version {}",
+            manifest.format, manifest.version
+        )));
+    }
+    let schema_version = queries::schema_version(&state.db).await?;
+    if manifest.schema_version != schema_version {
+        return Err(AppError::BadRequest(format!(
+            "backup schema version {} does not match this server's schema version {schema_version}",
+            manifest.schema_version
+        )));
+    }
+
+    let previous_paths = queries::list_attachment_storage_paths(&state.db).await?;
+    queries::restore_backup_tables(
+        &state.db,
+        &contents.tables,
+        &actor_from_headers(&headers),
+        client_ip.address().as_deref(),
+    )
+    .await?;
+
+    for name in &contents.attachments {
+        tokio::fs::rename(staging.0.join(name), storage_dir.join(name))
+            .await
+            .map_err(storage_error)?;
+    }
+    for path in previous_paths
+        .iter()
+        .filter(|path| !contents.attachments.contains(path))
+    {
+        if let Err(error) = tokio::fs::remove_file(storage_dir.join(path)).await {
+            if error.kind() != ErrorKind::NotFound {
+                tracing::warn!(error = ?error, path, "failed to remove replaced attachment file");
+            }
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(ImportResponse {
+            schema_version,
+            tables: contents
+                .tables
+                .iter()
+                .map(|(table, rows)| (table.clone(), rows.len()))
+                .collect(),
+            attachments: contents.attachments.len(),
+        }),
+    ))
+}
+
+fn write_archive(
+    path: &FsPath,
+    manifest: &BackupManifest,
+    tables: &[(&str, TableRows)],
+    storage_dir: &FsPath,
+    storage_paths: &[String],
+) -> std::io::Result<File> {
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    let mut builder = tar::Builder::new(file);
+
+    append_json(&mut builder, MANIFEST_PATH, manifest)?;
+    for (table, rows) in tables {
+        append_json(&mut builder, &format!("{TABLE_PREFIX}{table}.json"), rows)?;
+    }
+    for storage_path in storage_paths {
+        if !is_safe_blob_name(storage_path) {
+            tracing::warn!(storage_path, "skipped unsafe storage path in backup");
+            continue;
+        }
+        match File::open(storage_dir.join(storage_path)) {
+            Ok(mut blob) => {
+                builder.append_file(format!("{ATTACHMENT_PREFIX}{storage_path}"), &mut blob)?
+            }
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                tracing::warn!(storage_path, "attachment file missing from backup");
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let mut file = builder.into_inner()?;
+    file.flush()?;
+    file.seek(SeekFrom::Start(0))?;
+    Ok(file)
+}
+
+fn append_json<T: Serialize + ?Sized>(
+    builder: &mut tar::Builder<File>,
+    path: &str,
+    value: &T,
+) -> std::io::Result<()> {
+    let bytes = serde_json::to_vec_pretty(value)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, path, bytes.as_slice())
+}
+
+/// Reads the manifest and table dumps into memory and unpacks attachment blobs into
+/// `staging`, so nothing touches live storage until the database restore has committed.
+fn read_archive(path: &FsPath, staging: &FsPath) -> AppResult<BackupContents> {
+    let file = File::open(path).map_err(storage_error)?;
+    std::fs::create_dir_all(staging).map_err(storage_error)?;
+
+    let mut contents = BackupContents {
+        manifest: None,
+        tables: HashMap::new(),
+        attachments: Vec::new(),
+    };
+    let mut archive = tar::Archive::new(file);
+    for entry in archive.entries().map_err(invalid_archive)? {
+        let mut entry = entry.map_err(invalid_archive)?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let name = entry
+            .path()
+            .map_err(invalid_archive)?
+            .to_string_lossy()
+            .into_owned();
+
+        if name == MANIFEST_PATH {
+            contents.manifest = Some(read_json(&mut entry, &name)?);
+        } else if let Some(table) = name
+            .strip_prefix(TABLE_PREFIX)
+            .and_then(|rest| rest.strip_suffix(".json"))
+        {
+            let rows: TableRows = read_json(&mut entry, &name)?;
+            contents.tables.insert(table.to_string(), rows);
+        } else if let Some(blob) = name
+            .strip_prefix(ATTACHMENT_PREFIX)
+            .filter(|blob| is_safe_blob_name(blob))
+        {
+            let mut output = File::create(staging.join(blob)).map_err(storage_error)?;
+            std::io::copy(&mut entry, &mut output).map_err(invalid_archive)?;
+            contents.attachments.push(blob.to_string());
+        } else {
+            return Err(AppError::BadRequest(format!(
+                "unexpected backup entry '{name}'"
+            )));
+        }
+    }
+
+    Ok(contents)
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(entry: impl Read, name: &str) -> AppResult<T> {
+    serde_json::from_reader(entry)
+        .map_err(|error| AppError::BadRequest(format!("invalid backup entry '{name}': {error}")))
+}
+
+/// Attachment blobs are stored flat under the storage dir as `<uuid>.blob`.
+fn is_safe_blob_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+}
+
+fn invalid_archive(error: std::io::Error) -> AppError {
+    tracing::warn!(error = ?error, "invalid backup archive");
+    AppError::BadRequest("invalid backup archive".to_string())
+}
+
+fn storage_error(error: std::io::Error) -> AppError {
+    tracing::error!(error = ?error, "backup storage error");
+    AppError::Internal
+}
+
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use reqwest::StatusCode;
+    use serde_json::Value;
+    use sqlx::AnyPool;
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::db::queries::{NewAttachmentInput, NewTaskInput};
+    use crate::state::AppState;
+    use crate::webhooks;
+
+    async fn spawn_instance(dir: &Path) -> (AnyPool, String) {
+        let storage_dir = dir.join("storage");
+        std::fs::create_dir_all(&storage_dir).expect("storage dir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", dir.join("lattice.db").display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir,
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+
+        let state = AppState::new(config, pool.clone());
+        webhooks::spawn_dispatcher(state.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .route("/readyz", get(api::readyz))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                crate::rate_limit::enforce_limits,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        tokio::spawn(async move {
+            let _ = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await;
+        });
+
+        (pool, format!("http://{addr}/api/v1"))
+    }
+
+    #[tokio::test]
+    async fn stats_and_readiness_report_the_webhook_dispatcher() {
+        let dir = tempdir().expect("tempdir should be created");
+        let (pool, api) = spawn_instance(dir.path()).await;
+        queries::create_project_with_slug(&pool, "Stats", "count me", "STATS")
+            .await
+            .expect("project should be created");
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+
+        let readyz = format!("{}/readyz", api.trim_end_matches("/api/v1"));
+        let mut ready = None;
+        for _ in 0..50 {
+            let response = client
+                .get(&readyz)
+                .send()
+                .await
+                .expect("readyz should complete");
+            if response.status() == StatusCode::OK {
+                ready = Some(response.json::<Value>().await.expect("body should parse"));
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        let ready = ready.expect("dispatcher should become ready");
+        assert_eq!(ready["status"], "ready");
+        assert_eq!(ready["database"], true);
+        assert_eq!(ready["webhook_dispatcher"]["status"], "running");
+
+        let stats: Value = client
+            .get(format!("{api}/admin/stats"))
+            .send()
+            .await
+            .expect("stats should complete")
+            .json()
+            .await
+            .expect("stats body should parse");
+        assert_eq!(stats["counts"]["projects"], 1);
+        assert_eq!(stats["counts"]["tasks"], 0);
+        assert_eq!(stats["webhook_dispatcher"]["healthy"], true);
+        assert_eq!(stats["webhook_dispatcher"]["restarts"], 0);
+        assert_eq!(stats["webhook_dispatcher"]["lag_secs"], 0);
+    }
+
+    #[tokio::test]
+    async fn sse_listing_shows_open_streams_until_they_close() {
+        let dir = tempdir().expect("tempdir should be created");
+        let (pool, api) = spawn_instance(dir.path()).await;
+        queries::create_project_with_slug(&pool, "Live", "watch me", "LIVE")
+            .await
+            .expect("project should be created");
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let list_streams = || async {
+            client
+                .get(format!("{api}/admin/sse"))
+                .send()
+                .await
+                .expect("sse listing should complete")
+                .json::<Value>()
+                .await
+                .expect("sse listing should parse")
+        };
+
+        let stream = reqwest::Client::new()
+            .get(format!("{api}/projects/LIVE/events"))
+            .send()
+            .await
+            .expect("stream should open");
+        assert_eq!(stream.status(), StatusCode::OK);
+
+        let listing = list_streams().await;
+        assert_eq!(listing["active"], 1);
+        assert_eq!(listing["max_global"], 400);
+        let open = &listing["streams"][0];
+        assert_eq!(open["identity"], "ip:127.0.0.1");
+        assert_eq!(open["projects"], serde_json::json!(["LIVE"]));
+        assert_eq!(open["events_sent"], 0);
+        assert!(open["connected_secs"].is_u64());
+
+        drop(stream);
+        let mut active = None;
+        for _ in 0..50 {
+            active = list_streams().await["active"].as_u64();
+            if active == Some(0) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(active, Some(0), "closing the stream frees its slot");
+    }
+
+    #[tokio::test]
+    async fn export_restores_into_another_instance() {
+        let source_dir = tempdir().expect("tempdir should be created");
+        let target_dir = tempdir().expect("tempdir should be created");
+        let (source_pool, source_api) = spawn_instance(source_dir.path()).await;
+        let (target_pool, target_api) = spawn_instance(target_dir.path()).await;
+
+        queries::create_project_with_slug(&source_pool, "Backup", "move hosts", "BACKUP")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &source_pool,
+            &FieldLimitConfig::default(),
+            "BACKUP",
+            NewTaskInput {
+                title: "survive the move".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "high".to_string(),
+                review_state: "ready".to_string(),
+                labels: vec!["ops".to_string()],
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let epic = queries::create_task(
+            &source_pool,
+            &FieldLimitConfig::default(),
+            "BACKUP",
+            NewTaskInput {
+                title: "move hosts".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "high".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        // The parent is created after its child, so a plain row-order restore would fail.
+        queries::set_task_parent(&source_pool, "BACKUP", &task.id, Some(&epic.id), "human")
+            .await
+            .expect("parent should be set");
+        queries::add_task_dependency(&source_pool, "BACKUP", &task.id, &epic.id, "human")
+            .await
+            .expect("dependency should be added");
+        let milestone = queries::create_milestone(
+            &source_pool,
+            "BACKUP",
+            queries::CreateMilestoneInput {
+                name: "cutover".to_string(),
+                target_date: None,
+            },
+        )
+        .await
+        .expect("milestone should be created");
+        queries::set_task_milestone(
+            &source_pool,
+            "BACKUP",
+            &task.id,
+            Some(&milestone.id),
+            "human",
+        )
+        .await
+        .expect("milestone should be set");
+        std::fs::write(
+            source_dir.path().join("storage/att-1.blob"),
+            b"attachment bytes",
+        )
+        .expect("blob should be written");
+        queries::create_attachment(
+            &source_pool,
+            "BACKUP",
+            &task.id,
+            NewAttachmentInput {
+                id: "att-1".to_string(),
+                filename: "notes.txt".to_string(),
+                content_type: "text/plain".to_string(),
+                size_bytes: 16,
+                storage_path: "att-1.blob".to_string(),
+                uploaded_by: "human".to_string(),
+            },
+        )
+        .await
+        .expect("attachment should be created");
+        queries::create_project_with_slug(&target_pool, "Stale", "replaced", "STALE")
+            .await
+            .expect("project should be created");
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let export = client
+            .get(format!("{source_api}/admin/export"))
+            .send()
+            .await
+            .expect("export should complete");
+        assert_eq!(export.status(), StatusCode::OK);
+        assert_eq!(export.headers()["content-type"], "application/x-tar");
+        let archive = export.bytes().await.expect("export body should read");
+
+        let refused = client
+            .post(format!("{target_api}/admin/import"))
+            .body(archive.clone())
+            .send()
+            .await
+            .expect("import should complete");
+        assert_eq!(refused.status(), StatusCode::CONFLICT);
+
+        let imported = client
+            .post(format!("{target_api}/admin/import?replace=true"))
+            .body(archive)
+            .send()
+            .await
+            .expect("import should complete");
+        assert_eq!(imported.status(), StatusCode::OK);
+        let summary: Value = imported.json().await.expect("import body should parse");
+        assert_eq!(summary["tables"]["tasks"], 2);
+        assert_eq!(summary["attachments"], 1);
+
+        queries::get_project(&target_pool, "BACKUP")
+            .await
+            .expect("restored project should exist");
+        assert!(queries::get_project(&target_pool, "STALE").await.is_err());
+        let restored = queries::get_task_details(&target_pool, "BACKUP", &task.id)
+            .await
+            .expect("restored task should exist");
+        assert_eq!(restored.task.title, "survive the move");
+        assert_eq!(restored.labels, vec!["ops".to_string()]);
+        assert_eq!(
+            restored.task.parent_task_id.as_deref(),
+            Some(epic.id.as_str())
+        );
+        assert_eq!(
+            restored.task.milestone_id.as_deref(),
+            Some(milestone.id.as_str())
+        );
+        assert_eq!(restored.dependencies.blocked_by[0].task_id, epic.id);
+
+        let file = client
+            .get(format!("{target_api}/files/att-1"))
+            .send()
+            .await
+            .expect("download should complete");
+        assert_eq!(file.status(), StatusCode::OK);
+        assert_eq!(
+            file.bytes().await.expect("file body should read").as_ref(),
+            b"attachment bytes"
+        );
+
+        let audit = queries::list_audit_events(&target_pool, 10, 0)
+            .await
+            .expect("audit events should load");
+        assert!(audit
+            .iter()
+            .any(|event| event.action == "instance.imported"));
+    }
+}
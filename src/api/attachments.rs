@@ -1,21 +1,65 @@
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::{Component, Path as FsPath, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
 
-use axum::body::Body;
-use axum::extract::{Multipart, Path, State};
+use axum::body::{Body, Bytes};
+use axum::extract::{Multipart, Path, Query, State};
 use axum::http::header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE};
-use axum::http::{HeaderMap, HeaderValue, StatusCode};
-use axum::response::Response;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{delete, get, post};
 use axum::{Json, Router};
+use chrono::{Duration, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use uuid::Uuid;
 
-use crate::db::models::AttachmentRecord;
+use crate::api::json::ApiJson;
+use crate::db::models::{AttachmentRecord, AttachmentUploadRecord};
 use crate::db::queries;
-use crate::db::queries::NewAttachmentInput;
+use crate::db::queries::{NewAttachmentInput, NewAttachmentUploadInput};
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 
+/// Where a resumable upload is in its file, sent on `PATCH` and echoed on every upload response.
+const UPLOAD_OFFSET: HeaderName = HeaderName::from_static("upload-offset");
+/// Uploads with no chunk for this long are dropped, along with their partial files.
+const UPLOAD_EXPIRY_HOURS: i64 = 24;
+/// Preview sizes in KiB, when `max_kb` is left out and at most.
+const DEFAULT_PREVIEW_KB: usize = 16;
+const MAX_PREVIEW_KB: usize = 256;
+
+/// One lock per upload being written, so requests for the same upload take turns.
+#[derive(Clone, Default)]
+pub struct UploadLocks(Arc<Mutex<HashMap<String, Weak<tokio::sync::Mutex<()>>>>>);
+
+impl UploadLocks {
+    /// Waits for the other requests on `upload_id`. Entries go away once nobody holds them.
+    pub async fn lock(&self, upload_id: &str) -> tokio::sync::OwnedMutexGuard<()> {
+        let upload = {
+            let mut locks = lock(&self.0);
+            locks.retain(|_, upload| upload.strong_count() > 0);
+            match locks.get(upload_id).and_then(Weak::upgrade) {
+                Some(upload) => upload,
+                None => {
+                    let upload = Arc::new(tokio::sync::Mutex::new(()));
+                    locks.insert(upload_id.to_string(), Arc::downgrade(&upload));
+                    upload
+                }
+            }
+        };
+        upload.lock_owned().await
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route(
@@ -26,7 +70,20 @@ pub fn router() -> Router<AppState> {
             "/projects/{slug}/tasks/{task_ref}/attachments/{attachment_id}",
             delete(delete_attachment),
         )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/attachments/uploads",
+            post(create_upload),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/attachments/uploads/{upload_id}",
+            get(get_upload).patch(append_upload).delete(abort_upload),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/attachments/uploads/{upload_id}/complete",
+            post(complete_upload),
+        )
         .route("/files/{id}", get(download_attachment))
+        .route("/files/{id}/preview", get(preview_attachment))
 }
 
 async fn upload_attachment(
@@ -128,6 +185,302 @@ async fn upload_attachment(
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct CreateUploadRequest {
+    filename: String,
+    content_type: Option<String>,
+    size_bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct UploadResponse {
+    id: String,
+    task_id: String,
+    filename: String,
+    content_type: String,
+    size_bytes: i64,
+    /// Bytes received so far; the next chunk starts here.
+    offset: i64,
+    expires_at: String,
+    created_at: String,
+    updated_at: String,
+}
+
+async fn create_upload(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(request): ApiJson<CreateUploadRequest>,
+) -> AppResult<Response> {
+    if u64::try_from(request.size_bytes).is_ok_and(|size| size > state.config.max_file_size) {
+        return Err(AppError::BadRequest(format!(
+            "file exceeds max size of {} bytes",
+            state.config.max_file_size
+        )));
+    }
+    sweep_stale_uploads(&state).await;
+
+    let filename = sanitize_filename(&request.filename);
+    let content_type = request
+        .content_type
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| guess_mime_type(&filename));
+    let upload = queries::create_attachment_upload(
+        &state.db,
+        &slug,
+        &task_ref,
+        NewAttachmentUploadInput {
+            id: Uuid::new_v4().to_string(),
+            filename,
+            content_type,
+            size_bytes: request.size_bytes,
+            uploaded_by: actor_from_headers(&headers),
+        },
+    )
+    .await?;
+
+    let path = upload_file_path(&state, &upload.id)?;
+    let created = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::File::create(&path).await.map(drop)
+    }
+    .await;
+    if let Err(error) = created {
+        tracing::error!(error = ?error, path = %path.display(), "failed to create upload file");
+        queries::delete_attachment_upload(&state.db, &upload.id).await?;
+        return Err(AppError::Internal);
+    }
+
+    Ok(upload_response(StatusCode::CREATED, upload, 0))
+}
+
+async fn get_upload(
+    State(state): State<AppState>,
+    Path((slug, task_ref, upload_id)): Path<(String, String, String)>,
+) -> AppResult<Response> {
+    let upload = queries::get_attachment_upload(&state.db, &slug, &task_ref, &upload_id).await?;
+    let offset = upload_offset(&state, &upload).await?;
+    Ok(upload_response(StatusCode::OK, upload, offset))
+}
+
+/// Writes one chunk at `Upload-Offset`, which must match the bytes already received.
+async fn append_upload(
+    State(state): State<AppState>,
+    Path((slug, task_ref, upload_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    chunk: Bytes,
+) -> AppResult<Response> {
+    let offset = headers
+        .get(&UPLOAD_OFFSET)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .ok_or_else(|| {
+            AppError::BadRequest("Upload-Offset header must be a byte offset".to_string())
+        })?;
+    let upload = queries::get_attachment_upload(&state.db, &slug, &task_ref, &upload_id).await?;
+    // Held until the chunk is written, so of two chunks sent for the same offset one is
+    // written and the other sees the new offset.
+    let _writing = state.upload_locks.lock(&upload.id).await;
+    let current = upload_offset(&state, &upload).await?;
+    if offset != current {
+        return Err(AppError::Conflict(format!(
+            "upload '{upload_id}' has {current} bytes, so the next chunk starts at {current}, not {offset}"
+        )));
+    }
+    let end = i64::try_from(chunk.len())
+        .ok()
+        .and_then(|length| current.checked_add(length))
+        .filter(|end| *end <= upload.size_bytes)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "chunk runs past the declared size of {} bytes",
+                upload.size_bytes
+            ))
+        })?;
+
+    let path = upload_file_path(&state, &upload.id)?;
+    let written = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .await?;
+        file.seek(std::io::SeekFrom::Start(current.unsigned_abs()))
+            .await?;
+        file.write_all(&chunk).await?;
+        file.flush().await
+    }
+    .await;
+    if let Err(error) = written {
+        tracing::error!(error = ?error, path = %path.display(), "failed to write upload chunk");
+        return Err(AppError::Internal);
+    }
+    queries::touch_attachment_upload(&state.db, &upload.id).await?;
+
+    let upload = queries::get_attachment_upload(&state.db, &slug, &task_ref, &upload_id).await?;
+    Ok(upload_response(StatusCode::OK, upload, end))
+}
+
+/// Turns a fully received upload into an attachment.
+async fn complete_upload(
+    State(state): State<AppState>,
+    Path((slug, task_ref, upload_id)): Path<(String, String, String)>,
+) -> AppResult<(StatusCode, Json<AttachmentRecord>)> {
+    let upload = queries::get_attachment_upload(&state.db, &slug, &task_ref, &upload_id).await?;
+    let _writing = state.upload_locks.lock(&upload.id).await;
+    let offset = upload_offset(&state, &upload).await?;
+    if offset != upload.size_bytes {
+        return Err(AppError::Conflict(format!(
+            "upload '{upload_id}' has {offset} of {} bytes",
+            upload.size_bytes
+        )));
+    }
+    if !queries::delete_attachment_upload(&state.db, &upload.id).await? {
+        return Err(AppError::NotFound(format!(
+            "upload '{upload_id}' not found"
+        )));
+    }
+
+    let part_path = upload_file_path(&state, &upload.id)?;
+    let storage_path = format!("{}.blob", upload.id);
+    let absolute_path = storage_file_path(&state.config.storage_dir, &storage_path)?;
+    tokio::fs::rename(&part_path, &absolute_path)
+        .await
+        .map_err(|error| {
+            tracing::error!(error = ?error, path = %part_path.display(), "failed to move completed upload");
+            AppError::Internal
+        })?;
+
+    let created = queries::create_attachment(
+        &state.db,
+        &slug,
+        &task_ref,
+        NewAttachmentInput {
+            id: upload.id,
+            filename: upload.filename,
+            content_type: upload.content_type,
+            size_bytes: upload.size_bytes,
+            storage_path,
+            uploaded_by: upload.uploaded_by,
+        },
+    )
+    .await;
+
+    match created {
+        Ok(record) => Ok((StatusCode::CREATED, Json(record))),
+        Err(error) => {
+            remove_file_quietly(&absolute_path).await;
+            Err(error)
+        }
+    }
+}
+
+async fn abort_upload(
+    State(state): State<AppState>,
+    Path((slug, task_ref, upload_id)): Path<(String, String, String)>,
+) -> AppResult<StatusCode> {
+    let upload = queries::get_attachment_upload(&state.db, &slug, &task_ref, &upload_id).await?;
+    queries::delete_attachment_upload(&state.db, &upload.id).await?;
+    remove_file_quietly(&upload_file_path(&state, &upload.id)?).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn upload_response(status: StatusCode, upload: AttachmentUploadRecord, offset: i64) -> Response {
+    let expires_at = chrono::DateTime::parse_from_rfc3339(&upload.updated_at)
+        .map(|updated_at| {
+            (updated_at.with_timezone(&Utc) + Duration::hours(UPLOAD_EXPIRY_HOURS))
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+        .unwrap_or_default();
+    let body = UploadResponse {
+        id: upload.id,
+        task_id: upload.task_id,
+        filename: upload.filename,
+        content_type: upload.content_type,
+        size_bytes: upload.size_bytes,
+        offset,
+        expires_at,
+        created_at: upload.created_at,
+        updated_at: upload.updated_at,
+    };
+    (status, [(UPLOAD_OFFSET, offset.to_string())], Json(body)).into_response()
+}
+
+fn upload_file_path(state: &AppState, upload_id: &str) -> AppResult<PathBuf> {
+    storage_file_path(
+        &state.config.storage_dir,
+        &format!("uploads/{upload_id}.part"),
+    )
+}
+
+/// The bytes received so far, which is the length of the partial file.
+async fn upload_offset(state: &AppState, upload: &AttachmentUploadRecord) -> AppResult<i64> {
+    let path = upload_file_path(state, &upload.id)?;
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|error| match error.kind() {
+            ErrorKind::NotFound => {
+                AppError::NotFound(format!("upload '{}' is missing from disk", upload.id))
+            }
+            _ => {
+                tracing::error!(error = ?error, path = %path.display(), "failed to read upload file");
+                AppError::Internal
+            }
+        })?;
+    i64::try_from(metadata.len()).map_err(|_| AppError::Internal)
+}
+
+/// Drops sessions idle for `UPLOAD_EXPIRY_HOURS`, plus partial files that old whose session is
+/// already gone, for example because the task was deleted.
+async fn sweep_stale_uploads(state: &AppState) {
+    let cutoff = Utc::now() - Duration::hours(UPLOAD_EXPIRY_HOURS);
+    let cutoff_text = cutoff.to_rfc3339_opts(SecondsFormat::Millis, true);
+    match queries::delete_stale_attachment_uploads(&state.db, &cutoff_text).await {
+        Ok(ids) => {
+            for id in ids {
+                if let Ok(path) = upload_file_path(state, &id) {
+                    remove_file_quietly(&path).await;
+                }
+            }
+        }
+        Err(error) => tracing::warn!(error = ?error, "failed to expire stale uploads"),
+    }
+
+    let Ok(mut entries) = tokio::fs::read_dir(state.config.storage_dir.join("uploads")).await
+    else {
+        return;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let stale = entry
+            .metadata()
+            .await
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| chrono::DateTime::<Utc>::from(modified) < cutoff);
+        if stale {
+            remove_file_quietly(&entry.path()).await;
+        }
+    }
+}
+
+/// Removes the files behind attachments that no longer exist, such as those of a purged task.
+pub async fn remove_stored_files(storage_dir: &FsPath, storage_paths: &[String]) {
+    for storage_path in storage_paths {
+        if let Ok(path) = storage_file_path(storage_dir, storage_path) {
+            remove_file_quietly(&path).await;
+        }
+    }
+}
+
+async fn remove_file_quietly(path: &FsPath) {
+    if let Err(error) = tokio::fs::remove_file(path).await {
+        if error.kind() != ErrorKind::NotFound {
+            tracing::warn!(error = ?error, path = %path.display(), "failed to remove file");
+        }
+    }
+}
+
 async fn delete_attachment(
     State(state): State<AppState>,
     Path((slug, task_ref, attachment_id)): Path<(String, String, String)>,
@@ -198,6 +551,160 @@ async fn download_attachment(
     Ok(response)
 }
 
+#[derive(Debug, Deserialize)]
+struct PreviewQuery {
+    max_kb: Option<usize>,
+}
+
+/// The start of a text attachment, cut back to a whole line where one fits.
+#[derive(Debug, Serialize)]
+pub struct AttachmentPreview {
+    pub id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Highlighting hint such as `json`, `csv`, `log`, or `plaintext`.
+    pub syntax: &'static str,
+    pub content: String,
+    pub preview_bytes: usize,
+    pub truncated: bool,
+}
+
+async fn preview_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<PreviewQuery>,
+) -> AppResult<Json<AttachmentPreview>> {
+    let attachment = queries::get_attachment(&state.db, &id).await?;
+    let preview = read_preview(&state.config.storage_dir, attachment, query.max_kb).await?;
+    Ok(Json(preview))
+}
+
+/// Reads at most `max_kb` KiB of a text-like attachment. Binary files are refused.
+pub async fn read_preview(
+    storage_dir: &FsPath,
+    attachment: AttachmentRecord,
+    max_kb: Option<usize>,
+) -> AppResult<AttachmentPreview> {
+    let max_kb = max_kb.unwrap_or(DEFAULT_PREVIEW_KB);
+    if !(1..=MAX_PREVIEW_KB).contains(&max_kb) {
+        return Err(AppError::BadRequest(format!(
+            "max_kb must be between 1 and {MAX_PREVIEW_KB}"
+        )));
+    }
+    let not_text = || {
+        AppError::BadRequest(format!(
+            "attachment '{}' is not a text file and cannot be previewed",
+            attachment.id
+        ))
+    };
+    let syntax =
+        syntax_hint(&attachment.filename, &attachment.content_type).ok_or_else(not_text)?;
+
+    let path = storage_file_path(storage_dir, &attachment.storage_path)?;
+    let read_error = |error: std::io::Error| match error.kind() {
+        ErrorKind::NotFound => AppError::NotFound(format!(
+            "attachment file '{}' is missing from disk",
+            attachment.id
+        )),
+        _ => {
+            tracing::error!(error = ?error, path = %path.display(), "failed to read attachment file");
+            AppError::Internal
+        }
+    };
+    let file = tokio::fs::File::open(&path).await.map_err(read_error)?;
+
+    // One byte past the limit tells us whether anything was left unread.
+    let limit = max_kb * 1024;
+    let mut bytes = Vec::new();
+    file.take(limit as u64 + 1)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(read_error)?;
+    let truncated = bytes.len() > limit;
+    bytes.truncate(limit);
+    if bytes.contains(&0) {
+        return Err(not_text());
+    }
+
+    if truncated {
+        if let Err(error) = std::str::from_utf8(&bytes) {
+            if error.error_len().is_none() {
+                bytes.truncate(error.valid_up_to());
+            }
+        }
+        if let Some(newline) = bytes.iter().rposition(|byte| *byte == b'\n') {
+            bytes.truncate(newline + 1);
+        }
+    }
+
+    let content = String::from_utf8_lossy(&bytes).into_owned();
+    Ok(AttachmentPreview {
+        preview_bytes: bytes.len(),
+        id: attachment.id,
+        filename: attachment.filename,
+        content_type: attachment.content_type,
+        size_bytes: attachment.size_bytes,
+        syntax,
+        content,
+        truncated,
+    })
+}
+
+/// Picks a syntax hint from the file extension, then the content type. `None` means not text.
+fn syntax_hint(filename: &str, content_type: &str) -> Option<&'static str> {
+    let extension = FsPath::new(filename)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let by_extension = match extension.as_deref() {
+        Some("log" | "out") => Some("log"),
+        Some("csv") => Some("csv"),
+        Some("tsv") => Some("tsv"),
+        Some("json") => Some("json"),
+        Some("jsonl" | "ndjson") => Some("jsonl"),
+        Some("md" | "markdown") => Some("markdown"),
+        Some("yaml" | "yml") => Some("yaml"),
+        Some("toml") => Some("toml"),
+        Some("xml") => Some("xml"),
+        Some("html" | "htm") => Some("html"),
+        Some("diff" | "patch") => Some("diff"),
+        Some("sql") => Some("sql"),
+        Some("sh" | "bash") => Some("shell"),
+        Some("rs") => Some("rust"),
+        Some("py") => Some("python"),
+        Some("js") => Some("javascript"),
+        Some("ts") => Some("typescript"),
+        Some("txt" | "text") => Some("plaintext"),
+        _ => None,
+    };
+    if by_extension.is_some() {
+        return by_extension;
+    }
+
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    match essence.as_str() {
+        "application/json" => Some("json"),
+        "application/x-ndjson" | "application/jsonl" => Some("jsonl"),
+        "text/csv" => Some("csv"),
+        "text/tab-separated-values" => Some("tsv"),
+        "text/markdown" => Some("markdown"),
+        "application/yaml" | "application/x-yaml" | "text/yaml" => Some("yaml"),
+        "application/toml" => Some("toml"),
+        "application/xml" | "text/xml" => Some("xml"),
+        "text/html" => Some("html"),
+        other if other.ends_with("+json") => Some("json"),
+        other if other.ends_with("+xml") => Some("xml"),
+        other if other.starts_with("text/") => Some("plaintext"),
+        _ => None,
+    }
+}
+
 fn sanitize_filename(raw: &str) -> String {
     let leaf = raw.rsplit(['/', '\\']).next().unwrap_or(raw).trim();
     if leaf.is_empty() {
@@ -266,6 +773,7 @@ fn actor_from_headers(headers: &HeaderMap) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::path::{Path, PathBuf};
     use std::time::Duration;
 
     use axum::middleware;
@@ -274,30 +782,43 @@ mod tests {
     use reqwest::multipart::{Form, Part};
     use reqwest::StatusCode;
     use tempfile::tempdir;
+    use tokio::task::JoinHandle;
 
     use crate::api;
-    use crate::config::{Config, RateLimitConfig};
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
     use crate::db;
+    use crate::db::models::TaskRecord;
     use crate::db::queries;
     use crate::db::queries::NewTaskInput;
     use crate::state::AppState;
 
-    #[tokio::test]
-    async fn upload_download_and_delete_attachment_roundtrip() {
-        let temp_dir = tempdir().expect("tempdir should be created");
-        let db_path = temp_dir.path().join("phase6_attachment_test.db");
+    /// Serves the API over a fresh database with project `ATTACH` and one task in it.
+    async fn spawn_app(temp_dir: &Path) -> (String, PathBuf, TaskRecord, JoinHandle<()>) {
+        let db_path = temp_dir.join("phase6_attachment_test.db");
         let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
-        let storage_dir = temp_dir.path().join("storage");
+        let storage_dir = temp_dir.join("storage");
         std::fs::create_dir_all(&storage_dir).expect("storage dir should be created");
 
         let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
             port: 0,
             db_url,
             token: None,
             log_level: "info".to_string(),
             storage_dir: storage_dir.clone(),
             max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
             rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
         };
 
         let pool = db::connect_and_migrate(&config)
@@ -308,6 +829,7 @@ mod tests {
             .expect("project should be created");
         let task = queries::create_task(
             &pool,
+            &FieldLimitConfig::default(),
             "ATTACH",
             NewTaskInput {
                 title: "attachment target".to_string(),
@@ -317,6 +839,8 @@ mod tests {
                 review_state: "ready".to_string(),
                 labels: Vec::new(),
                 created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
             },
         )
         .await
@@ -342,15 +866,19 @@ mod tests {
             let _ = axum::serve(listener, app).await;
         });
 
+        (format!("http://{addr}/api/v1"), storage_dir, task, server)
+    }
+
+    #[tokio::test]
+    async fn upload_download_and_delete_attachment_roundtrip() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (api, storage_dir, task, server) = spawn_app(temp_dir.path()).await;
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(10))
             .build()
             .expect("client should build");
 
-        let upload_url = format!(
-            "http://{addr}/api/v1/projects/ATTACH/tasks/{}/attachments",
-            task.id
-        );
+        let upload_url = format!("{api}/projects/ATTACH/tasks/{}/attachments", task.id);
         let form = Form::new().part(
             "file",
             Part::bytes(b"hello from lattice".to_vec())
@@ -387,7 +915,7 @@ mod tests {
             Some("demo.txt")
         );
 
-        let download_url = format!("http://{addr}/api/v1/files/{attachment_id}");
+        let download_url = format!("{api}/files/{attachment_id}");
         let download = client
             .get(&download_url)
             .send()
@@ -401,7 +929,7 @@ mod tests {
         assert_eq!(content.as_ref(), b"hello from lattice");
 
         let delete_url = format!(
-            "http://{addr}/api/v1/projects/ATTACH/tasks/{}/attachments/{attachment_id}",
+            "{api}/projects/ATTACH/tasks/{}/attachments/{attachment_id}",
             task.id
         );
         let deleted = client
@@ -424,4 +952,242 @@ mod tests {
 
         server.abort();
     }
+
+    #[tokio::test]
+    async fn upload_locks_make_writers_to_one_upload_take_turns() {
+        let locks = super::UploadLocks::default();
+        let first = locks.lock("upload-a").await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), locks.lock("upload-a"))
+                .await
+                .is_err(),
+            "a second writer waits for the first"
+        );
+        let other = tokio::time::timeout(Duration::from_millis(20), locks.lock("upload-b"))
+            .await
+            .expect("other uploads are not blocked");
+
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_millis(20), locks.lock("upload-a"))
+            .await
+            .expect("the lock is free once the first writer is done");
+        drop((second, other));
+        let _ = locks.lock("upload-c").await;
+        assert_eq!(
+            super::lock(&locks.0).len(),
+            1,
+            "released uploads are forgotten"
+        );
+    }
+
+    #[tokio::test]
+    async fn resumable_uploads_accept_chunks_in_order_and_complete() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (api, storage_dir, task, server) = spawn_app(temp_dir.path()).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("client should build");
+        let uploads = format!("{api}/projects/ATTACH/tasks/ATTACH-1/attachments/uploads");
+
+        let too_big = client
+            .post(&uploads)
+            .json(&serde_json::json!({ "filename": "huge.bin", "size_bytes": 11 * 1024 * 1024 }))
+            .send()
+            .await
+            .expect("create request should succeed");
+        assert_eq!(too_big.status(), StatusCode::BAD_REQUEST);
+
+        let created = client
+            .post(&uploads)
+            .json(&serde_json::json!({ "filename": "notes.txt", "size_bytes": 11 }))
+            .send()
+            .await
+            .expect("create request should succeed");
+        assert_eq!(created.status(), StatusCode::CREATED);
+        let created: serde_json::Value = created.json().await.expect("upload json should parse");
+        assert_eq!(created["offset"], 0);
+        assert_eq!(created["content_type"], "text/plain");
+        let upload_url = format!("{uploads}/{}", created["id"].as_str().expect("upload id"));
+
+        let send_chunk = |offset: u64, chunk: &'static [u8]| {
+            client
+                .patch(&upload_url)
+                .header("Upload-Offset", offset.to_string())
+                .body(chunk)
+                .send()
+        };
+        let first = send_chunk(0, b"hello")
+            .await
+            .expect("chunk request should succeed");
+        assert_eq!(first.status(), StatusCode::OK);
+        assert_eq!(first.headers()["upload-offset"], "5");
+        let retried = send_chunk(0, b"hello")
+            .await
+            .expect("chunk request should succeed");
+        assert_eq!(retried.status(), StatusCode::CONFLICT);
+
+        let early = client
+            .post(format!("{upload_url}/complete"))
+            .send()
+            .await
+            .expect("complete request should succeed");
+        assert_eq!(early.status(), StatusCode::CONFLICT);
+        let overflow = send_chunk(5, b" world, and more")
+            .await
+            .expect("chunk request should succeed");
+        assert_eq!(overflow.status(), StatusCode::BAD_REQUEST);
+
+        let resumed: serde_json::Value = client
+            .get(&upload_url)
+            .send()
+            .await
+            .expect("status request should succeed")
+            .json()
+            .await
+            .expect("upload json should parse");
+        assert_eq!(resumed["offset"], 5);
+        let last = send_chunk(5, b" world")
+            .await
+            .expect("chunk request should succeed");
+        assert_eq!(last.status(), StatusCode::OK);
+
+        let completed = client
+            .post(format!("{upload_url}/complete"))
+            .send()
+            .await
+            .expect("complete request should succeed");
+        assert_eq!(completed.status(), StatusCode::CREATED);
+        let attachment: serde_json::Value = completed
+            .json()
+            .await
+            .expect("attachment json should parse");
+        assert_eq!(attachment["task_id"], task.id);
+        assert_eq!(attachment["size_bytes"], 11);
+        assert!(
+            !storage_dir
+                .join("uploads")
+                .read_dir()
+                .expect("uploads dir")
+                .any(|_| true),
+            "partial file should be moved into place"
+        );
+
+        let download = client
+            .get(format!(
+                "{api}/files/{}",
+                attachment["id"].as_str().expect("attachment id")
+            ))
+            .send()
+            .await
+            .expect("download request should succeed");
+        assert_eq!(
+            download.bytes().await.expect("bytes").as_ref(),
+            b"hello world"
+        );
+        let gone = client
+            .get(&upload_url)
+            .send()
+            .await
+            .expect("status request should succeed");
+        assert_eq!(gone.status(), StatusCode::NOT_FOUND);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn previews_return_the_start_of_text_attachments() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (api, _storage_dir, task, server) = spawn_app(temp_dir.path()).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("client should build");
+        let upload_url = format!("{api}/projects/ATTACH/tasks/{}/attachments", task.id);
+        let upload = |filename: &'static str, mime: &'static str, bytes: Vec<u8>| {
+            let client = client.clone();
+            let upload_url = upload_url.clone();
+            async move {
+                let form = Form::new().part(
+                    "file",
+                    Part::bytes(bytes)
+                        .file_name(filename)
+                        .mime_str(mime)
+                        .expect("mime should parse"),
+                );
+                let response = client
+                    .post(upload_url)
+                    .multipart(form)
+                    .send()
+                    .await
+                    .expect("upload request should succeed");
+                assert_eq!(response.status(), StatusCode::CREATED);
+                let body: serde_json::Value =
+                    response.json().await.expect("upload json should parse");
+                body["id"]
+                    .as_str()
+                    .map(ToOwned::to_owned)
+                    .expect("attachment id should be present")
+            }
+        };
+
+        let log = (1..=200)
+            .map(|line| format!("line {line:04} ok\n"))
+            .collect::<String>();
+        let log_id = upload(
+            "build.log",
+            "application/octet-stream",
+            log.clone().into_bytes(),
+        )
+        .await;
+        let preview: serde_json::Value = client
+            .get(format!("{api}/files/{log_id}/preview?max_kb=1"))
+            .send()
+            .await
+            .expect("preview request should succeed")
+            .json()
+            .await
+            .expect("preview json should parse");
+        let content = preview["content"].as_str().expect("content should be text");
+        assert_eq!(preview["syntax"], "log");
+        assert_eq!(preview["truncated"], true);
+        assert_eq!(preview["size_bytes"], log.len());
+        assert!(content.len() <= 1024);
+        assert!(
+            content.ends_with('\n'),
+            "preview should stop at a whole line"
+        );
+        assert!(log.starts_with(content));
+        assert_eq!(preview["preview_bytes"], content.len());
+
+        let json_id = upload("data.bin", "application/json", br#"{"ok":true}"#.to_vec()).await;
+        let preview: serde_json::Value = client
+            .get(format!("{api}/files/{json_id}/preview"))
+            .send()
+            .await
+            .expect("preview request should succeed")
+            .json()
+            .await
+            .expect("preview json should parse");
+        assert_eq!(preview["syntax"], "json");
+        assert_eq!(preview["truncated"], false);
+        assert_eq!(preview["content"], r#"{"ok":true}"#);
+
+        let image_id = upload("shot.png", "image/png", vec![0x89, b'P', b'N', b'G', 0]).await;
+        let refused = client
+            .get(format!("{api}/files/{image_id}/preview"))
+            .send()
+            .await
+            .expect("preview request should succeed");
+        assert_eq!(refused.status(), StatusCode::BAD_REQUEST);
+
+        let too_big = client
+            .get(format!("{api}/files/{log_id}/preview?max_kb=100000"))
+            .send()
+            .await
+            .expect("preview request should succeed");
+        assert_eq!(too_big.status(), StatusCode::BAD_REQUEST);
+
+        server.abort();
+    }
 }
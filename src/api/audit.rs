@@ -0,0 +1,31 @@
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::Router;
+
+use crate::api::auth::AccessScope;
+use crate::api::{ListQuery, Paginated};
+use crate::db::models::AuditEventRecord;
+use crate::db::queries;
+use crate::error::AppResult;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/audit-events", get(list_audit_events))
+}
+
+async fn list_audit_events(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Query(query): Query<ListQuery>,
+) -> AppResult<Paginated<AuditEventRecord>> {
+    scope.require_instance()?;
+    let (limit, offset) = query.normalize()?;
+    let total = queries::count_audit_events(&state.db).await?;
+    let items = queries::list_audit_events(&state.db, limit, offset).await?;
+    Ok(Paginated {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
@@ -1,22 +1,88 @@
-use axum::extract::State;
+use std::convert::Infallible;
+use std::fmt::Write as _;
+
+use axum::extract::{FromRequestParts, State};
 use axum::http::header::AUTHORIZATION;
-use axum::http::Request;
+use axum::http::request::Parts;
+use axum::http::{Method, Request};
 use axum::middleware::Next;
 use axum::response::Response;
+use sha2::{Digest, Sha256};
 
+use crate::api::workspaces::WorkspaceSelector;
+use crate::db::queries;
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 
+/// What the authenticated caller is allowed to see.
+///
+/// Instance scope comes from the global `LATTICE_TOKEN` (or from running with auth
+/// disabled). Workspace scope comes from a per-workspace token, or from a
+/// `/api/v1/w/{workspace}/...` URL, and confines the request to one workspace. Guest scope
+/// comes from a short-lived guest token and only allows reading one project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessScope {
+    Instance,
+    Workspace(String),
+    Guest {
+        workspace_id: String,
+        project_slug: String,
+    },
+}
+
+impl AccessScope {
+    pub fn workspace_id(&self) -> Option<&str> {
+        match self {
+            Self::Instance => None,
+            Self::Workspace(workspace_id) | Self::Guest { workspace_id, .. } => Some(workspace_id),
+        }
+    }
+
+    pub fn require_instance(&self) -> AppResult<()> {
+        match self {
+            Self::Instance => Ok(()),
+            Self::Workspace(_) | Self::Guest { .. } => Err(AppError::Forbidden(
+                "this endpoint requires an instance-level token".to_string(),
+            )),
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for AccessScope
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // `require_auth` always inserts a scope; routers assembled without it are instance-wide.
+        Ok(parts
+            .extensions
+            .get::<AccessScope>()
+            .cloned()
+            .unwrap_or(AccessScope::Instance))
+    }
+}
+
+/// The name of the MCP key a `/mcp` request authenticated with. Tool calls record it as
+/// their actor instead of the self-reported `MCP-Client` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpIdentity(pub String);
+
+/// Inbound hook calls come from external systems and are authenticated by an HMAC signature
+/// in the handler instead of a bearer token.
+const INBOUND_PREFIX: &str = "/api/v1/inbound/";
+/// Share links carry their own token in the path and only allow reads.
+const SHARE_PREFIX: &str = "/api/v1/share/";
+/// MCP requests may authenticate with a named MCP key instead of the instance token.
+const MCP_PATH: &str = "/mcp";
+
 pub async fn require_auth(
     State(state): State<AppState>,
-    request: Request<axum::body::Body>,
+    mut request: Request<axum::body::Body>,
     next: Next,
 ) -> AppResult<Response> {
-    let Some(configured_token) = state.config.token.as_deref() else {
-        return Ok(next.run(request).await);
-    };
-
-    if configured_token.trim().is_empty() {
+    if bypasses_bearer_auth(request.method(), request.uri().path()) {
         return Ok(next.run(request).await);
     }
 
@@ -24,12 +90,229 @@ pub async fn require_auth(
         .headers()
         .get(AUTHORIZATION)
         .and_then(|value| value.to_str().ok())
-        .and_then(parse_bearer_token);
+        .and_then(parse_bearer_token)
+        .map(ToOwned::to_owned);
+
+    if is_mcp_path(request.uri().path()) {
+        let key_name = match provided.as_deref() {
+            Some(token) => {
+                queries::mcp_key_name_by_token_hash(&state.db, &hash_token(token)).await?
+            }
+            None => None,
+        };
+        if let Some(name) = key_name {
+            request.extensions_mut().insert(AccessScope::Instance);
+            request.extensions_mut().insert(McpIdentity(name));
+            return Ok(next.run(request).await);
+        }
+        if state.config.mcp_require_key {
+            return Err(AppError::Unauthorized);
+        }
+    }
+
+    let token_scope = resolve_token_scope(&state, provided.as_deref()).await?;
+    let scope = apply_workspace_selector(
+        &state,
+        token_scope,
+        request.extensions().get::<WorkspaceSelector>(),
+    )
+    .await?;
+    enforce_guest_scope(&scope, request.method(), request.uri().path())?;
+    enforce_workspace_scope(&state, &scope, request.uri().path()).await?;
+
+    request.extensions_mut().insert(scope);
+    Ok(next.run(request).await)
+}
+
+fn bypasses_bearer_auth(method: &Method, path: &str) -> bool {
+    path.starts_with(INBOUND_PREFIX)
+        || (path.starts_with(SHARE_PREFIX) && (method == Method::GET || method == Method::HEAD))
+}
+
+fn is_mcp_path(path: &str) -> bool {
+    path == MCP_PATH || path.starts_with("/mcp/")
+}
+
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    let digest = hasher.finalize();
+
+    let mut output = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(&mut output, "{byte:02x}");
+    }
+    output
+}
+
+async fn resolve_token_scope(state: &AppState, provided: Option<&str>) -> AppResult<AccessScope> {
+    if let Some(token) = provided {
+        if state.config.auth_enabled() && state.config.token.as_deref() == Some(token) {
+            return Ok(AccessScope::Instance);
+        }
+
+        let token_hash = hash_token(token);
+        if let Some(workspace_id) =
+            queries::workspace_id_by_token_hash(&state.db, &token_hash).await?
+        {
+            return Ok(AccessScope::Workspace(workspace_id));
+        }
+
+        if let Some((workspace_id, project_slug)) =
+            queries::guest_scope_by_token_hash(&state.db, &token_hash).await?
+        {
+            return Ok(AccessScope::Guest {
+                workspace_id,
+                project_slug,
+            });
+        }
+    }
+
+    if state.config.auth_enabled() {
+        return Err(AppError::Unauthorized);
+    }
+
+    Ok(AccessScope::Instance)
+}
+
+async fn apply_workspace_selector(
+    state: &AppState,
+    scope: AccessScope,
+    selector: Option<&WorkspaceSelector>,
+) -> AppResult<AccessScope> {
+    let Some(selector) = selector else {
+        return Ok(scope);
+    };
+
+    let workspace = queries::get_workspace(&state.db, &selector.0).await?;
+    match scope {
+        AccessScope::Workspace(workspace_id) | AccessScope::Guest { workspace_id, .. }
+            if workspace_id != workspace.id =>
+        {
+            Err(AppError::NotFound(format!(
+                "workspace '{}' not found",
+                selector.0
+            )))
+        }
+        guest @ AccessScope::Guest { .. } => Ok(guest),
+        _ => Ok(AccessScope::Workspace(workspace.id)),
+    }
+}
+
+/// Project sub-resources that hold secrets or grant access and stay hidden from guests.
+const GUEST_HIDDEN_RESOURCES: [&str; 4] =
+    ["webhooks", "inbound-hooks", "share-links", "guest-tokens"];
+
+/// Guests may only `GET` their own project's routes under `/api/v1/projects/{slug}`.
+fn enforce_guest_scope(scope: &AccessScope, method: &Method, path: &str) -> AppResult<()> {
+    let AccessScope::Guest { project_slug, .. } = scope else {
+        return Ok(());
+    };
+
+    if method != Method::GET && method != Method::HEAD {
+        return Err(AppError::Forbidden(
+            "guest tokens are read-only".to_string(),
+        ));
+    }
+
+    let mut segments = path
+        .strip_prefix("/api/v1/projects/")
+        .unwrap_or_default()
+        .split('/');
+    let slug = segments.next().unwrap_or_default();
+    if !slug.eq_ignore_ascii_case(project_slug) {
+        return Err(AppError::Forbidden(format!(
+            "guest token only grants access to project '{project_slug}'"
+        )));
+    }
 
-    match provided {
-        Some(value) if value == configured_token => Ok(next.run(request).await),
-        _ => Err(AppError::Unauthorized),
+    if let Some(resource) = segments
+        .next()
+        .filter(|resource| GUEST_HIDDEN_RESOURCES.contains(resource))
+    {
+        return Err(AppError::Forbidden(format!(
+            "guest tokens cannot read {resource}"
+        )));
     }
+
+    Ok(())
+}
+
+async fn enforce_workspace_scope(
+    state: &AppState,
+    scope: &AccessScope,
+    path: &str,
+) -> AppResult<()> {
+    let Some(workspace_id) = scope.workspace_id() else {
+        return Ok(());
+    };
+
+    if path == "/api/v1/workspaces" || path.starts_with("/api/v1/workspaces/") {
+        return scope.require_instance();
+    }
+
+    // Checked on the decoded segment, as the handler's `Path` sees it, and failing closed:
+    // anything not owned by the workspace reads as missing.
+    if let Some(segment) =
+        path_segment_after(path, "/api/v1/projects/").filter(|segment| *segment != "bulk")
+    {
+        let slug = decode_path_segment(segment);
+        let owner = match slug.as_deref() {
+            Some(slug) => queries::project_workspace_id(&state.db, slug).await?,
+            None => None,
+        };
+        if owner.as_deref() != Some(workspace_id) {
+            return Err(AppError::NotFound(format!(
+                "project '{}' not found",
+                slug.as_deref().unwrap_or(segment)
+            )));
+        }
+    }
+
+    if let Some(segment) = path_segment_after(path, "/api/v1/files/") {
+        let attachment_id = decode_path_segment(segment);
+        let owner = match attachment_id.as_deref() {
+            Some(attachment_id) => {
+                queries::attachment_workspace_id(&state.db, attachment_id).await?
+            }
+            None => None,
+        };
+        if owner.as_deref() != Some(workspace_id) {
+            return Err(AppError::NotFound(format!(
+                "attachment '{}' not found",
+                attachment_id.as_deref().unwrap_or(segment)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Percent-decodes a path segment the way axum's `Path` extractor does. `None` for a malformed
+/// escape or a result that is not UTF-8.
+fn decode_path_segment(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = bytes.get(index + 1..index + 3)?;
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            decoded.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+fn path_segment_after<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(prefix)?;
+    rest.split('/').next().filter(|segment| !segment.is_empty())
 }
 
 fn parse_bearer_token(value: &str) -> Option<&str> {
@@ -0,0 +1,358 @@
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api::auth::AccessScope;
+use crate::api::events::{map_task_event, TaskEventPayload};
+use crate::api::ListQuery;
+use crate::db::models::{ProjectQuestionRecord, ProjectSummary, TaskRecord};
+use crate::db::queries;
+use crate::db::queries::TaskFilters;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+const DEFAULT_DASHBOARD_ITEMS: i64 = 5;
+const MAX_DASHBOARD_ITEMS: i64 = 20;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/dashboard", get(get_dashboard))
+}
+
+#[derive(Debug, Deserialize)]
+struct DashboardQuery {
+    /// Comma-separated slugs; every project in scope when missing.
+    project: Option<String>,
+    /// How many questions, stale tasks, and events to include per project.
+    items: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardResponse {
+    generated_at: String,
+    /// `null` when stale detection is disabled, in which case `stale_tasks` stays empty.
+    stale_after_days: Option<u32>,
+    total: i64,
+    limit: i64,
+    offset: i64,
+    projects: Vec<DashboardProject>,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardProject {
+    #[serde(flatten)]
+    summary: ProjectSummary,
+    open_questions: Vec<DashboardQuestion>,
+    stale_tasks: Vec<DashboardTask>,
+    recent_events: Vec<TaskEventPayload>,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardQuestion {
+    id: String,
+    task_id: String,
+    task_display_key: String,
+    question: String,
+    asked_by: String,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DashboardTask {
+    id: String,
+    display_key: String,
+    title: String,
+    status: String,
+    priority: String,
+    updated_at: String,
+}
+
+async fn get_dashboard(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Query(query): Query<DashboardQuery>,
+) -> AppResult<Json<DashboardResponse>> {
+    let (limit, offset) = ListQuery {
+        limit: query.limit,
+        offset: query.offset,
+    }
+    .normalize()?;
+    let items = query.items.unwrap_or(DEFAULT_DASHBOARD_ITEMS);
+    if !(1..=MAX_DASHBOARD_ITEMS).contains(&items) {
+        return Err(AppError::BadRequest(format!(
+            "items must be between 1 and {MAX_DASHBOARD_ITEMS}"
+        )));
+    }
+
+    let (summaries, total) = match selected_slugs(query.project.as_deref())? {
+        Some(slugs) => {
+            let mut summaries = Vec::with_capacity(slugs.len());
+            for slug in &slugs {
+                let summary = queries::get_project(&state.db, slug).await?;
+                if scope
+                    .workspace_id()
+                    .is_some_and(|workspace_id| summary.project.workspace_id != workspace_id)
+                {
+                    return Err(AppError::NotFound(format!("project '{slug}' not found")));
+                }
+                summaries.push(summary);
+            }
+            let total = summaries.len() as i64;
+            let page = summaries
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect();
+            (page, total)
+        }
+        None => (
+            queries::list_projects(&state.db, scope.workspace_id(), None, limit, offset).await?,
+            queries::count_projects(&state.db, scope.workspace_id(), None).await?,
+        ),
+    };
+
+    let stale_after_days = state.config.stale_threshold_days();
+    let stale_before = stale_after_days.map(queries::timestamp_days_ago);
+    let mut projects = Vec::with_capacity(summaries.len());
+    for summary in summaries {
+        projects.push(load_project(&state, summary, stale_before.as_deref(), items).await?);
+    }
+
+    Ok(Json(DashboardResponse {
+        generated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        stale_after_days,
+        total,
+        limit,
+        offset,
+        projects,
+    }))
+}
+
+fn selected_slugs(value: Option<&str>) -> AppResult<Option<Vec<String>>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let mut slugs = Vec::new();
+    for slug in value.split(',').filter(|slug| !slug.trim().is_empty()) {
+        let slug = queries::normalize_slug(slug)?;
+        if !slugs.contains(&slug) {
+            slugs.push(slug);
+        }
+    }
+    Ok(Some(slugs))
+}
+
+async fn load_project(
+    state: &AppState,
+    summary: ProjectSummary,
+    stale_before: Option<&str>,
+    items: i64,
+) -> AppResult<DashboardProject> {
+    let slug = summary.project.slug.clone();
+    let questions = queries::list_project_open_questions(&state.db, &slug, items, 0).await?;
+    let stale_tasks = match stale_before {
+        Some(cutoff) => {
+            queries::list_tasks(
+                &state.db,
+                &slug,
+                TaskFilters {
+                    stale_before: Some(cutoff.to_string()),
+                    sort: Some("updated_at:asc".to_string()),
+                    ..TaskFilters::default()
+                },
+                items,
+                0,
+            )
+            .await?
+        }
+        None => Vec::new(),
+    };
+    let events = queries::list_recent_system_events(&state.db, &slug, items).await?;
+
+    Ok(DashboardProject {
+        open_questions: questions
+            .into_iter()
+            .map(|question| dashboard_question(&slug, question))
+            .collect(),
+        stale_tasks: stale_tasks
+            .into_iter()
+            .map(|task| dashboard_task(&slug, task))
+            .collect(),
+        recent_events: events.into_iter().map(map_task_event).collect(),
+        summary,
+    })
+}
+
+fn dashboard_question(slug: &str, question: ProjectQuestionRecord) -> DashboardQuestion {
+    DashboardQuestion {
+        id: question.id,
+        task_id: question.task_id,
+        task_display_key: queries::display_key(slug, question.task_number),
+        question: question.question,
+        asked_by: question.asked_by,
+        created_at: question.created_at,
+    }
+}
+
+fn dashboard_task(slug: &str, task: TaskRecord) -> DashboardTask {
+    DashboardTask {
+        display_key: queries::display_key(slug, task.task_number),
+        id: task.id,
+        title: task.title,
+        status: task.status,
+        priority: task.priority,
+        updated_at: task.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::Router;
+    use reqwest::StatusCode;
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::db::queries::NewTaskInput;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn dashboard_combines_counts_questions_stale_tasks_and_events() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("dashboard.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        for slug in ["ALPHA", "BETA"] {
+            queries::create_project_with_slug(&pool, slug, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+        for title in ["stuck", "fresh"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "ALPHA",
+                NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "in_progress".to_string(),
+                    priority: "high".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+        sqlx::query("UPDATE tasks SET updated_at = ? WHERE title = 'stuck'")
+            .bind(queries::timestamp_days_ago(30))
+            .execute(&pool)
+            .await
+            .expect("task should be backdated");
+        queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "ALPHA",
+            "ALPHA-2",
+            "Which API?",
+            "",
+            "agent",
+        )
+        .await
+        .expect("question should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let dashboard: Value = client
+            .get(format!("{api}/dashboard"))
+            .send()
+            .await
+            .expect("dashboard should load")
+            .json()
+            .await
+            .expect("dashboard should parse");
+        assert_eq!(dashboard["total"], 2);
+        assert_eq!(dashboard["stale_after_days"], 7);
+
+        let selected: Value = client
+            .get(format!("{api}/dashboard?project=alpha&items=10"))
+            .send()
+            .await
+            .expect("dashboard should load")
+            .json()
+            .await
+            .expect("dashboard should parse");
+        let alpha = &selected["projects"][0];
+        assert_eq!(selected["total"], 1);
+        assert_eq!(alpha["project"]["slug"], "ALPHA");
+        assert_eq!(alpha["in_progress_count"], 2);
+        assert_eq!(alpha["open_question_count"], 1);
+        assert_eq!(alpha["open_questions"][0]["task_display_key"], "ALPHA-2");
+        assert_eq!(alpha["stale_tasks"].as_array().map(Vec::len), Some(1));
+        assert_eq!(alpha["stale_tasks"][0]["display_key"], "ALPHA-1");
+        assert!(alpha["recent_events"]
+            .as_array()
+            .is_some_and(|events| events.iter().any(|event| event["action"] == "task.created")));
+
+        let missing = client
+            .get(format!("{api}/dashboard?project=ALPHA,NOPE"))
+            .send()
+            .await
+            .expect("dashboard should respond");
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        server.abort();
+    }
+}
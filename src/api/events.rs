@@ -1,31 +1,58 @@
 use std::collections::BTreeSet;
 use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration;
 
 use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
 use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::routing::get;
-use axum::Router;
+use axum::{Extension, Json, Router};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use tokio::sync::mpsc;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::MissedTickBehavior;
-use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tokio_stream::{Stream, StreamExt};
 
+use crate::api::auth::AccessScope;
 use crate::db::models::SystemEventRecord;
 use crate::db::queries;
 use crate::error::AppResult;
+use crate::event_kind::EventKind;
+use crate::metrics::SseConnectionMetrics;
+use crate::rate_limit::SseStreamId;
 use crate::state::AppState;
 
 const SSE_POLL_LIMIT: i64 = 100;
 const SSE_POLL_INTERVAL_MS: u64 = 750;
+const SSE_BUFFER: usize = 64;
+/// A client whose buffer stays full this long is disconnected, so a stalled reader does not
+/// hold its stream slot and polling task forever.
+const SSE_SLOW_CONSUMER_AFTER: Duration = Duration::from_secs(30);
+/// Sent as the `retry` hint with the closing event.
+const SSE_RECONNECT_AFTER: Duration = Duration::from_secs(5);
+/// The last event a stream sends before it is closed for falling behind.
+const SSE_CLOSED_EVENT: &str = "stream.closed";
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/events", get(stream_events))
+        .route("/event-types", get(list_event_types))
         .route("/projects/{slug}/events", get(stream_project_events))
 }
 
+#[derive(Debug, Serialize)]
+struct EventTypeResponse {
+    name: EventKind,
+    namespace: &'static str,
+    description: &'static str,
+    /// Whether webhooks may list it in `events`.
+    subscribable: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct EventsQuery {
     #[serde(default)]
@@ -33,7 +60,7 @@ struct EventsQuery {
 }
 
 #[derive(Debug, Serialize)]
-struct TaskEventPayload {
+pub struct TaskEventPayload {
     id: String,
     project: String,
     task_id: Option<String>,
@@ -41,52 +68,225 @@ struct TaskEventPayload {
     task_display_key: Option<String>,
     action: String,
     actor: String,
+    actor_display_name: Option<String>,
+    actor_kind: Option<String>,
+    actor_avatar_url: Option<String>,
     detail: Value,
     created_at: String,
 }
 
+/// Every event type lattice records, for clients building webhook subscriptions or SSE
+/// handlers.
+async fn list_event_types() -> Json<Vec<EventTypeResponse>> {
+    Json(
+        EventKind::ALL
+            .into_iter()
+            .map(|kind| EventTypeResponse {
+                name: kind,
+                namespace: kind.namespace(),
+                description: kind.description(),
+                subscribable: kind.subscribable(),
+            })
+            .collect(),
+    )
+}
+
 async fn stream_events(
     State(state): State<AppState>,
+    scope: AccessScope,
+    stream_id: Option<Extension<SseStreamId>>,
+    headers: HeaderMap,
     Query(query): Query<EventsQuery>,
 ) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
     let projects = normalize_project_filters(query.project)?;
-    Ok(build_sse_stream(state, projects))
+    let workspace_id = scope.workspace_id().map(ToOwned::to_owned);
+    let resume_from = resume_cursor(&state, &headers).await?;
+    Ok(build_sse_stream(
+        state,
+        stream_id.map(|Extension(id)| id),
+        workspace_id,
+        projects,
+        resume_from,
+    ))
 }
 
 async fn stream_project_events(
     State(state): State<AppState>,
+    stream_id: Option<Extension<SseStreamId>>,
+    headers: HeaderMap,
     Path(slug): Path<String>,
 ) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
     let project_slug = queries::normalize_slug(&slug)?;
     let _ = queries::get_project(&state.db, &project_slug).await?;
-    Ok(build_sse_stream(state, vec![project_slug]))
+    let resume_from = resume_cursor(&state, &headers).await?;
+    Ok(build_sse_stream(
+        state,
+        stream_id.map(|Extension(id)| id),
+        None,
+        vec![project_slug],
+        resume_from,
+    ))
 }
 
+/// A reconnecting client sends the id of the last event it received in `Last-Event-ID`, and
+/// the stream picks up right after it. Unknown ids start from the newest event as usual.
+async fn resume_cursor(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> AppResult<Option<(String, String)>> {
+    let Some(event_id) = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    queries::system_event_cursor(&state.db, event_id).await
+}
+
+/// `stream_id` is the rate limiter's lease for the stream; it is missing when the limiter
+/// does not run, as in tests.
 fn build_sse_stream(
     state: AppState,
+    stream_id: Option<SseStreamId>,
+    workspace_id: Option<String>,
     project_slugs: Vec<String>,
+    resume_from: Option<(String, String)>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let (sender, receiver) = mpsc::channel::<Result<Event, Infallible>>(64);
+    let rate_limiter = state.rate_limiter.clone();
+    let events = spawn_event_stream(
+        state,
+        workspace_id.clone(),
+        project_slugs.clone(),
+        resume_from,
+        SSE_SLOW_CONSUMER_AFTER,
+    );
+    if let Some(stream_id) = stream_id {
+        rate_limiter.describe_sse_stream(
+            stream_id,
+            workspace_id.as_deref(),
+            &project_slugs,
+            events.connection.id(),
+        );
+    }
+    let stream = events.map(|message| Ok(message.into_event()));
+
+    Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    )
+}
+
+/// One SSE frame, kept apart from axum's `Event` so the stream can track what it sends.
+#[derive(Debug)]
+struct SseMessage {
+    id: Option<String>,
+    event: String,
+    data: String,
+    retry: Option<Duration>,
+}
+
+impl SseMessage {
+    fn into_event(self) -> Event {
+        let mut event = Event::default().event(self.event).data(self.data);
+        if let Some(id) = self.id {
+            event = event.id(id);
+        }
+        if let Some(retry) = self.retry {
+            event = event.retry(retry);
+        }
+        event
+    }
+}
+
+/// Yields the polled events, or only the closing event once the poller gave up on a client
+/// that stopped reading; whatever was still buffered is dropped, since the client resumes
+/// from the closing event's id.
+struct EventStream {
+    events: mpsc::Receiver<SseMessage>,
+    closing: Option<oneshot::Receiver<SseMessage>>,
+    closed: bool,
+    connection: Arc<SseConnectionMetrics>,
+}
+
+impl Stream for EventStream {
+    type Item = SseMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.closed {
+            return Poll::Ready(None);
+        }
+        if let Some(closing) = self.closing.as_mut() {
+            if let Poll::Ready(result) = Pin::new(closing).poll(cx) {
+                self.closing = None;
+                if let Ok(message) = result {
+                    self.closed = true;
+                    return Poll::Ready(Some(message));
+                }
+            }
+        }
+
+        let polled = self.events.poll_recv(cx);
+        if let Poll::Ready(Some(message)) = &polled {
+            self.connection.sent(message.id.as_deref());
+        }
+        polled
+    }
+}
+
+fn spawn_event_stream(
+    state: AppState,
+    workspace_id: Option<String>,
+    project_slugs: Vec<String>,
+    resume_from: Option<(String, String)>,
+    slow_consumer_after: Duration,
+) -> EventStream {
+    let (sender, receiver) = mpsc::channel::<SseMessage>(SSE_BUFFER);
+    let (close, closing) = oneshot::channel::<SseMessage>();
+    let connection = state.metrics.open_sse_connection();
+    let stream = EventStream {
+        events: receiver,
+        closing: Some(closing),
+        closed: false,
+        connection: connection.clone(),
+    };
     let db = state.db.clone();
 
     tokio::spawn(async move {
-        let (mut last_created_at, mut last_event_id) =
-            match queries::latest_system_event_cursor(&db, &project_slugs).await {
-                Ok(Some((created_at, event_id))) => (Some(created_at), Some(event_id)),
-                Ok(None) => (None, None),
-                Err(error) => {
-                    tracing::error!(error = ?error, "failed to initialize sse cursor");
-                    (None, None)
+        let cursor = match resume_from {
+            Some(cursor) => Some(cursor),
+            None => {
+                match queries::latest_system_event_cursor(
+                    &db,
+                    workspace_id.as_deref(),
+                    &project_slugs,
+                )
+                .await
+                {
+                    Ok(cursor) => cursor,
+                    Err(error) => {
+                        tracing::error!(error = ?error, "failed to initialize sse cursor");
+                        None
+                    }
                 }
-            };
+            }
+        };
+        let resumed_from = cursor.as_ref().map(|(_, event_id)| event_id.clone());
+        let (mut last_created_at, mut last_event_id) = cursor.unzip();
         let mut interval = tokio::time::interval(Duration::from_millis(SSE_POLL_INTERVAL_MS));
         interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         loop {
             interval.tick().await;
+            if sender.is_closed() {
+                return;
+            }
 
             let events = match queries::list_system_events(
                 &db,
+                workspace_id.as_deref(),
                 &project_slugs,
                 last_created_at.as_deref(),
                 last_event_id.as_deref(),
@@ -114,23 +314,49 @@ fn build_sse_stream(
                     }
                 };
 
-                let event = Event::default()
-                    .id(payload.id)
-                    .event(payload.action)
-                    .data(serialized);
+                let message = SseMessage {
+                    id: Some(payload.id),
+                    event: payload.action,
+                    data: serialized,
+                    retry: None,
+                };
 
-                if sender.send(Ok(event)).await.is_err() {
-                    return;
+                connection.queued();
+                match tokio::time::timeout(slow_consumer_after, sender.send(message)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(_)) => return,
+                    Err(_) => {
+                        let resume_id = connection.last_sent_id().or(resumed_from);
+                        tracing::warn!(
+                            connection = connection.id(),
+                            "closing sse stream of a client that stopped reading"
+                        );
+                        connection.disconnected_slow_consumer();
+                        let _ = close.send(closing_message(resume_id));
+                        return;
+                    }
                 }
             }
         }
     });
 
-    Sse::new(ReceiverStream::new(receiver)).keep_alive(
-        KeepAlive::new()
-            .interval(Duration::from_secs(15))
-            .text("keep-alive"),
-    )
+    stream
+}
+
+/// Tells a client that fell too far behind to reconnect, resuming after `resume_id`. The
+/// event carries that id, so an `EventSource` sends it back as `Last-Event-ID` on its own.
+fn closing_message(resume_id: Option<String>) -> SseMessage {
+    SseMessage {
+        data: json!({
+            "reason": "slow_consumer",
+            "message": "the client fell behind; reconnect with Last-Event-ID to resume",
+            "last_event_id": resume_id,
+        })
+        .to_string(),
+        id: resume_id,
+        event: SSE_CLOSED_EVENT.to_string(),
+        retry: Some(SSE_RECONNECT_AFTER),
+    }
 }
 
 fn normalize_project_filters(projects: Vec<String>) -> AppResult<Vec<String>> {
@@ -141,7 +367,7 @@ fn normalize_project_filters(projects: Vec<String>) -> AppResult<Vec<String>> {
     Ok(normalized.into_iter().collect())
 }
 
-fn map_task_event(event: SystemEventRecord) -> TaskEventPayload {
+pub fn map_task_event(event: SystemEventRecord) -> TaskEventPayload {
     let display_key = event
         .task_number
         .map(|task_number| queries::display_key(&event.project_slug, task_number));
@@ -153,6 +379,9 @@ fn map_task_event(event: SystemEventRecord) -> TaskEventPayload {
         task_display_key: display_key,
         action: event.action,
         actor: event.actor,
+        actor_display_name: event.actor_display_name,
+        actor_kind: event.actor_kind,
+        actor_avatar_url: event.actor_avatar_url,
         detail: parse_event_detail(&event.detail),
         created_at: event.created_at,
     }
@@ -171,11 +400,16 @@ mod tests {
     use axum::Router;
     use reqwest::header::ACCEPT;
     use reqwest::StatusCode;
+    use serde_json::Value;
     use tempfile::tempdir;
     use tokio::time::timeout;
+    use tokio_stream::StreamExt;
 
+    use super::{list_event_types, spawn_event_stream, SSE_BUFFER, SSE_CLOSED_EVENT};
     use crate::api;
-    use crate::config::{Config, RateLimitConfig};
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
     use crate::db;
     use crate::db::queries;
     use crate::db::queries::NewTaskInput;
@@ -189,13 +423,23 @@ mod tests {
         let storage_dir = temp_dir.path().join("storage");
 
         let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
             port: 0,
             db_url,
             token: None,
             log_level: "info".to_string(),
             storage_dir,
             max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
             rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
         };
 
         let pool = db::connect_and_migrate(&config)
@@ -241,6 +485,7 @@ mod tests {
 
         queries::create_task(
             &pool,
+            &FieldLimitConfig::default(),
             "EVENTS",
             NewTaskInput {
                 title: "Trigger SSE".to_string(),
@@ -250,6 +495,8 @@ mod tests {
                 review_state: "ready".to_string(),
                 labels: Vec::new(),
                 created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
             },
         )
         .await
@@ -282,4 +529,125 @@ mod tests {
 
         server.abort();
     }
+
+    #[tokio::test]
+    async fn event_types_list_each_kind_with_its_namespace() {
+        let types =
+            serde_json::to_value(list_event_types().await.0).expect("event types should serialize");
+        let find = |name: &str| {
+            types
+                .as_array()
+                .and_then(|types| types.iter().find(|entry| entry["name"] == name))
+                .cloned()
+                .unwrap_or(Value::Null)
+        };
+        assert_eq!(find("task.created")["namespace"], "task");
+        assert_eq!(find("task.created")["subscribable"], true);
+        assert_eq!(find("events.held")["subscribable"], false);
+    }
+
+    #[tokio::test]
+    async fn slow_consumers_are_closed_and_can_resume_after_the_last_event() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("lattice.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Events", "SSE", "EVENTS")
+            .await
+            .expect("project should be created");
+        let state = AppState::new(config, pool.clone());
+        let projects = vec!["EVENTS".to_string()];
+
+        let mut stalled = spawn_event_stream(
+            state.clone(),
+            None,
+            projects.clone(),
+            None,
+            Duration::from_millis(200),
+        );
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        for number in 0..SSE_BUFFER + 6 {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "EVENTS",
+                NewTaskInput {
+                    title: format!("Flood {number}"),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task creation should succeed");
+        }
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let closing = timeout(Duration::from_secs(5), stalled.next())
+            .await
+            .expect("closing event should arrive")
+            .expect("stream should yield the closing event");
+        assert_eq!(closing.event, SSE_CLOSED_EVENT);
+        let data: Value = serde_json::from_str(&closing.data).expect("data should be json");
+        assert_eq!(data["reason"], "slow_consumer");
+        assert!(
+            stalled.next().await.is_none(),
+            "nothing follows the closing event"
+        );
+        assert!(state
+            .metrics
+            .render_prometheus()
+            .lines()
+            .any(|line| line == "lattice_sse_slow_consumer_disconnects_total 1"));
+
+        let events = queries::list_system_events(&pool, None, &projects, None, None, 200)
+            .await
+            .expect("events should load");
+        let first_task = events
+            .iter()
+            .position(|event| event.action == "task.created")
+            .expect("task events should exist");
+        let resume_from = queries::system_event_cursor(&pool, &events[first_task].id)
+            .await
+            .expect("cursor should load");
+        let mut resumed = spawn_event_stream(
+            state.clone(),
+            None,
+            projects,
+            resume_from,
+            Duration::from_secs(5),
+        );
+        let next = timeout(Duration::from_secs(5), resumed.next())
+            .await
+            .expect("resumed stream should yield")
+            .expect("resumed stream should stay open");
+        assert_eq!(next.id.as_deref(), Some(events[first_task + 1].id.as_str()));
+    }
 }
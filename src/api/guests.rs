@@ -0,0 +1,244 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::auth::{hash_token, AccessScope};
+use crate::api::json::ApiJson;
+use crate::db::models::GuestTokenRecord;
+use crate::db::queries;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+const DEFAULT_GUEST_TOKEN_HOURS: u32 = 24 * 7;
+const MAX_GUEST_TOKEN_HOURS: u32 = 24 * 30;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{slug}/guest-tokens",
+            get(list_tokens).post(create_token),
+        )
+        .route(
+            "/projects/{slug}/guest-tokens/{token_id}",
+            delete(revoke_token),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateGuestTokenRequest {
+    #[serde(default)]
+    label: String,
+    expires_in_hours: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct GuestTokenSecretResponse {
+    guest_token: GuestTokenRecord,
+    token: String,
+}
+
+async fn list_tokens(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<GuestTokenRecord>>> {
+    scope.require_instance()?;
+    let tokens = queries::list_guest_tokens(&state.db, &slug).await?;
+    Ok(Json(tokens))
+}
+
+async fn create_token(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    ApiJson(request): ApiJson<CreateGuestTokenRequest>,
+) -> AppResult<(StatusCode, Json<GuestTokenSecretResponse>)> {
+    scope.require_instance()?;
+    let hours = request
+        .expires_in_hours
+        .unwrap_or(DEFAULT_GUEST_TOKEN_HOURS);
+    if !(1..=MAX_GUEST_TOKEN_HOURS).contains(&hours) {
+        return Err(AppError::BadRequest(format!(
+            "expires_in_hours must be between 1 and {MAX_GUEST_TOKEN_HOURS}"
+        )));
+    }
+
+    let expires_at = (Utc::now() + chrono::Duration::hours(i64::from(hours)))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+    let token = format!("lgt_{}", Uuid::new_v4().simple());
+    let guest_token = queries::create_guest_token(
+        &state.db,
+        &slug,
+        &hash_token(&token),
+        &request.label,
+        &actor_from_headers(&headers),
+        &expires_at,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(GuestTokenSecretResponse { guest_token, token }),
+    ))
+}
+
+async fn revoke_token(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path((slug, token_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    scope.require_instance()?;
+    queries::revoke_guest_token(&state.db, &slug, &token_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::Router;
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn guest_tokens_read_one_project_until_they_expire() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("guests_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: Some("instance-token".to_string()),
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        for (name, slug) in [("Design", "DESIGN"), ("Other", "OTHER")] {
+            queries::create_project_with_slug(&pool, name, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let minted: Value = client
+            .post(format!("{api}/projects/DESIGN/guest-tokens"))
+            .bearer_auth("instance-token")
+            .json(&json!({ "label": "review week", "expires_in_hours": 48 }))
+            .send()
+            .await
+            .expect("guest token mint should succeed")
+            .json()
+            .await
+            .expect("guest token body should parse");
+        let guest = minted["token"].as_str().expect("token should be returned");
+
+        let status_of = |request: reqwest::RequestBuilder| async move {
+            request
+                .bearer_auth(guest)
+                .send()
+                .await
+                .expect("request should complete")
+                .status()
+        };
+        assert_eq!(
+            status_of(client.get(format!("{api}/projects/DESIGN/tasks"))).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_of(client.get(format!("{api}/projects/DESIGN/spec"))).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_of(client.get(format!("{api}/projects/OTHER/tasks"))).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status_of(client.get(format!("{api}/projects/DESIGN/webhooks"))).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status_of(
+                client
+                    .post(format!("{api}/projects/DESIGN/tasks"))
+                    .json(&json!({ "title": "nope" }))
+            )
+            .await,
+            StatusCode::FORBIDDEN
+        );
+
+        sqlx::query("UPDATE guest_tokens SET expires_at = ?")
+            .bind(queries::timestamp_days_ago(1))
+            .execute(&pool)
+            .await
+            .expect("token should be expired");
+        assert_eq!(
+            status_of(client.get(format!("{api}/projects/DESIGN/tasks"))).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        server.abort();
+    }
+}
@@ -0,0 +1,701 @@
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, patch, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::api::json::ApiJson;
+use crate::db::models::InboundHookRecord;
+use crate::db::queries;
+use crate::db::queries::{CreateInboundHookInput, NewTaskInput, UpdateInboundHookInput};
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+const SIGNATURE_HEADER: &str = "X-Lattice-Signature";
+const TIMESTAMP_HEADER: &str = "X-Lattice-Timestamp";
+/// How far `X-Lattice-Timestamp` may drift from the server clock before a call is rejected.
+const MAX_TIMESTAMP_SKEW_SECS: i64 = 300;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{slug}/inbound-hooks",
+            get(list_hooks).post(create_hook),
+        )
+        .route(
+            "/projects/{slug}/inbound-hooks/{hook_id}",
+            patch(update_hook).delete(delete_hook),
+        )
+        .route(
+            "/projects/{slug}/inbound-hooks/{hook_id}/secret",
+            post(rotate_secret),
+        )
+        .route("/inbound/{slug}/{hook_id}", post(receive))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateInboundHookRequest {
+    name: String,
+    kind: String,
+    template: Value,
+    active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateInboundHookRequest {
+    name: Option<String>,
+    template: Option<Value>,
+    active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct InboundHookResponse {
+    id: String,
+    name: String,
+    kind: String,
+    url: String,
+    template: Value,
+    active: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InboundHookSecretResponse {
+    hook: InboundHookResponse,
+    secret: String,
+}
+
+/// Fields of a task created by an inbound call, after the template is rendered.
+#[derive(Debug, Deserialize)]
+struct InboundTaskFields {
+    title: String,
+    description: Option<String>,
+    status: Option<String>,
+    priority: Option<String>,
+    review_state: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InboundQuestionFields {
+    task: String,
+    question: String,
+    #[serde(default)]
+    context: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InboundResult {
+    kind: String,
+    id: String,
+    task_id: String,
+    display_key: Option<String>,
+}
+
+async fn list_hooks(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<InboundHookResponse>>> {
+    let hooks = queries::list_inbound_hooks(&state.db, &slug).await?;
+    Ok(Json(
+        hooks
+            .into_iter()
+            .map(|hook| map_hook(&slug, hook))
+            .collect(),
+    ))
+}
+
+async fn create_hook(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(request): ApiJson<CreateInboundHookRequest>,
+) -> AppResult<(StatusCode, Json<InboundHookSecretResponse>)> {
+    let secret = new_secret();
+    let hook = queries::create_inbound_hook(
+        &state.db,
+        &state.keyring,
+        &slug,
+        CreateInboundHookInput {
+            name: request.name,
+            kind: request.kind,
+            template: request.template,
+            secret: secret.clone(),
+            active: request.active.unwrap_or(true),
+        },
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InboundHookSecretResponse {
+            hook: map_hook(&slug, hook),
+            secret,
+        }),
+    ))
+}
+
+async fn update_hook(
+    State(state): State<AppState>,
+    Path((slug, hook_id)): Path<(String, String)>,
+    ApiJson(request): ApiJson<UpdateInboundHookRequest>,
+) -> AppResult<Json<InboundHookResponse>> {
+    if request.name.is_none() && request.template.is_none() && request.active.is_none() {
+        return Err(AppError::BadRequest(
+            "at least one field must be provided".to_string(),
+        ));
+    }
+
+    let hook = queries::update_inbound_hook(
+        &state.db,
+        &state.keyring,
+        &slug,
+        &hook_id,
+        UpdateInboundHookInput {
+            name: request.name,
+            template: request.template,
+            secret: None,
+            active: request.active,
+        },
+    )
+    .await?;
+    Ok(Json(map_hook(&slug, hook)))
+}
+
+async fn rotate_secret(
+    State(state): State<AppState>,
+    Path((slug, hook_id)): Path<(String, String)>,
+) -> AppResult<Json<InboundHookSecretResponse>> {
+    let secret = new_secret();
+    let hook = queries::update_inbound_hook(
+        &state.db,
+        &state.keyring,
+        &slug,
+        &hook_id,
+        UpdateInboundHookInput {
+            secret: Some(secret.clone()),
+            ..UpdateInboundHookInput::default()
+        },
+    )
+    .await?;
+
+    Ok(Json(InboundHookSecretResponse {
+        hook: map_hook(&slug, hook),
+        secret,
+    }))
+}
+
+async fn delete_hook(
+    State(state): State<AppState>,
+    Path((slug, hook_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    queries::delete_inbound_hook(&state.db, &slug, &hook_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Creates a task or question from a signed external call.
+///
+/// This route skips bearer auth; the `X-Lattice-Signature: sha256=<hex>` HMAC of
+/// `<X-Lattice-Timestamp>.<raw body>` under the hook's secret authenticates the caller instead.
+/// Timestamps more than [`MAX_TIMESTAMP_SKEW_SECS`] away from now are rejected so captured
+/// calls cannot be replayed later, and unknown hooks answer `401` like bad signatures.
+async fn receive(
+    State(state): State<AppState>,
+    Path((slug, hook_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<(StatusCode, Json<InboundResult>)> {
+    let slug = queries::normalize_slug(&slug)?;
+    let timestamp = fresh_timestamp(&headers)?;
+    let hook = match queries::get_inbound_hook(&state.db, &slug, &hook_id).await {
+        Ok(hook) => hook,
+        Err(AppError::NotFound(_)) => return Err(AppError::Unauthorized),
+        Err(error) => return Err(error),
+    };
+    let secret = state.keyring.open(&hook.secret)?;
+    verify_signature(&secret, &headers, timestamp, &body)?;
+    if hook.active != 1 {
+        return Err(AppError::Conflict(format!(
+            "inbound hook '{hook_id}' is disabled"
+        )));
+    }
+
+    let payload = serde_json::from_slice::<Value>(&body)
+        .map_err(|error| AppError::BadRequest(format!("invalid JSON body: {error}")))?;
+    let template = serde_json::from_str::<Value>(&hook.template).map_err(|error| {
+        tracing::error!(error = ?error, hook_id = %hook.id, "stored inbound template is invalid");
+        AppError::Internal
+    })?;
+    let rendered = render_template(&template, &payload);
+    let actor = format!("inbound:{}", hook.name);
+
+    let result = match hook.kind.as_str() {
+        "question" => {
+            let fields = serde_json::from_value::<InboundQuestionFields>(rendered)
+                .map_err(|error| rendered_field_error(&error))?;
+            let question = queries::create_open_question(
+                &state.db,
+                &state.config.field_limits,
+                &slug,
+                fields.task.trim(),
+                &fields.question,
+                &fields.context,
+                &actor,
+            )
+            .await?;
+            InboundResult {
+                kind: hook.kind,
+                id: question.id,
+                task_id: question.task_id,
+                display_key: None,
+            }
+        }
+        _ => {
+            let fields = serde_json::from_value::<InboundTaskFields>(rendered)
+                .map_err(|error| rendered_field_error(&error))?;
+            let task = queries::create_task(
+                &state.db,
+                &state.config.field_limits,
+                &slug,
+                NewTaskInput {
+                    title: fields.title,
+                    description: fields.description.unwrap_or_default(),
+                    status: non_empty_or(fields.status, "backlog"),
+                    priority: non_empty_or(fields.priority, "medium"),
+                    review_state: non_empty_or(fields.review_state, "ready"),
+                    labels: fields.labels,
+                    created_by: actor,
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await?;
+            InboundResult {
+                kind: hook.kind,
+                id: task.id.clone(),
+                task_id: task.id,
+                display_key: Some(queries::display_key(&slug, task.task_number)),
+            }
+        }
+    };
+
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
+fn fresh_timestamp(headers: &HeaderMap) -> AppResult<i64> {
+    let timestamp = headers
+        .get(TIMESTAMP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok())
+        .ok_or(AppError::Unauthorized)?;
+    if (Utc::now().timestamp() - timestamp).abs() > MAX_TIMESTAMP_SKEW_SECS {
+        return Err(AppError::Unauthorized);
+    }
+    Ok(timestamp)
+}
+
+fn verify_signature(
+    secret: &str,
+    headers: &HeaderMap,
+    timestamp: i64,
+    body: &[u8],
+) -> AppResult<()> {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().strip_prefix("sha256="))
+        .and_then(decode_hex)
+        .ok_or(AppError::Unauthorized)?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|_| AppError::Internal)?;
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| AppError::Unauthorized)
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(value.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// Replaces `{{/json/pointer}}` placeholders in template strings with values from `payload`.
+///
+/// A string that is exactly one placeholder takes the referenced value as-is (so arrays can
+/// feed `labels`); otherwise values are interpolated as text and missing ones become empty.
+fn render_template(template: &Value, payload: &Value) -> Value {
+    match template {
+        Value::String(text) => render_string(text, payload),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_template(item, payload))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), render_template(value, payload)))
+                .collect::<Map<_, _>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn render_string(text: &str, payload: &Value) -> Value {
+    if let Some(pointer) = text
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+        .filter(|pointer| !pointer.contains("{{"))
+    {
+        return payload
+            .pointer(pointer.trim())
+            .cloned()
+            .unwrap_or_else(|| Value::String(String::new()));
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let pointer = rest[start + 2..start + end].trim();
+        match payload.pointer(pointer) {
+            Some(Value::String(value)) => output.push_str(value),
+            Some(Value::Null) | None => {}
+            Some(value) => output.push_str(&value.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    output.push_str(rest);
+    Value::String(output)
+}
+
+fn rendered_field_error(error: &serde_json::Error) -> AppError {
+    AppError::BadRequest(format!(
+        "request body did not fill the hook template: {error}"
+    ))
+}
+
+fn non_empty_or(value: Option<String>, default: &str) -> String {
+    value
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn new_secret() -> String {
+    format!("lih_{}", Uuid::new_v4().simple())
+}
+
+fn map_hook(slug: &str, hook: InboundHookRecord) -> InboundHookResponse {
+    let template = serde_json::from_str::<Value>(&hook.template).unwrap_or(Value::Null);
+    InboundHookResponse {
+        url: format!("/api/v1/inbound/{slug}/{}", hook.id),
+        id: hook.id,
+        name: hook.name,
+        kind: hook.kind,
+        template,
+        active: hook.active == 1,
+        created_at: hook.created_at,
+        updated_at: hook.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::Router;
+    use hmac::{Hmac, Mac};
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+    use sha2::Sha256;
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::state::AppState;
+
+    fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac should init");
+        mac.update(format!("{timestamp}.").as_bytes());
+        mac.update(body);
+        let hex = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        format!("sha256={hex}")
+    }
+
+    #[test]
+    fn templates_render_pointers_into_fields() {
+        let payload = json!({
+            "alert": { "name": "disk full", "severity": 2, "tags": ["ops", "disk"] }
+        });
+        let rendered = super::render_template(
+            &json!({
+                "title": "[sev {{/alert/severity}}] {{ /alert/name }}",
+                "labels": "{{/alert/tags}}",
+                "description": "{{/alert/missing}}",
+                "priority": "high"
+            }),
+            &payload,
+        );
+
+        assert_eq!(rendered["title"], "[sev 2] disk full");
+        assert_eq!(rendered["labels"], json!(["ops", "disk"]));
+        assert_eq!(rendered["description"], "");
+        assert_eq!(rendered["priority"], "high");
+    }
+
+    #[tokio::test]
+    async fn signed_inbound_calls_create_tasks_without_bearer_auth() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("inbound_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: Some("instance-token".to_string()),
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig {
+                secret_key: Some("inbound test key".to_string()),
+                previous_secret_keys: Vec::new(),
+            },
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Ops", "alerts", "OPS")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let keyring = state.keyring.clone();
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let rejected = client
+            .post(format!("{api}/projects/OPS/inbound-hooks"))
+            .bearer_auth("instance-token")
+            .json(&json!({ "name": "alerts", "kind": "task", "template": { "summary": "x" } }))
+            .send()
+            .await
+            .expect("hook create should complete");
+        assert_eq!(rejected.status(), StatusCode::BAD_REQUEST);
+
+        let created: Value = client
+            .post(format!("{api}/projects/OPS/inbound-hooks"))
+            .bearer_auth("instance-token")
+            .json(&json!({
+                "name": "alerts",
+                "kind": "task",
+                "template": {
+                    "title": "{{/alert/name}}",
+                    "priority": "{{/alert/priority}}",
+                    "labels": ["alert"]
+                }
+            }))
+            .send()
+            .await
+            .expect("hook create should succeed")
+            .json()
+            .await
+            .expect("hook body should parse");
+        let secret = created["secret"]
+            .as_str()
+            .expect("secret should be returned")
+            .to_string();
+        let stored_secret = || async {
+            sqlx::query_scalar::<sqlx::Any, String>("SELECT secret FROM inbound_hooks")
+                .fetch_one(&pool)
+                .await
+                .expect("secret should be stored")
+        };
+        let sealed = stored_secret().await;
+        assert!(sealed.starts_with("enc:v1:") && !sealed.contains(&secret));
+        let url = format!(
+            "http://{addr}{}",
+            created["hook"]["url"]
+                .as_str()
+                .expect("url should be returned")
+        );
+
+        let body = serde_json::to_vec(
+            &json!({ "alert": { "name": "disk full", "priority": "critical" } }),
+        )
+        .expect("body should encode");
+        let unsigned = client
+            .post(&url)
+            .body(body.clone())
+            .send()
+            .await
+            .expect("unsigned call should complete");
+        assert_eq!(unsigned.status(), StatusCode::UNAUTHORIZED);
+
+        let now = chrono::Utc::now().timestamp();
+        let untimed = client
+            .post(&url)
+            .header("X-Lattice-Signature", sign(&secret, now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("untimed call should complete");
+        assert_eq!(untimed.status(), StatusCode::UNAUTHORIZED);
+
+        let forged = client
+            .post(&url)
+            .header("X-Lattice-Timestamp", now.to_string())
+            .header("X-Lattice-Signature", sign("wrong-secret", now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("forged call should complete");
+        assert_eq!(forged.status(), StatusCode::UNAUTHORIZED);
+
+        // A captured call replayed outside the skew window is refused even though it is signed.
+        let stale = now - 600;
+        let replayed = client
+            .post(&url)
+            .header("X-Lattice-Timestamp", stale.to_string())
+            .header("X-Lattice-Signature", sign(&secret, stale, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("replayed call should complete");
+        assert_eq!(replayed.status(), StatusCode::UNAUTHORIZED);
+
+        let unknown = client
+            .post(format!("{api}/inbound/OPS/missing-hook"))
+            .header("X-Lattice-Timestamp", now.to_string())
+            .header("X-Lattice-Signature", sign(&secret, now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("unknown hook call should complete");
+        assert_eq!(unknown.status(), StatusCode::UNAUTHORIZED);
+
+        let accepted = client
+            .post(&url)
+            .header("X-Lattice-Timestamp", now.to_string())
+            .header("X-Lattice-Signature", sign(&secret, now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("signed call should succeed");
+        assert_eq!(accepted.status(), StatusCode::CREATED);
+        let result: Value = accepted.json().await.expect("result should parse");
+        assert_eq!(result["display_key"], "OPS-1");
+
+        let task = queries::get_task_details(&pool, "OPS", "OPS-1")
+            .await
+            .expect("task should exist");
+        assert_eq!(task.task.title, "disk full");
+        assert_eq!(task.task.priority, "critical");
+        assert_eq!(task.task.created_by, "inbound:alerts");
+        assert_eq!(task.labels, vec!["alert".to_string()]);
+
+        // A secret stored before encryption was turned on is sealed by the startup pass.
+        sqlx::query("UPDATE inbound_hooks SET secret = ?")
+            .bind(&secret)
+            .execute(&pool)
+            .await
+            .expect("plaintext secret should be written");
+        let rewrapped = queries::rewrap_stored_secrets(&pool, &keyring)
+            .await
+            .expect("secrets should rewrap");
+        assert_eq!(rewrapped, 1);
+        assert!(stored_secret().await.starts_with("enc:v1:"));
+        let resealed = client
+            .post(&url)
+            .header("X-Lattice-Timestamp", now.to_string())
+            .header("X-Lattice-Signature", sign(&secret, now, &body))
+            .body(body.clone())
+            .send()
+            .await
+            .expect("signed call should succeed");
+        assert_eq!(resealed.status(), StatusCode::CREATED);
+
+        let rotated = client
+            .post(format!(
+                "{api}/projects/OPS/inbound-hooks/{}/secret",
+                created["hook"]["id"]
+                    .as_str()
+                    .expect("id should be returned")
+            ))
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("rotation should complete");
+        assert_eq!(rotated.status(), StatusCode::OK);
+        let rotated: Value = rotated.json().await.expect("rotation body should parse");
+        assert_ne!(rotated["secret"], secret.as_str());
+        let outdated = client
+            .post(&url)
+            .header("X-Lattice-Timestamp", now.to_string())
+            .header("X-Lattice-Signature", sign(&secret, now, &body))
+            .body(body)
+            .send()
+            .await
+            .expect("old-secret call should complete");
+        assert_eq!(outdated.status(), StatusCode::UNAUTHORIZED);
+
+        server.abort();
+    }
+}
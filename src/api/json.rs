@@ -0,0 +1,79 @@
+use axum::extract::{FromRequest, Request};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// JSON body extractor that honours `LATTICE_STRICT_JSON`.
+///
+/// With strict mode off this behaves exactly like [`Json`]. With it on, any field the target
+/// type does not know about is rejected with a 400 naming the offending path.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApiJson<T>(pub T);
+
+impl<T> FromRequest<AppState> for ApiJson<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &AppState) -> Result<Self, Self::Rejection> {
+        if !state.config.strict_json {
+            let Json(value) = Json::<T>::from_request(request, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Ok(Self(value));
+        }
+
+        let Json(raw) = Json::<Value>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        from_value_strict(raw)
+            .map(Self)
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+/// Deserializes `value`, failing if it carries fields `T` would silently ignore.
+pub fn from_value_strict<T: DeserializeOwned>(value: Value) -> Result<T, AppError> {
+    let mut unknown = Vec::new();
+    let parsed = serde_ignored::deserialize(value, |path| unknown.push(path.to_string()))
+        .map_err(|error| AppError::BadRequest(format!("invalid request body: {error}")))?;
+
+    if !unknown.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "unknown field(s) in request body: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::from_value_strict;
+
+    #[derive(Debug, Deserialize)]
+    struct Sample {
+        #[allow(dead_code)]
+        title: String,
+        #[allow(dead_code)]
+        priority: Option<String>,
+    }
+
+    #[test]
+    fn strict_parsing_reports_misspelled_fields() {
+        assert!(from_value_strict::<Sample>(json!({ "title": "a", "priority": "high" })).is_ok());
+
+        let error = from_value_strict::<Sample>(json!({ "title": "a", "prioirty": "high" }))
+            .expect_err("unknown field should be rejected");
+        assert!(error.to_string().contains("prioirty"));
+    }
+}
@@ -0,0 +1,80 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::auth::{hash_token, AccessScope};
+use crate::api::json::ApiJson;
+use crate::db::models::McpKeyRecord;
+use crate::db::queries;
+use crate::error::AppResult;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/mcp-keys", get(list_keys).post(create_key))
+        .route("/mcp-keys/{key_id}", delete(revoke_key))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateMcpKeyRequest {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct McpKeySecretResponse {
+    key: McpKeyRecord,
+    token: String,
+}
+
+async fn list_keys(
+    State(state): State<AppState>,
+    scope: AccessScope,
+) -> AppResult<Json<Vec<McpKeyRecord>>> {
+    scope.require_instance()?;
+    let keys = queries::list_mcp_keys(&state.db).await?;
+    Ok(Json(keys))
+}
+
+async fn create_key(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+    ApiJson(request): ApiJson<CreateMcpKeyRequest>,
+) -> AppResult<(StatusCode, Json<McpKeySecretResponse>)> {
+    scope.require_instance()?;
+    let token = format!("lmk_{}", Uuid::new_v4().simple());
+    let key = queries::create_mcp_key(
+        &state.db,
+        &request.name,
+        &hash_token(&token),
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(McpKeySecretResponse { key, token }),
+    ))
+}
+
+async fn revoke_key(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(key_id): Path<String>,
+) -> AppResult<StatusCode> {
+    scope.require_instance()?;
+    queries::revoke_mcp_key(&state.db, &key_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
@@ -0,0 +1,552 @@
+use std::collections::BTreeMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::auth::AccessScope;
+use crate::db::models::TaskTransitionRecord;
+use crate::db::queries;
+use crate::error::{AppError, AppResult};
+use crate::event_kind::EventKind;
+use crate::state::AppState;
+
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+const MAX_WINDOW_DAYS: i64 = 365;
+const STATUSES: [&str; 5] = ["backlog", "ready", "in_progress", "review", "done"];
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/metrics", get(get_prometheus_metrics))
+        .route("/projects/{slug}/metrics/flow", get(get_flow_metrics))
+        .route(
+            "/projects/{slug}/metrics/throughput",
+            get(get_throughput_metrics),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct FlowMetricsQuery {
+    days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ThroughputQuery {
+    window: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ThroughputResponse {
+    project: String,
+    window_days: i64,
+    generated_at: String,
+    total_created: i64,
+    total_completed: i64,
+    days: Vec<ThroughputPoint>,
+}
+
+#[derive(Debug, Serialize)]
+struct ThroughputPoint {
+    date: String,
+    created: i64,
+    completed: i64,
+    open: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct FlowMetricsResponse {
+    project: String,
+    window_days: i64,
+    generated_at: String,
+    lead_time: DurationStats,
+    cycle_time: DurationStats,
+    cumulative_flow: Vec<CumulativeFlowPoint>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct DurationStats {
+    count: usize,
+    p50_hours: Option<f64>,
+    p85_hours: Option<f64>,
+    p95_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct CumulativeFlowPoint {
+    date: String,
+    backlog: i64,
+    ready: i64,
+    in_progress: i64,
+    review: i64,
+    done: i64,
+}
+
+/// Status changes for one task, oldest first.
+#[derive(Debug, Default)]
+struct TaskTimeline {
+    created_at: Option<DateTime<Utc>>,
+    changes: Vec<(DateTime<Utc>, String)>,
+}
+
+impl TaskTimeline {
+    fn status_at(&self, at: DateTime<Utc>) -> Option<&str> {
+        if self.created_at.is_some_and(|created_at| created_at >= at) {
+            return None;
+        }
+
+        self.changes
+            .iter()
+            .take_while(|(changed_at, _)| *changed_at < at)
+            .last()
+            .map(|(_, status)| status.as_str())
+    }
+
+    fn first_entered(&self, status: &str) -> Option<DateTime<Utc>> {
+        self.changes
+            .iter()
+            .find(|(_, candidate)| candidate == status)
+            .map(|(changed_at, _)| *changed_at)
+    }
+}
+
+/// Process-wide counters in the Prometheus text exposition format.
+async fn get_prometheus_metrics(
+    State(state): State<AppState>,
+    scope: AccessScope,
+) -> AppResult<impl IntoResponse> {
+    scope.require_instance()?;
+    Ok((
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render_prometheus(),
+    ))
+}
+
+async fn get_flow_metrics(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<FlowMetricsQuery>,
+) -> AppResult<Json<FlowMetricsResponse>> {
+    let window_days = query.days.unwrap_or(DEFAULT_WINDOW_DAYS);
+    if !(1..=MAX_WINDOW_DAYS).contains(&window_days) {
+        return Err(AppError::BadRequest(format!(
+            "days must be between 1 and {MAX_WINDOW_DAYS}"
+        )));
+    }
+
+    let project = queries::get_project(&state.db, &slug).await?;
+    let transitions = queries::list_status_transitions(&state.db, &project.project.slug).await?;
+    let now = Utc::now();
+
+    Ok(Json(compute_flow_metrics(
+        project.project.slug,
+        transitions,
+        window_days,
+        now,
+    )))
+}
+
+async fn get_throughput_metrics(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<ThroughputQuery>,
+) -> AppResult<Json<ThroughputResponse>> {
+    let window_days = match query.window.as_deref() {
+        Some(value) => parse_window(value)?,
+        None => DEFAULT_WINDOW_DAYS,
+    };
+
+    let project = queries::get_project(&state.db, &slug).await?;
+    let transitions = queries::list_status_transitions(&state.db, &project.project.slug).await?;
+
+    Ok(Json(compute_throughput(
+        project.project.slug,
+        transitions,
+        window_days,
+        Utc::now(),
+    )))
+}
+
+/// Accepts `30d` or a bare day count.
+fn parse_window(value: &str) -> AppResult<i64> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_suffix('d').unwrap_or(trimmed);
+    let days = digits
+        .parse::<i64>()
+        .map_err(|_| AppError::BadRequest("window must look like '30d'".to_string()))?;
+
+    if !(1..=MAX_WINDOW_DAYS).contains(&days) {
+        return Err(AppError::BadRequest(format!(
+            "window must be between 1d and {MAX_WINDOW_DAYS}d"
+        )));
+    }
+
+    Ok(days)
+}
+
+fn compute_throughput(
+    project: String,
+    transitions: Vec<TaskTransitionRecord>,
+    window_days: i64,
+    now: DateTime<Utc>,
+) -> ThroughputResponse {
+    let timelines = build_timelines(transitions);
+    let mut days = Vec::with_capacity(window_days as usize);
+
+    for day in window_dates(now, window_days) {
+        let day_start = start_of_day(day);
+        let day_end = day_end(day, now);
+
+        let mut created = 0;
+        let mut completed = 0;
+        let mut open = 0;
+        for timeline in timelines.values() {
+            if timeline
+                .created_at
+                .is_some_and(|created_at| created_at >= day_start && created_at < day_end)
+            {
+                created += 1;
+            }
+
+            // Count arrivals in done, so a task reopened and finished again counts twice.
+            let mut previous: Option<&str> = None;
+            for (changed_at, status) in &timeline.changes {
+                if status == "done"
+                    && previous != Some("done")
+                    && *changed_at >= day_start
+                    && *changed_at < day_end
+                {
+                    completed += 1;
+                }
+                previous = Some(status);
+            }
+
+            if timeline
+                .status_at(day_end)
+                .is_some_and(|status| status != "done")
+            {
+                open += 1;
+            }
+        }
+
+        days.push(ThroughputPoint {
+            date: day.format("%Y-%m-%d").to_string(),
+            created,
+            completed,
+            open,
+        });
+    }
+
+    ThroughputResponse {
+        project,
+        window_days,
+        generated_at: now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        total_created: days.iter().map(|point| point.created).sum(),
+        total_completed: days.iter().map(|point| point.completed).sum(),
+        days,
+    }
+}
+
+fn compute_flow_metrics(
+    project: String,
+    transitions: Vec<TaskTransitionRecord>,
+    window_days: i64,
+    now: DateTime<Utc>,
+) -> FlowMetricsResponse {
+    let timelines = build_timelines(transitions);
+    let window_start = window_dates(now, window_days)
+        .next()
+        .map(start_of_day)
+        .unwrap_or(now);
+
+    let mut lead_hours = Vec::new();
+    let mut cycle_hours = Vec::new();
+    for timeline in timelines.values() {
+        let Some(done_at) = timeline.first_entered("done") else {
+            continue;
+        };
+        if done_at < window_start {
+            continue;
+        }
+
+        if let Some(created_at) = timeline.created_at {
+            lead_hours.push(hours_between(created_at, done_at));
+        }
+
+        if let Some(started_at) = timeline.first_entered("in_progress") {
+            if started_at <= done_at {
+                cycle_hours.push(hours_between(started_at, done_at));
+            }
+        }
+    }
+
+    let mut cumulative_flow = Vec::with_capacity(window_days as usize);
+    for day in window_dates(now, window_days) {
+        let end_of_day = day_end(day, now);
+
+        let mut counts = [0_i64; STATUSES.len()];
+        for timeline in timelines.values() {
+            if let Some(index) = timeline
+                .status_at(end_of_day)
+                .and_then(|status| STATUSES.iter().position(|candidate| *candidate == status))
+            {
+                counts[index] += 1;
+            }
+        }
+
+        cumulative_flow.push(CumulativeFlowPoint {
+            date: day.format("%Y-%m-%d").to_string(),
+            backlog: counts[0],
+            ready: counts[1],
+            in_progress: counts[2],
+            review: counts[3],
+            done: counts[4],
+        });
+    }
+
+    FlowMetricsResponse {
+        project,
+        window_days,
+        generated_at: now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        lead_time: duration_stats(lead_hours),
+        cycle_time: duration_stats(cycle_hours),
+        cumulative_flow,
+    }
+}
+
+fn build_timelines(transitions: Vec<TaskTransitionRecord>) -> BTreeMap<String, TaskTimeline> {
+    let mut timelines: BTreeMap<String, TaskTimeline> = BTreeMap::new();
+
+    for transition in transitions {
+        let Some(changed_at) = parse_timestamp(&transition.created_at) else {
+            continue;
+        };
+        let detail = serde_json::from_str::<Value>(&transition.detail).unwrap_or(Value::Null);
+        let Some(status) = status_after(&transition.action, &detail) else {
+            continue;
+        };
+
+        let timeline = timelines.entry(transition.task_id).or_default();
+        if timeline.created_at.is_none() {
+            timeline.created_at = parse_timestamp(&transition.task_created_at);
+        }
+
+        // Creation shares a second with fast follow-up moves, so keep it first regardless.
+        if transition.action == EventKind::TaskCreated.as_str() {
+            timeline.changes.insert(0, (changed_at, status));
+        } else {
+            timeline.changes.push((changed_at, status));
+        }
+    }
+
+    timelines
+}
+
+/// The status a task ends up in after a history entry, if the entry records one.
+fn status_after(action: &str, detail: &Value) -> Option<String> {
+    let status = match action.parse::<EventKind>().ok()? {
+        EventKind::TaskCreated | EventKind::TaskUpdated => detail.get("status"),
+        EventKind::TaskMoved => detail.get("to_status"),
+        EventKind::TaskUndone => detail.get("restored").and_then(|value| value.get("status")),
+        _ => None,
+    }?;
+
+    status.as_str().map(ToOwned::to_owned)
+}
+
+fn duration_stats(mut hours: Vec<f64>) -> DurationStats {
+    hours.sort_by(f64::total_cmp);
+    DurationStats {
+        count: hours.len(),
+        p50_hours: percentile(&hours, 50.0),
+        p85_hours: percentile(&hours, 85.0),
+        p95_hours: percentile(&hours, 95.0),
+    }
+}
+
+/// Nearest-rank percentile over already sorted values.
+fn percentile(sorted: &[f64], percentile: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = ((percentile / 100.0) * sorted.len() as f64).ceil() as usize;
+    let value = sorted[rank.clamp(1, sorted.len()) - 1];
+    Some((value * 100.0).round() / 100.0)
+}
+
+fn hours_between(start: DateTime<Utc>, end: DateTime<Utc>) -> f64 {
+    (end - start).num_seconds().max(0) as f64 / 3600.0
+}
+
+/// UTC calendar days in the window, oldest first, ending today.
+fn window_dates(now: DateTime<Utc>, window_days: i64) -> impl Iterator<Item = NaiveDate> {
+    let today = now.date_naive();
+    let first_day = today
+        .checked_sub_days(Days::new((window_days - 1) as u64))
+        .unwrap_or(today);
+    first_day.iter_days().take_while(move |day| *day <= today)
+}
+
+/// End of a day's bucket; today's bucket ends now.
+fn day_end(day: NaiveDate, now: DateTime<Utc>) -> DateTime<Utc> {
+    day.checked_add_days(Days::new(1))
+        .map(start_of_day)
+        .unwrap_or(now)
+        .min(now)
+}
+
+fn start_of_day(day: NaiveDate) -> DateTime<Utc> {
+    day.and_hms_opt(0, 0, 0)
+        .map(|value| value.and_utc())
+        .unwrap_or_default()
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|value| value.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::{compute_flow_metrics, compute_throughput, parse_window, percentile};
+    use crate::db::models::TaskTransitionRecord;
+
+    fn transition(task_id: &str, action: &str, detail: &str, at: &str) -> TaskTransitionRecord {
+        TaskTransitionRecord {
+            task_id: task_id.to_string(),
+            task_created_at: "2026-03-01T09:00:00Z".to_string(),
+            action: action.to_string(),
+            detail: detail.to_string(),
+            created_at: at.to_string(),
+        }
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let values = [1.0, 2.0, 3.0, 4.0, 10.0];
+        assert_eq!(percentile(&values, 50.0), Some(3.0));
+        assert_eq!(percentile(&values, 95.0), Some(10.0));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn flow_metrics_derive_lead_cycle_and_daily_counts() {
+        let transitions = vec![
+            transition(
+                "a",
+                "task.created",
+                r#"{"status":"backlog"}"#,
+                "2026-03-01T09:00:00Z",
+            ),
+            transition(
+                "a",
+                "task.moved",
+                r#"{"from_status":"backlog","to_status":"in_progress"}"#,
+                "2026-03-02T09:00:00Z",
+            ),
+            transition(
+                "a",
+                "task.moved",
+                r#"{"from_status":"in_progress","to_status":"done"}"#,
+                "2026-03-02T21:00:00Z",
+            ),
+            transition(
+                "b",
+                "task.created",
+                r#"{"status":"ready"}"#,
+                "2026-03-01T09:00:00Z",
+            ),
+        ];
+        let now = DateTime::parse_from_rfc3339("2026-03-03T12:00:00Z")
+            .expect("timestamp should parse")
+            .with_timezone(&Utc);
+
+        let metrics = compute_flow_metrics("FLOW".to_string(), transitions, 3, now);
+
+        assert_eq!(metrics.lead_time.count, 1);
+        assert_eq!(metrics.lead_time.p50_hours, Some(36.0));
+        assert_eq!(metrics.cycle_time.p50_hours, Some(12.0));
+
+        let days = metrics
+            .cumulative_flow
+            .iter()
+            .map(|point| {
+                (
+                    point.date.as_str(),
+                    point.backlog,
+                    point.ready,
+                    point.in_progress,
+                    point.done,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            days,
+            vec![
+                ("2026-03-01", 1, 1, 0, 0),
+                ("2026-03-02", 0, 1, 0, 1),
+                ("2026-03-03", 0, 1, 0, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn throughput_counts_created_completed_and_open_per_day() {
+        let transitions = vec![
+            transition(
+                "a",
+                "task.created",
+                r#"{"status":"backlog"}"#,
+                "2026-03-01T09:00:00Z",
+            ),
+            transition(
+                "a",
+                "task.moved",
+                r#"{"from_status":"backlog","to_status":"done"}"#,
+                "2026-03-02T10:00:00Z",
+            ),
+            transition(
+                "b",
+                "task.created",
+                r#"{"status":"ready"}"#,
+                "2026-03-01T09:00:00Z",
+            ),
+        ];
+        let now = DateTime::parse_from_rfc3339("2026-03-02T12:00:00Z")
+            .expect("timestamp should parse")
+            .with_timezone(&Utc);
+
+        let report = compute_throughput("FLOW".to_string(), transitions, 2, now);
+        let days = report
+            .days
+            .iter()
+            .map(|point| {
+                (
+                    point.date.as_str(),
+                    point.created,
+                    point.completed,
+                    point.open,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(days, vec![("2026-03-01", 2, 0, 2), ("2026-03-02", 0, 1, 1)]);
+        assert_eq!(report.total_created, 2);
+        assert_eq!(report.total_completed, 1);
+    }
+
+    #[test]
+    fn throughput_window_accepts_day_suffix() {
+        assert_eq!(parse_window("30d").expect("30d should parse"), 30);
+        assert_eq!(parse_window("7").expect("7 should parse"), 7);
+        assert!(parse_window("0d").is_err());
+        assert!(parse_window("2w").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::api::json::ApiJson;
+use crate::db::models::MilestoneRecord;
+use crate::db::queries;
+use crate::db::queries::{CreateMilestoneInput, UpdateMilestoneInput};
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{slug}/milestones",
+            get(list_milestones).post(create_milestone),
+        )
+        .route(
+            "/projects/{slug}/milestones/{milestone_id}",
+            get(get_milestone)
+                .patch(update_milestone)
+                .delete(delete_milestone),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateMilestoneRequest {
+    name: String,
+    target_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateMilestoneRequest {
+    name: Option<String>,
+    target_date: Option<String>,
+    status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MilestoneResponse {
+    id: String,
+    name: String,
+    target_date: Option<String>,
+    status: String,
+    progress: MilestoneProgress,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MilestoneProgress {
+    total: i64,
+    done: i64,
+    /// Whole percent of attached tasks that are done; 0 for an empty milestone.
+    percent: i64,
+}
+
+async fn list_milestones(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<MilestoneResponse>>> {
+    let milestones = queries::list_milestones(&state.db, &slug).await?;
+    Ok(Json(milestones.into_iter().map(map_milestone).collect()))
+}
+
+async fn create_milestone(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(request): ApiJson<CreateMilestoneRequest>,
+) -> AppResult<(StatusCode, Json<MilestoneResponse>)> {
+    let milestone = queries::create_milestone(
+        &state.db,
+        &slug,
+        CreateMilestoneInput {
+            name: request.name,
+            target_date: request.target_date,
+        },
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(map_milestone(milestone))))
+}
+
+async fn get_milestone(
+    State(state): State<AppState>,
+    Path((slug, milestone_id)): Path<(String, String)>,
+) -> AppResult<Json<MilestoneResponse>> {
+    let milestone = queries::get_milestone(&state.db, &slug, &milestone_id).await?;
+    Ok(Json(map_milestone(milestone)))
+}
+
+async fn update_milestone(
+    State(state): State<AppState>,
+    Path((slug, milestone_id)): Path<(String, String)>,
+    ApiJson(request): ApiJson<UpdateMilestoneRequest>,
+) -> AppResult<Json<MilestoneResponse>> {
+    if request.name.is_none() && request.target_date.is_none() && request.status.is_none() {
+        return Err(AppError::BadRequest(
+            "at least one field must be provided".to_string(),
+        ));
+    }
+
+    let milestone = queries::update_milestone(
+        &state.db,
+        &slug,
+        &milestone_id,
+        UpdateMilestoneInput {
+            name: request.name,
+            target_date: request.target_date,
+            status: request.status,
+        },
+    )
+    .await?;
+    Ok(Json(map_milestone(milestone)))
+}
+
+async fn delete_milestone(
+    State(state): State<AppState>,
+    Path((slug, milestone_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    queries::delete_milestone(&state.db, &slug, &milestone_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn map_milestone(record: MilestoneRecord) -> MilestoneResponse {
+    let percent = if record.total_tasks == 0 {
+        0
+    } else {
+        record.done_tasks * 100 / record.total_tasks
+    };
+    MilestoneResponse {
+        id: record.id,
+        name: record.name,
+        target_date: record.target_date,
+        status: record.status,
+        progress: MilestoneProgress {
+            total: record.total_tasks,
+            done: record.done_tasks,
+            percent,
+        },
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
@@ -1,30 +1,68 @@
+pub mod actors;
+pub mod admin;
 pub mod attachments;
+pub mod audit;
 pub mod auth;
+pub mod dashboard;
 pub mod events;
+pub mod guests;
+pub mod inbound;
+pub mod json;
+pub mod mcp_keys;
+pub mod metrics;
+pub mod milestones;
+pub mod notifications;
 pub mod projects;
 pub mod questions;
 pub mod review;
+pub mod share;
+pub mod sla;
 pub mod spec;
 pub mod tasks;
 pub mod webhooks;
+pub mod workspaces;
 
+use std::fmt::Write as _;
+
+use axum::extract::State;
+use axum::http::header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
 use axum::Json;
 use axum::Router;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::AnyPool;
 
+use crate::db::queries;
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
     Router::new()
+        .merge(actors::router())
+        .merge(admin::router())
         .merge(attachments::router())
+        .merge(audit::router())
+        .merge(dashboard::router())
         .merge(projects::router())
         .merge(spec::router())
         .merge(tasks::router())
         .merge(questions::router())
         .merge(review::router())
+        .merge(sla::router())
+        .merge(milestones::router())
         .merge(events::router())
+        .merge(metrics::router())
+        .merge(notifications::router())
         .merge(webhooks::router())
+        .merge(inbound::router())
+        .merge(share::router())
+        .merge(guests::router())
+        .merge(mcp_keys::router())
+        .merge(workspaces::router())
+        .route("/version", get(version))
 }
 
 #[derive(Debug, Serialize)]
@@ -36,6 +74,58 @@ pub async fn healthz() -> Json<HealthzResponse> {
     Json(HealthzResponse { status: "ok" })
 }
 
+#[derive(Debug, Serialize)]
+pub struct ReadyzResponse {
+    pub status: &'static str,
+    pub database: bool,
+    pub webhook_dispatcher: crate::webhooks::health::DispatcherReport,
+}
+
+/// Unlike `/healthz`, fails with `503` while the database is unreachable or the webhook
+/// dispatcher is down or stalled.
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyzResponse>) {
+    let latest = queries::latest_system_event_cursor(&state.db, None, &[]).await;
+    let database = latest.is_ok();
+    let latest_event_at = latest.ok().flatten().map(|(created_at, _)| created_at);
+    let webhook_dispatcher = state.dispatcher.report(latest_event_at.as_deref());
+
+    let ready = database && webhook_dispatcher.healthy;
+    let (code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+    (
+        code,
+        Json(ReadyzResponse {
+            status,
+            database,
+            webhook_dispatcher,
+        }),
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionResponse {
+    pub version: &'static str,
+    /// The address the server listens on, e.g. the port picked when `LATTICE_PORT=0`.
+    pub bound_addr: Option<String>,
+}
+
+pub async fn version(State(state): State<AppState>) -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION"),
+        bound_addr: state.bound_addr().map(|addr| addr.to_string()),
+    })
+}
+
+/// `?dry_run=true` on create endpoints: validate and report instead of writing.
+#[derive(Debug, Deserialize)]
+pub struct DryRunQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ListQuery {
     pub limit: Option<i64>,
@@ -68,3 +158,403 @@ impl ListQuery {
         Ok((limit, offset))
     }
 }
+
+/// A page of list results; the body stays a bare JSON array and the totals go in headers.
+///
+/// Sets `X-Total-Count`, `X-Limit`, and `X-Offset` so pagers can be rendered without changing
+/// the response shape existing clients parse.
+#[derive(Debug)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl<T: Serialize> IntoResponse for Paginated<T> {
+    fn into_response(self) -> Response {
+        let mut response = Json(self.items).into_response();
+        let headers = response.headers_mut();
+        for (name, value) in [
+            ("x-total-count", self.total),
+            ("x-limit", self.limit),
+            ("x-offset", self.offset),
+        ] {
+            headers.insert(name, HeaderValue::from(value));
+        }
+        response
+    }
+}
+
+/// A weak ETag for reads derived from one project, built from its content version and the
+/// request's path and query, so each view of the project gets its own tag.
+///
+/// Handlers check it before loading anything else, so a poll of an unchanged project costs a
+/// single lookup and an empty `304 Not Modified`.
+#[derive(Debug, Clone)]
+pub struct ProjectETag(HeaderValue);
+
+impl ProjectETag {
+    pub async fn load(db: &AnyPool, slug: &str, uri: &Uri) -> AppResult<Self> {
+        let version = queries::project_content_version(db, slug).await?;
+        // Parameter order does not change the response, so it does not change the tag either.
+        let mut params = uri
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .collect::<Vec<_>>();
+        params.sort_unstable();
+        let digest = Sha256::new()
+            .chain_update(version.as_bytes())
+            .chain_update(b"\n")
+            .chain_update(uri.path().as_bytes())
+            .chain_update(b"?")
+            .chain_update(params.join("&").as_bytes())
+            .finalize();
+
+        let mut tag = String::from("W/\"");
+        for byte in &digest[..12] {
+            let _ = write!(&mut tag, "{byte:02x}");
+        }
+        tag.push('"');
+        let value = HeaderValue::from_str(&tag).map_err(|_| AppError::Internal)?;
+        Ok(Self(value))
+    }
+
+    /// Whether `If-None-Match` names this tag, compared weakly as RFC 9110 asks for `GET`.
+    pub fn matches(&self, headers: &HeaderMap) -> bool {
+        let Ok(tag) = self.0.to_str() else {
+            return false;
+        };
+        headers
+            .get_all(IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|candidate| candidate.trim() == "*" || opaque_tag(candidate) == opaque_tag(tag))
+    }
+
+    pub fn not_modified(&self) -> Response {
+        self.tag(StatusCode::NOT_MODIFIED)
+    }
+
+    /// `response` carrying this tag, marked for revalidation on every use.
+    pub fn tag(&self, response: impl IntoResponse) -> Response {
+        let mut response = response.into_response();
+        let headers = response.headers_mut();
+        headers.insert(ETAG, self.0.clone());
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+        response
+    }
+}
+
+fn opaque_tag(tag: &str) -> &str {
+    tag.trim().trim_start_matches("W/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use std::path::Path;
+
+    use axum::Router;
+    use reqwest::header::{ETAG, IF_NONE_MATCH};
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use sqlx::AnyPool;
+    use tempfile::tempdir;
+    use tokio::task::JoinHandle;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn polled_reads_answer_not_modified_until_the_project_changes() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("etag_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Demo", "goal", "DEMO")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+        let get = |path: &str, etag: Option<&str>| {
+            let mut request = client.get(format!("{api}{path}"));
+            if let Some(etag) = etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            request.send()
+        };
+
+        let first = get("/projects/DEMO/tasks", None)
+            .await
+            .expect("list should complete");
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[ETAG]
+            .to_str()
+            .expect("etag should be text")
+            .to_string();
+        assert!(etag.starts_with("W/\""));
+
+        let unchanged = get("/projects/DEMO/tasks", Some(&etag))
+            .await
+            .expect("conditional list should complete");
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(unchanged.headers()[ETAG], etag.as_str());
+
+        // Other views of the same project carry their own tags.
+        for path in [
+            "/projects/DEMO/tasks?status=ready",
+            "/projects/DEMO/spec/overview",
+        ] {
+            let other = get(path, Some(&etag))
+                .await
+                .expect("conditional read should complete");
+            assert_eq!(other.status(), StatusCode::OK, "{path}");
+            assert_ne!(other.headers()[ETAG], etag.as_str(), "{path}");
+        }
+        let filtered = get("/projects/DEMO/tasks?status=ready&limit=5", None)
+            .await
+            .expect("filtered list should complete");
+        let filtered_etag = filtered.headers()[ETAG]
+            .to_str()
+            .expect("etag should be text")
+            .to_string();
+        let reordered = get(
+            "/projects/DEMO/tasks?limit=5&status=ready",
+            Some(&filtered_etag),
+        )
+        .await
+        .expect("conditional list should complete");
+        assert_eq!(
+            reordered.status(),
+            StatusCode::NOT_MODIFIED,
+            "parameter order does not change the tag"
+        );
+
+        let created = client
+            .post(format!("{api}/projects/DEMO/tasks"))
+            .json(&json!({ "title": "Ship it" }))
+            .send()
+            .await
+            .expect("create should complete");
+        assert_eq!(created.status(), StatusCode::CREATED);
+
+        let changed = get("/projects/DEMO/tasks", Some(&etag))
+            .await
+            .expect("conditional list should complete");
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(changed.headers()[ETAG], etag.as_str());
+
+        let stale = get("/projects/DEMO/tasks?stale=true", Some("*"))
+            .await
+            .expect("stale list should complete");
+        assert_eq!(stale.status(), StatusCode::OK);
+        assert!(stale.headers().get(ETAG).is_none());
+        for path in [
+            "/projects/DEMO/tasks?overdue=true",
+            "/projects/DEMO/tasks?overdue=false",
+        ] {
+            let overdue = get(path, Some("*"))
+                .await
+                .expect("overdue list should complete");
+            assert_eq!(overdue.status(), StatusCode::OK, "{path}");
+            assert!(overdue.headers().get(ETAG).is_none(), "{path}");
+        }
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn project_summaries_answer_not_modified_until_the_project_changes() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (_pool, api, server) = spawn_api(temp_dir.path()).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let get = |path: &str, etag: &str| {
+            client
+                .get(format!("{api}{path}"))
+                .header(IF_NONE_MATCH, etag)
+                .send()
+        };
+
+        let first = client
+            .get(format!("{api}/projects/DEMO"))
+            .send()
+            .await
+            .expect("summary read should complete");
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[ETAG]
+            .to_str()
+            .expect("etag should be text")
+            .to_string();
+
+        let unchanged = get("/projects/DEMO", &etag)
+            .await
+            .expect("conditional summary read should complete");
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(unchanged.headers()[ETAG], etag.as_str());
+        let other_query = get("/projects/DEMO?view=compact", &etag)
+            .await
+            .expect("conditional summary read should complete");
+        assert_eq!(other_query.status(), StatusCode::OK);
+        assert_ne!(other_query.headers()[ETAG], etag.as_str());
+
+        let updated = client
+            .patch(format!("{api}/projects/DEMO"))
+            .json(&json!({ "goal": "Ship sooner" }))
+            .send()
+            .await
+            .expect("update should complete");
+        assert_eq!(updated.status(), StatusCode::OK);
+        let changed = get("/projects/DEMO", &etag)
+            .await
+            .expect("conditional summary read should complete");
+        assert_eq!(changed.status(), StatusCode::OK);
+        assert_ne!(changed.headers()[ETAG], etag.as_str());
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn list_headers_count_every_match_regardless_of_the_page() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (pool, api, server) = spawn_api(temp_dir.path()).await;
+        for title in ["first", "second", "third"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "DEMO",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        for (query, offset) in [("limit=1", "0"), ("limit=1&offset=2", "2")] {
+            let page = client
+                .get(format!("{api}/projects/DEMO/tasks?{query}"))
+                .send()
+                .await
+                .expect("list should complete");
+            assert_eq!(page.status(), StatusCode::OK, "{query}");
+            assert_eq!(page.headers()["x-total-count"], "3", "{query}");
+            assert_eq!(page.headers()["x-limit"], "1", "{query}");
+            assert_eq!(page.headers()["x-offset"], offset, "{query}");
+            let tasks: serde_json::Value = page.json().await.expect("page should parse");
+            assert_eq!(tasks.as_array().map(Vec::len), Some(1), "{query}");
+        }
+
+        server.abort();
+    }
+
+    /// Serves the API over a fresh database with an empty project `DEMO`.
+    async fn spawn_api(temp_dir: &Path) -> (AnyPool, String, JoinHandle<()>) {
+        let db_path = temp_dir.join("api_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Demo", "goal", "DEMO")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        (pool, format!("http://{addr}/api/v1"), server)
+    }
+}
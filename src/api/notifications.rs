@@ -0,0 +1,288 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+
+use crate::api::auth::AccessScope;
+use crate::api::{ListQuery, Paginated};
+use crate::db::models::NotificationRecord;
+use crate::db::queries;
+use crate::error::AppResult;
+use crate::state::AppState;
+
+const SSE_POLL_LIMIT: i64 = 100;
+const SSE_POLL_INTERVAL_MS: u64 = 750;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/notifications", get(list_notifications))
+        .route("/notifications/unread-count", get(unread_count))
+        .route("/notifications/read-all", post(mark_all_read))
+        .route("/notifications/stream", get(stream_notifications))
+        .route("/notifications/{notification_id}/read", post(mark_read))
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/watch",
+            put(watch_task).delete(unwatch_task),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/watchers",
+            get(list_watchers),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationListQuery {
+    #[serde(default)]
+    unread: bool,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct NotificationResponse {
+    id: String,
+    project: String,
+    task_id: Option<String>,
+    task_number: Option<i64>,
+    task_display_key: Option<String>,
+    action: String,
+    actor: String,
+    reason: String,
+    detail: Value,
+    read: bool,
+    read_at: Option<String>,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct UnreadCountResponse {
+    unread: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct MarkAllReadResponse {
+    marked: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct WatchersResponse {
+    watchers: Vec<String>,
+}
+
+async fn list_notifications(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+    Query(query): Query<NotificationListQuery>,
+) -> AppResult<Paginated<NotificationResponse>> {
+    let (limit, offset) = ListQuery {
+        limit: query.limit,
+        offset: query.offset,
+    }
+    .normalize()?;
+    let recipient = actor_from_headers(&headers);
+    let workspace_id = scope.workspace_id();
+
+    let records = queries::list_notifications(
+        &state.db,
+        &recipient,
+        workspace_id,
+        query.unread,
+        limit,
+        offset,
+    )
+    .await?;
+    let total =
+        queries::count_notifications(&state.db, &recipient, workspace_id, query.unread).await?;
+
+    Ok(Paginated {
+        items: records.into_iter().map(map_notification).collect(),
+        total,
+        limit,
+        offset,
+    })
+}
+
+async fn unread_count(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+) -> AppResult<Json<UnreadCountResponse>> {
+    let recipient = actor_from_headers(&headers);
+    let unread =
+        queries::count_notifications(&state.db, &recipient, scope.workspace_id(), true).await?;
+    Ok(Json(UnreadCountResponse { unread }))
+}
+
+async fn mark_read(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+    Path(notification_id): Path<String>,
+) -> AppResult<Json<NotificationResponse>> {
+    let recipient = actor_from_headers(&headers);
+    let record = queries::mark_notification_read(
+        &state.db,
+        &recipient,
+        scope.workspace_id(),
+        &notification_id,
+    )
+    .await?;
+    Ok(Json(map_notification(record)))
+}
+
+async fn mark_all_read(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+) -> AppResult<Json<MarkAllReadResponse>> {
+    let recipient = actor_from_headers(&headers);
+    let marked =
+        queries::mark_all_notifications_read(&state.db, &recipient, scope.workspace_id()).await?;
+    Ok(Json(MarkAllReadResponse { marked }))
+}
+
+async fn watch_task(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<WatchersResponse>> {
+    let watchers =
+        queries::watch_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    Ok(Json(WatchersResponse { watchers }))
+}
+
+async fn unwatch_task(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<WatchersResponse>> {
+    let watchers =
+        queries::unwatch_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    Ok(Json(WatchersResponse { watchers }))
+}
+
+async fn list_watchers(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<WatchersResponse>> {
+    let watchers = queries::list_task_watchers(&state.db, &slug, &task_ref).await?;
+    Ok(Json(WatchersResponse { watchers }))
+}
+
+/// Streams new notifications for the calling actor as `notification.created` events.
+async fn stream_notifications(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+) -> AppResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    let recipient = actor_from_headers(&headers);
+    let workspace_id = scope.workspace_id().map(ToOwned::to_owned);
+    let (sender, receiver) = mpsc::channel::<Result<Event, Infallible>>(64);
+    let db = state.db.clone();
+
+    tokio::spawn(async move {
+        let mut cursor =
+            match queries::latest_notification_cursor(&db, &recipient, workspace_id.as_deref())
+                .await
+            {
+                Ok(value) => value,
+                Err(error) => {
+                    tracing::error!(error = ?error, "failed to initialize notification cursor");
+                    None
+                }
+            };
+        let mut interval = tokio::time::interval(Duration::from_millis(SSE_POLL_INTERVAL_MS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            let notifications = match queries::list_new_notifications(
+                &db,
+                &recipient,
+                workspace_id.as_deref(),
+                cursor
+                    .as_ref()
+                    .map(|(created_at, id)| (created_at.as_str(), id.as_str())),
+                SSE_POLL_LIMIT,
+            )
+            .await
+            {
+                Ok(value) => value,
+                Err(error) => {
+                    tracing::error!(error = ?error, "failed to query notifications for sse");
+                    break;
+                }
+            };
+
+            for notification in notifications {
+                cursor = Some((notification.created_at.clone(), notification.id.clone()));
+
+                let payload = map_notification(notification);
+                let serialized = match serde_json::to_string(&payload) {
+                    Ok(value) => value,
+                    Err(error) => {
+                        tracing::error!(error = ?error, "failed to serialize notification");
+                        continue;
+                    }
+                };
+
+                let event = Event::default()
+                    .id(payload.id)
+                    .event("notification.created")
+                    .data(serialized);
+
+                if sender.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(ReceiverStream::new(receiver)).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    ))
+}
+
+fn map_notification(record: NotificationRecord) -> NotificationResponse {
+    let task_display_key = record
+        .task_number
+        .map(|task_number| queries::display_key(&record.project_slug, task_number));
+    NotificationResponse {
+        id: record.id,
+        project: record.project_slug,
+        task_id: record.task_id,
+        task_number: record.task_number,
+        task_display_key,
+        action: record.action,
+        actor: record.actor,
+        reason: record.reason,
+        detail: serde_json::from_str::<Value>(&record.detail)
+            .unwrap_or_else(|_| Value::String(record.detail.clone())),
+        read: record.read_at.is_some(),
+        read_at: record.read_at,
+        created_at: record.created_at,
+    }
+}
+
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
@@ -1,25 +1,77 @@
-use axum::extract::{Path, Query, State};
-use axum::http::HeaderMap;
+use std::collections::BTreeMap;
+
+use axum::body::Body;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::header::LOCATION;
 use axum::http::StatusCode;
-use axum::routing::get;
+use axum::http::{HeaderMap, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::api::ListQuery;
-use crate::db::models::ProjectSummary;
+use crate::api::auth::AccessScope;
+use crate::api::json::ApiJson;
+use crate::api::workspaces::WorkspaceSelector;
+use crate::api::{DryRunQuery, ListQuery, Paginated, ProjectETag};
+use crate::client_ip::ClientIp;
+use crate::db::models::{
+    BulkProjectResult, DigestScheduleRecord, GoalRevision, ProjectActivityRecord, ProjectDigest,
+    ProjectFacets, ProjectSettings, ProjectSummary,
+};
 use crate::db::queries;
+use crate::db::queries::{DigestScheduleInput, TaskHistoryFilters, UpdateProjectSettingsInput};
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/projects", get(list_projects).post(create_project))
+        .route("/projects/bulk", post(create_projects_bulk))
         .route(
             "/projects/{slug}",
             get(get_project)
                 .patch(update_project)
                 .delete(delete_project),
         )
+        .route("/projects/{slug}/rename", post(rename_project))
+        .route(
+            "/projects/{slug}/star",
+            put(star_project).delete(unstar_project),
+        )
+        .route("/projects/{slug}/facets", get(get_project_facets))
+        .route("/projects/{slug}/labels/rename", post(rename_label))
+        .route("/projects/{slug}/board/snapshot", get(get_board_snapshot))
+        .route("/projects/{slug}/goal/history", get(get_goal_history))
+        .route("/projects/{slug}/activity", get(get_project_activity))
+        .route(
+            "/projects/{slug}/settings",
+            get(get_project_settings).patch(update_project_settings),
+        )
+        .route(
+            "/projects/{slug}/digest",
+            get(get_digest_schedule)
+                .put(set_digest_schedule)
+                .delete(delete_digest_schedule),
+        )
+        .route("/projects/{slug}/digest/preview", get(preview_digest))
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectListQuery {
+    /// Only projects the calling actor has starred.
+    #[serde(default)]
+    starred: bool,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct StarResponse {
+    project: String,
+    starred: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,44 +82,271 @@ pub struct CreateProjectRequest {
     pub goal: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct BulkCreateProjectsRequest {
+    projects: Vec<BulkProjectRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkProjectRequest {
+    name: String,
+    slug: String,
+    #[serde(default)]
+    goal: String,
+    /// Initial content by spec section, e.g. `{"overview": "..."}`.
+    #[serde(default)]
+    spec: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BulkCreateProjectsResponse {
+    created: usize,
+    failed: usize,
+    results: Vec<BulkProjectResult>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateProjectRequest {
     pub name: Option<String>,
     pub goal: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RenameProjectRequest {
+    pub slug: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RenameLabelRequest {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RenameLabelResponse {
+    from: String,
+    to: String,
+    affected: i64,
+}
+
+const PROJECTS_PREFIX: &str = "/api/v1/projects/";
+
+/// Answers requests addressed to a project's former slug, or to a task under the display key
+/// it had before it was transferred, with a `308` to the same URL at its current address, so
+/// bookmarks and scripts survive renames and transfers.
+pub async fn redirect_former_addresses(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> AppResult<Response> {
+    let Some(rest) = request.uri().path().strip_prefix(PROJECTS_PREFIX) else {
+        return Ok(next.run(request).await);
+    };
+    let (slug, remainder) = rest.split_once('/').unwrap_or((rest, ""));
+    if slug.is_empty() {
+        return Ok(next.run(request).await);
+    }
+    if let Some(current) = queries::renamed_project_slug(&state.db, slug).await? {
+        return Ok(redirect_to(&request, &current, remainder));
+    }
+
+    let Some(task_path) = remainder.strip_prefix("tasks/") else {
+        return Ok(next.run(request).await);
+    };
+    let (task_ref, task_rest) = task_path.split_once('/').unwrap_or((task_path, ""));
+    let Some((current, task_number)) =
+        queries::transferred_task_key(&state.db, slug, task_ref).await?
+    else {
+        return Ok(next.run(request).await);
+    };
+    let mut remainder = match queries::parse_task_ref(task_ref) {
+        Ok(queries::TaskRef::Uuid(_)) => format!("tasks/{task_ref}"),
+        _ => format!("tasks/{}", queries::display_key(&current, task_number)),
+    };
+    if !task_rest.is_empty() {
+        remainder.push('/');
+        remainder.push_str(task_rest);
+    }
+    Ok(redirect_to(&request, &current, &remainder))
+}
+
+fn redirect_to(request: &Request<Body>, slug: &str, remainder: &str) -> Response {
+    let mut location = match request.extensions().get::<WorkspaceSelector>() {
+        Some(WorkspaceSelector(workspace)) => format!("/api/v1/w/{workspace}/projects/{slug}"),
+        None => format!("{PROJECTS_PREFIX}{slug}"),
+    };
+    if !remainder.is_empty() {
+        location.push('/');
+        location.push_str(remainder);
+    }
+    if let Some(query) = request.uri().query() {
+        location.push('?');
+        location.push_str(query);
+    }
+
+    (StatusCode::PERMANENT_REDIRECT, [(LOCATION, location)]).into_response()
+}
+
 async fn list_projects(
     State(state): State<AppState>,
-    Query(query): Query<ListQuery>,
-) -> AppResult<Json<Vec<ProjectSummary>>> {
-    let (limit, offset) = query.normalize()?;
-    let projects = queries::list_projects(&state.db, limit, offset).await?;
-    Ok(Json(projects))
+    scope: AccessScope,
+    headers: HeaderMap,
+    Query(query): Query<ProjectListQuery>,
+) -> AppResult<Paginated<ProjectSummary>> {
+    let (limit, offset) = ListQuery {
+        limit: query.limit,
+        offset: query.offset,
+    }
+    .normalize()?;
+    let actor = actor_from_headers(&headers);
+    let starred_by = query.starred.then_some(actor.as_str());
+    let total = queries::count_projects(&state.db, scope.workspace_id(), starred_by).await?;
+    let items =
+        queries::list_projects(&state.db, scope.workspace_id(), starred_by, limit, offset).await?;
+    Ok(Paginated {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
+
+async fn star_project(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> AppResult<Json<StarResponse>> {
+    queries::star_project(&state.db, &slug, &actor_from_headers(&headers)).await?;
+    Ok(Json(StarResponse {
+        project: slug,
+        starred: true,
+    }))
+}
+
+async fn unstar_project(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(slug): Path<String>,
+) -> AppResult<Json<StarResponse>> {
+    queries::unstar_project(&state.db, &slug, &actor_from_headers(&headers)).await?;
+    Ok(Json(StarResponse {
+        project: slug,
+        starred: false,
+    }))
 }
 
 async fn create_project(
     State(state): State<AppState>,
-    Json(payload): Json<CreateProjectRequest>,
-) -> AppResult<(StatusCode, Json<ProjectSummary>)> {
-    let project =
-        queries::create_project_with_slug(&state.db, &payload.name, &payload.goal, &payload.slug)
-            .await?;
-    Ok((StatusCode::CREATED, Json(project)))
+    scope: AccessScope,
+    Query(query): Query<DryRunQuery>,
+    ApiJson(payload): ApiJson<CreateProjectRequest>,
+) -> AppResult<Response> {
+    if query.dry_run {
+        let report = queries::dry_run_create_project(
+            &state.db,
+            scope
+                .workspace_id()
+                .unwrap_or(queries::DEFAULT_WORKSPACE_ID),
+            &payload.name,
+            &payload.goal,
+            &payload.slug,
+        )
+        .await?;
+        return Ok(Json(report).into_response());
+    }
+
+    let project = match scope.workspace_id() {
+        Some(workspace_id) => {
+            queries::create_project_in_workspace(
+                &state.db,
+                workspace_id,
+                &payload.name,
+                &payload.goal,
+                &payload.slug,
+            )
+            .await?
+        }
+        None => {
+            queries::create_project_with_slug(
+                &state.db,
+                &payload.name,
+                &payload.goal,
+                &payload.slug,
+            )
+            .await?
+        }
+    };
+    Ok((StatusCode::CREATED, Json(project)).into_response())
+}
+
+async fn create_projects_bulk(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<BulkCreateProjectsRequest>,
+) -> AppResult<(StatusCode, Json<BulkCreateProjectsResponse>)> {
+    let items = payload
+        .projects
+        .into_iter()
+        .map(|project| queries::BulkProjectItem {
+            name: project.name,
+            goal: project.goal,
+            slug: project.slug,
+            spec: project.spec,
+        })
+        .collect();
+    let results = queries::create_projects_bulk(
+        &state.db,
+        &state.config.field_limits,
+        scope
+            .workspace_id()
+            .unwrap_or(queries::DEFAULT_WORKSPACE_ID),
+        items,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+
+    // The batch is all or nothing, so any failed entry means nothing was created.
+    let created = results.iter().filter(|result| result.created).count();
+    let status = if created == 0 {
+        StatusCode::CONFLICT
+    } else {
+        StatusCode::CREATED
+    };
+    Ok((
+        status,
+        Json(BulkCreateProjectsResponse {
+            created,
+            failed: results
+                .iter()
+                .filter(|result| result.error.is_some())
+                .count(),
+            results,
+        }),
+    ))
 }
 
 async fn get_project(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> AppResult<Json<ProjectSummary>> {
+    uri: Uri,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    // Dashboards poll every project; an unchanged one is answered without counting its tasks.
+    let etag = ProjectETag::load(&state.db, &slug, &uri).await?;
+    if etag.matches(&headers) {
+        return Ok(etag.not_modified());
+    }
+
     let project = queries::get_project(&state.db, &slug).await?;
-    Ok(Json(project))
+    Ok(etag.tag(Json(project)))
 }
 
 async fn update_project(
     State(state): State<AppState>,
     Path(slug): Path<String>,
     headers: HeaderMap,
-    Json(payload): Json<UpdateProjectRequest>,
+    ApiJson(payload): ApiJson<UpdateProjectRequest>,
 ) -> AppResult<Json<ProjectSummary>> {
     if payload.name.is_none() && payload.goal.is_none() {
         return Err(AppError::BadRequest(
@@ -86,11 +365,317 @@ async fn update_project(
     Ok(Json(project))
 }
 
+async fn rename_project(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<RenameProjectRequest>,
+) -> AppResult<Json<ProjectSummary>> {
+    let project = queries::rename_project(
+        &state.db,
+        &slug,
+        &payload.slug,
+        &actor_from_headers(&headers),
+        client_ip.address().as_deref(),
+    )
+    .await?;
+    Ok(Json(project))
+}
+
+async fn rename_label(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<RenameLabelRequest>,
+) -> AppResult<Json<RenameLabelResponse>> {
+    let affected = queries::rename_label(
+        &state.db,
+        &slug,
+        &payload.from,
+        &payload.to,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok(Json(RenameLabelResponse {
+        from: payload.from.trim().to_string(),
+        to: payload.to.trim().to_string(),
+        affected,
+    }))
+}
+
+/// Filter values present on the project's tasks, with counts, for building filter dropdowns.
+async fn get_project_facets(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> AppResult<Json<ProjectFacets>> {
+    let (limit, offset) = query.normalize()?;
+    let facets = queries::project_facets(&state.db, &slug, limit, offset).await?;
+    Ok(Json(facets))
+}
+
+#[derive(Debug, Deserialize)]
+struct BoardSnapshotQuery {
+    date: Option<String>,
+}
+
+/// The whole board as of now, or the daily snapshot stored for `date`.
+async fn get_board_snapshot(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<BoardSnapshotQuery>,
+) -> AppResult<Response> {
+    Ok(match query.date {
+        Some(date) => {
+            Json(queries::get_board_snapshot(&state.db, &slug, &date).await?).into_response()
+        }
+        None => Json(queries::board_snapshot(&state.db, &slug).await?).into_response(),
+    })
+}
+
+async fn get_goal_history(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<ListQuery>,
+) -> AppResult<Paginated<GoalRevision>> {
+    let (limit, offset) = query.normalize()?;
+    let history = queries::list_goal_history(&state.db, &slug).await?;
+    let total = history.len() as i64;
+    let items = history
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+    Ok(Paginated {
+        items,
+        total,
+        limit,
+        offset,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectActivityQuery {
+    action: Option<String>,
+    actor: Option<String>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectActivityResponse {
+    entries: Vec<ProjectActivityEntry>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectActivityEntry {
+    id: String,
+    task_id: String,
+    task_number: i64,
+    task_display_key: String,
+    action: String,
+    actor: String,
+    actor_display_name: Option<String>,
+    actor_kind: Option<String>,
+    actor_avatar_url: Option<String>,
+    detail: Value,
+    created_at: String,
+}
+
+impl ProjectActivityEntry {
+    fn new(project_slug: &str, record: ProjectActivityRecord) -> Self {
+        Self {
+            task_display_key: queries::display_key(project_slug, record.task_number),
+            detail: serde_json::from_str(&record.detail).unwrap_or(Value::String(record.detail)),
+            id: record.id,
+            task_id: record.task_id,
+            task_number: record.task_number,
+            action: record.action,
+            actor: record.actor,
+            actor_display_name: record.actor_display_name,
+            actor_kind: record.actor_kind,
+            actor_avatar_url: record.actor_avatar_url,
+            created_at: record.created_at,
+        }
+    }
+}
+
+async fn get_project_activity(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<ProjectActivityQuery>,
+) -> AppResult<Json<ProjectActivityResponse>> {
+    let (limit, _) = ListQuery {
+        limit: query.limit,
+        offset: None,
+    }
+    .normalize()?;
+    let slug = queries::normalize_slug(&slug)?;
+    let page = queries::list_project_activity(
+        &state.db,
+        &slug,
+        TaskHistoryFilters {
+            action: query.action,
+            actor: query.actor,
+        },
+        limit,
+        query.cursor.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(ProjectActivityResponse {
+        entries: page
+            .entries
+            .into_iter()
+            .map(|record| ProjectActivityEntry::new(&slug, record))
+            .collect(),
+        next_cursor: page.next_cursor,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateProjectSettingsRequest {
+    review_checklist_required: Option<bool>,
+    review_gate: Option<String>,
+    review_gate_scope: Option<String>,
+    auto_archive_after_days: Option<i64>,
+    column_order: Option<Vec<String>>,
+    column_colors: Option<BTreeMap<String, String>>,
+    enforce_dependencies: Option<bool>,
+    timezone: Option<String>,
+}
+
+async fn get_project_settings(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<ProjectSettings>> {
+    let settings = queries::get_project_settings(&state.db, &slug).await?;
+    Ok(Json(settings))
+}
+
+async fn update_project_settings(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(payload): ApiJson<UpdateProjectSettingsRequest>,
+) -> AppResult<Json<ProjectSettings>> {
+    if payload.review_checklist_required.is_none()
+        && payload.review_gate.is_none()
+        && payload.review_gate_scope.is_none()
+        && payload.auto_archive_after_days.is_none()
+        && payload.column_order.is_none()
+        && payload.column_colors.is_none()
+        && payload.enforce_dependencies.is_none()
+        && payload.timezone.is_none()
+    {
+        return Err(AppError::BadRequest(
+            "at least one field must be provided".to_string(),
+        ));
+    }
+
+    let settings = queries::update_project_settings(
+        &state.db,
+        &slug,
+        UpdateProjectSettingsInput {
+            review_checklist_required: payload.review_checklist_required,
+            review_gate: payload.review_gate,
+            review_gate_scope: payload.review_gate_scope,
+            auto_archive_after_days: payload.auto_archive_after_days,
+            column_order: payload.column_order,
+            column_colors: payload.column_colors,
+            enforce_dependencies: payload.enforce_dependencies,
+            timezone: payload.timezone,
+        },
+    )
+    .await?;
+    Ok(Json(settings))
+}
+
+#[derive(Debug, Deserialize)]
+struct DigestScheduleRequest {
+    frequency: String,
+    hour: i64,
+    weekday: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DigestPreviewQuery {
+    days: Option<u32>,
+}
+
+async fn get_digest_schedule(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<DigestScheduleRecord>> {
+    let schedule = queries::get_digest_schedule(&state.db, &slug).await?;
+    Ok(Json(schedule))
+}
+
+async fn set_digest_schedule(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(payload): ApiJson<DigestScheduleRequest>,
+) -> AppResult<Json<DigestScheduleRecord>> {
+    let schedule = queries::set_digest_schedule(
+        &state.db,
+        &slug,
+        DigestScheduleInput {
+            frequency: payload.frequency,
+            hour: payload.hour,
+            weekday: payload.weekday,
+        },
+    )
+    .await?;
+    Ok(Json(schedule))
+}
+
+async fn delete_digest_schedule(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<StatusCode> {
+    queries::delete_digest_schedule(&state.db, &slug).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// What a digest covering the last `days` days (default 1) would contain, without sending it.
+async fn preview_digest(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<DigestPreviewQuery>,
+) -> AppResult<Json<ProjectDigest>> {
+    let days = query.days.unwrap_or(1);
+    if !(1..=31).contains(&days) {
+        return Err(AppError::BadRequest(
+            "days must be between 1 and 31".to_string(),
+        ));
+    }
+
+    let since = queries::timestamp_days_ago(days);
+    let until = queries::timestamp_days_ago(0);
+    let stale_before = state
+        .config
+        .stale_threshold_days()
+        .map(queries::timestamp_days_ago);
+    let digest =
+        queries::build_project_digest(&state.db, &slug, &since, &until, stale_before.as_deref())
+            .await?;
+    Ok(Json(digest))
+}
+
 async fn delete_project(
     State(state): State<AppState>,
     Path(slug): Path<String>,
+    client_ip: ClientIp,
+    headers: HeaderMap,
 ) -> AppResult<StatusCode> {
-    queries::delete_project(&state.db, &slug).await?;
+    queries::delete_project(
+        &state.db,
+        &slug,
+        &actor_from_headers(&headers),
+        client_ip.address().as_deref(),
+    )
+    .await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -103,3 +688,771 @@ fn actor_from_headers(headers: &HeaderMap) -> String {
         .map(ToOwned::to_owned)
         .unwrap_or_else(|| "human".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::Router;
+    use reqwest::redirect::Policy;
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn former_slugs_and_task_keys_redirect() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("projects_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Demo", "goal", "DEMO")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                super::redirect_former_addresses,
+            ))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .redirect(Policy::none())
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let renamed: Value = client
+            .post(format!("{api}/projects/DEMO/rename"))
+            .json(&json!({ "slug": "SHOWCASE" }))
+            .send()
+            .await
+            .expect("rename should succeed")
+            .json()
+            .await
+            .expect("rename body should parse");
+        assert_eq!(renamed["project"]["slug"], "SHOWCASE");
+
+        let redirect = client
+            .get(format!("{api}/projects/DEMO/tasks?status=ready"))
+            .send()
+            .await
+            .expect("legacy request should complete");
+        assert_eq!(redirect.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            redirect.headers()["location"],
+            "/api/v1/projects/SHOWCASE/tasks?status=ready"
+        );
+
+        let current = client
+            .get(format!("{api}/projects/SHOWCASE"))
+            .send()
+            .await
+            .expect("current slug request should complete");
+        assert_eq!(current.status(), StatusCode::OK);
+
+        queries::create_project_with_slug(&pool, "Other", "goal", "OTHER")
+            .await
+            .expect("project should be created");
+        let created: Value = client
+            .post(format!("{api}/projects/SHOWCASE/tasks"))
+            .json(&json!({ "title": "Move me" }))
+            .send()
+            .await
+            .expect("create should succeed")
+            .json()
+            .await
+            .expect("create body should parse");
+        assert_eq!(created["display_key"], "SHOWCASE-1");
+        let moved: Value = client
+            .post(format!("{api}/projects/SHOWCASE/tasks/SHOWCASE-1/transfer"))
+            .json(&json!({ "project": "OTHER" }))
+            .send()
+            .await
+            .expect("transfer should succeed")
+            .json()
+            .await
+            .expect("transfer body should parse");
+        assert_eq!(moved["display_key"], "OTHER-1");
+        assert_eq!(moved["id"], created["id"]);
+
+        let tombstone = client
+            .get(format!("{api}/projects/SHOWCASE/tasks/SHOWCASE-1/history"))
+            .send()
+            .await
+            .expect("tombstone request should complete");
+        assert_eq!(tombstone.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            tombstone.headers()["location"],
+            "/api/v1/projects/OTHER/tasks/OTHER-1/history"
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn project_activity_pages_and_filters_history_across_tasks() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("activity.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Activity", "goal", "ACT")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+        for (title, actor) in [
+            ("first", "human"),
+            ("second", "agent-a"),
+            ("third", "human"),
+        ] {
+            let status = client
+                .post(format!("{api}/projects/ACT/tasks"))
+                .header("MCP-Client", actor)
+                .json(&json!({ "title": title }))
+                .send()
+                .await
+                .expect("task should be created")
+                .status();
+            assert!(status.is_success());
+        }
+        let status = client
+            .post(format!("{api}/projects/ACT/tasks/ACT-1/move"))
+            .json(&json!({ "status": "ready" }))
+            .send()
+            .await
+            .expect("task should move")
+            .status();
+        assert!(status.is_success());
+
+        let activity = |query: &'static str| {
+            let client = client.clone();
+            let api = api.clone();
+            async move {
+                client
+                    .get(format!("{api}/projects/act/activity{query}"))
+                    .send()
+                    .await
+                    .expect("activity should load")
+            }
+        };
+
+        let page: Value = activity("?limit=3").await.json().await.expect("json");
+        assert_eq!(page["entries"].as_array().map(Vec::len), Some(3));
+        let cursor = page["next_cursor"].as_str().expect("another page exists");
+
+        let rest: Value = client
+            .get(format!(
+                "{api}/projects/ACT/activity?limit=3&cursor={cursor}"
+            ))
+            .send()
+            .await
+            .expect("next page should load")
+            .json()
+            .await
+            .expect("json");
+        assert_eq!(rest["entries"].as_array().map(Vec::len), Some(1));
+        assert!(rest["next_cursor"].is_null());
+        let mut ids: Vec<&Value> = page["entries"]
+            .as_array()
+            .into_iter()
+            .chain(rest["entries"].as_array())
+            .flatten()
+            .map(|entry| &entry["id"])
+            .collect();
+        ids.sort_by_key(|id| id.to_string());
+        ids.dedup();
+        assert_eq!(ids.len(), 4, "pages do not overlap");
+
+        let by_agent: Value = activity("?actor=agent-a").await.json().await.expect("json");
+        assert_eq!(by_agent["entries"].as_array().map(Vec::len), Some(1));
+        assert_eq!(by_agent["entries"][0]["task_display_key"], "ACT-2");
+
+        let moves: Value = activity("?action=task.moved")
+            .await
+            .json()
+            .await
+            .expect("json");
+        assert_eq!(moves["entries"].as_array().map(Vec::len), Some(1));
+        assert_eq!(moves["entries"][0]["task_display_key"], "ACT-1");
+        assert!(moves["entries"][0]["detail"].is_object());
+
+        assert_eq!(
+            activity("?cursor=missing").await.status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn starred_projects_are_listed_per_actor() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("stars.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        for slug in ["ONE", "TWO", "THREE"] {
+            queries::create_project_with_slug(&pool, slug, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+        for (slug, actor) in [("ONE", "human"), ("THREE", "human"), ("TWO", "agent-a")] {
+            for _ in 0..2 {
+                let starred: Value = client
+                    .put(format!("{api}/projects/{slug}/star"))
+                    .header("MCP-Client", actor)
+                    .send()
+                    .await
+                    .expect("star should succeed")
+                    .json()
+                    .await
+                    .expect("json");
+                assert_eq!(starred["starred"], true);
+            }
+        }
+
+        let starred = |actor: &'static str| {
+            let client = client.clone();
+            let api = api.clone();
+            async move {
+                let response = client
+                    .get(format!("{api}/projects?starred=true"))
+                    .header("MCP-Client", actor)
+                    .send()
+                    .await
+                    .expect("list should succeed");
+                let total = response.headers()["x-total-count"]
+                    .to_str()
+                    .expect("header")
+                    .to_string();
+                let body: Value = response.json().await.expect("json");
+                let mut slugs: Vec<String> = body
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|project| project["project"]["slug"].as_str())
+                    .map(ToOwned::to_owned)
+                    .collect();
+                slugs.sort();
+                (total, slugs)
+            }
+        };
+        assert_eq!(
+            starred("human").await,
+            (
+                "2".to_string(),
+                vec!["ONE".to_string(), "THREE".to_string()]
+            )
+        );
+        assert_eq!(
+            starred("agent-a").await,
+            ("1".to_string(), vec!["TWO".to_string()])
+        );
+
+        let unstarred = client
+            .delete(format!("{api}/projects/ONE/star"))
+            .send()
+            .await
+            .expect("unstar should succeed");
+        assert_eq!(unstarred.status(), StatusCode::OK);
+        assert_eq!(
+            starred("human").await,
+            ("1".to_string(), vec!["THREE".to_string()])
+        );
+        let all: Value = client
+            .get(format!("{api}/projects"))
+            .send()
+            .await
+            .expect("list should succeed")
+            .json()
+            .await
+            .expect("json");
+        assert_eq!(all.as_array().map(Vec::len), Some(3));
+        assert_eq!(
+            client
+                .put(format!("{api}/projects/MISSING/star"))
+                .send()
+                .await
+                .expect("request should complete")
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn bulk_project_create_is_all_or_nothing() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("bulk_projects.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Taken", "goal", "TAKEN")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let bulk = |projects: Value| {
+            let request = client
+                .post(format!("http://{addr}/api/v1/projects/bulk"))
+                .header("MCP-Client", "platform-bot")
+                .json(&json!({ "projects": projects }));
+            async move {
+                let response = request.send().await.expect("bulk create should complete");
+                let status = response.status();
+                (status, response.json::<Value>().await.expect("json"))
+            }
+        };
+
+        let (status, body) = bulk(json!([
+            { "name": "Api", "slug": "api" },
+            { "name": "Dup", "slug": "API" },
+            { "name": "Old", "slug": "taken" },
+            { "name": "Web", "slug": "web", "spec": { "roadmap": "x" } },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["created"], 0);
+        assert_eq!(body["failed"], 3);
+        let errors: Vec<Option<&str>> = body["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|result| result["error"].as_str())
+            .collect();
+        assert_eq!(errors[0], None);
+        assert!(errors[1].is_some_and(|error| error.contains("more than once")));
+        assert!(errors[2].is_some_and(|error| error.contains("already taken")));
+        assert!(errors[3].is_some_and(|error| error.contains("invalid spec section")));
+        assert_eq!(
+            queries::count_projects(&pool, None, None)
+                .await
+                .expect("projects should count"),
+            1
+        );
+
+        let (status, body) = bulk(json!([
+            { "name": "Api", "slug": "api", "spec": { "overview": "# API\n" } },
+            { "name": "Web", "slug": "web", "goal": "ship" },
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["created"], 2);
+        assert_eq!(body["results"][1]["project"]["project"]["goal"], "ship");
+        let overview =
+            queries::get_spec_section(&pool, &FieldLimitConfig::default(), "API", "overview")
+                .await
+                .expect("spec should load");
+        assert_eq!(overview.content, "# API\n");
+        let history = queries::list_spec_history(&pool, "API", "overview", 10, 0, true)
+            .await
+            .expect("history should load");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].edited_by, "platform-bot");
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn dry_run_creates_report_every_problem_without_writing() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!(
+                "sqlite://{}?mode=rwc",
+                temp_dir.path().join("dry_run.db").display()
+            ),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Taken", "goal", "TAKEN")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+        let dry_run = |path: &str, body: Value| {
+            let request = client.post(format!("{api}{path}?dry_run=true")).json(&body);
+            async move {
+                let response = request.send().await.expect("dry run should complete");
+                assert_eq!(response.status(), StatusCode::OK);
+                response.json::<Value>().await.expect("json")
+            }
+        };
+
+        let project = dry_run(
+            "/projects",
+            json!({ "name": " ", "goal": "", "slug": "TAKEN" }),
+        )
+        .await;
+        assert_eq!(project["valid"], false);
+        assert_eq!(project["preview"], Value::Null);
+        let errors: Vec<&str> = project["errors"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|issue| issue["error"].as_str())
+            .collect();
+        assert_eq!(errors, ["bad_request", "conflict"]);
+
+        let fresh = dry_run(
+            "/projects",
+            json!({ "name": "Fresh", "goal": "ship", "slug": "fresh" }),
+        )
+        .await;
+        assert_eq!(fresh["valid"], true);
+        assert_eq!(fresh["preview"]["slug"], "FRESH");
+        assert_eq!(
+            fresh["warnings"],
+            json!(["slug 'fresh' will be stored as 'FRESH'"])
+        );
+
+        let task = dry_run(
+            "/projects/TAKEN/tasks",
+            json!({ "title": "", "status": "someday", "priority": "urgent" }),
+        )
+        .await;
+        assert_eq!(task["valid"], false);
+        assert_eq!(task["errors"].as_array().map(Vec::len), Some(3));
+
+        let task = dry_run(
+            "/projects/TAKEN/tasks",
+            json!({ "title": "Write docs", "labels": ["docs", " docs ", ""] }),
+        )
+        .await;
+        assert_eq!(task["valid"], true);
+        assert_eq!(task["preview"]["display_key"], "TAKEN-1");
+        assert_eq!(task["preview"]["labels"], json!(["docs"]));
+        assert_eq!(task["warnings"].as_array().map(Vec::len), Some(1));
+
+        let webhook = dry_run(
+            "/projects/TAKEN/webhooks",
+            json!({
+                "name": "ci",
+                "url": "ftp://example.com",
+                "platform": "generic",
+                "events": ["task.nope"],
+            }),
+        )
+        .await;
+        assert_eq!(webhook["valid"], false);
+        assert_eq!(webhook["errors"].as_array().map(Vec::len), Some(2));
+
+        let webhook = dry_run(
+            "/projects/TAKEN/webhooks",
+            json!({
+                "name": "ci",
+                "url": "https://example.com/hook?token=abc",
+                "platform": "generic",
+                "events": ["task.*"],
+            }),
+        )
+        .await;
+        assert_eq!(webhook["valid"], true);
+        assert_eq!(webhook["preview"]["has_secret"], false);
+
+        let projects = queries::count_projects(&pool, None, None)
+            .await
+            .expect("projects should count");
+        assert_eq!(projects, 1);
+        let project = queries::get_project(&pool, "TAKEN")
+            .await
+            .expect("project should load");
+        assert_eq!(project.project.task_counter, 0);
+        let webhooks = queries::list_project_webhooks(&pool, "TAKEN")
+            .await
+            .expect("webhooks should list");
+        assert!(webhooks.is_empty());
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn audit_events_record_the_forwarded_client_behind_a_trusted_proxy() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("client_ip_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig {
+                trusted_proxies: vec!["127.0.0.1/32".parse().expect("valid proxy")],
+                ..RateLimitConfig::default()
+            },
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        for slug in ["GONE", "ALSO"] {
+            queries::create_project_with_slug(&pool, slug, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                crate::rate_limit::enforce_limits,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+        for (slug, forwarded) in [("GONE", "6.6.6.6, 203.0.113.7"), ("ALSO", "")] {
+            let status = client
+                .delete(format!("{api}/projects/{slug}"))
+                .header("x-forwarded-for", forwarded)
+                .send()
+                .await
+                .expect("delete request should succeed")
+                .status();
+            assert_eq!(status, StatusCode::NO_CONTENT);
+        }
+
+        let audit = queries::list_audit_events(&pool, 10, 0)
+            .await
+            .expect("audit events should list");
+        let client_ip = |subject: &str| {
+            audit
+                .iter()
+                .find(|event| event.subject == subject)
+                .and_then(|event| event.client_ip.clone())
+        };
+        assert_eq!(client_ip("GONE").as_deref(), Some("203.0.113.7"));
+        assert_eq!(client_ip("ALSO").as_deref(), Some("127.0.0.1"));
+
+        server.abort();
+    }
+}
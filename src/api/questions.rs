@@ -4,8 +4,9 @@ use axum::routing::{get, patch, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 
-use crate::api::ListQuery;
-use crate::db::models::OpenQuestionRecord;
+use crate::api::json::ApiJson;
+use crate::api::{ListQuery, Paginated};
+use crate::db::models::{BulkAnswerResult, OpenQuestionRecord};
 use crate::db::queries;
 use crate::error::AppResult;
 use crate::state::AppState;
@@ -13,6 +14,7 @@ use crate::state::AppState;
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/projects/{slug}/questions", get(list_open_questions))
+        .route("/projects/{slug}/questions/bulk-answer", post(bulk_answer))
         .route(
             "/projects/{slug}/tasks/{task_ref}/questions",
             post(create_question),
@@ -21,6 +23,10 @@ pub fn router() -> Router<AppState> {
             "/projects/{slug}/tasks/{task_ref}/questions/{question_id}",
             patch(answer_question),
         )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/questions/{question_id}/verdict",
+            post(review_answer),
+        )
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +40,32 @@ struct AnswerQuestionRequest {
     answer: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct BulkAnswerRequest {
+    answers: Vec<BulkAnswerEntry>,
+    #[serde(default)]
+    combined_event: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkAnswerEntry {
+    question_id: String,
+    answer: String,
+}
+
+#[derive(Debug, Serialize)]
+struct BulkAnswerResponse {
+    resolved: usize,
+    failed: usize,
+    results: Vec<BulkAnswerResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnswerVerdictRequest {
+    verdict: String,
+    reason: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct ProjectOpenQuestionResponse {
     id: String,
@@ -44,6 +76,8 @@ struct ProjectOpenQuestionResponse {
     context: String,
     answer: Option<String>,
     status: String,
+    acceptance: String,
+    dispute_reason: Option<String>,
     asked_by: String,
     resolved_by: Option<String>,
     created_at: String,
@@ -54,11 +88,12 @@ async fn list_open_questions(
     State(state): State<AppState>,
     Path(slug): Path<String>,
     Query(query): Query<ListQuery>,
-) -> AppResult<Json<Vec<ProjectOpenQuestionResponse>>> {
+) -> AppResult<Paginated<ProjectOpenQuestionResponse>> {
     let (limit, offset) = query.normalize()?;
+    let total = queries::count_project_open_questions(&state.db, &slug).await?;
     let records = queries::list_project_open_questions(&state.db, &slug, limit, offset).await?;
 
-    let payload = records
+    let items = records
         .into_iter()
         .map(|record| ProjectOpenQuestionResponse {
             task_display_key: queries::display_key(&slug, record.task_number),
@@ -69,6 +104,8 @@ async fn list_open_questions(
             context: record.context,
             answer: record.answer,
             status: record.status,
+            acceptance: record.acceptance,
+            dispute_reason: record.dispute_reason,
             asked_by: record.asked_by,
             resolved_by: record.resolved_by,
             created_at: record.created_at,
@@ -76,17 +113,23 @@ async fn list_open_questions(
         })
         .collect();
 
-    Ok(Json(payload))
+    Ok(Paginated {
+        items,
+        total,
+        limit,
+        offset,
+    })
 }
 
 async fn create_question(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<CreateQuestionRequest>,
+    ApiJson(payload): ApiJson<CreateQuestionRequest>,
 ) -> AppResult<(StatusCode, Json<OpenQuestionRecord>)> {
     let question = queries::create_open_question(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &task_ref,
         &payload.question,
@@ -102,10 +145,11 @@ async fn answer_question(
     State(state): State<AppState>,
     Path((slug, task_ref, question_id)): Path<(String, String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<AnswerQuestionRequest>,
+    ApiJson(payload): ApiJson<AnswerQuestionRequest>,
 ) -> AppResult<Json<OpenQuestionRecord>> {
     let question = queries::answer_open_question(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &task_ref,
         &question_id,
@@ -117,6 +161,61 @@ async fn answer_question(
     Ok(Json(question))
 }
 
+async fn bulk_answer(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<BulkAnswerRequest>,
+) -> AppResult<Json<BulkAnswerResponse>> {
+    let items = payload
+        .answers
+        .into_iter()
+        .map(|entry| queries::BulkAnswerItem {
+            question_id: entry.question_id,
+            answer: entry.answer,
+        })
+        .collect();
+    let results = queries::answer_open_questions_bulk(
+        &state.db,
+        &state.config.field_limits,
+        &slug,
+        items,
+        payload.combined_event,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+
+    let resolved = results.iter().filter(|result| result.resolved).count();
+    Ok(Json(BulkAnswerResponse {
+        resolved,
+        failed: results.len() - resolved,
+        results,
+    }))
+}
+
+async fn review_answer(
+    State(state): State<AppState>,
+    Path((slug, task_ref, question_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<AnswerVerdictRequest>,
+) -> AppResult<Json<OpenQuestionRecord>> {
+    let question = queries::review_question_answer(
+        &state.db,
+        &state.config.field_limits,
+        &slug,
+        &task_ref,
+        &question_id,
+        queries::ReviewAnswerInput {
+            verdict: payload.verdict,
+            reason: payload.reason,
+            actor: actor_from_headers(&headers),
+        },
+    )
+    .await?;
+
+    Ok(Json(question))
+}
+
 fn actor_from_headers(headers: &HeaderMap) -> String {
     headers
         .get("MCP-Client")
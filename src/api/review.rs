@@ -1,19 +1,43 @@
 use axum::extract::{Path, State};
-use axum::http::HeaderMap;
-use axum::routing::post;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, patch, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 
-use crate::db::models::TaskRecord;
+use crate::api::json::ApiJson;
+use crate::db::models::{
+    ReviewChecklistTemplateRecord, TaskRecord, TaskReviewItemRecord, TaskReviewRecord,
+};
 use crate::db::queries;
 use crate::error::AppResult;
 use crate::state::AppState;
 
 pub fn router() -> Router<AppState> {
-    Router::new().route(
-        "/projects/{slug}/tasks/{task_ref}/review",
-        post(set_review_state),
-    )
+    Router::new()
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/review",
+            post(set_review_state),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/reviews",
+            get(list_reviews).post(request_review),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/reviews/{review_id}/verdict",
+            post(submit_verdict),
+        )
+        .route(
+            "/projects/{slug}/review-checklist",
+            get(get_checklist_template).put(replace_checklist_template),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/review-checklist",
+            get(list_review_items),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/review-checklist/{item_id}",
+            patch(update_review_item),
+        )
 }
 
 #[derive(Debug, Deserialize)]
@@ -21,6 +45,27 @@ struct SetReviewStateRequest {
     review_state: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct RequestReviewRequest {
+    reviewer: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitVerdictRequest {
+    verdict: String,
+    comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplaceChecklistRequest {
+    items: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateReviewItemRequest {
+    done: bool,
+}
+
 #[derive(Debug, Serialize)]
 struct TaskReviewResponse {
     id: String,
@@ -41,7 +86,7 @@ async fn set_review_state(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<SetReviewStateRequest>,
+    ApiJson(payload): ApiJson<SetReviewStateRequest>,
 ) -> AppResult<Json<TaskReviewResponse>> {
     let task = queries::set_review_state(
         &state.db,
@@ -55,6 +100,95 @@ async fn set_review_state(
     Ok(Json(map_task_record(&slug, task)))
 }
 
+async fn list_reviews(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<Vec<TaskReviewRecord>>> {
+    let reviews = queries::list_task_reviews(&state.db, &slug, &task_ref).await?;
+    Ok(Json(reviews))
+}
+
+async fn request_review(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<RequestReviewRequest>,
+) -> AppResult<(StatusCode, Json<TaskReviewRecord>)> {
+    let review = queries::request_review(
+        &state.db,
+        &slug,
+        &task_ref,
+        &payload.reviewer,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(review)))
+}
+
+async fn submit_verdict(
+    State(state): State<AppState>,
+    Path((slug, task_ref, review_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<SubmitVerdictRequest>,
+) -> AppResult<Json<TaskReviewRecord>> {
+    let review = queries::complete_review(
+        &state.db,
+        &slug,
+        &task_ref,
+        &review_id,
+        &payload.verdict,
+        payload.comment,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+
+    Ok(Json(review))
+}
+
+async fn get_checklist_template(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<ReviewChecklistTemplateRecord>>> {
+    let items = queries::list_review_checklist_template(&state.db, &slug).await?;
+    Ok(Json(items))
+}
+
+async fn replace_checklist_template(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(payload): ApiJson<ReplaceChecklistRequest>,
+) -> AppResult<Json<Vec<ReviewChecklistTemplateRecord>>> {
+    let items = queries::replace_review_checklist_template(&state.db, &slug, payload.items).await?;
+    Ok(Json(items))
+}
+
+async fn list_review_items(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<Vec<TaskReviewItemRecord>>> {
+    let items = queries::list_task_review_items(&state.db, &slug, &task_ref).await?;
+    Ok(Json(items))
+}
+
+async fn update_review_item(
+    State(state): State<AppState>,
+    Path((slug, task_ref, item_id)): Path<(String, String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<UpdateReviewItemRequest>,
+) -> AppResult<Json<TaskReviewItemRecord>> {
+    let item = queries::set_review_item_done(
+        &state.db,
+        &slug,
+        &task_ref,
+        &item_id,
+        payload.done,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok(Json(item))
+}
+
 fn map_task_record(slug: &str, task: TaskRecord) -> TaskReviewResponse {
     TaskReviewResponse {
         id: task.id,
@@ -0,0 +1,442 @@
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::auth::hash_token;
+use crate::api::json::ApiJson;
+use crate::api::{ListQuery, Paginated};
+use crate::db::models::{ShareLinkRecord, SubtaskRecord, TaskRecord};
+use crate::db::queries;
+use crate::db::queries::TaskFilters;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+const MAX_SHARE_LINK_DAYS: u32 = 365;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{slug}/share-links",
+            get(list_links).post(create_link),
+        )
+        .route(
+            "/projects/{slug}/share-links/{link_id}",
+            delete(revoke_link),
+        )
+        .route("/share/{token}", get(shared_project))
+        .route("/share/{token}/tasks", get(shared_tasks))
+        .route("/share/{token}/tasks/{task_ref}", get(shared_task))
+        .route("/share/{token}/spec", get(shared_spec))
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateShareLinkRequest {
+    #[serde(default)]
+    label: String,
+    expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ShareLinkSecretResponse {
+    link: ShareLinkRecord,
+    token: String,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SharedTaskListQuery {
+    status: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedProjectResponse {
+    slug: String,
+    name: String,
+    goal: String,
+    backlog_count: i64,
+    ready_count: i64,
+    in_progress_count: i64,
+    review_count: i64,
+    done_count: i64,
+    updated_at: String,
+}
+
+/// Read-only view of a task. Leaves out authorship and anything that links to files.
+#[derive(Debug, Serialize)]
+struct SharedTaskResponse {
+    display_key: String,
+    title: String,
+    description: String,
+    status: String,
+    priority: String,
+    review_state: String,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedTaskDetailsResponse {
+    task: SharedTaskResponse,
+    labels: Vec<String>,
+    subtasks: Vec<SharedSubtaskResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedSubtaskResponse {
+    title: String,
+    done: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedSpecSection {
+    section: String,
+    content: String,
+    updated_at: String,
+}
+
+async fn list_links(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<ShareLinkRecord>>> {
+    let links = queries::list_share_links(&state.db, &slug).await?;
+    Ok(Json(links))
+}
+
+async fn create_link(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    ApiJson(request): ApiJson<CreateShareLinkRequest>,
+) -> AppResult<(StatusCode, Json<ShareLinkSecretResponse>)> {
+    let expires_at = match request.expires_in_days {
+        Some(days) if !(1..=MAX_SHARE_LINK_DAYS).contains(&days) => {
+            return Err(AppError::BadRequest(format!(
+                "expires_in_days must be between 1 and {MAX_SHARE_LINK_DAYS}"
+            )));
+        }
+        Some(days) => Some(
+            (Utc::now() + chrono::Duration::days(i64::from(days)))
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        ),
+        None => None,
+    };
+
+    let token = format!("lsh_{}", Uuid::new_v4().simple());
+    let link = queries::create_share_link(
+        &state.db,
+        &slug,
+        &hash_token(&token),
+        &request.label,
+        &actor_from_headers(&headers),
+        expires_at.as_deref(),
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ShareLinkSecretResponse {
+            link,
+            url: format!("/api/v1/share/{token}"),
+            token,
+        }),
+    ))
+}
+
+async fn revoke_link(
+    State(state): State<AppState>,
+    Path((slug, link_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    queries::revoke_share_link(&state.db, &slug, &link_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn shared_project(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Json<SharedProjectResponse>> {
+    let slug = resolve_share_token(&state, &token).await?;
+    let summary = queries::get_project(&state.db, &slug).await?;
+    Ok(Json(SharedProjectResponse {
+        slug: summary.project.slug,
+        name: summary.project.name,
+        goal: summary.project.goal,
+        backlog_count: summary.backlog_count,
+        ready_count: summary.ready_count,
+        in_progress_count: summary.in_progress_count,
+        review_count: summary.review_count,
+        done_count: summary.done_count,
+        updated_at: summary.project.updated_at,
+    }))
+}
+
+async fn shared_tasks(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(query): Query<SharedTaskListQuery>,
+) -> AppResult<Paginated<SharedTaskResponse>> {
+    let slug = resolve_share_token(&state, &token).await?;
+    let (limit, offset) = ListQuery {
+        limit: query.limit,
+        offset: query.offset,
+    }
+    .normalize()?;
+    let filters = TaskFilters {
+        statuses: queries::parse_filter_list(query.status.as_deref()),
+        ..TaskFilters::default()
+    };
+
+    let total = queries::count_tasks(&state.db, &slug, filters.clone()).await?;
+    let tasks = queries::list_tasks(&state.db, &slug, filters, limit, offset).await?;
+    Ok(Paginated {
+        items: tasks
+            .into_iter()
+            .map(|task| map_shared_task(&slug, task))
+            .collect(),
+        total,
+        limit,
+        offset,
+    })
+}
+
+async fn shared_task(
+    State(state): State<AppState>,
+    Path((token, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<SharedTaskDetailsResponse>> {
+    let slug = resolve_share_token(&state, &token).await?;
+    let details = queries::get_task_details(&state.db, &slug, &task_ref).await?;
+    Ok(Json(SharedTaskDetailsResponse {
+        task: map_shared_task(&slug, details.task),
+        labels: details.labels,
+        subtasks: details
+            .subtasks
+            .into_iter()
+            .map(map_shared_subtask)
+            .collect(),
+    }))
+}
+
+async fn shared_spec(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Json<Vec<SharedSpecSection>>> {
+    let slug = resolve_share_token(&state, &token).await?;
+    let sections =
+        queries::list_spec_sections(&state.db, &state.config.field_limits, &slug).await?;
+    Ok(Json(
+        sections
+            .into_iter()
+            .map(|section| SharedSpecSection {
+                section: section.section,
+                content: section.content,
+                updated_at: section.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+async fn resolve_share_token(state: &AppState, token: &str) -> AppResult<String> {
+    queries::project_slug_by_share_token(&state.db, &hash_token(token)).await
+}
+
+fn map_shared_task(slug: &str, task: TaskRecord) -> SharedTaskResponse {
+    SharedTaskResponse {
+        display_key: queries::display_key(slug, task.task_number),
+        title: task.title,
+        description: task.description,
+        status: task.status,
+        priority: task.priority,
+        review_state: task.review_state,
+        created_at: task.created_at,
+        updated_at: task.updated_at,
+    }
+}
+
+fn map_shared_subtask(subtask: SubtaskRecord) -> SharedSubtaskResponse {
+    SharedSubtaskResponse {
+        title: subtask.title,
+        done: subtask.done == 1,
+    }
+}
+
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::Router;
+    use reqwest::StatusCode;
+    use serde_json::{json, Value};
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::db::queries::NewTaskInput;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn share_links_grant_revocable_read_only_access() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("share_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: Some("instance-token".to_string()),
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Board", "shared", "BOARD")
+            .await
+            .expect("project should be created");
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BOARD",
+            NewTaskInput {
+                title: "visible".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: vec!["public".to_string()],
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let state = AppState::new(config, pool);
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let minted: Value = client
+            .post(format!("{api}/projects/BOARD/share-links"))
+            .bearer_auth("instance-token")
+            .json(&json!({ "label": "stakeholders" }))
+            .send()
+            .await
+            .expect("share link create should succeed")
+            .json()
+            .await
+            .expect("share link body should parse");
+        let shared = format!(
+            "http://{addr}{}",
+            minted["url"].as_str().expect("url should be returned")
+        );
+        let link_id = minted["link"]["id"]
+            .as_str()
+            .expect("link id should be returned")
+            .to_string();
+
+        let tasks = client
+            .get(format!("{shared}/tasks"))
+            .send()
+            .await
+            .expect("shared task list should complete");
+        assert_eq!(tasks.status(), StatusCode::OK);
+        let tasks: Value = tasks.json().await.expect("tasks should parse");
+        assert_eq!(tasks[0]["display_key"], "BOARD-1");
+        assert!(tasks[0].get("created_by").is_none());
+
+        let task: Value = client
+            .get(format!("{shared}/tasks/BOARD-1"))
+            .send()
+            .await
+            .expect("shared task should load")
+            .json()
+            .await
+            .expect("task should parse");
+        assert_eq!(task["labels"], json!(["public"]));
+        assert!(task.get("attachments").is_none());
+
+        let spec = client
+            .get(format!("{shared}/spec"))
+            .send()
+            .await
+            .expect("shared spec should complete");
+        assert_eq!(spec.status(), StatusCode::OK);
+
+        let write = client
+            .post(format!("{shared}/tasks"))
+            .json(&json!({ "title": "sneaky" }))
+            .send()
+            .await
+            .expect("write attempt should complete");
+        assert_eq!(write.status(), StatusCode::UNAUTHORIZED);
+
+        let revoked = client
+            .delete(format!("{api}/projects/BOARD/share-links/{link_id}"))
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("revoke should succeed");
+        assert_eq!(revoked.status(), StatusCode::NO_CONTENT);
+
+        let after = client
+            .get(&shared)
+            .send()
+            .await
+            .expect("revoked read should complete");
+        assert_eq!(after.status(), StatusCode::NOT_FOUND);
+
+        server.abort();
+    }
+}
@@ -0,0 +1,99 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::api::json::ApiJson;
+use crate::db::models::SlaPolicyRecord;
+use crate::db::queries;
+use crate::db::queries::CreateSlaPolicyInput;
+use crate::error::AppResult;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/projects/{slug}/sla-policies",
+            get(list_policies).post(create_policy),
+        )
+        .route(
+            "/projects/{slug}/sla-policies/{policy_id}",
+            delete(delete_policy),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateSlaPolicyRequest {
+    name: String,
+    status: String,
+    priority: Option<String>,
+    max_hours: i64,
+    #[serde(default)]
+    escalate_priority: bool,
+    webhook_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SlaPolicyResponse {
+    id: String,
+    name: String,
+    status: String,
+    priority: Option<String>,
+    max_hours: i64,
+    escalate_priority: bool,
+    webhook_id: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+async fn list_policies(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<SlaPolicyResponse>>> {
+    let policies = queries::list_sla_policies(&state.db, &slug).await?;
+    Ok(Json(policies.into_iter().map(map_policy).collect()))
+}
+
+async fn create_policy(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(request): ApiJson<CreateSlaPolicyRequest>,
+) -> AppResult<(StatusCode, Json<SlaPolicyResponse>)> {
+    let policy = queries::create_sla_policy(
+        &state.db,
+        &slug,
+        CreateSlaPolicyInput {
+            name: request.name,
+            status: request.status,
+            priority: request.priority,
+            max_hours: request.max_hours,
+            escalate_priority: request.escalate_priority,
+            webhook_id: request.webhook_id,
+        },
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(map_policy(policy))))
+}
+
+async fn delete_policy(
+    State(state): State<AppState>,
+    Path((slug, policy_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    queries::delete_sla_policy(&state.db, &slug, &policy_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn map_policy(record: SlaPolicyRecord) -> SlaPolicyResponse {
+    SlaPolicyResponse {
+        id: record.id,
+        name: record.name,
+        status: record.status,
+        priority: record.priority,
+        max_hours: record.max_hours,
+        escalate_priority: record.escalate_priority == 1,
+        webhook_id: record.webhook_id,
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
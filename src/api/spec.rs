@@ -1,11 +1,13 @@
 use axum::extract::{Path, Query, State};
-use axum::http::HeaderMap;
-use axum::routing::get;
+use axum::http::{HeaderMap, Uri};
+use axum::response::Response;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 use serde::Deserialize;
 
-use crate::api::ListQuery;
-use crate::db::models::{SpecRevisionRecord, SpecSectionRecord};
+use crate::api::json::ApiJson;
+use crate::api::{ListQuery, Paginated, ProjectETag};
+use crate::db::models::{SpecLintReport, SpecRevisionRecord, SpecSectionRecord};
 use crate::db::queries;
 use crate::error::AppResult;
 use crate::state::AppState;
@@ -13,10 +15,15 @@ use crate::state::AppState;
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/projects/{slug}/spec", get(list_spec_sections))
+        .route("/projects/{slug}/spec/lint", get(lint_spec))
         .route(
             "/projects/{slug}/spec/{section}",
             get(get_spec_section).put(update_spec_section),
         )
+        .route(
+            "/projects/{slug}/spec/{section}/lock",
+            post(lock_spec_section).delete(unlock_spec_section),
+        )
         .route(
             "/projects/{slug}/spec/{section}/history",
             get(get_spec_section_history),
@@ -26,36 +33,129 @@ pub fn router() -> Router<AppState> {
 #[derive(Debug, Deserialize)]
 struct UpdateSpecSectionRequest {
     content: String,
+    /// Writes even while someone else holds the section's lock.
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockSpecSectionRequest {
+    holder: Option<String>,
+    ttl_secs: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpecHistoryQuery {
+    include_content: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LintQuery {
+    section: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnlockQuery {
+    #[serde(default)]
+    force: bool,
 }
 
 async fn list_spec_sections(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-) -> AppResult<Json<Vec<SpecSectionRecord>>> {
-    let sections = queries::list_spec_sections(&state.db, &slug).await?;
-    Ok(Json(sections))
+    uri: Uri,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let etag = ProjectETag::load(&state.db, &slug, &uri).await?;
+    if etag.matches(&headers) {
+        return Ok(etag.not_modified());
+    }
+
+    let sections =
+        queries::list_spec_sections(&state.db, &state.config.field_limits, &slug).await?;
+    Ok(etag.tag(Json(sections)))
+}
+
+async fn lint_spec(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<LintQuery>,
+) -> AppResult<Json<SpecLintReport>> {
+    let report = queries::lint_spec(&state.db, &slug, query.section.as_deref()).await?;
+    Ok(Json(report))
 }
 
 async fn get_spec_section(
     State(state): State<AppState>,
     Path((slug, section)): Path<(String, String)>,
-) -> AppResult<Json<SpecSectionRecord>> {
-    let record = queries::get_spec_section(&state.db, &slug, &section).await?;
-    Ok(Json(record))
+    uri: Uri,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let etag = ProjectETag::load(&state.db, &slug, &uri).await?;
+    if etag.matches(&headers) {
+        return Ok(etag.not_modified());
+    }
+
+    let record =
+        queries::get_spec_section(&state.db, &state.config.field_limits, &slug, &section).await?;
+    Ok(etag.tag(Json(record)))
 }
 
 async fn update_spec_section(
     State(state): State<AppState>,
     Path((slug, section)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<UpdateSpecSectionRequest>,
+    ApiJson(payload): ApiJson<UpdateSpecSectionRequest>,
 ) -> AppResult<Json<SpecSectionRecord>> {
     let record = queries::update_spec_section(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &section,
         &payload.content,
         &actor_from_headers(&headers),
+        payload.force,
+    )
+    .await?;
+
+    Ok(Json(record))
+}
+
+async fn lock_spec_section(
+    State(state): State<AppState>,
+    Path((slug, section)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<LockSpecSectionRequest>,
+) -> AppResult<Json<SpecSectionRecord>> {
+    let holder = payload
+        .holder
+        .unwrap_or_else(|| actor_from_headers(&headers));
+    let record = queries::lock_spec_section(
+        &state.db,
+        &state.config.field_limits,
+        &slug,
+        &section,
+        &holder,
+        payload.ttl_secs,
+    )
+    .await?;
+
+    Ok(Json(record))
+}
+
+async fn unlock_spec_section(
+    State(state): State<AppState>,
+    Path((slug, section)): Path<(String, String)>,
+    Query(query): Query<UnlockQuery>,
+    headers: HeaderMap,
+) -> AppResult<Json<SpecSectionRecord>> {
+    let record = queries::unlock_spec_section(
+        &state.db,
+        &state.config.field_limits,
+        &slug,
+        &section,
+        &actor_from_headers(&headers),
+        query.force,
     )
     .await?;
 
@@ -66,10 +166,25 @@ async fn get_spec_section_history(
     State(state): State<AppState>,
     Path((slug, section)): Path<(String, String)>,
     Query(query): Query<ListQuery>,
-) -> AppResult<Json<Vec<SpecRevisionRecord>>> {
+    Query(history): Query<SpecHistoryQuery>,
+) -> AppResult<Paginated<SpecRevisionRecord>> {
     let (limit, offset) = query.normalize()?;
-    let history = queries::list_spec_history(&state.db, &slug, &section, limit, offset).await?;
-    Ok(Json(history))
+    let total = queries::count_spec_history(&state.db, &slug, &section).await?;
+    let items = queries::list_spec_history(
+        &state.db,
+        &slug,
+        &section,
+        limit,
+        offset,
+        history.include_content.unwrap_or(true),
+    )
+    .await?;
+    Ok(Paginated {
+        items,
+        total,
+        limit,
+        offset,
+    })
 }
 
 fn actor_from_headers(headers: &HeaderMap) -> String {
@@ -1,13 +1,24 @@
+use std::collections::HashMap;
+
 use axum::extract::{Path, Query, State};
-use axum::http::{HeaderMap, StatusCode};
-use axum::routing::{get, patch, post};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, patch, post, put};
 use axum::{Json, Router};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-use crate::db::models::{SubtaskRecord, TaskDetails, TaskRecord};
+use crate::api::json::ApiJson;
+use crate::api::{DryRunQuery, Paginated, ProjectETag};
+use crate::db::models::{
+    SubtaskRecord, TaskDependencies, TaskDetails, TaskHistoryDiff, TaskHistoryPage, TaskRecord,
+    TaskRecurrenceRecord,
+};
 use crate::db::queries;
 use crate::db::queries::{
-    MoveTaskInput, NewTaskInput, TaskFilters, UpdateSubtaskInput, UpdateTaskInput,
+    MoveTaskInput, NewTaskInput, ReorderTasksInput, SubtaskBulkItem, TaskFilters,
+    TaskHistoryFilters, UpdateSubtaskInput, UpdateTaskInput,
 };
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
@@ -15,19 +26,71 @@ use crate::state::AppState;
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/projects/{slug}/tasks", get(list_tasks).post(create_task))
+        .route(
+            "/projects/{slug}/tasks/export.md",
+            get(export_tasks_markdown),
+        )
         .route(
             "/projects/{slug}/tasks/{task_ref}",
             get(get_task).patch(update_task).delete(delete_task),
         )
         .route("/projects/{slug}/tasks/{task_ref}/move", post(move_task))
+        .route("/projects/{slug}/tasks/{task_ref}/undo", post(undo_task))
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/transfer",
+            post(transfer_task),
+        )
+        .route("/projects/{slug}/tasks/rebalance", post(rebalance_tasks))
+        .route("/projects/{slug}/tasks/reorder", post(reorder_tasks))
+        .route("/projects/{slug}/tasks/batch-get", post(batch_get_tasks))
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/history",
+            get(get_task_history),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/history/{history_id}/diff",
+            get(get_task_history_diff),
+        )
         .route(
             "/projects/{slug}/tasks/{task_ref}/subtasks",
-            post(add_subtask),
+            post(add_subtask).put(bulk_update_subtasks),
         )
         .route(
             "/projects/{slug}/tasks/{task_ref}/subtasks/{subtask_id}",
             patch(update_subtask).delete(delete_subtask),
         )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/dependencies",
+            get(list_dependencies),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/dependencies/{blocker_ref}",
+            put(add_dependency).delete(remove_dependency),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/parent",
+            put(set_parent).delete(clear_parent),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/milestone",
+            put(set_milestone).delete(clear_milestone),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/archive",
+            post(archive_task),
+        )
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/unarchive",
+            post(unarchive_task),
+        )
+        .route("/projects/{slug}/tasks/{task_ref}/purge", post(purge_task))
+        .route(
+            "/projects/{slug}/tasks/{task_ref}/recurrence",
+            get(get_recurrence)
+                .put(set_recurrence)
+                .delete(delete_recurrence),
+        )
+        .route("/projects/{slug}/recurrences", get(list_recurrences))
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,8 +98,78 @@ struct TaskListQuery {
     limit: Option<i64>,
     offset: Option<i64>,
     status: Option<String>,
+    #[serde(rename = "status!", alias = "exclude_status")]
+    exclude_status: Option<String>,
     label: Option<String>,
+    #[serde(rename = "label!", alias = "exclude_label")]
+    exclude_label: Option<String>,
+    priority: Option<String>,
+    /// `priority>=high` arrives as key `priority>` with value `high`.
+    #[serde(rename = "priority>", alias = "min_priority")]
+    min_priority: Option<String>,
+    #[serde(rename = "priority<", alias = "max_priority")]
+    max_priority: Option<String>,
     review_state: Option<String>,
+    created_by: Option<String>,
+    assignee: Option<String>,
+    due_before: Option<String>,
+    overdue: Option<bool>,
+    parent: Option<String>,
+    created_after: Option<String>,
+    created_before: Option<String>,
+    updated_after: Option<String>,
+    updated_before: Option<String>,
+    stale: Option<bool>,
+    archived: Option<bool>,
+    include_archived: Option<bool>,
+    sort: Option<String>,
+    group_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransferTaskRequest {
+    project: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReorderTasksRequest {
+    status: String,
+    tasks: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchGetTasksRequest {
+    tasks: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BatchGetTasksResponse {
+    tasks: Vec<TaskResponse>,
+    missing: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RebalanceQuery {
+    status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RebalanceResponse {
+    columns: Vec<RebalancedColumn>,
+}
+
+#[derive(Debug, Serialize)]
+struct RebalancedColumn {
+    status: String,
+    task_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct TaskHistoryQuery {
+    action: Option<String>,
+    actor: Option<String>,
+    limit: Option<i64>,
+    cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,6 +181,8 @@ struct CreateTaskRequest {
     review_state: Option<String>,
     #[serde(default)]
     labels: Vec<String>,
+    assignee: Option<String>,
+    due_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +193,23 @@ struct UpdateTaskRequest {
     priority: Option<String>,
     review_state: Option<String>,
     labels: Option<Vec<String>>,
+    assignee: Option<String>,
+    due_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetParentRequest {
+    parent: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetMilestoneRequest {
+    milestone_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SetRecurrenceRequest {
+    schedule: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -71,6 +223,17 @@ struct CreateSubtaskRequest {
     title: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct BulkSubtasksRequest {
+    subtasks: Vec<BulkSubtaskItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkSubtaskItem {
+    id: String,
+    done: Option<bool>,
+}
+
 #[derive(Debug, Deserialize)]
 struct UpdateSubtaskRequest {
     title: Option<String>,
@@ -78,7 +241,7 @@ struct UpdateSubtaskRequest {
     sort_order: Option<f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct TaskResponse {
     id: String,
     display_key: String,
@@ -92,6 +255,29 @@ struct TaskResponse {
     created_by: String,
     created_at: String,
     updated_at: String,
+    archived_at: Option<String>,
+    assignee: Option<String>,
+    due_at: Option<String>,
+    parent_task_id: Option<String>,
+    milestone_id: Option<String>,
+    local: LocalTimes,
+}
+
+/// The task's timestamps in the project's timezone, with their offsets.
+#[derive(Debug, Clone, Serialize)]
+struct LocalTimes {
+    timezone: String,
+    created_at: String,
+    updated_at: String,
+    archived_at: Option<String>,
+    due_at: Option<String>,
+}
+
+/// The tasks of one swimlane, in list order.
+#[derive(Debug, Serialize)]
+struct TaskLane {
+    key: Option<String>,
+    tasks: Vec<TaskResponse>,
 }
 
 #[derive(Debug, Serialize)]
@@ -100,8 +286,13 @@ struct TaskDetailsResponse {
     labels: Vec<String>,
     subtasks: Vec<crate::db::models::SubtaskRecord>,
     open_questions: Vec<crate::db::models::OpenQuestionRecord>,
+    reviews: Vec<crate::db::models::TaskReviewRecord>,
+    review_checklist: Vec<crate::db::models::TaskReviewItemRecord>,
     attachments: Vec<crate::db::models::AttachmentRecord>,
     history: Vec<crate::db::models::TaskHistoryRecord>,
+    blocked_by: Vec<crate::db::models::TaskDependencyRecord>,
+    blocks: Vec<crate::db::models::TaskDependencyRecord>,
+    children: crate::db::models::TaskChildren,
 }
 
 #[derive(Debug, Serialize)]
@@ -118,54 +309,181 @@ async fn list_tasks(
     State(state): State<AppState>,
     Path(slug): Path<String>,
     Query(query): Query<TaskListQuery>,
-) -> AppResult<Json<Vec<TaskResponse>>> {
+    uri: Uri,
+    headers: HeaderMap,
+) -> AppResult<Response> {
     let (limit, offset) = normalize_list_query(query.limit, query.offset)?;
+    let swimlanes = query
+        .group_by
+        .as_deref()
+        .map(queries::Swimlanes::parse)
+        .transpose()?;
 
-    let tasks = queries::list_tasks(
-        &state.db,
-        &slug,
-        TaskFilters {
-            status: query.status,
-            label: query.label,
-            review_state: query.review_state,
-        },
-        limit,
-        offset,
-    )
-    .await?;
+    // Staleness and overdueness move with the clock rather than the data, so those lists are
+    // never tagged.
+    let etag = if query.stale == Some(true) || query.overdue.is_some() {
+        None
+    } else {
+        Some(ProjectETag::load(&state.db, &slug, &uri).await?)
+    };
+    if let Some(etag) = etag.as_ref().filter(|etag| etag.matches(&headers)) {
+        return Ok(etag.not_modified());
+    }
 
-    let payload = tasks
-        .into_iter()
-        .map(|task| map_task_record(&slug, task))
-        .collect();
+    let filters = task_filters(&state, query)?;
+    let total = queries::count_tasks(&state.db, &slug, filters.clone()).await?;
+    let tasks = queries::list_tasks(&state.db, &slug, filters, limit, offset).await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
 
-    Ok(Json(payload))
+    // Lanes group the requested page; the pagination headers still count tasks.
+    let page = match swimlanes {
+        None => Paginated {
+            items: tasks
+                .into_iter()
+                .map(|task| map_task_record(&slug, task, timezone))
+                .collect(),
+            total,
+            limit,
+            offset,
+        }
+        .into_response(),
+        Some(swimlanes) => {
+            let task_ids: Vec<String> = tasks.iter().map(|task| task.id.clone()).collect();
+            let mut labels = queries::labels_by_task(&state.db, &task_ids).await?;
+            let entries: Vec<(TaskResponse, Vec<String>)> = tasks
+                .into_iter()
+                .map(|task| {
+                    let task_labels = labels.remove(&task.id).unwrap_or_default();
+                    (map_task_record(&slug, task, timezone), task_labels)
+                })
+                .collect();
+            let items = swimlanes
+                .group(entries, |(task, _)| &task.priority, |(_, labels)| labels)
+                .into_iter()
+                .map(|(key, members)| TaskLane {
+                    key,
+                    tasks: members.into_iter().map(|(task, _)| task).collect(),
+                })
+                .collect();
+            Paginated {
+                items,
+                total,
+                limit,
+                offset,
+            }
+            .into_response()
+        }
+    };
+    Ok(match etag {
+        Some(etag) => etag.tag(page),
+        None => page.into_response(),
+    })
+}
+
+/// The tasks `list_tasks` would return, as a Markdown checklist with subtasks nested under
+/// each task, for pasting into PR descriptions and planning docs.
+async fn export_tasks_markdown(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<TaskListQuery>,
+) -> AppResult<Response> {
+    let (limit, offset) = normalize_list_query(query.limit, query.offset)?;
+    let filters = task_filters(&state, query)?;
+    let total = queries::count_tasks(&state.db, &slug, filters.clone()).await?;
+    let tasks = queries::list_tasks(&state.db, &slug, filters, limit, offset).await?;
+    let task_ids: Vec<String> = tasks.iter().map(|task| task.id.clone()).collect();
+    let subtasks = queries::subtasks_by_task(&state.db, &task_ids).await?;
+
+    let markdown = render_markdown_checklist(&slug, &tasks, &subtasks, offset, total);
+    let mut response = markdown.into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/markdown; charset=utf-8"),
+    );
+    for (name, value) in [
+        ("x-total-count", total),
+        ("x-limit", limit),
+        ("x-offset", offset),
+    ] {
+        headers.insert(name, HeaderValue::from(value));
+    }
+    Ok(response)
+}
+
+/// `- [ ] ROADMAP-3 Title` per task, checked once done, with subtasks as nested checkboxes. A
+/// partial page ends with a note saying which tasks it covers.
+fn render_markdown_checklist(
+    slug: &str,
+    tasks: &[TaskRecord],
+    subtasks: &HashMap<String, Vec<SubtaskRecord>>,
+    offset: i64,
+    total: i64,
+) -> String {
+    let mut markdown = String::new();
+    for task in tasks {
+        let mark = if task.status == "done" { 'x' } else { ' ' };
+        markdown.push_str(&format!(
+            "- [{mark}] {} {}\n",
+            queries::display_key(slug, task.task_number),
+            single_line(&task.title)
+        ));
+        for subtask in subtasks.get(&task.id).into_iter().flatten() {
+            let mark = if subtask.done == 1 { 'x' } else { ' ' };
+            markdown.push_str(&format!("  - [{mark}] {}\n", single_line(&subtask.title)));
+        }
+    }
+
+    let shown = tasks.len() as i64;
+    if shown == 0 {
+        markdown.push_str("_No matching tasks._\n");
+    } else if shown < total {
+        markdown.push_str(&format!(
+            "\n_Tasks {}–{} of {total}._\n",
+            offset + 1,
+            offset + shown
+        ));
+    }
+    markdown
+}
+
+/// Line breaks in a title would end its list item early.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 async fn create_task(
     State(state): State<AppState>,
     Path(slug): Path<String>,
     headers: HeaderMap,
-    Json(payload): Json<CreateTaskRequest>,
-) -> AppResult<(StatusCode, Json<TaskResponse>)> {
-    let actor = actor_from_headers(&headers);
+    Query(query): Query<DryRunQuery>,
+    ApiJson(payload): ApiJson<CreateTaskRequest>,
+) -> AppResult<Response> {
+    let input = NewTaskInput {
+        title: payload.title,
+        description: payload.description.unwrap_or_default(),
+        status: payload.status.unwrap_or_else(|| "backlog".to_string()),
+        priority: payload.priority.unwrap_or_else(|| "medium".to_string()),
+        review_state: payload.review_state.unwrap_or_else(|| "ready".to_string()),
+        labels: payload.labels,
+        created_by: actor_from_headers(&headers),
+        assignee: payload.assignee,
+        due_at: payload.due_at,
+    };
+    if query.dry_run {
+        let report =
+            queries::dry_run_create_task(&state.db, &state.config.field_limits, &slug, input)
+                .await?;
+        return Ok(Json(report).into_response());
+    }
 
-    let task = queries::create_task(
-        &state.db,
-        &slug,
-        NewTaskInput {
-            title: payload.title,
-            description: payload.description.unwrap_or_default(),
-            status: payload.status.unwrap_or_else(|| "backlog".to_string()),
-            priority: payload.priority.unwrap_or_else(|| "medium".to_string()),
-            review_state: payload.review_state.unwrap_or_else(|| "ready".to_string()),
-            labels: payload.labels,
-            created_by: actor,
-        },
+    let task = queries::create_task(&state.db, &state.config.field_limits, &slug, input).await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok((
+        StatusCode::CREATED,
+        Json(map_task_record(&slug, task, timezone)),
     )
-    .await?;
-
-    Ok((StatusCode::CREATED, Json(map_task_record(&slug, task))))
+        .into_response())
 }
 
 async fn get_task(
@@ -173,14 +491,15 @@ async fn get_task(
     Path((slug, task_ref)): Path<(String, String)>,
 ) -> AppResult<Json<TaskDetailsResponse>> {
     let details = queries::get_task_details(&state.db, &slug, &task_ref).await?;
-    Ok(Json(map_task_details(&slug, details)))
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_details(&slug, details, timezone)))
 }
 
 async fn update_task(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<UpdateTaskRequest>,
+    ApiJson(payload): ApiJson<UpdateTaskRequest>,
 ) -> AppResult<Json<TaskResponse>> {
     if payload.title.is_none()
         && payload.description.is_none()
@@ -188,6 +507,8 @@ async fn update_task(
         && payload.priority.is_none()
         && payload.review_state.is_none()
         && payload.labels.is_none()
+        && payload.assignee.is_none()
+        && payload.due_at.is_none()
     {
         return Err(AppError::BadRequest(
             "at least one field must be provided".to_string(),
@@ -196,6 +517,7 @@ async fn update_task(
 
     let task = queries::update_task(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &task_ref,
         UpdateTaskInput {
@@ -205,19 +527,51 @@ async fn update_task(
             priority: payload.priority,
             review_state: payload.review_state,
             labels: payload.labels,
+            assignee: payload.assignee,
+            due_at: payload.due_at,
             actor: actor_from_headers(&headers),
         },
     )
     .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn get_task_history(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    Query(query): Query<TaskHistoryQuery>,
+) -> AppResult<Json<TaskHistoryPage>> {
+    let (limit, _) = normalize_list_query(query.limit, None)?;
+    let page = queries::list_task_history(
+        &state.db,
+        &slug,
+        &task_ref,
+        TaskHistoryFilters {
+            action: query.action,
+            actor: query.actor,
+        },
+        limit,
+        query.cursor.as_deref(),
+    )
+    .await?;
+    Ok(Json(page))
+}
 
-    Ok(Json(map_task_record(&slug, task)))
+async fn get_task_history_diff(
+    State(state): State<AppState>,
+    Path((slug, task_ref, history_id)): Path<(String, String, String)>,
+) -> AppResult<Json<TaskHistoryDiff>> {
+    let diff = queries::get_task_history_diff(&state.db, &slug, &task_ref, &history_id).await?;
+    Ok(Json(diff))
 }
 
 async fn move_task(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<MoveTaskRequest>,
+    ApiJson(payload): ApiJson<MoveTaskRequest>,
 ) -> AppResult<Json<TaskResponse>> {
     let actor = actor_from_headers(&headers);
     let task = queries::move_task(
@@ -232,18 +586,106 @@ async fn move_task(
         },
     )
     .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn undo_task(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskResponse>> {
+    let task =
+        queries::undo_last_task_change(&state.db, &slug, &task_ref, &actor_from_headers(&headers))
+            .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn transfer_task(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<TransferTaskRequest>,
+) -> AppResult<Json<TaskResponse>> {
+    let (target_slug, task) = queries::transfer_task(
+        &state.db,
+        &slug,
+        &task_ref,
+        &payload.project,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &target_slug).await?;
+    Ok(Json(map_task_record(&target_slug, task, timezone)))
+}
+
+async fn reorder_tasks(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<ReorderTasksRequest>,
+) -> AppResult<Json<Vec<TaskResponse>>> {
+    let tasks = queries::reorder_tasks(
+        &state.db,
+        &slug,
+        ReorderTasksInput {
+            status: payload.status,
+            task_refs: payload.tasks,
+            actor: actor_from_headers(&headers),
+            mcp_origin: headers.get("MCP-Client").is_some(),
+        },
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+
+    let payload = tasks
+        .into_iter()
+        .map(|task| map_task_record(&slug, task, timezone))
+        .collect();
+    Ok(Json(payload))
+}
 
-    Ok(Json(map_task_record(&slug, task)))
+async fn batch_get_tasks(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    ApiJson(payload): ApiJson<BatchGetTasksRequest>,
+) -> AppResult<Json<BatchGetTasksResponse>> {
+    let (tasks, missing) = queries::get_tasks_by_refs(&state.db, &slug, &payload.tasks).await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+
+    Ok(Json(BatchGetTasksResponse {
+        tasks: tasks
+            .into_iter()
+            .map(|task| map_task_record(&slug, task, timezone))
+            .collect(),
+        missing,
+    }))
+}
+
+async fn rebalance_tasks(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<RebalanceQuery>,
+) -> AppResult<Json<RebalanceResponse>> {
+    let columns = queries::rebalance_task_columns(&state.db, &slug, query.status.as_deref())
+        .await?
+        .into_iter()
+        .map(|(status, task_count)| RebalancedColumn { status, task_count })
+        .collect();
+    Ok(Json(RebalanceResponse { columns }))
 }
 
 async fn add_subtask(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<CreateSubtaskRequest>,
+    ApiJson(payload): ApiJson<CreateSubtaskRequest>,
 ) -> AppResult<(StatusCode, Json<SubtaskResponse>)> {
     let subtask = queries::add_subtask(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &task_ref,
         &payload.title,
@@ -258,7 +700,7 @@ async fn update_subtask(
     State(state): State<AppState>,
     Path((slug, task_ref, subtask_id)): Path<(String, String, String)>,
     headers: HeaderMap,
-    Json(payload): Json<UpdateSubtaskRequest>,
+    ApiJson(payload): ApiJson<UpdateSubtaskRequest>,
 ) -> AppResult<Json<SubtaskResponse>> {
     if payload.title.is_none() && payload.done.is_none() && payload.sort_order.is_none() {
         return Err(AppError::BadRequest(
@@ -268,6 +710,7 @@ async fn update_subtask(
 
     let subtask = queries::update_subtask(
         &state.db,
+        &state.config.field_limits,
         &slug,
         &task_ref,
         &subtask_id,
@@ -283,6 +726,33 @@ async fn update_subtask(
     Ok(Json(map_subtask(subtask)))
 }
 
+async fn bulk_update_subtasks(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<BulkSubtasksRequest>,
+) -> AppResult<Json<Vec<SubtaskResponse>>> {
+    let items = payload
+        .subtasks
+        .into_iter()
+        .map(|item| SubtaskBulkItem {
+            id: item.id,
+            done: item.done,
+        })
+        .collect();
+
+    let subtasks = queries::bulk_update_subtasks(
+        &state.db,
+        &slug,
+        &task_ref,
+        items,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+
+    Ok(Json(subtasks.into_iter().map(map_subtask).collect()))
+}
+
 async fn delete_subtask(
     State(state): State<AppState>,
     Path((slug, task_ref, subtask_id)): Path<(String, String, String)>,
@@ -300,16 +770,209 @@ async fn delete_subtask(
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn list_dependencies(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<TaskDependencies>> {
+    let dependencies = queries::list_task_dependencies(&state.db, &slug, &task_ref).await?;
+    Ok(Json(dependencies))
+}
+
+async fn add_dependency(
+    State(state): State<AppState>,
+    Path((slug, task_ref, blocker_ref)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskDependencies>> {
+    let dependencies = queries::add_task_dependency(
+        &state.db,
+        &slug,
+        &task_ref,
+        &blocker_ref,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok(Json(dependencies))
+}
+
+async fn remove_dependency(
+    State(state): State<AppState>,
+    Path((slug, task_ref, blocker_ref)): Path<(String, String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskDependencies>> {
+    let dependencies = queries::remove_task_dependency(
+        &state.db,
+        &slug,
+        &task_ref,
+        &blocker_ref,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok(Json(dependencies))
+}
+
+async fn set_parent(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<SetParentRequest>,
+) -> AppResult<Json<TaskResponse>> {
+    let task = queries::set_task_parent(
+        &state.db,
+        &slug,
+        &task_ref,
+        Some(&payload.parent),
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn clear_parent(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskResponse>> {
+    let task = queries::set_task_parent(
+        &state.db,
+        &slug,
+        &task_ref,
+        None,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn set_milestone(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<SetMilestoneRequest>,
+) -> AppResult<Json<TaskResponse>> {
+    let task = queries::set_task_milestone(
+        &state.db,
+        &slug,
+        &task_ref,
+        Some(&payload.milestone_id),
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn clear_milestone(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskResponse>> {
+    let task = queries::set_task_milestone(
+        &state.db,
+        &slug,
+        &task_ref,
+        None,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn list_recurrences(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<TaskRecurrenceRecord>>> {
+    let recurrences = queries::list_task_recurrences(&state.db, &slug).await?;
+    Ok(Json(recurrences))
+}
+
+async fn get_recurrence(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<Json<TaskRecurrenceRecord>> {
+    let recurrence = queries::get_task_recurrence(&state.db, &slug, &task_ref).await?;
+    Ok(Json(recurrence))
+}
+
+async fn set_recurrence(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+    ApiJson(payload): ApiJson<SetRecurrenceRequest>,
+) -> AppResult<Json<TaskRecurrenceRecord>> {
+    let recurrence = queries::set_task_recurrence(
+        &state.db,
+        &slug,
+        &task_ref,
+        &payload.schedule,
+        &actor_from_headers(&headers),
+    )
+    .await?;
+    Ok(Json(recurrence))
+}
+
+async fn delete_recurrence(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    queries::delete_task_recurrence(&state.db, &slug, &task_ref).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Deleting a task archives it; `purge` is the only way to remove it for good.
 async fn delete_task(
     State(state): State<AppState>,
     Path((slug, task_ref)): Path<(String, String)>,
     headers: HeaderMap,
 ) -> AppResult<StatusCode> {
-    queries::delete_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    queries::archive_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn archive_task(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskResponse>> {
+    let task =
+        queries::archive_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn unarchive_task(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<Json<TaskResponse>> {
+    let task =
+        queries::unarchive_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    let timezone = queries::project_timezone(&state.db, &slug).await?;
+    Ok(Json(map_task_record(&slug, task, timezone)))
+}
+
+async fn purge_task(
+    State(state): State<AppState>,
+    Path((slug, task_ref)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> AppResult<StatusCode> {
+    let storage_paths =
+        queries::purge_task(&state.db, &slug, &task_ref, &actor_from_headers(&headers)).await?;
+    crate::api::attachments::remove_stored_files(&state.config.storage_dir, &storage_paths).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
-fn map_task_record(slug: &str, task: TaskRecord) -> TaskResponse {
+fn map_task_record(slug: &str, task: TaskRecord, timezone: Tz) -> TaskResponse {
+    let local = |timestamp: &str| queries::localize_timestamp(timestamp, timezone);
+    let local = LocalTimes {
+        timezone: timezone.name().to_string(),
+        created_at: local(&task.created_at),
+        updated_at: local(&task.updated_at),
+        archived_at: task.archived_at.as_deref().map(local),
+        due_at: task.due_at.as_deref().map(local),
+    };
     TaskResponse {
         id: task.id,
         display_key: queries::display_key(slug, task.task_number),
@@ -323,17 +986,28 @@ fn map_task_record(slug: &str, task: TaskRecord) -> TaskResponse {
         created_by: task.created_by,
         created_at: task.created_at,
         updated_at: task.updated_at,
+        archived_at: task.archived_at,
+        assignee: task.assignee,
+        due_at: task.due_at,
+        parent_task_id: task.parent_task_id,
+        milestone_id: task.milestone_id,
+        local,
     }
 }
 
-fn map_task_details(slug: &str, details: TaskDetails) -> TaskDetailsResponse {
+fn map_task_details(slug: &str, details: TaskDetails, timezone: Tz) -> TaskDetailsResponse {
     TaskDetailsResponse {
-        task: map_task_record(slug, details.task),
+        task: map_task_record(slug, details.task, timezone),
         labels: details.labels,
         subtasks: details.subtasks,
         open_questions: details.open_questions,
+        reviews: details.reviews,
+        review_checklist: details.review_checklist,
         attachments: details.attachments,
         history: details.history,
+        blocked_by: details.dependencies.blocked_by,
+        blocks: details.dependencies.blocks,
+        children: details.children,
     }
 }
 
@@ -348,6 +1022,43 @@ fn map_subtask(subtask: SubtaskRecord) -> SubtaskResponse {
     }
 }
 
+fn task_filters(state: &AppState, query: TaskListQuery) -> AppResult<TaskFilters> {
+    Ok(TaskFilters {
+        statuses: queries::parse_filter_list(query.status.as_deref()),
+        exclude_statuses: queries::parse_filter_list(query.exclude_status.as_deref()),
+        labels: queries::parse_filter_list(query.label.as_deref()),
+        exclude_labels: queries::parse_filter_list(query.exclude_label.as_deref()),
+        priorities: queries::parse_filter_list(query.priority.as_deref()),
+        min_priority: query.min_priority,
+        max_priority: query.max_priority,
+        review_state: query.review_state,
+        created_by: query.created_by,
+        assignees: queries::parse_filter_list(query.assignee.as_deref()),
+        due_before: query.due_before,
+        overdue: query.overdue,
+        parent: query.parent,
+        created_after: query.created_after,
+        created_before: query.created_before,
+        updated_after: query.updated_after,
+        updated_before: query.updated_before,
+        stale_before: stale_cutoff(state, query.stale)?,
+        archived: query.archived,
+        include_archived: query.include_archived.unwrap_or(false),
+        sort: query.sort,
+    })
+}
+
+fn stale_cutoff(state: &AppState, stale: Option<bool>) -> AppResult<Option<String>> {
+    if stale != Some(true) {
+        return Ok(None);
+    }
+
+    let days = state.config.stale_threshold_days().ok_or_else(|| {
+        AppError::BadRequest("stale detection is disabled (LATTICE_STALE_AFTER_DAYS=0)".to_string())
+    })?;
+    Ok(Some(queries::timestamp_days_ago(days)))
+}
+
 fn actor_from_headers(headers: &HeaderMap) -> String {
     headers
         .get("MCP-Client")
@@ -382,3 +1093,88 @@ fn normalize_list_query(limit: Option<i64>, offset: Option<i64>) -> AppResult<(i
 
     Ok((limit, offset))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::db::models::{SubtaskRecord, TaskRecord};
+
+    use super::{map_task_record, render_markdown_checklist};
+
+    fn task(id: &str, task_number: i64, title: &str, status: &str) -> TaskRecord {
+        TaskRecord {
+            id: id.to_string(),
+            project_id: "p".to_string(),
+            task_number,
+            title: title.to_string(),
+            description: String::new(),
+            status: status.to_string(),
+            priority: "medium".to_string(),
+            review_state: "ready".to_string(),
+            sort_order: 1.0,
+            created_by: "ana".to_string(),
+            created_at: "2026-03-01T10:00:00Z".to_string(),
+            updated_at: "2026-03-01T10:00:00Z".to_string(),
+            archived_at: None,
+            assignee: None,
+            due_at: None,
+            parent_task_id: None,
+            milestone_id: None,
+        }
+    }
+
+    fn subtask(task_id: &str, title: &str, done: bool) -> SubtaskRecord {
+        SubtaskRecord {
+            id: format!("{task_id}-{title}"),
+            task_id: task_id.to_string(),
+            title: title.to_string(),
+            done: i64::from(done),
+            sort_order: 1.0,
+            created_at: "2026-03-01T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn markdown_checklist_nests_subtasks_and_notes_partial_pages() {
+        let tasks = [
+            task("a", 3, "Ship the\nexporter", "in_progress"),
+            task("b", 7, "Write docs", "done"),
+        ];
+        let subtasks = HashMap::from([(
+            "a".to_string(),
+            vec![subtask("a", "render", true), subtask("a", "test", false)],
+        )]);
+
+        assert_eq!(
+            render_markdown_checklist("ROADMAP", &tasks, &subtasks, 0, 2),
+            "- [ ] ROADMAP-3 Ship the exporter\n  - [x] render\n  - [ ] test\n- [x] ROADMAP-7 Write docs\n"
+        );
+        assert!(
+            render_markdown_checklist("ROADMAP", &tasks[..1], &subtasks, 4, 9)
+                .ends_with("\n_Tasks 5–5 of 9._\n")
+        );
+        assert_eq!(
+            render_markdown_checklist("ROADMAP", &[], &subtasks, 0, 0),
+            "_No matching tasks._\n"
+        );
+    }
+
+    #[test]
+    fn task_responses_localize_timestamps_to_the_project_timezone() {
+        let mut record = task("a", 1, "Ship", "ready");
+        record.updated_at = "2026-03-01T10:00:00.250Z".to_string();
+        record.due_at = Some("2026-07-01T17:00:00.000Z".to_string());
+
+        let response = map_task_record("ROADMAP", record, chrono_tz::Europe::Berlin);
+        assert_eq!(response.updated_at, "2026-03-01T10:00:00.250Z");
+        assert_eq!(response.local.timezone, "Europe/Berlin");
+        assert_eq!(response.local.created_at, "2026-03-01T11:00:00.000+01:00");
+        assert_eq!(response.local.updated_at, "2026-03-01T11:00:00.250+01:00");
+        assert_eq!(
+            response.local.due_at.as_deref(),
+            Some("2026-07-01T19:00:00.000+02:00")
+        );
+        assert_eq!(response.local.archived_at, None);
+    }
+}
@@ -1,9 +1,12 @@
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::api::json::ApiJson;
+use crate::api::DryRunQuery;
 use crate::db::models::WebhookRecord;
 use crate::db::queries;
 use crate::db::queries::{CreateWebhookInput, UpdateWebhookInput};
@@ -35,6 +38,15 @@ struct CreateWebhookRequest {
     events: Vec<String>,
     secret: Option<String>,
     active: Option<bool>,
+    payload_format: Option<String>,
+    schema_version: Option<i64>,
+    include_task_snapshot: Option<bool>,
+    quiet_hours: Option<String>,
+    quiet_hours_timezone: Option<String>,
+    max_deliveries_per_minute: Option<i64>,
+    timeout_secs: Option<i64>,
+    max_retries: Option<i64>,
+    retry_backoff_secs: Option<i64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +57,27 @@ struct UpdateWebhookRequest {
     events: Option<Vec<String>>,
     secret: Option<String>,
     active: Option<bool>,
+    payload_format: Option<String>,
+    schema_version: Option<i64>,
+    include_task_snapshot: Option<bool>,
+    quiet_hours: Option<String>,
+    quiet_hours_timezone: Option<String>,
+    max_deliveries_per_minute: Option<i64>,
+    /// `null` goes back to the server default; a missing field leaves the value alone.
+    #[serde(default, deserialize_with = "nullable")]
+    timeout_secs: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    max_retries: Option<Option<i64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    retry_backoff_secs: Option<Option<i64>>,
+}
+
+/// Tells an explicit `null` (`Some(None)`) apart from a missing field (`None`).
+fn nullable<'de, D>(deserializer: D) -> Result<Option<Option<i64>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<i64>::deserialize(deserializer).map(Some)
 }
 
 #[derive(Debug, Serialize)]
@@ -55,6 +88,16 @@ struct WebhookResponse {
     platform: String,
     events: Vec<String>,
     active: bool,
+    payload_format: String,
+    schema_version: i64,
+    include_task_snapshot: bool,
+    quiet_hours: Option<String>,
+    quiet_hours_timezone: String,
+    max_deliveries_per_minute: i64,
+    /// `null` when the webhook uses the server default.
+    timeout_secs: Option<i64>,
+    max_retries: Option<i64>,
+    retry_backoff_secs: Option<i64>,
     has_secret: bool,
     created_at: String,
     updated_at: String,
@@ -75,29 +118,48 @@ async fn list_webhooks(
 async fn create_webhook(
     State(state): State<AppState>,
     Path(slug): Path<String>,
-    Json(request): Json<CreateWebhookRequest>,
-) -> AppResult<(StatusCode, Json<WebhookResponse>)> {
+    headers: HeaderMap,
+    Query(query): Query<DryRunQuery>,
+    ApiJson(request): ApiJson<CreateWebhookRequest>,
+) -> AppResult<Response> {
+    let input = CreateWebhookInput {
+        name: request.name,
+        url: request.url,
+        platform: request.platform,
+        events: request.events,
+        secret: request.secret,
+        active: request.active.unwrap_or(true),
+        payload_format: request.payload_format,
+        schema_version: request.schema_version,
+        include_task_snapshot: request.include_task_snapshot.unwrap_or(false),
+        quiet_hours: request.quiet_hours,
+        quiet_hours_timezone: request.quiet_hours_timezone,
+        max_deliveries_per_minute: request.max_deliveries_per_minute,
+        timeout_secs: request.timeout_secs,
+        max_retries: request.max_retries,
+        retry_backoff_secs: request.retry_backoff_secs,
+    };
+    if query.dry_run {
+        let report = queries::dry_run_create_webhook(&state.db, &slug, input).await?;
+        return Ok(Json(report).into_response());
+    }
+
     let created = queries::create_webhook(
         &state.db,
+        &state.keyring,
         &slug,
-        CreateWebhookInput {
-            name: request.name,
-            url: request.url,
-            platform: request.platform,
-            events: request.events,
-            secret: request.secret,
-            active: request.active.unwrap_or(true),
-        },
+        input,
+        &actor_from_headers(&headers),
     )
     .await?;
-
-    Ok((StatusCode::CREATED, Json(map_webhook(created)?)))
+    Ok((StatusCode::CREATED, Json(map_webhook(created)?)).into_response())
 }
 
 async fn update_webhook(
     State(state): State<AppState>,
     Path((slug, webhook_id)): Path<(String, String)>,
-    Json(request): Json<UpdateWebhookRequest>,
+    headers: HeaderMap,
+    ApiJson(request): ApiJson<UpdateWebhookRequest>,
 ) -> AppResult<Json<WebhookResponse>> {
     if request.name.is_none()
         && request.url.is_none()
@@ -105,6 +167,15 @@ async fn update_webhook(
         && request.events.is_none()
         && request.secret.is_none()
         && request.active.is_none()
+        && request.payload_format.is_none()
+        && request.schema_version.is_none()
+        && request.include_task_snapshot.is_none()
+        && request.quiet_hours.is_none()
+        && request.quiet_hours_timezone.is_none()
+        && request.max_deliveries_per_minute.is_none()
+        && request.timeout_secs.is_none()
+        && request.max_retries.is_none()
+        && request.retry_backoff_secs.is_none()
     {
         return Err(AppError::BadRequest(
             "at least one field must be provided".to_string(),
@@ -113,6 +184,7 @@ async fn update_webhook(
 
     let updated = queries::update_webhook(
         &state.db,
+        &state.keyring,
         &slug,
         &webhook_id,
         UpdateWebhookInput {
@@ -122,7 +194,17 @@ async fn update_webhook(
             events: request.events,
             secret: request.secret,
             active: request.active,
+            payload_format: request.payload_format,
+            schema_version: request.schema_version,
+            include_task_snapshot: request.include_task_snapshot,
+            quiet_hours: request.quiet_hours,
+            quiet_hours_timezone: request.quiet_hours_timezone,
+            max_deliveries_per_minute: request.max_deliveries_per_minute,
+            timeout_secs: request.timeout_secs,
+            max_retries: request.max_retries,
+            retry_backoff_secs: request.retry_backoff_secs,
         },
+        &actor_from_headers(&headers),
     )
     .await?;
 
@@ -132,8 +214,9 @@ async fn update_webhook(
 async fn delete_webhook(
     State(state): State<AppState>,
     Path((slug, webhook_id)): Path<(String, String)>,
+    headers: HeaderMap,
 ) -> AppResult<StatusCode> {
-    queries::delete_webhook(&state.db, &slug, &webhook_id).await?;
+    queries::delete_webhook(&state.db, &slug, &webhook_id, &actor_from_headers(&headers)).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -154,6 +237,15 @@ fn map_webhook(record: WebhookRecord) -> AppResult<WebhookResponse> {
         platform: record.platform,
         events,
         active: record.active == 1,
+        payload_format: record.payload_format,
+        schema_version: record.schema_version,
+        include_task_snapshot: record.include_task_snapshot == 1,
+        quiet_hours: record.quiet_hours,
+        quiet_hours_timezone: record.quiet_hours_timezone,
+        max_deliveries_per_minute: record.max_deliveries_per_minute,
+        timeout_secs: record.timeout_secs,
+        max_retries: record.max_retries,
+        retry_backoff_secs: record.retry_backoff_secs,
         has_secret: record
             .secret
             .as_deref()
@@ -163,8 +255,19 @@ fn map_webhook(record: WebhookRecord) -> AppResult<WebhookResponse> {
     })
 }
 
+fn actor_from_headers(headers: &HeaderMap) -> String {
+    headers
+        .get("MCP-Client")
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| "human".to_string())
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
     use std::time::Duration;
 
     use axum::extract::State;
@@ -174,12 +277,16 @@ mod tests {
     use axum::{Json, Router};
     use reqwest::StatusCode;
     use serde_json::json;
+    use sqlx::AnyPool;
     use tempfile::tempdir;
     use tokio::sync::mpsc;
+    use tokio::task::JoinHandle;
     use tokio::time::timeout;
 
     use crate::api;
-    use crate::config::{Config, RateLimitConfig};
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
     use crate::db;
     use crate::db::queries;
     use crate::state::AppState;
@@ -199,13 +306,23 @@ mod tests {
         std::fs::create_dir_all(&storage_dir).expect("storage dir should be created");
 
         let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
             port: 0,
             db_url,
             token: None,
             log_level: "info".to_string(),
             storage_dir,
             max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
             rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
         };
         let pool = db::connect_and_migrate(&config)
             .await
@@ -261,7 +378,8 @@ mod tests {
                 "platform": "generic",
                 "events": ["task.created"],
                 "secret": "top-secret",
-                "active": true
+                "active": true,
+                "timeout_secs": 30
             }))
             .send()
             .await
@@ -309,6 +427,20 @@ mod tests {
             "test payload should include event field"
         );
 
+        let policy_url = format!("http://{addr}/api/v1/projects/HOOKS/webhooks/{webhook_id}");
+        let policy: serde_json::Value = client
+            .patch(policy_url)
+            .json(&json!({ "timeout_secs": null, "max_retries": 3 }))
+            .send()
+            .await
+            .expect("policy update request should succeed")
+            .json()
+            .await
+            .expect("policy update body should parse");
+        assert_eq!(policy["timeout_secs"], json!(null));
+        assert_eq!(policy["max_retries"], json!(3));
+        assert_eq!(policy["retry_backoff_secs"], json!(null));
+
         let delete_url = format!("http://{addr}/api/v1/projects/HOOKS/webhooks/{webhook_id}");
         let deleted = client
             .delete(delete_url)
@@ -321,6 +453,137 @@ mod tests {
         server.abort();
     }
 
+    #[tokio::test]
+    async fn webhook_changes_are_audited_without_their_secrets() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let (pool, api, server) = spawn_api(temp_dir.path()).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("client should build");
+
+        let created: serde_json::Value = client
+            .post(format!("{api}/projects/HOOKS/webhooks"))
+            .json(&json!({
+                "name": "capture",
+                "url": "http://127.0.0.1:9/webhook",
+                "platform": "generic",
+                "events": ["task.created"],
+                "secret": "top-secret",
+                "active": true
+            }))
+            .send()
+            .await
+            .expect("create webhook request should succeed")
+            .json()
+            .await
+            .expect("create webhook body should parse");
+        let webhook_url = format!(
+            "{api}/projects/HOOKS/webhooks/{}",
+            created["id"]
+                .as_str()
+                .expect("created webhook should include id")
+        );
+        let updated = client
+            .patch(&webhook_url)
+            .header("MCP-Client", "ops-bot")
+            .json(&json!({ "name": "capture-2", "secret": "rotated-secret" }))
+            .send()
+            .await
+            .expect("update webhook request should succeed");
+        assert_eq!(updated.status(), StatusCode::OK);
+        let deleted = client
+            .delete(&webhook_url)
+            .send()
+            .await
+            .expect("delete webhook request should succeed");
+        assert_eq!(deleted.status(), StatusCode::NO_CONTENT);
+
+        let audit: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT action, actor, detail FROM system_events WHERE action LIKE 'webhook.%'",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("webhook events should load");
+        let mut actions: Vec<&str> = audit.iter().map(|(action, _, _)| action.as_str()).collect();
+        actions.sort_unstable();
+        assert_eq!(
+            actions,
+            vec!["webhook.created", "webhook.deleted", "webhook.updated"]
+        );
+        for (action, _, detail) in &audit {
+            assert!(!detail.contains("top-secret") && !detail.contains("rotated-secret"));
+            if action != "webhook.updated" {
+                assert!(detail.contains("\"http://127.0.0.1:9/…\""), "{detail}");
+            }
+        }
+        let (_, actor, detail) = audit
+            .iter()
+            .find(|(action, _, _)| action == "webhook.updated")
+            .expect("update should be audited");
+        assert_eq!(actor, "ops-bot");
+        let detail: serde_json::Value =
+            serde_json::from_str(detail).expect("event detail should parse");
+        assert_eq!(detail["secret_changed"], json!(true));
+        assert_eq!(
+            detail["changes"],
+            json!({ "name": { "from": "capture", "to": "capture-2" } })
+        );
+
+        server.abort();
+    }
+
+    /// Serves the API over a fresh database with project `HOOKS`.
+    async fn spawn_api(temp_dir: &Path) -> (AnyPool, String, JoinHandle<()>) {
+        let db_path = temp_dir.join("webhook_audit_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Webhooks", "test", "HOOKS")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool.clone());
+        let app = Router::new()
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("api listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("api listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        (pool, format!("http://{addr}/api/v1"), server)
+    }
+
     async fn capture_webhook(
         State(capture_tx): State<mpsc::UnboundedSender<CapturedWebhook>>,
         headers: HeaderMap,
@@ -0,0 +1,368 @@
+use axum::body::Body;
+use axum::extract::{Path, State};
+use axum::http::{Request, StatusCode, Uri};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::auth::{hash_token, AccessScope};
+use crate::api::json::ApiJson;
+use crate::db::models::WorkspaceRecord;
+use crate::db::queries;
+use crate::error::AppResult;
+use crate::state::AppState;
+
+const SCOPED_PREFIX: &str = "/api/v1/w/";
+
+/// Workspace slug taken from a `/api/v1/w/{workspace}/...` URL before routing.
+#[derive(Debug, Clone)]
+pub struct WorkspaceSelector(pub String);
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/workspaces", get(list_workspaces).post(create_workspace))
+        .route(
+            "/workspaces/{workspace}",
+            get(get_workspace).delete(delete_workspace),
+        )
+        .route(
+            "/workspaces/{workspace}/token",
+            post(rotate_workspace_token).delete(revoke_workspace_token),
+        )
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateWorkspaceRequest {
+    name: String,
+    slug: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkspaceResponse {
+    id: String,
+    slug: String,
+    name: String,
+    has_token: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkspaceTokenResponse {
+    workspace: WorkspaceResponse,
+    token: String,
+}
+
+/// Rewrites `/api/v1/w/{workspace}/rest` to `/api/v1/rest` and records the workspace.
+///
+/// This has to wrap the whole router because URI changes made after routing are ignored.
+pub async fn rewrite_scoped_path(mut request: Request<Body>, next: Next) -> Response {
+    if let Some((workspace, rewritten)) = split_scoped_path(request.uri()) {
+        request
+            .extensions_mut()
+            .insert(WorkspaceSelector(workspace.to_ascii_uppercase()));
+        *request.uri_mut() = rewritten;
+    }
+
+    next.run(request).await
+}
+
+fn split_scoped_path(uri: &Uri) -> Option<(String, Uri)> {
+    let rest = uri.path().strip_prefix(SCOPED_PREFIX)?;
+    let (workspace, remainder) = rest.split_once('/')?;
+    if workspace.is_empty() || remainder.is_empty() {
+        return None;
+    }
+
+    let mut rewritten = format!("/api/v1/{remainder}");
+    if let Some(query) = uri.query() {
+        rewritten.push('?');
+        rewritten.push_str(query);
+    }
+
+    let rewritten = rewritten.parse::<Uri>().ok()?;
+    Some((workspace.to_string(), rewritten))
+}
+
+async fn list_workspaces(
+    State(state): State<AppState>,
+    scope: AccessScope,
+) -> AppResult<Json<Vec<WorkspaceResponse>>> {
+    scope.require_instance()?;
+    let workspaces = queries::list_workspaces(&state.db).await?;
+    Ok(Json(workspaces.into_iter().map(map_workspace).collect()))
+}
+
+async fn create_workspace(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    ApiJson(payload): ApiJson<CreateWorkspaceRequest>,
+) -> AppResult<(StatusCode, Json<WorkspaceResponse>)> {
+    scope.require_instance()?;
+    let workspace = queries::create_workspace(&state.db, &payload.name, &payload.slug).await?;
+    Ok((StatusCode::CREATED, Json(map_workspace(workspace))))
+}
+
+async fn get_workspace(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(workspace): Path<String>,
+) -> AppResult<Json<WorkspaceResponse>> {
+    scope.require_instance()?;
+    let workspace = queries::get_workspace(&state.db, &workspace).await?;
+    Ok(Json(map_workspace(workspace)))
+}
+
+async fn delete_workspace(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(workspace): Path<String>,
+) -> AppResult<StatusCode> {
+    scope.require_instance()?;
+    queries::delete_workspace(&state.db, &workspace).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn rotate_workspace_token(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(workspace): Path<String>,
+) -> AppResult<(StatusCode, Json<WorkspaceTokenResponse>)> {
+    scope.require_instance()?;
+    let token = format!("lws_{}", Uuid::new_v4().simple());
+    let workspace =
+        queries::set_workspace_token_hash(&state.db, &workspace, Some(&hash_token(&token))).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WorkspaceTokenResponse {
+            workspace: map_workspace(workspace),
+            token,
+        }),
+    ))
+}
+
+async fn revoke_workspace_token(
+    State(state): State<AppState>,
+    scope: AccessScope,
+    Path(workspace): Path<String>,
+) -> AppResult<StatusCode> {
+    scope.require_instance()?;
+    queries::set_workspace_token_hash(&state.db, &workspace, None).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn map_workspace(record: WorkspaceRecord) -> WorkspaceResponse {
+    WorkspaceResponse {
+        id: record.id,
+        slug: record.slug,
+        name: record.name,
+        has_token: record.token_hash.is_some(),
+        created_at: record.created_at,
+        updated_at: record.updated_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::routing::get;
+    use axum::Router;
+    use axum::ServiceExt;
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use tempfile::tempdir;
+    use tower::Layer;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::state::AppState;
+
+    #[tokio::test]
+    async fn workspace_tokens_only_see_their_own_projects() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("workspaces_test.db");
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url,
+            token: Some("instance-token".to_string()),
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Shared", "default workspace", "SHARED")
+            .await
+            .expect("default project should be created");
+
+        let state = AppState::new(config, pool);
+        let router = Router::new()
+            .nest("/api/v1", api::router())
+            .route("/healthz", get(api::healthz))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let app = middleware::from_fn(super::rewrite_scoped_path).layer(router);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(
+                listener,
+                ServiceExt::<axum::extract::Request>::into_make_service(app),
+            )
+            .await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let api = format!("http://{addr}/api/v1");
+
+        let created = client
+            .post(format!("{api}/workspaces"))
+            .bearer_auth("instance-token")
+            .json(&json!({ "name": "Team A", "slug": "TEAM-A" }))
+            .send()
+            .await
+            .expect("workspace create should succeed");
+        assert_eq!(created.status(), StatusCode::CREATED);
+
+        let minted: serde_json::Value = client
+            .post(format!("{api}/workspaces/TEAM-A/token"))
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("token mint should succeed")
+            .json()
+            .await
+            .expect("token body should parse");
+        let team_token = minted
+            .get("token")
+            .and_then(serde_json::Value::as_str)
+            .map(ToOwned::to_owned)
+            .expect("minted token should be returned");
+
+        let scoped_create = client
+            .post(format!("{api}/w/TEAM-A/projects"))
+            .bearer_auth(&team_token)
+            .json(&json!({ "name": "Alpha", "slug": "ALPHA" }))
+            .send()
+            .await
+            .expect("scoped project create should succeed");
+        assert_eq!(scoped_create.status(), StatusCode::CREATED);
+
+        let listed: serde_json::Value = client
+            .get(format!("{api}/projects"))
+            .bearer_auth(&team_token)
+            .send()
+            .await
+            .expect("project list should succeed")
+            .json()
+            .await
+            .expect("project list should parse");
+        let slugs = listed
+            .as_array()
+            .expect("project list should be an array")
+            .iter()
+            .filter_map(|item| item.pointer("/project/slug").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(slugs, vec!["ALPHA"]);
+
+        let hidden = client
+            .get(format!("{api}/projects/SHARED"))
+            .bearer_auth(&team_token)
+            .send()
+            .await
+            .expect("cross-workspace read should complete");
+        assert_eq!(hidden.status(), StatusCode::NOT_FOUND);
+
+        for path in [
+            "projects/%53HARED",
+            "projects/%53HARED/tasks",
+            "files/%61bc",
+        ] {
+            let encoded = client
+                .get(format!("{api}/{path}"))
+                .bearer_auth(&team_token)
+                .send()
+                .await
+                .expect("encoded cross-workspace read should complete");
+            assert_eq!(encoded.status(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        let admin_only = client
+            .get(format!("{api}/workspaces"))
+            .bearer_auth(&team_token)
+            .send()
+            .await
+            .expect("workspace list should complete");
+        assert_eq!(admin_only.status(), StatusCode::FORBIDDEN);
+
+        let instance_view = client
+            .get(format!("{api}/projects/ALPHA"))
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("instance read should succeed");
+        assert_eq!(instance_view.status(), StatusCode::OK);
+
+        // List endpoints report the full count next to the page they return.
+        let page = client
+            .get(format!("{api}/projects?limit=1"))
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("paged project list should succeed");
+        assert_eq!(page.status(), StatusCode::OK);
+        assert_eq!(page.headers()["x-total-count"], "2");
+        assert_eq!(page.headers()["x-limit"], "1");
+        assert_eq!(page.headers()["x-offset"], "0");
+        let projects: serde_json::Value = page.json().await.expect("page should parse");
+        assert_eq!(projects.as_array().map(Vec::len), Some(1));
+
+        let tasks = client
+            .get(format!("{api}/projects/ALPHA/tasks?offset=5"))
+            .bearer_auth(&team_token)
+            .send()
+            .await
+            .expect("task list should succeed");
+        assert_eq!(tasks.status(), StatusCode::OK);
+        assert_eq!(tasks.headers()["x-total-count"], "0");
+        assert_eq!(tasks.headers()["x-limit"], "50");
+        assert_eq!(tasks.headers()["x-offset"], "5");
+
+        server.abort();
+    }
+}
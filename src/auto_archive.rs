@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+
+use crate::db::queries;
+use crate::state::AppState;
+
+const AUTO_ARCHIVE_INTERVAL_SECS: u64 = 900;
+
+/// Periodically archives done tasks in projects that set `auto_archive_after_days`,
+/// emitting `task.archived` for each.
+pub fn spawn_auto_archiver(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(AUTO_ARCHIVE_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            archive_done_tasks(&state).await;
+        }
+    });
+}
+
+async fn archive_done_tasks(state: &AppState) {
+    let projects = match queries::list_auto_archive_projects(&state.db).await {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(error = ?error, "failed to query auto-archive settings");
+            return;
+        }
+    };
+
+    for (project_id, after_days) in projects {
+        let days = u32::try_from(after_days).unwrap_or(u32::MAX);
+        let cutoff = queries::timestamp_days_ago(days);
+        if let Err(error) =
+            queries::archive_done_tasks(&state.db, &project_id, &cutoff, after_days).await
+        {
+            tracing::error!(error = ?error, project_id = %project_id, "failed to archive done tasks");
+        }
+    }
+}
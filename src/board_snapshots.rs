@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::time::MissedTickBehavior;
+
+use crate::db::queries;
+use crate::state::AppState;
+
+const BOARD_SNAPSHOT_INTERVAL_SECS: u64 = 3600;
+
+/// Stores one board snapshot per project per UTC day, taken on the first pass after midnight
+/// (or after the project is created), so past boards can be fetched by date.
+pub fn spawn_board_snapshotter(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(BOARD_SNAPSHOT_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            store_daily_snapshots(&state).await;
+        }
+    });
+}
+
+async fn store_daily_snapshots(state: &AppState) {
+    let date = Utc::now().date_naive().to_string();
+    let slugs = match queries::list_projects_missing_board_snapshot(&state.db, &date).await {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(error = ?error, "failed to query projects for board snapshots");
+            return;
+        }
+    };
+
+    for slug in slugs {
+        if let Err(error) = queries::store_board_snapshot(&state.db, &slug, &date).await {
+            tracing::error!(error = ?error, project = %slug, "failed to store board snapshot");
+        }
+    }
+}
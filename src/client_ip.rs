@@ -0,0 +1,180 @@
+//! Works out which address a request really came from when lattice sits behind proxies.
+//!
+//! Each proxy appends the address it received the request from to `x-forwarded-for`, so only
+//! the entries added by proxies we trust are reliable; anything to their left was supplied by
+//! the client and may be forged.
+
+use std::convert::Infallible;
+use std::net::IpAddr;
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+use ipnet::IpNet;
+
+/// The client address of the current request, attached by `rate_limit::enforce_limits`.
+/// `None` when the server was not started with connection info, as in tests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+impl ClientIp {
+    /// The address as stored in the audit log.
+    pub fn address(self) -> Option<String> {
+        self.0.map(|ip| ip.to_string())
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<ClientIp>()
+            .copied()
+            .unwrap_or_default())
+    }
+}
+
+/// The address of the client behind `peer`, the socket the request arrived on.
+///
+/// Forwarded headers are only read when `peer` is a trusted proxy: in `trusted_proxies`, or,
+/// with that list empty, any peer once `hops` is set. With `hops > 0` the client is the
+/// address `hops` entries from the right of `x-forwarded-for` plus the peer; otherwise it is
+/// the rightmost address that is not a trusted proxy. `x-real-ip` stands in for a missing
+/// `x-forwarded-for`.
+pub fn client_ip(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &[IpNet],
+    hops: usize,
+) -> Option<IpAddr> {
+    let peer = peer?;
+    let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+    let peer_trusted = if trusted_proxies.is_empty() {
+        hops > 0
+    } else {
+        is_trusted(&peer)
+    };
+    if !peer_trusted {
+        return Some(peer);
+    }
+
+    let mut chain = forwarded_chain(headers);
+    chain.push(Some(peer));
+
+    if hops > 0 {
+        let index = chain.len().saturating_sub(hops + 1);
+        // An entry that is not an address was not written by a proxy we trust; fall back to
+        // the closest address on its right.
+        return chain[index..].iter().flatten().next().copied();
+    }
+
+    let mut client = peer;
+    for entry in chain.iter().rev().skip(1) {
+        match entry {
+            Some(ip) if is_trusted(&client) => client = *ip,
+            _ => break,
+        }
+    }
+    Some(client)
+}
+
+/// `x-forwarded-for` entries left to right, or `x-real-ip` when that header is absent.
+///
+/// Proxies may append their own header line instead of extending the first one, so every
+/// line is read in order. A line that is not text counts as one entry that is not an address.
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let name = if headers.contains_key("x-forwarded-for") {
+        "x-forwarded-for"
+    } else {
+        "x-real-ip"
+    };
+    let mut chain = Vec::new();
+    for line in headers.get_all(name) {
+        let Ok(line) = line.to_str() else {
+            chain.push(None);
+            continue;
+        };
+        chain.extend(
+            line.split(',')
+                .map(str::trim)
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| entry.parse::<IpAddr>().ok()),
+        );
+    }
+    chain
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use axum::http::{HeaderMap, HeaderValue};
+    use ipnet::IpNet;
+
+    use super::client_ip;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().expect("valid ip")
+    }
+
+    #[test]
+    fn forged_leading_entries_are_skipped() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 203.0.113.9, 10.0.0.3"),
+        );
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().expect("valid cidr")];
+        let peer = Some(ip("10.0.0.2"));
+
+        // Two proxies (10.0.0.3 then 10.0.0.2) saw 203.0.113.9; 6.6.6.6 came from the client.
+        assert_eq!(
+            client_ip(&headers, peer, &trusted, 0),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(client_ip(&headers, peer, &[], 2), Some(ip("203.0.113.9")));
+        assert_eq!(client_ip(&headers, peer, &[], 1), Some(ip("10.0.0.3")));
+        // Untrusted peers are taken at their word only for their own address.
+        assert_eq!(
+            client_ip(&headers, Some(ip("198.51.100.4")), &trusted, 0),
+            Some(ip("198.51.100.4"))
+        );
+        assert_eq!(client_ip(&headers, peer, &[], 0), peer);
+    }
+
+    #[test]
+    fn short_or_garbled_chains_stop_at_the_last_trusted_hop() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("not-an-ip, 203.0.113.9"),
+        );
+        let peer = Some(ip("10.0.0.2"));
+
+        assert_eq!(client_ip(&headers, peer, &[], 2), Some(ip("203.0.113.9")));
+        assert_eq!(client_ip(&headers, peer, &[], 5), Some(ip("203.0.113.9")));
+        assert_eq!(client_ip(&HeaderMap::new(), peer, &[], 1), peer);
+        assert_eq!(client_ip(&headers, None, &[], 1), None);
+    }
+
+    #[test]
+    fn repeated_forwarded_for_lines_are_read_as_one_chain() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 203.0.113.9"),
+        );
+        headers.append("x-forwarded-for", HeaderValue::from_static("10.0.0.3"));
+        let trusted: Vec<IpNet> = vec!["10.0.0.0/8".parse().expect("valid cidr")];
+        let peer = Some(ip("10.0.0.2"));
+
+        assert_eq!(
+            client_ip(&headers, peer, &trusted, 0),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(client_ip(&headers, peer, &[], 1), Some(ip("10.0.0.3")));
+        assert_eq!(client_ip(&headers, peer, &[], 2), Some(ip("203.0.113.9")));
+    }
+}
@@ -1,12 +1,20 @@
+use std::fmt;
 use std::fs;
+use std::net::IpAddr;
 use std::path::{Path, PathBuf};
 
 use clap::{Args, Parser};
+use ipnet::IpNet;
 use tracing::warn;
 
 #[derive(Clone, Debug, Parser)]
 #[command(name = "lattice")]
 pub struct Config {
+    /// Address to listen on; use `127.0.0.1` to keep lattice off the network.
+    #[arg(long, env = "LATTICE_BIND_ADDR", default_value = "0.0.0.0")]
+    pub bind_addr: IpAddr,
+
+    /// Port to listen on; `0` picks a free port, reported at startup and in `/api/v1/version`.
     #[arg(long, env = "LATTICE_PORT", default_value_t = 7400)]
     pub port: u16,
 
@@ -25,8 +33,138 @@ pub struct Config {
     #[arg(long, env = "LATTICE_MAX_FILE_SIZE", default_value_t = 10 * 1024 * 1024)]
     pub max_file_size: u64,
 
+    /// In-progress tasks untouched for this many days are flagged stale; 0 disables it.
+    #[arg(long, env = "LATTICE_STALE_AFTER_DAYS", default_value_t = 7)]
+    pub stale_after_days: u32,
+
+    /// Reject request bodies that contain fields the endpoint does not recognise.
+    #[arg(long, env = "LATTICE_STRICT_JSON", default_value_t = false)]
+    pub strict_json: bool,
+
+    /// Require a named MCP key on `/mcp`. Needs `LATTICE_TOKEN`, which guards minting keys.
+    #[arg(long, env = "LATTICE_MCP_REQUIRE_KEY", default_value_t = false)]
+    pub mcp_require_key: bool,
+
+    /// Hide `lattice_delete_project` from MCP clients.
+    #[arg(
+        long,
+        env = "LATTICE_MCP_DISABLE_PROJECT_DELETE",
+        default_value_t = false
+    )]
+    pub mcp_disable_project_delete: bool,
+
+    /// Log one line per HTTP request with status, latency, and caller identity.
+    #[arg(long, env = "LATTICE_ACCESS_LOG", default_value_t = false)]
+    pub access_log: bool,
+
+    /// Address the web UI is reached at, such as `https://lattice.example.com`. Slack and
+    /// Discord messages link to tasks and spec sections under it; without it they carry no links.
+    #[arg(long, env = "LATTICE_PUBLIC_URL")]
+    pub public_url: Option<String>,
+
     #[command(flatten)]
     pub rate_limits: RateLimitConfig,
+
+    #[command(flatten)]
+    pub field_limits: FieldLimitConfig,
+
+    #[command(flatten)]
+    pub secret_keys: SecretKeyConfig,
+
+    #[command(flatten)]
+    pub webhook_delivery: WebhookDeliveryConfig,
+}
+
+/// Longest a webhook may wait for a receiver, in seconds.
+pub const MAX_WEBHOOK_TIMEOUT_SECS: i64 = 120;
+pub const MAX_WEBHOOK_RETRIES: i64 = 10;
+/// Longest wait before a retry, in seconds; doubled backoffs are capped here too.
+pub const MAX_WEBHOOK_RETRY_BACKOFF_SECS: i64 = 3600;
+
+/// How webhook deliveries are sent and retried, unless a webhook overrides it.
+#[derive(Clone, Debug, Args)]
+pub struct WebhookDeliveryConfig {
+    /// Seconds to wait for a receiver to respond.
+    #[arg(long, env = "LATTICE_WEBHOOK_TIMEOUT_SECS", default_value_t = 5)]
+    pub webhook_timeout_secs: i64,
+
+    /// Retries after a failed delivery; 0 gives up at once.
+    #[arg(long, env = "LATTICE_WEBHOOK_MAX_RETRIES", default_value_t = 1)]
+    pub webhook_max_retries: i64,
+
+    /// Seconds before the first retry. Each further retry waits twice as long.
+    #[arg(long, env = "LATTICE_WEBHOOK_RETRY_BACKOFF_SECS", default_value_t = 30)]
+    pub webhook_retry_backoff_secs: i64,
+}
+
+impl Default for WebhookDeliveryConfig {
+    fn default() -> Self {
+        Self {
+            webhook_timeout_secs: 5,
+            webhook_max_retries: 1,
+            webhook_retry_backoff_secs: 30,
+        }
+    }
+}
+
+/// Keys for encrypting webhook and inbound hook secrets at rest. See `db::secrets`.
+#[derive(Clone, Default, Args)]
+pub struct SecretKeyConfig {
+    /// Encrypts new secrets, and existing ones at startup. Unset stores them in plaintext.
+    #[arg(long, env = "LATTICE_SECRET_KEY", hide_env_values = true)]
+    pub secret_key: Option<String>,
+
+    /// Comma-separated keys used before `LATTICE_SECRET_KEY`. Secrets under them stay readable
+    /// and are moved to the current key at startup, after which they can be dropped.
+    #[arg(
+        long,
+        env = "LATTICE_SECRET_KEY_PREVIOUS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub previous_secret_keys: Vec<String>,
+}
+
+/// Maximum lengths, in characters, for free-text fields. Only new or changed values are
+/// checked, so rows written before a limit was lowered stay readable and editable.
+#[derive(Clone, Debug, Args)]
+pub struct FieldLimitConfig {
+    /// Task and subtask titles.
+    #[arg(long, env = "LATTICE_MAX_TITLE_LENGTH", default_value_t = 500)]
+    pub max_title_length: usize,
+
+    #[arg(long, env = "LATTICE_MAX_DESCRIPTION_LENGTH", default_value_t = 65_536)]
+    pub max_description_length: usize,
+
+    /// One spec section.
+    #[arg(long, env = "LATTICE_MAX_SPEC_LENGTH", default_value_t = 262_144)]
+    pub max_spec_length: usize,
+
+    /// Comma-separated `section=characters` overrides of `LATTICE_MAX_SPEC_LENGTH`, such as
+    /// `architecture=65536,references=16384`.
+    #[arg(
+        long,
+        env = "LATTICE_MAX_SPEC_SECTION_LENGTHS",
+        value_delimiter = ',',
+        value_parser = parse_spec_section_limit
+    )]
+    pub max_spec_section_lengths: Vec<(String, usize)>,
+
+    /// Open question text, context, and answers.
+    #[arg(long, env = "LATTICE_MAX_QUESTION_LENGTH", default_value_t = 16_384)]
+    pub max_question_length: usize,
+}
+
+impl Default for FieldLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_title_length: 500,
+            max_description_length: 65_536,
+            max_spec_length: 262_144,
+            max_spec_section_lengths: Vec::new(),
+            max_question_length: 16_384,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Args)]
@@ -101,6 +239,20 @@ pub struct RateLimitConfig {
     )]
     pub mcp_burst: u32,
 
+    #[arg(
+        long = "rate-limit-mcp-write-per-min",
+        env = "LATTICE_RATE_LIMIT_MCP_WRITE_PER_MIN",
+        default_value_t = 40
+    )]
+    pub mcp_write_per_min: u32,
+
+    #[arg(
+        long = "rate-limit-mcp-write-burst",
+        env = "LATTICE_RATE_LIMIT_MCP_WRITE_BURST",
+        default_value_t = 10
+    )]
+    pub mcp_write_burst: u32,
+
     #[arg(
         long = "rate-limit-sse-connect-per-min",
         env = "LATTICE_RATE_LIMIT_SSE_CONNECT_PER_MIN",
@@ -129,8 +281,86 @@ pub struct RateLimitConfig {
     )]
     pub sse_max_global: u32,
 
+    /// Requests one identity may have in progress at once, counted until the response body
+    /// has been sent. SSE streams are capped separately.
+    #[arg(
+        long = "rate-limit-max-in-flight-per-identity",
+        env = "LATTICE_RATE_LIMIT_MAX_IN_FLIGHT_PER_IDENTITY",
+        default_value_t = 16
+    )]
+    pub max_in_flight_per_identity: u32,
+
     #[arg(long = "max-request-body-bytes", env = "LATTICE_MAX_REQUEST_BODY_BYTES", default_value_t = 12 * 1024 * 1024)]
     pub max_request_body_bytes: usize,
+
+    /// Comma-separated proxy addresses or CIDRs. Only requests arriving from one of them have
+    /// their `x-forwarded-for`/`x-real-ip` header believed; any other request is attributed to
+    /// the connecting socket's address, so a client cannot pick its own rate limit bucket or
+    /// audit log address.
+    #[arg(
+        long = "trusted-proxies",
+        env = "LATTICE_TRUSTED_PROXIES",
+        value_delimiter = ',',
+        value_parser = parse_trusted_proxy
+    )]
+    pub trusted_proxies: Vec<IpNet>,
+
+    /// Number of proxies in front of lattice. The client is the address that many entries
+    /// from the right of `x-forwarded-for` (counting the connecting proxy). 0 instead skips
+    /// every trailing entry in `LATTICE_TRUSTED_PROXIES`.
+    #[arg(
+        long = "trusted-proxy-hops",
+        env = "LATTICE_TRUSTED_PROXY_HOPS",
+        default_value_t = 0
+    )]
+    pub trusted_proxy_hops: usize,
+}
+
+/// A CIDR such as `10.0.0.0/8`, or a single address treated as a one-address network.
+fn parse_trusted_proxy(value: &str) -> Result<IpNet, String> {
+    let value = value.trim();
+    value
+        .parse::<IpNet>()
+        .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("'{value}' is not an IP address or CIDR"))
+}
+
+impl fmt::Debug for SecretKeyConfig {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SecretKeyConfig")
+            .field("secret_key_set", &self.secret_key.is_some())
+            .field("previous_secret_keys", &self.previous_secret_keys.len())
+            .finish()
+    }
+}
+
+impl FieldLimitConfig {
+    /// The character limit for one spec section, and the env var that sets it.
+    pub fn spec_section_limit(&self, section: &str) -> (usize, &'static str) {
+        self.max_spec_section_lengths
+            .iter()
+            .find(|(name, _)| name == section)
+            .map_or(
+                (self.max_spec_length, "LATTICE_MAX_SPEC_LENGTH"),
+                |(_, max)| (*max, "LATTICE_MAX_SPEC_SECTION_LENGTHS"),
+            )
+    }
+}
+
+fn parse_spec_section_limit(value: &str) -> Result<(String, usize), String> {
+    let (section, max) = value
+        .trim()
+        .split_once('=')
+        .ok_or_else(|| format!("'{value}' is not section=characters"))?;
+    let section = section.trim();
+    crate::db::queries::validate_spec_section(section)
+        .map_err(|_| format!("'{section}' is not a spec section"))?;
+    let max = max
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| format!("'{max}' is not a character count"))?;
+    Ok((section.to_string(), max))
 }
 
 impl Default for RateLimitConfig {
@@ -146,11 +376,16 @@ impl Default for RateLimitConfig {
             webhook_test_burst: 5,
             mcp_per_min: 80,
             mcp_burst: 20,
+            mcp_write_per_min: 40,
+            mcp_write_burst: 10,
             sse_connect_per_min: 40,
             sse_connect_burst: 10,
             sse_max_per_identity: 10,
             sse_max_global: 400,
+            max_in_flight_per_identity: 16,
             max_request_body_bytes: 12 * 1024 * 1024,
+            trusted_proxies: Vec::new(),
+            trusted_proxy_hops: 0,
         }
     }
 }
@@ -168,6 +403,18 @@ impl Config {
             .is_some_and(|value| !value.trim().is_empty())
     }
 
+    /// `public_url` without a trailing slash, or `None` when unset.
+    pub fn public_base_url(&self) -> Option<&str> {
+        self.public_url
+            .as_deref()
+            .map(|value| value.trim().trim_end_matches('/'))
+            .filter(|value| !value.is_empty())
+    }
+
+    pub fn stale_threshold_days(&self) -> Option<u32> {
+        (self.stale_after_days > 0).then_some(self.stale_after_days)
+    }
+
     pub fn ensure_storage_dir(&self) -> std::io::Result<()> {
         ensure_directory(&self.storage_dir)
     }
@@ -175,13 +422,32 @@ impl Config {
     pub fn log_startup_warnings(&self) {
         if !self.auth_enabled() {
             warn!("LATTICE_TOKEN is unset, auth is disabled and all requests are allowed");
-            warn!(
-                "no-auth mode enabled, rate limiting identity falls back to forwarded client IP headers"
-            );
+            if self.rate_limits.trusted_proxies.is_empty()
+                && self.rate_limits.trusted_proxy_hops == 0
+            {
+                warn!(
+                    "no-auth mode enabled, rate limiting identity is the connecting peer address"
+                );
+            } else {
+                warn!(
+                    "no-auth mode enabled, rate limiting identity is the peer address or, behind trusted proxies, the forwarded client IP"
+                );
+            }
         }
     }
 
     fn validate(&self) {
+        // Without a token anyone could mint the MCP keys this setting asks for.
+        assert!(
+            !self.mcp_require_key || self.auth_enabled(),
+            "LATTICE_MCP_REQUIRE_KEY requires LATTICE_TOKEN, which guards minting MCP keys"
+        );
+        if let Some(url) = self.public_base_url() {
+            assert!(
+                url.starts_with("http://") || url.starts_with("https://"),
+                "LATTICE_PUBLIC_URL must start with http:// or https://"
+            );
+        }
         assert_non_zero_u32(
             "LATTICE_RATE_LIMIT_READ_PER_MIN",
             self.rate_limits.read_per_min,
@@ -216,6 +482,14 @@ impl Config {
             self.rate_limits.mcp_per_min,
         );
         assert_non_zero_u32("LATTICE_RATE_LIMIT_MCP_BURST", self.rate_limits.mcp_burst);
+        assert_non_zero_u32(
+            "LATTICE_RATE_LIMIT_MCP_WRITE_PER_MIN",
+            self.rate_limits.mcp_write_per_min,
+        );
+        assert_non_zero_u32(
+            "LATTICE_RATE_LIMIT_MCP_WRITE_BURST",
+            self.rate_limits.mcp_write_burst,
+        );
         assert_non_zero_u32(
             "LATTICE_RATE_LIMIT_SSE_CONNECT_PER_MIN",
             self.rate_limits.sse_connect_per_min,
@@ -232,10 +506,45 @@ impl Config {
             "LATTICE_RATE_LIMIT_SSE_MAX_GLOBAL",
             self.rate_limits.sse_max_global,
         );
+        assert_non_zero_u32(
+            "LATTICE_RATE_LIMIT_MAX_IN_FLIGHT_PER_IDENTITY",
+            self.rate_limits.max_in_flight_per_identity,
+        );
         assert_non_zero_usize(
             "LATTICE_MAX_REQUEST_BODY_BYTES",
             self.rate_limits.max_request_body_bytes,
         );
+        assert_non_zero_usize(
+            "LATTICE_MAX_TITLE_LENGTH",
+            self.field_limits.max_title_length,
+        );
+        assert_non_zero_usize(
+            "LATTICE_MAX_DESCRIPTION_LENGTH",
+            self.field_limits.max_description_length,
+        );
+        assert_non_zero_usize("LATTICE_MAX_SPEC_LENGTH", self.field_limits.max_spec_length);
+        assert_non_zero_usize(
+            "LATTICE_MAX_QUESTION_LENGTH",
+            self.field_limits.max_question_length,
+        );
+        assert_in_range(
+            "LATTICE_WEBHOOK_TIMEOUT_SECS",
+            self.webhook_delivery.webhook_timeout_secs,
+            1,
+            MAX_WEBHOOK_TIMEOUT_SECS,
+        );
+        assert_in_range(
+            "LATTICE_WEBHOOK_MAX_RETRIES",
+            self.webhook_delivery.webhook_max_retries,
+            0,
+            MAX_WEBHOOK_RETRIES,
+        );
+        assert_in_range(
+            "LATTICE_WEBHOOK_RETRY_BACKOFF_SECS",
+            self.webhook_delivery.webhook_retry_backoff_secs,
+            1,
+            MAX_WEBHOOK_RETRY_BACKOFF_SECS,
+        );
     }
 }
 
@@ -250,3 +559,10 @@ fn assert_non_zero_u32(key: &'static str, value: u32) {
 fn assert_non_zero_usize(key: &'static str, value: usize) {
     assert!(value > 0, "{key} must be greater than 0");
 }
+
+fn assert_in_range(key: &'static str, value: i64, min: i64, max: i64) {
+    assert!(
+        (min..=max).contains(&value),
+        "{key} must be between {min} and {max}"
+    );
+}
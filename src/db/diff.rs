@@ -0,0 +1,373 @@
+//! Line diffs for long text fields, stored in task history instead of full before/after copies.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Above this many line pairs the changed region is recorded as one replacement instead of
+/// running the quadratic LCS.
+const MAX_LCS_CELLS: usize = 4_000_000;
+/// Headings listed in a change summary before the rest are left out.
+const MAX_SUMMARY_HEADINGS: usize = 20;
+
+/// One step of a line diff. Lines keep their trailing newline, so applying a diff
+/// reproduces the text byte for byte. Serialized as `{"keep":3}`, `{"delete":[..]}`, or
+/// `{"insert":[..]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Keep(usize),
+    Delete(Vec<String>),
+    Insert(Vec<String>),
+}
+
+/// Hex SHA-256 of `text`, used to check that a diff is applied to the text it was made from.
+pub fn content_hash(text: &str) -> String {
+    let digest = Sha256::digest(text.as_bytes());
+    let mut encoded = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(&mut encoded, "{byte:02x}");
+    }
+    encoded
+}
+
+/// Diffs `from` against `to` line by line. Unchanged lines are only counted.
+pub fn diff_lines(from: &str, to: &str) -> Vec<DiffOp> {
+    let old: Vec<&str> = from.split_inclusive('\n').collect();
+    let new: Vec<&str> = to.split_inclusive('\n').collect();
+
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut ops = Vec::new();
+    push_keep(&mut ops, prefix);
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_LCS_CELLS {
+        push_delete(&mut ops, old_middle);
+        push_insert(&mut ops, new_middle);
+    } else {
+        diff_middle(&mut ops, old_middle, new_middle);
+    }
+    push_keep(&mut ops, suffix);
+    ops
+}
+
+/// What an edit changed: bytes on removed and added lines, and the markdown headings whose
+/// sections the changed lines fall under (a changed heading counts as touched itself).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSummary {
+    pub bytes_added: usize,
+    pub bytes_removed: usize,
+    pub headings: Vec<String>,
+}
+
+pub fn summarize(from: &str, to: &str) -> ChangeSummary {
+    let mut summary = ChangeSummary::default();
+    let mut old_lines = from.split_inclusive('\n');
+    let mut new_lines = to.split_inclusive('\n');
+    let (mut old_heading, mut new_heading) = (None, None);
+
+    for op in diff_lines(from, to) {
+        match op {
+            DiffOp::Keep(count) => {
+                for _ in 0..count {
+                    if let Some(heading) = old_lines.next().and_then(heading_text) {
+                        old_heading = Some(heading);
+                    }
+                    if let Some(heading) = new_lines.next().and_then(heading_text) {
+                        new_heading = Some(heading);
+                    }
+                }
+            }
+            DiffOp::Delete(lines) => {
+                for line in &lines {
+                    old_lines.next();
+                    if let Some(heading) = heading_text(line) {
+                        old_heading = Some(heading);
+                    }
+                    summary.bytes_removed += line.len();
+                    summary.touch(old_heading.as_deref());
+                }
+            }
+            DiffOp::Insert(lines) => {
+                for line in &lines {
+                    new_lines.next();
+                    if let Some(heading) = heading_text(line) {
+                        new_heading = Some(heading);
+                    }
+                    summary.bytes_added += line.len();
+                    summary.touch(new_heading.as_deref());
+                }
+            }
+        }
+    }
+    summary
+}
+
+impl ChangeSummary {
+    fn touch(&mut self, heading: Option<&str>) {
+        if let Some(heading) = heading {
+            if self.headings.len() < MAX_SUMMARY_HEADINGS
+                && !self.headings.iter().any(|touched| touched == heading)
+            {
+                self.headings.push(heading.to_string());
+            }
+        }
+    }
+}
+
+/// The text of an ATX heading line such as `## Storage`, or `None` for any other line.
+fn heading_text(line: &str) -> Option<String> {
+    let line = line.trim_end();
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    if !(1..=6).contains(&level) || !(text.is_empty() || text.starts_with(' ')) {
+        return None;
+    }
+    let text = text.trim().trim_end_matches('#').trim_end();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Rebuilds the new text from the old one, or the old text from the new one when `reverse`
+/// is set. Returns `None` when `base` does not match the diff.
+pub fn apply(base: &str, ops: &[DiffOp], reverse: bool) -> Option<String> {
+    let mut lines = base.split_inclusive('\n');
+    let mut output = String::with_capacity(base.len());
+
+    for op in ops {
+        match (op, reverse) {
+            (DiffOp::Keep(count), _) => {
+                for _ in 0..*count {
+                    output.push_str(lines.next()?);
+                }
+            }
+            (DiffOp::Delete(removed), false) | (DiffOp::Insert(removed), true) => {
+                for line in removed {
+                    if lines.next()? != line {
+                        return None;
+                    }
+                }
+            }
+            (DiffOp::Insert(added), false) | (DiffOp::Delete(added), true) => {
+                for line in added {
+                    output.push_str(line);
+                }
+            }
+        }
+    }
+
+    lines.next().is_none().then_some(output)
+}
+
+/// Renders `ops` as a unified diff without context lines, like `diff -U0`.
+pub fn render_unified(ops: &[DiffOp], label: &str) -> String {
+    let mut output = format!("--- a/{label}\n+++ b/{label}\n");
+    let (mut old_line, mut new_line) = (1, 1);
+    let mut index = 0;
+
+    while index < ops.len() {
+        if let DiffOp::Keep(count) = ops[index] {
+            old_line += count;
+            new_line += count;
+            index += 1;
+            continue;
+        }
+
+        let mut removed: Vec<&str> = Vec::new();
+        let mut added: Vec<&str> = Vec::new();
+        while let Some(op) = ops.get(index) {
+            match op {
+                DiffOp::Delete(lines) => removed.extend(lines.iter().map(String::as_str)),
+                DiffOp::Insert(lines) => added.extend(lines.iter().map(String::as_str)),
+                DiffOp::Keep(_) => break,
+            }
+            index += 1;
+        }
+
+        let _ = writeln!(
+            output,
+            "@@ -{} +{} @@",
+            hunk_range(old_line, removed.len()),
+            hunk_range(new_line, added.len())
+        );
+        for (marker, lines) in [('-', &removed), ('+', &added)] {
+            for line in lines.iter() {
+                output.push(marker);
+                output.push_str(line.strip_suffix('\n').unwrap_or(line));
+                output.push('\n');
+                if !line.ends_with('\n') {
+                    output.push_str("\\ No newline at end of file\n");
+                }
+            }
+        }
+        old_line += removed.len();
+        new_line += added.len();
+    }
+
+    output
+}
+
+/// `start,len` for a hunk header; an empty side points at the line before the change.
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start - 1),
+        1 => start.to_string(),
+        _ => format!("{start},{len}"),
+    }
+}
+
+fn diff_middle(ops: &mut Vec<DiffOp>, old: &[&str], new: &[&str]) {
+    // lcs[i][j] is the LCS length of old[i..] and new[j..].
+    let width = new.len() + 1;
+    let mut lcs = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            push_keep(ops, 1);
+            i += 1;
+            j += 1;
+        } else if j < new.len()
+            && (i == old.len() || lcs[i * width + j + 1] >= lcs[(i + 1) * width + j])
+        {
+            push_insert(ops, &new[j..j + 1]);
+            j += 1;
+        } else {
+            push_delete(ops, &old[i..i + 1]);
+            i += 1;
+        }
+    }
+}
+
+fn push_keep(ops: &mut Vec<DiffOp>, count: usize) {
+    if count == 0 {
+        return;
+    }
+    if let Some(DiffOp::Keep(existing)) = ops.last_mut() {
+        *existing += count;
+    } else {
+        ops.push(DiffOp::Keep(count));
+    }
+}
+
+fn push_delete(ops: &mut Vec<DiffOp>, lines: &[&str]) {
+    if lines.is_empty() {
+        return;
+    }
+    // Keep deletions ahead of insertions within a change so hunks read `-` then `+`.
+    let position = match ops.last() {
+        Some(DiffOp::Insert(_)) => ops.len() - 1,
+        _ => ops.len(),
+    };
+    if let Some(DiffOp::Delete(existing)) = position.checked_sub(1).and_then(|at| ops.get_mut(at)) {
+        existing.extend(lines.iter().map(|line| line.to_string()));
+    } else {
+        let op = DiffOp::Delete(lines.iter().map(|line| line.to_string()).collect());
+        ops.insert(position, op);
+    }
+}
+
+fn push_insert(ops: &mut Vec<DiffOp>, lines: &[&str]) {
+    if lines.is_empty() {
+        return;
+    }
+    if let Some(DiffOp::Insert(existing)) = ops.last_mut() {
+        existing.extend(lines.iter().map(|line| line.to_string()));
+    } else {
+        ops.push(DiffOp::Insert(
+            lines.iter().map(|line| line.to_string()).collect(),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply, diff_lines, render_unified, summarize, DiffOp};
+
+    #[test]
+    fn diffs_round_trip_in_both_directions() {
+        let cases = [
+            ("", ""),
+            ("", "new\n"),
+            ("one\ntwo\nthree\n", "one\n2\nthree\nfour"),
+            ("a\nb\nc\nd\n", "a\nc\nd\ne\n"),
+            ("same", "same"),
+            ("x\ny\n", ""),
+        ];
+        for (from, to) in cases {
+            let ops = diff_lines(from, to);
+            assert_eq!(
+                apply(from, &ops, false).as_deref(),
+                Some(to),
+                "{from:?} -> {to:?}"
+            );
+            assert_eq!(
+                apply(to, &ops, true).as_deref(),
+                Some(from),
+                "{to:?} -> {from:?}"
+            );
+        }
+
+        let ops = diff_lines("a\nb\n", "a\nc\n");
+        assert_eq!(apply("a\nz\n", &ops, false), None);
+    }
+
+    #[test]
+    fn unchanged_lines_are_counted_and_hunks_render_without_context() {
+        let ops = diff_lines(
+            "intro\nold step\noutro\n",
+            "intro\nnew step\nextra\noutro\n",
+        );
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Keep(1),
+                DiffOp::Delete(vec!["old step\n".to_string()]),
+                DiffOp::Insert(vec!["new step\n".to_string(), "extra\n".to_string()]),
+                DiffOp::Keep(1),
+            ]
+        );
+        assert_eq!(
+            render_unified(&ops, "description"),
+            "--- a/description\n+++ b/description\n@@ -2 +2,2 @@\n-old step\n+new step\n+extra\n"
+        );
+    }
+
+    #[test]
+    fn summaries_count_bytes_and_name_touched_sections() {
+        let summary = summarize(
+            "# Goals\nship it\n## Storage\nsqlite\n## API\nrest\n",
+            "# Goals\nship it\n## Storage\npostgres\n## Transport\nrest\n",
+        );
+        assert_eq!(summary.bytes_removed, "sqlite\n".len() + "## API\n".len());
+        assert_eq!(
+            summary.bytes_added,
+            "postgres\n".len() + "## Transport\n".len()
+        );
+        assert_eq!(summary.headings, vec!["Storage", "API", "Transport"]);
+
+        let summary = summarize("", "intro\n#hashtag\n");
+        assert_eq!(summary.bytes_added, 15);
+        assert!(summary.headings.is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS actors (
+    id           TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL,
+    kind         TEXT NOT NULL DEFAULT 'agent',
+    avatar_url   TEXT,
+    created_at   TEXT NOT NULL,
+    updated_at   TEXT NOT NULL,
+    CHECK (kind IN ('human', 'agent'))
+);
+
+INSERT INTO actors (id, display_name, kind, avatar_url, created_at, updated_at)
+SELECT
+    seen.actor,
+    seen.actor,
+    CASE WHEN seen.actor = 'human' THEN 'human' ELSE 'agent' END,
+    NULL,
+    MIN(seen.created_at),
+    MIN(seen.created_at)
+FROM (
+    SELECT actor, created_at FROM task_history
+    UNION ALL
+    SELECT actor, created_at FROM system_events
+) seen
+GROUP BY seen.actor;
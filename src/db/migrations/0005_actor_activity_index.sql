@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_system_events_actor_created
+    ON system_events(actor, created_at);
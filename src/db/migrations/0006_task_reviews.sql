@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS task_reviews (
+    id           TEXT PRIMARY KEY,
+    task_id      TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    reviewer     TEXT NOT NULL,
+    requested_by TEXT NOT NULL,
+    status       TEXT NOT NULL DEFAULT 'pending',
+    comment      TEXT,
+    completed_by TEXT,
+    created_at   TEXT NOT NULL,
+    completed_at TEXT,
+    CHECK (status IN ('pending', 'approved', 'changes_requested'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_reviews_task ON task_reviews(task_id, created_at);
@@ -0,0 +1,31 @@
+CREATE TABLE IF NOT EXISTS project_settings (
+    project_id                TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    review_checklist_required INTEGER NOT NULL DEFAULT 1,
+    updated_at                TEXT NOT NULL,
+    CHECK (review_checklist_required IN (0, 1))
+);
+
+CREATE TABLE IF NOT EXISTS review_checklist_templates (
+    id         TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    title      TEXT NOT NULL,
+    sort_order REAL NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_checklist_templates_project
+    ON review_checklist_templates(project_id, sort_order);
+
+CREATE TABLE IF NOT EXISTS task_review_items (
+    id           TEXT PRIMARY KEY,
+    task_id      TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    title        TEXT NOT NULL,
+    done         INTEGER NOT NULL DEFAULT 0,
+    sort_order   REAL NOT NULL DEFAULT 0,
+    completed_by TEXT,
+    completed_at TEXT,
+    created_at   TEXT NOT NULL,
+    CHECK (done IN (0, 1))
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_review_items_task ON task_review_items(task_id, sort_order);
@@ -0,0 +1,7 @@
+ALTER TABLE project_settings
+    ADD COLUMN review_gate TEXT NOT NULL DEFAULT 'agents'
+    CHECK (review_gate IN ('off', 'agents', 'everyone'));
+
+ALTER TABLE project_settings
+    ADD COLUMN review_gate_scope TEXT NOT NULL DEFAULT 'all_moves'
+    CHECK (review_gate_scope IN ('all_moves', 'start_and_finish'));
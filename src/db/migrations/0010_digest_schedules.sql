@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS digest_schedules (
+    project_id   TEXT PRIMARY KEY REFERENCES projects(id) ON DELETE CASCADE,
+    frequency    TEXT NOT NULL,
+    hour         INTEGER NOT NULL,
+    weekday      INTEGER NOT NULL DEFAULT 1,
+    last_sent_at TEXT,
+    created_at   TEXT NOT NULL,
+    updated_at   TEXT NOT NULL,
+    CHECK (frequency IN ('daily', 'weekly')),
+    CHECK (hour BETWEEN 0 AND 23),
+    CHECK (weekday BETWEEN 1 AND 7)
+);
@@ -0,0 +1,28 @@
+ALTER TABLE tasks ADD COLUMN status_changed_at TEXT;
+
+UPDATE tasks SET status_changed_at = updated_at WHERE status_changed_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS sla_policies (
+    id                TEXT PRIMARY KEY,
+    project_id        TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name              TEXT NOT NULL,
+    status            TEXT NOT NULL,
+    priority          TEXT,
+    max_hours         INTEGER NOT NULL,
+    escalate_priority INTEGER NOT NULL DEFAULT 0,
+    webhook_id        TEXT REFERENCES webhooks(id) ON DELETE SET NULL,
+    created_at        TEXT NOT NULL,
+    updated_at        TEXT NOT NULL,
+    CHECK (max_hours > 0),
+    CHECK (escalate_priority IN (0, 1))
+);
+
+CREATE INDEX IF NOT EXISTS idx_sla_policies_project ON sla_policies(project_id);
+
+CREATE TABLE IF NOT EXISTS sla_breaches (
+    policy_id   TEXT NOT NULL REFERENCES sla_policies(id) ON DELETE CASCADE,
+    task_id     TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    entered_at  TEXT NOT NULL,
+    breached_at TEXT NOT NULL,
+    PRIMARY KEY (policy_id, task_id, entered_at)
+);
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS inbound_hooks (
+    id         TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name       TEXT NOT NULL,
+    kind       TEXT NOT NULL,
+    template   TEXT NOT NULL DEFAULT '{}',
+    secret     TEXT NOT NULL,
+    active     INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    CHECK (kind IN ('task', 'question')),
+    CHECK (active IN (0, 1))
+);
+
+CREATE INDEX IF NOT EXISTS idx_inbound_hooks_project ON inbound_hooks(project_id);
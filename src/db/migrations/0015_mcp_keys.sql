@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS mcp_keys (
+    id         TEXT PRIMARY KEY,
+    name       TEXT NOT NULL UNIQUE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_by TEXT NOT NULL,
+    revoked_at TEXT,
+    created_at TEXT NOT NULL
+);
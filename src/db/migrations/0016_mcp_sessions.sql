@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS mcp_sessions (
+    id         TEXT PRIMARY KEY,
+    initialize TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_mcp_sessions_created ON mcp_sessions(created_at);
@@ -0,0 +1,12 @@
+-- Instance-level audit trail for actions whose subject may no longer exist, such as deleted
+-- projects. Unlike system_events, rows are not tied to a project and survive its deletion.
+CREATE TABLE IF NOT EXISTS audit_events (
+    id         TEXT PRIMARY KEY,
+    actor      TEXT NOT NULL,
+    action     TEXT NOT NULL,
+    subject    TEXT NOT NULL,
+    detail     TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_events_created ON audit_events(created_at, id);
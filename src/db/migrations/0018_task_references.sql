@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS task_links (
+    source_task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    target_task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    source_kind    TEXT NOT NULL,
+    source_id      TEXT NOT NULL,
+    created_by     TEXT NOT NULL,
+    created_at     TEXT NOT NULL,
+    PRIMARY KEY (source_kind, source_id, target_task_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_links_target ON task_links(target_task_id);
+
+CREATE TABLE IF NOT EXISTS task_mentions (
+    task_id      TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    actor        TEXT NOT NULL,
+    source_kind  TEXT NOT NULL,
+    source_id    TEXT NOT NULL,
+    mentioned_by TEXT NOT NULL,
+    created_at   TEXT NOT NULL,
+    PRIMARY KEY (source_kind, source_id, actor)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_mentions_task ON task_mentions(task_id);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS project_slug_aliases (
+    slug       TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_slug_aliases_project ON project_slug_aliases(project_id);
@@ -0,0 +1 @@
+ALTER TABLE webhooks ADD COLUMN payload_format TEXT NOT NULL DEFAULT 'lattice';
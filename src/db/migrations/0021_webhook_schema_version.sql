@@ -0,0 +1,2 @@
+-- Existing webhooks keep the payload shape they were built against.
+ALTER TABLE webhooks ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
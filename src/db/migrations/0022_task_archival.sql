@@ -0,0 +1,6 @@
+ALTER TABLE tasks ADD COLUMN archived_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_archived ON tasks(project_id, archived_at);
+
+-- 0 leaves done tasks on the board indefinitely.
+ALTER TABLE project_settings ADD COLUMN auto_archive_after_days INTEGER NOT NULL DEFAULT 0;
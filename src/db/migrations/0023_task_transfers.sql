@@ -0,0 +1,11 @@
+-- Former display keys of tasks moved to another project, so old links can be redirected.
+CREATE TABLE IF NOT EXISTS task_transfers (
+    from_project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    from_task_number INTEGER NOT NULL,
+    task_id          TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    transferred_by   TEXT NOT NULL,
+    created_at       TEXT NOT NULL,
+    PRIMARY KEY (from_project_id, from_task_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_transfers_task ON task_transfers(task_id);
@@ -0,0 +1 @@
+ALTER TABLE webhooks ADD COLUMN include_task_snapshot INTEGER NOT NULL DEFAULT 0;
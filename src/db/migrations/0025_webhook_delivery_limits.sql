@@ -0,0 +1,5 @@
+-- `quiet_hours` is `HH:MM-HH:MM` in `quiet_hours_timezone`; NULL means always deliver.
+ALTER TABLE webhooks ADD COLUMN quiet_hours TEXT;
+ALTER TABLE webhooks ADD COLUMN quiet_hours_timezone TEXT NOT NULL DEFAULT 'UTC';
+-- 0 means unlimited.
+ALTER TABLE webhooks ADD COLUMN max_deliveries_per_minute INTEGER NOT NULL DEFAULT 0;
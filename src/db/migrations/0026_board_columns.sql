@@ -0,0 +1,4 @@
+-- JSON array of every status in display order; NULL keeps the canonical flow.
+ALTER TABLE project_settings ADD COLUMN column_order TEXT;
+-- JSON object of status to `#rrggbb`; NULL leaves every column uncolored.
+ALTER TABLE project_settings ADD COLUMN column_colors TEXT;
@@ -0,0 +1,2 @@
+-- Address of the client that made the change; NULL for background jobs and older rows.
+ALTER TABLE audit_events ADD COLUMN client_ip TEXT;
@@ -0,0 +1,2 @@
+-- Random token replaced whenever a project's tasks, spec, or settings change; backs HTTP ETags.
+ALTER TABLE projects ADD COLUMN content_version TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,8 @@
+-- One board per project per UTC day, stored as the JSON served by the snapshot endpoint.
+CREATE TABLE IF NOT EXISTS board_snapshots (
+    project_id    TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    snapshot_date TEXT NOT NULL,
+    snapshot      TEXT NOT NULL,
+    created_at    TEXT NOT NULL,
+    PRIMARY KEY (project_id, snapshot_date)
+);
@@ -0,0 +1,4 @@
+-- Whether the asker accepted an answer: pending, accepted, or disputed. A disputed answer
+-- reopens the question and keeps the reason until it is answered again.
+ALTER TABLE open_questions ADD COLUMN acceptance TEXT NOT NULL DEFAULT 'pending';
+ALTER TABLE open_questions ADD COLUMN dispute_reason TEXT;
@@ -0,0 +1,3 @@
+-- Soft edit locks: who is editing a section and until when. Expired locks are ignored.
+ALTER TABLE spec_sections ADD COLUMN locked_by TEXT;
+ALTER TABLE spec_sections ADD COLUMN lock_expires_at TEXT;
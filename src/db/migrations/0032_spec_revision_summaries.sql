@@ -0,0 +1,5 @@
+-- Change summaries computed when a revision is written, so history lists can describe edits
+-- without shipping every body. Revisions written before this read as no change.
+ALTER TABLE spec_revisions ADD COLUMN bytes_added INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE spec_revisions ADD COLUMN bytes_removed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE spec_revisions ADD COLUMN headings_touched TEXT NOT NULL DEFAULT '[]';
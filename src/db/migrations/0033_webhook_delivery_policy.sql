@@ -0,0 +1,4 @@
+-- Per-webhook overrides of the server's delivery timeout and retry policy. NULL uses the default.
+ALTER TABLE webhooks ADD COLUMN timeout_secs INTEGER;
+ALTER TABLE webhooks ADD COLUMN max_retries INTEGER;
+ALTER TABLE webhooks ADD COLUMN retry_backoff_secs INTEGER;
@@ -0,0 +1,9 @@
+-- Projects an actor has starred, so `GET /projects?starred=true` lists the few they work in.
+CREATE TABLE IF NOT EXISTS project_stars (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    actor      TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (project_id, actor)
+);
+
+CREATE INDEX IF NOT EXISTS idx_project_stars_actor ON project_stars(actor);
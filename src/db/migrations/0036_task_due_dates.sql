@@ -0,0 +1,4 @@
+-- RFC3339 in UTC, so due dates compare as strings like the other timestamps.
+ALTER TABLE tasks ADD COLUMN due_at TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_tasks_due_at ON tasks(project_id, due_at);
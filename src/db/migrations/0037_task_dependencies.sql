@@ -0,0 +1,15 @@
+-- `blocker_task_id` blocks `blocked_task_id`; both tasks are in the same project.
+CREATE TABLE IF NOT EXISTS task_dependencies (
+    blocker_task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    blocked_task_id TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    created_by      TEXT NOT NULL,
+    created_at      TEXT NOT NULL,
+    PRIMARY KEY (blocker_task_id, blocked_task_id),
+    CHECK (blocker_task_id <> blocked_task_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_dependencies_blocked ON task_dependencies(blocked_task_id);
+
+ALTER TABLE project_settings
+    ADD COLUMN enforce_dependencies INTEGER NOT NULL DEFAULT 0
+    CHECK (enforce_dependencies IN (0, 1));
@@ -0,0 +1,2 @@
+-- IANA name used to localize timestamps in API responses; storage stays UTC.
+ALTER TABLE project_settings ADD COLUMN timezone TEXT NOT NULL DEFAULT 'UTC';
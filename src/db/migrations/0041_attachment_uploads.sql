@@ -0,0 +1,15 @@
+-- Resumable upload sessions. The bytes received so far live in `uploads/{id}.part` under the
+-- storage directory; the row goes away once the upload completes or is aborted.
+CREATE TABLE IF NOT EXISTS attachment_uploads (
+    id           TEXT PRIMARY KEY,
+    task_id      TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    filename     TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes   INTEGER NOT NULL,
+    uploaded_by  TEXT NOT NULL,
+    created_at   TEXT NOT NULL,
+    updated_at   TEXT NOT NULL,
+    CHECK (size_bytes > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachment_uploads_updated ON attachment_uploads(updated_at);
@@ -0,0 +1,14 @@
+-- A task copied into a new task on a cron schedule. `next_run_at` is computed in the
+-- project's timezone and is NULL once the schedule has no future runs.
+CREATE TABLE IF NOT EXISTS task_recurrences (
+    task_id      TEXT PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+    schedule     TEXT NOT NULL,
+    next_run_at  TEXT,
+    last_run_at  TEXT,
+    last_task_id TEXT REFERENCES tasks(id) ON DELETE SET NULL,
+    created_by   TEXT NOT NULL,
+    created_at   TEXT NOT NULL,
+    updated_at   TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_recurrences_next_run ON task_recurrences(next_run_at);
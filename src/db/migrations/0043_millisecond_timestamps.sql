@@ -0,0 +1,69 @@
+-- Rewrites second-precision timestamps (`2026-05-01T17:00:05Z`) written before timestamps
+-- carried milliseconds into `2026-05-01T17:00:05.000Z`, so old and new values compare as
+-- text in time order.
+
+UPDATE actors SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE actors SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE attachment_uploads SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE attachment_uploads SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE attachments SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE audit_events SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE board_snapshots SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE digest_schedules SET last_sent_at = substr(last_sent_at, 1, 19) || '.000Z' WHERE last_sent_at LIKE '____-__-__T__:__:__Z';
+UPDATE digest_schedules SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE digest_schedules SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE guest_tokens SET expires_at = substr(expires_at, 1, 19) || '.000Z' WHERE expires_at LIKE '____-__-__T__:__:__Z';
+UPDATE guest_tokens SET revoked_at = substr(revoked_at, 1, 19) || '.000Z' WHERE revoked_at LIKE '____-__-__T__:__:__Z';
+UPDATE guest_tokens SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE inbound_hooks SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE inbound_hooks SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE mcp_keys SET revoked_at = substr(revoked_at, 1, 19) || '.000Z' WHERE revoked_at LIKE '____-__-__T__:__:__Z';
+UPDATE mcp_keys SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE mcp_sessions SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE milestones SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE milestones SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE notifications SET read_at = substr(read_at, 1, 19) || '.000Z' WHERE read_at LIKE '____-__-__T__:__:__Z';
+UPDATE notifications SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE open_questions SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE open_questions SET resolved_at = substr(resolved_at, 1, 19) || '.000Z' WHERE resolved_at LIKE '____-__-__T__:__:__Z';
+UPDATE project_settings SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE project_slug_aliases SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE project_stars SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE projects SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE projects SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE review_checklist_templates SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE share_links SET expires_at = substr(expires_at, 1, 19) || '.000Z' WHERE expires_at LIKE '____-__-__T__:__:__Z';
+UPDATE share_links SET revoked_at = substr(revoked_at, 1, 19) || '.000Z' WHERE revoked_at LIKE '____-__-__T__:__:__Z';
+UPDATE share_links SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE sla_breaches SET entered_at = substr(entered_at, 1, 19) || '.000Z' WHERE entered_at LIKE '____-__-__T__:__:__Z';
+UPDATE sla_breaches SET breached_at = substr(breached_at, 1, 19) || '.000Z' WHERE breached_at LIKE '____-__-__T__:__:__Z';
+UPDATE sla_policies SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE sla_policies SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE spec_revisions SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE spec_sections SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE spec_sections SET lock_expires_at = substr(lock_expires_at, 1, 19) || '.000Z' WHERE lock_expires_at LIKE '____-__-__T__:__:__Z';
+UPDATE subtasks SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE system_events SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_dependencies SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_history SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_links SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_mentions SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_recurrences SET next_run_at = substr(next_run_at, 1, 19) || '.000Z' WHERE next_run_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_recurrences SET last_run_at = substr(last_run_at, 1, 19) || '.000Z' WHERE last_run_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_recurrences SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_recurrences SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_review_items SET completed_at = substr(completed_at, 1, 19) || '.000Z' WHERE completed_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_review_items SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_reviews SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_reviews SET completed_at = substr(completed_at, 1, 19) || '.000Z' WHERE completed_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_transfers SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE task_watchers SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE tasks SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE tasks SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE tasks SET status_changed_at = substr(status_changed_at, 1, 19) || '.000Z' WHERE status_changed_at LIKE '____-__-__T__:__:__Z';
+UPDATE tasks SET archived_at = substr(archived_at, 1, 19) || '.000Z' WHERE archived_at LIKE '____-__-__T__:__:__Z';
+UPDATE tasks SET due_at = substr(due_at, 1, 19) || '.000Z' WHERE due_at LIKE '____-__-__T__:__:__Z';
+UPDATE webhooks SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE webhooks SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
+UPDATE workspaces SET created_at = substr(created_at, 1, 19) || '.000Z' WHERE created_at LIKE '____-__-__T__:__:__Z';
+UPDATE workspaces SET updated_at = substr(updated_at, 1, 19) || '.000Z' WHERE updated_at LIKE '____-__-__T__:__:__Z';
@@ -1,5 +1,10 @@
+pub mod diff;
 pub mod models;
 pub mod queries;
+pub mod references;
+pub mod secrets;
+pub mod slug_cache;
+pub mod spec_lint;
 
 use std::str::FromStr;
 
@@ -8,6 +13,7 @@ use sqlx::any::{AnyConnectOptions, AnyPoolOptions};
 use sqlx::{AnyPool, ConnectOptions, Executor};
 
 use crate::config::Config;
+use crate::redact;
 
 static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!("./src/db/migrations");
 
@@ -17,7 +23,12 @@ pub async fn connect_and_migrate(config: &Config) -> anyhow::Result<AnyPool> {
     let db_url = normalized_db_url(&config.db_url);
 
     let connect_options = AnyConnectOptions::from_str(&db_url)
-        .with_context(|| format!("invalid LATTICE_DB_URL: {}", config.db_url))?
+        .with_context(|| {
+            format!(
+                "invalid LATTICE_DB_URL: {}",
+                redact::credentials(&config.db_url)
+            )
+        })?
         .disable_statement_logging();
 
     let pool = AnyPoolOptions::new()
@@ -40,6 +51,21 @@ pub async fn connect_and_migrate(config: &Config) -> anyhow::Result<AnyPool> {
         .await
         .context("failed to run migrations")?;
 
+    queries::ensure_default_workspace(&pool)
+        .await
+        .context("failed to create default workspace")?;
+
+    let keyring = secrets::Keyring::from_config(&config.secret_keys);
+    let rewrapped = queries::rewrap_stored_secrets(&pool, &keyring)
+        .await
+        .context("failed to encrypt stored secrets")?;
+    if rewrapped > 0 {
+        tracing::info!(
+            count = rewrapped,
+            "moved webhook and inbound hook secrets to the current secret key"
+        );
+    }
+
     Ok(pool)
 }
 
@@ -1,9 +1,38 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
 use serde::Serialize;
+use serde_json::Value;
 use sqlx::FromRow;
 
+use crate::db::diff::DiffOp;
+use crate::error::{AppError, AppResult};
+use crate::redact;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WorkspaceRecord {
+    pub id: String,
+    pub slug: String,
+    pub name: String,
+    pub token_hash: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ActorRecord {
+    pub id: String,
+    pub display_name: String,
+    pub kind: String,
+    pub avatar_url: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct ProjectRecord {
     pub id: String,
+    pub workspace_id: String,
     pub slug: String,
     pub name: String,
     pub goal: String,
@@ -26,6 +55,15 @@ pub struct TaskRecord {
     pub created_by: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Set while a done task is archived off the board.
+    pub archived_at: Option<String>,
+    /// The actor responsible for the task, if anyone.
+    pub assignee: Option<String>,
+    /// RFC3339 in UTC.
+    pub due_at: Option<String>,
+    /// The epic this task belongs to, in the same project.
+    pub parent_task_id: Option<String>,
+    pub milestone_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
@@ -46,12 +84,157 @@ pub struct OpenQuestionRecord {
     pub context: String,
     pub answer: Option<String>,
     pub status: String,
+    pub acceptance: String,
+    pub dispute_reason: Option<String>,
     pub asked_by: String,
     pub resolved_by: Option<String>,
     pub created_at: String,
     pub resolved_at: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskReviewRecord {
+    pub id: String,
+    pub task_id: String,
+    pub reviewer: String,
+    pub requested_by: String,
+    pub status: String,
+    pub comment: Option<String>,
+    pub completed_by: Option<String>,
+    pub created_at: String,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectSettings {
+    pub project_id: String,
+    pub review_checklist_required: bool,
+    /// Who is stopped from moving `not_ready` tasks: `off`, `agents`, or `everyone`.
+    pub review_gate: String,
+    /// Which moves are gated: `all_moves` or `start_and_finish` (into in_progress/done).
+    pub review_gate_scope: String,
+    /// Done tasks are archived this many days after reaching `done`; 0 turns it off.
+    pub auto_archive_after_days: i64,
+    /// Every status in board display order.
+    pub column_order: Vec<String>,
+    /// `#rrggbb` colors for the columns that have one.
+    pub column_colors: BTreeMap<String, String>,
+    /// Moving a task to `done` is refused while any task blocking it is not done.
+    pub enforce_dependencies: bool,
+    /// IANA timezone that task responses localize their timestamps to.
+    pub timezone: String,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReviewChecklistTemplateRecord {
+    pub id: String,
+    pub project_id: String,
+    pub title: String,
+    pub sort_order: f64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskReviewItemRecord {
+    pub id: String,
+    pub task_id: String,
+    pub title: String,
+    pub done: i64,
+    pub sort_order: f64,
+    pub completed_by: Option<String>,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A dated project goal that tasks can be attached to, with its progress.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct MilestoneRecord {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// `YYYY-MM-DD`.
+    pub target_date: Option<String>,
+    /// `open` or `closed`.
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Attached tasks, archived ones included.
+    pub total_tasks: i64,
+    pub done_tasks: i64,
+}
+
+/// A time limit for tasks sitting in `status` (optionally only at `priority`).
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SlaPolicyRecord {
+    pub id: String,
+    pub project_id: String,
+    pub project_slug: String,
+    pub name: String,
+    pub status: String,
+    pub priority: Option<String>,
+    pub max_hours: i64,
+    pub escalate_priority: i64,
+    pub webhook_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// When a project's digest is posted to its webhooks. Hours are UTC; `weekday` is ISO (1 = Monday).
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DigestScheduleRecord {
+    pub project_id: String,
+    pub project_slug: String,
+    pub frequency: String,
+    pub hour: i64,
+    pub weekday: i64,
+    pub last_sent_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A task copied into a new task on a cron `schedule`, read in the project's timezone.
+/// `next_run_at` is `None` once the schedule has no future runs.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskRecurrenceRecord {
+    pub task_id: String,
+    pub project_slug: String,
+    pub task_number: i64,
+    pub schedule: String,
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    pub last_task_id: Option<String>,
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectDigest {
+    pub project_slug: String,
+    pub since: String,
+    pub until: String,
+    pub completed: Vec<TaskRecord>,
+    pub new_questions: Vec<ProjectQuestionRecord>,
+    pub stalled: Vec<TaskRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct NotificationRecord {
+    pub id: String,
+    pub recipient: String,
+    pub project_slug: String,
+    pub task_id: Option<String>,
+    pub task_number: Option<i64>,
+    pub history_id: Option<String>,
+    pub action: String,
+    pub actor: String,
+    pub reason: String,
+    pub detail: String,
+    pub read_at: Option<String>,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct ProjectQuestionRecord {
     pub id: String,
@@ -61,6 +244,8 @@ pub struct ProjectQuestionRecord {
     pub context: String,
     pub answer: Option<String>,
     pub status: String,
+    pub acceptance: String,
+    pub dispute_reason: Option<String>,
     pub asked_by: String,
     pub resolved_by: Option<String>,
     pub created_at: String,
@@ -74,16 +259,53 @@ pub struct SpecSectionRecord {
     pub section: String,
     pub content: String,
     pub updated_at: String,
+    /// Holder of the section's edit lock, if an unexpired one exists.
+    pub locked_by: Option<String>,
+    pub lock_expires_at: Option<String>,
+    /// Counted from `content` when the section is read.
+    #[sqlx(skip)]
+    pub word_count: usize,
+    #[sqlx(skip)]
+    pub char_count: usize,
+    /// The most characters an update may store in this section.
+    #[sqlx(skip)]
+    pub max_length: usize,
 }
 
-#[derive(Debug, Clone, Serialize, FromRow)]
+/// One saved version of a spec section. `content` is left out when the history is listed
+/// without bodies; the change summary is always present.
+#[derive(Debug, Clone, Serialize)]
 pub struct SpecRevisionRecord {
     pub id: String,
     pub project_id: String,
     pub section: String,
-    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
     pub edited_by: String,
     pub created_at: String,
+    pub bytes_added: i64,
+    pub bytes_removed: i64,
+    pub headings_touched: Vec<String>,
+}
+
+/// One problem found in a spec section. Replacing `text` at `line` and `column` (both 1-based,
+/// columns in characters) with `fix`, when present, resolves it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpecLintFinding {
+    pub section: String,
+    pub line: usize,
+    pub column: usize,
+    pub rule: &'static str,
+    pub message: String,
+    pub text: String,
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpecLintReport {
+    pub project_slug: String,
+    pub sections_checked: usize,
+    pub findings: Vec<SpecLintFinding>,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
@@ -98,6 +320,19 @@ pub struct AttachmentRecord {
     pub created_at: String,
 }
 
+/// A resumable upload that becomes an attachment once all `size_bytes` have arrived.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AttachmentUploadRecord {
+    pub id: String,
+    pub task_id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub uploaded_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct TaskHistoryRecord {
     pub id: String,
@@ -105,6 +340,36 @@ pub struct TaskHistoryRecord {
     pub actor: String,
     pub action: String,
     pub detail: String,
+    pub actor_display_name: Option<String>,
+    pub actor_kind: Option<String>,
+    pub actor_avatar_url: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHistoryPage {
+    pub entries: Vec<TaskHistoryRecord>,
+    pub next_cursor: Option<String>,
+}
+
+/// A description edit from task history, as structured ops and as a unified diff.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHistoryDiff {
+    pub history_id: String,
+    pub action: String,
+    pub field: String,
+    pub from_sha256: String,
+    pub to_sha256: String,
+    pub diff: Vec<DiffOp>,
+    pub unified: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskTransitionRecord {
+    pub task_id: String,
+    pub task_created_at: String,
+    pub action: String,
+    pub detail: String,
     pub created_at: String,
 }
 
@@ -116,6 +381,86 @@ pub struct ProjectActivityRecord {
     pub actor: String,
     pub action: String,
     pub detail: String,
+    pub actor_display_name: Option<String>,
+    pub actor_kind: Option<String>,
+    pub actor_avatar_url: Option<String>,
+    pub created_at: String,
+}
+
+/// What a create called with `?dry_run=true` would do. `errors` would reject the request;
+/// `warnings` would not. `preview` shows the record that would be created, once it is valid.
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub dry_run: bool,
+    pub valid: bool,
+    pub errors: Vec<DryRunIssue>,
+    pub warnings: Vec<String>,
+    pub preview: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunIssue {
+    pub error: &'static str,
+    pub message: String,
+}
+
+impl Default for DryRunReport {
+    fn default() -> Self {
+        Self {
+            dry_run: true,
+            valid: true,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            preview: None,
+        }
+    }
+}
+
+impl DryRunReport {
+    /// Records a failed check as an error and returns the checked value otherwise. Internal
+    /// errors are not a property of the request, so they still fail the call.
+    pub fn check<T>(&mut self, result: AppResult<T>) -> AppResult<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(AppError::Internal) => Err(AppError::Internal),
+            Err(error) => {
+                self.reject(error);
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn reject(&mut self, error: AppError) {
+        let (_, error, message) = error.public_parts();
+        self.errors.push(DryRunIssue { error, message });
+    }
+
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    pub fn finish(mut self, preview: Value) -> Self {
+        self.valid = self.errors.is_empty();
+        self.preview = self.valid.then_some(preview);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectActivityPage {
+    pub entries: Vec<ProjectActivityRecord>,
+    pub next_cursor: Option<String>,
+}
+
+/// An instance-level audit entry, kept even after its subject is deleted.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AuditEventRecord {
+    pub id: String,
+    pub actor: String,
+    pub client_ip: Option<String>,
+    pub action: String,
+    pub subject: String,
+    pub detail: String,
     pub created_at: String,
 }
 
@@ -128,10 +473,13 @@ pub struct SystemEventRecord {
     pub actor: String,
     pub action: String,
     pub detail: String,
+    pub actor_display_name: Option<String>,
+    pub actor_kind: Option<String>,
+    pub actor_avatar_url: Option<String>,
     pub created_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, FromRow)]
+#[derive(Clone, Serialize, FromRow)]
 pub struct WebhookRecord {
     pub id: String,
     pub project_id: String,
@@ -141,10 +489,97 @@ pub struct WebhookRecord {
     pub events: String,
     pub secret: Option<String>,
     pub active: i64,
+    pub payload_format: String,
+    pub schema_version: i64,
+    pub include_task_snapshot: i64,
+    pub quiet_hours: Option<String>,
+    pub quiet_hours_timezone: String,
+    pub max_deliveries_per_minute: i64,
+    /// Overrides of `LATTICE_WEBHOOK_TIMEOUT_SECS`, `LATTICE_WEBHOOK_MAX_RETRIES`, and
+    /// `LATTICE_WEBHOOK_RETRY_BACKOFF_SECS`; `None` uses the server default.
+    pub timeout_secs: Option<i64>,
+    pub max_retries: Option<i64>,
+    pub retry_backoff_secs: Option<i64>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// A named MCP API key. Its name becomes the actor for every tool call made with it.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct McpKeyRecord {
+    pub id: String,
+    pub name: String,
+    pub created_by: String,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A short-lived bearer token limited to reading one project.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct GuestTokenRecord {
+    pub id: String,
+    pub project_slug: String,
+    pub label: String,
+    pub created_by: String,
+    pub expires_at: String,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+/// A revocable read-only link to a project board. The token itself is only stored hashed.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ShareLinkRecord {
+    pub id: String,
+    pub project_slug: String,
+    pub label: String,
+    pub created_by: String,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+}
+
+/// An endpoint external systems call to create tasks or questions. `template` maps the
+/// created item's fields to values from the request body.
+#[derive(Clone, FromRow)]
+pub struct InboundHookRecord {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub template: String,
+    pub secret: String,
+    pub active: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Leaves out the url's path and the secret, so a logged record leaks no credentials.
+impl fmt::Debug for WebhookRecord {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("WebhookRecord")
+            .field("id", &self.id)
+            .field("project_id", &self.project_id)
+            .field("name", &self.name)
+            .field("url", &redact::url(&self.url))
+            .field("platform", &self.platform)
+            .field("has_secret", &self.secret.is_some())
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for InboundHookRecord {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("InboundHookRecord")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("active", &self.active)
+            .finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct ProjectSummary {
     pub project: ProjectRecord,
@@ -154,15 +589,171 @@ pub struct ProjectSummary {
     pub review_count: i64,
     pub done_count: i64,
     pub open_question_count: i64,
+    pub disputed_question_count: i64,
     pub not_ready_count: i64,
 }
 
+/// One board column in display order, truncated to a per-column limit.
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardColumn {
+    pub status: String,
+    pub color: Option<String>,
+    pub total: i64,
+    pub tasks: Vec<BoardCard>,
+}
+
+/// One step in a project's goal history. The oldest entry is the goal the project was created
+/// with, so it has no `previous_goal` and no `changed_by`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalRevision {
+    pub goal: String,
+    pub previous_goal: Option<String>,
+    pub changed_by: Option<String>,
+    pub changed_at: String,
+}
+
+/// The outcome of one entry in a bulk project create. `slug` is normalized once it is valid.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkProjectResult {
+    pub slug: String,
+    pub created: bool,
+    pub error: Option<String>,
+    pub project: Option<ProjectSummary>,
+}
+
+/// The outcome of one entry in a bulk answer: the resolved question, or why it was skipped.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkAnswerResult {
+    pub question_id: String,
+    pub resolved: bool,
+    pub error: Option<String>,
+    pub question: Option<OpenQuestionRecord>,
+}
+
+/// One swimlane of the board: the lane's tasks laid out in the usual columns. `key` is the
+/// priority or label the lane groups by; `None` collects unlabeled tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardLane {
+    pub key: Option<String>,
+    pub columns: Vec<BoardColumn>,
+}
+
+/// Every board column with all of its unarchived tasks at `taken_at`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardSnapshot {
+    pub project: String,
+    pub taken_at: String,
+    pub total: i64,
+    pub columns: Vec<BoardColumn>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardCard {
+    pub id: String,
+    pub display_key: String,
+    pub title: String,
+    pub priority: String,
+    pub review_state: String,
+    pub labels: Vec<String>,
+    pub sort_order: f64,
+}
+
+/// One value of a filterable task field and how many live tasks carry it.
+#[derive(Debug, Clone, Serialize)]
+pub struct FacetValue {
+    pub value: String,
+    pub count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+/// A page of facet values, most common first, with the number of distinct values.
+#[derive(Debug, Clone, Serialize)]
+pub struct FacetPage {
+    pub items: Vec<FacetValue>,
+    pub total: i64,
+}
+
+/// Distinct labels, creating actors, and priorities across a project's unarchived tasks.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectFacets {
+    pub labels: FacetPage,
+    pub actors: FacetPage,
+    /// Every priority, lowest first, including those no task has.
+    pub priorities: Vec<FacetValue>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// A task whose title, description, question, or review comment mentions another task.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskBacklinkRecord {
+    pub task_id: String,
+    pub display_key: String,
+    pub title: String,
+    pub status: String,
+    pub source_kind: String,
+    pub source_id: String,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+/// The task at the other end of a dependency.
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskDependencyRecord {
+    pub task_id: String,
+    pub display_key: String,
+    pub title: String,
+    pub status: String,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskDependencies {
+    /// Tasks that must be done before this one.
+    pub blocked_by: Vec<TaskDependencyRecord>,
+    /// Tasks waiting on this one.
+    pub blocks: Vec<TaskDependencyRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TaskMentionRecord {
+    pub actor: String,
+    pub source_kind: String,
+    pub source_id: String,
+    pub mentioned_by: String,
+    pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct TaskDetails {
     pub task: TaskRecord,
     pub labels: Vec<String>,
     pub subtasks: Vec<SubtaskRecord>,
     pub open_questions: Vec<OpenQuestionRecord>,
+    pub reviews: Vec<TaskReviewRecord>,
+    pub review_checklist: Vec<TaskReviewItemRecord>,
     pub attachments: Vec<AttachmentRecord>,
     pub history: Vec<TaskHistoryRecord>,
+    pub backlinks: Vec<TaskBacklinkRecord>,
+    pub mentions: Vec<TaskMentionRecord>,
+    pub dependencies: TaskDependencies,
+    pub children: TaskChildren,
+}
+
+/// A task's child tasks, with how many of them are done.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskChildren {
+    pub total: i64,
+    pub done: i64,
+    pub tasks: Vec<ChildTaskRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChildTaskRecord {
+    pub task_id: String,
+    pub display_key: String,
+    pub title: String,
+    pub status: String,
 }
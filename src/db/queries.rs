@@ -1,16 +1,42 @@
-use chrono::{SecondsFormat, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use chrono_tz::Tz;
 use serde_json::Value;
 use sqlx::query_builder::QueryBuilder;
-use sqlx::{Any, AnyPool};
+use sqlx::{Any, AnyPool, Column, Row};
 use uuid::Uuid;
 
+use crate::config::{
+    FieldLimitConfig, MAX_WEBHOOK_RETRIES, MAX_WEBHOOK_RETRY_BACKOFF_SECS, MAX_WEBHOOK_TIMEOUT_SECS,
+};
+use crate::db::diff::{self, DiffOp};
 use crate::db::models::{
-    AttachmentRecord, OpenQuestionRecord, ProjectActivityRecord, ProjectQuestionRecord,
-    ProjectRecord, ProjectSummary, SpecRevisionRecord, SpecSectionRecord, SubtaskRecord,
-    SystemEventRecord, TaskDetails, TaskHistoryRecord, TaskRecord, WebhookRecord,
+    ActorRecord, AttachmentRecord, AttachmentUploadRecord, AuditEventRecord, BoardCard,
+    BoardColumn, BoardLane, BoardSnapshot, BulkAnswerResult, BulkProjectResult, ChildTaskRecord,
+    DigestScheduleRecord, DryRunReport, FacetPage, FacetValue, GoalRevision, GuestTokenRecord,
+    InboundHookRecord, McpKeyRecord, MilestoneRecord, NotificationRecord, OpenQuestionRecord,
+    ProjectActivityPage, ProjectActivityRecord, ProjectDigest, ProjectFacets,
+    ProjectQuestionRecord, ProjectRecord, ProjectSettings, ProjectSummary,
+    ReviewChecklistTemplateRecord, ShareLinkRecord, SlaPolicyRecord, SpecLintReport,
+    SpecRevisionRecord, SpecSectionRecord, SubtaskRecord, SystemEventRecord, TaskBacklinkRecord,
+    TaskChildren, TaskDependencies, TaskDependencyRecord, TaskDetails, TaskHistoryDiff,
+    TaskHistoryPage, TaskHistoryRecord, TaskMentionRecord, TaskRecord, TaskRecurrenceRecord,
+    TaskReviewItemRecord, TaskReviewRecord, TaskTransitionRecord, WebhookRecord, WorkspaceRecord,
 };
+use crate::db::references;
+use crate::db::secrets::Keyring;
+use crate::db::slug_cache;
+use crate::db::spec_lint::{self, LintContext};
 use crate::error::{AppError, AppResult};
+use crate::event_kind::EventKind;
+use crate::recurrence::{self, Schedule};
+use crate::redact;
 
+pub const DEFAULT_WORKSPACE_ID: &str = "default";
+pub const DEFAULT_WORKSPACE_SLUG: &str = "DEFAULT";
+
+const MCP_SESSION_RETENTION_DAYS: u32 = 30;
 const SPEC_SECTIONS: [&str; 6] = [
     "overview",
     "requirements",
@@ -20,23 +46,125 @@ const SPEC_SECTIONS: [&str; 6] = [
     "references",
 ];
 
-const WEBHOOK_EVENTS: [&str; 9] = [
-    "task.created",
-    "task.updated",
-    "task.moved",
-    "task.deleted",
-    "task.review_state_changed",
-    "question.created",
-    "question.resolved",
-    "spec.updated",
-    "goal.updated",
+const TASK_STATUSES: [&str; 5] = ["backlog", "ready", "in_progress", "review", "done"];
+/// Task priorities from lowest to highest.
+const TASK_PRIORITIES: [&str; 4] = ["low", "medium", "high", "critical"];
+const MILESTONE_STATUSES: [&str; 2] = ["open", "closed"];
+const MAX_REORDER_TASKS: usize = 500;
+const REVIEW_GATES: [&str; 3] = ["off", "agents", "everyone"];
+const REVIEW_GATE_SCOPES: [&str; 2] = ["all_moves", "start_and_finish"];
+const MAX_BATCH_GET_TASKS: usize = 100;
+const MAX_BULK_UPDATE_TASKS: usize = 100;
+const MAX_BULK_ANSWERS: usize = 100;
+const MAX_BULK_PROJECTS: usize = 50;
+/// Words tried after a taken slug, between numbered variants, when suggesting free slugs.
+const SLUG_SUGGESTION_WORDS: [&str; 4] = ["APP", "CORE", "HQ", "NEXT"];
+const MAX_SLUG_SUGGESTIONS: usize = 3;
+const MAX_ASSIGNEE_CHARS: usize = 100;
+/// The `assignee` filter value that matches unassigned tasks.
+const UNASSIGNED_FILTER: &str = "none";
+/// The `parent` filter value that matches tasks without a parent.
+const NO_PARENT_FILTER: &str = "none";
+const DEFAULT_SPEC_LOCK_SECS: i64 = 300;
+const MAX_SPEC_LOCK_SECS: i64 = 3600;
+const INBOUND_HOOK_KINDS: [&str; 2] = ["task", "question"];
+const INBOUND_TASK_FIELDS: [&str; 6] = [
+    "title",
+    "description",
+    "status",
+    "priority",
+    "review_state",
+    "labels",
+];
+const INBOUND_QUESTION_FIELDS: [&str; 3] = ["task", "question", "context"];
+const MAX_SLA_HOURS: i64 = 24 * 365;
+const DIGEST_FREQUENCIES: [&str; 2] = ["daily", "weekly"];
+/// Cap on each list in a digest so one busy week cannot produce an unpostable message.
+const DIGEST_ITEM_LIMIT: i64 = 50;
+/// Adjacent `sort_order` values closer than this trigger a column rebalance.
+const SORT_ORDER_MIN_GAP: f64 = 1e-6;
+
+const MAX_AUTO_ARCHIVE_DAYS: i64 = 3650;
+const MAX_WEBHOOK_DELIVERIES_PER_MINUTE: i64 = 600;
+
+const UNDOABLE_ACTIONS: [EventKind; 3] = [
+    EventKind::TaskMoved,
+    EventKind::TaskUpdated,
+    EventKind::TaskReviewStateChanged,
 ];
+const UNDO_LOOKBACK: i64 = 100;
+
+/// Rejects `value` when it is longer than `max` characters. `env` names the setting so the
+/// error says how to raise the limit.
+fn check_length(field: &str, value: &str, max: usize, env: &str) -> AppResult<()> {
+    let length = value.chars().count();
+    if length > max {
+        return Err(AppError::BadRequest(format!(
+            "{field} is {length} characters, the limit is {max} (set by {env})"
+        )));
+    }
+    Ok(())
+}
 
-#[derive(Debug, Clone)]
+fn check_title_length(limits: &FieldLimitConfig, field: &str, value: &str) -> AppResult<()> {
+    let max = limits.max_title_length;
+    check_length(field, value, max, "LATTICE_MAX_TITLE_LENGTH")
+}
+
+fn check_description_length(limits: &FieldLimitConfig, value: &str) -> AppResult<()> {
+    let max = limits.max_description_length;
+    check_length(
+        "task description",
+        value,
+        max,
+        "LATTICE_MAX_DESCRIPTION_LENGTH",
+    )
+}
+
+fn check_question_length(limits: &FieldLimitConfig, field: &str, value: &str) -> AppResult<()> {
+    let max = limits.max_question_length;
+    check_length(field, value, max, "LATTICE_MAX_QUESTION_LENGTH")
+}
+
+/// Newest webhook payload schema. Version 2 adds `id`, the event's stable id; version 1 is
+/// the original shape. New webhooks start on the newest version, existing ones keep theirs.
+pub const LATEST_WEBHOOK_SCHEMA_VERSION: i64 = 2;
+
+/// Task list filters. List fields match any of their values; empty lists do not filter.
+#[derive(Debug, Clone, Default)]
 pub struct TaskFilters {
-    pub status: Option<String>,
-    pub label: Option<String>,
+    pub statuses: Vec<String>,
+    pub exclude_statuses: Vec<String>,
+    pub labels: Vec<String>,
+    pub exclude_labels: Vec<String>,
+    pub priorities: Vec<String>,
+    /// Inclusive lower bound on priority, e.g. `high` matches high and critical.
+    pub min_priority: Option<String>,
+    /// Inclusive upper bound on priority.
+    pub max_priority: Option<String>,
     pub review_state: Option<String>,
+    pub created_by: Option<String>,
+    /// Matches tasks assigned to any of these actors; `none` matches unassigned tasks.
+    pub assignees: Vec<String>,
+    /// RFC3339 timestamp or `YYYY-MM-DD` date, exclusive. Tasks without a due date never match.
+    pub due_before: Option<String>,
+    /// `Some(true)` keeps only unfinished tasks past their due date; `Some(false)` leaves them out.
+    pub overdue: Option<bool>,
+    /// RFC3339 timestamps or `YYYY-MM-DD` dates; `after` bounds are inclusive, `before` exclusive.
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub updated_after: Option<String>,
+    pub updated_before: Option<String>,
+    /// Only in-progress tasks last updated before this timestamp.
+    pub stale_before: Option<String>,
+    /// `Some(true)` lists only archived tasks; otherwise archived tasks are left out.
+    pub archived: Option<bool>,
+    /// Lists archived tasks alongside the rest, unless `archived` asks for them alone.
+    pub include_archived: bool,
+    /// A task ref whose children match; `none` matches tasks without a parent.
+    pub parent: Option<String>,
+    /// `field` or `field:asc|desc`; `None` keeps board order.
+    pub sort: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +176,9 @@ pub struct NewTaskInput {
     pub review_state: String,
     pub labels: Vec<String>,
     pub created_by: String,
+    pub assignee: Option<String>,
+    /// RFC3339 timestamp, stored in UTC.
+    pub due_at: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +189,10 @@ pub struct UpdateTaskInput {
     pub priority: Option<String>,
     pub review_state: Option<String>,
     pub labels: Option<Vec<String>>,
+    /// An empty string unassigns the task.
+    pub assignee: Option<String>,
+    /// An empty string clears the due date.
+    pub due_at: Option<String>,
     pub actor: String,
 }
 
@@ -69,6 +204,82 @@ pub struct MoveTaskInput {
     pub mcp_origin: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct ReorderTasksInput {
+    pub status: String,
+    pub task_refs: Vec<String>,
+    pub actor: String,
+    pub mcp_origin: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateMilestoneInput {
+    pub name: String,
+    /// `YYYY-MM-DD`.
+    pub target_date: Option<String>,
+}
+
+/// `None` keeps a field; a blank `target_date` clears it.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateMilestoneInput {
+    pub name: Option<String>,
+    pub target_date: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateSlaPolicyInput {
+    pub name: String,
+    pub status: String,
+    pub priority: Option<String>,
+    pub max_hours: i64,
+    pub escalate_priority: bool,
+    pub webhook_id: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DigestScheduleInput {
+    pub frequency: String,
+    pub hour: i64,
+    pub weekday: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateProjectSettingsInput {
+    pub review_checklist_required: Option<bool>,
+    pub review_gate: Option<String>,
+    pub review_gate_scope: Option<String>,
+    pub auto_archive_after_days: Option<i64>,
+    /// Every status exactly once; an empty list restores the canonical order.
+    pub column_order: Option<Vec<String>>,
+    /// Replaces all column colors; an empty map clears them.
+    pub column_colors: Option<BTreeMap<String, String>>,
+    pub enforce_dependencies: Option<bool>,
+    /// An IANA name such as `Europe/Berlin`.
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkProjectItem {
+    pub name: String,
+    pub goal: String,
+    pub slug: String,
+    /// Initial content by spec section name; other sections start empty.
+    pub spec: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BulkAnswerItem {
+    pub question_id: String,
+    pub answer: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubtaskBulkItem {
+    pub id: String,
+    pub done: Option<bool>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UpdateSubtaskInput {
     pub title: Option<String>,
@@ -77,6 +288,14 @@ pub struct UpdateSubtaskInput {
     pub actor: String,
 }
 
+/// The asker's verdict on an answer: `accepted`, or `disputed` with a reason.
+#[derive(Debug, Clone)]
+pub struct ReviewAnswerInput {
+    pub verdict: String,
+    pub reason: Option<String>,
+    pub actor: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct NewAttachmentInput {
     pub id: String,
@@ -87,6 +306,15 @@ pub struct NewAttachmentInput {
     pub uploaded_by: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct NewAttachmentUploadInput {
+    pub id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub uploaded_by: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreateWebhookInput {
     pub name: String,
@@ -95,6 +323,33 @@ pub struct CreateWebhookInput {
     pub events: Vec<String>,
     pub secret: Option<String>,
     pub active: bool,
+    pub payload_format: Option<String>,
+    pub schema_version: Option<i64>,
+    pub include_task_snapshot: bool,
+    /// `HH:MM-HH:MM`; on update an empty string turns quiet hours off.
+    pub quiet_hours: Option<String>,
+    pub quiet_hours_timezone: Option<String>,
+    pub max_deliveries_per_minute: Option<i64>,
+    pub timeout_secs: Option<i64>,
+    pub max_retries: Option<i64>,
+    pub retry_backoff_secs: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateInboundHookInput {
+    pub name: String,
+    pub kind: String,
+    pub template: Value,
+    pub secret: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateInboundHookInput {
+    pub name: Option<String>,
+    pub template: Option<Value>,
+    pub secret: Option<String>,
+    pub active: Option<bool>,
 }
 
 #[derive(Debug, Clone)]
@@ -105,6 +360,30 @@ pub struct UpdateWebhookInput {
     pub events: Option<Vec<String>>,
     pub secret: Option<String>,
     pub active: Option<bool>,
+    pub payload_format: Option<String>,
+    pub schema_version: Option<i64>,
+    pub include_task_snapshot: Option<bool>,
+    /// `HH:MM-HH:MM`; on update an empty string turns quiet hours off.
+    pub quiet_hours: Option<String>,
+    pub quiet_hours_timezone: Option<String>,
+    pub max_deliveries_per_minute: Option<i64>,
+    /// `Some(None)` goes back to the server default.
+    pub timeout_secs: Option<Option<i64>>,
+    pub max_retries: Option<Option<i64>>,
+    pub retry_backoff_secs: Option<Option<i64>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskHistoryFilters {
+    pub action: Option<String>,
+    pub actor: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateActorInput {
+    pub display_name: Option<String>,
+    pub kind: Option<String>,
+    pub avatar_url: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -166,2361 +445,14906 @@ pub fn validate_spec_section(section: &str) -> AppResult<()> {
     }
 }
 
-pub async fn list_projects(
-    pool: &AnyPool,
-    limit: i64,
-    offset: i64,
-) -> AppResult<Vec<ProjectSummary>> {
-    let projects = sqlx::query_as::<Any, ProjectRecord>(
+pub async fn ensure_default_workspace(pool: &AnyPool) -> AppResult<()> {
+    let now = now_timestamp();
+    sqlx::query(
         r#"
-        SELECT id, slug, name, goal, task_counter, created_at, updated_at
-        FROM projects
-        ORDER BY created_at DESC
-        LIMIT ? OFFSET ?
+        INSERT INTO workspaces (id, slug, name, token_hash, created_at, updated_at)
+        VALUES (?, ?, 'Default', NULL, ?, ?)
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(DEFAULT_WORKSPACE_ID)
+    .bind(DEFAULT_WORKSPACE_SLUG)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn list_workspaces(pool: &AnyPool) -> AppResult<Vec<WorkspaceRecord>> {
+    let workspaces = sqlx::query_as::<Any, WorkspaceRecord>(
+        r#"
+        SELECT id, slug, name, token_hash, created_at, updated_at
+        FROM workspaces
+        ORDER BY created_at ASC
         "#,
     )
-    .bind(limit)
-    .bind(offset)
     .fetch_all(pool)
     .await?;
 
-    let mut results = Vec::with_capacity(projects.len());
-    for project in projects {
-        let project_id = project.id.clone();
-        results.push(project_summary_by_id(pool, &project_id, project).await?);
-    }
+    Ok(workspaces)
+}
 
-    Ok(results)
+pub async fn get_workspace(pool: &AnyPool, slug: &str) -> AppResult<WorkspaceRecord> {
+    let workspace = sqlx::query_as::<Any, WorkspaceRecord>(
+        r#"
+        SELECT id, slug, name, token_hash, created_at, updated_at
+        FROM workspaces
+        WHERE slug = ?
+        "#,
+    )
+    .bind(slug)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("workspace '{slug}' not found")))?;
+
+    Ok(workspace)
 }
 
-pub async fn create_project_with_slug(
+pub async fn create_workspace(
     pool: &AnyPool,
     name: &str,
-    goal: &str,
     slug: &str,
-) -> AppResult<ProjectSummary> {
+) -> AppResult<WorkspaceRecord> {
     let normalized_name = name.trim();
     if normalized_name.is_empty() {
         return Err(AppError::BadRequest(
-            "project name cannot be empty".to_string(),
+            "workspace name cannot be empty".to_string(),
         ));
     }
 
     let normalized_slug = normalize_slug(slug)?;
-    create_project_record(pool, normalized_name, goal, &normalized_slug).await
-}
-
-async fn create_project_record(
-    pool: &AnyPool,
-    name: &str,
-    goal: &str,
-    slug: &str,
-) -> AppResult<ProjectSummary> {
     let now = now_timestamp();
-    let project_id = Uuid::new_v4().to_string();
-
-    let mut tx = pool.begin().await?;
 
     sqlx::query(
         r#"
-        INSERT INTO projects (id, slug, name, goal, task_counter, created_at, updated_at)
-        VALUES (?, ?, ?, ?, 0, ?, ?)
+        INSERT INTO workspaces (id, slug, name, token_hash, created_at, updated_at)
+        VALUES (?, ?, ?, NULL, ?, ?)
         "#,
     )
-    .bind(&project_id)
-    .bind(slug)
-    .bind(name)
-    .bind(goal)
+    .bind(Uuid::new_v4().to_string())
+    .bind(&normalized_slug)
+    .bind(normalized_name)
     .bind(&now)
     .bind(&now)
-    .execute(&mut *tx)
+    .execute(pool)
     .await?;
 
-    for section in SPEC_SECTIONS {
-        sqlx::query(
-            r#"
-            INSERT INTO spec_sections (id, project_id, section, content, updated_at)
-            VALUES (?, ?, ?, '', ?)
-            "#,
-        )
-        .bind(Uuid::new_v4().to_string())
-        .bind(&project_id)
-        .bind(section)
-        .bind(&now)
-        .execute(&mut *tx)
-        .await?;
+    get_workspace(pool, &normalized_slug).await
+}
+
+pub async fn delete_workspace(pool: &AnyPool, slug: &str) -> AppResult<()> {
+    let workspace = get_workspace(pool, slug).await?;
+    if workspace.id == DEFAULT_WORKSPACE_ID {
+        return Err(AppError::BadRequest(
+            "the default workspace cannot be deleted".to_string(),
+        ));
     }
 
-    tx.commit().await?;
+    let project_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM projects WHERE workspace_id = ?")
+            .bind(&workspace.id)
+            .fetch_one(pool)
+            .await?;
+    if project_count > 0 {
+        return Err(AppError::Conflict(format!(
+            "workspace '{slug}' still has {project_count} project(s)"
+        )));
+    }
 
-    let project = sqlx::query_as::<Any, ProjectRecord>(
+    sqlx::query("DELETE FROM workspaces WHERE id = ?")
+        .bind(&workspace.id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_workspace_token_hash(
+    pool: &AnyPool,
+    slug: &str,
+    token_hash: Option<&str>,
+) -> AppResult<WorkspaceRecord> {
+    let workspace = get_workspace(pool, slug).await?;
+
+    sqlx::query("UPDATE workspaces SET token_hash = ?, updated_at = ? WHERE id = ?")
+        .bind(token_hash)
+        .bind(now_timestamp())
+        .bind(&workspace.id)
+        .execute(pool)
+        .await?;
+
+    get_workspace(pool, slug).await
+}
+
+pub async fn workspace_id_by_token_hash(
+    pool: &AnyPool,
+    token_hash: &str,
+) -> AppResult<Option<String>> {
+    let workspace_id =
+        sqlx::query_scalar::<Any, String>("SELECT id FROM workspaces WHERE token_hash = ?")
+            .bind(token_hash)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(workspace_id)
+}
+
+pub async fn create_guest_token(
+    pool: &AnyPool,
+    project_slug: &str,
+    token_hash: &str,
+    label: &str,
+    created_by: &str,
+    expires_at: &str,
+) -> AppResult<GuestTokenRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let token_id = Uuid::new_v4().to_string();
+    sqlx::query(
         r#"
-        SELECT id, slug, name, goal, task_counter, created_at, updated_at
-        FROM projects
-        WHERE id = ?
+        INSERT INTO guest_tokens (id, project_id, token_hash, label, created_by, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
+    .bind(&token_id)
     .bind(&project_id)
-    .fetch_one(pool)
+    .bind(token_hash)
+    .bind(label.trim())
+    .bind(created_by)
+    .bind(expires_at)
+    .bind(now_timestamp())
+    .execute(pool)
     .await?;
 
-    project_summary_by_id(pool, &project_id, project).await
+    get_guest_token(pool, &project_id, &token_id).await
 }
 
-pub async fn get_project(pool: &AnyPool, slug: &str) -> AppResult<ProjectSummary> {
-    let project = sqlx::query_as::<Any, ProjectRecord>(
+pub async fn list_guest_tokens(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<GuestTokenRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = guest_token_query();
+    query.push(" WHERE g.project_id = ");
+    query.push_bind(project_id);
+    query.push(" ORDER BY g.created_at DESC, g.id DESC");
+    let tokens = query
+        .build_query_as::<GuestTokenRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(tokens)
+}
+
+pub async fn revoke_guest_token(
+    pool: &AnyPool,
+    project_slug: &str,
+    token_id: &str,
+) -> AppResult<GuestTokenRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let token = get_guest_token(pool, &project_id, token_id).await?;
+    if token.revoked_at.is_none() {
+        sqlx::query("UPDATE guest_tokens SET revoked_at = ? WHERE id = ?")
+            .bind(now_timestamp())
+            .bind(token_id)
+            .execute(pool)
+            .await?;
+    }
+
+    get_guest_token(pool, &project_id, token_id).await
+}
+
+/// Workspace id and project slug of a live guest token. Expired and revoked tokens resolve
+/// to nothing.
+pub async fn guest_scope_by_token_hash(
+    pool: &AnyPool,
+    token_hash: &str,
+) -> AppResult<Option<(String, String)>> {
+    let scope = sqlx::query_as::<Any, (String, String)>(
         r#"
-        SELECT id, slug, name, goal, task_counter, created_at, updated_at
-        FROM projects
-        WHERE slug = ?
+        SELECT p.workspace_id, p.slug
+        FROM guest_tokens g
+        INNER JOIN projects p ON p.id = g.project_id
+        WHERE g.token_hash = ? AND g.revoked_at IS NULL AND g.expires_at > ?
         "#,
     )
-    .bind(slug)
+    .bind(token_hash)
+    .bind(now_timestamp())
     .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("project '{slug}' not found")))?;
+    .await?;
 
-    let project_id = project.id.clone();
-    project_summary_by_id(pool, &project_id, project).await
+    Ok(scope)
 }
 
-pub async fn update_project(
+pub async fn create_mcp_key(
     pool: &AnyPool,
-    slug: &str,
-    name: Option<String>,
-    goal: Option<String>,
-    actor: &str,
-) -> AppResult<ProjectSummary> {
-    let existing = sqlx::query_as::<Any, ProjectRecord>(
+    name: &str,
+    token_hash: &str,
+    created_by: &str,
+) -> AppResult<McpKeyRecord> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "mcp key name cannot be empty".to_string(),
+        ));
+    }
+
+    let exists = sqlx::query_scalar::<Any, String>("SELECT id FROM mcp_keys WHERE name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await?;
+    if exists.is_some() {
+        return Err(AppError::Conflict(format!(
+            "mcp key '{name}' already exists"
+        )));
+    }
+
+    let key_id = Uuid::new_v4().to_string();
+    sqlx::query(
         r#"
-        SELECT id, slug, name, goal, task_counter, created_at, updated_at
-        FROM projects
-        WHERE slug = ?
+        INSERT INTO mcp_keys (id, name, token_hash, created_by, created_at)
+        VALUES (?, ?, ?, ?, ?)
         "#,
     )
-    .bind(slug)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("project '{slug}' not found")))?;
+    .bind(&key_id)
+    .bind(name)
+    .bind(token_hash)
+    .bind(created_by)
+    .bind(now_timestamp())
+    .execute(pool)
+    .await?;
 
-    let updated_name = match name {
-        Some(value) => {
-            let trimmed = value.trim().to_string();
-            if trimmed.is_empty() {
-                return Err(AppError::BadRequest(
-                    "project name cannot be empty".to_string(),
-                ));
-            }
-            trimmed
-        }
-        None => existing.name,
-    };
-
-    let previous_goal = existing.goal.clone();
-    let updated_goal = goal.unwrap_or(existing.goal);
-    let now = now_timestamp();
-    let mut tx = pool.begin().await?;
+    get_mcp_key(pool, &key_id).await
+}
 
-    sqlx::query(
+pub async fn list_mcp_keys(pool: &AnyPool) -> AppResult<Vec<McpKeyRecord>> {
+    let keys = sqlx::query_as::<Any, McpKeyRecord>(
         r#"
-        UPDATE projects
-        SET name = ?, goal = ?, updated_at = ?
-        WHERE id = ?
+        SELECT id, name, created_by, revoked_at, created_at
+        FROM mcp_keys
+        ORDER BY created_at DESC, id DESC
         "#,
     )
-    .bind(&updated_name)
-    .bind(&updated_goal)
-    .bind(&now)
-    .bind(&existing.id)
-    .execute(&mut *tx)
+    .fetch_all(pool)
     .await?;
+    Ok(keys)
+}
 
-    if updated_goal != previous_goal {
-        insert_project_event(
-            &mut tx,
-            &existing.id,
-            actor,
-            "goal.updated",
-            serde_json::json!({
-                "from_goal": previous_goal,
-                "to_goal": updated_goal,
-            }),
-        )
-        .await?;
+pub async fn revoke_mcp_key(pool: &AnyPool, key_id: &str) -> AppResult<McpKeyRecord> {
+    let key = get_mcp_key(pool, key_id).await?;
+    if key.revoked_at.is_none() {
+        sqlx::query("UPDATE mcp_keys SET revoked_at = ? WHERE id = ?")
+            .bind(now_timestamp())
+            .bind(key_id)
+            .execute(pool)
+            .await?;
     }
 
-    tx.commit().await?;
-    get_project(pool, slug).await
+    get_mcp_key(pool, key_id).await
+}
+
+/// Name of the live MCP key with this hash; revoked keys resolve to nothing.
+pub async fn mcp_key_name_by_token_hash(
+    pool: &AnyPool,
+    token_hash: &str,
+) -> AppResult<Option<String>> {
+    let name = sqlx::query_scalar::<Any, String>(
+        "SELECT name FROM mcp_keys WHERE token_hash = ? AND revoked_at IS NULL",
+    )
+    .bind(token_hash)
+    .fetch_optional(pool)
+    .await?;
+    Ok(name)
 }
 
-pub async fn delete_project(pool: &AnyPool, slug: &str) -> AppResult<()> {
-    let result = sqlx::query("DELETE FROM projects WHERE slug = ?")
-        .bind(slug)
+/// Records a session's `initialize` request so the session can be rebuilt after a restart,
+/// and drops sessions older than the retention window.
+pub async fn save_mcp_session(pool: &AnyPool, session_id: &str, initialize: &str) -> AppResult<()> {
+    sqlx::query("DELETE FROM mcp_sessions WHERE created_at < ?")
+        .bind(timestamp_days_ago(MCP_SESSION_RETENTION_DAYS))
+        .execute(pool)
+        .await?;
+    sqlx::query("INSERT INTO mcp_sessions (id, initialize, created_at) VALUES (?, ?, ?)")
+        .bind(session_id)
+        .bind(initialize)
+        .bind(now_timestamp())
         .execute(pool)
         .await?;
+    Ok(())
+}
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("project '{slug}' not found")));
-    }
+pub async fn mcp_session_initialize(pool: &AnyPool, session_id: &str) -> AppResult<Option<String>> {
+    let initialize = sqlx::query_scalar::<Any, String>(
+        "SELECT initialize FROM mcp_sessions WHERE id = ? AND created_at >= ?",
+    )
+    .bind(session_id)
+    .bind(timestamp_days_ago(MCP_SESSION_RETENTION_DAYS))
+    .fetch_optional(pool)
+    .await?;
+    Ok(initialize)
+}
 
+pub async fn delete_mcp_session(pool: &AnyPool, session_id: &str) -> AppResult<()> {
+    sqlx::query("DELETE FROM mcp_sessions WHERE id = ?")
+        .bind(session_id)
+        .execute(pool)
+        .await?;
     Ok(())
 }
 
-pub async fn list_project_webhooks(
+pub async fn create_share_link(
     pool: &AnyPool,
     project_slug: &str,
-) -> AppResult<Vec<WebhookRecord>> {
+    token_hash: &str,
+    label: &str,
+    created_by: &str,
+    expires_at: Option<&str>,
+) -> AppResult<ShareLinkRecord> {
     let project_id = project_id_by_slug(pool, project_slug).await?;
-
-    let webhooks = sqlx::query_as::<Any, WebhookRecord>(
+    let link_id = Uuid::new_v4().to_string();
+    sqlx::query(
         r#"
-        SELECT
-            id,
-            project_id,
-            name,
-            url,
-            platform,
-            events,
-            secret,
-            active,
-            created_at,
-            updated_at
-        FROM webhooks
-        WHERE project_id = ?
-        ORDER BY created_at DESC
+        INSERT INTO share_links (id, project_id, token_hash, label, created_by, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(project_id)
-    .fetch_all(pool)
+    .bind(&link_id)
+    .bind(&project_id)
+    .bind(token_hash)
+    .bind(label.trim())
+    .bind(created_by)
+    .bind(expires_at)
+    .bind(now_timestamp())
+    .execute(pool)
     .await?;
 
-    Ok(webhooks)
+    get_share_link(pool, &project_id, &link_id).await
 }
 
-pub async fn get_project_webhook(
+/// All share links of a project, including revoked and expired ones.
+pub async fn list_share_links(
     pool: &AnyPool,
     project_slug: &str,
-    webhook_id: &str,
-) -> AppResult<WebhookRecord> {
+) -> AppResult<Vec<ShareLinkRecord>> {
     let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = share_link_query();
+    query.push(" WHERE s.project_id = ");
+    query.push_bind(project_id);
+    query.push(" ORDER BY s.created_at DESC, s.id DESC");
+    let links = query
+        .build_query_as::<ShareLinkRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(links)
+}
 
-    let webhook = sqlx::query_as::<Any, WebhookRecord>(
+pub async fn revoke_share_link(
+    pool: &AnyPool,
+    project_slug: &str,
+    link_id: &str,
+) -> AppResult<ShareLinkRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let link = get_share_link(pool, &project_id, link_id).await?;
+    if link.revoked_at.is_none() {
+        sqlx::query("UPDATE share_links SET revoked_at = ? WHERE id = ?")
+            .bind(now_timestamp())
+            .bind(link_id)
+            .execute(pool)
+            .await?;
+    }
+
+    get_share_link(pool, &project_id, link_id).await
+}
+
+/// Project slug a share token grants access to. Revoked, expired, and unknown tokens all
+/// report the same not-found error.
+pub async fn project_slug_by_share_token(pool: &AnyPool, token_hash: &str) -> AppResult<String> {
+    sqlx::query_scalar::<Any, String>(
         r#"
-        SELECT
-            id,
-            project_id,
-            name,
-            url,
-            platform,
-            events,
-            secret,
-            active,
-            created_at,
-            updated_at
-        FROM webhooks
-        WHERE project_id = ? AND id = ?
+        SELECT p.slug
+        FROM share_links s
+        INNER JOIN projects p ON p.id = s.project_id
+        WHERE s.token_hash = ?
+          AND s.revoked_at IS NULL
+          AND (s.expires_at IS NULL OR s.expires_at > ?)
         "#,
     )
-    .bind(project_id)
-    .bind(webhook_id)
+    .bind(token_hash)
+    .bind(now_timestamp())
     .fetch_optional(pool)
     .await?
-    .ok_or_else(|| AppError::NotFound(format!("webhook '{webhook_id}' not found")))?;
+    .ok_or_else(|| AppError::NotFound("share link not found".to_string()))
+}
 
-    Ok(webhook)
+/// Project slugs starting with `prefix` (case-insensitive), for argument completion.
+pub async fn complete_project_slugs(
+    pool: &AnyPool,
+    workspace_id: Option<&str>,
+    prefix: &str,
+    limit: i64,
+) -> AppResult<Vec<String>> {
+    let prefix = prefix.trim().to_ascii_uppercase();
+    if !prefix
+        .chars()
+        .all(|character| character.is_ascii_alphanumeric() || character == '-')
+    {
+        return Ok(Vec::new());
+    }
+
+    let mut query = QueryBuilder::<Any>::new("SELECT slug FROM projects WHERE slug LIKE ");
+    query.push_bind(format!("{prefix}%"));
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND workspace_id = ");
+        query.push_bind(workspace_id);
+    }
+    query.push(" ORDER BY slug LIMIT ");
+    query.push_bind(limit);
+
+    let slugs = query.build_query_scalar::<String>().fetch_all(pool).await?;
+    Ok(slugs)
 }
 
-pub async fn create_webhook(
+/// Display keys of tasks in a project whose number starts with `number_prefix`, newest first.
+pub async fn complete_task_refs(
     pool: &AnyPool,
     project_slug: &str,
-    input: CreateWebhookInput,
-) -> AppResult<WebhookRecord> {
-    let project_id = project_id_by_slug(pool, project_slug).await?;
-    let name = input.name.trim().to_string();
-    if name.is_empty() {
-        return Err(AppError::BadRequest(
-            "webhook name cannot be empty".to_string(),
-        ));
+    number_prefix: &str,
+    limit: i64,
+) -> AppResult<Vec<String>> {
+    if !number_prefix
+        .chars()
+        .all(|character| character.is_ascii_digit())
+    {
+        return Ok(Vec::new());
     }
 
-    let url = normalize_webhook_url(&input.url)?;
-    let platform = normalize_webhook_platform(&input.platform)?;
-    let events = normalize_webhook_events(input.events)?;
-    let events_json = serde_json::to_string(&events).map_err(|error| {
-        tracing::error!(error = ?error, "failed to serialize webhook events");
-        AppError::Internal
-    })?;
-    let secret = normalize_optional_secret(input.secret);
+    let numbers = sqlx::query_scalar::<Any, i64>(
+        r#"
+        SELECT tasks.task_number
+        FROM tasks
+        JOIN projects ON projects.id = tasks.project_id
+        WHERE projects.slug = ? AND CAST(tasks.task_number AS TEXT) LIKE ?
+        ORDER BY tasks.task_number DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(project_slug)
+    .bind(format!("{number_prefix}%"))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
 
-    let webhook_id = Uuid::new_v4().to_string();
-    let now = now_timestamp();
+    Ok(numbers
+        .into_iter()
+        .map(|number| display_key(project_slug, number))
+        .collect())
+}
 
-    sqlx::query(
+/// Spec section names starting with `prefix`.
+pub fn complete_spec_sections(prefix: &str) -> Vec<String> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    SPEC_SECTIONS
+        .iter()
+        .filter(|section| section.starts_with(&prefix))
+        .map(|section| section.to_string())
+        .collect()
+}
+
+/// Owning workspace of the project known by `slug`, including slugs it was renamed from.
+pub async fn project_workspace_id(pool: &AnyPool, slug: &str) -> AppResult<Option<String>> {
+    let workspace_id = sqlx::query_scalar::<Any, String>(
         r#"
-        INSERT INTO webhooks (
-            id,
-            project_id,
-            name,
-            url,
-            platform,
-            events,
-            secret,
-            active,
-            created_at,
-            updated_at
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        SELECT workspace_id
+        FROM projects
+        WHERE slug = ?
+           OR id IN (SELECT project_id FROM project_slug_aliases WHERE slug = ?)
         "#,
     )
-    .bind(&webhook_id)
-    .bind(&project_id)
-    .bind(&name)
-    .bind(&url)
-    .bind(&platform)
-    .bind(&events_json)
-    .bind(secret)
-    .bind(i64::from(input.active))
-    .bind(&now)
-    .bind(&now)
-    .execute(pool)
+    .bind(slug)
+    .bind(slug)
+    .fetch_optional(pool)
     .await?;
 
-    get_project_webhook(pool, project_slug, &webhook_id).await
+    Ok(workspace_id)
 }
 
-pub async fn update_webhook(
+/// Current slug of the project that used to be called `slug`, or `None` when `slug` is
+/// in use (or was never used).
+pub async fn renamed_project_slug(pool: &AnyPool, slug: &str) -> AppResult<Option<String>> {
+    let current = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT p.slug
+        FROM project_slug_aliases a
+        INNER JOIN projects p ON p.id = a.project_id
+        WHERE a.slug = ?
+        "#,
+    )
+    .bind(slug)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(current.filter(|current| current != slug))
+}
+
+pub async fn attachment_workspace_id(
     pool: &AnyPool,
-    project_slug: &str,
-    webhook_id: &str,
-    input: UpdateWebhookInput,
-) -> AppResult<WebhookRecord> {
-    let existing = get_project_webhook(pool, project_slug, webhook_id).await?;
+    attachment_id: &str,
+) -> AppResult<Option<String>> {
+    let workspace_id = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT p.workspace_id
+        FROM attachments a
+        INNER JOIN tasks t ON t.id = a.task_id
+        INNER JOIN projects p ON p.id = t.project_id
+        WHERE a.id = ?
+        "#,
+    )
+    .bind(attachment_id)
+    .fetch_optional(pool)
+    .await?;
 
-    let name = match input.name {
+    Ok(workspace_id)
+}
+
+/// Known actors; with a workspace, only those with recorded activity in its projects.
+pub async fn list_actors(
+    pool: &AnyPool,
+    workspace_id: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<ActorRecord>> {
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT id, display_name, kind, avatar_url, created_at, updated_at
+        FROM actors
+        "#,
+    );
+
+    if let Some(workspace_id) = workspace_id {
+        query.push(
+            r#"WHERE EXISTS (
+                SELECT 1
+                FROM system_events e
+                INNER JOIN projects p ON p.id = e.project_id
+                WHERE e.actor = actors.id AND p.workspace_id = "#,
+        );
+        query.push_bind(workspace_id);
+        query.push(") ");
+    }
+
+    query.push("ORDER BY display_name ASC, id ASC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+
+    let actors = query
+        .build_query_as::<ActorRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(actors)
+}
+
+pub async fn get_actor(pool: &AnyPool, actor_id: &str) -> AppResult<ActorRecord> {
+    sqlx::query_as::<Any, ActorRecord>(
+        r#"
+        SELECT id, display_name, kind, avatar_url, created_at, updated_at
+        FROM actors
+        WHERE id = ?
+        "#,
+    )
+    .bind(actor_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("actor '{actor_id}' not found")))
+}
+
+pub async fn update_actor(
+    pool: &AnyPool,
+    actor_id: &str,
+    input: UpdateActorInput,
+) -> AppResult<ActorRecord> {
+    let existing = get_actor(pool, actor_id).await?;
+
+    let display_name = match input.display_name {
         Some(value) => {
             let trimmed = value.trim().to_string();
             if trimmed.is_empty() {
                 return Err(AppError::BadRequest(
-                    "webhook name cannot be empty".to_string(),
+                    "display_name cannot be empty".to_string(),
                 ));
             }
             trimmed
         }
-        None => existing.name,
-    };
-
-    let url = match input.url {
-        Some(value) => normalize_webhook_url(&value)?,
-        None => existing.url,
+        None => existing.display_name,
     };
 
-    let platform = match input.platform {
-        Some(value) => normalize_webhook_platform(&value)?,
-        None => existing.platform,
+    let kind = match input.kind {
+        Some(value) => normalize_actor_kind(&value)?,
+        None => existing.kind,
     };
 
-    let events = match input.events {
-        Some(value) => {
-            let normalized = normalize_webhook_events(value)?;
-            serde_json::to_string(&normalized).map_err(|error| {
-                tracing::error!(error = ?error, "failed to serialize webhook events");
-                AppError::Internal
-            })?
-        }
-        None => existing.events,
+    let avatar_url = match input.avatar_url {
+        Some(value) => normalize_avatar_url(&value)?,
+        None => existing.avatar_url,
     };
 
-    let secret = match input.secret {
-        Some(value) => normalize_optional_secret(Some(value)),
-        None => existing.secret,
-    };
-
-    let active = input.active.unwrap_or(existing.active == 1);
-    let now = now_timestamp();
-
     sqlx::query(
         r#"
-        UPDATE webhooks
-        SET name = ?, url = ?, platform = ?, events = ?, secret = ?, active = ?, updated_at = ?
-        WHERE id = ? AND project_id = ?
+        UPDATE actors
+        SET display_name = ?, kind = ?, avatar_url = ?, updated_at = ?
+        WHERE id = ?
         "#,
     )
-    .bind(name)
-    .bind(url)
-    .bind(platform)
-    .bind(events)
-    .bind(secret)
-    .bind(i64::from(active))
-    .bind(now)
-    .bind(webhook_id)
-    .bind(existing.project_id)
+    .bind(display_name)
+    .bind(kind)
+    .bind(avatar_url)
+    .bind(now_timestamp())
+    .bind(actor_id)
     .execute(pool)
     .await?;
 
-    get_project_webhook(pool, project_slug, webhook_id).await
+    get_actor(pool, actor_id).await
 }
 
-pub async fn delete_webhook(pool: &AnyPool, project_slug: &str, webhook_id: &str) -> AppResult<()> {
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+/// Projects newest first, limited to one workspace and to those `starred_by` starred when given.
+pub async fn list_projects(
+    pool: &AnyPool,
+    workspace_id: Option<&str>,
+    starred_by: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<ProjectSummary>> {
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT id, workspace_id, slug, name, goal, task_counter, created_at, updated_at
+        FROM projects
+        WHERE 1 = 1
+        "#,
+    );
+    push_project_filters(&mut query, workspace_id, starred_by);
 
-    let result = sqlx::query("DELETE FROM webhooks WHERE id = ? AND project_id = ?")
-        .bind(webhook_id)
-        .bind(project_id)
-        .execute(pool)
+    query.push(" ORDER BY created_at DESC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+
+    let projects = query
+        .build_query_as::<ProjectRecord>()
+        .fetch_all(pool)
         .await?;
 
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "webhook '{webhook_id}' not found"
-        )));
+    let mut results = Vec::with_capacity(projects.len());
+    for project in projects {
+        let project_id = project.id.clone();
+        results.push(project_summary_by_id(pool, &project_id, project).await?);
     }
 
-    Ok(())
+    Ok(results)
 }
 
-pub async fn list_active_project_webhooks(
+pub async fn count_projects(
     pool: &AnyPool,
-    project_slug: &str,
-) -> AppResult<Vec<WebhookRecord>> {
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+    workspace_id: Option<&str>,
+    starred_by: Option<&str>,
+) -> AppResult<i64> {
+    let mut query = QueryBuilder::<Any>::new("SELECT COUNT(*) FROM projects WHERE 1 = 1");
+    push_project_filters(&mut query, workspace_id, starred_by);
+
+    let total = query.build_query_scalar::<i64>().fetch_one(pool).await?;
+    Ok(total)
+}
 
-    let webhooks = sqlx::query_as::<Any, WebhookRecord>(
+fn push_project_filters<'a>(
+    query: &mut QueryBuilder<'a, Any>,
+    workspace_id: Option<&'a str>,
+    starred_by: Option<&'a str>,
+) {
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND workspace_id = ");
+        query.push_bind(workspace_id);
+    }
+    if let Some(actor) = starred_by {
+        query.push(
+            " AND EXISTS (SELECT 1 FROM project_stars s WHERE s.project_id = projects.id AND s.actor = ",
+        );
+        query.push_bind(actor);
+        query.push(")");
+    }
+}
+
+/// Stars the project for `actor`; starring it again is a no-op.
+pub async fn star_project(pool: &AnyPool, project_slug: &str, actor: &str) -> AppResult<()> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    sqlx::query(
         r#"
-        SELECT
-            id,
-            project_id,
-            name,
-            url,
-            platform,
-            events,
-            secret,
-            active,
-            created_at,
-            updated_at
-        FROM webhooks
-        WHERE project_id = ? AND active = 1
-        ORDER BY created_at DESC
+        INSERT INTO project_stars (project_id, actor, created_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT (project_id, actor) DO NOTHING
         "#,
     )
-    .bind(project_id)
-    .fetch_all(pool)
+    .bind(&project_id)
+    .bind(actor)
+    .bind(now_timestamp())
+    .execute(pool)
     .await?;
 
-    Ok(webhooks)
+    Ok(())
 }
 
-pub async fn list_spec_sections(
-    pool: &AnyPool,
-    project_slug: &str,
-) -> AppResult<Vec<SpecSectionRecord>> {
+pub async fn unstar_project(pool: &AnyPool, project_slug: &str, actor: &str) -> AppResult<()> {
     let project_id = project_id_by_slug(pool, project_slug).await?;
+    sqlx::query("DELETE FROM project_stars WHERE project_id = ? AND actor = ?")
+        .bind(&project_id)
+        .bind(actor)
+        .execute(pool)
+        .await?;
 
-    let sections = sqlx::query_as::<Any, SpecSectionRecord>(
-        r#"
-        SELECT id, project_id, section, content, updated_at
-        FROM spec_sections
-        WHERE project_id = ?
-        ORDER BY
-            CASE section
-                WHEN 'overview' THEN 0
-                WHEN 'requirements' THEN 1
-                WHEN 'architecture' THEN 2
-                WHEN 'technical_design' THEN 3
-                WHEN 'open_decisions' THEN 4
-                WHEN 'references' THEN 5
-                ELSE 6
-            END
-        "#,
-    )
-    .bind(project_id)
-    .fetch_all(pool)
-    .await?;
+    Ok(())
+}
 
-    Ok(sections)
+pub async fn create_project_with_slug(
+    pool: &AnyPool,
+    name: &str,
+    goal: &str,
+    slug: &str,
+) -> AppResult<ProjectSummary> {
+    create_project_in_workspace(pool, DEFAULT_WORKSPACE_ID, name, goal, slug).await
 }
 
-pub async fn get_spec_section(
+pub async fn create_project_in_workspace(
     pool: &AnyPool,
-    project_slug: &str,
-    section: &str,
-) -> AppResult<SpecSectionRecord> {
-    validate_spec_section(section)?;
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+    workspace_id: &str,
+    name: &str,
+    goal: &str,
+    slug: &str,
+) -> AppResult<ProjectSummary> {
+    let normalized_name = name.trim();
+    if normalized_name.is_empty() {
+        return Err(AppError::BadRequest(
+            "project name cannot be empty".to_string(),
+        ));
+    }
 
-    let record = sqlx::query_as::<Any, SpecSectionRecord>(
-        r#"
-        SELECT id, project_id, section, content, updated_at
-        FROM spec_sections
-        WHERE project_id = ? AND section = ?
-        "#,
-    )
-    .bind(project_id)
-    .bind(section)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| {
-        AppError::NotFound(format!(
-            "spec section '{section}' not found for project '{project_slug}'"
-        ))
-    })?;
+    let normalized_slug = normalize_slug(slug)?;
+    create_project_record(pool, workspace_id, normalized_name, goal, &normalized_slug).await
+}
 
-    Ok(record)
+/// Runs the checks `create_project_in_workspace` makes, plus the slug uniqueness the insert
+/// would hit, without creating anything.
+pub async fn dry_run_create_project(
+    pool: &AnyPool,
+    workspace_id: &str,
+    name: &str,
+    goal: &str,
+    slug: &str,
+) -> AppResult<DryRunReport> {
+    let mut report = DryRunReport::default();
+    let name = name.trim();
+    if name.is_empty() {
+        report.reject(AppError::BadRequest(
+            "project name cannot be empty".to_string(),
+        ));
+    }
+
+    let normalized_slug = report.check(normalize_slug(slug))?;
+    if let Some(normalized) = normalized_slug.as_deref() {
+        if normalized != slug {
+            report.warn(format!("slug '{slug}' will be stored as '{normalized}'"));
+        }
+        report.check(check_project_slug_available(pool, normalized).await)?;
+    }
+    if goal.trim().is_empty() {
+        report.warn("project has no goal");
+    }
+
+    Ok(report.finish(serde_json::json!({
+        "workspace_id": workspace_id,
+        "slug": normalized_slug,
+        "name": name,
+        "goal": goal,
+    })))
 }
 
-pub async fn update_spec_section(
+async fn create_project_record(
     pool: &AnyPool,
-    project_slug: &str,
-    section: &str,
-    content: &str,
-    edited_by: &str,
-) -> AppResult<SpecSectionRecord> {
-    validate_spec_section(section)?;
-    let project_id = project_id_by_slug(pool, project_slug).await?;
-    let now = now_timestamp();
+    workspace_id: &str,
+    name: &str,
+    goal: &str,
+    slug: &str,
+) -> AppResult<ProjectSummary> {
+    check_project_slug_available(pool, slug).await?;
 
     let mut tx = pool.begin().await?;
-    let updated = sqlx::query(
+    // Another create can still claim the slug between the check and the insert.
+    let project_id = match insert_project(&mut tx, workspace_id, name, goal, slug).await {
+        Err(AppError::Conflict(_)) => {
+            let message = format!("project slug '{slug}' is already taken");
+            return Err(slug_taken(pool, slug, message).await?);
+        }
+        result => result?,
+    };
+    tx.commit().await?;
+
+    let project = sqlx::query_as::<Any, ProjectRecord>(
         r#"
-        UPDATE spec_sections
-        SET content = ?, updated_at = ?
-        WHERE project_id = ? AND section = ?
+        SELECT id, workspace_id, slug, name, goal, task_counter, created_at, updated_at
+        FROM projects
+        WHERE id = ?
         "#,
     )
-    .bind(content)
-    .bind(&now)
     .bind(&project_id)
-    .bind(section)
-    .execute(&mut *tx)
+    .fetch_one(pool)
     .await?;
 
-    if updated.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "spec section '{section}' not found for project '{project_slug}'"
-        )));
-    }
+    project_summary_by_id(pool, &project_id, project).await
+}
+
+/// Inserts a project with its empty spec sections and returns the project's id.
+async fn insert_project(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    workspace_id: &str,
+    name: &str,
+    goal: &str,
+    slug: &str,
+) -> AppResult<String> {
+    let now = now_timestamp();
+    let project_id = Uuid::new_v4().to_string();
 
     sqlx::query(
         r#"
-        INSERT INTO spec_revisions (id, project_id, section, content, edited_by, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO projects (id, workspace_id, slug, name, goal, task_counter, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, 0, ?, ?)
         "#,
     )
-    .bind(Uuid::new_v4().to_string())
     .bind(&project_id)
-    .bind(section)
-    .bind(content)
-    .bind(edited_by)
+    .bind(workspace_id)
+    .bind(slug)
+    .bind(name)
+    .bind(goal)
     .bind(&now)
-    .execute(&mut *tx)
-    .await?;
-
-    insert_project_event(
-        &mut tx,
-        &project_id,
-        edited_by,
-        "spec.updated",
-        serde_json::json!({ "section": section }),
-    )
+    .bind(&now)
+    .execute(&mut **tx)
     .await?;
 
-    tx.commit().await?;
+    for section in SPEC_SECTIONS {
+        sqlx::query(
+            r#"
+            INSERT INTO spec_sections (id, project_id, section, content, updated_at)
+            VALUES (?, ?, ?, '', ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&project_id)
+        .bind(section)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
 
-    get_spec_section(pool, project_slug, section).await
+    Ok(project_id)
 }
 
-pub async fn list_spec_history(
+/// Creates up to `MAX_BULK_PROJECTS` projects in one transaction. Every entry is checked
+/// first; if any would fail (invalid, a taken slug, or a slug repeated in the batch), nothing
+/// is created and each result says what was wrong with its entry. Initial spec content is
+/// recorded as the first revision of its section, edited by `actor`.
+pub async fn create_projects_bulk(
     pool: &AnyPool,
-    project_slug: &str,
-    section: &str,
-    limit: i64,
-    offset: i64,
-) -> AppResult<Vec<SpecRevisionRecord>> {
-    validate_spec_section(section)?;
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+    limits: &FieldLimitConfig,
+    workspace_id: &str,
+    items: Vec<BulkProjectItem>,
+    actor: &str,
+) -> AppResult<Vec<BulkProjectResult>> {
+    if items.is_empty() {
+        return Err(AppError::BadRequest("projects cannot be empty".to_string()));
+    }
+    if items.len() > MAX_BULK_PROJECTS {
+        return Err(AppError::BadRequest(format!(
+            "too many projects in one call, max is {MAX_BULK_PROJECTS}"
+        )));
+    }
 
-    let revisions = sqlx::query_as::<Any, SpecRevisionRecord>(
-        r#"
-        SELECT id, project_id, section, content, edited_by, created_at
-        FROM spec_revisions
-        WHERE project_id = ? AND section = ?
-        ORDER BY created_at DESC
-        LIMIT ? OFFSET ?
-        "#,
-    )
-    .bind(project_id)
-    .bind(section)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(pool)
-    .await?;
+    let mut planned: Vec<Option<(String, BulkProjectItem)>> = Vec::with_capacity(items.len());
+    let mut results = Vec::with_capacity(items.len());
+    for item in items {
+        let plan = match plan_bulk_project(pool, limits, &item).await {
+            Ok(slug) if planned.iter().flatten().any(|(other, _)| *other == slug) => Err(
+                AppError::Conflict(format!("project slug '{slug}' is listed more than once")),
+            ),
+            Err(AppError::Internal) => return Err(AppError::Internal),
+            plan => plan,
+        };
+        results.push(BulkProjectResult {
+            slug: plan
+                .as_ref()
+                .map_or_else(|_| item.slug.clone(), Clone::clone),
+            created: false,
+            error: plan.as_ref().err().map(ToString::to_string),
+            project: None,
+        });
+        planned.push(plan.ok().map(|slug| (slug, item)));
+    }
+
+    if planned.iter().any(Option::is_none) {
+        return Ok(results);
+    }
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+    for (slug, item) in planned.iter().flatten() {
+        let project_id =
+            insert_project(&mut tx, workspace_id, item.name.trim(), &item.goal, slug).await?;
+        for (section, content) in item.spec.iter().filter(|(_, content)| !content.is_empty()) {
+            sqlx::query(
+                "UPDATE spec_sections SET content = ? WHERE project_id = ? AND section = ?",
+            )
+            .bind(content)
+            .bind(&project_id)
+            .bind(section)
+            .execute(&mut *tx)
+            .await?;
+            let summary = diff::summarize("", content);
+            sqlx::query(
+                r#"
+                INSERT INTO spec_revisions (
+                    id, project_id, section, content, edited_by, created_at,
+                    bytes_added, bytes_removed, headings_touched
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&project_id)
+            .bind(section)
+            .bind(content)
+            .bind(actor)
+            .bind(&now)
+            .bind(summary.bytes_added as i64)
+            .bind(summary.bytes_removed as i64)
+            .bind(Value::from(summary.headings).to_string())
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+    tx.commit().await?;
 
-    Ok(revisions)
+    for result in &mut results {
+        result.created = true;
+        result.project = Some(get_project(pool, &result.slug).await?);
+    }
+    Ok(results)
 }
 
-pub async fn list_project_open_questions(
+/// Runs the checks a single create makes for one bulk entry and returns its normalized slug.
+async fn plan_bulk_project(
     pool: &AnyPool,
-    project_slug: &str,
-    limit: i64,
-    offset: i64,
-) -> AppResult<Vec<ProjectQuestionRecord>> {
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+    limits: &FieldLimitConfig,
+    item: &BulkProjectItem,
+) -> AppResult<String> {
+    if item.name.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "project name cannot be empty".to_string(),
+        ));
+    }
+    let slug = normalize_slug(&item.slug)?;
+    for (section, content) in &item.spec {
+        validate_spec_section(section)?;
+        let (max, env) = limits.spec_section_limit(section);
+        check_length(&format!("spec section '{section}'"), content, max, env)?;
+    }
 
-    let questions = sqlx::query_as::<Any, ProjectQuestionRecord>(
+    check_project_slug_available(pool, &slug).await?;
+    Ok(slug)
+}
+
+/// Fails with a conflict when `slug` belongs to a project or is still reserved as an alias.
+async fn check_project_slug_available(pool: &AnyPool, slug: &str) -> AppResult<()> {
+    let message = if let Some(current) = renamed_project_slug(pool, slug).await? {
+        format!("project slug '{slug}' still redirects to '{current}'")
+    } else if project_slug_in_use(pool, slug).await? {
+        format!("project slug '{slug}' is already taken")
+    } else {
+        return Ok(());
+    };
+    Err(slug_taken(pool, slug, message).await?)
+}
+
+/// Builds the conflict for a taken `slug`, suggesting free slugs like `SLUG2` and `SLUG-APP`.
+async fn slug_taken(pool: &AnyPool, slug: &str, message: String) -> AppResult<AppError> {
+    // `LATTICE2` reads well, but `V2` followed by `2` does not.
+    let separator = if slug.ends_with(|character: char| character.is_ascii_digit()) {
+        "-"
+    } else {
+        ""
+    };
+    let mut suggestions = Vec::new();
+    for (number, word) in (2..).zip(SLUG_SUGGESTION_WORDS) {
+        for candidate in [
+            format!("{slug}{separator}{number}"),
+            format!("{slug}-{word}"),
+        ] {
+            if suggestions.len() < MAX_SLUG_SUGGESTIONS
+                && !project_slug_in_use(pool, &candidate).await?
+            {
+                suggestions.push(candidate);
+            }
+        }
+    }
+    Ok(AppError::SlugTaken {
+        message,
+        suggestions,
+    })
+}
+
+/// Whether `slug` names a project or is still reserved as a former slug.
+async fn project_slug_in_use(pool: &AnyPool, slug: &str) -> AppResult<bool> {
+    let owner = sqlx::query_scalar::<Any, String>(
         r#"
-        SELECT
-            q.id,
-            q.task_id,
-            t.task_number,
-            q.question,
-            q.context,
-            q.answer,
-            q.status,
-            q.asked_by,
-            q.resolved_by,
-            q.created_at,
-            q.resolved_at
-        FROM open_questions q
-        INNER JOIN tasks t ON t.id = q.task_id
-        WHERE t.project_id = ? AND q.status = 'open'
-        ORDER BY q.created_at DESC
-        LIMIT ? OFFSET ?
+        SELECT id FROM projects WHERE slug = ?
+        UNION ALL
+        SELECT project_id FROM project_slug_aliases WHERE slug = ?
         "#,
     )
-    .bind(project_id)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(pool)
+    .bind(slug)
+    .bind(slug)
+    .fetch_optional(pool)
     .await?;
+    Ok(owner.is_some())
+}
 
-    Ok(questions)
+pub async fn get_project(pool: &AnyPool, slug: &str) -> AppResult<ProjectSummary> {
+    let project = sqlx::query_as::<Any, ProjectRecord>(
+        r#"
+        SELECT id, workspace_id, slug, name, goal, task_counter, created_at, updated_at
+        FROM projects
+        WHERE slug = ?
+        "#,
+    )
+    .bind(slug)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("project '{slug}' not found")))?;
+
+    let project_id = project.id.clone();
+    project_summary_by_id(pool, &project_id, project).await
 }
 
-pub async fn list_recent_project_activity(
+pub async fn update_project(
     pool: &AnyPool,
-    project_slug: &str,
-    limit: i64,
-) -> AppResult<Vec<ProjectActivityRecord>> {
-    if limit <= 0 || limit > 100 {
-        return Err(AppError::BadRequest(
-            "limit must be between 1 and 100".to_string(),
-        ));
-    }
+    slug: &str,
+    name: Option<String>,
+    goal: Option<String>,
+    actor: &str,
+) -> AppResult<ProjectSummary> {
+    let existing = sqlx::query_as::<Any, ProjectRecord>(
+        r#"
+        SELECT id, workspace_id, slug, name, goal, task_counter, created_at, updated_at
+        FROM projects
+        WHERE slug = ?
+        "#,
+    )
+    .bind(slug)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("project '{slug}' not found")))?;
 
-    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let updated_name = match name {
+        Some(value) => {
+            let trimmed = value.trim().to_string();
+            if trimmed.is_empty() {
+                return Err(AppError::BadRequest(
+                    "project name cannot be empty".to_string(),
+                ));
+            }
+            trimmed
+        }
+        None => existing.name,
+    };
+
+    let previous_goal = existing.goal.clone();
+    let updated_goal = goal.unwrap_or(existing.goal);
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
 
-    let activity = sqlx::query_as::<Any, ProjectActivityRecord>(
+    sqlx::query(
         r#"
-        SELECT
-            h.id,
-            h.task_id,
-            t.task_number,
-            h.actor,
-            h.action,
-            h.detail,
-            h.created_at
-        FROM task_history h
-        INNER JOIN tasks t ON t.id = h.task_id
-        WHERE t.project_id = ?
-        ORDER BY h.created_at DESC
-        LIMIT ?
+        UPDATE projects
+        SET name = ?, goal = ?, updated_at = ?
+        WHERE id = ?
         "#,
     )
-    .bind(project_id)
-    .bind(limit)
-    .fetch_all(pool)
+    .bind(&updated_name)
+    .bind(&updated_goal)
+    .bind(&now)
+    .bind(&existing.id)
+    .execute(&mut *tx)
     .await?;
 
-    Ok(activity)
-}
-
-pub async fn list_system_events(
-    pool: &AnyPool,
-    project_slugs: &[String],
-    after_created_at: Option<&str>,
-    after_id: Option<&str>,
-    limit: i64,
-) -> AppResult<Vec<SystemEventRecord>> {
-    if limit <= 0 || limit > 200 {
-        return Err(AppError::BadRequest(
-            "limit must be between 1 and 200".to_string(),
-        ));
+    if updated_goal != previous_goal {
+        insert_project_event(
+            &mut tx,
+            &existing.id,
+            actor,
+            EventKind::GoalUpdated,
+            serde_json::json!({
+                "from_goal": previous_goal,
+                "to_goal": updated_goal,
+            }),
+        )
+        .await?;
     }
 
-    if after_created_at.is_some() != after_id.is_some() {
-        return Err(AppError::BadRequest(
-            "after_created_at and after_id must be provided together".to_string(),
-        ));
-    }
+    tx.commit().await?;
+    get_project(pool, slug).await
+}
 
-    let mut query = QueryBuilder::<Any>::new(
+/// A project's goals over time, newest first, rebuilt from its `goal.updated` events. The
+/// oldest entry is the goal the project started with.
+pub async fn list_goal_history(pool: &AnyPool, slug: &str) -> AppResult<Vec<GoalRevision>> {
+    let project_id = project_id_by_slug(pool, slug).await?;
+    let (goal, created_at) = sqlx::query_as::<Any, (String, String)>(
+        "SELECT goal, created_at FROM projects WHERE id = ?",
+    )
+    .bind(&project_id)
+    .fetch_one(pool)
+    .await?;
+    let events = sqlx::query_as::<Any, (String, String, String)>(
         r#"
-        SELECT
-            e.id,
-            p.slug AS project_slug,
-            e.task_id,
-            e.task_number,
-            e.actor,
-            e.action,
-            e.detail,
-            e.created_at
-        FROM system_events e
-        INNER JOIN projects p ON p.id = e.project_id
-        WHERE 1 = 1
+        SELECT actor, detail, created_at
+        FROM system_events
+        WHERE project_id = ? AND action = 'goal.updated'
+        ORDER BY created_at ASC, id ASC
         "#,
-    );
+    )
+    .bind(&project_id)
+    .fetch_all(pool)
+    .await?;
 
-    if !project_slugs.is_empty() {
-        query.push(" AND p.slug IN (");
-        {
-            let mut separated = query.separated(", ");
-            for slug in project_slugs {
-                separated.push_bind(slug);
-            }
-        }
-        query.push(")");
+    let mut pending = Vec::with_capacity(events.len());
+    for (actor, detail, changed_at) in events {
+        let detail: Value = serde_json::from_str(&detail).map_err(|error| {
+            tracing::error!(error = ?error, project_id = %project_id, "goal.updated event detail is not valid JSON");
+            AppError::Internal
+        })?;
+        let text = |key: &str| detail[key].as_str().unwrap_or_default().to_string();
+        pending.push(GoalRevision {
+            goal: text("to_goal"),
+            previous_goal: Some(text("from_goal")),
+            changed_by: Some(actor),
+            changed_at,
+        });
     }
 
-    if let (Some(created_at), Some(event_id)) = (after_created_at, after_id) {
-        query.push(" AND (e.created_at > ");
-        query.push_bind(created_at);
-        query.push(" OR (e.created_at = ");
-        query.push_bind(created_at);
-        query.push(" AND e.id > ");
-        query.push_bind(event_id);
-        query.push("))");
+    // Timestamps have one-second resolution, so changes made within the same second are put
+    // in order by following each change's previous goal from the one before it.
+    let mut changes: Vec<GoalRevision> = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let same_second = pending
+            .iter()
+            .take_while(|change| change.changed_at == pending[0].changed_at)
+            .count();
+        let last_goal = changes.last().map(|change| &change.goal);
+        let next = pending[..same_second]
+            .iter()
+            .position(|change| change.previous_goal.as_ref() == last_goal)
+            .or_else(|| {
+                pending[..same_second].iter().position(|change| {
+                    !pending[..same_second]
+                        .iter()
+                        .any(|other| Some(&other.goal) == change.previous_goal.as_ref())
+                })
+            })
+            .unwrap_or(0);
+        changes.push(pending.remove(next));
     }
 
-    query.push(" ORDER BY e.created_at ASC, e.id ASC LIMIT ");
-    query.push_bind(limit);
-
-    let events = query
-        .build_query_as::<SystemEventRecord>()
-        .fetch_all(pool)
-        .await?;
-    Ok(events)
+    let original = match changes.first() {
+        Some(first) => first.previous_goal.clone().unwrap_or_default(),
+        None => goal,
+    };
+    let mut history = vec![GoalRevision {
+        goal: original,
+        previous_goal: None,
+        changed_by: None,
+        changed_at: created_at,
+    }];
+    history.extend(changes);
+    history.reverse();
+    Ok(history)
 }
 
-pub async fn latest_system_event_cursor(
+/// Changes a project's slug. The old slug is kept as an alias so display keys and URLs
+/// minted under it keep resolving; renaming back to an alias reclaims it.
+pub async fn rename_project(
     pool: &AnyPool,
-    project_slugs: &[String],
-) -> AppResult<Option<(String, String)>> {
-    #[derive(sqlx::FromRow)]
-    struct CursorRow {
-        created_at: String,
-        id: String,
+    slug: &str,
+    new_slug: &str,
+    actor: &str,
+    client_ip: Option<&str>,
+) -> AppResult<ProjectSummary> {
+    let new_slug = normalize_slug(new_slug)?;
+    let summary = get_project(pool, slug).await?;
+    if new_slug == summary.project.slug {
+        return Ok(summary);
     }
 
-    let mut query = QueryBuilder::<Any>::new(
+    let taken = sqlx::query_scalar::<Any, String>(
         r#"
-        SELECT e.created_at, e.id
-        FROM system_events e
-        INNER JOIN projects p ON p.id = e.project_id
-        WHERE 1 = 1
+        SELECT id FROM projects WHERE slug = ?
+        UNION ALL
+        SELECT project_id FROM project_slug_aliases WHERE slug = ?
         "#,
-    );
-
-    if !project_slugs.is_empty() {
-        query.push(" AND p.slug IN (");
-        {
-            let mut separated = query.separated(", ");
-            for slug in project_slugs {
-                separated.push_bind(slug);
-            }
-        }
-        query.push(")");
-    }
-
-    query.push(" ORDER BY e.created_at DESC, e.id DESC LIMIT 1");
-
-    let row = query
-        .build_query_as::<CursorRow>()
-        .fetch_optional(pool)
-        .await?;
-    Ok(row.map(|cursor| (cursor.created_at, cursor.id)))
-}
-
-pub async fn create_attachment(
-    pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-    input: NewAttachmentInput,
-) -> AppResult<AttachmentRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let filename = input.filename.trim().to_string();
-    if filename.is_empty() {
-        return Err(AppError::BadRequest(
-            "attachment filename cannot be empty".to_string(),
-        ));
-    }
-
-    if input.size_bytes < 0 {
-        return Err(AppError::BadRequest(
-            "attachment size cannot be negative".to_string(),
-        ));
+    )
+    .bind(&new_slug)
+    .bind(&new_slug)
+    .fetch_all(pool)
+    .await?;
+    if taken.iter().any(|owner| owner != &summary.project.id) {
+        return Err(AppError::Conflict(format!(
+            "project slug '{new_slug}' is already taken"
+        )));
     }
 
-    let content_type = if input.content_type.trim().is_empty() {
-        "application/octet-stream".to_string()
-    } else {
-        input.content_type.trim().to_string()
-    };
-
     let now = now_timestamp();
     let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM project_slug_aliases WHERE slug = ?")
+        .bind(&new_slug)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("INSERT INTO project_slug_aliases (slug, project_id, created_at) VALUES (?, ?, ?)")
+        .bind(&summary.project.slug)
+        .bind(&summary.project.id)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE projects SET slug = ?, updated_at = ? WHERE id = ?")
+        .bind(&new_slug)
+        .bind(&now)
+        .bind(&summary.project.id)
+        .execute(&mut *tx)
+        .await?;
 
-    sqlx::query(
-        r#"
-        INSERT INTO attachments (
-            id,
-            task_id,
-            filename,
-            content_type,
-            size_bytes,
-            storage_path,
-            uploaded_by,
-            created_at
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-        "#,
+    let detail = serde_json::json!({
+        "from_slug": summary.project.slug,
+        "to_slug": new_slug,
+    });
+    insert_project_event(
+        &mut tx,
+        &summary.project.id,
+        actor,
+        EventKind::ProjectRenamed,
+        detail.clone(),
     )
-    .bind(&input.id)
-    .bind(&task_id)
-    .bind(&filename)
-    .bind(content_type)
-    .bind(input.size_bytes)
-    .bind(input.storage_path)
-    .bind(&input.uploaded_by)
-    .bind(&now)
-    .execute(&mut *tx)
     .await?;
-
-    insert_history(
+    insert_audit_event(
         &mut tx,
-        &task_id,
-        &input.uploaded_by,
-        "attachment.created",
-        serde_json::json!({
-            "attachment_id": input.id,
-            "filename": filename,
-            "size_bytes": input.size_bytes,
-        }),
+        actor,
+        client_ip,
+        "project.renamed",
+        &new_slug,
+        detail,
     )
     .await?;
-
     tx.commit().await?;
-    get_attachment_for_task(pool, &task_id, &input.id).await
-}
-
-pub async fn get_attachment(pool: &AnyPool, attachment_id: &str) -> AppResult<AttachmentRecord> {
-    let attachment = sqlx::query_as::<Any, AttachmentRecord>(
-        r#"
-        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
-        FROM attachments
-        WHERE id = ?
-        "#,
-    )
-    .bind(attachment_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("attachment '{attachment_id}' not found")))?;
+    slug_cache::invalidate();
 
-    Ok(attachment)
+    get_project(pool, &new_slug).await
 }
 
-pub async fn delete_attachment(
+/// Deletes a project with everything in it and records `project.deleted` in the audit log.
+pub async fn delete_project(
     pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-    attachment_id: &str,
+    slug: &str,
     actor: &str,
-) -> AppResult<AttachmentRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let attachment = get_attachment_for_task(pool, &task_id, attachment_id).await?;
+    client_ip: Option<&str>,
+) -> AppResult<()> {
+    let summary = get_project(pool, slug).await?;
+    let task_count = summary.backlog_count
+        + summary.ready_count
+        + summary.in_progress_count
+        + summary.review_count
+        + summary.done_count;
 
     let mut tx = pool.begin().await?;
-    let result = sqlx::query("DELETE FROM attachments WHERE id = ? AND task_id = ?")
-        .bind(attachment_id)
-        .bind(&task_id)
+    sqlx::query("DELETE FROM projects WHERE id = ?")
+        .bind(&summary.project.id)
         .execute(&mut *tx)
         .await?;
-
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "attachment '{attachment_id}' not found for task '{task_ref}'"
-        )));
-    }
-
-    insert_history(
+    insert_audit_event(
         &mut tx,
-        &task_id,
         actor,
-        "attachment.deleted",
+        client_ip,
+        "project.deleted",
+        slug,
         serde_json::json!({
-            "attachment_id": attachment_id,
-            "filename": attachment.filename,
+            "project_id": summary.project.id,
+            "name": summary.project.name,
+            "task_count": task_count,
         }),
     )
     .await?;
-
     tx.commit().await?;
-    Ok(attachment)
+    slug_cache::invalidate();
+
+    Ok(())
 }
 
-pub async fn create_open_question(
+pub async fn list_audit_events(
     pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-    question: &str,
-    context: &str,
-    asked_by: &str,
-) -> AppResult<OpenQuestionRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let trimmed_question = question.trim().to_string();
-    if trimmed_question.is_empty() {
-        return Err(AppError::BadRequest("question cannot be empty".to_string()));
-    }
-
-    let now = now_timestamp();
-    let question_id = Uuid::new_v4().to_string();
-    let mut tx = pool.begin().await?;
-
-    sqlx::query(
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<AuditEventRecord>> {
+    let events = sqlx::query_as::<Any, AuditEventRecord>(
         r#"
-        INSERT INTO open_questions (
-            id,
-            task_id,
-            question,
-            context,
-            answer,
-            status,
-            asked_by,
-            resolved_by,
-            created_at,
-            resolved_at
-        )
-        VALUES (?, ?, ?, ?, NULL, 'open', ?, NULL, ?, NULL)
+        SELECT id, actor, client_ip, action, subject, detail, created_at
+        FROM audit_events
+        ORDER BY created_at DESC, id DESC
+        LIMIT ? OFFSET ?
         "#,
     )
-    .bind(&question_id)
-    .bind(&task_id)
-    .bind(&trimmed_question)
-    .bind(context)
-    .bind(asked_by)
-    .bind(&now)
-    .execute(&mut *tx)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
     .await?;
+    Ok(events)
+}
 
-    insert_history(
-        &mut tx,
-        &task_id,
-        asked_by,
-        "question.created",
-        serde_json::json!({
-            "question_id": question_id,
-            "question": trimmed_question,
-        }),
-    )
-    .await?;
+pub async fn count_audit_events(pool: &AnyPool) -> AppResult<i64> {
+    let total = sqlx::query_scalar::<Any, i64>("SELECT COUNT(*) FROM audit_events")
+        .fetch_one(pool)
+        .await?;
+    Ok(total)
+}
 
-    tx.commit().await?;
+/// Tables counted by `GET /api/v1/admin/stats`.
+const STATS_TABLES: [&str; 4] = ["workspaces", "projects", "tasks", "webhooks"];
 
-    get_open_question_by_id(pool, &task_id, &question_id).await
+pub async fn instance_row_counts(pool: &AnyPool) -> AppResult<BTreeMap<&'static str, i64>> {
+    let mut counts = BTreeMap::new();
+    for table in STATS_TABLES {
+        let count = sqlx::query_scalar::<Any, i64>(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(pool)
+            .await?;
+        counts.insert(table, count);
+    }
+    Ok(counts)
 }
 
-pub async fn answer_open_question(
-    pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-    question_id: &str,
-    answer: &str,
-    resolved_by: &str,
-) -> AppResult<OpenQuestionRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let trimmed_answer = answer.trim().to_string();
-    if trimmed_answer.is_empty() {
-        return Err(AppError::BadRequest("answer cannot be empty".to_string()));
-    }
+/// Tables copied by instance backups, parents before children so rows restore in order.
+/// MCP sessions are left out; they belong to the server that created them.
+pub const BACKUP_TABLES: [&str; 36] = [
+    "workspaces",
+    "projects",
+    "project_slug_aliases",
+    "project_settings",
+    "review_checklist_templates",
+    "spec_sections",
+    "spec_revisions",
+    "milestones",
+    "tasks",
+    "task_transfers",
+    "task_labels",
+    "task_links",
+    "task_dependencies",
+    "task_recurrences",
+    "task_mentions",
+    "subtasks",
+    "open_questions",
+    "attachments",
+    "task_history",
+    "task_reviews",
+    "task_review_items",
+    "webhooks",
+    "system_events",
+    "board_snapshots",
+    "actors",
+    "task_watchers",
+    "project_stars",
+    "notifications",
+    "digest_schedules",
+    "sla_policies",
+    "sla_breaches",
+    "inbound_hooks",
+    "share_links",
+    "guest_tokens",
+    "mcp_keys",
+    "audit_events",
+];
 
-    let existing = get_open_question_by_id(pool, &task_id, question_id).await?;
-    if existing.status != "open" {
-        return Err(AppError::Conflict(format!(
-            "question '{question_id}' is already resolved"
-        )));
-    }
+/// The newest applied migration, which a backup must match to be restored.
+pub async fn schema_version(pool: &AnyPool) -> AppResult<i64> {
+    let version =
+        sqlx::query_scalar::<Any, Option<i64>>("SELECT MAX(version) FROM _sqlx_migrations")
+            .fetch_one(pool)
+            .await?;
+    Ok(version.unwrap_or_default())
+}
 
-    let now = now_timestamp();
+/// Reads every backup table inside one transaction, so the dump is a consistent snapshot.
+pub async fn export_backup_tables(
+    pool: &AnyPool,
+) -> AppResult<Vec<(&'static str, Vec<serde_json::Map<String, Value>>)>> {
     let mut tx = pool.begin().await?;
+    let mut tables = Vec::with_capacity(BACKUP_TABLES.len());
+    for table in BACKUP_TABLES {
+        tables.push((table, export_table_rows(&mut tx, table).await?));
+    }
+    tx.commit().await?;
 
-    sqlx::query(
-        r#"
-        UPDATE open_questions
-        SET answer = ?, status = 'resolved', resolved_by = ?, resolved_at = ?
-        WHERE id = ? AND task_id = ? AND status = 'open'
-        "#,
-    )
-    .bind(&trimmed_answer)
-    .bind(resolved_by)
-    .bind(&now)
-    .bind(question_id)
-    .bind(&task_id)
-    .execute(&mut *tx)
-    .await?;
+    Ok(tables)
+}
 
-    insert_history(
-        &mut tx,
-        &task_id,
-        resolved_by,
-        "question.resolved",
-        serde_json::json!({
-            "question_id": question_id,
-        }),
-    )
-    .await?;
+/// Reads every row of a backup table as a JSON object keyed by column name.
+async fn export_table_rows(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    table: &str,
+) -> AppResult<Vec<serde_json::Map<String, Value>>> {
+    let rows = sqlx::query(&format!("SELECT * FROM {table}"))
+        .fetch_all(&mut **tx)
+        .await?;
 
-    tx.commit().await?;
+    let mut exported = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut object = serde_json::Map::new();
+        for column in row.columns() {
+            let index = column.ordinal();
+            let value = if let Ok(value) = row.try_get::<Option<i64>, _>(index) {
+                value.map_or(Value::Null, Value::from)
+            } else if let Ok(value) = row.try_get::<Option<f64>, _>(index) {
+                value.map_or(Value::Null, Value::from)
+            } else {
+                row.try_get::<Option<String>, _>(index)?
+                    .map_or(Value::Null, Value::from)
+            };
+            object.insert(column.name().to_string(), value);
+        }
+        exported.push(object);
+    }
 
-    get_open_question_by_id(pool, &task_id, question_id).await
+    Ok(exported)
 }
 
-pub async fn set_review_state(
-    pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-    review_state: &str,
-    actor: &str,
-) -> AppResult<TaskRecord> {
-    validate_review_state(review_state)?;
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let current = get_task_record_by_id(pool, &task_id).await?;
+/// Storage paths of every stored attachment, so blobs can be copied or cleaned up.
+pub async fn list_attachment_storage_paths(pool: &AnyPool) -> AppResult<Vec<String>> {
+    let paths = sqlx::query_scalar::<Any, String>("SELECT storage_path FROM attachments")
+        .fetch_all(pool)
+        .await?;
+    Ok(paths)
+}
 
-    if current.review_state == review_state {
-        return Ok(current);
+/// Replaces the contents of every backup table with `tables` in one transaction and records
+/// `instance.imported` in the audit log. Tables missing from `tables` end up empty.
+pub async fn restore_backup_tables(
+    pool: &AnyPool,
+    tables: &HashMap<String, Vec<serde_json::Map<String, Value>>>,
+    actor: &str,
+    client_ip: Option<&str>,
+) -> AppResult<()> {
+    if let Some(unknown) = tables
+        .keys()
+        .find(|table| !BACKUP_TABLES.contains(&table.as_str()))
+    {
+        return Err(AppError::BadRequest(format!(
+            "backup contains unknown table '{unknown}'"
+        )));
     }
 
-    let now = now_timestamp();
     let mut tx = pool.begin().await?;
+    for table in BACKUP_TABLES.iter().rev() {
+        sqlx::query(&format!("DELETE FROM {table}"))
+            .execute(&mut *tx)
+            .await?;
+    }
 
-    sqlx::query("UPDATE tasks SET review_state = ?, updated_at = ? WHERE id = ?")
-        .bind(review_state)
-        .bind(&now)
-        .bind(&task_id)
-        .execute(&mut *tx)
-        .await?;
+    // A task can be listed before its parent, so parents are linked once every task exists.
+    let mut parents = Vec::new();
+    for table in BACKUP_TABLES {
+        for row in tables.get(table).into_iter().flatten() {
+            if let ("tasks", Some(Value::String(task_id)), Some(Value::String(parent_id))) =
+                (table, row.get("id"), row.get("parent_task_id"))
+            {
+                parents.push((task_id.clone(), parent_id.clone()));
+                let mut row = row.clone();
+                row.remove("parent_task_id");
+                insert_backup_row(&mut tx, table, &row).await?;
+            } else {
+                insert_backup_row(&mut tx, table, row).await?;
+            }
+        }
+    }
+    for (task_id, parent_id) in parents {
+        sqlx::query("UPDATE tasks SET parent_task_id = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+    }
 
-    insert_history(
+    let counts: serde_json::Map<String, Value> = tables
+        .iter()
+        .map(|(table, rows)| (table.clone(), Value::from(rows.len())))
+        .collect();
+    insert_audit_event(
         &mut tx,
-        &task_id,
         actor,
-        "task.review_state_changed",
-        serde_json::json!({
-            "from_review_state": current.review_state,
-            "to_review_state": review_state,
-        }),
+        client_ip,
+        "instance.imported",
+        "instance",
+        serde_json::json!({ "rows": counts }),
     )
     .await?;
-
     tx.commit().await?;
-    get_task_record_by_id(pool, &task_id).await
+    slug_cache::invalidate();
+
+    Ok(())
 }
 
-pub async fn list_tasks(
-    pool: &AnyPool,
-    project_slug: &str,
-    filters: TaskFilters,
-    limit: i64,
-    offset: i64,
-) -> AppResult<Vec<TaskRecord>> {
-    if let Some(status) = filters.status.as_deref() {
-        validate_status(status)?;
+/// Null values are left out of the column list so every column falls back to its default,
+/// which keeps the bind types portable across database backends.
+async fn insert_backup_row(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    table: &str,
+    row: &serde_json::Map<String, Value>,
+) -> AppResult<()> {
+    let values: Vec<(&String, &Value)> = row.iter().filter(|(_, value)| !value.is_null()).collect();
+    if let Some((column, _)) = values.iter().find(|(column, _)| {
+        column.is_empty()
+            || !column
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_')
+    }) {
+        return Err(AppError::BadRequest(format!(
+            "backup table '{table}' has invalid column '{column}'"
+        )));
     }
 
-    if let Some(review_state) = filters.review_state.as_deref() {
-        validate_review_state(review_state)?;
+    let mut query = QueryBuilder::<Any>::new(format!("INSERT INTO {table} ("));
+    let mut columns = query.separated(", ");
+    for (column, _) in &values {
+        columns.push(column.as_str());
+    }
+    query.push(") VALUES (");
+    let mut binds = query.separated(", ");
+    for (column, value) in &values {
+        match value {
+            Value::String(text) => {
+                binds.push_bind(text.clone());
+            }
+            Value::Bool(flag) => {
+                binds.push_bind(i64::from(*flag));
+            }
+            Value::Number(number) => {
+                if let Some(integer) = number.as_i64() {
+                    binds.push_bind(integer);
+                } else {
+                    binds.push_bind(number.as_f64().unwrap_or_default());
+                }
+            }
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "backup table '{table}' has a non-scalar value in column '{column}'"
+                )));
+            }
+        }
     }
+    query.push(")");
 
-    let mut query = QueryBuilder::<Any>::new(
+    query.build().execute(&mut **tx).await?;
+    Ok(())
+}
+
+pub async fn list_project_webhooks(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<WebhookRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let webhooks = sqlx::query_as::<Any, WebhookRecord>(
         r#"
         SELECT
-            t.id,
-            t.project_id,
-            t.task_number,
-            t.title,
-            t.description,
-            t.status,
-            t.priority,
-            t.review_state,
-            t.sort_order,
-            t.created_by,
-            t.created_at,
-            t.updated_at
-        FROM tasks t
-        INNER JOIN projects p ON p.id = t.project_id
-        WHERE p.slug =
+            id,
+            project_id,
+            name,
+            url,
+            platform,
+            events,
+            secret,
+            active,
+            payload_format,
+            schema_version,
+            include_task_snapshot,
+            quiet_hours,
+            quiet_hours_timezone,
+            max_deliveries_per_minute,
+            timeout_secs,
+            max_retries,
+            retry_backoff_secs,
+            created_at,
+            updated_at
+        FROM webhooks
+        WHERE project_id = ?
+        ORDER BY created_at DESC
         "#,
-    );
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
 
-    query.push_bind(project_slug);
+    Ok(webhooks)
+}
 
-    if let Some(status) = filters.status {
-        query.push(" AND t.status = ");
-        query.push_bind(status);
-    }
+pub async fn get_project_webhook(
+    pool: &AnyPool,
+    project_slug: &str,
+    webhook_id: &str,
+) -> AppResult<WebhookRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    if let Some(review_state) = filters.review_state {
-        query.push(" AND t.review_state = ");
-        query.push_bind(review_state);
-    }
+    let webhook = sqlx::query_as::<Any, WebhookRecord>(
+        r#"
+        SELECT
+            id,
+            project_id,
+            name,
+            url,
+            platform,
+            events,
+            secret,
+            active,
+            payload_format,
+            schema_version,
+            include_task_snapshot,
+            quiet_hours,
+            quiet_hours_timezone,
+            max_deliveries_per_minute,
+            timeout_secs,
+            max_retries,
+            retry_backoff_secs,
+            created_at,
+            updated_at
+        FROM webhooks
+        WHERE project_id = ? AND id = ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(webhook_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("webhook '{webhook_id}' not found")))?;
 
-    if let Some(label) = filters.label {
-        query.push(
-            r#"
-            AND EXISTS (
-                SELECT 1
-                FROM task_labels l
-                WHERE l.task_id = t.id AND l.label =
-            "#,
-        );
-        query.push_bind(label);
-        query.push(')');
-    }
+    Ok(webhook)
+}
 
-    query.push(
+/// The current stored webhook, or `None` once it has been deleted.
+pub async fn find_webhook(pool: &AnyPool, webhook_id: &str) -> AppResult<Option<WebhookRecord>> {
+    let webhook = sqlx::query_as::<Any, WebhookRecord>(
         r#"
-        ORDER BY
-            CASE t.status
-                WHEN 'backlog' THEN 0
-                WHEN 'ready' THEN 1
-                WHEN 'in_progress' THEN 2
-                WHEN 'review' THEN 3
-                WHEN 'done' THEN 4
-                ELSE 5
-            END,
-            t.sort_order ASC,
-            t.created_at ASC
-        LIMIT
+        SELECT
+            id,
+            project_id,
+            name,
+            url,
+            platform,
+            events,
+            secret,
+            active,
+            payload_format,
+            schema_version,
+            include_task_snapshot,
+            quiet_hours,
+            quiet_hours_timezone,
+            max_deliveries_per_minute,
+            timeout_secs,
+            max_retries,
+            retry_backoff_secs,
+            created_at,
+            updated_at
+        FROM webhooks
+        WHERE id = ?
         "#,
-    );
-    query.push_bind(limit);
-    query.push(" OFFSET ");
-    query.push_bind(offset);
+    )
+    .bind(webhook_id)
+    .fetch_optional(pool)
+    .await?;
 
-    let tasks = query.build_query_as::<TaskRecord>().fetch_all(pool).await?;
-    Ok(tasks)
+    Ok(webhook)
 }
 
-pub async fn create_task(
+pub async fn create_webhook(
     pool: &AnyPool,
+    keyring: &Keyring,
     project_slug: &str,
-    input: NewTaskInput,
-) -> AppResult<TaskRecord> {
-    validate_status(&input.status)?;
-    validate_priority(&input.priority)?;
-    validate_review_state(&input.review_state)?;
-
-    let title = input.title.trim().to_string();
-    if title.is_empty() {
+    input: CreateWebhookInput,
+    actor: &str,
+) -> AppResult<WebhookRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
         return Err(AppError::BadRequest(
-            "task title cannot be empty".to_string(),
+            "webhook name cannot be empty".to_string(),
         ));
     }
 
+    let url = normalize_webhook_url(&input.url)?;
+    let platform = normalize_webhook_platform(&input.platform)?;
+    let events = normalize_webhook_events(input.events)?;
+    let events_json = serde_json::to_string(&events).map_err(|error| {
+        tracing::error!(error = ?error, "failed to serialize webhook events");
+        AppError::Internal
+    })?;
+    let secret = seal_webhook_secret(keyring, input.secret)?;
+    let payload_format = normalize_webhook_payload_format(
+        &platform,
+        input.payload_format.as_deref().unwrap_or("lattice"),
+    )?;
+    let schema_version = validate_webhook_schema_version(
+        input
+            .schema_version
+            .unwrap_or(LATEST_WEBHOOK_SCHEMA_VERSION),
+    )?;
+
+    let quiet_hours = normalize_quiet_hours(input.quiet_hours.as_deref())?;
+    let quiet_hours_timezone =
+        normalize_timezone(input.quiet_hours_timezone.as_deref().unwrap_or("UTC"))?;
+    let max_deliveries_per_minute =
+        validate_max_deliveries_per_minute(input.max_deliveries_per_minute.unwrap_or(0))?;
+    let (timeout_secs, max_retries, retry_backoff_secs) = validate_webhook_delivery_policy(
+        input.timeout_secs,
+        input.max_retries,
+        input.retry_backoff_secs,
+    )?;
+
+    let webhook_id = Uuid::new_v4().to_string();
     let now = now_timestamp();
-    let task_id = Uuid::new_v4().to_string();
+    let webhook = WebhookRecord {
+        id: webhook_id.clone(),
+        project_id: project_id.clone(),
+        name,
+        url,
+        platform,
+        events: events_json,
+        secret,
+        active: i64::from(input.active),
+        payload_format,
+        schema_version,
+        include_task_snapshot: i64::from(input.include_task_snapshot),
+        quiet_hours,
+        quiet_hours_timezone,
+        max_deliveries_per_minute,
+        timeout_secs,
+        max_retries,
+        retry_backoff_secs,
+        created_at: now.clone(),
+        updated_at: now,
+    };
 
     let mut tx = pool.begin().await?;
-
-    let project_id: String = sqlx::query_scalar(
+    sqlx::query(
         r#"
-        SELECT id
-        FROM projects
-        WHERE slug = ?
+        INSERT INTO webhooks (
+            id,
+            project_id,
+            name,
+            url,
+            platform,
+            events,
+            secret,
+            active,
+            payload_format,
+            schema_version,
+            include_task_snapshot,
+            quiet_hours,
+            quiet_hours_timezone,
+            max_deliveries_per_minute,
+            timeout_secs,
+            max_retries,
+            retry_backoff_secs,
+            created_at,
+            updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(project_slug)
-    .fetch_optional(&mut *tx)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("project '{project_slug}' not found")))?;
-
-    sqlx::query("UPDATE projects SET task_counter = task_counter + 1, updated_at = ? WHERE id = ?")
-        .bind(&now)
-        .bind(&project_id)
-        .execute(&mut *tx)
-        .await?;
-
-    let task_number: i64 = sqlx::query_scalar("SELECT task_counter FROM projects WHERE id = ?")
-        .bind(&project_id)
-        .fetch_one(&mut *tx)
-        .await?;
+    .bind(&webhook.id)
+    .bind(&webhook.project_id)
+    .bind(&webhook.name)
+    .bind(&webhook.url)
+    .bind(&webhook.platform)
+    .bind(&webhook.events)
+    .bind(&webhook.secret)
+    .bind(webhook.active)
+    .bind(&webhook.payload_format)
+    .bind(webhook.schema_version)
+    .bind(webhook.include_task_snapshot)
+    .bind(&webhook.quiet_hours)
+    .bind(&webhook.quiet_hours_timezone)
+    .bind(webhook.max_deliveries_per_minute)
+    .bind(webhook.timeout_secs)
+    .bind(webhook.max_retries)
+    .bind(webhook.retry_backoff_secs)
+    .bind(&webhook.created_at)
+    .bind(&webhook.updated_at)
+    .execute(&mut *tx)
+    .await?;
 
-    let sort_order: f64 = sqlx::query_scalar(
-        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
+    let mut detail = webhook_audit_fields(&webhook)?;
+    detail.insert("webhook_id".to_string(), serde_json::json!(webhook.id));
+    insert_project_event(
+        &mut tx,
+        &project_id,
+        actor,
+        EventKind::WebhookCreated,
+        Value::Object(detail),
     )
-    .bind(&project_id)
-    .bind(&input.status)
-    .fetch_one(&mut *tx)
     .await?;
+    tx.commit().await?;
 
-    sqlx::query(
+    get_project_webhook(pool, project_slug, &webhook_id).await
+}
+
+/// Runs every check `create_webhook` makes and reports all failures, without creating the
+/// webhook. The preview shows its settings the way `webhook.created` events do.
+pub async fn dry_run_create_webhook(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: CreateWebhookInput,
+) -> AppResult<DryRunReport> {
+    let mut report = DryRunReport::default();
+    report.check(project_id_by_slug(pool, project_slug).await)?;
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        report.reject(AppError::BadRequest(
+            "webhook name cannot be empty".to_string(),
+        ));
+    }
+
+    let url = report.check(normalize_webhook_url(&input.url))?;
+    let platform = report.check(normalize_webhook_platform(&input.platform))?;
+    let events = report.check(normalize_webhook_events(input.events))?;
+    let payload_format = match platform.as_deref() {
+        Some(platform) => report.check(normalize_webhook_payload_format(
+            platform,
+            input.payload_format.as_deref().unwrap_or("lattice"),
+        ))?,
+        None => None,
+    };
+    let schema_version = report.check(validate_webhook_schema_version(
+        input
+            .schema_version
+            .unwrap_or(LATEST_WEBHOOK_SCHEMA_VERSION),
+    ))?;
+    let quiet_hours = report.check(normalize_quiet_hours(input.quiet_hours.as_deref()))?;
+    let quiet_hours_timezone = report.check(normalize_timezone(
+        input.quiet_hours_timezone.as_deref().unwrap_or("UTC"),
+    ))?;
+    let max_deliveries_per_minute = report.check(validate_max_deliveries_per_minute(
+        input.max_deliveries_per_minute.unwrap_or(0),
+    ))?;
+    let delivery_policy = report.check(validate_webhook_delivery_policy(
+        input.timeout_secs,
+        input.max_retries,
+        input.retry_backoff_secs,
+    ))?;
+    let secret = normalize_optional_secret(input.secret);
+
+    if events
+        .as_ref()
+        .is_some_and(|events| events.iter().any(|event| event == "*"))
+    {
+        report.warn("subscribed to '*', which includes event types added in later releases");
+    }
+    if secret.is_none() && platform.as_deref() == Some("generic") {
+        report.warn("without a secret, deliveries carry no X-Lattice-Signature");
+    }
+    if url.as_deref().is_some_and(|url| url.starts_with("http://")) {
+        report.warn("url uses plain http, so payloads are sent unencrypted");
+    }
+    if !input.active {
+        report.warn("webhook will be created inactive and deliver nothing");
+    }
+
+    let (timeout_secs, max_retries, retry_backoff_secs) = delivery_policy.unwrap_or_default();
+    Ok(report.finish(serde_json::json!({
+        "name": name,
+        "url": url.as_deref().map(redact::url),
+        "platform": platform,
+        "events": events,
+        "has_secret": secret.is_some(),
+        "active": input.active,
+        "payload_format": payload_format,
+        "schema_version": schema_version,
+        "include_task_snapshot": input.include_task_snapshot,
+        "quiet_hours": quiet_hours.flatten(),
+        "quiet_hours_timezone": quiet_hours_timezone,
+        "max_deliveries_per_minute": max_deliveries_per_minute,
+        "timeout_secs": timeout_secs,
+        "max_retries": max_retries,
+        "retry_backoff_secs": retry_backoff_secs,
+    })))
+}
+
+pub async fn update_webhook(
+    pool: &AnyPool,
+    keyring: &Keyring,
+    project_slug: &str,
+    webhook_id: &str,
+    input: UpdateWebhookInput,
+    actor: &str,
+) -> AppResult<WebhookRecord> {
+    let existing = get_project_webhook(pool, project_slug, webhook_id).await?;
+    let previous = existing.clone();
+
+    let name = match input.name {
+        Some(value) => {
+            let trimmed = value.trim().to_string();
+            if trimmed.is_empty() {
+                return Err(AppError::BadRequest(
+                    "webhook name cannot be empty".to_string(),
+                ));
+            }
+            trimmed
+        }
+        None => existing.name,
+    };
+
+    let url = match input.url {
+        Some(value) => normalize_webhook_url(&value)?,
+        None => existing.url,
+    };
+
+    let platform = match input.platform {
+        Some(value) => normalize_webhook_platform(&value)?,
+        None => existing.platform,
+    };
+
+    let events = match input.events {
+        Some(value) => {
+            let normalized = normalize_webhook_events(value)?;
+            serde_json::to_string(&normalized).map_err(|error| {
+                tracing::error!(error = ?error, "failed to serialize webhook events");
+                AppError::Internal
+            })?
+        }
+        None => existing.events,
+    };
+
+    let secret = match input.secret {
+        Some(value) => seal_webhook_secret(keyring, Some(value))?,
+        None => existing.secret,
+    };
+
+    let active = input.active.unwrap_or(existing.active == 1);
+    let payload_format = normalize_webhook_payload_format(
+        &platform,
+        input
+            .payload_format
+            .as_deref()
+            .unwrap_or(&existing.payload_format),
+    )?;
+    let schema_version =
+        validate_webhook_schema_version(input.schema_version.unwrap_or(existing.schema_version))?;
+    let include_task_snapshot = input
+        .include_task_snapshot
+        .unwrap_or(existing.include_task_snapshot == 1);
+    let quiet_hours = match input.quiet_hours {
+        Some(value) => normalize_quiet_hours(Some(&value))?,
+        None => existing.quiet_hours,
+    };
+    let quiet_hours_timezone = match input.quiet_hours_timezone {
+        Some(value) => normalize_timezone(&value)?,
+        None => existing.quiet_hours_timezone,
+    };
+    let max_deliveries_per_minute = validate_max_deliveries_per_minute(
+        input
+            .max_deliveries_per_minute
+            .unwrap_or(existing.max_deliveries_per_minute),
+    )?;
+    let (timeout_secs, max_retries, retry_backoff_secs) = validate_webhook_delivery_policy(
+        input.timeout_secs.unwrap_or(existing.timeout_secs),
+        input.max_retries.unwrap_or(existing.max_retries),
+        input
+            .retry_backoff_secs
+            .unwrap_or(existing.retry_backoff_secs),
+    )?;
+    let updated = WebhookRecord {
+        name,
+        url,
+        platform,
+        events,
+        secret,
+        active: i64::from(active),
+        payload_format,
+        schema_version,
+        include_task_snapshot: i64::from(include_task_snapshot),
+        quiet_hours,
+        quiet_hours_timezone,
+        max_deliveries_per_minute,
+        timeout_secs,
+        max_retries,
+        retry_backoff_secs,
+        updated_at: now_timestamp(),
+        ..existing
+    };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
         r#"
-        INSERT INTO tasks (
-            id,
-            project_id,
-            task_number,
-            title,
-            description,
-            status,
-            priority,
-            review_state,
-            sort_order,
-            created_by,
-            created_at,
-            updated_at
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        UPDATE webhooks
+        SET name = ?, url = ?, platform = ?, events = ?, secret = ?, active = ?,
+            payload_format = ?, schema_version = ?, include_task_snapshot = ?, quiet_hours = ?,
+            quiet_hours_timezone = ?, max_deliveries_per_minute = ?, timeout_secs = ?,
+            max_retries = ?, retry_backoff_secs = ?, updated_at = ?
+        WHERE id = ? AND project_id = ?
         "#,
     )
-    .bind(&task_id)
-    .bind(&project_id)
-    .bind(task_number)
-    .bind(&title)
-    .bind(input.description)
-    .bind(&input.status)
-    .bind(&input.priority)
-    .bind(&input.review_state)
-    .bind(sort_order)
-    .bind(&input.created_by)
-    .bind(&now)
-    .bind(&now)
+    .bind(&updated.name)
+    .bind(&updated.url)
+    .bind(&updated.platform)
+    .bind(&updated.events)
+    .bind(&updated.secret)
+    .bind(updated.active)
+    .bind(&updated.payload_format)
+    .bind(updated.schema_version)
+    .bind(updated.include_task_snapshot)
+    .bind(&updated.quiet_hours)
+    .bind(&updated.quiet_hours_timezone)
+    .bind(updated.max_deliveries_per_minute)
+    .bind(updated.timeout_secs)
+    .bind(updated.max_retries)
+    .bind(updated.retry_backoff_secs)
+    .bind(&updated.updated_at)
+    .bind(webhook_id)
+    .bind(&updated.project_id)
     .execute(&mut *tx)
     .await?;
 
-    let labels = normalized_labels(input.labels);
-    for label in labels {
-        sqlx::query("INSERT INTO task_labels (task_id, label) VALUES (?, ?)")
-            .bind(&task_id)
-            .bind(label)
-            .execute(&mut *tx)
-            .await?;
+    let before = webhook_audit_fields(&previous)?;
+    let after = webhook_audit_fields(&updated)?;
+    let changes: serde_json::Map<String, Value> = after
+        .iter()
+        .filter(|(field, value)| before.get(*field) != Some(*value))
+        .map(|(field, value)| {
+            let change = serde_json::json!({ "from": before[field], "to": value });
+            (field.clone(), change)
+        })
+        .collect();
+    let secret_changed = previous.secret != updated.secret;
+    if !changes.is_empty() || secret_changed {
+        insert_project_event(
+            &mut tx,
+            &updated.project_id,
+            actor,
+            EventKind::WebhookUpdated,
+            serde_json::json!({
+                "webhook_id": webhook_id,
+                "name": updated.name,
+                "changes": changes,
+                "secret_changed": secret_changed,
+            }),
+        )
+        .await?;
     }
+    tx.commit().await?;
 
-    insert_history(
+    get_project_webhook(pool, project_slug, webhook_id).await
+}
+
+pub async fn delete_webhook(
+    pool: &AnyPool,
+    project_slug: &str,
+    webhook_id: &str,
+    actor: &str,
+) -> AppResult<()> {
+    let existing = get_project_webhook(pool, project_slug, webhook_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM webhooks WHERE id = ? AND project_id = ?")
+        .bind(webhook_id)
+        .bind(&existing.project_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "webhook '{webhook_id}' not found"
+        )));
+    }
+
+    let mut detail = webhook_audit_fields(&existing)?;
+    detail.insert("webhook_id".to_string(), serde_json::json!(webhook_id));
+    insert_project_event(
         &mut tx,
-        &task_id,
-        &input.created_by,
-        "task.created",
-        serde_json::json!({ "status": input.status, "priority": input.priority }),
+        &existing.project_id,
+        actor,
+        EventKind::WebhookDeleted,
+        Value::Object(detail),
     )
     .await?;
-
     tx.commit().await?;
 
-    get_task_record_by_id(pool, &task_id).await
+    Ok(())
 }
 
-pub async fn get_task_details(
-    pool: &AnyPool,
-    project_slug: &str,
-    task_ref: &str,
-) -> AppResult<TaskDetails> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let task = get_task_record_by_id(pool, &task_id).await?;
+/// The settings of a webhook as they appear in its audit events. The secret is reduced to
+/// whether one is set, and the url to its origin, since chat webhook urls carry tokens.
+fn webhook_audit_fields(webhook: &WebhookRecord) -> AppResult<serde_json::Map<String, Value>> {
+    let fields = [
+        ("name", serde_json::json!(webhook.name)),
+        ("url", serde_json::json!(redact::url(&webhook.url))),
+        ("platform", serde_json::json!(webhook.platform)),
+        (
+            "events",
+            serde_json::json!(parse_webhook_events(&webhook.events)?),
+        ),
+        ("active", serde_json::json!(webhook.active == 1)),
+        ("payload_format", serde_json::json!(webhook.payload_format)),
+        ("schema_version", serde_json::json!(webhook.schema_version)),
+        (
+            "include_task_snapshot",
+            serde_json::json!(webhook.include_task_snapshot == 1),
+        ),
+        ("quiet_hours", serde_json::json!(webhook.quiet_hours)),
+        (
+            "quiet_hours_timezone",
+            serde_json::json!(webhook.quiet_hours_timezone),
+        ),
+        (
+            "max_deliveries_per_minute",
+            serde_json::json!(webhook.max_deliveries_per_minute),
+        ),
+        ("timeout_secs", serde_json::json!(webhook.timeout_secs)),
+        ("max_retries", serde_json::json!(webhook.max_retries)),
+        (
+            "retry_backoff_secs",
+            serde_json::json!(webhook.retry_backoff_secs),
+        ),
+        (
+            "has_secret",
+            serde_json::json!(webhook
+                .secret
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())),
+        ),
+    ];
+    Ok(fields
+        .into_iter()
+        .map(|(field, value)| (field.to_string(), value))
+        .collect())
+}
 
-    let labels: Vec<String> =
-        sqlx::query_scalar("SELECT label FROM task_labels WHERE task_id = ? ORDER BY label ASC")
-            .bind(&task.id)
-            .fetch_all(pool)
+/// Moves every webhook and inbound hook secret onto the current `LATTICE_SECRET_KEY`,
+/// encrypting any still in plaintext. Returns how many were rewritten. Secrets under a key
+/// that is no longer configured are left as they are and logged.
+pub async fn rewrap_stored_secrets(pool: &AnyPool, keyring: &Keyring) -> AppResult<u64> {
+    let mut rewritten = 0;
+    for table in ["webhooks", "inbound_hooks"] {
+        let stored = sqlx::query_as::<Any, (String, String)>(&format!(
+            "SELECT id, secret FROM {table} WHERE secret IS NOT NULL AND secret <> ''"
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        for (id, secret) in stored {
+            let rewrapped = match keyring.rewrap(&secret) {
+                Ok(Some(rewrapped)) => rewrapped,
+                Ok(None) => continue,
+                Err(_) => {
+                    tracing::warn!(table, id, "secret could not be moved to the current key");
+                    continue;
+                }
+            };
+            sqlx::query(&format!(
+                "UPDATE {table} SET secret = ? WHERE id = ? AND secret = ?"
+            ))
+            .bind(rewrapped)
+            .bind(&id)
+            .bind(secret)
+            .execute(pool)
             .await?;
+            rewritten += 1;
+        }
+    }
 
-    let subtasks = sqlx::query_as::<Any, SubtaskRecord>(
+    Ok(rewritten)
+}
+
+pub async fn create_inbound_hook(
+    pool: &AnyPool,
+    keyring: &Keyring,
+    project_slug: &str,
+    input: CreateInboundHookInput,
+) -> AppResult<InboundHookRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let name = normalize_inbound_hook_name(&input.name)?;
+    if !INBOUND_HOOK_KINDS.contains(&input.kind.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "invalid inbound hook kind '{}', expected one of {}",
+            input.kind,
+            INBOUND_HOOK_KINDS.join(", ")
+        )));
+    }
+    let template = validate_inbound_template(&input.kind, &input.template)?;
+    let secret = keyring.seal(&input.secret)?;
+
+    let hook_id = Uuid::new_v4().to_string();
+    let now = now_timestamp();
+    sqlx::query(
         r#"
-        SELECT id, task_id, title, done, sort_order, created_at
-        FROM subtasks
-        WHERE task_id = ?
-        ORDER BY sort_order ASC, created_at ASC
+        INSERT INTO inbound_hooks (
+            id, project_id, name, kind, template, secret, active, created_at, updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(&task.id)
-    .fetch_all(pool)
+    .bind(&hook_id)
+    .bind(&project_id)
+    .bind(&name)
+    .bind(&input.kind)
+    .bind(&template)
+    .bind(&secret)
+    .bind(i64::from(input.active))
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
     .await?;
 
-    let open_questions = sqlx::query_as::<Any, OpenQuestionRecord>(
+    get_inbound_hook(pool, project_slug, &hook_id).await
+}
+
+pub async fn list_inbound_hooks(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<InboundHookRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let hooks = sqlx::query_as::<Any, InboundHookRecord>(
         r#"
-        SELECT id, task_id, question, context, answer, status, asked_by, resolved_by, created_at, resolved_at
-        FROM open_questions
-        WHERE task_id = ?
+        SELECT id, name, kind, template, secret, active, created_at, updated_at
+        FROM inbound_hooks
+        WHERE project_id = ?
         ORDER BY created_at DESC
         "#,
     )
-    .bind(&task.id)
+    .bind(project_id)
     .fetch_all(pool)
     .await?;
 
-    let attachments = sqlx::query_as::<Any, AttachmentRecord>(
+    Ok(hooks)
+}
+
+pub async fn get_inbound_hook(
+    pool: &AnyPool,
+    project_slug: &str,
+    hook_id: &str,
+) -> AppResult<InboundHookRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    sqlx::query_as::<Any, InboundHookRecord>(
         r#"
-        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
-        FROM attachments
-        WHERE task_id = ?
-        ORDER BY created_at DESC
+        SELECT id, name, kind, template, secret, active, created_at, updated_at
+        FROM inbound_hooks
+        WHERE project_id = ? AND id = ?
         "#,
     )
-    .bind(&task.id)
-    .fetch_all(pool)
-    .await?;
+    .bind(project_id)
+    .bind(hook_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("inbound hook '{hook_id}' not found")))
+}
 
-    let history = sqlx::query_as::<Any, TaskHistoryRecord>(
+pub async fn update_inbound_hook(
+    pool: &AnyPool,
+    keyring: &Keyring,
+    project_slug: &str,
+    hook_id: &str,
+    input: UpdateInboundHookInput,
+) -> AppResult<InboundHookRecord> {
+    let existing = get_inbound_hook(pool, project_slug, hook_id).await?;
+    let name = match input.name {
+        Some(value) => normalize_inbound_hook_name(&value)?,
+        None => existing.name,
+    };
+    let template = match input.template {
+        Some(value) => validate_inbound_template(&existing.kind, &value)?,
+        None => existing.template,
+    };
+    let secret = match input.secret {
+        Some(value) => keyring.seal(&value)?,
+        None => existing.secret,
+    };
+    let active = input.active.map_or(existing.active, i64::from);
+
+    sqlx::query(
         r#"
-        SELECT id, task_id, actor, action, detail, created_at
-        FROM task_history
-        WHERE task_id = ?
-        ORDER BY created_at DESC
+        UPDATE inbound_hooks
+        SET name = ?, template = ?, secret = ?, active = ?, updated_at = ?
+        WHERE id = ?
         "#,
     )
-    .bind(&task.id)
-    .fetch_all(pool)
+    .bind(&name)
+    .bind(&template)
+    .bind(&secret)
+    .bind(active)
+    .bind(now_timestamp())
+    .bind(hook_id)
+    .execute(pool)
     .await?;
 
-    Ok(TaskDetails {
-        task,
-        labels,
-        subtasks,
-        open_questions,
-        attachments,
-        history,
-    })
+    get_inbound_hook(pool, project_slug, hook_id).await
 }
 
-pub async fn add_subtask(
+pub async fn delete_inbound_hook(
     pool: &AnyPool,
     project_slug: &str,
-    task_ref: &str,
-    title: &str,
-    actor: &str,
-) -> AppResult<SubtaskRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let trimmed_title = title.trim().to_string();
-    if trimmed_title.is_empty() {
-        return Err(AppError::BadRequest(
-            "subtask title cannot be empty".to_string(),
-        ));
-    }
-
-    let now = now_timestamp();
-    let mut tx = pool.begin().await?;
+    hook_id: &str,
+) -> AppResult<()> {
+    let hook = get_inbound_hook(pool, project_slug, hook_id).await?;
+    sqlx::query("DELETE FROM inbound_hooks WHERE id = ?")
+        .bind(hook.id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
 
-    let sort_order: f64 = sqlx::query_scalar(
-        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM subtasks WHERE task_id = ?",
-    )
-    .bind(&task_id)
-    .fetch_one(&mut *tx)
-    .await?;
+pub async fn list_active_project_webhooks(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<WebhookRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    let subtask_id = Uuid::new_v4().to_string();
-    sqlx::query(
+    let webhooks = sqlx::query_as::<Any, WebhookRecord>(
         r#"
-        INSERT INTO subtasks (id, task_id, title, done, sort_order, created_at)
-        VALUES (?, ?, ?, 0, ?, ?)
+        SELECT
+            id,
+            project_id,
+            name,
+            url,
+            platform,
+            events,
+            secret,
+            active,
+            payload_format,
+            schema_version,
+            include_task_snapshot,
+            quiet_hours,
+            quiet_hours_timezone,
+            max_deliveries_per_minute,
+            timeout_secs,
+            max_retries,
+            retry_backoff_secs,
+            created_at,
+            updated_at
+        FROM webhooks
+        WHERE project_id = ? AND active = 1
+        ORDER BY created_at DESC
         "#,
     )
-    .bind(&subtask_id)
-    .bind(&task_id)
-    .bind(&trimmed_title)
-    .bind(sort_order)
-    .bind(&now)
-    .execute(&mut *tx)
+    .bind(project_id)
+    .fetch_all(pool)
     .await?;
 
-    insert_history(
-        &mut tx,
-        &task_id,
-        actor,
-        "subtask.created",
-        serde_json::json!({
-            "subtask_id": subtask_id,
-            "title": trimmed_title,
-        }),
+    Ok(webhooks)
+}
+
+pub async fn list_spec_sections(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+) -> AppResult<Vec<SpecSectionRecord>> {
+    let sections = spec_section_rows(pool, project_slug).await?;
+    Ok(sections
+        .into_iter()
+        .map(|record| with_spec_counts(limits, record))
+        .collect())
+}
+
+/// A project's spec sections in display order, without the derived counts.
+async fn spec_section_rows(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<SpecSectionRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let sections = sqlx::query_as::<Any, SpecSectionRecord>(
+        r#"
+        SELECT
+            id,
+            project_id,
+            section,
+            content,
+            updated_at,
+            CASE WHEN lock_expires_at > ? THEN locked_by END AS locked_by,
+            CASE WHEN lock_expires_at > ? THEN lock_expires_at END AS lock_expires_at
+        FROM spec_sections
+        WHERE project_id = ?
+        ORDER BY
+            CASE section
+                WHEN 'overview' THEN 0
+                WHEN 'requirements' THEN 1
+                WHEN 'architecture' THEN 2
+                WHEN 'technical_design' THEN 3
+                WHEN 'open_decisions' THEN 4
+                WHEN 'references' THEN 5
+                ELSE 6
+            END
+        "#,
     )
+    .bind(now_timestamp())
+    .bind(now_timestamp())
+    .bind(project_id)
+    .fetch_all(pool)
     .await?;
 
-    tx.commit().await?;
-
-    get_subtask_by_id(pool, &task_id, &subtask_id).await
+    Ok(sections)
 }
 
-pub async fn update_subtask(
+pub async fn get_spec_section(
     pool: &AnyPool,
+    limits: &FieldLimitConfig,
     project_slug: &str,
-    task_ref: &str,
-    subtask_id: &str,
-    input: UpdateSubtaskInput,
-) -> AppResult<SubtaskRecord> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let existing = get_subtask_by_id(pool, &task_id, subtask_id).await?;
+    section: &str,
+) -> AppResult<SpecSectionRecord> {
+    validate_spec_section(section)?;
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    let title = match input.title {
-        Some(value) => {
-            let trimmed = value.trim().to_string();
-            if trimmed.is_empty() {
-                return Err(AppError::BadRequest(
-                    "subtask title cannot be empty".to_string(),
-                ));
-            }
-            trimmed
-        }
-        None => existing.title.clone(),
-    };
+    let record = sqlx::query_as::<Any, SpecSectionRecord>(
+        r#"
+        SELECT
+            id,
+            project_id,
+            section,
+            content,
+            updated_at,
+            CASE WHEN lock_expires_at > ? THEN locked_by END AS locked_by,
+            CASE WHEN lock_expires_at > ? THEN lock_expires_at END AS lock_expires_at
+        FROM spec_sections
+        WHERE project_id = ? AND section = ?
+        "#,
+    )
+    .bind(now_timestamp())
+    .bind(now_timestamp())
+    .bind(project_id)
+    .bind(section)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| {
+        AppError::NotFound(format!(
+            "spec section '{section}' not found for project '{project_slug}'"
+        ))
+    })?;
 
-    let done = input.done.map_or(existing.done, i64::from);
-    let sort_order = input.sort_order.unwrap_or(existing.sort_order);
+    Ok(with_spec_counts(limits, record))
+}
 
-    let mut tx = pool.begin().await?;
+/// Lints every spec section, or just `section`, against the project's tasks and attachments.
+pub async fn lint_spec(
+    pool: &AnyPool,
+    project_slug: &str,
+    section: Option<&str>,
+) -> AppResult<SpecLintReport> {
+    if let Some(section) = section {
+        validate_spec_section(section)?;
+    }
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let sections = spec_section_rows(pool, project_slug)
+        .await?
+        .into_iter()
+        .filter(|record| section.is_none_or(|section| record.section == section))
+        .collect::<Vec<_>>();
 
-    sqlx::query(
-        "UPDATE subtasks SET title = ?, done = ?, sort_order = ? WHERE id = ? AND task_id = ?",
+    let task_numbers =
+        sqlx::query_scalar::<Any, i64>("SELECT task_number FROM tasks WHERE project_id = ?")
+            .bind(&project_id)
+            .fetch_all(pool)
+            .await?;
+    let attachment_ids = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT a.id
+        FROM attachments a
+        INNER JOIN tasks t ON t.id = a.task_id
+        WHERE t.project_id = ?
+        "#,
     )
-    .bind(&title)
-    .bind(done)
-    .bind(sort_order)
-    .bind(subtask_id)
-    .bind(&task_id)
-    .execute(&mut *tx)
+    .bind(&project_id)
+    .fetch_all(pool)
     .await?;
-
-    insert_history(
-        &mut tx,
-        &task_id,
-        &input.actor,
-        "subtask.updated",
-        serde_json::json!({
-            "subtask_id": subtask_id,
-            "done": done == 1,
-        }),
+    let old_slugs = sqlx::query_scalar::<Any, String>(
+        "SELECT slug FROM project_slug_aliases WHERE project_id = ?",
     )
+    .bind(&project_id)
+    .fetch_all(pool)
     .await?;
+    let context = LintContext {
+        project_slug: project_slug.to_string(),
+        old_slugs: old_slugs.into_iter().collect(),
+        task_numbers: task_numbers.into_iter().collect(),
+        attachment_ids: attachment_ids.into_iter().collect(),
+    };
 
-    tx.commit().await?;
+    let findings = sections
+        .iter()
+        .flat_map(|record| spec_lint::lint_section(&record.section, &record.content, &context))
+        .collect();
+    Ok(SpecLintReport {
+        project_slug: project_slug.to_string(),
+        sections_checked: sections.len(),
+        findings,
+    })
+}
 
-    get_subtask_by_id(pool, &task_id, subtask_id).await
+fn with_spec_counts(limits: &FieldLimitConfig, mut record: SpecSectionRecord) -> SpecSectionRecord {
+    record.word_count = record.content.split_whitespace().count();
+    record.char_count = record.content.chars().count();
+    record.max_length = limits.spec_section_limit(&record.section).0;
+    record
 }
 
-pub async fn delete_subtask(
+/// Takes or renews a soft edit lock on a section for `ttl_secs` (default
+/// `DEFAULT_SPEC_LOCK_SECS`). Fails with a conflict while someone else holds an unexpired lock.
+pub async fn lock_spec_section(
     pool: &AnyPool,
+    limits: &FieldLimitConfig,
     project_slug: &str,
-    task_ref: &str,
-    subtask_id: &str,
-    actor: &str,
-) -> AppResult<()> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-
-    let mut tx = pool.begin().await?;
-
-    let result = sqlx::query("DELETE FROM subtasks WHERE id = ? AND task_id = ?")
-        .bind(subtask_id)
-        .bind(&task_id)
-        .execute(&mut *tx)
-        .await?;
-
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "subtask '{subtask_id}' not found on task '{task_ref}'"
+    section: &str,
+    holder: &str,
+    ttl_secs: Option<i64>,
+) -> AppResult<SpecSectionRecord> {
+    validate_spec_section(section)?;
+    let holder = holder.trim();
+    if holder.is_empty() {
+        return Err(AppError::BadRequest(
+            "lock holder cannot be empty".to_string(),
+        ));
+    }
+    let ttl_secs = ttl_secs.unwrap_or(DEFAULT_SPEC_LOCK_SECS);
+    if !(1..=MAX_SPEC_LOCK_SECS).contains(&ttl_secs) {
+        return Err(AppError::BadRequest(format!(
+            "ttl_secs must be between 1 and {MAX_SPEC_LOCK_SECS}"
         )));
     }
 
-    insert_history(
-        &mut tx,
-        &task_id,
-        actor,
-        "subtask.deleted",
-        serde_json::json!({ "subtask_id": subtask_id }),
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let now = now_timestamp();
+    let expires_at = (Utc::now() + chrono::Duration::seconds(ttl_secs))
+        .to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let mut tx = pool.begin().await?;
+    let locked = sqlx::query(
+        r#"
+        UPDATE spec_sections
+        SET locked_by = ?, lock_expires_at = ?
+        WHERE project_id = ? AND section = ?
+          AND (locked_by IS NULL OR locked_by = ? OR lock_expires_at <= ?)
+        "#,
     )
+    .bind(holder)
+    .bind(&expires_at)
+    .bind(&project_id)
+    .bind(section)
+    .bind(holder)
+    .bind(&now)
+    .execute(&mut *tx)
     .await?;
-
+    if locked.rows_affected() == 0 {
+        drop(tx);
+        let current = get_spec_section(pool, limits, project_slug, section).await?;
+        return Err(spec_lock_conflict(&current));
+    }
+    touch_content_version(&mut tx, &project_id).await?;
     tx.commit().await?;
-    Ok(())
+
+    get_spec_section(pool, limits, project_slug, section).await
 }
 
-pub async fn update_task(
+/// Releases a section's lock. Only the holder may release it unless `force` is set; releasing
+/// a section that is not locked is a no-op.
+pub async fn unlock_spec_section(
     pool: &AnyPool,
+    limits: &FieldLimitConfig,
     project_slug: &str,
-    task_ref: &str,
-    input: UpdateTaskInput,
-) -> AppResult<TaskRecord> {
-    let details = get_task_details(pool, project_slug, task_ref).await?;
-    let task = details.task;
-
-    let title = match input.title {
-        Some(value) => {
-            let trimmed = value.trim().to_string();
-            if trimmed.is_empty() {
-                return Err(AppError::BadRequest(
-                    "task title cannot be empty".to_string(),
-                ));
-            }
-            trimmed
+    section: &str,
+    holder: &str,
+    force: bool,
+) -> AppResult<SpecSectionRecord> {
+    let current = get_spec_section(pool, limits, project_slug, section).await?;
+    match current.locked_by.as_deref() {
+        None => return Ok(current),
+        Some(locked_by) if locked_by != holder && !force => {
+            return Err(spec_lock_conflict(&current));
         }
-        None => task.title,
-    };
+        Some(_) => {}
+    }
 
-    let description = input.description.unwrap_or(task.description);
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE spec_sections SET locked_by = NULL, lock_expires_at = NULL WHERE id = ?")
+        .bind(&current.id)
+        .execute(&mut *tx)
+        .await?;
+    touch_content_version(&mut tx, &current.project_id).await?;
+    tx.commit().await?;
 
-    let status = match input.status {
-        Some(value) => {
-            validate_status(&value)?;
-            value
-        }
-        None => task.status,
-    };
-
-    let priority = match input.priority {
-        Some(value) => {
-            validate_priority(&value)?;
-            value
-        }
-        None => task.priority,
-    };
+    get_spec_section(pool, limits, project_slug, section).await
+}
 
-    let review_state = match input.review_state {
-        Some(value) => {
-            validate_review_state(&value)?;
-            value
-        }
-        None => task.review_state,
-    };
+fn spec_lock_conflict(section: &SpecSectionRecord) -> AppError {
+    AppError::Conflict(format!(
+        "spec section '{}' is locked by '{}' until {}; pass force to override",
+        section.section,
+        section.locked_by.as_deref().unwrap_or_default(),
+        section.lock_expires_at.as_deref().unwrap_or_default()
+    ))
+}
 
+pub async fn update_spec_section(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    section: &str,
+    content: &str,
+    edited_by: &str,
+    force: bool,
+) -> AppResult<SpecSectionRecord> {
+    validate_spec_section(section)?;
+    let (max, env) = limits.spec_section_limit(section);
+    check_length(&format!("spec section '{section}'"), content, max, env)?;
+    let current = get_spec_section(pool, limits, project_slug, section).await?;
+    let overridden_lock = current
+        .locked_by
+        .clone()
+        .filter(|holder| holder != edited_by);
+    if overridden_lock.is_some() && !force {
+        return Err(spec_lock_conflict(&current));
+    }
+    let project_id = current.project_id;
+    let summary = diff::summarize(&current.content, content);
     let now = now_timestamp();
-    let mut tx = pool.begin().await?;
 
-    sqlx::query(
+    let mut tx = pool.begin().await?;
+    let updated = sqlx::query(
         r#"
-        UPDATE tasks
-        SET title = ?, description = ?, status = ?, priority = ?, review_state = ?, updated_at = ?
-        WHERE id = ?
+        UPDATE spec_sections
+        SET content = ?, updated_at = ?
+        WHERE project_id = ? AND section = ?
         "#,
     )
-    .bind(&title)
-    .bind(&description)
-    .bind(&status)
-    .bind(&priority)
-    .bind(&review_state)
+    .bind(content)
     .bind(&now)
-    .bind(&task.id)
+    .bind(&project_id)
+    .bind(section)
     .execute(&mut *tx)
     .await?;
 
-    if let Some(labels) = input.labels {
-        sqlx::query("DELETE FROM task_labels WHERE task_id = ?")
-            .bind(&task.id)
-            .execute(&mut *tx)
-            .await?;
-
-        let normalized = normalized_labels(labels);
-        for label in normalized {
-            sqlx::query("INSERT INTO task_labels (task_id, label) VALUES (?, ?)")
-                .bind(&task.id)
-                .bind(label)
-                .execute(&mut *tx)
-                .await?;
-        }
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "spec section '{section}' not found for project '{project_slug}'"
+        )));
     }
 
-    insert_history(
+    sqlx::query(
+        r#"
+        INSERT INTO spec_revisions (
+            id, project_id, section, content, edited_by, created_at,
+            bytes_added, bytes_removed, headings_touched
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&project_id)
+    .bind(section)
+    .bind(content)
+    .bind(edited_by)
+    .bind(&now)
+    .bind(summary.bytes_added as i64)
+    .bind(summary.bytes_removed as i64)
+    .bind(Value::from(summary.headings).to_string())
+    .execute(&mut *tx)
+    .await?;
+
+    insert_project_event(
         &mut tx,
-        &task.id,
-        &input.actor,
-        "task.updated",
-        serde_json::json!({
-            "status": status,
-            "priority": priority,
-            "review_state": review_state,
-        }),
+        &project_id,
+        edited_by,
+        EventKind::SpecUpdated,
+        match overridden_lock {
+            Some(holder) => serde_json::json!({ "section": section, "overrode_lock_of": holder }),
+            None => serde_json::json!({ "section": section }),
+        },
     )
     .await?;
 
     tx.commit().await?;
 
-    get_task_record_by_id(pool, &task.id).await
+    get_spec_section(pool, limits, project_slug, section).await
 }
 
-pub async fn move_task(
+/// Lists a section's revisions newest first. Without `include_content` only the change
+/// summaries are loaded, which keeps long histories cheap to page through.
+pub async fn list_spec_history(
     pool: &AnyPool,
     project_slug: &str,
-    task_ref: &str,
-    input: MoveTaskInput,
-) -> AppResult<TaskRecord> {
-    validate_status(&input.status)?;
-
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-    let task = get_task_record_by_id(pool, &task_id).await?;
-
-    if input.mcp_origin && task.review_state == "not_ready" {
-        return Err(AppError::BadRequest(
-            "task is not_ready, set review_state to ready before moving".to_string(),
-        ));
+    section: &str,
+    limit: i64,
+    offset: i64,
+    include_content: bool,
+) -> AppResult<Vec<SpecRevisionRecord>> {
+    #[derive(sqlx::FromRow)]
+    struct RevisionRow {
+        id: String,
+        project_id: String,
+        section: String,
+        content: Option<String>,
+        edited_by: String,
+        created_at: String,
+        bytes_added: i64,
+        bytes_removed: i64,
+        headings_touched: String,
     }
 
-    let now = now_timestamp();
-    let mut tx = pool.begin().await?;
+    validate_spec_section(section)?;
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    let sort_order = match input.sort_order {
-        Some(value) => value,
-        None => {
-            sqlx::query_scalar::<Any, f64>(
-                "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
-            )
-            .bind(&task.project_id)
-            .bind(&input.status)
-            .fetch_one(&mut *tx)
-            .await?
-        }
+    let content_column = if include_content {
+        "content"
+    } else {
+        "NULL AS content"
     };
-
-    sqlx::query("UPDATE tasks SET status = ?, sort_order = ?, updated_at = ? WHERE id = ?")
-        .bind(&input.status)
-        .bind(sort_order)
-        .bind(&now)
-        .bind(&task.id)
-        .execute(&mut *tx)
-        .await?;
-
-    insert_history(
-        &mut tx,
-        &task.id,
-        &input.actor,
-        "task.moved",
-        serde_json::json!({
-            "from_status": task.status,
-            "to_status": input.status,
-            "sort_order": sort_order,
-        }),
-    )
+    let rows = sqlx::query_as::<Any, RevisionRow>(&format!(
+        r#"
+        SELECT
+            id,
+            project_id,
+            section,
+            {content_column},
+            edited_by,
+            created_at,
+            bytes_added,
+            bytes_removed,
+            headings_touched
+        FROM spec_revisions
+        WHERE project_id = ? AND section = ?
+        ORDER BY created_at DESC
+        LIMIT ? OFFSET ?
+        "#
+    ))
+    .bind(project_id)
+    .bind(section)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
     .await?;
 
-    tx.commit().await?;
-
-    get_task_record_by_id(pool, &task.id).await
+    Ok(rows
+        .into_iter()
+        .map(|row| SpecRevisionRecord {
+            headings_touched: serde_json::from_str(&row.headings_touched).unwrap_or_default(),
+            id: row.id,
+            project_id: row.project_id,
+            section: row.section,
+            content: row.content,
+            edited_by: row.edited_by,
+            created_at: row.created_at,
+            bytes_added: row.bytes_added,
+            bytes_removed: row.bytes_removed,
+        })
+        .collect())
 }
 
-pub async fn delete_task(
+pub async fn count_spec_history(
     pool: &AnyPool,
     project_slug: &str,
-    task_ref: &str,
-    actor: &str,
-) -> AppResult<()> {
-    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
-
-    let mut tx = pool.begin().await?;
+    section: &str,
+) -> AppResult<i64> {
+    validate_spec_section(section)?;
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    insert_history(
-        &mut tx,
-        &task_id,
-        actor,
-        "task.deleted",
-        serde_json::json!({}),
+    let total = sqlx::query_scalar::<Any, i64>(
+        "SELECT COUNT(*) FROM spec_revisions WHERE project_id = ? AND section = ?",
     )
+    .bind(project_id)
+    .bind(section)
+    .fetch_one(pool)
     .await?;
 
-    let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
-        .bind(&task_id)
-        .execute(&mut *tx)
-        .await?;
-
-    if result.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("task '{task_ref}' not found")));
-    }
-
-    tx.commit().await?;
-    Ok(())
+    Ok(total)
 }
 
-async fn project_summary_by_id(
-    pool: &AnyPool,
-    project_id: &str,
-    project: ProjectRecord,
-) -> AppResult<ProjectSummary> {
-    let backlog_count = count_tasks_by_status(pool, project_id, "backlog").await?;
-    let ready_count = count_tasks_by_status(pool, project_id, "ready").await?;
-    let in_progress_count = count_tasks_by_status(pool, project_id, "in_progress").await?;
-    let review_count = count_tasks_by_status(pool, project_id, "review").await?;
-    let done_count = count_tasks_by_status(pool, project_id, "done").await?;
+pub async fn count_project_open_questions(pool: &AnyPool, project_slug: &str) -> AppResult<i64> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-    let open_question_count: i64 = sqlx::query_scalar(
+    let total = sqlx::query_scalar::<Any, i64>(
         r#"
         SELECT COUNT(*)
         FROM open_questions q
         INNER JOIN tasks t ON t.id = q.task_id
-        WHERE t.project_id = ? AND q.status = 'open'
+        WHERE t.project_id = ? AND q.status = 'open' AND t.archived_at IS NULL
         "#,
     )
     .bind(project_id)
     .fetch_one(pool)
     .await?;
 
-    let not_ready_count: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM tasks WHERE project_id = ? AND review_state = 'not_ready'",
+    Ok(total)
+}
+
+pub async fn list_project_open_questions(
+    pool: &AnyPool,
+    project_slug: &str,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<ProjectQuestionRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let questions = sqlx::query_as::<Any, ProjectQuestionRecord>(
+        r#"
+        SELECT
+            q.id,
+            q.task_id,
+            t.task_number,
+            q.question,
+            q.context,
+            q.answer,
+            q.status,
+            q.acceptance,
+            q.dispute_reason,
+            q.asked_by,
+            q.resolved_by,
+            q.created_at,
+            q.resolved_at
+        FROM open_questions q
+        INNER JOIN tasks t ON t.id = q.task_id
+        WHERE t.project_id = ? AND q.status = 'open' AND t.archived_at IS NULL
+        ORDER BY q.created_at DESC
+        LIMIT ? OFFSET ?
+        "#,
     )
     .bind(project_id)
-    .fetch_one(pool)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
     .await?;
 
-    Ok(ProjectSummary {
-        project,
-        backlog_count,
-        ready_count,
-        in_progress_count,
-        review_count,
-        done_count,
-        open_question_count,
-        not_ready_count,
-    })
+    Ok(questions)
 }
 
-async fn count_tasks_by_status(pool: &AnyPool, project_id: &str, status: &str) -> AppResult<i64> {
-    let count = sqlx::query_scalar::<Any, i64>(
-        "SELECT COUNT(*) FROM tasks WHERE project_id = ? AND status = ?",
+pub async fn list_recent_project_activity(
+    pool: &AnyPool,
+    project_slug: &str,
+    limit: i64,
+) -> AppResult<Vec<ProjectActivityRecord>> {
+    let page = list_project_activity(
+        pool,
+        project_slug,
+        TaskHistoryFilters::default(),
+        limit,
+        None,
     )
-    .bind(project_id)
-    .bind(status)
-    .fetch_one(pool)
     .await?;
-    Ok(count)
-}
-
-async fn project_id_by_slug(pool: &AnyPool, project_slug: &str) -> AppResult<String> {
-    let project_id = sqlx::query_scalar::<Any, String>("SELECT id FROM projects WHERE slug = ?")
-        .bind(project_slug)
-        .fetch_optional(pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound(format!("project '{project_slug}' not found")))?;
-
-    Ok(project_id)
+    Ok(page.entries)
 }
 
-async fn resolve_task_id(pool: &AnyPool, project_slug: &str, task_ref: &str) -> AppResult<String> {
-    match parse_task_ref(task_ref)? {
-        TaskRef::Uuid(task_id) => {
-            let result = sqlx::query_scalar::<Any, String>(
-                r#"
-                SELECT t.id
-                FROM tasks t
-                INNER JOIN projects p ON p.id = t.project_id
-                WHERE p.slug = ? AND t.id = ?
-                "#,
-            )
-            .bind(project_slug)
-            .bind(task_id)
-            .fetch_optional(pool)
-            .await?;
+/// History of every task in a project, newest first, paged like `list_task_history`.
+pub async fn list_project_activity(
+    pool: &AnyPool,
+    project_slug: &str,
+    filters: TaskHistoryFilters,
+    limit: i64,
+    cursor: Option<&str>,
+) -> AppResult<ProjectActivityPage> {
+    if limit <= 0 || limit > 100 {
+        return Err(AppError::BadRequest(
+            "limit must be between 1 and 100".to_string(),
+        ));
+    }
 
-            result.ok_or_else(|| AppError::NotFound(format!("task '{task_ref}' not found")))
-        }
-        TaskRef::DisplayKey { slug, task_number } => {
-            if slug != project_slug {
-                return Err(AppError::NotFound(format!(
-                    "task '{task_ref}' is outside project '{project_slug}'"
-                )));
-            }
+    let project_id = project_id_by_slug(pool, project_slug).await?;
 
-            let result = sqlx::query_scalar::<Any, String>(
-                r#"
-                SELECT t.id
-                FROM tasks t
-                INNER JOIN projects p ON p.id = t.project_id
-                WHERE p.slug = ? AND t.task_number = ?
-                "#,
-            )
-            .bind(project_slug)
-            .bind(task_number)
-            .fetch_optional(pool)
-            .await?;
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            h.id,
+            h.task_id,
+            t.task_number,
+            h.actor,
+            h.action,
+            h.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            h.created_at
+        FROM task_history h
+        INNER JOIN tasks t ON t.id = h.task_id
+        LEFT JOIN actors a ON a.id = h.actor
+        WHERE t.project_id = "#,
+    );
+    query.push_bind(&project_id);
 
-            result.ok_or_else(|| AppError::NotFound(format!("task '{task_ref}' not found")))
-        }
+    if let Some(action) = filters
+        .action
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        query.push(" AND h.action = ");
+        query.push_bind(action.to_string());
     }
-}
 
-async fn get_open_question_by_id(
-    pool: &AnyPool,
-    task_id: &str,
-    question_id: &str,
-) -> AppResult<OpenQuestionRecord> {
-    let record = sqlx::query_as::<Any, OpenQuestionRecord>(
-        r#"
-        SELECT id, task_id, question, context, answer, status, asked_by, resolved_by, created_at, resolved_at
-        FROM open_questions
-        WHERE id = ? AND task_id = ?
-        "#,
-    )
-    .bind(question_id)
-    .bind(task_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("question '{question_id}' not found")))?;
+    if let Some(actor) = filters
+        .actor
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        query.push(" AND h.actor = ");
+        query.push_bind(actor.to_string());
+    }
 
-    Ok(record)
+    if let Some(cursor) = cursor {
+        let cursor_created_at = sqlx::query_scalar::<Any, String>(
+            r#"
+            SELECT h.created_at
+            FROM task_history h
+            INNER JOIN tasks t ON t.id = h.task_id
+            WHERE h.id = ? AND t.project_id = ?
+            "#,
+        )
+        .bind(cursor)
+        .bind(&project_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "cursor '{cursor}' is not a history entry of this project"
+            ))
+        })?;
+
+        query.push(" AND (h.created_at < ");
+        query.push_bind(cursor_created_at.clone());
+        query.push(" OR (h.created_at = ");
+        query.push_bind(cursor_created_at);
+        query.push(" AND h.id < ");
+        query.push_bind(cursor.to_string());
+        query.push("))");
+    }
+
+    // Fetch one extra row to learn whether another page exists.
+    query.push(" ORDER BY h.created_at DESC, h.id DESC LIMIT ");
+    query.push_bind(limit + 1);
+
+    let mut entries = query
+        .build_query_as::<ProjectActivityRecord>()
+        .fetch_all(pool)
+        .await?;
+
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|entry| entry.id.clone())
+    } else {
+        None
+    };
+
+    Ok(ProjectActivityPage {
+        entries,
+        next_cursor,
+    })
 }
 
-async fn get_subtask_by_id(
+/// History entries that can change a task's status, oldest first, for flow metrics.
+pub async fn list_status_transitions(
     pool: &AnyPool,
-    task_id: &str,
-    subtask_id: &str,
-) -> AppResult<SubtaskRecord> {
-    let subtask = sqlx::query_as::<Any, SubtaskRecord>(
+    project_slug: &str,
+) -> AppResult<Vec<TaskTransitionRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let transitions = sqlx::query_as::<Any, TaskTransitionRecord>(
         r#"
-        SELECT id, task_id, title, done, sort_order, created_at
-        FROM subtasks
-        WHERE id = ? AND task_id = ?
+        SELECT
+            h.task_id,
+            t.created_at AS task_created_at,
+            h.action,
+            h.detail,
+            h.created_at
+        FROM task_history h
+        INNER JOIN tasks t ON t.id = h.task_id
+        WHERE t.project_id = ?
+          AND h.action IN ('task.created', 'task.moved', 'task.updated', 'task.undone')
+        ORDER BY h.created_at ASC, h.id ASC
         "#,
     )
-    .bind(subtask_id)
-    .bind(task_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("subtask '{subtask_id}' not found")))?;
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
 
-    Ok(subtask)
+    Ok(transitions)
 }
 
-async fn get_attachment_for_task(
+pub async fn list_system_events(
     pool: &AnyPool,
-    task_id: &str,
-    attachment_id: &str,
-) -> AppResult<AttachmentRecord> {
-    let attachment = sqlx::query_as::<Any, AttachmentRecord>(
+    workspace_id: Option<&str>,
+    project_slugs: &[String],
+    after_created_at: Option<&str>,
+    after_id: Option<&str>,
+    limit: i64,
+) -> AppResult<Vec<SystemEventRecord>> {
+    if limit <= 0 || limit > 200 {
+        return Err(AppError::BadRequest(
+            "limit must be between 1 and 200".to_string(),
+        ));
+    }
+
+    if after_created_at.is_some() != after_id.is_some() {
+        return Err(AppError::BadRequest(
+            "after_created_at and after_id must be provided together".to_string(),
+        ));
+    }
+
+    let mut query = QueryBuilder::<Any>::new(
         r#"
-        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
-        FROM attachments
-        WHERE id = ? AND task_id = ?
+        SELECT
+            e.id,
+            p.slug AS project_slug,
+            e.task_id,
+            e.task_number,
+            e.actor,
+            e.action,
+            e.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            e.created_at
+        FROM system_events e
+        INNER JOIN projects p ON p.id = e.project_id
+        LEFT JOIN actors a ON a.id = e.actor
+        WHERE 1 = 1
         "#,
-    )
-    .bind(attachment_id)
-    .bind(task_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("attachment '{attachment_id}' not found")))?;
+    );
 
-    Ok(attachment)
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND p.workspace_id = ");
+        query.push_bind(workspace_id);
+    }
+
+    if !project_slugs.is_empty() {
+        query.push(" AND p.slug IN (");
+        {
+            let mut separated = query.separated(", ");
+            for slug in project_slugs {
+                separated.push_bind(slug);
+            }
+        }
+        query.push(")");
+    }
+
+    if let (Some(created_at), Some(event_id)) = (after_created_at, after_id) {
+        query.push(" AND (e.created_at > ");
+        query.push_bind(created_at);
+        query.push(" OR (e.created_at = ");
+        query.push_bind(created_at);
+        query.push(" AND e.id > ");
+        query.push_bind(event_id);
+        query.push("))");
+    }
+
+    query.push(" ORDER BY e.created_at ASC, e.id ASC LIMIT ");
+    query.push_bind(limit);
+
+    let events = query
+        .build_query_as::<SystemEventRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(events)
 }
 
-async fn get_task_record_by_id(pool: &AnyPool, task_id: &str) -> AppResult<TaskRecord> {
-    let task = sqlx::query_as::<Any, TaskRecord>(
+/// A project's newest system events, newest first.
+pub async fn list_recent_system_events(
+    pool: &AnyPool,
+    project_slug: &str,
+    limit: i64,
+) -> AppResult<Vec<SystemEventRecord>> {
+    let events = sqlx::query_as::<Any, SystemEventRecord>(
         r#"
         SELECT
-            id,
-            project_id,
-            task_number,
-            title,
-            description,
-            status,
-            priority,
-            review_state,
-            sort_order,
-            created_by,
-            created_at,
-            updated_at
-        FROM tasks
-        WHERE id = ?
+            e.id,
+            p.slug AS project_slug,
+            e.task_id,
+            e.task_number,
+            e.actor,
+            e.action,
+            e.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            e.created_at
+        FROM system_events e
+        INNER JOIN projects p ON p.id = e.project_id
+        LEFT JOIN actors a ON a.id = e.actor
+        WHERE p.slug = ?
+        ORDER BY e.created_at DESC, e.id DESC
+        LIMIT ?
         "#,
     )
-    .bind(task_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound(format!("task '{task_id}' not found")))?;
-
-    Ok(task)
+    .bind(project_slug)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(events)
 }
 
-async fn insert_history(
-    tx: &mut sqlx::Transaction<'_, Any>,
-    task_id: &str,
+/// Newest-first events recorded by one actor; task history is mirrored into `system_events`.
+pub async fn list_actor_activity(
+    pool: &AnyPool,
+    workspace_id: Option<&str>,
     actor: &str,
-    action: &str,
-    detail: Value,
-) -> AppResult<()> {
-    let now = now_timestamp();
-    let detail_json = detail.to_string();
-
-    sqlx::query(
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<SystemEventRecord>> {
+    let mut query = QueryBuilder::<Any>::new(
         r#"
-        INSERT INTO task_history (id, task_id, actor, action, detail, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        SELECT
+            e.id,
+            p.slug AS project_slug,
+            e.task_id,
+            e.task_number,
+            e.actor,
+            e.action,
+            e.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            e.created_at
+        FROM system_events e
+        INNER JOIN projects p ON p.id = e.project_id
+        LEFT JOIN actors a ON a.id = e.actor
+        WHERE e.actor = "#,
+    );
+    query.push_bind(actor);
+
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND p.workspace_id = ");
+        query.push_bind(workspace_id);
+    }
+
+    query.push(" ORDER BY e.created_at DESC, e.id DESC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+
+    let events = query
+        .build_query_as::<SystemEventRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(events)
+}
+
+pub async fn latest_system_event_cursor(
+    pool: &AnyPool,
+    workspace_id: Option<&str>,
+    project_slugs: &[String],
+) -> AppResult<Option<(String, String)>> {
+    #[derive(sqlx::FromRow)]
+    struct CursorRow {
+        created_at: String,
+        id: String,
+    }
+
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT e.created_at, e.id
+        FROM system_events e
+        INNER JOIN projects p ON p.id = e.project_id
+        WHERE 1 = 1
         "#,
+    );
+
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND p.workspace_id = ");
+        query.push_bind(workspace_id);
+    }
+
+    if !project_slugs.is_empty() {
+        query.push(" AND p.slug IN (");
+        {
+            let mut separated = query.separated(", ");
+            for slug in project_slugs {
+                separated.push_bind(slug);
+            }
+        }
+        query.push(")");
+    }
+
+    query.push(" ORDER BY e.created_at DESC, e.id DESC LIMIT 1");
+
+    let row = query
+        .build_query_as::<CursorRow>()
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|cursor| (cursor.created_at, cursor.id)))
+}
+
+/// The cursor of one system event, for resuming a stream from its `Last-Event-ID`.
+pub async fn system_event_cursor(
+    pool: &AnyPool,
+    event_id: &str,
+) -> AppResult<Option<(String, String)>> {
+    let cursor = sqlx::query_as::<Any, (String, String)>(
+        "SELECT created_at, id FROM system_events WHERE id = ?",
     )
-    .bind(Uuid::new_v4().to_string())
-    .bind(task_id)
-    .bind(actor)
-    .bind(action)
-    .bind(&detail_json)
-    .bind(&now)
-    .execute(&mut **tx)
+    .bind(event_id)
+    .fetch_optional(pool)
     .await?;
+    Ok(cursor)
+}
 
-    let inserted = sqlx::query(
+pub async fn create_attachment(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    input: NewAttachmentInput,
+) -> AppResult<AttachmentRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let filename = input.filename.trim().to_string();
+    if filename.is_empty() {
+        return Err(AppError::BadRequest(
+            "attachment filename cannot be empty".to_string(),
+        ));
+    }
+
+    if input.size_bytes < 0 {
+        return Err(AppError::BadRequest(
+            "attachment size cannot be negative".to_string(),
+        ));
+    }
+
+    let content_type = if input.content_type.trim().is_empty() {
+        "application/octet-stream".to_string()
+    } else {
+        input.content_type.trim().to_string()
+    };
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
         r#"
-        INSERT INTO system_events (id, project_id, task_id, task_number, actor, action, detail, created_at)
-        SELECT ?, t.project_id, t.id, t.task_number, ?, ?, ?, ?
-        FROM tasks t
-        WHERE t.id = ?
+        INSERT INTO attachments (
+            id,
+            task_id,
+            filename,
+            content_type,
+            size_bytes,
+            storage_path,
+            uploaded_by,
+            created_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(Uuid::new_v4().to_string())
-    .bind(actor)
-    .bind(action)
-    .bind(&detail_json)
+    .bind(&input.id)
+    .bind(&task_id)
+    .bind(&filename)
+    .bind(content_type)
+    .bind(input.size_bytes)
+    .bind(input.storage_path)
+    .bind(&input.uploaded_by)
     .bind(&now)
-    .bind(task_id)
-    .execute(&mut **tx)
+    .execute(&mut *tx)
     .await?;
 
-    if inserted.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!("task '{task_id}' not found")));
-    }
+    insert_history(
+        &mut tx,
+        &task_id,
+        &input.uploaded_by,
+        EventKind::AttachmentCreated,
+        serde_json::json!({
+            "attachment_id": input.id,
+            "filename": filename,
+            "size_bytes": input.size_bytes,
+        }),
+    )
+    .await?;
 
-    Ok(())
+    tx.commit().await?;
+    get_attachment_for_task(pool, &task_id, &input.id).await
 }
 
-async fn insert_project_event(
-    tx: &mut sqlx::Transaction<'_, Any>,
-    project_id: &str,
-    actor: &str,
-    action: &str,
-    detail: Value,
-) -> AppResult<()> {
+pub async fn create_attachment_upload(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    input: NewAttachmentUploadInput,
+) -> AppResult<AttachmentUploadRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let filename = input.filename.trim().to_string();
+    if filename.is_empty() {
+        return Err(AppError::BadRequest(
+            "attachment filename cannot be empty".to_string(),
+        ));
+    }
+    if input.size_bytes <= 0 {
+        return Err(AppError::BadRequest(
+            "upload size_bytes must be positive".to_string(),
+        ));
+    }
+
     let now = now_timestamp();
     sqlx::query(
         r#"
-        INSERT INTO system_events (id, project_id, task_id, task_number, actor, action, detail, created_at)
-        VALUES (?, ?, NULL, NULL, ?, ?, ?, ?)
+        INSERT INTO attachment_uploads (
+            id, task_id, filename, content_type, size_bytes, uploaded_by, created_at, updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(Uuid::new_v4().to_string())
-    .bind(project_id)
-    .bind(actor)
-    .bind(action)
-    .bind(detail.to_string())
-    .bind(now)
-    .execute(&mut **tx)
+    .bind(&input.id)
+    .bind(&task_id)
+    .bind(&filename)
+    .bind(&input.content_type)
+    .bind(input.size_bytes)
+    .bind(&input.uploaded_by)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
     .await?;
 
-    Ok(())
+    get_attachment_upload(pool, project_slug, task_ref, &input.id).await
 }
 
-fn now_timestamp() -> String {
-    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+pub async fn get_attachment_upload(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    upload_id: &str,
+) -> AppResult<AttachmentUploadRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    sqlx::query_as::<Any, AttachmentUploadRecord>(
+        r#"
+        SELECT id, task_id, filename, content_type, size_bytes, uploaded_by, created_at, updated_at
+        FROM attachment_uploads
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(upload_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("upload '{upload_id}' not found")))
 }
 
-fn validate_status(value: &str) -> AppResult<()> {
-    match value {
-        "backlog" | "ready" | "in_progress" | "review" | "done" => Ok(()),
-        _ => Err(AppError::BadRequest(format!(
-            "invalid task status '{value}'"
-        ))),
-    }
+/// Marks an upload as active so it is not swept as abandoned.
+pub async fn touch_attachment_upload(pool: &AnyPool, upload_id: &str) -> AppResult<()> {
+    sqlx::query("UPDATE attachment_uploads SET updated_at = ? WHERE id = ?")
+        .bind(now_timestamp())
+        .bind(upload_id)
+        .execute(pool)
+        .await?;
+    Ok(())
 }
 
-fn validate_priority(value: &str) -> AppResult<()> {
-    match value {
-        "low" | "medium" | "high" | "critical" => Ok(()),
-        _ => Err(AppError::BadRequest(format!(
-            "invalid task priority '{value}'"
-        ))),
-    }
+/// Removes an upload session. Returns false when it was already gone, so only one of two
+/// racing completions goes on to create the attachment.
+pub async fn delete_attachment_upload(pool: &AnyPool, upload_id: &str) -> AppResult<bool> {
+    let deleted = sqlx::query("DELETE FROM attachment_uploads WHERE id = ?")
+        .bind(upload_id)
+        .execute(pool)
+        .await?;
+    Ok(deleted.rows_affected() > 0)
 }
 
-fn validate_review_state(value: &str) -> AppResult<()> {
-    match value {
-        "ready" | "not_ready" => Ok(()),
-        _ => Err(AppError::BadRequest(format!(
-            "invalid review state '{value}'"
-        ))),
-    }
+/// Deletes upload sessions untouched since `cutoff` and returns their ids.
+pub async fn delete_stale_attachment_uploads(
+    pool: &AnyPool,
+    cutoff: &str,
+) -> AppResult<Vec<String>> {
+    let mut tx = pool.begin().await?;
+    let ids =
+        sqlx::query_scalar::<Any, String>("SELECT id FROM attachment_uploads WHERE updated_at < ?")
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await?;
+    sqlx::query("DELETE FROM attachment_uploads WHERE updated_at < ?")
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(ids)
+}
+
+pub async fn get_attachment(pool: &AnyPool, attachment_id: &str) -> AppResult<AttachmentRecord> {
+    let attachment = sqlx::query_as::<Any, AttachmentRecord>(
+        r#"
+        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
+        FROM attachments
+        WHERE id = ?
+        "#,
+    )
+    .bind(attachment_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("attachment '{attachment_id}' not found")))?;
+
+    Ok(attachment)
 }
 
-fn normalize_webhook_platform(value: &str) -> AppResult<String> {
-    let platform = value.trim().to_ascii_lowercase();
-    match platform.as_str() {
-        "slack" | "discord" | "generic" => Ok(platform),
-        _ => Err(AppError::BadRequest(format!(
-            "invalid webhook platform '{value}'"
-        ))),
-    }
-}
+pub async fn get_task_attachment(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    attachment_id: &str,
+) -> AppResult<AttachmentRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    get_attachment_for_task(pool, &task_id, attachment_id).await
+}
+
+pub async fn delete_attachment(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    attachment_id: &str,
+    actor: &str,
+) -> AppResult<AttachmentRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let attachment = get_attachment_for_task(pool, &task_id, attachment_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("DELETE FROM attachments WHERE id = ? AND task_id = ?")
+        .bind(attachment_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "attachment '{attachment_id}' not found for task '{task_ref}'"
+        )));
+    }
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::AttachmentDeleted,
+        serde_json::json!({
+            "attachment_id": attachment_id,
+            "filename": attachment.filename,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(attachment)
+}
+
+pub async fn create_open_question(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    question: &str,
+    context: &str,
+    asked_by: &str,
+) -> AppResult<OpenQuestionRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let trimmed_question = question.trim().to_string();
+    if trimmed_question.is_empty() {
+        return Err(AppError::BadRequest("question cannot be empty".to_string()));
+    }
+    check_question_length(limits, "question", &trimmed_question)?;
+    check_question_length(limits, "question context", context)?;
+
+    let now = now_timestamp();
+    let question_id = Uuid::new_v4().to_string();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO open_questions (
+            id,
+            task_id,
+            question,
+            context,
+            answer,
+            status,
+            asked_by,
+            resolved_by,
+            created_at,
+            resolved_at
+        )
+        VALUES (?, ?, ?, ?, NULL, 'open', ?, NULL, ?, NULL)
+        "#,
+    )
+    .bind(&question_id)
+    .bind(&task_id)
+    .bind(&trimmed_question)
+    .bind(context)
+    .bind(asked_by)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        asked_by,
+        EventKind::QuestionCreated,
+        serde_json::json!({
+            "question_id": question_id,
+            "question": trimmed_question,
+        }),
+    )
+    .await?;
+    sync_references(
+        &mut tx,
+        &task_id,
+        "question",
+        &question_id,
+        &format!("{trimmed_question}\n{context}"),
+        asked_by,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    get_open_question_by_id(pool, &task_id, &question_id).await
+}
+
+pub async fn answer_open_question(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    question_id: &str,
+    answer: &str,
+    resolved_by: &str,
+) -> AppResult<OpenQuestionRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let answer = checked_answer(limits, answer)?;
+
+    let existing = get_open_question_by_id(pool, &task_id, question_id).await?;
+    if existing.status != "open" {
+        return Err(AppError::Conflict(format!(
+            "question '{question_id}' is already resolved"
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    write_answer(&mut tx, &existing, &answer, resolved_by, true).await?;
+    tx.commit().await?;
+
+    get_open_question_by_id(pool, &task_id, question_id).await
+}
+
+/// Answers up to `MAX_BULK_ANSWERS` questions across a project in one transaction. Entries that
+/// cannot be applied (unknown, already resolved, empty or repeated) are reported in their result
+/// and skipped; the rest are written together. With `combined_event`, a single
+/// `questions.resolved` project event replaces the per-question `question.resolved` entries.
+pub async fn answer_open_questions_bulk(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    items: Vec<BulkAnswerItem>,
+    combined_event: bool,
+    resolved_by: &str,
+) -> AppResult<Vec<BulkAnswerResult>> {
+    if items.is_empty() {
+        return Err(AppError::BadRequest("answers cannot be empty".to_string()));
+    }
+    if items.len() > MAX_BULK_ANSWERS {
+        return Err(AppError::BadRequest(format!(
+            "too many answers in one call, max is {MAX_BULK_ANSWERS}"
+        )));
+    }
+
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut planned: Vec<Option<(OpenQuestionRecord, String)>> = Vec::with_capacity(items.len());
+    let mut results = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let plan = if items[..index]
+            .iter()
+            .any(|other| other.question_id == item.question_id)
+        {
+            Err(AppError::BadRequest(format!(
+                "question '{}' is listed more than once",
+                item.question_id
+            )))
+        } else {
+            plan_bulk_answer(pool, limits, &project_id, item).await
+        };
+        results.push(BulkAnswerResult {
+            question_id: item.question_id.clone(),
+            resolved: plan.is_ok(),
+            error: plan.as_ref().err().map(ToString::to_string),
+            question: None,
+        });
+        planned.push(plan.ok());
+    }
+
+    let answered: Vec<&(OpenQuestionRecord, String)> = planned.iter().flatten().collect();
+    if answered.is_empty() {
+        return Ok(results);
+    }
+
+    let mut tx = pool.begin().await?;
+    for (question, answer) in &answered {
+        write_answer(&mut tx, question, answer, resolved_by, !combined_event).await?;
+    }
+    if combined_event {
+        insert_project_event(
+            &mut tx,
+            &project_id,
+            resolved_by,
+            EventKind::QuestionsResolved,
+            serde_json::json!({
+                "count": answered.len(),
+                "questions": answered
+                    .iter()
+                    .map(|(question, _)| serde_json::json!({
+                        "question_id": question.id,
+                        "task_id": question.task_id,
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    for (result, plan) in results.iter_mut().zip(&planned) {
+        if let Some((question, _)) = plan {
+            result.question =
+                Some(get_open_question_by_id(pool, &question.task_id, &question.id).await?);
+        }
+    }
+    Ok(results)
+}
+
+async fn plan_bulk_answer(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_id: &str,
+    item: &BulkAnswerItem,
+) -> AppResult<(OpenQuestionRecord, String)> {
+    let answer = checked_answer(limits, &item.answer)?;
+    let question = sqlx::query_as::<Any, OpenQuestionRecord>(
+        r#"
+        SELECT
+            q.id,
+            q.task_id,
+            q.question,
+            q.context,
+            q.answer,
+            q.status,
+            q.acceptance,
+            q.dispute_reason,
+            q.asked_by,
+            q.resolved_by,
+            q.created_at,
+            q.resolved_at
+        FROM open_questions q
+        INNER JOIN tasks t ON t.id = q.task_id
+        WHERE q.id = ? AND t.project_id = ?
+        "#,
+    )
+    .bind(&item.question_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("question '{}' not found", item.question_id)))?;
+    if question.status != "open" {
+        return Err(AppError::Conflict(format!(
+            "question '{}' is already resolved",
+            item.question_id
+        )));
+    }
+
+    Ok((question, answer))
+}
+
+fn checked_answer(limits: &FieldLimitConfig, answer: &str) -> AppResult<String> {
+    let answer = answer.trim().to_string();
+    if answer.is_empty() {
+        return Err(AppError::BadRequest("answer cannot be empty".to_string()));
+    }
+    check_question_length(limits, "answer", &answer)?;
+    Ok(answer)
+}
+
+/// Resolves `question` with `answer`. `history` is false when the caller records one combined
+/// event instead of a `question.resolved` entry per question.
+async fn write_answer(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    question: &OpenQuestionRecord,
+    answer: &str,
+    resolved_by: &str,
+    history: bool,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE open_questions
+        SET answer = ?, status = 'resolved', acceptance = 'pending', resolved_by = ?, resolved_at = ?
+        WHERE id = ? AND task_id = ? AND status = 'open'
+        "#,
+    )
+    .bind(answer)
+    .bind(resolved_by)
+    .bind(now_timestamp())
+    .bind(&question.id)
+    .bind(&question.task_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if history {
+        insert_history(
+            tx,
+            &question.task_id,
+            resolved_by,
+            EventKind::QuestionResolved,
+            serde_json::json!({
+                "question_id": question.id,
+            }),
+        )
+        .await?;
+    }
+    sync_references(
+        tx,
+        &question.task_id,
+        "question",
+        &question.id,
+        &format!("{}\n{}\n{answer}", question.question, question.context),
+        resolved_by,
+    )
+    .await
+}
+
+/// Records whether the asker accepts an answer. `disputed` needs a reason and reopens the
+/// question so it shows up as open again until someone answers it anew.
+pub async fn review_question_answer(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    question_id: &str,
+    input: ReviewAnswerInput,
+) -> AppResult<OpenQuestionRecord> {
+    let ReviewAnswerInput {
+        verdict,
+        reason,
+        actor,
+    } = input;
+    let actor = actor.as_str();
+    if !matches!(verdict.as_str(), "accepted" | "disputed") {
+        return Err(AppError::BadRequest(format!(
+            "invalid answer verdict '{verdict}', expected accepted or disputed"
+        )));
+    }
+    let reason = reason
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if let Some(reason) = &reason {
+        check_question_length(limits, "dispute reason", reason)?;
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let existing = get_open_question_by_id(pool, &task_id, question_id).await?;
+    if existing.status != "resolved" {
+        return Err(AppError::Conflict(format!(
+            "question '{question_id}' has no answer to review yet"
+        )));
+    }
+    if existing.acceptance == "accepted" {
+        return Err(AppError::Conflict(format!(
+            "the answer to question '{question_id}' is already accepted"
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    if verdict == "accepted" {
+        sqlx::query(
+            "UPDATE open_questions SET acceptance = 'accepted' WHERE id = ? AND task_id = ?",
+        )
+        .bind(question_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_history(
+            &mut tx,
+            &task_id,
+            actor,
+            EventKind::QuestionAccepted,
+            serde_json::json!({ "question_id": question_id }),
+        )
+        .await?;
+    } else {
+        let Some(reason) = reason else {
+            return Err(AppError::BadRequest(
+                "a disputed answer needs a reason".to_string(),
+            ));
+        };
+        sqlx::query(
+            r#"
+            UPDATE open_questions
+            SET status = 'open', acceptance = 'disputed', dispute_reason = ?, resolved_by = NULL, resolved_at = NULL
+            WHERE id = ? AND task_id = ?
+            "#,
+        )
+        .bind(&reason)
+        .bind(question_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_history(
+            &mut tx,
+            &task_id,
+            actor,
+            EventKind::QuestionDisputed,
+            serde_json::json!({
+                "question_id": question_id,
+                "question": existing.question,
+                "answer": existing.answer,
+                "answered_by": existing.resolved_by,
+                "reason": reason,
+            }),
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+    get_open_question_by_id(pool, &task_id, question_id).await
+}
+
+pub async fn set_review_state(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    review_state: &str,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    validate_review_state(review_state)?;
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let current = get_task_record_by_id(pool, &task_id).await?;
+
+    if current.review_state == review_state {
+        return Ok(current);
+    }
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("UPDATE tasks SET review_state = ?, updated_at = ? WHERE id = ?")
+        .bind(review_state)
+        .bind(&now)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskReviewStateChanged,
+        serde_json::json!({
+            "from_review_state": current.review_state,
+            "to_review_state": review_state,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    get_task_record_by_id(pool, &task_id).await
+}
+
+/// Asks `reviewer` to review a task. A reviewer can only have one pending request per task.
+pub async fn request_review(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    reviewer: &str,
+    actor: &str,
+) -> AppResult<TaskReviewRecord> {
+    let reviewer = reviewer.trim();
+    if reviewer.is_empty() {
+        return Err(AppError::BadRequest("reviewer cannot be empty".to_string()));
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let pending = sqlx::query_scalar::<Any, String>(
+        "SELECT id FROM task_reviews WHERE task_id = ? AND reviewer = ? AND status = 'pending'",
+    )
+    .bind(&task_id)
+    .bind(reviewer)
+    .fetch_optional(pool)
+    .await?;
+    if pending.is_some() {
+        return Err(AppError::Conflict(format!(
+            "review from '{reviewer}' is already pending on task '{task_ref}'"
+        )));
+    }
+
+    let review_id = Uuid::new_v4().to_string();
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO task_reviews (id, task_id, reviewer, requested_by, status, created_at)
+        VALUES (?, ?, ?, ?, 'pending', ?)
+        "#,
+    )
+    .bind(&review_id)
+    .bind(&task_id)
+    .bind(reviewer)
+    .bind(actor)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::ReviewRequested,
+        serde_json::json!({
+            "review_id": review_id,
+            "reviewer": reviewer,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    get_review_by_id(pool, &task_id, &review_id).await
+}
+
+/// Records an `approved` or `changes_requested` verdict on a pending review.
+pub async fn complete_review(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    review_id: &str,
+    verdict: &str,
+    comment: Option<String>,
+    actor: &str,
+) -> AppResult<TaskReviewRecord> {
+    if !matches!(verdict, "approved" | "changes_requested") {
+        return Err(AppError::BadRequest(format!(
+            "invalid review verdict '{verdict}', expected approved or changes_requested"
+        )));
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let review = get_review_by_id(pool, &task_id, review_id).await?;
+    if review.status != "pending" {
+        return Err(AppError::Conflict(format!(
+            "review '{review_id}' is already {}",
+            review.status
+        )));
+    }
+
+    let comment = comment
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE task_reviews
+        SET status = ?, comment = ?, completed_by = ?, completed_at = ?
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(verdict)
+    .bind(&comment)
+    .bind(actor)
+    .bind(&now)
+    .bind(review_id)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::ReviewCompleted,
+        serde_json::json!({
+            "review_id": review_id,
+            "reviewer": review.reviewer,
+            "requested_by": review.requested_by,
+            "verdict": verdict,
+            "comment": comment,
+        }),
+    )
+    .await?;
+    if let Some(comment) = &comment {
+        sync_references(&mut tx, &task_id, "review", review_id, comment, actor).await?;
+    }
+
+    tx.commit().await?;
+    get_review_by_id(pool, &task_id, review_id).await
+}
+
+pub async fn list_task_reviews(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<Vec<TaskReviewRecord>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    list_reviews_for_task(pool, &task_id).await
+}
+
+/// Project settings, falling back to defaults when none have been saved.
+pub async fn get_project_settings(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<ProjectSettings> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    project_settings_by_id(pool, &project_id).await
+}
+
+pub async fn update_project_settings(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: UpdateProjectSettingsInput,
+) -> AppResult<ProjectSettings> {
+    let current = get_project_settings(pool, project_slug).await?;
+    let review_checklist_required = input
+        .review_checklist_required
+        .unwrap_or(current.review_checklist_required);
+    let review_gate = input.review_gate.unwrap_or(current.review_gate);
+    if !REVIEW_GATES.contains(&review_gate.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "invalid review_gate '{review_gate}', expected one of {}",
+            REVIEW_GATES.join(", ")
+        )));
+    }
+    let review_gate_scope = input.review_gate_scope.unwrap_or(current.review_gate_scope);
+    if !REVIEW_GATE_SCOPES.contains(&review_gate_scope.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "invalid review_gate_scope '{review_gate_scope}', expected one of {}",
+            REVIEW_GATE_SCOPES.join(", ")
+        )));
+    }
+
+    let auto_archive_after_days = input
+        .auto_archive_after_days
+        .unwrap_or(current.auto_archive_after_days);
+    if !(0..=MAX_AUTO_ARCHIVE_DAYS).contains(&auto_archive_after_days) {
+        return Err(AppError::BadRequest(format!(
+            "auto_archive_after_days must be between 0 (off) and {MAX_AUTO_ARCHIVE_DAYS}"
+        )));
+    }
+
+    let column_order = match input.column_order {
+        Some(order) => normalize_column_order(&order)?
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        None => current.column_order,
+    };
+    let column_colors = match input.column_colors {
+        Some(colors) => normalize_column_colors(colors)?,
+        None => current.column_colors,
+    };
+    let enforce_dependencies = input
+        .enforce_dependencies
+        .unwrap_or(current.enforce_dependencies);
+    let timezone = match input.timezone {
+        Some(value) => normalize_timezone(&value)?,
+        None => current.timezone,
+    };
+    let stored_order =
+        (column_order != TASK_STATUSES).then(|| Value::from(column_order).to_string());
+    let stored_colors = (!column_colors.is_empty()).then(|| {
+        Value::Object(
+            column_colors
+                .into_iter()
+                .map(|(status, color)| (status, Value::String(color)))
+                .collect(),
+        )
+        .to_string()
+    });
+
+    sqlx::query(
+        r#"
+        INSERT INTO project_settings (
+            project_id, review_checklist_required, review_gate, review_gate_scope,
+            auto_archive_after_days, column_order, column_colors, enforce_dependencies, timezone,
+            updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT (project_id) DO UPDATE SET
+            review_checklist_required = excluded.review_checklist_required,
+            review_gate = excluded.review_gate,
+            review_gate_scope = excluded.review_gate_scope,
+            auto_archive_after_days = excluded.auto_archive_after_days,
+            column_order = excluded.column_order,
+            column_colors = excluded.column_colors,
+            enforce_dependencies = excluded.enforce_dependencies,
+            timezone = excluded.timezone,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&current.project_id)
+    .bind(i64::from(review_checklist_required))
+    .bind(&review_gate)
+    .bind(&review_gate_scope)
+    .bind(auto_archive_after_days)
+    .bind(stored_order)
+    .bind(stored_colors)
+    .bind(i64::from(enforce_dependencies))
+    .bind(&timezone)
+    .bind(now_timestamp())
+    .execute(pool)
+    .await?;
+    touch_content_version(&mut *pool.acquire().await?, &current.project_id).await?;
+
+    get_project_settings(pool, project_slug).await
+}
+
+pub async fn get_digest_schedule(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<DigestScheduleRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = digest_schedule_query();
+    query.push(" WHERE d.project_id = ");
+    query.push_bind(project_id);
+
+    query
+        .build_query_as::<DigestScheduleRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("project '{project_slug}' has no digest schedule"))
+        })
+}
+
+/// Creates or replaces a project's digest schedule. Changing it does not resend past digests.
+pub async fn set_digest_schedule(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: DigestScheduleInput,
+) -> AppResult<DigestScheduleRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    if !DIGEST_FREQUENCIES.contains(&input.frequency.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "invalid frequency '{}', expected one of {}",
+            input.frequency,
+            DIGEST_FREQUENCIES.join(", ")
+        )));
+    }
+    if !(0..=23).contains(&input.hour) {
+        return Err(AppError::BadRequest(
+            "hour must be between 0 and 23 (UTC)".to_string(),
+        ));
+    }
+    let weekday = input.weekday.unwrap_or(1);
+    if !(1..=7).contains(&weekday) {
+        return Err(AppError::BadRequest(
+            "weekday must be between 1 (Monday) and 7 (Sunday)".to_string(),
+        ));
+    }
+
+    let now = now_timestamp();
+    sqlx::query(
+        r#"
+        INSERT INTO digest_schedules (project_id, frequency, hour, weekday, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT (project_id) DO UPDATE SET
+            frequency = excluded.frequency,
+            hour = excluded.hour,
+            weekday = excluded.weekday,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&input.frequency)
+    .bind(input.hour)
+    .bind(weekday)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+
+    get_digest_schedule(pool, project_slug).await
+}
+
+pub async fn delete_digest_schedule(pool: &AnyPool, project_slug: &str) -> AppResult<()> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let deleted = sqlx::query("DELETE FROM digest_schedules WHERE project_id = ?")
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "project '{project_slug}' has no digest schedule"
+        )));
+    }
+
+    Ok(())
+}
+
+pub async fn list_digest_schedules(pool: &AnyPool) -> AppResult<Vec<DigestScheduleRecord>> {
+    let mut query = digest_schedule_query();
+    query.push(" ORDER BY p.slug ASC");
+    let schedules = query
+        .build_query_as::<DigestScheduleRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(schedules)
+}
+
+pub async fn mark_digest_sent(pool: &AnyPool, project_id: &str, sent_at: &str) -> AppResult<()> {
+    sqlx::query("UPDATE digest_schedules SET last_sent_at = ? WHERE project_id = ?")
+        .bind(sent_at)
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn get_task_recurrence(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<TaskRecurrenceRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let mut query = task_recurrence_query();
+    query.push(" WHERE r.task_id = ");
+    query.push_bind(task_id);
+
+    query
+        .build_query_as::<TaskRecurrenceRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("task '{task_ref}' has no recurrence")))
+}
+
+pub async fn list_task_recurrences(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<TaskRecurrenceRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = task_recurrence_query();
+    query.push(" WHERE t.project_id = ");
+    query.push_bind(project_id);
+    query.push(" ORDER BY t.task_number ASC");
+
+    let recurrences = query
+        .build_query_as::<TaskRecurrenceRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(recurrences)
+}
+
+/// Creates or replaces a task's recurrence. The next run is the schedule's first slot from now,
+/// so changing a schedule never creates copies for slots already passed.
+pub async fn set_task_recurrence(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    schedule: &str,
+    actor: &str,
+) -> AppResult<TaskRecurrenceRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    if task.archived_at.is_some() {
+        return Err(AppError::Conflict(format!(
+            "task '{task_ref}' is archived; unarchive it before scheduling copies"
+        )));
+    }
+
+    let schedule = schedule.trim();
+    let timezone = project_timezone(pool, project_slug).await?;
+    let next_run_at = Schedule::parse(schedule)?
+        .next_after(Utc::now(), timezone)
+        .map(recurrence::timestamp)
+        .ok_or_else(|| AppError::BadRequest(format!("schedule '{schedule}' never runs")))?;
+
+    let now = now_timestamp();
+    sqlx::query(
+        r#"
+        INSERT INTO task_recurrences (task_id, schedule, next_run_at, created_by, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT (task_id) DO UPDATE SET
+            schedule = excluded.schedule,
+            next_run_at = excluded.next_run_at,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(&task_id)
+    .bind(schedule)
+    .bind(&next_run_at)
+    .bind(actor)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+
+    get_task_recurrence(pool, project_slug, &task_id).await
+}
+
+pub async fn delete_task_recurrence(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<()> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let deleted = sqlx::query("DELETE FROM task_recurrences WHERE task_id = ?")
+        .bind(task_id)
+        .execute(pool)
+        .await?;
+
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "task '{task_ref}' has no recurrence"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Recurrences whose next run is at or before `now`. Archived tasks are not copied.
+pub async fn list_due_recurrences(
+    pool: &AnyPool,
+    now: &str,
+) -> AppResult<Vec<TaskRecurrenceRecord>> {
+    let mut query = task_recurrence_query();
+    query.push(" WHERE t.archived_at IS NULL AND r.next_run_at <= ");
+    query.push_bind(now.to_string());
+    query.push(" ORDER BY r.next_run_at ASC");
+
+    let recurrences = query
+        .build_query_as::<TaskRecurrenceRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(recurrences)
+}
+
+/// Claims a due recurrence by moving it on to `next_run_at`, then copies its task into a new
+/// `ready` task with the same title, description, priority, labels, and assignee. Returns `None`
+/// when another scan claimed the run first.
+///
+/// The claim and the copy commit together, so a copy that fails leaves the run due for the
+/// next scan.
+pub async fn run_task_recurrence(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    recurrence: &TaskRecurrenceRecord,
+    next_run_at: Option<&str>,
+) -> AppResult<Option<TaskRecord>> {
+    let source = get_task_record_by_id(pool, &recurrence.task_id).await?;
+    let mut labels = labels_by_task(pool, std::slice::from_ref(&source.id)).await?;
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+    let claimed = sqlx::query(
+        r#"
+        UPDATE task_recurrences
+        SET next_run_at = ?, last_run_at = ?, updated_at = ?
+        WHERE task_id = ? AND next_run_at = ?
+        "#,
+    )
+    .bind(next_run_at)
+    .bind(&now)
+    .bind(&now)
+    .bind(&recurrence.task_id)
+    .bind(&recurrence.next_run_at)
+    .execute(&mut *tx)
+    .await?;
+    if claimed.rows_affected() == 0 {
+        return Ok(None);
+    }
+
+    let task_id = insert_task(
+        &mut tx,
+        limits,
+        &recurrence.project_slug,
+        NewTaskInput {
+            title: source.title,
+            description: source.description,
+            status: "ready".to_string(),
+            priority: source.priority,
+            review_state: "ready".to_string(),
+            labels: labels.remove(&source.id).unwrap_or_default(),
+            created_by: "system".to_string(),
+            assignee: source.assignee,
+            due_at: None,
+        },
+    )
+    .await?;
+
+    sqlx::query("UPDATE task_recurrences SET last_task_id = ? WHERE task_id = ?")
+        .bind(&task_id)
+        .bind(&recurrence.task_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await.map(Some)
+}
+
+/// Summarizes `[since, until)`: tasks moved to done, questions asked, and in-progress tasks
+/// untouched since `stale_before` (when stale detection is enabled).
+pub async fn build_project_digest(
+    pool: &AnyPool,
+    project_slug: &str,
+    since: &str,
+    until: &str,
+    stale_before: Option<&str>,
+) -> AppResult<ProjectDigest> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let completed = sqlx::query_as::<Any, TaskRecord>(
+        r#"
+        SELECT
+            t.id,
+            t.project_id,
+            t.task_number,
+            t.title,
+            t.description,
+            t.status,
+            t.priority,
+            t.review_state,
+            t.sort_order,
+            t.created_by,
+            t.created_at,
+            t.updated_at,
+            t.archived_at,
+            t.assignee,
+            t.due_at,
+            t.parent_task_id,
+            t.milestone_id
+        FROM tasks t
+        WHERE t.project_id = ?
+          AND t.status = 'done'
+          AND EXISTS (
+              SELECT 1
+              FROM task_history h
+              WHERE h.task_id = t.id
+                AND h.action = 'task.moved'
+                AND h.created_at >= ?
+                AND h.created_at < ?
+          )
+        ORDER BY t.updated_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&project_id)
+    .bind(since)
+    .bind(until)
+    .bind(DIGEST_ITEM_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let new_questions = sqlx::query_as::<Any, ProjectQuestionRecord>(
+        r#"
+        SELECT
+            q.id,
+            q.task_id,
+            t.task_number,
+            q.question,
+            q.context,
+            q.answer,
+            q.status,
+            q.acceptance,
+            q.dispute_reason,
+            q.asked_by,
+            q.resolved_by,
+            q.created_at,
+            q.resolved_at
+        FROM open_questions q
+        INNER JOIN tasks t ON t.id = q.task_id
+        WHERE t.project_id = ? AND q.created_at >= ? AND q.created_at < ?
+        ORDER BY q.created_at DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&project_id)
+    .bind(since)
+    .bind(until)
+    .bind(DIGEST_ITEM_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let stalled = match stale_before {
+        Some(cutoff) => {
+            list_tasks(
+                pool,
+                project_slug,
+                TaskFilters {
+                    stale_before: Some(cutoff.to_string()),
+                    sort: Some("updated_at:asc".to_string()),
+                    ..TaskFilters::default()
+                },
+                DIGEST_ITEM_LIMIT,
+                0,
+            )
+            .await?
+        }
+        None => Vec::new(),
+    };
+
+    Ok(ProjectDigest {
+        project_slug: project_slug.to_string(),
+        since: since.to_string(),
+        until: until.to_string(),
+        completed,
+        new_questions,
+        stalled,
+    })
+}
+
+pub async fn list_review_checklist_template(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<ReviewChecklistTemplateRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let items = sqlx::query_as::<Any, ReviewChecklistTemplateRecord>(
+        r#"
+        SELECT id, project_id, title, sort_order, created_at
+        FROM review_checklist_templates
+        WHERE project_id = ?
+        ORDER BY sort_order ASC, created_at ASC
+        "#,
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+/// Replaces the project's review checklist template. Tasks already in review keep their items.
+pub async fn replace_review_checklist_template(
+    pool: &AnyPool,
+    project_slug: &str,
+    titles: Vec<String>,
+) -> AppResult<Vec<ReviewChecklistTemplateRecord>> {
+    let titles = titles
+        .into_iter()
+        .map(|title| title.trim().to_string())
+        .collect::<Vec<_>>();
+    if titles.iter().any(String::is_empty) {
+        return Err(AppError::BadRequest(
+            "review checklist items cannot be empty".to_string(),
+        ));
+    }
+
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM review_checklist_templates WHERE project_id = ?")
+        .bind(&project_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for (index, title) in titles.iter().enumerate() {
+        sqlx::query(
+            r#"
+            INSERT INTO review_checklist_templates (id, project_id, title, sort_order, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&project_id)
+        .bind(title)
+        .bind((index + 1) as f64)
+        .bind(&now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    list_review_checklist_template(pool, project_slug).await
+}
+
+pub async fn list_task_review_items(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<Vec<TaskReviewItemRecord>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    list_review_items_for_task(pool, &task_id).await
+}
+
+pub async fn set_review_item_done(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    item_id: &str,
+    done: bool,
+    actor: &str,
+) -> AppResult<TaskReviewItemRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let item = get_review_item_by_id(pool, &task_id, item_id).await?;
+    if (item.done == 1) == done {
+        return Ok(item);
+    }
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE task_review_items
+        SET done = ?, completed_by = ?, completed_at = ?
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(i64::from(done))
+    .bind(done.then_some(actor))
+    .bind(done.then_some(now.as_str()))
+    .bind(item_id)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::ReviewItemUpdated,
+        serde_json::json!({
+            "item_id": item_id,
+            "title": item.title,
+            "done": done,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    get_review_item_by_id(pool, &task_id, item_id).await
+}
+
+/// Subscribes `actor` to a task's notifications. Returns the task's watchers.
+pub async fn watch_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<Vec<String>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO task_watchers (task_id, actor, created_at)
+        VALUES (?, ?, ?)
+        ON CONFLICT (task_id, actor) DO NOTHING
+        "#,
+    )
+    .bind(&task_id)
+    .bind(actor)
+    .bind(now_timestamp())
+    .execute(pool)
+    .await?;
+
+    list_watchers_for_task(pool, &task_id).await
+}
+
+pub async fn unwatch_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<Vec<String>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    sqlx::query("DELETE FROM task_watchers WHERE task_id = ? AND actor = ?")
+        .bind(&task_id)
+        .bind(actor)
+        .execute(pool)
+        .await?;
+
+    list_watchers_for_task(pool, &task_id).await
+}
+
+pub async fn list_task_watchers(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<Vec<String>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    list_watchers_for_task(pool, &task_id).await
+}
+
+/// Newest-first inbox for `recipient`, limited to one workspace when given.
+pub async fn list_notifications(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+    unread_only: bool,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<NotificationRecord>> {
+    let mut query = notification_query(NOTIFICATION_COLUMNS, recipient, workspace_id);
+    if unread_only {
+        query.push(" AND n.read_at IS NULL");
+    }
+    query.push(" ORDER BY n.created_at DESC, n.id DESC LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+
+    let notifications = query
+        .build_query_as::<NotificationRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(notifications)
+}
+
+pub async fn count_notifications(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+    unread_only: bool,
+) -> AppResult<i64> {
+    let mut query = notification_query("SELECT COUNT(*)", recipient, workspace_id);
+    if unread_only {
+        query.push(" AND n.read_at IS NULL");
+    }
+    let total = query.build_query_scalar::<i64>().fetch_one(pool).await?;
+    Ok(total)
+}
+
+/// Notifications for `recipient` created after the `(created_at, id)` cursor, oldest first.
+pub async fn list_new_notifications(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+    cursor: Option<(&str, &str)>,
+    limit: i64,
+) -> AppResult<Vec<NotificationRecord>> {
+    let mut query = notification_query(NOTIFICATION_COLUMNS, recipient, workspace_id);
+    if let Some((created_at, id)) = cursor {
+        query.push(" AND (n.created_at > ");
+        query.push_bind(created_at.to_string());
+        query.push(" OR (n.created_at = ");
+        query.push_bind(created_at.to_string());
+        query.push(" AND n.id > ");
+        query.push_bind(id.to_string());
+        query.push("))");
+    }
+    query.push(" ORDER BY n.created_at ASC, n.id ASC LIMIT ");
+    query.push_bind(limit);
+
+    let notifications = query
+        .build_query_as::<NotificationRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(notifications)
+}
+
+pub async fn latest_notification_cursor(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+) -> AppResult<Option<(String, String)>> {
+    let mut query = notification_query("SELECT n.created_at, n.id", recipient, workspace_id);
+    query.push(" ORDER BY n.created_at DESC, n.id DESC LIMIT 1");
+    let row = query
+        .build_query_as::<(String, String)>()
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
+pub async fn mark_notification_read(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+    notification_id: &str,
+) -> AppResult<NotificationRecord> {
+    let notification = get_notification(pool, recipient, workspace_id, notification_id).await?;
+    if notification.read_at.is_some() {
+        return Ok(notification);
+    }
+
+    sqlx::query("UPDATE notifications SET read_at = ? WHERE id = ?")
+        .bind(now_timestamp())
+        .bind(&notification.id)
+        .execute(pool)
+        .await?;
+
+    get_notification(pool, recipient, workspace_id, notification_id).await
+}
+
+/// Marks every unread notification for `recipient` as read. Returns how many changed.
+pub async fn mark_all_notifications_read(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+) -> AppResult<u64> {
+    let mut query = QueryBuilder::<Any>::new("UPDATE notifications SET read_at = ");
+    query.push_bind(now_timestamp());
+    query.push(" WHERE read_at IS NULL AND recipient = ");
+    query.push_bind(recipient.to_string());
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND project_id IN (SELECT id FROM projects WHERE workspace_id = ");
+        query.push_bind(workspace_id.to_string());
+        query.push(')');
+    }
+
+    let result = query.build().execute(pool).await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn list_tasks(
+    pool: &AnyPool,
+    project_slug: &str,
+    filters: TaskFilters,
+    limit: i64,
+    offset: i64,
+) -> AppResult<Vec<TaskRecord>> {
+    let filters = resolve_parent_filter(pool, project_slug, filters).await?;
+    let order_by = match task_sort_clause(filters.sort.as_deref())? {
+        Some(clause) => clause.to_string(),
+        None => board_order(&column_order_by_slug(pool, project_slug).await?),
+    };
+    let mut query = task_filter_query(
+        r#"
+        SELECT
+            t.id,
+            t.project_id,
+            t.task_number,
+            t.title,
+            t.description,
+            t.status,
+            t.priority,
+            t.review_state,
+            t.sort_order,
+            t.created_by,
+            t.created_at,
+            t.updated_at,
+            t.archived_at,
+            t.assignee,
+            t.due_at,
+            t.parent_task_id,
+            t.milestone_id
+        "#,
+        project_slug,
+        filters,
+    )?;
+
+    query.push(" ORDER BY ");
+    query.push(order_by);
+    query.push(" LIMIT ");
+    query.push_bind(limit);
+    query.push(" OFFSET ");
+    query.push_bind(offset);
+
+    let tasks = query.build_query_as::<TaskRecord>().fetch_all(pool).await?;
+    Ok(tasks)
+}
+
+/// Fetches tasks by ref in the order given, skipping duplicates.
+///
+/// Refs that do not resolve to a task in this project come back in the second list instead of
+/// failing the whole batch.
+pub async fn get_tasks_by_refs(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_refs: &[String],
+) -> AppResult<(Vec<TaskRecord>, Vec<String>)> {
+    if task_refs.len() > MAX_BATCH_GET_TASKS {
+        return Err(AppError::BadRequest(format!(
+            "tasks cannot contain more than {MAX_BATCH_GET_TASKS} task refs"
+        )));
+    }
+
+    project_id_by_slug(pool, project_slug).await?;
+
+    let mut found: Vec<TaskRecord> = Vec::with_capacity(task_refs.len());
+    let mut missing = Vec::new();
+    for task_ref in task_refs {
+        let task_ref = task_ref.trim();
+        match resolve_task_id(pool, project_slug, task_ref).await {
+            Ok(task_id) => {
+                if !found.iter().any(|task| task.id == task_id) {
+                    found.push(get_task_record_by_id(pool, &task_id).await?);
+                }
+            }
+            Err(AppError::NotFound(_) | AppError::BadRequest(_)) => {
+                if !missing.iter().any(|value| value == task_ref) {
+                    missing.push(task_ref.to_string());
+                }
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok((found, missing))
+}
+
+/// Every column of a project's board in board order, with up to `per_column_limit` cards each.
+pub async fn get_board(
+    pool: &AnyPool,
+    project_slug: &str,
+    per_column_limit: i64,
+) -> AppResult<Vec<BoardColumn>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let settings = project_settings_by_id(pool, &project_id).await?;
+    let mut columns = Vec::with_capacity(settings.column_order.len());
+    for status in &settings.column_order {
+        let total = sqlx::query_scalar::<Any, i64>(
+            "SELECT COUNT(*) FROM tasks WHERE project_id = ? AND status = ? AND archived_at IS NULL",
+        )
+        .bind(&project_id)
+        .bind(status)
+        .fetch_one(pool)
+        .await?;
+
+        let rows = sqlx::query_as::<Any, (String, i64, String, String, String, f64)>(
+            r#"
+            SELECT id, task_number, title, priority, review_state, sort_order
+            FROM tasks
+            WHERE project_id = ? AND status = ? AND archived_at IS NULL
+            ORDER BY sort_order ASC, created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&project_id)
+        .bind(status)
+        .bind(per_column_limit)
+        .fetch_all(pool)
+        .await?;
+
+        let task_ids = rows.iter().map(|row| row.0.clone()).collect::<Vec<_>>();
+        let mut labels = labels_by_task(pool, &task_ids).await?;
+        let tasks = rows
+            .into_iter()
+            .map(
+                |(id, task_number, title, priority, review_state, sort_order)| BoardCard {
+                    labels: labels.remove(&id).unwrap_or_default(),
+                    id,
+                    display_key: display_key(project_slug, task_number),
+                    title,
+                    priority,
+                    review_state,
+                    sort_order,
+                },
+            )
+            .collect();
+
+        columns.push(BoardColumn {
+            color: settings.column_colors.get(status).cloned(),
+            status: status.clone(),
+            total,
+            tasks,
+        });
+    }
+
+    Ok(columns)
+}
+
+/// How board cards and task lists are split into swimlanes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swimlanes {
+    Priority,
+    Label,
+}
+
+impl Swimlanes {
+    pub fn parse(value: &str) -> AppResult<Self> {
+        match value.trim() {
+            "priority" => Ok(Self::Priority),
+            "label" => Ok(Self::Label),
+            other => Err(AppError::BadRequest(format!(
+                "group_by must be one of priority, label (got '{other}')"
+            ))),
+        }
+    }
+
+    /// Sorts `items` into non-empty lanes, keeping their order within each lane. Priority lanes
+    /// run from critical to low. Label lanes are alphabetical, with unlabeled items last under
+    /// a `None` key; an item with several labels appears in each of their lanes.
+    pub fn group<T: Clone>(
+        self,
+        items: Vec<T>,
+        priority: impl Fn(&T) -> &str,
+        labels: impl Fn(&T) -> &[String],
+    ) -> Vec<(Option<String>, Vec<T>)> {
+        let mut lanes: Vec<(Option<String>, Vec<T>)> = Vec::new();
+        for item in items {
+            let keys = match self {
+                Self::Priority => vec![Some(priority(&item).to_string())],
+                Self::Label if labels(&item).is_empty() => vec![None],
+                Self::Label => labels(&item).iter().cloned().map(Some).collect(),
+            };
+            for key in keys {
+                match lanes.iter_mut().find(|(lane, _)| *lane == key) {
+                    Some((_, members)) => members.push(item.clone()),
+                    None => lanes.push((key, vec![item.clone()])),
+                }
+            }
+        }
+
+        let rank = |key: &Option<String>| match (self, key) {
+            (Self::Priority, Some(priority)) => TASK_PRIORITIES
+                .iter()
+                .rev()
+                .position(|candidate| candidate == priority)
+                .unwrap_or(TASK_PRIORITIES.len()),
+            (Self::Label, Some(_)) => 0,
+            (_, None) => 1,
+        };
+        lanes.sort_by(|(left, _), (right, _)| {
+            rank(left).cmp(&rank(right)).then_with(|| left.cmp(right))
+        });
+        lanes
+    }
+}
+
+/// The board split into swimlanes, each with every column and up to `per_column_limit` of the
+/// lane's tasks per column. Column totals count the lane's tasks only.
+pub async fn get_board_lanes(
+    pool: &AnyPool,
+    project_slug: &str,
+    swimlanes: Swimlanes,
+    per_column_limit: i64,
+) -> AppResult<Vec<BoardLane>> {
+    let board = get_board(pool, project_slug, i64::MAX).await?;
+    let columns: Vec<(String, Option<String>)> = board
+        .iter()
+        .map(|column| (column.status.clone(), column.color.clone()))
+        .collect();
+    let cards: Vec<(usize, BoardCard)> = board
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, column)| column.tasks.into_iter().map(move |card| (index, card)))
+        .collect();
+    let per_column_limit = usize::try_from(per_column_limit).unwrap_or(usize::MAX);
+
+    let lanes = swimlanes.group(cards, |(_, card)| &card.priority, |(_, card)| &card.labels);
+    Ok(lanes
+        .into_iter()
+        .map(|(key, cards)| BoardLane {
+            key,
+            columns: columns
+                .iter()
+                .enumerate()
+                .map(|(index, (status, color))| {
+                    let tasks: Vec<BoardCard> = cards
+                        .iter()
+                        .filter(|(column, _)| *column == index)
+                        .map(|(_, card)| card.clone())
+                        .collect();
+                    BoardColumn {
+                        status: status.clone(),
+                        color: color.clone(),
+                        total: tasks.len() as i64,
+                        tasks: tasks.into_iter().take(per_column_limit).collect(),
+                    }
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Every column with all of its unarchived tasks, as of now.
+pub async fn board_snapshot(pool: &AnyPool, project_slug: &str) -> AppResult<BoardSnapshot> {
+    let columns = get_board(pool, project_slug, i64::MAX).await?;
+    Ok(BoardSnapshot {
+        project: project_slug.to_string(),
+        taken_at: now_timestamp(),
+        total: columns.iter().map(|column| column.total).sum(),
+        columns,
+    })
+}
+
+/// The snapshot stored for `date` (`YYYY-MM-DD`, UTC), as it was served that day.
+pub async fn get_board_snapshot(
+    pool: &AnyPool,
+    project_slug: &str,
+    date: &str,
+) -> AppResult<Value> {
+    let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("date must be a YYYY-MM-DD date".to_string()))?
+        .to_string();
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let snapshot = sqlx::query_scalar::<Any, String>(
+        "SELECT snapshot FROM board_snapshots WHERE project_id = ? AND snapshot_date = ?",
+    )
+    .bind(&project_id)
+    .bind(&date)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| {
+        AppError::NotFound(format!(
+            "no board snapshot for project '{project_slug}' on {date}"
+        ))
+    })?;
+
+    serde_json::from_str(&snapshot).map_err(|error| {
+        tracing::error!(error = ?error, project_id = %project_id, %date, "stored board snapshot is not valid JSON");
+        AppError::Internal
+    })
+}
+
+/// Slugs of projects without a stored snapshot for `date`.
+pub async fn list_projects_missing_board_snapshot(
+    pool: &AnyPool,
+    date: &str,
+) -> AppResult<Vec<String>> {
+    let slugs = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT p.slug
+        FROM projects p
+        WHERE NOT EXISTS (
+            SELECT 1 FROM board_snapshots s WHERE s.project_id = p.id AND s.snapshot_date = ?
+        )
+        ORDER BY p.slug ASC
+        "#,
+    )
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+    Ok(slugs)
+}
+
+/// Takes and stores the project's snapshot for `date` unless one exists already.
+pub async fn store_board_snapshot(pool: &AnyPool, project_slug: &str, date: &str) -> AppResult<()> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let snapshot = board_snapshot(pool, project_slug).await?;
+    let snapshot_json = serde_json::to_string(&snapshot).map_err(|error| {
+        tracing::error!(error = ?error, project_id = %project_id, "failed to serialize board snapshot");
+        AppError::Internal
+    })?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO board_snapshots (project_id, snapshot_date, snapshot, created_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT (project_id, snapshot_date) DO NOTHING
+        "#,
+    )
+    .bind(&project_id)
+    .bind(date)
+    .bind(snapshot_json)
+    .bind(&snapshot.taken_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Label and actor facets are paged with `limit`/`offset`, most common first; priorities are
+/// always complete.
+pub async fn project_facets(
+    pool: &AnyPool,
+    project_slug: &str,
+    limit: i64,
+    offset: i64,
+) -> AppResult<ProjectFacets> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let label_total = sqlx::query_scalar::<Any, i64>(
+        r#"
+        SELECT COUNT(DISTINCT l.label)
+        FROM task_labels l
+        INNER JOIN tasks t ON t.id = l.task_id
+        WHERE t.project_id = ? AND t.archived_at IS NULL
+        "#,
+    )
+    .bind(&project_id)
+    .fetch_one(pool)
+    .await?;
+    let labels = sqlx::query_as::<Any, (String, i64)>(
+        r#"
+        SELECT l.label, COUNT(*) AS task_count
+        FROM task_labels l
+        INNER JOIN tasks t ON t.id = l.task_id
+        WHERE t.project_id = ? AND t.archived_at IS NULL
+        GROUP BY l.label
+        ORDER BY task_count DESC, l.label ASC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(&project_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let actor_total = sqlx::query_scalar::<Any, i64>(
+        "SELECT COUNT(DISTINCT created_by) FROM tasks WHERE project_id = ? AND archived_at IS NULL",
+    )
+    .bind(&project_id)
+    .fetch_one(pool)
+    .await?;
+    let actors = sqlx::query_as::<Any, (String, i64, Option<String>)>(
+        r#"
+        SELECT t.created_by, COUNT(*) AS task_count, MAX(a.display_name)
+        FROM tasks t
+        LEFT JOIN actors a ON a.id = t.created_by
+        WHERE t.project_id = ? AND t.archived_at IS NULL
+        GROUP BY t.created_by
+        ORDER BY task_count DESC, t.created_by ASC
+        LIMIT ? OFFSET ?
+        "#,
+    )
+    .bind(&project_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+
+    let priority_counts: HashMap<String, i64> = sqlx::query_as::<Any, (String, i64)>(
+        r#"
+        SELECT priority, COUNT(*)
+        FROM tasks
+        WHERE project_id = ? AND archived_at IS NULL
+        GROUP BY priority
+        "#,
+    )
+    .bind(&project_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+
+    Ok(ProjectFacets {
+        labels: FacetPage {
+            items: labels
+                .into_iter()
+                .map(|(value, count)| FacetValue {
+                    value,
+                    count,
+                    display_name: None,
+                })
+                .collect(),
+            total: label_total,
+        },
+        actors: FacetPage {
+            items: actors
+                .into_iter()
+                .map(|(value, count, display_name)| FacetValue {
+                    value,
+                    count,
+                    display_name,
+                })
+                .collect(),
+            total: actor_total,
+        },
+        priorities: TASK_PRIORITIES
+            .iter()
+            .map(|priority| FacetValue {
+                value: priority.to_string(),
+                count: priority_counts.get(*priority).copied().unwrap_or(0),
+                display_name: None,
+            })
+            .collect(),
+        limit,
+        offset,
+    })
+}
+
+/// Number of tasks matching `filters`, ignoring pagination and sort.
+pub async fn count_tasks(
+    pool: &AnyPool,
+    project_slug: &str,
+    filters: TaskFilters,
+) -> AppResult<i64> {
+    let filters = resolve_parent_filter(pool, project_slug, filters).await?;
+    let mut query = task_filter_query("SELECT COUNT(*)", project_slug, filters)?;
+    let total = query.build_query_scalar::<i64>().fetch_one(pool).await?;
+    Ok(total)
+}
+
+/// Swaps a `parent` task ref for the parent's id so the filter can bind it directly.
+async fn resolve_parent_filter(
+    pool: &AnyPool,
+    project_slug: &str,
+    mut filters: TaskFilters,
+) -> AppResult<TaskFilters> {
+    if let Some(parent) = filters.parent.take() {
+        let parent = parent.trim();
+        filters.parent = Some(if parent == NO_PARENT_FILTER {
+            NO_PARENT_FILTER.to_string()
+        } else {
+            resolve_task_id(pool, project_slug, parent).await?
+        });
+    }
+    Ok(filters)
+}
+
+/// Validates `filters` and builds `{select} FROM tasks ... WHERE ...` for one project.
+fn task_filter_query<'a>(
+    select: &str,
+    project_slug: &'a str,
+    filters: TaskFilters,
+) -> AppResult<QueryBuilder<'a, Any>> {
+    for status in filters.statuses.iter().chain(&filters.exclude_statuses) {
+        validate_status(status)?;
+    }
+
+    if let Some(review_state) = filters.review_state.as_deref() {
+        validate_review_state(review_state)?;
+    }
+
+    let priorities = allowed_priorities(
+        &filters.priorities,
+        filters.min_priority.as_deref(),
+        filters.max_priority.as_deref(),
+    )?;
+    let created_after = normalize_filter_timestamp("created_after", filters.created_after)?;
+    let created_before = normalize_filter_timestamp("created_before", filters.created_before)?;
+    let updated_after = normalize_filter_timestamp("updated_after", filters.updated_after)?;
+    let updated_before = normalize_filter_timestamp("updated_before", filters.updated_before)?;
+    let due_before = normalize_filter_timestamp("due_before", filters.due_before)?;
+
+    let mut query = QueryBuilder::<Any>::new(select);
+    query.push(
+        r#"
+        FROM tasks t
+        INNER JOIN projects p ON p.id = t.project_id
+        WHERE p.slug =
+        "#,
+    );
+    query.push_bind(project_slug);
+
+    if filters.archived == Some(true) {
+        query.push(" AND t.archived_at IS NOT NULL");
+    } else if !filters.include_archived {
+        query.push(" AND t.archived_at IS NULL");
+    }
+
+    push_in_list(&mut query, "t.status", &filters.statuses, false);
+    push_in_list(&mut query, "t.status", &filters.exclude_statuses, true);
+
+    if let Some(priorities) = priorities {
+        push_in_list(&mut query, "t.priority", &priorities, false);
+    }
+
+    if let Some(review_state) = filters.review_state {
+        query.push(" AND t.review_state = ");
+        query.push_bind(review_state);
+    }
+
+    if let Some(created_by) = filters.created_by {
+        query.push(" AND t.created_by = ");
+        query.push_bind(created_by);
+    }
+
+    if !filters.assignees.is_empty() {
+        let (unassigned, named): (Vec<String>, Vec<String>) = filters
+            .assignees
+            .into_iter()
+            .partition(|assignee| assignee == UNASSIGNED_FILTER);
+        query.push(" AND (");
+        if !named.is_empty() {
+            query.push("t.assignee IN (");
+            let mut separated = query.separated(", ");
+            for assignee in named.iter() {
+                separated.push_bind(assignee.clone());
+            }
+            separated.push_unseparated(")");
+        }
+        if !unassigned.is_empty() {
+            if !named.is_empty() {
+                query.push(" OR ");
+            }
+            query.push("t.assignee IS NULL");
+        }
+        query.push(")");
+    }
+
+    if let Some(due_before) = due_before {
+        query.push(" AND t.due_at < ");
+        query.push_bind(due_before);
+    }
+
+    match filters.parent.as_deref() {
+        None => {}
+        Some(NO_PARENT_FILTER) => {
+            query.push(" AND t.parent_task_id IS NULL");
+        }
+        Some(parent_id) => {
+            query.push(" AND t.parent_task_id = ");
+            query.push_bind(parent_id.to_string());
+        }
+    }
+
+    match filters.overdue {
+        Some(true) => {
+            query.push(" AND t.status <> 'done' AND t.due_at < ");
+            query.push_bind(now_timestamp());
+        }
+        Some(false) => {
+            query.push(" AND (t.status = 'done' OR t.due_at IS NULL OR t.due_at >= ");
+            query.push_bind(now_timestamp());
+            query.push(")");
+        }
+        None => {}
+    }
+
+    for (clause, bound) in [
+        (" AND t.created_at >= ", created_after),
+        (" AND t.created_at < ", created_before),
+        (" AND t.updated_at >= ", updated_after),
+        (" AND t.updated_at < ", updated_before),
+    ] {
+        if let Some(bound) = bound {
+            query.push(clause);
+            query.push_bind(bound);
+        }
+    }
+
+    if let Some(stale_before) = filters.stale_before {
+        query.push(" AND t.status = 'in_progress' AND t.updated_at < ");
+        query.push_bind(stale_before);
+    }
+
+    for (labels, prefix) in [
+        (&filters.labels, " AND EXISTS ("),
+        (&filters.exclude_labels, " AND NOT EXISTS ("),
+    ] {
+        if labels.is_empty() {
+            continue;
+        }
+
+        query.push(prefix);
+        query.push("SELECT 1 FROM task_labels l WHERE l.task_id = t.id");
+        push_in_list(&mut query, "l.label", labels, false);
+        query.push(')');
+    }
+
+    Ok(query)
+}
+
+/// In-progress tasks untouched since `cutoff` that have not been flagged since their last update.
+pub async fn list_newly_stale_tasks(pool: &AnyPool, cutoff: &str) -> AppResult<Vec<TaskRecord>> {
+    let tasks = sqlx::query_as::<Any, TaskRecord>(
+        r#"
+        SELECT
+            t.id,
+            t.project_id,
+            t.task_number,
+            t.title,
+            t.description,
+            t.status,
+            t.priority,
+            t.review_state,
+            t.sort_order,
+            t.created_by,
+            t.created_at,
+            t.updated_at,
+            t.archived_at,
+            t.assignee,
+            t.due_at,
+            t.parent_task_id,
+            t.milestone_id
+        FROM tasks t
+        WHERE t.status = 'in_progress'
+          AND t.archived_at IS NULL
+          AND t.updated_at < ?
+          AND NOT EXISTS (
+              SELECT 1
+              FROM task_history h
+              WHERE h.task_id = t.id
+                AND h.action = 'task.stale'
+                AND h.created_at >= t.updated_at
+          )
+        ORDER BY t.updated_at ASC
+        "#,
+    )
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tasks)
+}
+
+/// Projects with auto-archive turned on, as `(project_id, auto_archive_after_days)`.
+pub async fn list_auto_archive_projects(pool: &AnyPool) -> AppResult<Vec<(String, i64)>> {
+    let projects = sqlx::query_as::<Any, (String, i64)>(
+        r#"
+        SELECT project_id, auto_archive_after_days
+        FROM project_settings
+        WHERE auto_archive_after_days > 0
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(projects)
+}
+
+/// Archives the project's done tasks that reached `done` before `cutoff` and records
+/// `task.archived` for each. Returns how many were archived.
+pub async fn archive_done_tasks(
+    pool: &AnyPool,
+    project_id: &str,
+    cutoff: &str,
+    after_days: i64,
+) -> AppResult<usize> {
+    let due = sqlx::query_as::<Any, (String, String)>(
+        r#"
+        SELECT id, COALESCE(status_changed_at, updated_at)
+        FROM tasks
+        WHERE project_id = ?
+          AND status = 'done'
+          AND archived_at IS NULL
+          AND COALESCE(status_changed_at, updated_at) < ?
+        "#,
+    )
+    .bind(project_id)
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    // A task can be reopened or archived by hand after the scan, so each update rechecks it.
+    let mut archived = 0;
+    for (task_id, done_since) in &due {
+        let mut tx = pool.begin().await?;
+        let changed = sqlx::query(
+            "UPDATE tasks SET archived_at = ? WHERE id = ? AND status = 'done' AND archived_at IS NULL",
+        )
+        .bind(now_timestamp())
+        .bind(task_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if changed == 0 {
+            continue;
+        }
+        insert_history(
+            &mut tx,
+            task_id,
+            "system",
+            EventKind::TaskArchived,
+            serde_json::json!({
+                "done_since": done_since,
+                "auto_archive_after_days": after_days,
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+        archived += 1;
+    }
+
+    Ok(archived)
+}
+
+pub async fn mark_task_stale(
+    pool: &AnyPool,
+    task: &TaskRecord,
+    stale_after_days: u32,
+) -> AppResult<()> {
+    let mut tx = pool.begin().await?;
+    insert_history(
+        &mut tx,
+        &task.id,
+        "system",
+        EventKind::TaskStale,
+        serde_json::json!({
+            "status": task.status,
+            "last_updated_at": task.updated_at,
+            "stale_after_days": stale_after_days,
+        }),
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn create_sla_policy(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: CreateSlaPolicyInput,
+) -> AppResult<SlaPolicyRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "sla policy name cannot be empty".to_string(),
+        ));
+    }
+    validate_status(&input.status)?;
+    if input.status == "done" {
+        return Err(AppError::BadRequest(
+            "sla policies cannot target 'done'".to_string(),
+        ));
+    }
+    if let Some(priority) = input.priority.as_deref() {
+        validate_priority(priority)?;
+    }
+    if !(1..=MAX_SLA_HOURS).contains(&input.max_hours) {
+        return Err(AppError::BadRequest(format!(
+            "max_hours must be between 1 and {MAX_SLA_HOURS}"
+        )));
+    }
+    if let Some(webhook_id) = input.webhook_id.as_deref() {
+        get_project_webhook(pool, project_slug, webhook_id).await?;
+    }
+
+    let policy_id = Uuid::new_v4().to_string();
+    let now = now_timestamp();
+    sqlx::query(
+        r#"
+        INSERT INTO sla_policies (
+            id, project_id, name, status, priority, max_hours, escalate_priority, webhook_id,
+            created_at, updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&policy_id)
+    .bind(&project_id)
+    .bind(&name)
+    .bind(&input.status)
+    .bind(input.priority)
+    .bind(input.max_hours)
+    .bind(i64::from(input.escalate_priority))
+    .bind(input.webhook_id)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+
+    let mut query = sla_policy_query();
+    query.push(" WHERE s.id = ");
+    query.push_bind(policy_id);
+    let policy = query
+        .build_query_as::<SlaPolicyRecord>()
+        .fetch_one(pool)
+        .await?;
+    Ok(policy)
+}
+
+pub async fn list_sla_policies(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<SlaPolicyRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = sla_policy_query();
+    query.push(" WHERE s.project_id = ");
+    query.push_bind(project_id);
+    query.push(" ORDER BY s.created_at ASC, s.id ASC");
+    let policies = query
+        .build_query_as::<SlaPolicyRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(policies)
+}
+
+pub async fn delete_sla_policy(
+    pool: &AnyPool,
+    project_slug: &str,
+    policy_id: &str,
+) -> AppResult<()> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let deleted = sqlx::query("DELETE FROM sla_policies WHERE id = ? AND project_id = ?")
+        .bind(policy_id)
+        .bind(project_id)
+        .execute(pool)
+        .await?;
+
+    if deleted.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!(
+            "sla policy '{policy_id}' not found"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Every SLA policy across projects, for the background evaluator.
+pub async fn list_all_sla_policies(pool: &AnyPool) -> AppResult<Vec<SlaPolicyRecord>> {
+    let mut query = sla_policy_query();
+    query.push(" ORDER BY s.created_at ASC, s.id ASC");
+    let policies = query
+        .build_query_as::<SlaPolicyRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(policies)
+}
+
+/// Tasks that entered the policy's status before `cutoff` and have not been flagged for
+/// this stay in that status yet.
+pub async fn list_sla_breach_candidates(
+    pool: &AnyPool,
+    policy: &SlaPolicyRecord,
+    cutoff: &str,
+) -> AppResult<Vec<TaskRecord>> {
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            t.id,
+            t.project_id,
+            t.task_number,
+            t.title,
+            t.description,
+            t.status,
+            t.priority,
+            t.review_state,
+            t.sort_order,
+            t.created_by,
+            t.created_at,
+            t.updated_at,
+            t.archived_at,
+            t.assignee,
+            t.due_at,
+            t.parent_task_id,
+            t.milestone_id
+        FROM tasks t
+        WHERE t.project_id =
+        "#,
+    );
+    query.push_bind(policy.project_id.clone());
+    query.push(" AND t.status = ");
+    query.push_bind(policy.status.clone());
+    if let Some(priority) = &policy.priority {
+        query.push(" AND t.priority = ");
+        query.push_bind(priority.clone());
+    }
+    query.push(" AND t.status_changed_at < ");
+    query.push_bind(cutoff.to_string());
+    query.push(
+        r#"
+          AND NOT EXISTS (
+              SELECT 1
+              FROM sla_breaches b
+              WHERE b.task_id = t.id
+                AND b.entered_at = t.status_changed_at
+                AND b.policy_id =
+        "#,
+    );
+    query.push_bind(policy.id.clone());
+    query.push(") ORDER BY t.status_changed_at ASC");
+
+    let tasks = query.build_query_as::<TaskRecord>().fetch_all(pool).await?;
+    Ok(tasks)
+}
+
+/// Records an `sla.breached` event for `task`, bumping its priority one step when the policy
+/// escalates. Returns the task as it is after escalation and the event detail.
+pub async fn record_sla_breach(
+    pool: &AnyPool,
+    policy: &SlaPolicyRecord,
+    task: &TaskRecord,
+) -> AppResult<(TaskRecord, Value)> {
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+    let entered_at =
+        sqlx::query_scalar::<Any, String>("SELECT status_changed_at FROM tasks WHERE id = ?")
+            .bind(&task.id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO sla_breaches (policy_id, task_id, entered_at, breached_at)
+        VALUES (?, ?, ?, ?)
+        "#,
+    )
+    .bind(&policy.id)
+    .bind(&task.id)
+    .bind(&entered_at)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    let mut detail = serde_json::json!({
+        "policy_id": policy.id,
+        "policy": policy.name,
+        "status": policy.status,
+        "max_hours": policy.max_hours,
+        "entered_at": entered_at,
+    });
+
+    let next_priority = TASK_PRIORITIES
+        .iter()
+        .position(|candidate| *candidate == task.priority)
+        .and_then(|index| TASK_PRIORITIES.get(index + 1));
+    if let (true, Some(next_priority)) = (policy.escalate_priority == 1, next_priority) {
+        sqlx::query("UPDATE tasks SET priority = ?, updated_at = ? WHERE id = ?")
+            .bind(*next_priority)
+            .bind(&now)
+            .bind(&task.id)
+            .execute(&mut *tx)
+            .await?;
+        detail["priority"] = serde_json::json!({
+            "from": task.priority,
+            "to": next_priority,
+        });
+    }
+
+    insert_history(
+        &mut tx,
+        &task.id,
+        "system",
+        EventKind::SlaBreached,
+        detail.clone(),
+    )
+    .await?;
+    if let Some(priority) = detail.get("priority") {
+        record_priority_change(
+            &mut tx,
+            &task.id,
+            "system",
+            &task.priority,
+            priority["to"].as_str().unwrap_or_default(),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    let task = get_task_record_by_id(pool, &task.id).await?;
+    Ok((task, detail))
+}
+
+/// Runs every check `create_task` makes and reports all failures, without creating the task
+/// or advancing the project's task counter.
+pub async fn dry_run_create_task(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    input: NewTaskInput,
+) -> AppResult<DryRunReport> {
+    let mut report = DryRunReport::default();
+    report.check(validate_status(&input.status))?;
+    report.check(validate_priority(&input.priority))?;
+    report.check(validate_review_state(&input.review_state))?;
+
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        report.reject(AppError::BadRequest(
+            "task title cannot be empty".to_string(),
+        ));
+    }
+    report.check(check_title_length(limits, "task title", &title))?;
+    report.check(check_description_length(limits, &input.description))?;
+    let assignee = report.check(normalize_assignee(input.assignee))?.flatten();
+    let due_at = report.check(normalize_due_at(input.due_at))?.flatten();
+
+    let task_counter =
+        sqlx::query_scalar::<Any, i64>("SELECT task_counter FROM projects WHERE slug = ?")
+            .bind(project_slug)
+            .fetch_optional(pool)
+            .await?;
+    if task_counter.is_none() {
+        report.reject(AppError::NotFound(format!(
+            "project '{project_slug}' not found"
+        )));
+    }
+
+    let requested_labels = input.labels.len();
+    let labels = normalized_labels(input.labels);
+    if labels.len() < requested_labels {
+        report.warn("blank and duplicate labels will be dropped");
+    }
+
+    Ok(report.finish(serde_json::json!({
+        "display_key": task_counter.map(|counter| display_key(project_slug, counter + 1)),
+        "title": title,
+        "status": input.status,
+        "priority": input.priority,
+        "review_state": input.review_state,
+        "labels": labels,
+        "created_by": input.created_by,
+        "assignee": assignee,
+        "due_at": due_at,
+    })))
+}
+
+pub async fn create_task(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    input: NewTaskInput,
+) -> AppResult<TaskRecord> {
+    let mut tx = pool.begin().await?;
+    let task_id = insert_task(&mut tx, limits, project_slug, input).await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await
+}
+
+/// Validates and inserts a task, with its labels and history, inside `tx`. Returns its id.
+async fn insert_task(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    input: NewTaskInput,
+) -> AppResult<String> {
+    validate_status(&input.status)?;
+    validate_priority(&input.priority)?;
+    validate_review_state(&input.review_state)?;
+
+    let title = input.title.trim().to_string();
+    if title.is_empty() {
+        return Err(AppError::BadRequest(
+            "task title cannot be empty".to_string(),
+        ));
+    }
+    check_title_length(limits, "task title", &title)?;
+    check_description_length(limits, &input.description)?;
+    let assignee = normalize_assignee(input.assignee)?;
+    let due_at = normalize_due_at(input.due_at)?;
+
+    let now = now_timestamp();
+    let task_id = Uuid::new_v4().to_string();
+
+    let project_id: String = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM projects
+        WHERE slug = ?
+        "#,
+    )
+    .bind(project_slug)
+    .fetch_optional(&mut **tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("project '{project_slug}' not found")))?;
+
+    sqlx::query("UPDATE projects SET task_counter = task_counter + 1, updated_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(&project_id)
+        .execute(&mut **tx)
+        .await?;
+
+    let task_number: i64 = sqlx::query_scalar("SELECT task_counter FROM projects WHERE id = ?")
+        .bind(&project_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    let sort_order: f64 = sqlx::query_scalar(
+        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
+    )
+    .bind(&project_id)
+    .bind(&input.status)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tasks (
+            id,
+            project_id,
+            task_number,
+            title,
+            description,
+            status,
+            priority,
+            review_state,
+            sort_order,
+            created_by,
+            assignee,
+            due_at,
+            created_at,
+            updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&task_id)
+    .bind(&project_id)
+    .bind(task_number)
+    .bind(&title)
+    .bind(&input.description)
+    .bind(&input.status)
+    .bind(&input.priority)
+    .bind(&input.review_state)
+    .bind(sort_order)
+    .bind(&input.created_by)
+    .bind(&assignee)
+    .bind(&due_at)
+    .bind(&now)
+    .bind(&now)
+    .execute(&mut **tx)
+    .await?;
+
+    let labels = normalized_labels(input.labels);
+    for label in labels {
+        sqlx::query("INSERT INTO task_labels (task_id, label) VALUES (?, ?)")
+            .bind(&task_id)
+            .bind(label)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    enter_status(tx, &project_id, &task_id, &input.status).await?;
+
+    insert_history(
+        tx,
+        &task_id,
+        &input.created_by,
+        EventKind::TaskCreated,
+        serde_json::json!({ "status": input.status, "priority": input.priority }),
+    )
+    .await?;
+    if assignee.is_some() {
+        record_assignee_change(tx, &task_id, &input.created_by, None, assignee.as_deref()).await?;
+    }
+    if due_at.is_some() {
+        record_due_date_change(tx, &task_id, &input.created_by, None, due_at.as_deref()).await?;
+    }
+    sync_references(
+        tx,
+        &task_id,
+        "task",
+        &task_id,
+        &format!("{title}\n{}", input.description),
+        &input.created_by,
+    )
+    .await?;
+
+    Ok(task_id)
+}
+
+pub async fn get_task_details(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<TaskDetails> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+
+    let labels: Vec<String> =
+        sqlx::query_scalar("SELECT label FROM task_labels WHERE task_id = ? ORDER BY label ASC")
+            .bind(&task.id)
+            .fetch_all(pool)
+            .await?;
+
+    let subtasks = list_subtasks(pool, &task.id).await?;
+
+    let open_questions = sqlx::query_as::<Any, OpenQuestionRecord>(
+        r#"
+        SELECT id, task_id, question, context, answer, status, acceptance, dispute_reason, asked_by, resolved_by, created_at, resolved_at
+        FROM open_questions
+        WHERE task_id = ?
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+
+    let reviews = list_reviews_for_task(pool, &task.id).await?;
+    let review_checklist = list_review_items_for_task(pool, &task.id).await?;
+
+    let attachments = sqlx::query_as::<Any, AttachmentRecord>(
+        r#"
+        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
+        FROM attachments
+        WHERE task_id = ?
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+
+    let history = sqlx::query_as::<Any, TaskHistoryRecord>(
+        r#"
+        SELECT
+            h.id,
+            h.task_id,
+            h.actor,
+            h.action,
+            h.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            h.created_at
+        FROM task_history h
+        LEFT JOIN actors a ON a.id = h.actor
+        WHERE h.task_id = ?
+        ORDER BY h.created_at DESC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+
+    let backlinks = sqlx::query_as::<Any, TaskBacklinkRecord>(
+        r#"
+        SELECT
+            t.id AS task_id,
+            p.slug || '-' || CAST(t.task_number AS TEXT) AS display_key,
+            t.title,
+            t.status,
+            l.source_kind,
+            l.source_id,
+            l.created_by,
+            l.created_at
+        FROM task_links l
+        JOIN tasks t ON t.id = l.source_task_id
+        JOIN projects p ON p.id = t.project_id
+        WHERE l.target_task_id = ?
+        ORDER BY l.created_at DESC, t.task_number DESC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mentions = sqlx::query_as::<Any, TaskMentionRecord>(
+        r#"
+        SELECT actor, source_kind, source_id, mentioned_by, created_at
+        FROM task_mentions
+        WHERE task_id = ?
+        ORDER BY created_at DESC, actor ASC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+
+    let dependencies = dependencies_for_task(pool, &task.id).await?;
+
+    let child_tasks = sqlx::query_as::<Any, ChildTaskRecord>(
+        r#"
+        SELECT
+            t.id AS task_id,
+            p.slug || '-' || CAST(t.task_number AS TEXT) AS display_key,
+            t.title,
+            t.status
+        FROM tasks t
+        JOIN projects p ON p.id = t.project_id
+        WHERE t.parent_task_id = ?
+        ORDER BY t.task_number ASC
+        "#,
+    )
+    .bind(&task.id)
+    .fetch_all(pool)
+    .await?;
+    let children = TaskChildren {
+        total: child_tasks.len() as i64,
+        done: child_tasks
+            .iter()
+            .filter(|child| child.status == "done")
+            .count() as i64,
+        tasks: child_tasks,
+    };
+
+    Ok(TaskDetails {
+        task,
+        labels,
+        subtasks,
+        open_questions,
+        reviews,
+        review_checklist,
+        attachments,
+        history,
+        backlinks,
+        mentions,
+        dependencies,
+        children,
+    })
+}
+
+/// Newest-first history for one task. `cursor` is the id of the last entry already seen.
+pub async fn list_task_history(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    filters: TaskHistoryFilters,
+    limit: i64,
+    cursor: Option<&str>,
+) -> AppResult<TaskHistoryPage> {
+    if limit <= 0 || limit > 100 {
+        return Err(AppError::BadRequest(
+            "limit must be between 1 and 100".to_string(),
+        ));
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+
+    let mut query = QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            h.id,
+            h.task_id,
+            h.actor,
+            h.action,
+            h.detail,
+            a.display_name AS actor_display_name,
+            a.kind AS actor_kind,
+            a.avatar_url AS actor_avatar_url,
+            h.created_at
+        FROM task_history h
+        LEFT JOIN actors a ON a.id = h.actor
+        WHERE h.task_id = "#,
+    );
+    query.push_bind(&task_id);
+
+    if let Some(action) = filters
+        .action
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        query.push(" AND h.action = ");
+        query.push_bind(action.to_string());
+    }
+
+    if let Some(actor) = filters
+        .actor
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        query.push(" AND h.actor = ");
+        query.push_bind(actor.to_string());
+    }
+
+    if let Some(cursor) = cursor {
+        let cursor_created_at = sqlx::query_scalar::<Any, String>(
+            "SELECT created_at FROM task_history WHERE id = ? AND task_id = ?",
+        )
+        .bind(cursor)
+        .bind(&task_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "cursor '{cursor}' is not a history entry of this task"
+            ))
+        })?;
+
+        query.push(" AND (h.created_at < ");
+        query.push_bind(cursor_created_at.clone());
+        query.push(" OR (h.created_at = ");
+        query.push_bind(cursor_created_at);
+        query.push(" AND h.id < ");
+        query.push_bind(cursor.to_string());
+        query.push("))");
+    }
+
+    // Fetch one extra row to learn whether another page exists.
+    query.push(" ORDER BY h.created_at DESC, h.id DESC LIMIT ");
+    query.push_bind(limit + 1);
+
+    let mut entries = query
+        .build_query_as::<TaskHistoryRecord>()
+        .fetch_all(pool)
+        .await?;
+
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|entry| entry.id.clone())
+    } else {
+        None
+    };
+
+    Ok(TaskHistoryPage {
+        entries,
+        next_cursor,
+    })
+}
+
+pub async fn add_subtask(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    title: &str,
+    actor: &str,
+) -> AppResult<SubtaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let trimmed_title = title.trim().to_string();
+    if trimmed_title.is_empty() {
+        return Err(AppError::BadRequest(
+            "subtask title cannot be empty".to_string(),
+        ));
+    }
+    check_title_length(limits, "subtask title", &trimmed_title)?;
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    let sort_order: f64 = sqlx::query_scalar(
+        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM subtasks WHERE task_id = ?",
+    )
+    .bind(&task_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let subtask_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO subtasks (id, task_id, title, done, sort_order, created_at)
+        VALUES (?, ?, ?, 0, ?, ?)
+        "#,
+    )
+    .bind(&subtask_id)
+    .bind(&task_id)
+    .bind(&trimmed_title)
+    .bind(sort_order)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::SubtaskCreated,
+        serde_json::json!({
+            "subtask_id": subtask_id,
+            "title": trimmed_title,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    get_subtask_by_id(pool, &task_id, &subtask_id).await
+}
+
+pub async fn update_subtask(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    subtask_id: &str,
+    input: UpdateSubtaskInput,
+) -> AppResult<SubtaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let existing = get_subtask_by_id(pool, &task_id, subtask_id).await?;
+
+    let title = match input.title {
+        Some(value) => {
+            let trimmed = value.trim().to_string();
+            if trimmed.is_empty() {
+                return Err(AppError::BadRequest(
+                    "subtask title cannot be empty".to_string(),
+                ));
+            }
+            if trimmed != existing.title {
+                check_title_length(limits, "subtask title", &trimmed)?;
+            }
+            trimmed
+        }
+        None => existing.title.clone(),
+    };
+
+    let done = input.done.map_or(existing.done, i64::from);
+    let sort_order = input.sort_order.unwrap_or(existing.sort_order);
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE subtasks SET title = ?, done = ?, sort_order = ? WHERE id = ? AND task_id = ?",
+    )
+    .bind(&title)
+    .bind(done)
+    .bind(sort_order)
+    .bind(subtask_id)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        &input.actor,
+        EventKind::SubtaskUpdated,
+        serde_json::json!({
+            "subtask_id": subtask_id,
+            "title": title,
+            "done": done == 1,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    get_subtask_by_id(pool, &task_id, subtask_id).await
+}
+
+/// Reorders a task's checklist and applies done flags in one transaction.
+///
+/// Listed subtasks take positions 1..n in the given order; any not listed keep their relative
+/// order after them. Records a single `subtasks.updated` history entry.
+pub async fn bulk_update_subtasks(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    items: Vec<SubtaskBulkItem>,
+    actor: &str,
+) -> AppResult<Vec<SubtaskRecord>> {
+    if items.is_empty() {
+        return Err(AppError::BadRequest(
+            "subtasks must contain at least one subtask".to_string(),
+        ));
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let existing = list_subtasks(pool, &task_id).await?;
+
+    for (index, item) in items.iter().enumerate() {
+        if !existing.iter().any(|subtask| subtask.id == item.id) {
+            return Err(AppError::NotFound(format!(
+                "subtask '{}' not found on task '{task_ref}'",
+                item.id
+            )));
+        }
+        if items[..index].iter().any(|other| other.id == item.id) {
+            return Err(AppError::BadRequest(format!(
+                "subtask '{}' is listed more than once",
+                item.id
+            )));
+        }
+    }
+
+    let remaining = existing
+        .iter()
+        .filter(|subtask| !items.iter().any(|item| item.id == subtask.id));
+    let ordered = items
+        .iter()
+        .map(|item| (item.id.as_str(), item.done))
+        .chain(remaining.map(|subtask| (subtask.id.as_str(), None)));
+
+    let mut toggled = Vec::new();
+    let mut tx = pool.begin().await?;
+    for (index, (subtask_id, done)) in ordered.enumerate() {
+        let current = existing
+            .iter()
+            .find(|subtask| subtask.id == subtask_id)
+            .map_or(0, |subtask| subtask.done);
+        let done = done.map_or(current, i64::from);
+        if done != current {
+            toggled.push(serde_json::json!({ "subtask_id": subtask_id, "done": done == 1 }));
+        }
+
+        sqlx::query("UPDATE subtasks SET done = ?, sort_order = ? WHERE id = ? AND task_id = ?")
+            .bind(done)
+            .bind((index + 1) as f64)
+            .bind(subtask_id)
+            .bind(&task_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::SubtasksUpdated,
+        serde_json::json!({
+            "subtask_ids": items.iter().map(|item| item.id.as_str()).collect::<Vec<_>>(),
+            "toggled": toggled,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    list_subtasks(pool, &task_id).await
+}
+
+pub async fn delete_subtask(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    subtask_id: &str,
+    actor: &str,
+) -> AppResult<()> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let title: String =
+        sqlx::query_scalar("SELECT title FROM subtasks WHERE id = ? AND task_id = ?")
+            .bind(subtask_id)
+            .bind(&task_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "subtask '{subtask_id}' not found on task '{task_ref}'"
+                ))
+            })?;
+
+    sqlx::query("DELETE FROM subtasks WHERE id = ? AND task_id = ?")
+        .bind(subtask_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::SubtaskDeleted,
+        serde_json::json!({ "subtask_id": subtask_id, "title": title }),
+    )
+    .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn update_task(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    task_ref: &str,
+    input: UpdateTaskInput,
+) -> AppResult<TaskRecord> {
+    let details = get_task_details(pool, project_slug, task_ref).await?;
+    let plan = plan_task_update(limits, details, input)?;
+
+    let mut tx = pool.begin().await?;
+    apply_task_update(&mut tx, &plan).await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &plan.task_id).await
+}
+
+/// Applies up to `MAX_BULK_UPDATE_TASKS` updates in one transaction: every update is
+/// validated before anything is written, and any failure leaves all tasks untouched.
+pub async fn update_tasks_bulk(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    project_slug: &str,
+    updates: Vec<(String, UpdateTaskInput)>,
+) -> AppResult<Vec<TaskRecord>> {
+    if updates.is_empty() {
+        return Err(AppError::BadRequest("updates cannot be empty".to_string()));
+    }
+    if updates.len() > MAX_BULK_UPDATE_TASKS {
+        return Err(AppError::BadRequest(format!(
+            "too many updates in one call, max is {MAX_BULK_UPDATE_TASKS}"
+        )));
+    }
+
+    let mut plans: Vec<TaskUpdatePlan> = Vec::with_capacity(updates.len());
+    for (task_ref, input) in updates {
+        let details = get_task_details(pool, project_slug, &task_ref).await?;
+        if plans.iter().any(|plan| plan.task_id == details.task.id) {
+            return Err(AppError::BadRequest(format!(
+                "task '{task_ref}' appears more than once"
+            )));
+        }
+        plans.push(plan_task_update(limits, details, input)?);
+    }
+
+    let mut tx = pool.begin().await?;
+    for plan in &plans {
+        apply_task_update(&mut tx, plan).await?;
+    }
+    tx.commit().await?;
+
+    let mut tasks = Vec::with_capacity(plans.len());
+    for plan in &plans {
+        tasks.push(get_task_record_by_id(pool, &plan.task_id).await?);
+    }
+    Ok(tasks)
+}
+
+/// A validated task update, ready to be written inside a transaction.
+struct TaskUpdatePlan {
+    task_id: String,
+    project_id: String,
+    status_changed: bool,
+    title: String,
+    description: String,
+    status: String,
+    priority: String,
+    review_state: String,
+    labels: Option<Vec<String>>,
+    assignee: Option<String>,
+    due_at: Option<String>,
+    actor: String,
+    changes: serde_json::Map<String, serde_json::Value>,
+}
+
+fn plan_task_update(
+    limits: &FieldLimitConfig,
+    details: TaskDetails,
+    input: UpdateTaskInput,
+) -> AppResult<TaskUpdatePlan> {
+    let previous = details.task.clone();
+    let task = details.task;
+
+    let title = match input.title {
+        Some(value) => {
+            let trimmed = value.trim().to_string();
+            if trimmed.is_empty() {
+                return Err(AppError::BadRequest(
+                    "task title cannot be empty".to_string(),
+                ));
+            }
+            // Unchanged values pass even if they predate a lower limit.
+            if trimmed != task.title {
+                check_title_length(limits, "task title", &trimmed)?;
+            }
+            trimmed
+        }
+        None => task.title,
+    };
+
+    let description = match input.description {
+        Some(value) => {
+            if value != task.description {
+                check_description_length(limits, &value)?;
+            }
+            value
+        }
+        None => task.description,
+    };
+
+    let status = match input.status {
+        Some(value) => {
+            validate_status(&value)?;
+            value
+        }
+        None => task.status,
+    };
+
+    let priority = match input.priority {
+        Some(value) => {
+            validate_priority(&value)?;
+            value
+        }
+        None => task.priority,
+    };
+
+    let review_state = match input.review_state {
+        Some(value) => {
+            validate_review_state(&value)?;
+            value
+        }
+        None => task.review_state,
+    };
+
+    let assignee = match input.assignee {
+        Some(value) => normalize_assignee(Some(value))?,
+        None => task.assignee,
+    };
+
+    let due_at = match input.due_at {
+        Some(value) => normalize_due_at(Some(value))?,
+        None => task.due_at,
+    };
+
+    // `changes` keeps the previous values so the update can be undone later. Descriptions
+    // can be long, so they are stored as a line diff rather than two full copies.
+    let mut changes = serde_json::Map::new();
+    if description != previous.description {
+        changes.insert(
+            "description".to_string(),
+            description_change(&previous.description, &description),
+        );
+    }
+    for (field, from, to) in [
+        ("title", &previous.title, &title),
+        ("status", &previous.status, &status),
+        ("priority", &previous.priority, &priority),
+        ("review_state", &previous.review_state, &review_state),
+    ] {
+        if from != to {
+            changes.insert(
+                field.to_string(),
+                serde_json::json!({ "from": from, "to": to }),
+            );
+        }
+    }
+
+    for (field, from, to) in [
+        ("assignee", &previous.assignee, &assignee),
+        ("due_at", &previous.due_at, &due_at),
+    ] {
+        if from != to {
+            changes.insert(
+                field.to_string(),
+                serde_json::json!({ "from": from, "to": to }),
+            );
+        }
+    }
+
+    let labels = input.labels.map(normalized_labels);
+    if let Some(normalized) = labels.as_ref().filter(|labels| **labels != details.labels) {
+        changes.insert(
+            "labels".to_string(),
+            serde_json::json!({ "from": details.labels, "to": normalized }),
+        );
+    }
+
+    Ok(TaskUpdatePlan {
+        task_id: task.id,
+        project_id: task.project_id,
+        status_changed: status != previous.status,
+        title,
+        description,
+        status,
+        priority,
+        review_state,
+        labels,
+        assignee,
+        due_at,
+        actor: input.actor,
+        changes,
+    })
+}
+
+async fn apply_task_update(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    plan: &TaskUpdatePlan,
+) -> AppResult<()> {
+    if plan.status_changed {
+        enter_status(tx, &plan.project_id, &plan.task_id, &plan.status).await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE tasks
+        SET title = ?, description = ?, status = ?, priority = ?, review_state = ?, assignee = ?,
+            due_at = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&plan.title)
+    .bind(&plan.description)
+    .bind(&plan.status)
+    .bind(&plan.priority)
+    .bind(&plan.review_state)
+    .bind(&plan.assignee)
+    .bind(&plan.due_at)
+    .bind(now_timestamp())
+    .bind(&plan.task_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if let Some(labels) = &plan.labels {
+        replace_task_labels(tx, &plan.task_id, labels).await?;
+    }
+
+    insert_history(
+        tx,
+        &plan.task_id,
+        &plan.actor,
+        EventKind::TaskUpdated,
+        serde_json::json!({
+            "status": plan.status,
+            "priority": plan.priority,
+            "review_state": plan.review_state,
+            "assignee": plan.assignee,
+            "due_at": plan.due_at,
+            "changes": plan.changes,
+        }),
+    )
+    .await?;
+
+    if let (Some(change), Some(labels)) = (plan.changes.get("labels"), &plan.labels) {
+        let previous = string_list(&change["from"]);
+        record_labels_change(tx, &plan.task_id, &plan.actor, &previous, labels).await?;
+    }
+    if let Some(change) = plan.changes.get("priority") {
+        record_priority_change(
+            tx,
+            &plan.task_id,
+            &plan.actor,
+            change["from"].as_str().unwrap_or_default(),
+            &plan.priority,
+        )
+        .await?;
+    }
+    if let Some(change) = plan.changes.get("assignee") {
+        record_assignee_change(
+            tx,
+            &plan.task_id,
+            &plan.actor,
+            change["from"].as_str(),
+            plan.assignee.as_deref(),
+        )
+        .await?;
+    }
+    if let Some(change) = plan.changes.get("due_at") {
+        record_due_date_change(
+            tx,
+            &plan.task_id,
+            &plan.actor,
+            change["from"].as_str(),
+            plan.due_at.as_deref(),
+        )
+        .await?;
+    }
+
+    if plan.changes.contains_key("title") || plan.changes.contains_key("description") {
+        sync_references(
+            tx,
+            &plan.task_id,
+            "task",
+            &plan.task_id,
+            &format!("{}\n{}", plan.title, plan.description),
+            &plan.actor,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn list_task_dependencies(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<TaskDependencies> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    dependencies_for_task(pool, &task_id).await
+}
+
+/// Records that `blocker_ref` blocks `task_ref`. Both tasks must be in the project, and the
+/// link may not close a cycle. Adding a link that already exists changes nothing.
+pub async fn add_task_dependency(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    blocker_ref: &str,
+    actor: &str,
+) -> AppResult<TaskDependencies> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let blocker_id = resolve_task_id(pool, project_slug, blocker_ref).await?;
+    if task_id == blocker_id {
+        return Err(AppError::BadRequest(
+            "a task cannot block itself".to_string(),
+        ));
+    }
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    let blocker = get_task_record_by_id(pool, &blocker_id).await?;
+    let task_key = display_key(project_slug, task.task_number);
+    let blocker_key = display_key(project_slug, blocker.task_number);
+
+    let mut tx = pool.begin().await?;
+    let links = sqlx::query_as::<Any, (String, String)>(
+        r#"
+        SELECT d.blocker_task_id, d.blocked_task_id
+        FROM task_dependencies d
+        INNER JOIN tasks t ON t.id = d.blocked_task_id
+        WHERE t.project_id = ?
+        "#,
+    )
+    .bind(&task.project_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if blocks_transitively(&links, &task_id, &blocker_id) {
+        return Err(AppError::Conflict(format!(
+            "{task_key} already blocks {blocker_key}, so {blocker_key} cannot block it"
+        )));
+    }
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO task_dependencies (blocker_task_id, blocked_task_id, created_by, created_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT (blocker_task_id, blocked_task_id) DO NOTHING
+        "#,
+    )
+    .bind(&blocker_id)
+    .bind(&task_id)
+    .bind(actor)
+    .bind(now_timestamp())
+    .execute(&mut *tx)
+    .await?;
+    if inserted.rows_affected() > 0 {
+        insert_history(
+            &mut tx,
+            &task_id,
+            actor,
+            EventKind::TaskDependencyAdded,
+            serde_json::json!({ "blocker_task_id": blocker_id, "blocker_key": blocker_key }),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    dependencies_for_task(pool, &task_id).await
+}
+
+/// Removes the link making `blocker_ref` block `task_ref`, if there is one.
+pub async fn remove_task_dependency(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    blocker_ref: &str,
+    actor: &str,
+) -> AppResult<TaskDependencies> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let blocker_id = resolve_task_id(pool, project_slug, blocker_ref).await?;
+    let blocker = get_task_record_by_id(pool, &blocker_id).await?;
+
+    let mut tx = pool.begin().await?;
+    let deleted = sqlx::query(
+        "DELETE FROM task_dependencies WHERE blocker_task_id = ? AND blocked_task_id = ?",
+    )
+    .bind(&blocker_id)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+    if deleted.rows_affected() > 0 {
+        insert_history(
+            &mut tx,
+            &task_id,
+            actor,
+            EventKind::TaskDependencyRemoved,
+            serde_json::json!({
+                "blocker_task_id": blocker_id,
+                "blocker_key": display_key(project_slug, blocker.task_number),
+            }),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    dependencies_for_task(pool, &task_id).await
+}
+
+/// Puts `task_ref` under `parent_ref`, or detaches it when `parent_ref` is `None`.
+pub async fn set_task_parent(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    parent_ref: Option<&str>,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    let task_key = display_key(project_slug, task.task_number);
+    let parent = match parent_ref {
+        Some(parent_ref) => {
+            let parent_id = resolve_task_id(pool, project_slug, parent_ref).await?;
+            if parent_id == task_id {
+                return Err(AppError::BadRequest(
+                    "a task cannot be its own parent".to_string(),
+                ));
+            }
+            Some(get_task_record_by_id(pool, &parent_id).await?)
+        }
+        None => None,
+    };
+    if task.parent_task_id.as_deref() == parent.as_ref().map(|parent| parent.id.as_str()) {
+        return Ok(task);
+    }
+
+    let mut tx = pool.begin().await?;
+    if let Some(parent) = &parent {
+        // Walk up from the new parent; meeting the task means it would become its own ancestor.
+        let mut ancestor = parent.parent_task_id.clone();
+        while let Some(ancestor_id) = ancestor {
+            if ancestor_id == task_id {
+                return Err(AppError::Conflict(format!(
+                    "{} is already under {task_key}, so it cannot be its parent",
+                    display_key(project_slug, parent.task_number)
+                )));
+            }
+            ancestor = sqlx::query_scalar::<Any, Option<String>>(
+                "SELECT parent_task_id FROM tasks WHERE id = ?",
+            )
+            .bind(&ancestor_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .flatten();
+        }
+    }
+
+    let from_parent_key = match task.parent_task_id.as_deref() {
+        Some(previous_id) => {
+            let previous = get_task_record_by_id(pool, previous_id).await?;
+            Some(display_key(project_slug, previous.task_number))
+        }
+        None => None,
+    };
+    let to_parent_key = parent
+        .as_ref()
+        .map(|parent| display_key(project_slug, parent.task_number));
+
+    sqlx::query("UPDATE tasks SET parent_task_id = ?, updated_at = ? WHERE id = ?")
+        .bind(parent.as_ref().map(|parent| parent.id.clone()))
+        .bind(now_timestamp())
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskParentChanged,
+        serde_json::json!({
+            "from_parent_key": from_parent_key,
+            "to_parent_key": to_parent_key,
+        }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await
+}
+
+pub async fn list_milestones(
+    pool: &AnyPool,
+    project_slug: &str,
+) -> AppResult<Vec<MilestoneRecord>> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = milestone_query();
+    query.push(" WHERE m.project_id = ");
+    query.push_bind(project_id);
+    // Undated milestones sort last.
+    query.push(" ORDER BY m.target_date IS NULL, m.target_date ASC, m.created_at ASC, m.id ASC");
+    let milestones = query
+        .build_query_as::<MilestoneRecord>()
+        .fetch_all(pool)
+        .await?;
+    Ok(milestones)
+}
+
+pub async fn get_milestone(
+    pool: &AnyPool,
+    project_slug: &str,
+    milestone_id: &str,
+) -> AppResult<MilestoneRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let mut query = milestone_query();
+    query.push(" WHERE m.id = ");
+    query.push_bind(milestone_id.to_string());
+    query.push(" AND m.project_id = ");
+    query.push_bind(project_id);
+    query
+        .build_query_as::<MilestoneRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("milestone '{milestone_id}' not found")))
+}
+
+pub async fn create_milestone(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: CreateMilestoneInput,
+) -> AppResult<MilestoneRecord> {
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let name = normalize_milestone_name(&input.name)?;
+    let target_date = normalize_target_date(input.target_date)?;
+    check_milestone_name_free(pool, &project_id, &name, None).await?;
+
+    let milestone_id = Uuid::new_v4().to_string();
+    let now = now_timestamp();
+    sqlx::query(
+        r#"
+        INSERT INTO milestones (id, project_id, name, target_date, status, created_at, updated_at)
+        VALUES (?, ?, ?, ?, 'open', ?, ?)
+        "#,
+    )
+    .bind(&milestone_id)
+    .bind(&project_id)
+    .bind(&name)
+    .bind(target_date)
+    .bind(&now)
+    .bind(&now)
+    .execute(pool)
+    .await?;
+
+    get_milestone(pool, project_slug, &milestone_id).await
+}
+
+pub async fn update_milestone(
+    pool: &AnyPool,
+    project_slug: &str,
+    milestone_id: &str,
+    input: UpdateMilestoneInput,
+) -> AppResult<MilestoneRecord> {
+    let existing = get_milestone(pool, project_slug, milestone_id).await?;
+    let name = match input.name {
+        Some(value) => normalize_milestone_name(&value)?,
+        None => existing.name,
+    };
+    let target_date = match input.target_date {
+        Some(value) => normalize_target_date(Some(value))?,
+        None => existing.target_date,
+    };
+    let status = input.status.unwrap_or(existing.status);
+    if !MILESTONE_STATUSES.contains(&status.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "invalid milestone status '{status}', expected one of: {}",
+            MILESTONE_STATUSES.join(", ")
+        )));
+    }
+    check_milestone_name_free(pool, &existing.project_id, &name, Some(milestone_id)).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE milestones
+        SET name = ?, target_date = ?, status = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&name)
+    .bind(target_date)
+    .bind(&status)
+    .bind(now_timestamp())
+    .bind(milestone_id)
+    .execute(pool)
+    .await?;
+
+    get_milestone(pool, project_slug, milestone_id).await
+}
+
+/// Deletes a milestone; its tasks stay and are detached from it.
+pub async fn delete_milestone(
+    pool: &AnyPool,
+    project_slug: &str,
+    milestone_id: &str,
+) -> AppResult<()> {
+    let milestone = get_milestone(pool, project_slug, milestone_id).await?;
+    sqlx::query("DELETE FROM milestones WHERE id = ?")
+        .bind(milestone.id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Attaches `task_ref` to a milestone of its project, or detaches it when `milestone_id` is
+/// `None`.
+pub async fn set_task_milestone(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    milestone_id: Option<&str>,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    let milestone = match milestone_id {
+        Some(milestone_id) => Some(get_milestone(pool, project_slug, milestone_id).await?),
+        None => None,
+    };
+    if task.milestone_id.as_deref() == milestone.as_ref().map(|milestone| milestone.id.as_str()) {
+        return Ok(task);
+    }
+    let from_milestone = match task.milestone_id.as_deref() {
+        Some(previous_id) => Some(get_milestone(pool, project_slug, previous_id).await?.name),
+        None => None,
+    };
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE tasks SET milestone_id = ?, updated_at = ? WHERE id = ?")
+        .bind(milestone.as_ref().map(|milestone| milestone.id.clone()))
+        .bind(now_timestamp())
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskMilestoneChanged,
+        serde_json::json!({
+            "from_milestone": from_milestone,
+            "to_milestone": milestone.map(|milestone| milestone.name),
+        }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await
+}
+
+fn normalize_milestone_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "milestone name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Parses a `YYYY-MM-DD` target date; blank clears it.
+fn normalize_target_date(value: Option<String>) -> AppResult<Option<String>> {
+    let Some(value) = value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| {
+        AppError::BadRequest(format!(
+            "target_date must be a YYYY-MM-DD date (got '{value}')"
+        ))
+    })?;
+    Ok(Some(date.to_string()))
+}
+
+/// Fails with a conflict when another milestone of the project already uses `name`.
+async fn check_milestone_name_free(
+    pool: &AnyPool,
+    project_id: &str,
+    name: &str,
+    except_id: Option<&str>,
+) -> AppResult<()> {
+    let owner = sqlx::query_scalar::<Any, String>(
+        "SELECT id FROM milestones WHERE project_id = ? AND name = ?",
+    )
+    .bind(project_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+    match owner {
+        Some(owner) if Some(owner.as_str()) != except_id => Err(AppError::Conflict(format!(
+            "milestone '{name}' already exists"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn dependencies_for_task(pool: &AnyPool, task_id: &str) -> AppResult<TaskDependencies> {
+    // `{other}` is the column naming the task at the far end of the link.
+    let linked = |this: &str, other: &str| {
+        format!(
+            r#"
+            SELECT
+                t.id AS task_id,
+                p.slug || '-' || CAST(t.task_number AS TEXT) AS display_key,
+                t.title,
+                t.status,
+                d.created_by,
+                d.created_at
+            FROM task_dependencies d
+            JOIN tasks t ON t.id = d.{other}
+            JOIN projects p ON p.id = t.project_id
+            WHERE d.{this} = ? AND t.archived_at IS NULL
+            ORDER BY t.task_number ASC
+            "#
+        )
+    };
+
+    let blocked_by =
+        sqlx::query_as::<Any, TaskDependencyRecord>(&linked("blocked_task_id", "blocker_task_id"))
+            .bind(task_id)
+            .fetch_all(pool)
+            .await?;
+    let blocks =
+        sqlx::query_as::<Any, TaskDependencyRecord>(&linked("blocker_task_id", "blocked_task_id"))
+            .bind(task_id)
+            .fetch_all(pool)
+            .await?;
+
+    Ok(TaskDependencies { blocked_by, blocks })
+}
+
+/// Whether `to` can be reached from `from` by following `(blocker, blocked)` links.
+fn blocks_transitively(links: &[(String, String)], from: &str, to: &str) -> bool {
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![from];
+    while let Some(current) = pending.pop() {
+        if current == to {
+            return true;
+        }
+        if !seen.insert(current) {
+            continue;
+        }
+        pending.extend(
+            links
+                .iter()
+                .filter(|(blocker, _)| blocker == current)
+                .map(|(_, blocked)| blocked.as_str()),
+        );
+    }
+    false
+}
+
+pub async fn move_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    input: MoveTaskInput,
+) -> AppResult<TaskRecord> {
+    validate_status(&input.status)?;
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+
+    let settings = project_settings_by_id(pool, &task.project_id).await?;
+    check_review_gate(&settings, &task, task_ref, &input.status, input.mcp_origin)?;
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    let sort_order = match input.sort_order {
+        Some(value) => value,
+        None => {
+            sqlx::query_scalar::<Any, f64>(
+                "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
+            )
+            .bind(&task.project_id)
+            .bind(&input.status)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    if task.status != input.status {
+        enter_status(&mut tx, &task.project_id, &task.id, &input.status).await?;
+    }
+
+    sqlx::query("UPDATE tasks SET status = ?, sort_order = ?, updated_at = ? WHERE id = ?")
+        .bind(&input.status)
+        .bind(sort_order)
+        .bind(&now)
+        .bind(&task.id)
+        .execute(&mut *tx)
+        .await?;
+
+    insert_history(
+        &mut tx,
+        &task.id,
+        &input.actor,
+        EventKind::TaskMoved,
+        serde_json::json!({
+            "from_status": task.status,
+            "to_status": input.status,
+            "from_sort_order": task.sort_order,
+            "sort_order": sort_order,
+        }),
+    )
+    .await?;
+
+    // Client-computed fractional positions shrink with every insertion between neighbours.
+    if input.sort_order.is_some()
+        && column_needs_rebalance(&mut tx, &task.project_id, &input.status).await?
+    {
+        rebalance_column(&mut tx, &task.project_id, &input.status).await?;
+    }
+
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task.id).await
+}
+
+/// The description diff recorded by one history entry, for `task.updated` edits and for
+/// undos that restored a description. Entries written before diffs were stored carry the full
+/// before and after text, which is diffed on the fly.
+pub async fn get_task_history_diff(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    history_id: &str,
+) -> AppResult<TaskHistoryDiff> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let (action, detail) = sqlx::query_as::<Any, (String, String)>(
+        "SELECT action, detail FROM task_history WHERE id = ? AND task_id = ?",
+    )
+    .bind(history_id)
+    .bind(&task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("history entry '{history_id}' not found")))?;
+
+    let detail: Value = serde_json::from_str(&detail).unwrap_or(Value::Null);
+    let no_diff = || {
+        AppError::NotFound(format!(
+            "history entry '{history_id}' has no description diff"
+        ))
+    };
+    let change = ["changes", "restored"]
+        .into_iter()
+        .find_map(|key| detail.get(key)?.get("description"))
+        .ok_or_else(no_diff)?;
+
+    let (from_sha256, to_sha256, ops) = match (
+        change.get("from").and_then(Value::as_str),
+        change.get("to").and_then(Value::as_str),
+    ) {
+        (Some(from), Some(to)) => (
+            diff::content_hash(from),
+            diff::content_hash(to),
+            diff::diff_lines(from, to),
+        ),
+        _ => {
+            let hash = |key: &str| change.get(key).and_then(Value::as_str).map(str::to_string);
+            let ops = description_diff_ops(change).ok_or_else(no_diff)?;
+            (
+                hash("from_sha256").ok_or_else(no_diff)?,
+                hash("to_sha256").ok_or_else(no_diff)?,
+                ops,
+            )
+        }
+    };
+
+    Ok(TaskHistoryDiff {
+        history_id: history_id.to_string(),
+        action,
+        field: "description".to_string(),
+        from_sha256,
+        to_sha256,
+        unified: diff::render_unified(&ops, "description"),
+        diff: ops,
+    })
+}
+
+/// Reverses the newest move, update, or review-state change that has not been undone yet.
+pub async fn undo_last_task_change(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    #[derive(sqlx::FromRow)]
+    struct HistoryRow {
+        id: String,
+        action: String,
+        detail: String,
+    }
+
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+
+    let rows = sqlx::query_as::<Any, HistoryRow>(
+        r#"
+        SELECT id, action, detail
+        FROM task_history
+        WHERE task_id = ?
+        ORDER BY created_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(&task.id)
+    .bind(UNDO_LOOKBACK)
+    .fetch_all(pool)
+    .await?;
+
+    let entries = rows
+        .into_iter()
+        .map(|row| {
+            let detail = serde_json::from_str::<Value>(&row.detail).unwrap_or(Value::Null);
+            (row, detail)
+        })
+        .collect::<Vec<_>>();
+    let undone_ids = entries
+        .iter()
+        .filter(|(row, _)| row.action == "task.undone")
+        .filter_map(|(_, detail)| detail.get("undone_history_id").and_then(Value::as_str))
+        .map(ToOwned::to_owned)
+        .collect::<std::collections::HashSet<_>>();
+    let target = entries.into_iter().find(|(row, _)| {
+        UNDOABLE_ACTIONS
+            .iter()
+            .any(|kind| kind.as_str() == row.action)
+            && !undone_ids.contains(&row.id)
+    });
+
+    let Some((entry, detail)) = target else {
+        return Err(AppError::Conflict(
+            "task has no recent change that can be undone".to_string(),
+        ));
+    };
+
+    let mut tx = pool.begin().await?;
+    let mut restored = serde_json::Map::new();
+
+    match entry.action.parse::<EventKind>() {
+        Ok(EventKind::TaskMoved) => {
+            let from_status = detail_str(&detail, "from_status")?;
+            let to_status = detail_str(&detail, "to_status")?;
+            if task.status != to_status {
+                return Err(AppError::Conflict(format!(
+                    "task is no longer in '{to_status}', refusing to undo the move"
+                )));
+            }
+
+            let sort_order = match detail.get("from_sort_order").and_then(Value::as_f64) {
+                Some(value) => value,
+                None => {
+                    sqlx::query_scalar::<Any, f64>(
+                        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
+                    )
+                    .bind(&task.project_id)
+                    .bind(from_status)
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            };
+
+            sqlx::query("UPDATE tasks SET status = ?, sort_order = ?, updated_at = ? WHERE id = ?")
+                .bind(from_status)
+                .bind(sort_order)
+                .bind(now_timestamp())
+                .bind(&task.id)
+                .execute(&mut *tx)
+                .await?;
+            restored.insert("status".to_string(), Value::from(from_status));
+            restored.insert("sort_order".to_string(), Value::from(sort_order));
+        }
+        Ok(EventKind::TaskReviewStateChanged) => {
+            let from_review_state = detail_str(&detail, "from_review_state")?;
+            sqlx::query("UPDATE tasks SET review_state = ?, updated_at = ? WHERE id = ?")
+                .bind(from_review_state)
+                .bind(now_timestamp())
+                .bind(&task.id)
+                .execute(&mut *tx)
+                .await?;
+            restored.insert("review_state".to_string(), Value::from(from_review_state));
+        }
+        _ => {
+            let Some(changes) = detail.get("changes").and_then(Value::as_object) else {
+                return Err(AppError::Conflict(
+                    "this update was recorded without a diff and cannot be undone".to_string(),
+                ));
+            };
+
+            let mut reverted = task.clone();
+            for (field, change) in changes {
+                if field == "description" && change.get("diff").is_some() {
+                    reverted.description = revert_description_change(&task.description, change)?;
+                    restored.insert(
+                        field.clone(),
+                        description_change(&task.description, &reverted.description),
+                    );
+                    continue;
+                }
+
+                let from = change.get("from").cloned().unwrap_or(Value::Null);
+                let column = match field.as_str() {
+                    "title" => &mut reverted.title,
+                    "description" => &mut reverted.description,
+                    "status" => &mut reverted.status,
+                    "priority" => &mut reverted.priority,
+                    "review_state" => &mut reverted.review_state,
+                    "assignee" => {
+                        reverted.assignee = from.as_str().map(ToOwned::to_owned);
+                        restored.insert(field.clone(), from);
+                        continue;
+                    }
+                    "due_at" => {
+                        reverted.due_at = from.as_str().map(ToOwned::to_owned);
+                        restored.insert(field.clone(), from);
+                        continue;
+                    }
+                    "labels" => {
+                        let labels = string_list(&from);
+                        let current = sqlx::query_scalar::<Any, String>(
+                            "SELECT label FROM task_labels WHERE task_id = ?",
+                        )
+                        .bind(&task.id)
+                        .fetch_all(&mut *tx)
+                        .await?;
+                        replace_task_labels(&mut tx, &task.id, &labels).await?;
+                        record_labels_change(&mut tx, &task.id, actor, &current, &labels).await?;
+                        restored.insert(field.clone(), from);
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let Some(value) = from.as_str() else {
+                    return Err(AppError::Conflict(format!(
+                        "history entry has no previous value for '{field}'"
+                    )));
+                };
+                *column = value.to_string();
+                restored.insert(field.clone(), from);
+            }
+
+            sqlx::query(
+                r#"
+                UPDATE tasks
+                SET title = ?, description = ?, status = ?, priority = ?, review_state = ?,
+                    assignee = ?, due_at = ?, updated_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(&reverted.title)
+            .bind(&reverted.description)
+            .bind(&reverted.status)
+            .bind(&reverted.priority)
+            .bind(&reverted.review_state)
+            .bind(&reverted.assignee)
+            .bind(&reverted.due_at)
+            .bind(now_timestamp())
+            .bind(&task.id)
+            .execute(&mut *tx)
+            .await?;
+
+            if reverted.priority != task.priority {
+                record_priority_change(
+                    &mut tx,
+                    &task.id,
+                    actor,
+                    &task.priority,
+                    &reverted.priority,
+                )
+                .await?;
+            }
+            if reverted.assignee != task.assignee {
+                record_assignee_change(
+                    &mut tx,
+                    &task.id,
+                    actor,
+                    task.assignee.as_deref(),
+                    reverted.assignee.as_deref(),
+                )
+                .await?;
+            }
+            if reverted.due_at != task.due_at {
+                record_due_date_change(
+                    &mut tx,
+                    &task.id,
+                    actor,
+                    task.due_at.as_deref(),
+                    reverted.due_at.as_deref(),
+                )
+                .await?;
+            }
+
+            if restored.contains_key("title") || restored.contains_key("description") {
+                sync_references(
+                    &mut tx,
+                    &task.id,
+                    "task",
+                    &task.id,
+                    &format!("{}\n{}", reverted.title, reverted.description),
+                    actor,
+                )
+                .await?;
+            }
+        }
+    }
+
+    if restored.contains_key("status") {
+        touch_status_changed(&mut tx, &task.id).await?;
+    }
+
+    insert_history(
+        &mut tx,
+        &task.id,
+        actor,
+        EventKind::TaskUndone,
+        serde_json::json!({
+            "undone_history_id": entry.id,
+            "undone_action": entry.action,
+            "restored": restored,
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task.id).await
+}
+
+/// Places the given tasks at the top of a column in the given order, in one transaction.
+///
+/// Tasks from other columns are moved in; column members not listed keep their relative
+/// order after the listed ones. Returns the whole column in its new order.
+pub async fn reorder_tasks(
+    pool: &AnyPool,
+    project_slug: &str,
+    input: ReorderTasksInput,
+) -> AppResult<Vec<TaskRecord>> {
+    validate_status(&input.status)?;
+
+    if input.task_refs.is_empty() {
+        return Err(AppError::BadRequest(
+            "tasks must contain at least one task ref".to_string(),
+        ));
+    }
+
+    if input.task_refs.len() > MAX_REORDER_TASKS {
+        return Err(AppError::BadRequest(format!(
+            "tasks cannot contain more than {MAX_REORDER_TASKS} task refs"
+        )));
+    }
+
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let settings = project_settings_by_id(pool, &project_id).await?;
+    let mut listed = Vec::with_capacity(input.task_refs.len());
+    for task_ref in &input.task_refs {
+        let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+        if listed.iter().any(|task: &TaskRecord| task.id == task_id) {
+            return Err(AppError::BadRequest(format!(
+                "task '{task_ref}' is listed more than once"
+            )));
+        }
+
+        let task = get_task_record_by_id(pool, &task_id).await?;
+        // Reordering within the column is not a move for gating purposes.
+        if task.status != input.status {
+            check_review_gate(&settings, &task, task_ref, &input.status, input.mcp_origin)?;
+        }
+        listed.push(task);
+    }
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    // The gates above checked each task's status before the transaction; a task moved since
+    // then must not be moved again on that stale read.
+    let changed = || {
+        AppError::Conflict(
+            "a listed task changed status during the reorder; reload and retry".to_string(),
+        )
+    };
+    for task in &listed {
+        let status = sqlx::query_scalar::<Any, String>("SELECT status FROM tasks WHERE id = ?")
+            .bind(&task.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if status.as_deref() != Some(task.status.as_str()) {
+            return Err(changed());
+        }
+    }
+
+    let remaining = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT id
+        FROM tasks
+        WHERE project_id = ? AND status = ?
+        ORDER BY sort_order ASC, created_at ASC, id ASC
+        "#,
+    )
+    .bind(&project_id)
+    .bind(&input.status)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .filter(|task_id| !listed.iter().any(|task| &task.id == task_id))
+    .collect::<Vec<_>>();
+
+    for (index, task) in listed.iter().enumerate() {
+        let sort_order = (index + 1) as f64;
+        if task.status == input.status {
+            sqlx::query("UPDATE tasks SET sort_order = ? WHERE id = ?")
+                .bind(sort_order)
+                .bind(&task.id)
+                .execute(&mut *tx)
+                .await?;
+            continue;
+        }
+
+        let moved = sqlx::query(
+            "UPDATE tasks SET status = ?, sort_order = ?, updated_at = ? WHERE id = ? AND status = ?",
+        )
+        .bind(&input.status)
+        .bind(sort_order)
+        .bind(&now)
+        .bind(&task.id)
+        .bind(&task.status)
+        .execute(&mut *tx)
+        .await?;
+        if moved.rows_affected() == 0 {
+            return Err(changed());
+        }
+        enter_status(&mut tx, &project_id, &task.id, &input.status).await?;
+
+        insert_history(
+            &mut tx,
+            &task.id,
+            &input.actor,
+            EventKind::TaskMoved,
+            serde_json::json!({
+                "from_status": task.status,
+                "to_status": input.status,
+                "from_sort_order": task.sort_order,
+                "sort_order": sort_order,
+            }),
+        )
+        .await?;
+    }
+
+    for (offset, task_id) in remaining.iter().enumerate() {
+        sqlx::query("UPDATE tasks SET sort_order = ? WHERE id = ?")
+            .bind((listed.len() + offset + 1) as f64)
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    insert_project_event(
+        &mut tx,
+        &project_id,
+        &input.actor,
+        EventKind::TasksReordered,
+        serde_json::json!({
+            "status": input.status,
+            "task_ids": listed.iter().map(|task| task.id.as_str()).collect::<Vec<_>>(),
+        }),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    list_tasks(
+        pool,
+        project_slug,
+        TaskFilters {
+            statuses: vec![input.status],
+            ..TaskFilters::default()
+        },
+        (listed.len() + remaining.len()).max(1) as i64,
+        0,
+    )
+    .await
+}
+
+/// Renumbers tasks to 1.0, 2.0, ... per column, keeping their current order.
+///
+/// Rebalances every column unless `status` is given. Returns `(status, task_count)` pairs.
+pub async fn rebalance_task_columns(
+    pool: &AnyPool,
+    project_slug: &str,
+    status: Option<&str>,
+) -> AppResult<Vec<(String, usize)>> {
+    if let Some(status) = status {
+        validate_status(status)?;
+    }
+
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+    let statuses = match status {
+        Some(status) => vec![status],
+        None => TASK_STATUSES.to_vec(),
+    };
+
+    let mut tx = pool.begin().await?;
+    let mut results = Vec::with_capacity(statuses.len());
+    for status in statuses {
+        let count = rebalance_column(&mut tx, &project_id, status).await?;
+        results.push((status.to_string(), count));
+    }
+    tx.commit().await?;
+
+    Ok(results)
+}
+
+/// Moves a task to `target_slug` in the same workspace. The task keeps its id, subtasks,
+/// questions, attachments, and history, gets the next task number there, and its old display
+/// key is kept as a tombstone that points to the new one. Returns the target's current slug
+/// with the moved task.
+pub async fn transfer_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    target_slug: &str,
+    actor: &str,
+) -> AppResult<(String, TaskRecord)> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let target_slug = normalize_slug(target_slug)?;
+    let target_slug = renamed_project_slug(pool, &target_slug)
+        .await?
+        .unwrap_or(target_slug);
+
+    let project_row = |slug: String| async move {
+        sqlx::query_as::<Any, (String, String)>(
+            "SELECT id, workspace_id FROM projects WHERE slug = ?",
+        )
+        .bind(&slug)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("project '{slug}' not found")))
+    };
+    let (source_id, source_workspace) = project_row(project_slug.to_string()).await?;
+    let (target_id, target_workspace) = project_row(target_slug.clone()).await?;
+    if source_id == target_id {
+        return Err(AppError::BadRequest(format!(
+            "task is already in project '{target_slug}'"
+        )));
+    }
+    if source_workspace != target_workspace {
+        return Err(AppError::BadRequest(
+            "tasks can only be transferred between projects in the same workspace".to_string(),
+        ));
+    }
+
+    let now = now_timestamp();
+    let mut tx = pool.begin().await?;
+
+    let (from_number, status) =
+        sqlx::query_as::<Any, (i64, String)>("SELECT task_number, status FROM tasks WHERE id = ?")
+            .bind(&task_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+    sqlx::query("UPDATE projects SET task_counter = task_counter + 1, updated_at = ? WHERE id = ?")
+        .bind(&now)
+        .bind(&target_id)
+        .execute(&mut *tx)
+        .await?;
+    let task_number: i64 = sqlx::query_scalar("SELECT task_counter FROM projects WHERE id = ?")
+        .bind(&target_id)
+        .fetch_one(&mut *tx)
+        .await?;
+    let sort_order: f64 = sqlx::query_scalar(
+        "SELECT CAST(COALESCE(MAX(sort_order), 0) AS REAL) + 1.0 FROM tasks WHERE project_id = ? AND status = ?",
+    )
+    .bind(&target_id)
+    .bind(&status)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE tasks
+        SET project_id = ?, task_number = ?, sort_order = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(&target_id)
+    .bind(task_number)
+    .bind(sort_order)
+    .bind(&now)
+    .bind(&task_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO task_transfers (from_project_id, from_task_number, task_id, transferred_by, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&source_id)
+    .bind(from_number)
+    .bind(&task_id)
+    .bind(actor)
+    .bind(&now)
+    .execute(&mut *tx)
+    .await?;
+
+    // SLA policies belong to the source project; notifications follow the task.
+    sqlx::query("DELETE FROM sla_breaches WHERE task_id = ?")
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    // Dependencies, parents, and milestones only link tasks within one project.
+    sqlx::query("DELETE FROM task_dependencies WHERE blocker_task_id = ? OR blocked_task_id = ?")
+        .bind(&task_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE tasks SET parent_task_id = NULL WHERE id = ? OR parent_task_id = ?")
+        .bind(&task_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE tasks SET milestone_id = NULL WHERE id = ?")
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE notifications SET project_id = ? WHERE task_id = ?")
+        .bind(&target_id)
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let detail = serde_json::json!({
+        "from_project": project_slug,
+        "from_key": display_key(project_slug, from_number),
+        "to_project": target_slug,
+        "to_key": display_key(&target_slug, task_number),
+    });
+    insert_project_event(
+        &mut tx,
+        &source_id,
+        actor,
+        EventKind::TaskTransferred,
+        detail.clone(),
+    )
+    .await?;
+    insert_history(&mut tx, &task_id, actor, EventKind::TaskTransferred, detail).await?;
+
+    tx.commit().await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    Ok((target_slug, task))
+}
+
+/// Where a task that used to live at `task_ref` in `project_slug` is now, as its current
+/// project slug and task number. `None` when the task was never transferred out of the
+/// project or the reference is not a task reference at all.
+pub async fn transferred_task_key(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+) -> AppResult<Option<(String, i64)>> {
+    let current = match parse_task_ref(task_ref) {
+        Ok(TaskRef::DisplayKey { slug, task_number }) if slug == project_slug => {
+            sqlx::query_as::<Any, (String, i64)>(
+                r#"
+                SELECT p.slug, t.task_number
+                FROM task_transfers x
+                INNER JOIN projects fp ON fp.id = x.from_project_id
+                INNER JOIN tasks t ON t.id = x.task_id
+                INNER JOIN projects p ON p.id = t.project_id
+                WHERE fp.slug = ? AND x.from_task_number = ?
+                "#,
+            )
+            .bind(project_slug)
+            .bind(task_number)
+            .fetch_optional(pool)
+            .await?
+        }
+        Ok(TaskRef::Uuid(task_id)) => {
+            sqlx::query_as::<Any, (String, i64)>(
+                r#"
+                SELECT p.slug, t.task_number
+                FROM task_transfers x
+                INNER JOIN projects fp ON fp.id = x.from_project_id
+                INNER JOIN tasks t ON t.id = x.task_id
+                INNER JOIN projects p ON p.id = t.project_id
+                WHERE fp.slug = ? AND x.task_id = ? AND t.project_id <> x.from_project_id
+                LIMIT 1
+                "#,
+            )
+            .bind(project_slug)
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?
+        }
+        _ => None,
+    };
+
+    Ok(current)
+}
+
+/// Takes a task off the board and out of task lists, keeping everything attached to it.
+/// Archiving an archived task changes nothing.
+pub async fn archive_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    if task.archived_at.is_some() {
+        return Ok(task);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE tasks SET archived_at = ? WHERE id = ?")
+        .bind(now_timestamp())
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskArchived,
+        serde_json::json!({ "status": task.status }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await
+}
+
+/// Brings an archived task back to the board in the status it was archived from.
+pub async fn unarchive_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<TaskRecord> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    if task.archived_at.is_none() {
+        return Ok(task);
+    }
+
+    let mut tx = pool.begin().await?;
+    // Restarting the status clock keeps auto-archive from taking a restored done task straight
+    // back.
+    sqlx::query("UPDATE tasks SET archived_at = NULL, status_changed_at = ? WHERE id = ?")
+        .bind(now_timestamp())
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskUnarchived,
+        serde_json::json!({ "status": task.status }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    get_task_record_by_id(pool, &task_id).await
+}
+
+/// Permanently deletes an archived task and everything attached to it. Returns the storage
+/// paths of its attachments so their files can be removed too.
+pub async fn purge_task(
+    pool: &AnyPool,
+    project_slug: &str,
+    task_ref: &str,
+    actor: &str,
+) -> AppResult<Vec<String>> {
+    let task_id = resolve_task_id(pool, project_slug, task_ref).await?;
+    let task = get_task_record_by_id(pool, &task_id).await?;
+    if task.archived_at.is_none() {
+        return Err(AppError::Conflict(format!(
+            "task '{task_ref}' must be archived before it can be purged"
+        )));
+    }
+    let storage_paths =
+        sqlx::query_scalar::<Any, String>("SELECT storage_path FROM attachments WHERE task_id = ?")
+            .bind(&task_id)
+            .fetch_all(pool)
+            .await?;
+
+    let mut tx = pool.begin().await?;
+
+    insert_history(
+        &mut tx,
+        &task_id,
+        actor,
+        EventKind::TaskDeleted,
+        serde_json::json!({}),
+    )
+    .await?;
+
+    let result = sqlx::query("DELETE FROM tasks WHERE id = ?")
+        .bind(&task_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("task '{task_ref}' not found")));
+    }
+
+    tx.commit().await?;
+    Ok(storage_paths)
+}
+
+async fn project_summary_by_id(
+    pool: &AnyPool,
+    project_id: &str,
+    project: ProjectRecord,
+) -> AppResult<ProjectSummary> {
+    let backlog_count = count_tasks_by_status(pool, project_id, "backlog").await?;
+    let ready_count = count_tasks_by_status(pool, project_id, "ready").await?;
+    let in_progress_count = count_tasks_by_status(pool, project_id, "in_progress").await?;
+    let review_count = count_tasks_by_status(pool, project_id, "review").await?;
+    let done_count = count_tasks_by_status(pool, project_id, "done").await?;
+
+    let open_question_count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM open_questions q
+        INNER JOIN tasks t ON t.id = q.task_id
+        WHERE t.project_id = ? AND q.status = 'open' AND t.archived_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+
+    let disputed_question_count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM open_questions q
+        INNER JOIN tasks t ON t.id = q.task_id
+        WHERE t.project_id = ?
+          AND q.status = 'open'
+          AND q.acceptance = 'disputed'
+          AND t.archived_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+
+    let not_ready_count: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*)
+        FROM tasks
+        WHERE project_id = ? AND review_state = 'not_ready' AND archived_at IS NULL
+        "#,
+    )
+    .bind(project_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ProjectSummary {
+        project,
+        backlog_count,
+        ready_count,
+        in_progress_count,
+        review_count,
+        done_count,
+        open_question_count,
+        disputed_question_count,
+        not_ready_count,
+    })
+}
+
+async fn count_tasks_by_status(pool: &AnyPool, project_id: &str, status: &str) -> AppResult<i64> {
+    let count = sqlx::query_scalar::<Any, i64>(
+        "SELECT COUNT(*) FROM tasks WHERE project_id = ? AND status = ? AND archived_at IS NULL",
+    )
+    .bind(project_id)
+    .bind(status)
+    .fetch_one(pool)
+    .await?;
+    Ok(count)
+}
+
+async fn project_id_by_slug(pool: &AnyPool, project_slug: &str) -> AppResult<String> {
+    if let Some(project_id) = slug_cache::get(pool, project_slug) {
+        return Ok(project_id);
+    }
+
+    let generation = slug_cache::generation();
+    let project_id = sqlx::query_scalar::<Any, String>("SELECT id FROM projects WHERE slug = ?")
+        .bind(project_slug)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("project '{project_slug}' not found")))?;
+    slug_cache::insert(pool, project_slug, &project_id, generation);
+
+    Ok(project_id)
+}
+
+/// A token that changes whenever the project's tasks, spec, or settings do, for use as an
+/// HTTP ETag. It includes the project id, so a project recreated under the same slug does not
+/// match tokens handed out for its predecessor.
+pub async fn project_content_version(pool: &AnyPool, project_slug: &str) -> AppResult<String> {
+    let (project_id, version) = sqlx::query_as::<Any, (String, String)>(
+        "SELECT id, content_version FROM projects WHERE slug = ?",
+    )
+    .bind(project_slug)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("project '{project_slug}' not found")))?;
+
+    Ok(format!("{project_id}:{version}"))
+}
+
+async fn resolve_task_id(pool: &AnyPool, project_slug: &str, task_ref: &str) -> AppResult<String> {
+    match parse_task_ref(task_ref)? {
+        TaskRef::Uuid(task_id) => {
+            let result = sqlx::query_scalar::<Any, String>(
+                r#"
+                SELECT t.id
+                FROM tasks t
+                INNER JOIN projects p ON p.id = t.project_id
+                WHERE p.slug = ? AND t.id = ?
+                "#,
+            )
+            .bind(project_slug)
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?;
+
+            result.ok_or_else(|| AppError::NotFound(format!("task '{task_ref}' not found")))
+        }
+        TaskRef::DisplayKey { slug, task_number } => {
+            if slug != project_slug
+                && renamed_project_slug(pool, &slug).await?.as_deref() != Some(project_slug)
+            {
+                return Err(AppError::NotFound(format!(
+                    "task '{task_ref}' is outside project '{project_slug}'"
+                )));
+            }
+
+            let result = sqlx::query_scalar::<Any, String>(
+                r#"
+                SELECT t.id
+                FROM tasks t
+                INNER JOIN projects p ON p.id = t.project_id
+                WHERE p.slug = ? AND t.task_number = ?
+                "#,
+            )
+            .bind(project_slug)
+            .bind(task_number)
+            .fetch_optional(pool)
+            .await?;
+
+            if let Some(id) = result {
+                return Ok(id);
+            }
+            match transferred_task_key(pool, project_slug, task_ref).await? {
+                Some((slug, task_number)) => Err(AppError::NotFound(format!(
+                    "task '{task_ref}' was transferred to {}",
+                    display_key(&slug, task_number)
+                ))),
+                None => Err(AppError::NotFound(format!("task '{task_ref}' not found"))),
+            }
+        }
+    }
+}
+
+async fn get_open_question_by_id(
+    pool: &AnyPool,
+    task_id: &str,
+    question_id: &str,
+) -> AppResult<OpenQuestionRecord> {
+    let record = sqlx::query_as::<Any, OpenQuestionRecord>(
+        r#"
+        SELECT id, task_id, question, context, answer, status, acceptance, dispute_reason, asked_by, resolved_by, created_at, resolved_at
+        FROM open_questions
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(question_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("question '{question_id}' not found")))?;
+
+    Ok(record)
+}
+
+fn normalize_inbound_hook_name(value: &str) -> AppResult<String> {
+    let name = value.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "inbound hook name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name)
+}
+
+/// Checks that a template only maps fields the hook's kind understands and maps the required
+/// ones. Returns it serialized for storage.
+fn validate_inbound_template(kind: &str, template: &Value) -> AppResult<String> {
+    let Some(fields) = template.as_object() else {
+        return Err(AppError::BadRequest(
+            "inbound hook template must be a JSON object".to_string(),
+        ));
+    };
+
+    let (allowed, required): (&[&str], &[&str]) = match kind {
+        "question" => (&INBOUND_QUESTION_FIELDS, &["task", "question"]),
+        _ => (&INBOUND_TASK_FIELDS, &["title"]),
+    };
+    if let Some(unknown) = fields.keys().find(|key| !allowed.contains(&key.as_str())) {
+        return Err(AppError::BadRequest(format!(
+            "inbound {kind} templates cannot set '{unknown}', expected any of {}",
+            allowed.join(", ")
+        )));
+    }
+    if let Some(missing) = required.iter().find(|key| !fields.contains_key(**key)) {
+        return Err(AppError::BadRequest(format!(
+            "inbound {kind} templates must set '{missing}'"
+        )));
+    }
+
+    Ok(template.to_string())
+}
+
+fn guest_token_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            g.id,
+            p.slug AS project_slug,
+            g.label,
+            g.created_by,
+            g.expires_at,
+            g.revoked_at,
+            g.created_at
+        FROM guest_tokens g
+        INNER JOIN projects p ON p.id = g.project_id
+        "#,
+    )
+}
+
+async fn get_guest_token(
+    pool: &AnyPool,
+    project_id: &str,
+    token_id: &str,
+) -> AppResult<GuestTokenRecord> {
+    let mut query = guest_token_query();
+    query.push(" WHERE g.project_id = ");
+    query.push_bind(project_id.to_string());
+    query.push(" AND g.id = ");
+    query.push_bind(token_id.to_string());
+    query
+        .build_query_as::<GuestTokenRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("guest token '{token_id}' not found")))
+}
+
+async fn get_mcp_key(pool: &AnyPool, key_id: &str) -> AppResult<McpKeyRecord> {
+    sqlx::query_as::<Any, McpKeyRecord>(
+        "SELECT id, name, created_by, revoked_at, created_at FROM mcp_keys WHERE id = ?",
+    )
+    .bind(key_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("mcp key '{key_id}' not found")))
+}
+
+fn share_link_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            s.id,
+            p.slug AS project_slug,
+            s.label,
+            s.created_by,
+            s.expires_at,
+            s.revoked_at,
+            s.created_at
+        FROM share_links s
+        INNER JOIN projects p ON p.id = s.project_id
+        "#,
+    )
+}
+
+async fn get_share_link(
+    pool: &AnyPool,
+    project_id: &str,
+    link_id: &str,
+) -> AppResult<ShareLinkRecord> {
+    let mut query = share_link_query();
+    query.push(" WHERE s.project_id = ");
+    query.push_bind(project_id.to_string());
+    query.push(" AND s.id = ");
+    query.push_bind(link_id.to_string());
+    query
+        .build_query_as::<ShareLinkRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("share link '{link_id}' not found")))
+}
+
+fn milestone_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            m.id,
+            m.project_id,
+            m.name,
+            m.target_date,
+            m.status,
+            m.created_at,
+            m.updated_at,
+            (SELECT COUNT(*) FROM tasks t WHERE t.milestone_id = m.id) AS total_tasks,
+            (
+                SELECT COUNT(*)
+                FROM tasks t
+                WHERE t.milestone_id = m.id AND t.status = 'done'
+            ) AS done_tasks
+        FROM milestones m
+        "#,
+    )
+}
+
+fn sla_policy_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            s.id,
+            s.project_id,
+            p.slug AS project_slug,
+            s.name,
+            s.status,
+            s.priority,
+            s.max_hours,
+            s.escalate_priority,
+            s.webhook_id,
+            s.created_at,
+            s.updated_at
+        FROM sla_policies s
+        INNER JOIN projects p ON p.id = s.project_id
+        "#,
+    )
+}
+
+fn digest_schedule_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            d.project_id,
+            p.slug AS project_slug,
+            d.frequency,
+            d.hour,
+            d.weekday,
+            d.last_sent_at,
+            d.created_at,
+            d.updated_at
+        FROM digest_schedules d
+        INNER JOIN projects p ON p.id = d.project_id
+        "#,
+    )
+}
+
+fn task_recurrence_query<'a>() -> QueryBuilder<'a, Any> {
+    QueryBuilder::<Any>::new(
+        r#"
+        SELECT
+            r.task_id,
+            p.slug AS project_slug,
+            t.task_number,
+            r.schedule,
+            r.next_run_at,
+            r.last_run_at,
+            r.last_task_id,
+            r.created_by,
+            r.created_at,
+            r.updated_at
+        FROM task_recurrences r
+        INNER JOIN tasks t ON t.id = r.task_id
+        INNER JOIN projects p ON p.id = t.project_id
+        "#,
+    )
+}
+
+const NOTIFICATION_COLUMNS: &str = r#"
+    SELECT
+        n.id,
+        n.recipient,
+        p.slug AS project_slug,
+        n.task_id,
+        t.task_number,
+        n.history_id,
+        n.action,
+        n.actor,
+        n.reason,
+        n.detail,
+        n.read_at,
+        n.created_at
+"#;
+
+fn notification_query<'a>(
+    select: &str,
+    recipient: &str,
+    workspace_id: Option<&str>,
+) -> QueryBuilder<'a, Any> {
+    let mut query = QueryBuilder::<Any>::new(select);
+    query.push(
+        r#"
+        FROM notifications n
+        INNER JOIN projects p ON p.id = n.project_id
+        LEFT JOIN tasks t ON t.id = n.task_id
+        WHERE n.recipient =
+        "#,
+    );
+    query.push_bind(recipient.to_string());
+    if let Some(workspace_id) = workspace_id {
+        query.push(" AND p.workspace_id = ");
+        query.push_bind(workspace_id.to_string());
+    }
+    query
+}
+
+async fn get_notification(
+    pool: &AnyPool,
+    recipient: &str,
+    workspace_id: Option<&str>,
+    notification_id: &str,
+) -> AppResult<NotificationRecord> {
+    let mut query = notification_query(NOTIFICATION_COLUMNS, recipient, workspace_id);
+    query.push(" AND n.id = ");
+    query.push_bind(notification_id.to_string());
+    query
+        .build_query_as::<NotificationRecord>()
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("notification '{notification_id}' not found")))
+}
+
+async fn list_watchers_for_task(pool: &AnyPool, task_id: &str) -> AppResult<Vec<String>> {
+    let watchers = sqlx::query_scalar::<Any, String>(
+        "SELECT actor FROM task_watchers WHERE task_id = ? ORDER BY created_at ASC, actor ASC",
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(watchers)
+}
+
+async fn list_reviews_for_task(pool: &AnyPool, task_id: &str) -> AppResult<Vec<TaskReviewRecord>> {
+    let reviews = sqlx::query_as::<Any, TaskReviewRecord>(
+        r#"
+        SELECT id, task_id, reviewer, requested_by, status, comment, completed_by, created_at, completed_at
+        FROM task_reviews
+        WHERE task_id = ?
+        ORDER BY created_at DESC, id DESC
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reviews)
+}
+
+async fn get_review_by_id(
+    pool: &AnyPool,
+    task_id: &str,
+    review_id: &str,
+) -> AppResult<TaskReviewRecord> {
+    sqlx::query_as::<Any, TaskReviewRecord>(
+        r#"
+        SELECT id, task_id, reviewer, requested_by, status, comment, completed_by, created_at, completed_at
+        FROM task_reviews
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(review_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("review '{review_id}' not found")))
+}
+
+/// Side effects of a task entering `status`, run inside the caller's transaction.
+///
+/// Every entry restarts the SLA clock (`status_changed_at`). Entering `review` copies the project's checklist template onto the task (once); entering
+/// `done` is refused while checklist items are open, unless the project turned that off, and
+/// while blocking tasks are not done, if the project enforces dependencies.
+async fn enter_status(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    project_id: &str,
+    task_id: &str,
+    status: &str,
+) -> AppResult<()> {
+    touch_status_changed(tx, task_id).await?;
+
+    match status {
+        "review" => {
+            let existing = sqlx::query_scalar::<Any, i64>(
+                "SELECT COUNT(*) FROM task_review_items WHERE task_id = ?",
+            )
+            .bind(task_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            if existing > 0 {
+                return Ok(());
+            }
+
+            let now = now_timestamp();
+            let templates = sqlx::query_as::<Any, (String, f64)>(
+                r#"
+                SELECT title, sort_order
+                FROM review_checklist_templates
+                WHERE project_id = ?
+                ORDER BY sort_order ASC, created_at ASC
+                "#,
+            )
+            .bind(project_id)
+            .fetch_all(&mut **tx)
+            .await?;
+
+            for (title, sort_order) in templates {
+                sqlx::query(
+                    r#"
+                    INSERT INTO task_review_items (id, task_id, title, done, sort_order, created_at)
+                    VALUES (?, ?, ?, 0, ?, ?)
+                    "#,
+                )
+                .bind(Uuid::new_v4().to_string())
+                .bind(task_id)
+                .bind(title)
+                .bind(sort_order)
+                .bind(&now)
+                .execute(&mut **tx)
+                .await?;
+            }
+        }
+        "done" => {
+            let (required, enforce_dependencies) = sqlx::query_as::<Any, (i64, i64)>(
+                r#"
+                SELECT review_checklist_required, enforce_dependencies
+                FROM project_settings
+                WHERE project_id = ?
+                "#,
+            )
+            .bind(project_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .unwrap_or((1, 0));
+
+            if enforce_dependencies == 1 {
+                let blockers = sqlx::query_scalar::<Any, String>(
+                    r#"
+                    SELECT p.slug || '-' || CAST(t.task_number AS TEXT)
+                    FROM task_dependencies d
+                    INNER JOIN tasks t ON t.id = d.blocker_task_id
+                    INNER JOIN projects p ON p.id = t.project_id
+                    WHERE d.blocked_task_id = ? AND t.status <> 'done' AND t.archived_at IS NULL
+                    ORDER BY t.task_number ASC
+                    "#,
+                )
+                .bind(task_id)
+                .fetch_all(&mut **tx)
+                .await?;
+                if !blockers.is_empty() {
+                    return Err(AppError::Conflict(format!(
+                        "task is blocked by {}, finish them before moving to done",
+                        blockers.join(", ")
+                    )));
+                }
+            }
+
+            if required == 0 {
+                return Ok(());
+            }
+
+            let open = sqlx::query_scalar::<Any, i64>(
+                "SELECT COUNT(*) FROM task_review_items WHERE task_id = ? AND done = 0",
+            )
+            .bind(task_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            if open > 0 {
+                return Err(AppError::Conflict(format!(
+                    "review checklist has {open} open item(s), complete them before moving to done"
+                )));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Stamps a status change. Archival only applies to a task where it was archived, so any
+/// status change also brings an archived task back.
+async fn touch_status_changed(tx: &mut sqlx::Transaction<'_, Any>, task_id: &str) -> AppResult<()> {
+    sqlx::query("UPDATE tasks SET status_changed_at = ?, archived_at = NULL WHERE id = ?")
+        .bind(now_timestamp())
+        .bind(task_id)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+async fn project_settings_by_id(pool: &AnyPool, project_id: &str) -> AppResult<ProjectSettings> {
+    #[allow(clippy::type_complexity)]
+    let row = sqlx::query_as::<
+        Any,
+        (
+            i64,
+            String,
+            String,
+            i64,
+            Option<String>,
+            Option<String>,
+            i64,
+            String,
+            String,
+        ),
+    >(
+        r#"
+        SELECT review_checklist_required, review_gate, review_gate_scope, auto_archive_after_days,
+               column_order, column_colors, enforce_dependencies, timezone, updated_at
+        FROM project_settings
+        WHERE project_id = ?
+        "#,
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(match row {
+        Some((
+            review_checklist_required,
+            review_gate,
+            review_gate_scope,
+            auto_archive_after_days,
+            column_order,
+            column_colors,
+            enforce_dependencies,
+            timezone,
+            updated_at,
+        )) => ProjectSettings {
+            project_id: project_id.to_string(),
+            review_checklist_required: review_checklist_required == 1,
+            review_gate,
+            review_gate_scope,
+            auto_archive_after_days,
+            column_order: parse_column_order(column_order.as_deref())
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            column_colors: column_colors
+                .and_then(|value| serde_json::from_str(&value).ok())
+                .unwrap_or_default(),
+            enforce_dependencies: enforce_dependencies == 1,
+            timezone,
+            updated_at: Some(updated_at),
+        },
+        None => ProjectSettings {
+            project_id: project_id.to_string(),
+            review_checklist_required: true,
+            review_gate: "agents".to_string(),
+            review_gate_scope: "all_moves".to_string(),
+            auto_archive_after_days: 0,
+            column_order: TASK_STATUSES
+                .iter()
+                .map(|status| status.to_string())
+                .collect(),
+            column_colors: BTreeMap::new(),
+            enforce_dependencies: false,
+            timezone: "UTC".to_string(),
+            updated_at: None,
+        },
+    })
+}
+
+/// A stored column order, falling back to `TASK_STATUSES` when it is unset or no longer
+/// names every status.
+fn parse_column_order(stored: Option<&str>) -> Vec<&'static str> {
+    stored
+        .and_then(|value| serde_json::from_str::<Vec<String>>(value).ok())
+        .and_then(|order| normalize_column_order(&order).ok())
+        .unwrap_or_else(|| TASK_STATUSES.to_vec())
+}
+
+fn normalize_column_order(order: &[String]) -> AppResult<Vec<&'static str>> {
+    if order.is_empty() {
+        return Ok(TASK_STATUSES.to_vec());
+    }
+
+    let mut normalized = Vec::with_capacity(TASK_STATUSES.len());
+    for status in order {
+        let status = TASK_STATUSES
+            .iter()
+            .copied()
+            .find(|candidate| *candidate == status.trim())
+            .ok_or_else(|| AppError::BadRequest(format!("invalid column '{status}'")))?;
+        if normalized.contains(&status) {
+            return Err(AppError::BadRequest(format!(
+                "column '{status}' is listed more than once"
+            )));
+        }
+        normalized.push(status);
+    }
+    if normalized.len() != TASK_STATUSES.len() {
+        return Err(AppError::BadRequest(format!(
+            "column_order must list every column: {}",
+            TASK_STATUSES.join(", ")
+        )));
+    }
+    Ok(normalized)
+}
+
+fn normalize_column_colors(
+    colors: BTreeMap<String, String>,
+) -> AppResult<BTreeMap<String, String>> {
+    let mut normalized = BTreeMap::new();
+    for (status, color) in colors {
+        let status = status.trim();
+        validate_status(status)?;
+        let color = color.trim().to_ascii_lowercase();
+        let hex = color.strip_prefix('#').unwrap_or_default();
+        let color = match hex.len() {
+            6 if hex.chars().all(|c| c.is_ascii_hexdigit()) => color,
+            3 if hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                hex.chars().fold("#".to_string(), |mut expanded, c| {
+                    expanded.push(c);
+                    expanded.push(c);
+                    expanded
+                })
+            }
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "invalid color '{color}' for column '{status}', expected #rgb or #rrggbb"
+                )))
+            }
+        };
+        normalized.insert(status.to_string(), color);
+    }
+    Ok(normalized)
+}
+
+/// The board column order of the project with `project_slug`, or the canonical order when it
+/// has none or does not exist.
+async fn column_order_by_slug(pool: &AnyPool, project_slug: &str) -> AppResult<Vec<&'static str>> {
+    let stored = sqlx::query_scalar::<Any, Option<String>>(
+        r#"
+        SELECT s.column_order
+        FROM project_settings s
+        INNER JOIN projects p ON p.id = s.project_id
+        WHERE p.slug = ?
+        "#,
+    )
+    .bind(project_slug)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+    Ok(parse_column_order(stored.as_deref()))
+}
+
+/// The timezone of the project with `project_slug`, or UTC when it has none or does not exist.
+pub async fn project_timezone(pool: &AnyPool, project_slug: &str) -> AppResult<Tz> {
+    let stored = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT s.timezone
+        FROM project_settings s
+        INNER JOIN projects p ON p.id = s.project_id
+        WHERE p.slug = ?
+        "#,
+    )
+    .bind(project_slug)
+    .fetch_optional(pool)
+    .await?;
+    Ok(stored
+        .and_then(|name| name.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC))
+}
+
+/// Rejects a move of a `not_ready` task when the project's review gate covers it.
+fn check_review_gate(
+    settings: &ProjectSettings,
+    task: &TaskRecord,
+    task_ref: &str,
+    to_status: &str,
+    mcp_origin: bool,
+) -> AppResult<()> {
+    if task.review_state != "not_ready" {
+        return Ok(());
+    }
+
+    let gated_actor = match settings.review_gate.as_str() {
+        "everyone" => true,
+        "agents" => mcp_origin,
+        _ => false,
+    };
+    let gated_move = match settings.review_gate_scope.as_str() {
+        "start_and_finish" => {
+            task.status != to_status && matches!(to_status, "in_progress" | "done")
+        }
+        _ => true,
+    };
+
+    if gated_actor && gated_move {
+        return Err(AppError::BadRequest(format!(
+            "task '{task_ref}' is not_ready, set review_state to ready before moving"
+        )));
+    }
+
+    Ok(())
+}
+
+async fn list_review_items_for_task(
+    pool: &AnyPool,
+    task_id: &str,
+) -> AppResult<Vec<TaskReviewItemRecord>> {
+    let items = sqlx::query_as::<Any, TaskReviewItemRecord>(
+        r#"
+        SELECT id, task_id, title, done, sort_order, completed_by, completed_at, created_at
+        FROM task_review_items
+        WHERE task_id = ?
+        ORDER BY sort_order ASC, created_at ASC
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(items)
+}
+
+async fn get_review_item_by_id(
+    pool: &AnyPool,
+    task_id: &str,
+    item_id: &str,
+) -> AppResult<TaskReviewItemRecord> {
+    sqlx::query_as::<Any, TaskReviewItemRecord>(
+        r#"
+        SELECT id, task_id, title, done, sort_order, completed_by, completed_at, created_at
+        FROM task_review_items
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(item_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("review checklist item '{item_id}' not found")))
+}
+
+async fn list_subtasks(pool: &AnyPool, task_id: &str) -> AppResult<Vec<SubtaskRecord>> {
+    let subtasks = sqlx::query_as::<Any, SubtaskRecord>(
+        r#"
+        SELECT id, task_id, title, done, sort_order, created_at
+        FROM subtasks
+        WHERE task_id = ?
+        ORDER BY sort_order ASC, created_at ASC
+        "#,
+    )
+    .bind(task_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(subtasks)
+}
+
+async fn get_subtask_by_id(
+    pool: &AnyPool,
+    task_id: &str,
+    subtask_id: &str,
+) -> AppResult<SubtaskRecord> {
+    let subtask = sqlx::query_as::<Any, SubtaskRecord>(
+        r#"
+        SELECT id, task_id, title, done, sort_order, created_at
+        FROM subtasks
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(subtask_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("subtask '{subtask_id}' not found")))?;
+
+    Ok(subtask)
+}
+
+async fn get_attachment_for_task(
+    pool: &AnyPool,
+    task_id: &str,
+    attachment_id: &str,
+) -> AppResult<AttachmentRecord> {
+    let attachment = sqlx::query_as::<Any, AttachmentRecord>(
+        r#"
+        SELECT id, task_id, filename, content_type, size_bytes, storage_path, uploaded_by, created_at
+        FROM attachments
+        WHERE id = ? AND task_id = ?
+        "#,
+    )
+    .bind(attachment_id)
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("attachment '{attachment_id}' not found")))?;
+
+    Ok(attachment)
+}
+
+async fn get_task_record_by_id(pool: &AnyPool, task_id: &str) -> AppResult<TaskRecord> {
+    let task = sqlx::query_as::<Any, TaskRecord>(
+        r#"
+        SELECT
+            id,
+            project_id,
+            task_number,
+            title,
+            description,
+            status,
+            priority,
+            review_state,
+            sort_order,
+            created_by,
+            created_at,
+            updated_at,
+            archived_at,
+            assignee,
+            due_at,
+            parent_task_id,
+            milestone_id
+        FROM tasks
+        WHERE id = ?
+        "#,
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(format!("task '{task_id}' not found")))?;
+
+    Ok(task)
+}
+
+async fn column_needs_rebalance(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    project_id: &str,
+    status: &str,
+) -> AppResult<bool> {
+    let orders = sqlx::query_scalar::<Any, f64>(
+        "SELECT sort_order FROM tasks WHERE project_id = ? AND status = ? ORDER BY sort_order ASC",
+    )
+    .bind(project_id)
+    .bind(status)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(orders
+        .windows(2)
+        .any(|pair| pair[1] - pair[0] < SORT_ORDER_MIN_GAP))
+}
+
+async fn rebalance_column(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    project_id: &str,
+    status: &str,
+) -> AppResult<usize> {
+    let task_ids = sqlx::query_scalar::<Any, String>(
+        r#"
+        SELECT id
+        FROM tasks
+        WHERE project_id = ? AND status = ?
+        ORDER BY sort_order ASC, created_at ASC, id ASC
+        "#,
+    )
+    .bind(project_id)
+    .bind(status)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    // Positions are not a content change, so `updated_at` is left alone.
+    for (index, task_id) in task_ids.iter().enumerate() {
+        sqlx::query("UPDATE tasks SET sort_order = ? WHERE id = ?")
+            .bind((index + 1) as f64)
+            .bind(task_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+    if !task_ids.is_empty() {
+        touch_content_version(tx, project_id).await?;
+    }
+
+    Ok(task_ids.len())
+}
+
+/// A task with its labels, or `None` once the task is gone.
+pub async fn find_task_with_labels(
+    pool: &AnyPool,
+    task_id: &str,
+) -> AppResult<Option<(TaskRecord, Vec<String>)>> {
+    let task = match get_task_record_by_id(pool, task_id).await {
+        Ok(task) => task,
+        Err(AppError::NotFound(_)) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    let mut labels = labels_by_task(pool, std::slice::from_ref(&task.id)).await?;
+    let labels = labels.remove(&task.id).unwrap_or_default();
+    Ok(Some((task, labels)))
+}
+
+pub async fn labels_by_task(
+    pool: &AnyPool,
+    task_ids: &[String],
+) -> AppResult<HashMap<String, Vec<String>>> {
+    let mut labels: HashMap<String, Vec<String>> = HashMap::new();
+    if task_ids.is_empty() {
+        return Ok(labels);
+    }
+
+    let mut query = QueryBuilder::<Any>::new("SELECT task_id, label FROM task_labels WHERE 1 = 1");
+    push_in_list(&mut query, "task_id", task_ids, false);
+    query.push(" ORDER BY label ASC");
+    let rows = query
+        .build_query_as::<(String, String)>()
+        .fetch_all(pool)
+        .await?;
+    for (task_id, label) in rows {
+        labels.entry(task_id).or_default().push(label);
+    }
+    Ok(labels)
+}
+
+/// Renames `from` to `to` on every task in the project, archived ones included, and records one
+/// `labels.renamed` event. Tasks that already carry `to` simply lose `from`. Returns how many
+/// tasks had `from`.
+pub async fn rename_label(
+    pool: &AnyPool,
+    project_slug: &str,
+    from: &str,
+    to: &str,
+    actor: &str,
+) -> AppResult<i64> {
+    let (from, to) = (from.trim(), to.trim());
+    if from.is_empty() || to.is_empty() {
+        return Err(AppError::BadRequest(
+            "from and to labels must not be empty".to_string(),
+        ));
+    }
+    if from == to {
+        return Err(AppError::BadRequest(
+            "from and to labels must differ".to_string(),
+        ));
+    }
+    let project_id = project_id_by_slug(pool, project_slug).await?;
+
+    let mut tx = pool.begin().await?;
+    let affected = sqlx::query_scalar::<Any, i64>(
+        r#"
+        SELECT COUNT(*) FROM task_labels
+        WHERE label = ? AND task_id IN (SELECT id FROM tasks WHERE project_id = ?)
+        "#,
+    )
+    .bind(from)
+    .bind(&project_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if affected == 0 {
+        return Ok(0);
+    }
+
+    sqlx::query(
+        r#"
+        DELETE FROM task_labels
+        WHERE label = ?
+          AND task_id IN (SELECT id FROM tasks WHERE project_id = ?)
+          AND task_id IN (SELECT task_id FROM task_labels WHERE label = ?)
+        "#,
+    )
+    .bind(from)
+    .bind(&project_id)
+    .bind(to)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        r#"
+        UPDATE task_labels SET label = ?
+        WHERE label = ? AND task_id IN (SELECT id FROM tasks WHERE project_id = ?)
+        "#,
+    )
+    .bind(to)
+    .bind(from)
+    .bind(&project_id)
+    .execute(&mut *tx)
+    .await?;
+
+    insert_project_event(
+        &mut tx,
+        &project_id,
+        actor,
+        EventKind::LabelsRenamed,
+        serde_json::json!({ "from": from, "to": to, "affected": affected }),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(affected)
+}
+
+/// Subtasks of each task in checklist order, keyed by task id.
+pub async fn subtasks_by_task(
+    pool: &AnyPool,
+    task_ids: &[String],
+) -> AppResult<HashMap<String, Vec<SubtaskRecord>>> {
+    let mut subtasks: HashMap<String, Vec<SubtaskRecord>> = HashMap::new();
+    if task_ids.is_empty() {
+        return Ok(subtasks);
+    }
+
+    let mut query = QueryBuilder::<Any>::new(
+        "SELECT id, task_id, title, done, sort_order, created_at FROM subtasks WHERE 1 = 1",
+    );
+    push_in_list(&mut query, "task_id", task_ids, false);
+    query.push(" ORDER BY sort_order ASC, created_at ASC");
+    let rows = query
+        .build_query_as::<SubtaskRecord>()
+        .fetch_all(pool)
+        .await?;
+    for subtask in rows {
+        subtasks
+            .entry(subtask.task_id.clone())
+            .or_default()
+            .push(subtask);
+    }
+    Ok(subtasks)
+}
+
+async fn replace_task_labels(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    labels: &[String],
+) -> AppResult<()> {
+    sqlx::query("DELETE FROM task_labels WHERE task_id = ?")
+        .bind(task_id)
+        .execute(&mut **tx)
+        .await?;
+
+    for label in labels {
+        sqlx::query("INSERT INTO task_labels (task_id, label) VALUES (?, ?)")
+            .bind(task_id)
+            .bind(label)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// History detail for a description edit: hashes of both versions plus a line diff.
+fn description_change(from: &str, to: &str) -> Value {
+    serde_json::json!({
+        "from_sha256": diff::content_hash(from),
+        "to_sha256": diff::content_hash(to),
+        "diff": diff::diff_lines(from, to),
+    })
+}
+
+/// Undoes a `description_change` against the task's current description.
+fn revert_description_change(current: &str, change: &Value) -> AppResult<String> {
+    if change.get("to_sha256").and_then(Value::as_str) != Some(&diff::content_hash(current)) {
+        return Err(AppError::Conflict(
+            "description changed since this update, refusing to undo".to_string(),
+        ));
+    }
+
+    description_diff_ops(change)
+        .and_then(|ops| diff::apply(current, &ops, true))
+        .ok_or_else(|| AppError::Conflict("history entry has an unreadable diff".to_string()))
+}
+
+fn description_diff_ops(change: &Value) -> Option<Vec<DiffOp>> {
+    serde_json::from_value(change.get("diff")?.clone()).ok()
+}
+
+fn detail_str<'a>(detail: &'a Value, key: &str) -> AppResult<&'a str> {
+    detail.get(key).and_then(Value::as_str).ok_or_else(|| {
+        AppError::Conflict(format!(
+            "history entry is missing '{key}' and cannot be undone"
+        ))
+    })
+}
+
+/// Records a `task.labels_changed` entry with the labels added and removed, sorted, when the
+/// label set actually differs.
+async fn record_labels_change(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    actor: &str,
+    from: &[String],
+    to: &[String],
+) -> AppResult<()> {
+    let from: BTreeSet<&String> = from.iter().collect();
+    let to: BTreeSet<&String> = to.iter().collect();
+    let added: Vec<&String> = to.difference(&from).copied().collect();
+    let removed: Vec<&String> = from.difference(&to).copied().collect();
+    if added.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    insert_history(
+        tx,
+        task_id,
+        actor,
+        EventKind::TaskLabelsChanged,
+        serde_json::json!({ "added": added, "removed": removed, "labels": to }),
+    )
+    .await
+}
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(ToOwned::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Records a `task.priority_changed` entry next to the update that changed the priority, so
+/// webhooks can follow escalations without parsing every `task.updated` diff.
+async fn record_priority_change(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    actor: &str,
+    from: &str,
+    to: &str,
+) -> AppResult<()> {
+    insert_history(
+        tx,
+        task_id,
+        actor,
+        EventKind::TaskPriorityChanged,
+        serde_json::json!({ "from_priority": from, "to_priority": to }),
+    )
+    .await
+}
+
+async fn record_assignee_change(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    actor: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> AppResult<()> {
+    insert_history(
+        tx,
+        task_id,
+        actor,
+        EventKind::TaskAssigned,
+        serde_json::json!({ "from": from, "to": to }),
+    )
+    .await
+}
+
+async fn record_due_date_change(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    actor: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> AppResult<()> {
+    insert_history(
+        tx,
+        task_id,
+        actor,
+        EventKind::TaskDueDateChanged,
+        serde_json::json!({ "from_due_at": from, "to_due_at": to }),
+    )
+    .await
+}
+
+async fn insert_history(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    actor: &str,
+    action: EventKind,
+    detail: Value,
+) -> AppResult<()> {
+    let now = now_timestamp();
+    let detail_json = detail.to_string();
+    let history_id = Uuid::new_v4().to_string();
+    record_actor_seen(tx, actor, &now).await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO task_history (id, task_id, actor, action, detail, created_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&history_id)
+    .bind(task_id)
+    .bind(actor)
+    .bind(action.as_str())
+    .bind(&detail_json)
+    .bind(&now)
+    .execute(&mut **tx)
+    .await?;
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO system_events (id, project_id, task_id, task_number, actor, action, detail, created_at)
+        SELECT ?, t.project_id, t.id, t.task_number, ?, ?, ?, ?
+        FROM tasks t
+        WHERE t.id = ?
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(actor)
+    .bind(action.as_str())
+    .bind(&detail_json)
+    .bind(&now)
+    .bind(task_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("task '{task_id}' not found")));
+    }
+    sqlx::query(
+        "UPDATE projects SET content_version = ? WHERE id = (SELECT project_id FROM tasks WHERE id = ?)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(task_id)
+    .execute(&mut **tx)
+    .await?;
+
+    notify_recipients(tx, task_id, &history_id, actor, action, &detail, &now).await
+}
+
+/// Brings the stored task links and actor mentions of one piece of text (a task's title and
+/// description, a question, or a review comment) in line with its current content.
+///
+/// References to tasks outside the task's workspace, to unknown tasks, or to the task itself
+/// are ignored. Each newly mentioned actor gets a `task.mentioned` history entry, which
+/// notifies them; removing and re-adding a mention notifies again.
+async fn sync_references(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    source_kind: &str,
+    source_id: &str,
+    text: &str,
+    actor: &str,
+) -> AppResult<()> {
+    let workspace_id = sqlx::query_scalar::<Any, String>(
+        "SELECT p.workspace_id FROM tasks t JOIN projects p ON p.id = t.project_id WHERE t.id = ?",
+    )
+    .bind(task_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let mut targets: Vec<String> = Vec::new();
+    for (slug, task_number) in references::parse_task_refs(text) {
+        let target = sqlx::query_scalar::<Any, String>(
+            r#"
+            SELECT t.id
+            FROM tasks t
+            JOIN projects p ON p.id = t.project_id
+            WHERE (p.slug = ? OR p.id IN (SELECT project_id FROM project_slug_aliases WHERE slug = ?))
+              AND t.task_number = ?
+              AND p.workspace_id = ?
+            "#,
+        )
+        .bind(&slug)
+        .bind(&slug)
+        .bind(task_number)
+        .bind(&workspace_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        if let Some(target) = target.filter(|target| target != task_id) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+
+    let now = now_timestamp();
+    let existing_targets = sqlx::query_scalar::<Any, String>(
+        "SELECT target_task_id FROM task_links WHERE source_kind = ? AND source_id = ?",
+    )
+    .bind(source_kind)
+    .bind(source_id)
+    .fetch_all(&mut **tx)
+    .await?;
+    for stale in existing_targets
+        .iter()
+        .filter(|target| !targets.contains(target))
+    {
+        sqlx::query(
+            "DELETE FROM task_links WHERE source_kind = ? AND source_id = ? AND target_task_id = ?",
+        )
+        .bind(source_kind)
+        .bind(source_id)
+        .bind(stale)
+        .execute(&mut **tx)
+        .await?;
+    }
+    for target in targets
+        .iter()
+        .filter(|target| !existing_targets.contains(target))
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO task_links (
+                source_task_id, target_task_id, source_kind, source_id, created_by, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(task_id)
+        .bind(target)
+        .bind(source_kind)
+        .bind(source_id)
+        .bind(actor)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    let mentions = references::parse_mentions(text);
+    let existing_mentions = sqlx::query_scalar::<Any, String>(
+        "SELECT actor FROM task_mentions WHERE source_kind = ? AND source_id = ?",
+    )
+    .bind(source_kind)
+    .bind(source_id)
+    .fetch_all(&mut **tx)
+    .await?;
+    for stale in existing_mentions
+        .iter()
+        .filter(|mentioned| !mentions.contains(mentioned))
+    {
+        sqlx::query(
+            "DELETE FROM task_mentions WHERE source_kind = ? AND source_id = ? AND actor = ?",
+        )
+        .bind(source_kind)
+        .bind(source_id)
+        .bind(stale)
+        .execute(&mut **tx)
+        .await?;
+    }
+    for mentioned in mentions
+        .iter()
+        .filter(|mentioned| !existing_mentions.contains(mentioned))
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO task_mentions (task_id, actor, source_kind, source_id, mentioned_by, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(task_id)
+        .bind(mentioned)
+        .bind(source_kind)
+        .bind(source_id)
+        .bind(actor)
+        .bind(&now)
+        .execute(&mut **tx)
+        .await?;
+
+        if mentioned != actor {
+            insert_history(
+                tx,
+                task_id,
+                actor,
+                EventKind::TaskMentioned,
+                serde_json::json!({
+                    "mentioned": mentioned,
+                    "source_kind": source_kind,
+                    "source_id": source_id,
+                }),
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fans a history entry out to the inboxes of watchers and directly involved actors.
+///
+/// A recipient gets at most one notification per entry, and never for their own actions.
+async fn notify_recipients(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    task_id: &str,
+    history_id: &str,
+    actor: &str,
+    action: EventKind,
+    detail: &Value,
+    now: &str,
+) -> AppResult<()> {
+    let (project_id, created_by) = sqlx::query_as::<Any, (String, String)>(
+        "SELECT project_id, created_by FROM tasks WHERE id = ?",
+    )
+    .bind(task_id)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    let mut recipients: Vec<(String, &str)> = Vec::new();
+    let direct = match action {
+        EventKind::QuestionCreated => Some((created_by, "question")),
+        EventKind::ReviewRequested => detail
+            .get("reviewer")
+            .and_then(Value::as_str)
+            .map(|value| (value.to_string(), "review_requested")),
+        EventKind::ReviewCompleted => detail
+            .get("requested_by")
+            .and_then(Value::as_str)
+            .map(|value| (value.to_string(), "review_completed")),
+        EventKind::TaskMentioned => detail
+            .get("mentioned")
+            .and_then(Value::as_str)
+            .map(|value| (value.to_string(), "mention")),
+        EventKind::TaskAssigned => detail
+            .get("to")
+            .and_then(Value::as_str)
+            .map(|value| (value.to_string(), "assigned")),
+        _ => None,
+    };
+    recipients.extend(direct);
+
+    let watchers =
+        sqlx::query_scalar::<Any, String>("SELECT actor FROM task_watchers WHERE task_id = ?")
+            .bind(task_id)
+            .fetch_all(&mut **tx)
+            .await?;
+    for watcher in watchers {
+        if !recipients
+            .iter()
+            .any(|(recipient, _)| *recipient == watcher)
+        {
+            recipients.push((watcher, "watching"));
+        }
+    }
+
+    let detail_json = detail.to_string();
+    for (recipient, reason) in recipients.iter().filter(|(value, _)| value != actor) {
+        sqlx::query(
+            r#"
+            INSERT INTO notifications (
+                id, recipient, project_id, task_id, history_id, action, actor, reason, detail, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(recipient)
+        .bind(&project_id)
+        .bind(task_id)
+        .bind(history_id)
+        .bind(action.as_str())
+        .bind(actor)
+        .bind(*reason)
+        .bind(&detail_json)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn insert_audit_event(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    actor: &str,
+    client_ip: Option<&str>,
+    action: &str,
+    subject: &str,
+    detail: Value,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO audit_events (id, actor, client_ip, action, subject, detail, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(actor)
+    .bind(client_ip)
+    .bind(action)
+    .bind(subject)
+    .bind(detail.to_string())
+    .bind(now_timestamp())
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_project_event(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    project_id: &str,
+    actor: &str,
+    action: EventKind,
+    detail: Value,
+) -> AppResult<()> {
+    let now = now_timestamp();
+    record_actor_seen(tx, actor, &now).await?;
+    sqlx::query(
+        r#"
+        INSERT INTO system_events (id, project_id, task_id, task_number, actor, action, detail, created_at)
+        VALUES (?, ?, NULL, NULL, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(project_id)
+    .bind(actor)
+    .bind(action.as_str())
+    .bind(detail.to_string())
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+
+    touch_content_version(tx, project_id).await
+}
+
+/// Gives the project a new `content_version`, so ETags handed out for it stop matching.
+async fn touch_content_version(conn: &mut sqlx::AnyConnection, project_id: &str) -> AppResult<()> {
+    sqlx::query("UPDATE projects SET content_version = ? WHERE id = ?")
+        .bind(Uuid::new_v4().to_string())
+        .bind(project_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Registers an actor the first time it shows up so responses can resolve its profile.
+async fn record_actor_seen(
+    tx: &mut sqlx::Transaction<'_, Any>,
+    actor: &str,
+    now: &str,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO actors (id, display_name, kind, avatar_url, created_at, updated_at)
+        VALUES (?, ?, ?, NULL, ?, ?)
+        ON CONFLICT (id) DO NOTHING
+        "#,
+    )
+    .bind(actor)
+    .bind(actor)
+    .bind(default_actor_kind(actor))
+    .bind(now)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+fn default_actor_kind(actor: &str) -> &'static str {
+    if actor == "human" {
+        "human"
+    } else {
+        "agent"
+    }
+}
+
+/// Timestamp `days` days ago in the same format as stored timestamps, for staleness cutoffs.
+pub fn timestamp_days_ago(days: u32) -> String {
+    (Utc::now() - chrono::Duration::days(i64::from(days)))
+        .to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn now_timestamp() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// A stored UTC timestamp as wall-clock time in `timezone`, with its offset:
+/// `2026-05-01T17:00:00.000Z` in `Europe/Berlin` is `2026-05-01T19:00:00.000+02:00`. Values
+/// that do not parse are returned unchanged.
+pub fn localize_timestamp(timestamp: &str, timezone: Tz) -> String {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(parsed) => parsed
+            .with_timezone(&timezone)
+            .to_rfc3339_opts(SecondsFormat::Millis, false),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+fn validate_status(value: &str) -> AppResult<()> {
+    if TASK_STATUSES.contains(&value) {
+        return Ok(());
+    }
+
+    Err(AppError::BadRequest(format!(
+        "invalid task status '{value}'"
+    )))
+}
+
+/// Splits a comma-separated filter value into trimmed, non-empty parts.
+pub fn parse_filter_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Appends `AND column [NOT] IN (...)` with bound values; does nothing for an empty list.
+fn push_in_list(query: &mut QueryBuilder<'_, Any>, column: &str, values: &[String], negate: bool) {
+    if values.is_empty() {
+        return;
+    }
+
+    query.push(" AND ");
+    query.push(column);
+    query.push(if negate { " NOT IN (" } else { " IN (" });
+    let mut separated = query.separated(", ");
+    for value in values {
+        separated.push_bind(value.clone());
+    }
+    separated.push_unseparated(")");
+}
+
+/// Intersects an explicit priority list with an inclusive min/max range.
+///
+/// Returns `None` when no priority filter applies.
+fn allowed_priorities(
+    priorities: &[String],
+    min: Option<&str>,
+    max: Option<&str>,
+) -> AppResult<Option<Vec<String>>> {
+    if priorities.is_empty() && min.is_none() && max.is_none() {
+        return Ok(None);
+    }
+
+    for priority in priorities.iter().map(String::as_str).chain(min).chain(max) {
+        validate_priority(priority)?;
+    }
+
+    let rank = |value: &str| TASK_PRIORITIES.iter().position(|p| *p == value);
+    let low = min.and_then(rank).unwrap_or(0);
+    let high = max.and_then(rank).unwrap_or(TASK_PRIORITIES.len() - 1);
+
+    let allowed = TASK_PRIORITIES
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index >= low && *index <= high)
+        .map(|(_, value)| *value)
+        .filter(|value| priorities.is_empty() || priorities.iter().any(|p| p == value))
+        .map(ToOwned::to_owned)
+        .collect();
+
+    Ok(Some(allowed))
+}
+
+/// Parses an RFC3339 due date and stores it in UTC; blank clears it.
+fn normalize_due_at(value: Option<String>) -> AppResult<Option<String>> {
+    let Some(value) = value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    let parsed = DateTime::parse_from_rfc3339(&value).map_err(|_| {
+        AppError::BadRequest(format!(
+            "due_at must be an RFC3339 timestamp such as 2026-05-01T17:00:00Z (got '{value}')"
+        ))
+    })?;
+    Ok(Some(
+        parsed
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    ))
+}
+
+/// Accepts RFC3339 or `YYYY-MM-DD` (midnight UTC) and returns the stored timestamp format.
+fn normalize_filter_timestamp(field: &str, value: Option<String>) -> AppResult<Option<String>> {
+    let Some(value) = value.map(|value| value.trim().to_string()) else {
+        return Ok(None);
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    let parsed = DateTime::parse_from_rfc3339(&value)
+        .map(|value| value.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        })
+        .map_err(|_| {
+            AppError::BadRequest(format!(
+                "{field} must be an RFC3339 timestamp or YYYY-MM-DD date"
+            ))
+        })?;
+
+    Ok(Some(parsed.to_rfc3339_opts(SecondsFormat::Millis, true)))
+}
+
+/// Orders tasks column by column in `column_order`, then by position within each column.
+///
+/// `column_order` only holds entries of `TASK_STATUSES`, so the clause is safe to push.
+fn board_order(column_order: &[&'static str]) -> String {
+    let mut clause = "CASE t.status".to_string();
+    for (position, status) in column_order.iter().enumerate() {
+        clause.push_str(&format!(" WHEN '{status}' THEN {position}"));
+    }
+    clause.push_str(&format!(
+        " ELSE {} END, t.sort_order ASC, t.created_at ASC",
+        column_order.len()
+    ));
+    clause
+}
+
+/// Maps a `field[:asc|desc]` sort spec to a fixed ORDER BY clause.
+///
+/// Only whitelisted clauses are returned, so the result is safe to push into a query. `None`
+/// means board order, which depends on the project's column settings.
+fn task_sort_clause(sort: Option<&str>) -> AppResult<Option<&'static str>> {
+    let Some(sort) = sort.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+
+    let (field, direction) = sort.split_once(':').unwrap_or((sort, "asc"));
+    let descending = match direction.trim().to_ascii_lowercase().as_str() {
+        "asc" => false,
+        "desc" => true,
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "invalid sort direction '{other}', expected asc or desc"
+            )))
+        }
+    };
+
+    let clause = match (field.trim(), descending) {
+        ("priority", false) => {
+            "CASE t.priority WHEN 'low' THEN 0 WHEN 'medium' THEN 1 WHEN 'high' THEN 2 \
+             WHEN 'critical' THEN 3 ELSE 4 END ASC, t.task_number ASC"
+        }
+        ("priority", true) => {
+            "CASE t.priority WHEN 'low' THEN 0 WHEN 'medium' THEN 1 WHEN 'high' THEN 2 \
+             WHEN 'critical' THEN 3 ELSE 4 END DESC, t.task_number ASC"
+        }
+        ("created_at", false) => "t.created_at ASC, t.task_number ASC",
+        ("created_at", true) => "t.created_at DESC, t.task_number DESC",
+        ("updated_at", false) => "t.updated_at ASC, t.task_number ASC",
+        ("updated_at", true) => "t.updated_at DESC, t.task_number DESC",
+        ("task_number", false) => "t.task_number ASC",
+        ("task_number", true) => "t.task_number DESC",
+        (other, _) => {
+            return Err(AppError::BadRequest(format!(
+                "invalid sort field '{other}', expected priority, created_at, updated_at, or task_number"
+            )))
+        }
+    };
+
+    Ok(Some(clause))
+}
+
+fn validate_priority(value: &str) -> AppResult<()> {
+    if TASK_PRIORITIES.contains(&value) {
+        return Ok(());
+    }
+
+    Err(AppError::BadRequest(format!(
+        "invalid task priority '{value}'"
+    )))
+}
+
+fn validate_review_state(value: &str) -> AppResult<()> {
+    match value {
+        "ready" | "not_ready" => Ok(()),
+        _ => Err(AppError::BadRequest(format!(
+            "invalid review state '{value}'"
+        ))),
+    }
+}
+
+fn normalize_webhook_platform(value: &str) -> AppResult<String> {
+    let platform = value.trim().to_ascii_lowercase();
+    match platform.as_str() {
+        "slack" | "discord" | "generic" => Ok(platform),
+        _ => Err(AppError::BadRequest(format!(
+            "invalid webhook platform '{value}'"
+        ))),
+    }
+}
+
+/// `lattice` posts `WebhookPayload` as is; `cloudevents` wraps it in a CloudEvents 1.0
+/// envelope, which only makes sense for generic webhooks.
+fn normalize_webhook_payload_format(platform: &str, value: &str) -> AppResult<String> {
+    let format = value.trim().to_ascii_lowercase();
+    match format.as_str() {
+        "lattice" => Ok(format),
+        "cloudevents" if platform == "generic" => Ok(format),
+        "cloudevents" => Err(AppError::BadRequest(format!(
+            "payload format 'cloudevents' requires the generic platform, not '{platform}'"
+        ))),
+        _ => Err(AppError::BadRequest(format!(
+            "invalid webhook payload format '{value}'"
+        ))),
+    }
+}
+
+/// Parses `HH:MM-HH:MM` into minutes after midnight. The range may wrap past midnight.
+pub fn parse_quiet_hours(value: &str) -> Option<(u32, u32)> {
+    let minutes = |time: &str| {
+        let (hours, minutes) = time.trim().split_once(':')?;
+        let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+        (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+    };
+    let (start, end) = value.split_once('-')?;
+    let (start, end) = (minutes(start)?, minutes(end)?);
+    (start != end).then_some((start, end))
+}
+
+fn normalize_quiet_hours(value: Option<&str>) -> AppResult<Option<String>> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    let (start, end) = parse_quiet_hours(value).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "invalid quiet_hours '{value}', expected HH:MM-HH:MM with different start and end"
+        ))
+    })?;
+    Ok(Some(format!(
+        "{:02}:{:02}-{:02}:{:02}",
+        start / 60,
+        start % 60,
+        end / 60,
+        end % 60
+    )))
+}
+
+fn normalize_timezone(value: &str) -> AppResult<String> {
+    let timezone = value.trim();
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|timezone| timezone.name().to_string())
+        .map_err(|_| AppError::BadRequest(format!("unknown timezone '{timezone}'")))
+}
+
+fn validate_max_deliveries_per_minute(value: i64) -> AppResult<i64> {
+    if !(0..=MAX_WEBHOOK_DELIVERIES_PER_MINUTE).contains(&value) {
+        return Err(AppError::BadRequest(format!(
+            "max_deliveries_per_minute must be between 0 (unlimited) and {MAX_WEBHOOK_DELIVERIES_PER_MINUTE}"
+        )));
+    }
+    Ok(value)
+}
+
+/// Checks a webhook's delivery overrides against the same bounds as the server defaults.
+fn validate_webhook_delivery_policy(
+    timeout_secs: Option<i64>,
+    max_retries: Option<i64>,
+    retry_backoff_secs: Option<i64>,
+) -> AppResult<(Option<i64>, Option<i64>, Option<i64>)> {
+    let checks = [
+        ("timeout_secs", timeout_secs, 1, MAX_WEBHOOK_TIMEOUT_SECS),
+        ("max_retries", max_retries, 0, MAX_WEBHOOK_RETRIES),
+        (
+            "retry_backoff_secs",
+            retry_backoff_secs,
+            1,
+            MAX_WEBHOOK_RETRY_BACKOFF_SECS,
+        ),
+    ];
+    for (field, value, min, max) in checks {
+        if value.is_some_and(|value| !(min..=max).contains(&value)) {
+            return Err(AppError::BadRequest(format!(
+                "{field} must be between {min} and {max}"
+            )));
+        }
+    }
+    Ok((timeout_secs, max_retries, retry_backoff_secs))
+}
+
+fn validate_webhook_schema_version(version: i64) -> AppResult<i64> {
+    if !(1..=LATEST_WEBHOOK_SCHEMA_VERSION).contains(&version) {
+        return Err(AppError::BadRequest(format!(
+            "webhook schema_version must be between 1 and {LATEST_WEBHOOK_SCHEMA_VERSION}"
+        )));
+    }
+    Ok(version)
+}
+
+fn normalize_webhook_url(value: &str) -> AppResult<String> {
+    let trimmed = value.trim();
+    let parsed = reqwest::Url::parse(trimmed)
+        .map_err(|_| AppError::BadRequest("webhook url must be a valid http(s) URL".to_string()))?;
+
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed.to_string()),
+        _ => Err(AppError::BadRequest(
+            "webhook url must use http or https".to_string(),
+        )),
+    }
+}
+
+fn normalize_webhook_events(events: Vec<String>) -> AppResult<Vec<String>> {
+    let mut normalized = std::collections::BTreeSet::new();
+    for event in events {
+        let candidate = event.trim();
+        if candidate.is_empty() {
+            continue;
+        }
+
+        let known = EventKind::ALL
+            .into_iter()
+            .any(|kind| kind.subscribable() && webhook_event_matches(candidate, kind));
+        if !known {
+            return Err(AppError::BadRequest(format!(
+                "invalid webhook event '{candidate}'"
+            )));
+        }
+        normalized.insert(candidate.to_string());
+    }
+
+    if normalized.is_empty() {
+        return Err(AppError::BadRequest(
+            "webhook must subscribe to at least one event".to_string(),
+        ));
+    }
+
+    Ok(normalized.into_iter().collect())
+}
+
+/// Whether a webhook subscribed to `subscriptions` receives `event`. Besides exact names, a
+/// subscription may be `*` for every event or `task.*` for one namespace. Patterns are expanded
+/// against the `EventKind` registry at dispatch time, so they pick up event types added later.
+pub fn webhook_subscribes_to(subscriptions: &[String], event: &str) -> bool {
+    event.parse::<EventKind>().is_ok_and(|kind| {
+        kind.subscribable()
+            && subscriptions
+                .iter()
+                .any(|subscription| webhook_event_matches(subscription, kind))
+    })
+}
+
+fn webhook_event_matches(subscription: &str, kind: EventKind) -> bool {
+    if subscription == "*" {
+        return true;
+    }
+    match subscription.strip_suffix(".*") {
+        Some(namespace) => kind
+            .as_str()
+            .strip_prefix(namespace)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => subscription == kind.as_str(),
+    }
+}
+
+pub fn parse_webhook_events(raw: &str) -> AppResult<Vec<String>> {
+    let parsed = serde_json::from_str::<Vec<String>>(raw).map_err(|error| {
+        tracing::error!(error = ?error, raw, "failed to parse webhook events");
+        AppError::Internal
+    })?;
+
+    normalize_webhook_events(parsed)
+}
+
+fn normalize_actor_kind(value: &str) -> AppResult<String> {
+    let normalized = value.trim().to_ascii_lowercase();
+    match normalized.as_str() {
+        "human" | "agent" => Ok(normalized),
+        _ => Err(AppError::BadRequest(
+            "kind must be 'human' or 'agent'".to_string(),
+        )),
+    }
+}
+
+/// An empty string clears the avatar.
+fn normalize_avatar_url(value: &str) -> AppResult<Option<String>> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let parsed = reqwest::Url::parse(trimmed)
+        .map_err(|_| AppError::BadRequest("avatar_url must be a valid http(s) URL".to_string()))?;
+
+    match parsed.scheme() {
+        "http" | "https" => Ok(Some(parsed.to_string())),
+        _ => Err(AppError::BadRequest(
+            "avatar_url must use http or https".to_string(),
+        )),
+    }
+}
+
+/// A trimmed secret, encrypted when `LATTICE_SECRET_KEY` is set. Blank means no secret.
+fn seal_webhook_secret(keyring: &Keyring, value: Option<String>) -> AppResult<Option<String>> {
+    normalize_optional_secret(value)
+        .map(|secret| keyring.seal(&secret))
+        .transpose()
+}
+
+fn normalize_optional_secret(value: Option<String>) -> Option<String> {
+    match value {
+        Some(secret) => {
+            let trimmed = secret.trim().to_string();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed)
+            }
+        }
+        None => None,
+    }
+}
+
+/// Trims an assignee, treating a blank one as unassigned.
+fn normalize_assignee(value: Option<String>) -> AppResult<Option<String>> {
+    let Some(assignee) = value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    else {
+        return Ok(None);
+    };
+    if assignee == UNASSIGNED_FILTER {
+        return Err(AppError::BadRequest(format!(
+            "'{UNASSIGNED_FILTER}' cannot be an assignee, it filters for unassigned tasks"
+        )));
+    }
+    if assignee.chars().count() > MAX_ASSIGNEE_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "assignee must be at most {MAX_ASSIGNEE_CHARS} characters"
+        )));
+    }
+    Ok(Some(assignee))
+}
+
+fn normalized_labels(labels: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::BTreeSet::new();
+    for label in labels {
+        let trimmed = label.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        seen.insert(trimmed.to_string());
+    }
+
+    seen.into_iter().collect()
+}
+
+fn is_canonical_uuid(value: &str) -> bool {
+    let parsed = match Uuid::parse_str(value) {
+        Ok(uuid) => uuid,
+        Err(_) => return false,
+    };
+
+    let canonical = parsed.hyphenated().to_string();
+    value.eq_ignore_ascii_case(&canonical)
+}
+
+fn parse_display_key(value: &str) -> Option<(String, i64)> {
+    let (slug, number) = value.split_once('-')?;
+    if slug.is_empty()
+        || !slug
+            .chars()
+            .all(|character| character.is_ascii_uppercase() || character.is_ascii_digit())
+    {
+        return None;
+    }
+
+    if number.starts_with('0') {
+        return None;
+    }
+
+    let parsed_number: i64 = number.parse().ok()?;
+    if parsed_number <= 0 {
+        return None;
+    }
+
+    Some((slug.to_string(), parsed_number))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::Value;
+    use sqlx::{Any, AnyPool};
+    use tempfile::tempdir;
+
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::error::AppError;
+
+    #[test]
+    fn parse_task_ref_accepts_uuid_and_display_key() {
+        let uuid = "123e4567-e89b-12d3-a456-426614174000";
+        let parsed_uuid = queries::parse_task_ref(uuid).expect("uuid should parse");
+        match parsed_uuid {
+            queries::TaskRef::Uuid(value) => assert_eq!(value, uuid),
+            _ => panic!("expected uuid task ref"),
+        }
+
+        let parsed_display =
+            queries::parse_task_ref("LATTICE-42").expect("display key should parse");
+        match parsed_display {
+            queries::TaskRef::DisplayKey { slug, task_number } => {
+                assert_eq!(slug, "LATTICE");
+                assert_eq!(task_number, 42);
+            }
+            _ => panic!("expected display-key task ref"),
+        }
+    }
+
+    #[test]
+    fn parse_task_ref_rejects_invalid_display_key() {
+        let result = queries::parse_task_ref("lattice-01");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn normalize_slug_rejects_lowercase_and_symbols() {
+        let normalized =
+            queries::normalize_slug(" lattice-v1 ").expect("slug normalization should succeed");
+        assert_eq!(normalized, "LATTICE-V1");
+
+        assert!(queries::normalize_slug("bad_slug").is_err());
+        assert!(queries::normalize_slug("-BAD").is_err());
+    }
+
+    #[test]
+    fn webhook_wildcards_expand_against_known_events() {
+        let events = queries::parse_webhook_events(r#"["task.*", "question.created"]"#)
+            .expect("wildcards should be accepted");
+        assert!(queries::webhook_subscribes_to(
+            &events,
+            "task.labels_changed"
+        ));
+        assert!(queries::webhook_subscribes_to(&events, "question.created"));
+        assert!(!queries::webhook_subscribes_to(
+            &events,
+            "question.resolved"
+        ));
+        assert!(!queries::webhook_subscribes_to(&events, "tasks.unknown"));
+        assert!(
+            !queries::webhook_subscribes_to(&events, "subtask.created"),
+            "a namespace does not match names that merely share its prefix"
+        );
+
+        let all = vec!["*".to_string()];
+        assert!(queries::webhook_subscribes_to(&all, "digest"));
+        assert!(!queries::webhook_subscribes_to(&all, "not.an_event"));
+
+        for invalid in [r#"["nothing.*"]"#, r#"["task*"]"#, r#"["*.created"]"#] {
+            assert!(queries::parse_webhook_events(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    async fn setup_db(db_name: &str) -> (tempfile::TempDir, AnyPool) {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join(format!("{db_name}.db"));
+        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
+
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 7400,
+            db_url,
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+
+        (temp_dir, pool)
+    }
+
+    #[tokio::test]
+    async fn create_task_allocates_incrementing_numbers() {
+        let (_temp_dir, pool) = setup_db("lattice-test").await;
+
+        let project = queries::create_project_with_slug(&pool, "lattice", "goal", "LATTICE")
+            .await
+            .expect("project creation should succeed");
+
+        let first_task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "first".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("first task should be created");
+
+        let second_task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "second".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("second task should be created");
+
+        assert_eq!(first_task.task_number, 1);
+        assert_eq!(second_task.task_number, 2);
+        assert_eq!(
+            queries::display_key(&project.project.slug, second_task.task_number),
+            "LATTICE-2"
+        );
+    }
+
+    #[tokio::test]
+    async fn spec_locks_block_other_editors_until_released() {
+        let (_temp_dir, pool) = setup_db("spec-lock-test").await;
+        queries::create_project_with_slug(&pool, "locks", "goal", "LOCKS")
+            .await
+            .expect("project should be created");
+
+        let locked = queries::lock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "agent",
+            None,
+        )
+        .await
+        .expect("lock should be taken");
+        assert_eq!(locked.locked_by.as_deref(), Some("agent"));
+        assert!(locked.lock_expires_at.is_some());
+        let taken = queries::lock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "human",
+            Some(60),
+        )
+        .await;
+        assert!(matches!(taken, Err(AppError::Conflict(_))));
+        let too_long = queries::lock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "agent",
+            Some(86_400),
+        )
+        .await;
+        assert!(matches!(too_long, Err(AppError::BadRequest(_))));
+
+        queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "by agent",
+            "agent",
+            false,
+        )
+        .await
+        .expect("the holder can edit");
+        let blocked = queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "by human",
+            "human",
+            false,
+        )
+        .await;
+        assert!(matches!(blocked, Err(AppError::Conflict(_))));
+        let forced = queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "by human",
+            "human",
+            true,
+        )
+        .await
+        .expect("force overrides the lock");
+        assert_eq!(forced.content, "by human");
+
+        let not_holder = queries::unlock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "human",
+            false,
+        )
+        .await;
+        assert!(matches!(not_holder, Err(AppError::Conflict(_))));
+        let released = queries::unlock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "agent",
+            false,
+        )
+        .await
+        .expect("the holder can release");
+        assert_eq!(released.locked_by, None);
+
+        queries::lock_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "agent",
+            None,
+        )
+        .await
+        .expect("lock should be taken again");
+        sqlx::query("UPDATE spec_sections SET lock_expires_at = ? WHERE section = 'overview'")
+            .bind(queries::timestamp_days_ago(1))
+            .execute(&pool)
+            .await
+            .expect("lock should be expired");
+        let expired =
+            queries::get_spec_section(&pool, &FieldLimitConfig::default(), "LOCKS", "overview")
+                .await
+                .expect("section should load");
+        assert_eq!(expired.locked_by, None);
+        queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LOCKS",
+            "overview",
+            "after expiry",
+            "human",
+            false,
+        )
+        .await
+        .expect("an expired lock does not block edits");
+    }
+
+    #[tokio::test]
+    async fn update_spec_section_creates_revision() {
+        let (_temp_dir, pool) = setup_db("spec-test").await;
+        let project = queries::create_project_with_slug(&pool, "phase3spec", "goal", "PHASE3SPEC")
+            .await
+            .expect("project should be created");
+
+        let updated = queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            "overview",
+            "# Overview",
+            "human",
+            false,
+        )
+        .await
+        .expect("section update should succeed");
+        assert_eq!(updated.section, "overview");
+        assert_eq!(updated.content, "# Overview");
+
+        let history =
+            queries::list_spec_history(&pool, &project.project.slug, "overview", 50, 0, true)
+                .await
+                .expect("history should be listed");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content.as_deref(), Some("# Overview"));
+        assert_eq!(history[0].edited_by, "human");
+        assert_eq!(history[0].bytes_added, 10);
+        assert_eq!(history[0].headings_touched, vec!["Overview"]);
+
+        queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            "overview",
+            "# Overview\n## Scope\nAPI only\n",
+            "human",
+            false,
+        )
+        .await
+        .expect("second update should succeed");
+        let summaries =
+            queries::list_spec_history(&pool, &project.project.slug, "overview", 50, 0, false)
+                .await
+                .expect("summaries should be listed");
+        assert!(summaries.iter().all(|revision| revision.content.is_none()));
+        let second = summaries
+            .iter()
+            .find(|revision| revision.bytes_removed > 0)
+            .expect("second revision should be listed");
+        assert_eq!(second.bytes_removed, 10);
+        assert_eq!(second.headings_touched, vec!["Overview", "Scope"]);
+    }
+
+    #[tokio::test]
+    async fn spec_sections_report_counts_and_their_own_length_limit() {
+        let (_temp_dir, pool) = setup_db("spec-limits-test").await;
+        let project = queries::create_project_with_slug(&pool, "spec limits", "goal", "SPECLIM")
+            .await
+            .expect("project should be created");
+        let limits = FieldLimitConfig {
+            max_spec_section_lengths: vec![("architecture".to_string(), 100)],
+            ..FieldLimitConfig::default()
+        };
+        assert_eq!(
+            limits.spec_section_limit("architecture"),
+            (100, "LATTICE_MAX_SPEC_SECTION_LENGTHS")
+        );
+        assert_eq!(
+            limits.spec_section_limit("overview"),
+            (limits.max_spec_length, "LATTICE_MAX_SPEC_LENGTH")
+        );
+
+        let overview = queries::update_spec_section(
+            &pool,
+            &limits,
+            &project.project.slug,
+            "overview",
+            "# Overview",
+            "human",
+            false,
+        )
+        .await
+        .expect("section update should succeed");
+        assert_eq!((overview.word_count, overview.char_count), (2, 10));
+        assert_eq!(overview.max_length, limits.max_spec_length);
+
+        let too_long = queries::update_spec_section(
+            &pool,
+            &limits,
+            &project.project.slug,
+            "architecture",
+            &"a".repeat(101),
+            "human",
+            false,
+        )
+        .await;
+        match too_long {
+            Err(AppError::BadRequest(message)) => {
+                assert!(
+                    message.contains("LATTICE_MAX_SPEC_SECTION_LENGTHS"),
+                    "{message}"
+                );
+            }
+            other => panic!("expected a length error, got {other:?}"),
+        }
+        let architecture =
+            queries::get_spec_section(&pool, &limits, &project.project.slug, "architecture")
+                .await
+                .expect("section should load");
+        assert_eq!(architecture.max_length, 100);
+    }
+
+    #[tokio::test]
+    async fn open_question_can_be_created_and_resolved() {
+        let (_temp_dir, pool) = setup_db("questions-test").await;
+        let project =
+            queries::create_project_with_slug(&pool, "phase3questions", "goal", "PHASE3QUESTIONS")
+                .await
+                .expect("project should be created");
+
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "question task".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let task_ref = queries::display_key(&project.project.slug, task.task_number);
+        let created = queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            &task_ref,
+            "Use SSE?",
+            "Need realtime notifications.",
+            "human",
+        )
+        .await
+        .expect("open question should be created");
+        assert_eq!(created.status, "open");
+
+        let open_questions =
+            queries::list_project_open_questions(&pool, &project.project.slug, 50, 0)
+                .await
+                .expect("open question list should succeed");
+        assert_eq!(open_questions.len(), 1);
+
+        let resolved = queries::answer_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            &task_ref,
+            &created.id,
+            "Yes",
+            "human",
+        )
+        .await
+        .expect("open question should be resolved");
+        assert_eq!(resolved.status, "resolved");
+        assert_eq!(resolved.answer.as_deref(), Some("Yes"));
+
+        let remaining = queries::list_project_open_questions(&pool, &project.project.slug, 50, 0)
+            .await
+            .expect("remaining open question list should succeed");
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn disputed_answers_reopen_the_question_until_accepted() {
+        let (_temp_dir, pool) = setup_db("answer-review-test").await;
+        let project = queries::create_project_with_slug(&pool, "reviews", "goal", "REVIEWS")
+            .await
+            .expect("project should be created");
+        let limits = FieldLimitConfig::default();
+        let task = queries::create_task(
+            &pool,
+            &limits,
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "question task".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let task_ref = queries::display_key(&project.project.slug, task.task_number);
+        let created = queries::create_open_question(
+            &pool,
+            &limits,
+            &project.project.slug,
+            &task_ref,
+            "Use SSE?",
+            "",
+            "human",
+        )
+        .await
+        .expect("open question should be created");
+        queries::answer_open_question(
+            &pool,
+            &limits,
+            &project.project.slug,
+            &task_ref,
+            &created.id,
+            "Yes",
+            "human",
+        )
+        .await
+        .expect("open question should be resolved");
+
+        let review = |verdict: &'static str, reason: Option<&'static str>| {
+            queries::review_question_answer(
+                &pool,
+                &limits,
+                &project.project.slug,
+                &task_ref,
+                &created.id,
+                queries::ReviewAnswerInput {
+                    verdict: verdict.to_string(),
+                    reason: reason.map(ToOwned::to_owned),
+                    actor: "human".to_string(),
+                },
+            )
+        };
+        let unexplained = review("disputed", Some("  ")).await;
+        assert!(matches!(unexplained, Err(AppError::BadRequest(_))));
+        let disputed = review("disputed", Some("Only for the browser?"))
+            .await
+            .expect("answer should be disputed");
+        assert_eq!(disputed.status, "open");
+        assert_eq!(disputed.acceptance, "disputed");
+        assert_eq!(
+            disputed.dispute_reason.as_deref(),
+            Some("Only for the browser?")
+        );
+        let summary = queries::get_project(&pool, &project.project.slug)
+            .await
+            .expect("project should load");
+        assert_eq!(summary.disputed_question_count, 1);
+        let unanswered = review("accepted", None).await;
+        assert!(matches!(unanswered, Err(AppError::Conflict(_))));
+
+        queries::answer_open_question(
+            &pool,
+            &limits,
+            &project.project.slug,
+            &task_ref,
+            &created.id,
+            "Yes, the CLI polls",
+            "human",
+        )
+        .await
+        .expect("disputed question should be answered again");
+        let accepted = review("accepted", None)
+            .await
+            .expect("answer should be accepted");
+        assert_eq!(accepted.status, "resolved");
+        assert_eq!(accepted.acceptance, "accepted");
+        let again = review("disputed", Some("changed my mind")).await;
+        assert!(matches!(again, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn bulk_answers_apply_valid_entries_together() {
+        let (_temp_dir, pool) = setup_db("bulk-answer-test").await;
+        queries::create_project_with_slug(&pool, "bulk answers", "goal", "BQA")
+            .await
+            .expect("project should be created");
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BQA",
+            queries::NewTaskInput {
+                title: "triage".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let mut question_ids = Vec::new();
+        for question in ["Which region?", "Which database?", "Which queue?"] {
+            let created = queries::create_open_question(
+                &pool,
+                &FieldLimitConfig::default(),
+                "BQA",
+                "BQA-1",
+                question,
+                "",
+                "agent",
+            )
+            .await
+            .expect("question should be created");
+            question_ids.push(created.id);
+        }
+        queries::answer_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BQA",
+            "BQA-1",
+            &question_ids[2],
+            "SQS",
+            "human",
+        )
+        .await
+        .expect("question should be answered");
+
+        let item = |question_id: &str, answer: &str| queries::BulkAnswerItem {
+            question_id: question_id.to_string(),
+            answer: answer.to_string(),
+        };
+        let results = queries::answer_open_questions_bulk(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BQA",
+            vec![
+                item(&question_ids[0], "eu-west-1"),
+                item(&question_ids[0], "us-east-1"),
+                item(&question_ids[1], "Postgres"),
+                item(&question_ids[2], "Kafka"),
+                item("missing", "anything"),
+            ],
+            true,
+            "human",
+        )
+        .await
+        .expect("bulk answer should succeed");
+        let resolved = results
+            .iter()
+            .map(|result| result.resolved)
+            .collect::<Vec<_>>();
+        assert_eq!(resolved, vec![true, false, true, false, false]);
+        assert_eq!(
+            results[0]
+                .question
+                .as_ref()
+                .and_then(|question| question.answer.as_deref()),
+            Some("eu-west-1")
+        );
+        assert!(results[3]
+            .error
+            .as_deref()
+            .is_some_and(|error| error.contains("already resolved")));
+
+        let remaining = queries::list_project_open_questions(&pool, "BQA", 50, 0)
+            .await
+            .expect("open questions should list");
+        assert!(remaining.is_empty());
+        let actions = sqlx::query_scalar::<sqlx::Any, String>(
+            "SELECT action FROM system_events WHERE action LIKE 'question%' ORDER BY action",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("events should load");
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| *action == "question.resolved")
+                .count(),
+            1,
+            "only the single answer emits question.resolved"
+        );
+        assert!(actions.iter().any(|action| action == "questions.resolved"));
+
+        let empty = queries::answer_open_questions_bulk(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BQA",
+            Vec::new(),
+            false,
+            "human",
+        )
+        .await;
+        assert!(matches!(empty, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn spec_update_writes_system_event() {
+        let (_temp_dir, pool) = setup_db("spec-event-test").await;
+        let project =
+            queries::create_project_with_slug(&pool, "spec events", "goal", "SPEC-EVENTS")
+                .await
+                .expect("project should be created");
+
+        queries::update_spec_section(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            "architecture",
+            "## architecture",
+            "human",
+            false,
+        )
+        .await
+        .expect("spec update should succeed");
+
+        let events = queries::list_system_events(
+            &pool,
+            None,
+            std::slice::from_ref(&project.project.slug),
+            None,
+            None,
+            50,
+        )
+        .await
+        .expect("events should be listed");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, "spec.updated");
+        assert!(events[0].task_id.is_none());
+        assert!(events[0].task_number.is_none());
+    }
+
+    #[tokio::test]
+    async fn goal_update_writes_system_event() {
+        let (_temp_dir, pool) = setup_db("goal-event-test").await;
+        let project =
+            queries::create_project_with_slug(&pool, "goal events", "old goal", "GOAL-EVENTS")
+                .await
+                .expect("project should be created");
+
+        queries::update_project(
+            &pool,
+            &project.project.slug,
+            None,
+            Some("new goal".to_string()),
+            "human",
+        )
+        .await
+        .expect("goal update should succeed");
+
+        let events = queries::list_system_events(
+            &pool,
+            None,
+            std::slice::from_ref(&project.project.slug),
+            None,
+            None,
+            50,
+        )
+        .await
+        .expect("events should be listed");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, "goal.updated");
+        assert!(events[0].task_id.is_none());
+        assert!(events[0].task_number.is_none());
+        assert!(events[0].detail.contains("\"from_goal\":\"old goal\""));
+        assert!(events[0].detail.contains("\"to_goal\":\"new goal\""));
+    }
+
+    #[tokio::test]
+    async fn goal_history_starts_from_the_original_goal() {
+        let (_temp_dir, pool) = setup_db("goal-history-test").await;
+        queries::create_project_with_slug(&pool, "goals", "ship v1", "GOALS")
+            .await
+            .expect("project should be created");
+        let history = queries::list_goal_history(&pool, "GOALS")
+            .await
+            .expect("history should load");
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].goal, "ship v1");
+
+        for goal in ["ship v1 to beta users", "grow revenue"] {
+            queries::update_project(&pool, "GOALS", None, Some(goal.to_string()), "pm")
+                .await
+                .expect("goal should update");
+        }
+        let history = queries::list_goal_history(&pool, "GOALS")
+            .await
+            .expect("history should load");
+        let goals = history
+            .iter()
+            .map(|revision| revision.goal.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            goals,
+            vec!["grow revenue", "ship v1 to beta users", "ship v1"]
+        );
+        assert_eq!(history[0].changed_by.as_deref(), Some("pm"));
+        assert_eq!(history[2].changed_by, None);
+    }
+
+    #[tokio::test]
+    async fn actors_are_registered_on_first_sight_and_resolved_in_events() {
+        let (_temp_dir, pool) = setup_db("actor-registry-test").await;
+        let project = queries::create_project_with_slug(&pool, "actors", "goal", "ACTORS")
+            .await
+            .expect("project should be created");
+
+        queries::update_project(
+            &pool,
+            &project.project.slug,
+            None,
+            Some("new goal".to_string()),
+            "planner-bot",
+        )
+        .await
+        .expect("goal update should succeed");
+
+        let actor = queries::get_actor(&pool, "planner-bot")
+            .await
+            .expect("actor should be auto-registered");
+        assert_eq!(actor.display_name, "planner-bot");
+        assert_eq!(actor.kind, "agent");
+
+        queries::update_actor(
+            &pool,
+            "planner-bot",
+            queries::UpdateActorInput {
+                display_name: Some("Planner".to_string()),
+                kind: None,
+                avatar_url: Some("https://example.com/planner.png".to_string()),
+            },
+        )
+        .await
+        .expect("actor update should succeed");
+
+        let events = queries::list_system_events(
+            &pool,
+            None,
+            std::slice::from_ref(&project.project.slug),
+            None,
+            None,
+            50,
+        )
+        .await
+        .expect("events should be listed");
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].actor, "planner-bot");
+        assert_eq!(events[0].actor_display_name.as_deref(), Some("Planner"));
+        assert_eq!(events[0].actor_kind.as_deref(), Some("agent"));
+        assert_eq!(
+            events[0].actor_avatar_url.as_deref(),
+            Some("https://example.com/planner.png")
+        );
+    }
+
+    #[tokio::test]
+    async fn actor_activity_spans_projects_for_one_actor() {
+        let (_temp_dir, pool) = setup_db("actor-activity-test").await;
+        for (name, slug) in [("first", "ACT-ONE"), ("second", "ACT-TWO")] {
+            queries::create_project_with_slug(&pool, name, "goal", slug)
+                .await
+                .expect("project should be created");
+            queries::update_project(&pool, slug, None, Some(format!("{name} goal")), "night-bot")
+                .await
+                .expect("goal update should succeed");
+        }
+        queries::update_project(&pool, "ACT-ONE", None, Some("other".to_string()), "human")
+            .await
+            .expect("goal update should succeed");
+
+        let activity = queries::list_actor_activity(&pool, None, "night-bot", 50, 0)
+            .await
+            .expect("activity should be listed");
+        let mut projects = activity
+            .iter()
+            .map(|event| event.project_slug.as_str())
+            .collect::<Vec<_>>();
+        projects.sort_unstable();
+        assert_eq!(projects, vec!["ACT-ONE", "ACT-TWO"]);
+        assert!(activity.iter().all(|event| event.actor == "night-bot"));
+
+        let second_page = queries::list_actor_activity(&pool, None, "night-bot", 1, 1)
+            .await
+            .expect("paged activity should be listed");
+        assert_eq!(second_page.len(), 1);
+        assert_eq!(second_page[0].id, activity[1].id);
+    }
+
+    #[tokio::test]
+    async fn workspace_actor_lists_only_include_local_activity() {
+        let (_temp_dir, pool) = setup_db("actor-workspace-test").await;
+        let mut workspace_ids = Vec::new();
+        for (name, slug, actor) in [("red", "RED", "red-bot"), ("blue", "BLUE", "blue-bot")] {
+            let workspace = queries::create_workspace(&pool, name, &name.to_lowercase())
+                .await
+                .expect("workspace should be created");
+            queries::create_project_in_workspace(&pool, &workspace.id, name, "goal", slug)
+                .await
+                .expect("project should be created");
+            queries::update_project(&pool, slug, None, Some(format!("{name} goal")), actor)
+                .await
+                .expect("goal update should succeed");
+            workspace_ids.push(workspace.id);
+        }
+
+        let actor_ids = |actors: Vec<crate::db::models::ActorRecord>| {
+            actors.into_iter().map(|actor| actor.id).collect::<Vec<_>>()
+        };
+        let red = queries::list_actors(&pool, Some(&workspace_ids[0]), 50, 0)
+            .await
+            .expect("workspace actors should be listed");
+        assert_eq!(actor_ids(red), vec!["red-bot".to_string()]);
+
+        let all = queries::list_actors(&pool, None, 50, 0)
+            .await
+            .expect("actors should be listed");
+        assert_eq!(
+            actor_ids(all),
+            vec!["blue-bot".to_string(), "red-bot".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn priority_edits_and_their_undo_record_priority_changed() {
+        let (_temp_dir, pool) = setup_db("priority-change-test").await;
+        queries::create_project_with_slug(&pool, "priority", "goal", "PRIO")
+            .await
+            .expect("project should be created");
+        let limits = FieldLimitConfig::default();
+        queries::create_task(
+            &pool,
+            &limits,
+            "PRIO",
+            queries::NewTaskInput {
+                title: "escalate me".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let update = |title: Option<&str>, priority: Option<&str>| queries::UpdateTaskInput {
+            title: title.map(ToOwned::to_owned),
+            description: None,
+            status: None,
+            priority: priority.map(ToOwned::to_owned),
+            review_state: None,
+            labels: None,
+            assignee: None,
+            due_at: None,
+            actor: "ops-bot".to_string(),
+        };
+        let priority_changes = || async {
+            queries::get_task_details(&pool, "PRIO", "PRIO-1")
+                .await
+                .expect("task details should load")
+                .history
+                .into_iter()
+                .filter(|entry| entry.action == "task.priority_changed")
+                .map(|entry| {
+                    let detail: Value =
+                        serde_json::from_str(&entry.detail).expect("detail should parse");
+                    (
+                        entry.actor,
+                        detail["from_priority"].clone(),
+                        detail["to_priority"].clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        queries::update_task(
+            &pool,
+            &limits,
+            "PRIO",
+            "PRIO-1",
+            update(Some("renamed"), None),
+        )
+        .await
+        .expect("title should update");
+        assert!(priority_changes().await.is_empty());
+
+        queries::update_task(&pool, &limits, "PRIO", "PRIO-1", update(None, Some("high")))
+            .await
+            .expect("priority should update");
+        assert_eq!(
+            priority_changes().await,
+            vec![(
+                "ops-bot".to_string(),
+                Value::from("medium"),
+                Value::from("high")
+            )]
+        );
+
+        queries::undo_last_task_change(&pool, "PRIO", "PRIO-1", "human")
+            .await
+            .expect("priority change should be undone");
+        let mut changes = priority_changes().await;
+        changes.sort_by_key(|(actor, _, _)| actor.clone());
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    "human".to_string(),
+                    Value::from("high"),
+                    Value::from("medium")
+                ),
+                (
+                    "ops-bot".to_string(),
+                    Value::from("medium"),
+                    Value::from("high")
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn label_edits_record_what_was_added_and_removed() {
+        let (_temp_dir, pool) = setup_db("label-change-test").await;
+        queries::create_project_with_slug(&pool, "labels", "goal", "LBL")
+            .await
+            .expect("project should be created");
+        let limits = FieldLimitConfig::default();
+        queries::create_task(
+            &pool,
+            &limits,
+            "LBL",
+            queries::NewTaskInput {
+                title: "tag me".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: vec!["ui".to_string(), "api".to_string()],
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let relabel = |labels: &[&str]| queries::UpdateTaskInput {
+            title: None,
+            description: None,
+            status: None,
+            priority: None,
+            review_state: None,
+            labels: Some(labels.iter().map(ToString::to_string).collect()),
+            assignee: None,
+            due_at: None,
+            actor: "human".to_string(),
+        };
+        let label_changes = || async {
+            queries::get_task_details(&pool, "LBL", "LBL-1")
+                .await
+                .expect("task details should load")
+                .history
+                .into_iter()
+                .filter(|entry| entry.action == "task.labels_changed")
+                .map(|entry| {
+                    serde_json::from_str::<Value>(&entry.detail).expect("detail should parse")
+                })
+                .collect::<Vec<_>>()
+        };
+
+        queries::update_task(&pool, &limits, "LBL", "LBL-1", relabel(&["api", "backend"]))
+            .await
+            .expect("labels should update");
+        let changes = label_changes().await;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["added"], serde_json::json!(["backend"]));
+        assert_eq!(changes[0]["removed"], serde_json::json!(["ui"]));
+        assert_eq!(changes[0]["labels"], serde_json::json!(["api", "backend"]));
+
+        queries::undo_last_task_change(&pool, "LBL", "LBL-1", "human")
+            .await
+            .expect("label change should be undone");
+        let changes = label_changes().await;
+        assert_eq!(changes.len(), 2);
+        assert!(changes
+            .iter()
+            .any(|detail| detail["added"] == serde_json::json!(["ui"])
+                && detail["removed"] == serde_json::json!(["backend"])));
+
+        queries::update_task(&pool, &limits, "LBL", "LBL-1", relabel(&["api", "ui"]))
+            .await
+            .expect("reordered labels should save");
+        assert_eq!(
+            label_changes().await.len(),
+            2,
+            "the same labels in another order are not a change"
+        );
+    }
+
+    #[tokio::test]
+    async fn undo_reverses_latest_changes_in_order() {
+        let (_temp_dir, pool) = setup_db("undo-test").await;
+        let project = queries::create_project_with_slug(&pool, "undo", "goal", "UNDO")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "draggable".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: vec!["ui".to_string()],
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        queries::update_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            &task.id,
+            queries::UpdateTaskInput {
+                title: Some("renamed".to_string()),
+                description: None,
+                status: None,
+                priority: Some("high".to_string()),
+                review_state: None,
+                labels: Some(vec!["backend".to_string()]),
+                assignee: None,
+                due_at: None,
+                actor: "human".to_string(),
+            },
+        )
+        .await
+        .expect("task should update");
+
+        let undone =
+            queries::undo_last_task_change(&pool, &project.project.slug, &task.id, "human")
+                .await
+                .expect("update should be undone");
+        assert_eq!(undone.title, "draggable");
+        assert_eq!(undone.priority, "medium");
+
+        let details = queries::get_task_details(&pool, &project.project.slug, &task.id)
+            .await
+            .expect("task details should load");
+        assert_eq!(details.labels, vec!["ui".to_string()]);
+        assert!(details
+            .history
+            .iter()
+            .any(|entry| entry.action == "task.undone"));
+
+        let nothing_left =
+            queries::undo_last_task_change(&pool, &project.project.slug, &task.id, "human").await;
+        assert!(matches!(
+            nothing_left,
+            Err(crate::error::AppError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn stale_in_progress_tasks_are_flagged_once() {
+        let (_temp_dir, pool) = setup_db("stale-test").await;
+        let project = queries::create_project_with_slug(&pool, "stale", "goal", "STALE")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "forgotten".to_string(),
+                description: String::new(),
+                status: "in_progress".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        sqlx::query("UPDATE tasks SET updated_at = ? WHERE id = ?")
+            .bind(queries::timestamp_days_ago(10))
+            .bind(&task.id)
+            .execute(&pool)
+            .await
+            .expect("task timestamp should be backdated");
+
+        let cutoff = queries::timestamp_days_ago(7);
+        let stale = queries::list_newly_stale_tasks(&pool, &cutoff)
+            .await
+            .expect("stale tasks should be listed");
+        assert_eq!(stale.len(), 1);
+
+        let filtered = queries::list_tasks(
+            &pool,
+            &project.project.slug,
+            queries::TaskFilters {
+                stale_before: Some(cutoff.clone()),
+                ..queries::TaskFilters::default()
+            },
+            50,
+            0,
+        )
+        .await
+        .expect("stale filter should work");
+        assert_eq!(filtered.len(), 1);
+
+        queries::mark_task_stale(&pool, &stale[0], 7)
+            .await
+            .expect("task should be flagged");
+        let again = queries::list_newly_stale_tasks(&pool, &cutoff)
+            .await
+            .expect("stale tasks should be listed");
+        assert!(again.is_empty());
+    }
+
+    #[tokio::test]
+    async fn archived_in_progress_tasks_are_not_reported_stale() {
+        let (_temp_dir, pool) = setup_db("stale-archived-test").await;
+        let project = queries::create_project_with_slug(&pool, "stale", "goal", "STALE")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "deleted mid-flight".to_string(),
+                description: String::new(),
+                status: "in_progress".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        queries::archive_task(&pool, &project.project.slug, &task.id, "human")
+            .await
+            .expect("task should be archived");
+
+        sqlx::query("UPDATE tasks SET updated_at = ? WHERE id = ?")
+            .bind(queries::timestamp_days_ago(10))
+            .bind(&task.id)
+            .execute(&pool)
+            .await
+            .expect("task timestamp should be backdated");
+
+        let stale = queries::list_newly_stale_tasks(&pool, &queries::timestamp_days_ago(7))
+            .await
+            .expect("stale tasks should be listed");
+        assert!(stale.is_empty());
+    }
+
+    #[tokio::test]
+    async fn crowded_sort_orders_are_rebalanced_on_move() {
+        let (_temp_dir, pool) = setup_db("rebalance-test").await;
+        let project = queries::create_project_with_slug(&pool, "rebalance", "goal", "REBAL")
+            .await
+            .expect("project should be created");
+
+        let mut task_ids = Vec::new();
+        for title in ["first", "second", "third"] {
+            let task = queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                &project.project.slug,
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+            task_ids.push(task.id);
+        }
+
+        let first = queries::get_task_details(&pool, &project.project.slug, &task_ids[0])
+            .await
+            .expect("task should load")
+            .task;
+        let moved = queries::move_task(
+            &pool,
+            &project.project.slug,
+            &task_ids[2],
+            queries::MoveTaskInput {
+                status: "backlog".to_string(),
+                sort_order: Some(first.sort_order + 1e-9),
+                actor: "human".to_string(),
+                mcp_origin: false,
+            },
+        )
+        .await
+        .expect("task should move");
+        assert_eq!(moved.sort_order, 2.0);
+
+        let ordered = queries::list_tasks(
+            &pool,
+            &project.project.slug,
+            queries::TaskFilters {
+                statuses: vec!["backlog".to_string()],
+                ..queries::TaskFilters::default()
+            },
+            50,
+            0,
+        )
+        .await
+        .expect("tasks should list");
+        let titles = ordered
+            .iter()
+            .map(|task| (task.title.as_str(), task.sort_order))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles,
+            vec![("first", 1.0), ("third", 2.0), ("second", 3.0)]
+        );
+
+        let results = queries::rebalance_task_columns(&pool, &project.project.slug, None)
+            .await
+            .expect("explicit rebalance should succeed");
+        assert_eq!(results.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn list_tasks_sorts_by_requested_field() {
+        let (_temp_dir, pool) = setup_db("sort-test").await;
+        let project = queries::create_project_with_slug(&pool, "sort", "goal", "SORT")
+            .await
+            .expect("project should be created");
+
+        for (title, priority) in [("a", "low"), ("b", "critical"), ("c", "medium")] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                &project.project.slug,
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: priority.to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let titles = |tasks: Vec<crate::db::models::TaskRecord>| {
+            tasks.into_iter().map(|task| task.title).collect::<Vec<_>>()
+        };
+        let sorted = |sort: &str| queries::TaskFilters {
+            sort: Some(sort.to_string()),
+            ..queries::TaskFilters::default()
+        };
+
+        let by_priority = queries::list_tasks(&pool, "SORT", sorted("priority:desc"), 50, 0)
+            .await
+            .expect("priority sort should succeed");
+        assert_eq!(titles(by_priority), vec!["b", "c", "a"]);
+
+        let by_number = queries::list_tasks(&pool, "SORT", sorted("task_number:desc"), 50, 0)
+            .await
+            .expect("task_number sort should succeed");
+        assert_eq!(titles(by_number), vec!["c", "b", "a"]);
+
+        assert!(queries::list_tasks(&pool, "SORT", sorted("title"), 50, 0)
+            .await
+            .is_err());
+        assert!(
+            queries::list_tasks(&pool, "SORT", sorted("priority:sideways"), 50, 0)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn list_tasks_applies_multi_value_and_negated_filters() {
+        let (_temp_dir, pool) = setup_db("filter-test").await;
+        let project = queries::create_project_with_slug(&pool, "filter", "goal", "FILTER")
+            .await
+            .expect("project should be created");
+
+        for (title, status, priority, label, creator) in [
+            ("a", "ready", "low", "backend", "human"),
+            ("b", "in_progress", "high", "wontfix", "human"),
+            ("c", "in_progress", "critical", "backend", "agent"),
+            ("d", "done", "high", "backend", "human"),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                &project.project.slug,
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: priority.to_string(),
+                    review_state: "ready".to_string(),
+                    labels: vec![label.to_string()],
+                    created_by: creator.to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let titles = |filters: queries::TaskFilters| {
+            let pool = pool.clone();
+            async move {
+                let mut titles = queries::list_tasks(&pool, "FILTER", filters, 50, 0)
+                    .await
+                    .expect("filtered list should succeed")
+                    .into_iter()
+                    .map(|task| task.title)
+                    .collect::<Vec<_>>();
+                titles.sort();
+                titles
+            }
+        };
+
+        let active = titles(queries::TaskFilters {
+            statuses: queries::parse_filter_list(Some("ready, in_progress")),
+            exclude_labels: vec!["wontfix".to_string()],
+            ..queries::TaskFilters::default()
+        })
+        .await;
+        assert_eq!(active, vec!["a", "c"]);
+
+        let urgent = titles(queries::TaskFilters {
+            min_priority: Some("high".to_string()),
+            exclude_statuses: vec!["done".to_string()],
+            created_by: Some("human".to_string()),
+            ..queries::TaskFilters::default()
+        })
+        .await;
+        assert_eq!(urgent, vec!["b"]);
+
+        let backend = queries::TaskFilters {
+            labels: vec!["backend".to_string()],
+            ..queries::TaskFilters::default()
+        };
+        let total = queries::count_tasks(&pool, "FILTER", backend.clone())
+            .await
+            .expect("count should succeed");
+        let first_page = queries::list_tasks(&pool, "FILTER", backend, 2, 0)
+            .await
+            .expect("paged list should succeed");
+        assert_eq!(total, 3);
+        assert_eq!(first_page.len(), 2);
+
+        let none_yet = titles(queries::TaskFilters {
+            created_before: Some("2000-01-01".to_string()),
+            ..queries::TaskFilters::default()
+        })
+        .await;
+        assert!(none_yet.is_empty());
+
+        let invalid = queries::list_tasks(
+            &pool,
+            "FILTER",
+            queries::TaskFilters {
+                updated_after: Some("last tuesday".to_string()),
+                ..queries::TaskFilters::default()
+            },
+            50,
+            0,
+        )
+        .await;
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn batch_get_returns_found_tasks_and_missing_refs() {
+        let (_temp_dir, pool) = setup_db("batch-get-test").await;
+        let project = queries::create_project_with_slug(&pool, "batch", "goal", "BATCH")
+            .await
+            .expect("project should be created");
+
+        for title in ["one", "two"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                &project.project.slug,
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let refs = ["BATCH-2", "BATCH-9", "BATCH-1", "BATCH-2", "OTHER-1"]
+            .map(ToOwned::to_owned)
+            .to_vec();
+        let (found, missing) = queries::get_tasks_by_refs(&pool, "BATCH", &refs)
+            .await
+            .expect("batch get should succeed");
+
+        let titles = found
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["two", "one"]);
+        assert_eq!(missing, vec!["BATCH-9", "OTHER-1"]);
+    }
+
+    #[tokio::test]
+    async fn bulk_subtask_update_reorders_and_toggles_in_one_entry() {
+        let (_temp_dir, pool) = setup_db("subtask-bulk-test").await;
+        let project = queries::create_project_with_slug(&pool, "checklist", "goal", "CHECK")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "checklist".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let mut ids = Vec::new();
+        for title in ["first", "second", "third"] {
+            let subtask = queries::add_subtask(
+                &pool,
+                &FieldLimitConfig::default(),
+                "CHECK",
+                &task.id,
+                title,
+                "human",
+            )
+            .await
+            .expect("subtask should be created");
+            ids.push(subtask.id);
+        }
+
+        let subtasks = queries::bulk_update_subtasks(
+            &pool,
+            "CHECK",
+            &task.id,
+            vec![
+                queries::SubtaskBulkItem {
+                    id: ids[2].clone(),
+                    done: Some(true),
+                },
+                queries::SubtaskBulkItem {
+                    id: ids[0].clone(),
+                    done: None,
+                },
+            ],
+            "human",
+        )
+        .await
+        .expect("bulk update should succeed");
+
+        let order = subtasks
+            .iter()
+            .map(|subtask| (subtask.title.as_str(), subtask.done))
+            .collect::<Vec<_>>();
+        assert_eq!(order, vec![("third", 1), ("first", 0), ("second", 0)]);
+
+        let page = queries::list_task_history(
+            &pool,
+            "CHECK",
+            &task.id,
+            queries::TaskHistoryFilters {
+                action: Some("subtasks.updated".to_string()),
+                actor: None,
+            },
+            10,
+            None,
+        )
+        .await
+        .expect("history should load");
+        assert_eq!(page.entries.len(), 1);
+
+        let unknown = queries::bulk_update_subtasks(
+            &pool,
+            "CHECK",
+            &task.id,
+            vec![queries::SubtaskBulkItem {
+                id: "missing".to_string(),
+                done: None,
+            }],
+            "human",
+        )
+        .await;
+        assert!(unknown.is_err());
+    }
+
+    #[tokio::test]
+    async fn review_requests_record_verdicts_once() {
+        let (_temp_dir, pool) = setup_db("review-test").await;
+        let project = queries::create_project_with_slug(&pool, "review", "goal", "REVIEW")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "needs eyes".to_string(),
+                description: String::new(),
+                status: "review".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "agent".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let review = queries::request_review(&pool, "REVIEW", "REVIEW-1", "alice", "agent")
+            .await
+            .expect("review request should succeed");
+        assert_eq!(review.status, "pending");
+
+        let duplicate =
+            queries::request_review(&pool, "REVIEW", "REVIEW-1", "alice", "agent").await;
+        assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+
+        let invalid = queries::complete_review(
+            &pool, "REVIEW", "REVIEW-1", &review.id, "maybe", None, "alice",
+        )
+        .await;
+        assert!(matches!(invalid, Err(AppError::BadRequest(_))));
+
+        let completed = queries::complete_review(
+            &pool,
+            "REVIEW",
+            "REVIEW-1",
+            &review.id,
+            "changes_requested",
+            Some("  split the migration  ".to_string()),
+            "alice",
+        )
+        .await
+        .expect("verdict should be recorded");
+        assert_eq!(completed.status, "changes_requested");
+        assert_eq!(completed.comment.as_deref(), Some("split the migration"));
+        assert_eq!(completed.completed_by.as_deref(), Some("alice"));
+
+        let again = queries::complete_review(
+            &pool, "REVIEW", "REVIEW-1", &review.id, "approved", None, "alice",
+        )
+        .await;
+        assert!(matches!(again, Err(AppError::Conflict(_))));
+
+        let details = queries::get_task_details(&pool, "REVIEW", &task.id)
+            .await
+            .expect("details should load");
+        assert_eq!(details.reviews.len(), 1);
+        let actions = details
+            .history
+            .iter()
+            .map(|entry| entry.action.as_str())
+            .collect::<Vec<_>>();
+        assert!(actions.contains(&"review.requested"));
+        assert!(actions.contains(&"review.completed"));
+    }
+
+    #[tokio::test]
+    async fn review_checklist_is_instantiated_and_gates_done() {
+        let (_temp_dir, pool) = setup_db("review-checklist-test").await;
+        let project = queries::create_project_with_slug(&pool, "gate", "goal", "GATE")
+            .await
+            .expect("project should be created");
+        queries::replace_review_checklist_template(
+            &pool,
+            "GATE",
+            vec!["Tests pass".to_string(), "Docs updated".to_string()],
+        )
+        .await
+        .expect("template should be saved");
+
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "ship it".to_string(),
+                description: String::new(),
+                status: "in_progress".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let move_to = |status: &str| queries::MoveTaskInput {
+            status: status.to_string(),
+            sort_order: None,
+            actor: "human".to_string(),
+            mcp_origin: false,
+        };
+        queries::move_task(&pool, "GATE", &task.id, move_to("review"))
+            .await
+            .expect("move to review should succeed");
+
+        let items = queries::list_task_review_items(&pool, "GATE", &task.id)
+            .await
+            .expect("items should load");
+        let titles = items
+            .iter()
+            .map(|item| item.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Tests pass", "Docs updated"]);
+
+        let blocked = queries::move_task(&pool, "GATE", &task.id, move_to("done")).await;
+        assert!(matches!(blocked, Err(AppError::Conflict(_))));
+
+        queries::set_review_item_done(&pool, "GATE", &task.id, &items[0].id, true, "human")
+            .await
+            .expect("item should be checked");
+        queries::update_project_settings(
+            &pool,
+            "GATE",
+            queries::UpdateProjectSettingsInput {
+                review_checklist_required: Some(false),
+                ..queries::UpdateProjectSettingsInput::default()
+            },
+        )
+        .await
+        .expect("settings should update");
+
+        let done = queries::move_task(&pool, "GATE", &task.id, move_to("done"))
+            .await
+            .expect("move to done should succeed once gating is off");
+        assert_eq!(done.status, "done");
+    }
+
+    #[tokio::test]
+    async fn review_gate_policy_is_configurable_per_project() {
+        let (_temp_dir, pool) = setup_db("review-gate-test").await;
+        let project = queries::create_project_with_slug(&pool, "policy", "goal", "POLICY")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "draft".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "not_ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let move_to = |status: &str, mcp_origin: bool| queries::MoveTaskInput {
+            status: status.to_string(),
+            sort_order: None,
+            actor: "human".to_string(),
+            mcp_origin,
+        };
+        let set_policy = |gate: &str, scope: &str| queries::UpdateProjectSettingsInput {
+            review_gate: Some(gate.to_string()),
+            review_gate_scope: Some(scope.to_string()),
+            ..queries::UpdateProjectSettingsInput::default()
+        };
+
+        let agent = queries::move_task(&pool, "POLICY", &task.id, move_to("ready", true)).await;
+        assert!(agent.is_err(), "default policy blocks agents");
+        queries::move_task(&pool, "POLICY", &task.id, move_to("ready", false))
+            .await
+            .expect("default policy lets humans move");
+
+        queries::update_project_settings(&pool, "POLICY", set_policy("everyone", "all_moves"))
+            .await
+            .expect("policy should update");
+        let human = queries::move_task(&pool, "POLICY", &task.id, move_to("backlog", false)).await;
+        assert!(human.is_err(), "everyone policy blocks humans");
+
+        queries::update_project_settings(
+            &pool,
+            "POLICY",
+            set_policy("everyone", "start_and_finish"),
+        )
+        .await
+        .expect("policy should update");
+        queries::move_task(&pool, "POLICY", &task.id, move_to("review", false))
+            .await
+            .expect("moves outside in_progress/done are allowed");
+        let start =
+            queries::move_task(&pool, "POLICY", &task.id, move_to("in_progress", false)).await;
+        assert!(start.is_err(), "moves into in_progress stay gated");
+
+        queries::update_project_settings(&pool, "POLICY", set_policy("off", "all_moves"))
+            .await
+            .expect("policy should update");
+        queries::move_task(&pool, "POLICY", &task.id, move_to("in_progress", true))
+            .await
+            .expect("gate off allows agent moves");
+
+        let invalid =
+            queries::update_project_settings(&pool, "POLICY", set_policy("sometimes", "all_moves"))
+                .await;
+        assert!(invalid.is_err());
+    }
+
+    #[tokio::test]
+    async fn reorder_places_listed_tasks_first_and_moves_others_in() {
+        let (_temp_dir, pool) = setup_db("reorder-test").await;
+        let project = queries::create_project_with_slug(&pool, "reorder", "goal", "REORDER")
+            .await
+            .expect("project should be created");
+
+        let mut keys = Vec::new();
+        for (title, status) in [
+            ("a", "ready"),
+            ("b", "ready"),
+            ("c", "ready"),
+            ("d", "backlog"),
+        ] {
+            let task = queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                &project.project.slug,
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+            keys.push(queries::display_key(
+                &project.project.slug,
+                task.task_number,
+            ));
+        }
+
+        let column = queries::reorder_tasks(
+            &pool,
+            &project.project.slug,
+            queries::ReorderTasksInput {
+                status: "ready".to_string(),
+                task_refs: vec![keys[3].clone(), keys[2].clone()],
+                actor: "human".to_string(),
+                mcp_origin: false,
+            },
+        )
+        .await
+        .expect("reorder should succeed");
+
+        let titles = column
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["d", "c", "a", "b"]);
+
+        let duplicate = queries::reorder_tasks(
+            &pool,
+            &project.project.slug,
+            queries::ReorderTasksInput {
+                status: "ready".to_string(),
+                task_refs: vec![keys[0].clone(), keys[0].clone()],
+                actor: "human".to_string(),
+                mcp_origin: false,
+            },
+        )
+        .await;
+        assert!(duplicate.is_err());
+    }
+
+    #[tokio::test]
+    async fn task_history_pages_with_cursor_and_filters() {
+        let (_temp_dir, pool) = setup_db("task-history-page-test").await;
+        let project = queries::create_project_with_slug(&pool, "history", "goal", "HIST")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            &project.project.slug,
+            queries::NewTaskInput {
+                title: "paged".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        for status in ["ready", "in_progress", "review"] {
+            queries::move_task(
+                &pool,
+                &project.project.slug,
+                &task.id,
+                queries::MoveTaskInput {
+                    status: status.to_string(),
+                    sort_order: None,
+                    actor: "mover-bot".to_string(),
+                    mcp_origin: false,
+                },
+            )
+            .await
+            .expect("task should move");
+        }
+
+        let first = queries::list_task_history(
+            &pool,
+            &project.project.slug,
+            &task.id,
+            queries::TaskHistoryFilters::default(),
+            2,
+            None,
+        )
+        .await
+        .expect("first page should load");
+        assert_eq!(first.entries.len(), 2);
+        let cursor = first.next_cursor.expect("first page should have a cursor");
+
+        let second = queries::list_task_history(
+            &pool,
+            &project.project.slug,
+            &task.id,
+            queries::TaskHistoryFilters::default(),
+            2,
+            Some(&cursor),
+        )
+        .await
+        .expect("second page should load");
+        assert_eq!(second.entries.len(), 2);
+        assert!(second.next_cursor.is_none());
+        let mut seen = first
+            .entries
+            .iter()
+            .chain(second.entries.iter())
+            .map(|entry| entry.id.clone())
+            .collect::<Vec<_>>();
+        seen.sort_unstable();
+        seen.dedup();
+        assert_eq!(seen.len(), 4);
+
+        let moves = queries::list_task_history(
+            &pool,
+            &project.project.slug,
+            &task.id,
+            queries::TaskHistoryFilters {
+                action: Some("task.moved".to_string()),
+                actor: Some("mover-bot".to_string()),
+            },
+            50,
+            None,
+        )
+        .await
+        .expect("filtered history should load");
+        assert_eq!(moves.entries.len(), 3);
+        assert!(moves
+            .entries
+            .iter()
+            .all(|entry| entry.action == "task.moved"));
+    }
+
+    #[tokio::test]
+    async fn tasks_are_assigned_filtered_and_unassigned() {
+        let (_temp_dir, pool) = setup_db("assignee-test").await;
+        queries::create_project_with_slug(&pool, "assign", "goal", "ASSIGN")
+            .await
+            .expect("project should be created");
+        for (title, assignee) in [("api", Some(" ana ")), ("ui", Some("bot")), ("docs", None)] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "ASSIGN",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: assignee.map(ToOwned::to_owned),
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let assigned_to = |assignees: &[&str]| {
+            let pool = pool.clone();
+            let filters = queries::TaskFilters {
+                assignees: assignees.iter().map(|value| value.to_string()).collect(),
+                ..queries::TaskFilters::default()
+            };
+            async move {
+                queries::list_tasks(&pool, "ASSIGN", filters, 50, 0)
+                    .await
+                    .expect("tasks should list")
+                    .into_iter()
+                    .map(|task| task.title)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(assigned_to(&["ana"]).await, ["api"]);
+        assert_eq!(assigned_to(&["ana", "none"]).await, ["api", "docs"]);
+
+        let ana = queries::list_notifications(&pool, "ana", None, true, 50, 0)
+            .await
+            .expect("ana inbox should load");
+        assert_eq!(ana.len(), 1);
+        assert_eq!(ana[0].reason, "assigned");
+
+        let update = |task_ref: &'static str, assignee: &str| {
+            let pool = pool.clone();
+            let input = queries::UpdateTaskInput {
+                title: None,
+                description: None,
+                status: None,
+                priority: None,
+                review_state: None,
+                labels: None,
+                assignee: Some(assignee.to_string()),
+                due_at: None,
+                actor: "human".to_string(),
+            };
+            async move {
+                queries::update_task(
+                    &pool,
+                    &FieldLimitConfig::default(),
+                    "ASSIGN",
+                    task_ref,
+                    input,
+                )
+                .await
+            }
+        };
+        let task = update("ASSIGN-1", "")
+            .await
+            .expect("unassign should succeed");
+        assert_eq!(task.assignee, None);
+        assert!(matches!(
+            update("ASSIGN-2", "none").await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert_eq!(assigned_to(&["none"]).await, ["api", "docs"]);
+
+        let history = queries::get_task_details(&pool, "ASSIGN", "ASSIGN-1")
+            .await
+            .expect("task should load")
+            .history;
+        assert!(history.iter().any(
+            |entry| entry.action == "task.assigned" && entry.detail.contains(r#""from":"ana""#)
+        ));
+
+        let task = queries::undo_last_task_change(&pool, "ASSIGN", "ASSIGN-1", "human")
+            .await
+            .expect("undo should succeed");
+        assert_eq!(task.assignee.as_deref(), Some("ana"));
+    }
+
+    #[tokio::test]
+    async fn due_dates_are_normalized_and_filter_overdue_tasks() {
+        let (_temp_dir, pool) = setup_db("due-dates-test").await;
+        queries::create_project_with_slug(&pool, "due", "goal", "DUE")
+            .await
+            .expect("project should be created");
+        for (title, status, due_at) in [
+            ("late", "in_progress", Some("2020-01-01T12:00:00+02:00")),
+            ("shipped", "done", Some("2020-01-02T00:00:00Z")),
+            ("later", "ready", Some("2999-01-01T00:00:00Z")),
+            ("whenever", "ready", None),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "DUE",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: due_at.map(ToOwned::to_owned),
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let titles = |filters: queries::TaskFilters| {
+            let pool = pool.clone();
+            async move {
+                let mut titles = queries::list_tasks(&pool, "DUE", filters, 50, 0)
+                    .await
+                    .expect("tasks should list")
+                    .into_iter()
+                    .map(|task| task.title)
+                    .collect::<Vec<_>>();
+                titles.sort();
+                titles
+            }
+        };
+        let overdue = |overdue: bool| queries::TaskFilters {
+            overdue: Some(overdue),
+            ..queries::TaskFilters::default()
+        };
+        assert_eq!(titles(overdue(true)).await, ["late"]);
+        assert_eq!(
+            titles(overdue(false)).await,
+            ["later", "shipped", "whenever"]
+        );
+        let due_before = queries::TaskFilters {
+            due_before: Some("2021-01-01".to_string()),
+            ..queries::TaskFilters::default()
+        };
+        assert_eq!(titles(due_before).await, ["late", "shipped"]);
+
+        let update = |due_at: &str| {
+            let pool = pool.clone();
+            let input = queries::UpdateTaskInput {
+                title: None,
+                description: None,
+                status: None,
+                priority: None,
+                review_state: None,
+                labels: None,
+                assignee: None,
+                due_at: Some(due_at.to_string()),
+                actor: "human".to_string(),
+            };
+            async move {
+                queries::update_task(&pool, &FieldLimitConfig::default(), "DUE", "DUE-1", input)
+                    .await
+            }
+        };
+        assert!(matches!(
+            update("next friday").await,
+            Err(AppError::BadRequest(_))
+        ));
+        let task = update("").await.expect("clearing should succeed");
+        assert_eq!(task.due_at, None);
+
+        let history = queries::get_task_details(&pool, "DUE", "DUE-1")
+            .await
+            .expect("task should load")
+            .history;
+        let changes: Vec<&str> = history
+            .iter()
+            .filter(|entry| entry.action == "task.due_date_changed")
+            .map(|entry| entry.detail.as_str())
+            .collect();
+        assert_eq!(changes.len(), 2);
+        assert!(changes
+            .iter()
+            .any(|detail| detail.contains(r#""from_due_at":"2020-01-01T10:00:00.000Z""#)));
+    }
+
+    #[tokio::test]
+    async fn timestamps_have_milliseconds_and_projects_have_a_timezone() {
+        let (_temp_dir, pool) = setup_db("timezone-test").await;
+        let project = queries::create_project_with_slug(&pool, "tz", "goal", "TZ")
+            .await
+            .expect("project should be created");
+        assert_eq!(
+            project.project.created_at.len(),
+            "2026-01-01T00:00:00.000Z".len()
+        );
+        assert_eq!(
+            queries::project_timezone(&pool, "TZ")
+                .await
+                .expect("timezone should load"),
+            chrono_tz::UTC
+        );
+
+        let set_timezone = |timezone: &str| {
+            let pool = pool.clone();
+            let input = queries::UpdateProjectSettingsInput {
+                timezone: Some(timezone.to_string()),
+                ..Default::default()
+            };
+            async move { queries::update_project_settings(&pool, "TZ", input).await }
+        };
+        assert!(matches!(
+            set_timezone("Mars/Olympus").await,
+            Err(AppError::BadRequest(_))
+        ));
+        let settings = set_timezone(" America/New_York ")
+            .await
+            .expect("settings should update");
+        assert_eq!(settings.timezone, "America/New_York");
+        let timezone = queries::project_timezone(&pool, "TZ")
+            .await
+            .expect("timezone should load");
+        assert_eq!(
+            queries::localize_timestamp("2026-01-15T17:30:00.000Z", timezone),
+            "2026-01-15T12:30:00.000-05:00"
+        );
+        assert_eq!(queries::localize_timestamp("soon", timezone), "soon");
+
+        // Second-precision values from older versions are rewritten by migration 0043.
+        sqlx::query("UPDATE projects SET created_at = ?, updated_at = ? WHERE slug = 'TZ'")
+            .bind("2026-01-01T00:00:05Z")
+            .bind("not a timestamp")
+            .execute(&pool)
+            .await
+            .expect("legacy timestamp should be written");
+        sqlx::raw_sql(include_str!("migrations/0043_millisecond_timestamps.sql"))
+            .execute(&pool)
+            .await
+            .expect("migration should rerun");
+        let (created_at, updated_at) = sqlx::query_as::<Any, (String, String)>(
+            "SELECT created_at, updated_at FROM projects WHERE slug = 'TZ'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("project should load");
+        assert_eq!(created_at, "2026-01-01T00:00:05.000Z");
+        assert!(created_at.as_str() < "2026-01-01T00:00:05.123Z");
+        assert_eq!(updated_at, "not a timestamp");
+    }
+
+    #[tokio::test]
+    async fn dependencies_reject_cycles_and_can_gate_done() {
+        let (_temp_dir, pool) = setup_db("dependencies-test").await;
+        queries::create_project_with_slug(&pool, "deps", "goal", "DEPS")
+            .await
+            .expect("project should be created");
+        for title in ["schema", "api", "ui"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "DEPS",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let add = |task: &'static str, blocker: &'static str| {
+            let pool = pool.clone();
+            async move { queries::add_task_dependency(&pool, "DEPS", task, blocker, "human").await }
+        };
+        add("DEPS-2", "DEPS-1").await.expect("link should be added");
+        let api = add("DEPS-3", "DEPS-2").await.expect("link should be added");
+        assert_eq!(api.blocked_by[0].display_key, "DEPS-2");
+        add("DEPS-3", "DEPS-2")
+            .await
+            .expect("adding a link twice is a no-op");
+
+        assert!(matches!(
+            add("DEPS-1", "DEPS-3").await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            add("DEPS-1", "DEPS-1").await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let details = queries::get_task_details(&pool, "DEPS", "DEPS-2")
+            .await
+            .expect("task should load");
+        assert_eq!(details.dependencies.blocked_by[0].display_key, "DEPS-1");
+        assert_eq!(details.dependencies.blocks[0].display_key, "DEPS-3");
+        assert_eq!(
+            details
+                .history
+                .iter()
+                .filter(|entry| entry.action == "task.dependency_added")
+                .count(),
+            1
+        );
+
+        let move_to = |task: &'static str, status: &'static str| {
+            let pool = pool.clone();
+            let input = queries::MoveTaskInput {
+                status: status.to_string(),
+                sort_order: None,
+                actor: "human".to_string(),
+                mcp_origin: false,
+            };
+            async move { queries::move_task(&pool, "DEPS", task, input).await }
+        };
+        move_to("DEPS-2", "done")
+            .await
+            .expect("dependencies are advisory by default");
+        move_to("DEPS-2", "ready").await.expect("task should move");
+
+        queries::update_project_settings(
+            &pool,
+            "DEPS",
+            queries::UpdateProjectSettingsInput {
+                enforce_dependencies: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("settings should update");
+        let blocked = move_to("DEPS-2", "done").await;
+        assert!(
+            matches!(&blocked, Err(AppError::Conflict(message)) if message.contains("DEPS-1")),
+            "{blocked:?}"
+        );
+        move_to("DEPS-1", "done")
+            .await
+            .expect("blocker should move");
+        move_to("DEPS-2", "done")
+            .await
+            .expect("a done blocker no longer blocks");
+
+        let removed = queries::remove_task_dependency(&pool, "DEPS", "DEPS-3", "DEPS-2", "human")
+            .await
+            .expect("link should be removed");
+        assert!(removed.blocked_by.is_empty());
+        add("DEPS-1", "DEPS-3")
+            .await
+            .expect("without the link there is no cycle");
+    }
+
+    #[tokio::test]
+    async fn milestones_track_progress_of_attached_tasks() {
+        let (_temp_dir, pool) = setup_db("milestones-test").await;
+        queries::create_project_with_slug(&pool, "release", "goal", "REL")
+            .await
+            .expect("project should be created");
+        for (title, status) in [("docs", "done"), ("api", "ready"), ("ui", "ready")] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "REL",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let create = |name: &'static str, target_date: Option<&'static str>| {
+            let pool = pool.clone();
+            let input = queries::CreateMilestoneInput {
+                name: name.to_string(),
+                target_date: target_date.map(str::to_string),
+            };
+            async move { queries::create_milestone(&pool, "REL", input).await }
+        };
+        let beta = create("Beta", Some("2026-09-01"))
+            .await
+            .expect("milestone should be created");
+        let alpha = create(" Alpha ", Some("2026-06-01"))
+            .await
+            .expect("milestone should be created");
+        assert_eq!(alpha.name, "Alpha");
+        assert_eq!(alpha.status, "open");
+        assert!(matches!(
+            create("Beta", None).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            create("Gamma", Some("June")).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        for task in ["REL-1", "REL-2"] {
+            let task = queries::set_task_milestone(&pool, "REL", task, Some(&alpha.id), "human")
+                .await
+                .expect("milestone should be set");
+            assert_eq!(task.milestone_id.as_deref(), Some(alpha.id.as_str()));
+        }
+        queries::set_task_milestone(&pool, "REL", "REL-3", Some(&beta.id), "human")
+            .await
+            .expect("milestone should be set");
+
+        let milestones = queries::list_milestones(&pool, "REL")
+            .await
+            .expect("milestones should list");
+        assert_eq!(
+            milestones
+                .iter()
+                .map(|milestone| (
+                    milestone.name.as_str(),
+                    milestone.total_tasks,
+                    milestone.done_tasks
+                ))
+                .collect::<Vec<_>>(),
+            [("Alpha", 2, 1), ("Beta", 1, 0)]
+        );
+
+        let closed = queries::update_milestone(
+            &pool,
+            "REL",
+            &alpha.id,
+            queries::UpdateMilestoneInput {
+                status: Some("closed".to_string()),
+                target_date: Some(String::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("milestone should update");
+        assert_eq!(closed.status, "closed");
+        assert_eq!(closed.target_date, None);
+        assert_eq!(closed.total_tasks, 2);
+
+        queries::delete_milestone(&pool, "REL", &beta.id)
+            .await
+            .expect("milestone should be deleted");
+        let details = queries::get_task_details(&pool, "REL", "REL-3")
+            .await
+            .expect("task should load");
+        assert_eq!(details.task.milestone_id, None);
+        assert!(details
+            .history
+            .iter()
+            .any(|entry| entry.action == "task.milestone_changed"));
+    }
+
+    #[tokio::test]
+    async fn parents_roll_up_children_and_filter_lists() {
+        let (_temp_dir, pool) = setup_db("parents-test").await;
+        queries::create_project_with_slug(&pool, "epics", "goal", "EPIC")
+            .await
+            .expect("project should be created");
+        for (title, status) in [("epic", "ready"), ("login", "done"), ("signup", "ready")] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "EPIC",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let epic = queries::get_task_details(&pool, "EPIC", "EPIC-1")
+            .await
+            .expect("task should load")
+            .task;
+        for child in ["EPIC-2", "EPIC-3"] {
+            let task = queries::set_task_parent(&pool, "EPIC", child, Some("EPIC-1"), "human")
+                .await
+                .expect("parent should be set");
+            assert_eq!(task.parent_task_id.as_deref(), Some(epic.id.as_str()));
+        }
+        assert!(matches!(
+            queries::set_task_parent(&pool, "EPIC", "EPIC-1", Some("EPIC-2"), "human").await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            queries::set_task_parent(&pool, "EPIC", "EPIC-1", Some("EPIC-1"), "human").await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        let details = queries::get_task_details(&pool, "EPIC", "EPIC-1")
+            .await
+            .expect("task should load");
+        assert_eq!(details.children.total, 2);
+        assert_eq!(details.children.done, 1);
+        assert_eq!(details.children.tasks[0].display_key, "EPIC-2");
+
+        let list = |parent: &'static str| {
+            let pool = pool.clone();
+            let filters = queries::TaskFilters {
+                parent: Some(parent.to_string()),
+                ..Default::default()
+            };
+            async move { queries::list_tasks(&pool, "EPIC", filters, 50, 0).await }
+        };
+        let children = list("EPIC-1").await.expect("tasks should list");
+        assert_eq!(children.len(), 2);
+        let top_level = list("none").await.expect("tasks should list");
+        assert_eq!(top_level.len(), 1);
+        assert_eq!(top_level[0].task_number, 1);
+
+        let detached = queries::set_task_parent(&pool, "EPIC", "EPIC-3", None, "human")
+            .await
+            .expect("parent should be cleared");
+        assert!(detached.parent_task_id.is_none());
+        let history = queries::get_task_details(&pool, "EPIC", "EPIC-3")
+            .await
+            .expect("task should load")
+            .history;
+        assert_eq!(
+            history
+                .iter()
+                .filter(|entry| entry.action == "task.parent_changed")
+                .count(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn notifications_fan_out_to_watchers_and_involved_actors() {
+        let (_temp_dir, pool) = setup_db("notifications-test").await;
+        queries::create_project_with_slug(&pool, "inbox", "goal", "INBOX")
+            .await
+            .expect("project should be created");
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "INBOX",
+            queries::NewTaskInput {
+                title: "ping me".to_string(),
+                description: String::new(),
+                status: "in_progress".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let watchers = queries::watch_task(&pool, "INBOX", "INBOX-1", "bob")
+            .await
+            .expect("watch should succeed");
+        assert_eq!(watchers, vec!["bob".to_string()]);
+        queries::watch_task(&pool, "INBOX", "INBOX-1", "agent")
+            .await
+            .expect("repeat watch should be a no-op");
+
+        queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "INBOX",
+            "INBOX-1",
+            "which db?",
+            "",
+            "agent",
+        )
+        .await
+        .expect("question should be created");
+
+        let human = queries::list_notifications(&pool, "human", None, true, 50, 0)
+            .await
+            .expect("human inbox should load");
+        assert_eq!(human.len(), 1);
+        assert_eq!(human[0].reason, "question");
+        assert_eq!(human[0].task_number, Some(1));
+        let bob = queries::list_notifications(&pool, "bob", None, true, 50, 0)
+            .await
+            .expect("bob inbox should load");
+        assert_eq!(bob.len(), 1);
+        assert_eq!(bob[0].reason, "watching");
+        let own = queries::count_notifications(&pool, "agent", None, false)
+            .await
+            .expect("agent count should load");
+        assert_eq!(own, 0, "actors are not notified about their own changes");
+
+        let review = queries::request_review(&pool, "INBOX", "INBOX-1", "alice", "agent")
+            .await
+            .expect("review request should succeed");
+        queries::complete_review(
+            &pool, "INBOX", "INBOX-1", &review.id, "approved", None, "alice",
+        )
+        .await
+        .expect("verdict should be recorded");
+
+        let alice = queries::list_notifications(&pool, "alice", None, false, 50, 0)
+            .await
+            .expect("alice inbox should load");
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].reason, "review_requested");
+        let agent = queries::list_notifications(&pool, "agent", None, false, 50, 0)
+            .await
+            .expect("agent inbox should load");
+        assert_eq!(agent.len(), 1);
+        assert_eq!(agent[0].reason, "review_completed");
+
+        let read = queries::mark_notification_read(&pool, "human", None, &human[0].id)
+            .await
+            .expect("mark read should succeed");
+        assert!(read.read_at.is_some());
+        let foreign = queries::mark_notification_read(&pool, "bob", None, &human[0].id).await;
+        assert!(matches!(foreign, Err(AppError::NotFound(_))));
+
+        assert_eq!(
+            queries::count_notifications(&pool, "bob", None, true)
+                .await
+                .expect("bob count should load"),
+            3
+        );
+        let marked = queries::mark_all_notifications_read(&pool, "bob", None)
+            .await
+            .expect("mark all should succeed");
+        assert_eq!(marked, 3);
+        assert_eq!(
+            queries::count_notifications(&pool, "bob", None, true)
+                .await
+                .expect("bob count should load"),
+            0
+        );
+
+        let remaining = queries::unwatch_task(&pool, "INBOX", "INBOX-1", "bob")
+            .await
+            .expect("unwatch should succeed");
+        assert_eq!(remaining, vec!["agent".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn digest_schedules_validate_and_summaries_cover_window() {
+        let (_temp_dir, pool) = setup_db("digest-test").await;
+        queries::create_project_with_slug(&pool, "digest", "goal", "DIGEST")
+            .await
+            .expect("project should be created");
+
+        let missing = queries::get_digest_schedule(&pool, "DIGEST").await;
+        assert!(matches!(missing, Err(AppError::NotFound(_))));
+        for (frequency, hour, weekday) in [
+            ("hourly", 9, None),
+            ("daily", 24, None),
+            ("weekly", 9, Some(0)),
+        ] {
+            let invalid = queries::set_digest_schedule(
+                &pool,
+                "DIGEST",
+                queries::DigestScheduleInput {
+                    frequency: frequency.to_string(),
+                    hour,
+                    weekday,
+                },
+            )
+            .await;
+            assert!(matches!(invalid, Err(AppError::BadRequest(_))));
+        }
+
+        let schedule = queries::set_digest_schedule(
+            &pool,
+            "DIGEST",
+            queries::DigestScheduleInput {
+                frequency: "weekly".to_string(),
+                hour: 9,
+                weekday: Some(5),
+            },
+        )
+        .await
+        .expect("schedule should be saved");
+        assert_eq!(schedule.weekday, 5);
+        assert_eq!(
+            queries::list_digest_schedules(&pool)
+                .await
+                .expect("schedules should list")
+                .len(),
+            1
+        );
+
+        for (title, status) in [("shipped", "in_progress"), ("pending", "backlog")] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "DIGEST",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+        queries::move_task(
+            &pool,
+            "DIGEST",
+            "DIGEST-1",
+            queries::MoveTaskInput {
+                status: "done".to_string(),
+                sort_order: None,
+                actor: "human".to_string(),
+                mcp_origin: false,
+            },
+        )
+        .await
+        .expect("move should succeed");
+        queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "DIGEST",
+            "DIGEST-2",
+            "scope?",
+            "",
+            "agent",
+        )
+        .await
+        .expect("question should be created");
+
+        let since = queries::timestamp_days_ago(1);
+        let until = (chrono::Utc::now() + chrono::Duration::seconds(5))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let digest = queries::build_project_digest(&pool, "DIGEST", &since, &until, None)
+            .await
+            .expect("digest should build");
+        assert_eq!(digest.project_slug, "DIGEST");
+        assert_eq!(digest.completed.len(), 1);
+        assert_eq!(digest.completed[0].title, "shipped");
+        assert_eq!(digest.new_questions.len(), 1);
+        assert!(digest.stalled.is_empty());
+
+        let empty = queries::build_project_digest(&pool, "DIGEST", &until, &until, None)
+            .await
+            .expect("empty window should build");
+        assert!(empty.completed.is_empty() && empty.new_questions.is_empty());
+
+        queries::delete_digest_schedule(&pool, "DIGEST")
+            .await
+            .expect("schedule should be deleted");
+        let again = queries::delete_digest_schedule(&pool, "DIGEST").await;
+        assert!(matches!(again, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn sla_breaches_escalate_once_per_stay() {
+        let (_temp_dir, pool) = setup_db("sla-test").await;
+        queries::create_project_with_slug(&pool, "sla", "goal", "SLA")
+            .await
+            .expect("project should be created");
+        let policy_input = |status: &str, max_hours: i64| queries::CreateSlaPolicyInput {
+            name: "critical pickup".to_string(),
+            status: status.to_string(),
+            priority: Some("high".to_string()),
+            max_hours,
+            escalate_priority: true,
+            webhook_id: None,
+        };
+
+        for (status, max_hours) in [("done", 24), ("ready", 0), ("nowhere", 24)] {
+            let invalid =
+                queries::create_sla_policy(&pool, "SLA", policy_input(status, max_hours)).await;
+            assert!(matches!(invalid, Err(AppError::BadRequest(_))));
+        }
+        let policy = queries::create_sla_policy(&pool, "SLA", policy_input("ready", 24))
+            .await
+            .expect("policy should be created");
+        let policy = queries::list_all_sla_policies(&pool)
+            .await
+            .expect("policies should list")
+            .into_iter()
+            .find(|candidate| candidate.id == policy.id)
+            .expect("policy should be listed");
+        assert_eq!(policy.project_slug, "SLA");
+
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "SLA",
+            queries::NewTaskInput {
+                title: "urgent".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "high".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        let cutoff = queries::timestamp_days_ago(1);
+        assert!(queries::list_sla_breach_candidates(&pool, &policy, &cutoff)
+            .await
+            .expect("candidates should load")
+            .is_empty());
+
+        let age_task = |days: u32| {
+            sqlx::query("UPDATE tasks SET status_changed_at = ? WHERE id = ?")
+                .bind(queries::timestamp_days_ago(days))
+                .bind(task.id.clone())
+                .execute(&pool)
+        };
+        age_task(2).await.expect("task should be aged");
+        let candidates = queries::list_sla_breach_candidates(&pool, &policy, &cutoff)
+            .await
+            .expect("candidates should load");
+        assert_eq!(candidates.len(), 1);
+
+        let (escalated, detail) = queries::record_sla_breach(&pool, &policy, &candidates[0])
+            .await
+            .expect("breach should be recorded");
+        assert_eq!(escalated.priority, "critical");
+        assert_eq!(detail["priority"]["from"], "high");
+        assert!(queries::list_sla_breach_candidates(&pool, &policy, &cutoff)
+            .await
+            .expect("candidates should load")
+            .is_empty());
+
+        queries::update_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "SLA",
+            "SLA-1",
+            queries::UpdateTaskInput {
+                title: None,
+                description: None,
+                status: None,
+                priority: Some("high".to_string()),
+                review_state: None,
+                labels: None,
+                assignee: None,
+                due_at: None,
+                actor: "human".to_string(),
+            },
+        )
+        .await
+        .expect("priority should reset");
+        let mut priority_changes = sqlx::query_scalar::<sqlx::Any, String>(
+            "SELECT detail FROM task_history WHERE task_id = ? AND action = 'task.priority_changed'",
+        )
+        .bind(&task.id)
+        .fetch_all(&pool)
+        .await
+        .expect("history should load")
+        .into_iter()
+        .map(|detail| {
+            let detail: Value = serde_json::from_str(&detail).expect("detail should parse");
+            (detail["from_priority"].clone(), detail["to_priority"].clone())
+        })
+        .collect::<Vec<_>>();
+        priority_changes.sort_by_key(|(from, _)| from.to_string());
+        assert_eq!(
+            priority_changes,
+            vec![
+                (Value::from("critical"), Value::from("high")),
+                (Value::from("high"), Value::from("critical")),
+            ]
+        );
+        assert!(
+            queries::list_sla_breach_candidates(&pool, &policy, &cutoff)
+                .await
+                .expect("candidates should load")
+                .is_empty(),
+            "the same stay in ready does not breach twice"
+        );
+
+        for status in ["in_progress", "ready"] {
+            queries::move_task(
+                &pool,
+                "SLA",
+                "SLA-1",
+                queries::MoveTaskInput {
+                    status: status.to_string(),
+                    sort_order: None,
+                    actor: "human".to_string(),
+                    mcp_origin: false,
+                },
+            )
+            .await
+            .expect("move should succeed");
+        }
+        age_task(3).await.expect("task should be aged");
+        assert_eq!(
+            queries::list_sla_breach_candidates(&pool, &policy, &cutoff)
+                .await
+                .expect("candidates should load")
+                .len(),
+            1,
+            "re-entering ready starts a new stay"
+        );
+
+        let history = queries::list_task_history(
+            &pool,
+            "SLA",
+            "SLA-1",
+            queries::TaskHistoryFilters {
+                action: Some("sla.breached".to_string()),
+                actor: None,
+            },
+            10,
+            None,
+        )
+        .await
+        .expect("history should load");
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].actor, "system");
+    }
+
+    #[tokio::test]
+    async fn completion_queries_match_prefixes() {
+        let (_temp_dir, pool) = setup_db("completion-test").await;
+        for slug in ["ALPHA", "ALPINE", "BETA"] {
+            queries::create_project_with_slug(&pool, slug, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+        for index in 1..=12 {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "ALPHA",
+                queries::NewTaskInput {
+                    title: format!("task {index}"),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let slugs = queries::complete_project_slugs(&pool, None, "al", 10)
+            .await
+            .expect("slugs should complete");
+        assert_eq!(slugs, vec!["ALPHA", "ALPINE"]);
+        assert!(queries::complete_project_slugs(&pool, None, "%", 10)
+            .await
+            .expect("wildcards should be ignored")
+            .is_empty());
+
+        let refs = queries::complete_task_refs(&pool, "ALPHA", "1", 10)
+            .await
+            .expect("task refs should complete");
+        assert_eq!(refs, vec!["ALPHA-12", "ALPHA-11", "ALPHA-10", "ALPHA-1"]);
+
+        assert_eq!(
+            queries::complete_spec_sections("tech"),
+            vec!["technical_design"]
+        );
+    }
+
+    #[tokio::test]
+    async fn bulk_updates_apply_all_or_nothing() {
+        let (_temp_dir, pool) = setup_db("bulk-update-test").await;
+        queries::create_project_with_slug(&pool, "bulk", "goal", "BULK")
+            .await
+            .expect("project should be created");
+        for title in ["first", "second"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "BULK",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "backlog".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+        let change = |priority: &str, status: Option<&str>| queries::UpdateTaskInput {
+            title: None,
+            description: None,
+            status: status.map(ToOwned::to_owned),
+            priority: Some(priority.to_string()),
+            review_state: None,
+            labels: Some(vec!["triaged".to_string()]),
+            assignee: None,
+            due_at: None,
+            actor: "triage-bot".to_string(),
+        };
+
+        let rejected = queries::update_tasks_bulk(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BULK",
+            vec![
+                ("BULK-1".to_string(), change("high", Some("ready"))),
+                ("BULK-2".to_string(), change("urgent", None)),
+            ],
+        )
+        .await;
+        assert!(matches!(rejected, Err(AppError::BadRequest(_))));
+        let untouched = queries::get_task_details(&pool, "BULK", "BULK-1")
+            .await
+            .expect("task should load");
+        assert_eq!(untouched.task.priority, "medium");
+        assert!(untouched.labels.is_empty());
+
+        let duplicate = queries::update_tasks_bulk(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BULK",
+            vec![
+                ("BULK-1".to_string(), change("high", None)),
+                ("BULK-1".to_string(), change("low", None)),
+            ],
+        )
+        .await;
+        assert!(matches!(duplicate, Err(AppError::BadRequest(_))));
+
+        let updated = queries::update_tasks_bulk(
+            &pool,
+            &FieldLimitConfig::default(),
+            "BULK",
+            vec![
+                ("BULK-1".to_string(), change("high", Some("ready"))),
+                ("BULK-2".to_string(), change("critical", None)),
+            ],
+        )
+        .await
+        .expect("bulk update should succeed");
+        assert_eq!(
+            updated
+                .iter()
+                .map(|task| (task.status.as_str(), task.priority.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("ready", "high"), ("backlog", "critical")]
+        );
+        let details = queries::get_task_details(&pool, "BULK", "BULK-2")
+            .await
+            .expect("task should load");
+        assert_eq!(details.labels, vec!["triaged"]);
+        assert!(details
+            .history
+            .iter()
+            .any(|entry| entry.action == "task.updated" && entry.actor == "triage-bot"));
+    }
+
+    #[tokio::test]
+    async fn board_lists_columns_in_order_with_limits() {
+        let (_temp_dir, pool) = setup_db("board-test").await;
+        queries::create_project_with_slug(&pool, "board", "goal", "BOARD")
+            .await
+            .expect("project should be created");
+        for (title, status, labels) in [
+            ("one", "ready", vec!["api"]),
+            ("two", "ready", vec![]),
+            ("three", "ready", vec![]),
+            ("four", "done", vec!["ops", "api"]),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "BOARD",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: labels.into_iter().map(ToOwned::to_owned).collect(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let board = queries::get_board(&pool, "BOARD", 2)
+            .await
+            .expect("board should load");
+        assert_eq!(
+            board
+                .iter()
+                .map(|column| (column.status.as_str(), column.total, column.tasks.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("backlog", 0, 0),
+                ("ready", 3, 2),
+                ("in_progress", 0, 0),
+                ("review", 0, 0),
+                ("done", 1, 1),
+            ]
+        );
+        assert_eq!(board[1].tasks[0].display_key, "BOARD-1");
+        assert_eq!(board[1].tasks[0].labels, vec!["api"]);
+        assert_eq!(board[4].tasks[0].labels, vec!["api", "ops"]);
+    }
+
+    #[tokio::test]
+    async fn references_become_backlinks_and_mentions_notify() {
+        let (_temp_dir, pool) = setup_db("task_references").await;
+        queries::create_project_with_slug(&pool, "Refs", "goal", "REF")
+            .await
+            .expect("project should be created");
+        let new_task = |title: &str, description: &str| queries::NewTaskInput {
+            title: title.to_string(),
+            description: description.to_string(),
+            status: "backlog".to_string(),
+            priority: "medium".to_string(),
+            review_state: "ready".to_string(),
+            labels: Vec::new(),
+            created_by: "human".to_string(),
+            assignee: None,
+            due_at: None,
+        };
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "REF",
+            new_task("target", ""),
+        )
+        .await
+        .expect("target task should be created");
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "REF",
+            new_task(
+                "follow-up",
+                "blocked on REF-1 and REF-99, cc @alice and @human",
+            ),
+        )
+        .await
+        .expect("linking task should be created");
+
+        let target = queries::get_task_details(&pool, "REF", "REF-1")
+            .await
+            .expect("target should load");
+        assert_eq!(target.backlinks.len(), 1);
+        assert_eq!(target.backlinks[0].display_key, "REF-2");
+        assert_eq!(target.backlinks[0].source_kind, "task");
+
+        let linking = queries::get_task_details(&pool, "REF", "REF-2")
+            .await
+            .expect("linking task should load");
+        let mut mentioned: Vec<&str> = linking
+            .mentions
+            .iter()
+            .map(|mention| mention.actor.as_str())
+            .collect();
+        mentioned.sort_unstable();
+        assert_eq!(mentioned, vec!["alice", "human"]);
+        // Mentioning yourself is recorded but does not notify.
+        assert_eq!(
+            linking
+                .history
+                .iter()
+                .filter(|entry| entry.action == "task.mentioned")
+                .count(),
+            1
+        );
+        let inbox = queries::list_notifications(&pool, "alice", None, true, 10, 0)
+            .await
+            .expect("notifications should load");
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].reason, "mention");
+
+        queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "REF",
+            "REF-1",
+            "@bob is REF-2 still needed?",
+            "",
+            "agent",
+        )
+        .await
+        .expect("question should be created");
+        queries::update_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "REF",
+            "REF-2",
+            queries::UpdateTaskInput {
+                title: None,
+                description: Some("unblocked, thanks @alice".to_string()),
+                status: None,
+                priority: None,
+                review_state: None,
+                labels: None,
+                assignee: None,
+                due_at: None,
+                actor: "human".to_string(),
+            },
+        )
+        .await
+        .expect("task should update");
+
+        let target = queries::get_task_details(&pool, "REF", "REF-1")
+            .await
+            .expect("target should load");
+        assert!(target.backlinks.is_empty());
+        assert_eq!(target.mentions.len(), 1);
+        assert_eq!(target.mentions[0].actor, "bob");
+        assert_eq!(target.mentions[0].source_kind, "question");
+
+        let linking = queries::get_task_details(&pool, "REF", "REF-2")
+            .await
+            .expect("linking task should load");
+        assert_eq!(linking.backlinks.len(), 1);
+        assert_eq!(linking.backlinks[0].source_kind, "question");
+        assert_eq!(
+            linking
+                .mentions
+                .iter()
+                .map(|mention| mention.actor.as_str())
+                .collect::<Vec<_>>(),
+            vec!["alice"]
+        );
+        // alice stayed mentioned, so the edit does not notify her again.
+        let inbox = queries::list_notifications(&pool, "alice", None, true, 10, 0)
+            .await
+            .expect("notifications should load");
+        assert_eq!(inbox.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn renamed_projects_resolve_their_former_slugs() {
+        let (_temp_dir, pool) = setup_db("project_rename").await;
+        queries::create_project_with_slug(&pool, "Alpha", "goal", "ALPA")
+            .await
+            .expect("project should be created");
+        queries::create_project_with_slug(&pool, "Other", "goal", "OTHER")
+            .await
+            .expect("other project should be created");
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "ALPA",
+            queries::NewTaskInput {
+                title: "first".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let renamed = queries::rename_project(&pool, "ALPA", "alpha", "human", None)
+            .await
+            .expect("rename should succeed");
+        assert_eq!(renamed.project.slug, "ALPHA");
+        assert_eq!(
+            queries::renamed_project_slug(&pool, "ALPA")
+                .await
+                .expect("alias lookup should succeed")
+                .as_deref(),
+            Some("ALPHA")
+        );
+
+        let task = queries::get_task_details(&pool, "ALPHA", "ALPA-1")
+            .await
+            .expect("legacy display key should resolve");
+        assert_eq!(task.task.title, "first");
+
+        let taken = queries::rename_project(&pool, "OTHER", "ALPA", "human", None).await;
+        assert!(matches!(taken, Err(AppError::Conflict(_))));
+        let reused = queries::create_project_with_slug(&pool, "Again", "goal", "ALPA").await;
+        assert!(matches!(reused, Err(AppError::SlugTaken { .. })));
+
+        queries::rename_project(&pool, "ALPHA", "ALPA", "human", None)
+            .await
+            .expect("renaming back should reclaim the alias");
+        assert_eq!(
+            queries::renamed_project_slug(&pool, "ALPHA")
+                .await
+                .expect("alias lookup should succeed")
+                .as_deref(),
+            Some("ALPA")
+        );
+        assert_eq!(
+            queries::renamed_project_slug(&pool, "ALPA")
+                .await
+                .expect("alias lookup should succeed"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn taken_slugs_suggest_free_alternatives() {
+        let (_temp_dir, pool) = setup_db("slug_suggestions").await;
+        for slug in ["LATTICE", "LATTICE-APP", "V2"] {
+            queries::create_project_with_slug(&pool, "Taken", "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+
+        let taken = queries::create_project_with_slug(&pool, "Again", "goal", "lattice").await;
+        let Err(AppError::SlugTaken {
+            message,
+            suggestions,
+        }) = taken
+        else {
+            panic!("expected a slug conflict, got {taken:?}");
+        };
+        assert!(message.contains("already taken"));
+        assert_eq!(suggestions, ["LATTICE2", "LATTICE3", "LATTICE-CORE"]);
+
+        let Err(AppError::SlugTaken { suggestions, .. }) =
+            queries::create_project_with_slug(&pool, "Again", "goal", "V2").await
+        else {
+            panic!("expected a slug conflict");
+        };
+        assert_eq!(suggestions, ["V2-2", "V2-APP", "V2-3"]);
+    }
+
+    #[tokio::test]
+    async fn description_edits_are_stored_as_diffs() {
+        let (_temp_dir, pool) = setup_db("description_diff").await;
+        queries::create_project_with_slug(&pool, "Diffs", "goal", "DIFF")
+            .await
+            .expect("project should be created");
+        let original = "Steps:\n1. build\n2. test\n3. ship\n";
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "DIFF",
+            queries::NewTaskInput {
+                title: "release".to_string(),
+                description: original.to_string(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+
+        let edit = |description: &str| queries::UpdateTaskInput {
+            title: None,
+            description: Some(description.to_string()),
+            status: None,
+            priority: None,
+            review_state: None,
+            labels: None,
+            assignee: None,
+            due_at: None,
+            actor: "human".to_string(),
+        };
+        queries::update_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "DIFF",
+            "DIFF-1",
+            edit("Steps:\n1. build\n2. test twice\n3. ship\n"),
+        )
+        .await
+        .expect("description should update");
+
+        let details = queries::get_task_details(&pool, "DIFF", "DIFF-1")
+            .await
+            .expect("task should load");
+        let entry = details
+            .history
+            .iter()
+            .find(|entry| entry.action == "task.updated")
+            .expect("update should be recorded");
+        let detail: serde_json::Value =
+            serde_json::from_str(&entry.detail).expect("detail should be json");
+        let change = &detail["changes"]["description"];
+        assert!(change.get("from").is_none());
+        assert_eq!(
+            change["diff"],
+            serde_json::json!([
+                { "keep": 2 },
+                { "delete": ["2. test\n"] },
+                { "insert": ["2. test twice\n"] },
+                { "keep": 1 },
+            ])
+        );
+
+        let diff = queries::get_task_history_diff(&pool, "DIFF", "DIFF-1", &entry.id)
+            .await
+            .expect("diff should render");
+        assert!(diff
+            .unified
+            .contains("@@ -3 +3 @@\n-2. test\n+2. test twice\n"));
+
+        let undone = queries::undo_last_task_change(&pool, "DIFF", "DIFF-1", "human")
+            .await
+            .expect("description edit should be undone");
+        assert_eq!(undone.description, original);
+
+        queries::update_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "DIFF",
+            "DIFF-1",
+            edit("rewritten"),
+        )
+        .await
+        .expect("description should update");
+        sqlx::query("UPDATE tasks SET description = 'edited elsewhere'")
+            .execute(&pool)
+            .await
+            .expect("description should be overwritten");
+        let stale = queries::undo_last_task_change(&pool, "DIFF", "DIFF-1", "human").await;
+        assert!(matches!(stale, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn field_limits_reject_new_text_but_keep_existing_rows_editable() {
+        let (_temp_dir, pool) = setup_db("field_limits").await;
+        queries::create_project_with_slug(&pool, "Limits", "goal", "LIM")
+            .await
+            .expect("project should be created");
+        let limits = FieldLimitConfig {
+            max_title_length: 20,
+            max_description_length: 40,
+            max_question_length: 30,
+            ..FieldLimitConfig::default()
+        };
+        let new_task = |title: String| queries::NewTaskInput {
+            title,
+            description: String::new(),
+            status: "backlog".to_string(),
+            priority: "medium".to_string(),
+            review_state: "ready".to_string(),
+            labels: Vec::new(),
+            created_by: "human".to_string(),
+            assignee: None,
+            due_at: None,
+        };
+
+        let too_long = queries::create_task(
+            &pool,
+            &limits,
+            "LIM",
+            new_task("x".repeat(limits.max_title_length + 1)),
+        )
+        .await;
+        match too_long {
+            Err(AppError::BadRequest(message)) => {
+                assert!(message.contains("LATTICE_MAX_TITLE_LENGTH"), "{message}");
+            }
+            other => panic!("expected a length error, got {other:?}"),
+        }
+        // Limits belong to the caller, so a server with the defaults still takes the title.
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "LIM",
+            new_task("x".repeat(limits.max_title_length + 1)),
+        )
+        .await
+        .expect("default limits should allow the title");
+
+        queries::create_task(&pool, &limits, "LIM", new_task("legacy".to_string()))
+            .await
+            .expect("task should be created");
+        let oversized = "y".repeat(limits.max_description_length + 10);
+        sqlx::query("UPDATE tasks SET description = ?")
+            .bind(&oversized)
+            .execute(&pool)
+            .await
+            .expect("legacy description should be written");
 
-fn normalize_webhook_url(value: &str) -> AppResult<String> {
-    let trimmed = value.trim();
-    let parsed = reqwest::Url::parse(trimmed)
-        .map_err(|_| AppError::BadRequest("webhook url must be a valid http(s) URL".to_string()))?;
+        let update = |description: Option<String>| queries::UpdateTaskInput {
+            title: Some("legacy, renamed".to_string()),
+            description,
+            status: Some("ready".to_string()),
+            priority: None,
+            review_state: None,
+            labels: None,
+            assignee: None,
+            due_at: None,
+            actor: "human".to_string(),
+        };
+        queries::update_task(
+            &pool,
+            &limits,
+            "LIM",
+            "LIM-2",
+            update(Some(oversized.clone())),
+        )
+        .await
+        .expect("resending the grandfathered description should pass");
+        let grown = queries::update_task(
+            &pool,
+            &limits,
+            "LIM",
+            "LIM-2",
+            update(Some(oversized + "!")),
+        )
+        .await;
+        assert!(matches!(grown, Err(AppError::BadRequest(_))));
 
-    match parsed.scheme() {
-        "http" | "https" => Ok(parsed.to_string()),
-        _ => Err(AppError::BadRequest(
-            "webhook url must use http or https".to_string(),
-        )),
+        let question = queries::create_open_question(
+            &pool,
+            &limits,
+            "LIM",
+            "LIM-2",
+            &"?".repeat(limits.max_question_length + 1),
+            "",
+            "human",
+        )
+        .await;
+        assert!(matches!(question, Err(AppError::BadRequest(_))));
     }
-}
 
-fn normalize_webhook_events(events: Vec<String>) -> AppResult<Vec<String>> {
-    let mut normalized = std::collections::BTreeSet::new();
-    for event in events {
-        let candidate = event.trim();
-        if candidate.is_empty() {
-            continue;
+    #[tokio::test]
+    async fn archived_tasks_can_be_listed_restored_and_purged() {
+        let (_temp_dir, pool) = setup_db("archive-restore-test").await;
+        queries::create_project_with_slug(&pool, "archive", "goal", "ARC")
+            .await
+            .expect("project should be created");
+        for title in ["keep", "shelve"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "ARC",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
         }
 
-        if !WEBHOOK_EVENTS.contains(&candidate) {
-            return Err(AppError::BadRequest(format!(
-                "invalid webhook event '{candidate}'"
-            )));
-        }
-        normalized.insert(candidate.to_string());
-    }
+        assert!(matches!(
+            queries::purge_task(&pool, "ARC", "ARC-2", "human").await,
+            Err(AppError::Conflict(_))
+        ));
+        queries::add_task_dependency(&pool, "ARC", "ARC-1", "ARC-2", "human")
+            .await
+            .expect("dependency should be added");
+        queries::update_project_settings(
+            &pool,
+            "ARC",
+            queries::UpdateProjectSettingsInput {
+                enforce_dependencies: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("settings should update");
+        let archived = queries::archive_task(&pool, "ARC", "ARC-2", "human")
+            .await
+            .expect("task should be archived");
+        assert!(archived.archived_at.is_some());
+        assert_eq!(archived.status, "ready");
+        let summary = queries::get_project(&pool, "ARC")
+            .await
+            .expect("project should load");
+        assert_eq!(summary.ready_count, 1, "archived tasks are not counted");
+        let dependencies = queries::list_task_dependencies(&pool, "ARC", "ARC-1")
+            .await
+            .expect("dependencies should list");
+        assert!(dependencies.blocked_by.is_empty());
+        queries::move_task(
+            &pool,
+            "ARC",
+            "ARC-1",
+            queries::MoveTaskInput {
+                status: "done".to_string(),
+                sort_order: None,
+                actor: "human".to_string(),
+                mcp_origin: false,
+            },
+        )
+        .await
+        .expect("an archived blocker no longer blocks");
+
+        let titles = |include_archived: bool| {
+            let pool = pool.clone();
+            let filters = queries::TaskFilters {
+                include_archived,
+                ..Default::default()
+            };
+            async move {
+                queries::list_tasks(&pool, "ARC", filters, 50, 0)
+                    .await
+                    .expect("tasks should list")
+                    .into_iter()
+                    .map(|task| task.title)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(titles(false).await, ["keep"]);
+        assert_eq!(titles(true).await, ["shelve", "keep"]);
 
-    if normalized.is_empty() {
-        return Err(AppError::BadRequest(
-            "webhook must subscribe to at least one event".to_string(),
+        let restored = queries::unarchive_task(&pool, "ARC", "ARC-2", "human")
+            .await
+            .expect("task should be restored");
+        assert_eq!(restored.archived_at, None);
+        assert_eq!(titles(false).await, ["shelve", "keep"]);
+        let dependencies = queries::list_task_dependencies(&pool, "ARC", "ARC-1")
+            .await
+            .expect("dependencies should list");
+        assert_eq!(
+            dependencies.blocked_by[0].display_key, "ARC-2",
+            "restoring a task brings its links back"
+        );
+        let history = queries::get_task_details(&pool, "ARC", "ARC-2")
+            .await
+            .expect("task should load")
+            .history;
+        assert!(history
+            .iter()
+            .any(|entry| entry.action == "task.unarchived"));
+
+        queries::archive_task(&pool, "ARC", "ARC-2", "human")
+            .await
+            .expect("task should be archived");
+        queries::purge_task(&pool, "ARC", "ARC-2", "human")
+            .await
+            .expect("archived task should be purged");
+        assert!(matches!(
+            queries::get_task_details(&pool, "ARC", "ARC-2").await,
+            Err(AppError::NotFound(_))
         ));
     }
 
-    Ok(normalized.into_iter().collect())
-}
+    #[tokio::test]
+    async fn done_tasks_are_archived_after_the_project_threshold() {
+        let (_temp_dir, pool) = setup_db("auto_archive").await;
+        queries::create_project_with_slug(&pool, "Archive", "goal", "ARC")
+            .await
+            .expect("project should be created");
+        for (title, status) in [("shipped", "done"), ("recent", "done"), ("open", "ready")] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "ARC",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+        sqlx::query(
+            "UPDATE tasks SET status_changed_at = ?, updated_at = ? WHERE title != 'recent'",
+        )
+        .bind(queries::timestamp_days_ago(30))
+        .bind(queries::timestamp_days_ago(30))
+        .execute(&pool)
+        .await
+        .expect("tasks should be backdated");
 
-pub fn parse_webhook_events(raw: &str) -> AppResult<Vec<String>> {
-    let parsed = serde_json::from_str::<Vec<String>>(raw).map_err(|error| {
-        tracing::error!(error = ?error, raw, "failed to parse webhook events");
-        AppError::Internal
-    })?;
+        assert!(queries::list_auto_archive_projects(&pool)
+            .await
+            .expect("settings should load")
+            .is_empty());
+        queries::update_project_settings(
+            &pool,
+            "ARC",
+            queries::UpdateProjectSettingsInput {
+                auto_archive_after_days: Some(7),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("settings should update");
+        let projects = queries::list_auto_archive_projects(&pool)
+            .await
+            .expect("settings should load");
+        assert_eq!(projects.len(), 1);
 
-    normalize_webhook_events(parsed)
-}
+        let (project_id, days) = projects[0].clone();
+        let cutoff = queries::timestamp_days_ago(7);
+        let archived = queries::archive_done_tasks(&pool, &project_id, &cutoff, days)
+            .await
+            .expect("archival should run");
+        assert_eq!(archived, 1);
+        let again = queries::archive_done_tasks(&pool, &project_id, &cutoff, days)
+            .await
+            .expect("archival should run");
+        assert_eq!(again, 0);
 
-fn normalize_optional_secret(value: Option<String>) -> Option<String> {
-    match value {
-        Some(secret) => {
-            let trimmed = secret.trim().to_string();
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(trimmed)
-            }
-        }
-        None => None,
+        let visible = queries::list_tasks(&pool, "ARC", queries::TaskFilters::default(), 50, 0)
+            .await
+            .expect("tasks should list");
+        let titles: Vec<_> = visible.iter().map(|task| task.title.as_str()).collect();
+        assert_eq!(titles.len(), 2);
+        assert!(!titles.contains(&"shipped"));
+        let archived_tasks = queries::list_tasks(
+            &pool,
+            "ARC",
+            queries::TaskFilters {
+                archived: Some(true),
+                ..Default::default()
+            },
+            50,
+            0,
+        )
+        .await
+        .expect("tasks should list");
+        assert_eq!(archived_tasks.len(), 1);
+        assert_eq!(archived_tasks[0].title, "shipped");
+        assert!(archived_tasks[0].archived_at.is_some());
     }
-}
 
-fn normalized_labels(labels: Vec<String>) -> Vec<String> {
-    let mut seen = std::collections::BTreeSet::new();
-    for label in labels {
-        let trimmed = label.trim();
-        if trimmed.is_empty() {
-            continue;
+    #[tokio::test]
+    async fn transferred_tasks_keep_their_children_and_leave_a_tombstone() {
+        let (_temp_dir, pool) = setup_db("transfer").await;
+        for (name, slug) in [("Source", "SRC"), ("Target", "DST")] {
+            queries::create_project_with_slug(&pool, name, "goal", slug)
+                .await
+                .expect("project should be created");
         }
-        seen.insert(trimmed.to_string());
-    }
+        let new_task = |title: &str| queries::NewTaskInput {
+            title: title.to_string(),
+            description: String::new(),
+            status: "ready".to_string(),
+            priority: "medium".to_string(),
+            review_state: "ready".to_string(),
+            labels: Vec::new(),
+            created_by: "human".to_string(),
+            assignee: None,
+            due_at: None,
+        };
+        queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "DST",
+            new_task("already here"),
+        )
+        .await
+        .expect("task should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "SRC",
+            new_task("moving"),
+        )
+        .await
+        .expect("task should be created");
+        queries::add_subtask(
+            &pool,
+            &FieldLimitConfig::default(),
+            "SRC",
+            "SRC-1",
+            "step",
+            "human",
+        )
+        .await
+        .expect("subtask should be added");
+        queries::create_open_question(
+            &pool,
+            &FieldLimitConfig::default(),
+            "SRC",
+            "SRC-1",
+            "why?",
+            "",
+            "human",
+        )
+        .await
+        .expect("question should be asked");
 
-    seen.into_iter().collect()
-}
+        let same = queries::transfer_task(&pool, "SRC", "SRC-1", "SRC", "human").await;
+        assert!(matches!(same, Err(AppError::BadRequest(_))));
 
-fn is_canonical_uuid(value: &str) -> bool {
-    let parsed = match Uuid::parse_str(value) {
-        Ok(uuid) => uuid,
-        Err(_) => return false,
-    };
+        let (slug, moved) = queries::transfer_task(&pool, "SRC", "SRC-1", " dst", "human")
+            .await
+            .expect("task should transfer");
+        assert_eq!(slug, "DST");
+        assert_eq!(moved.id, task.id);
+        assert_eq!(moved.task_number, 2);
 
-    let canonical = parsed.hyphenated().to_string();
-    value.eq_ignore_ascii_case(&canonical)
-}
+        let details = queries::get_task_details(&pool, "DST", "DST-2")
+            .await
+            .expect("task should resolve under its new key");
+        assert_eq!(details.subtasks.len(), 1);
+        assert_eq!(details.open_questions.len(), 1);
+        assert!(details
+            .history
+            .iter()
+            .any(|entry| entry.action == "task.transferred"));
 
-fn parse_display_key(value: &str) -> Option<(String, i64)> {
-    let (slug, number) = value.split_once('-')?;
-    if slug.is_empty()
-        || !slug
-            .chars()
-            .all(|character| character.is_ascii_uppercase() || character.is_ascii_digit())
-    {
-        return None;
+        assert_eq!(
+            queries::transferred_task_key(&pool, "SRC", "SRC-1")
+                .await
+                .expect("tombstone should load"),
+            Some(("DST".to_string(), 2))
+        );
+        assert_eq!(
+            queries::transferred_task_key(&pool, "SRC", &task.id)
+                .await
+                .expect("tombstone should load"),
+            Some(("DST".to_string(), 2))
+        );
+        match queries::get_task_details(&pool, "SRC", "SRC-1").await {
+            Err(AppError::NotFound(message)) => assert!(message.contains("DST-2"), "{message}"),
+            other => panic!("expected a not found error, got {other:?}"),
+        }
     }
 
-    if number.starts_with('0') {
-        return None;
-    }
+    #[tokio::test]
+    async fn configured_column_order_drives_task_lists_and_the_board() {
+        let (_temp_dir, pool) = setup_db("column_order").await;
+        queries::create_project_with_slug(&pool, "Columns", "goal", "COL")
+            .await
+            .expect("project should be created");
+        for (title, status) in [
+            ("queued", "backlog"),
+            ("shipped", "done"),
+            ("next", "ready"),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "COL",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
 
-    let parsed_number: i64 = number.parse().ok()?;
-    if parsed_number <= 0 {
-        return None;
-    }
+        let invalid = queries::update_project_settings(
+            &pool,
+            "COL",
+            queries::UpdateProjectSettingsInput {
+                column_order: Some(vec!["done".to_string(), "ready".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(matches!(invalid, Err(AppError::BadRequest(_))));
 
-    Some((slug.to_string(), parsed_number))
-}
+        let order = ["done", "in_progress", "review", "ready", "backlog"];
+        let settings = queries::update_project_settings(
+            &pool,
+            "COL",
+            queries::UpdateProjectSettingsInput {
+                column_order: Some(order.iter().map(|status| status.to_string()).collect()),
+                column_colors: Some(BTreeMap::from([("done".to_string(), "#0A0".to_string())])),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("settings should update");
+        assert_eq!(settings.column_order, order);
+        assert_eq!(settings.column_colors["done"], "#00aa00");
 
-#[cfg(test)]
-mod tests {
-    use sqlx::AnyPool;
-    use tempfile::tempdir;
+        let titles: Vec<String> =
+            queries::list_tasks(&pool, "COL", queries::TaskFilters::default(), 50, 0)
+                .await
+                .expect("tasks should list")
+                .into_iter()
+                .map(|task| task.title)
+                .collect();
+        assert_eq!(titles, ["shipped", "next", "queued"]);
 
-    use crate::config::{Config, RateLimitConfig};
-    use crate::db;
-    use crate::db::queries;
+        let board = queries::get_board(&pool, "COL", 10)
+            .await
+            .expect("board should load");
+        let statuses: Vec<&str> = board.iter().map(|column| column.status.as_str()).collect();
+        assert_eq!(statuses, order);
+        assert_eq!(board[0].color.as_deref(), Some("#00aa00"));
+        assert_eq!(board[1].color, None);
 
-    #[test]
-    fn parse_task_ref_accepts_uuid_and_display_key() {
-        let uuid = "123e4567-e89b-12d3-a456-426614174000";
-        let parsed_uuid = queries::parse_task_ref(uuid).expect("uuid should parse");
-        match parsed_uuid {
-            queries::TaskRef::Uuid(value) => assert_eq!(value, uuid),
-            _ => panic!("expected uuid task ref"),
-        }
+        let reset = queries::update_project_settings(
+            &pool,
+            "COL",
+            queries::UpdateProjectSettingsInput {
+                column_order: Some(Vec::new()),
+                column_colors: Some(BTreeMap::new()),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("settings should reset");
+        assert_eq!(
+            reset.column_order,
+            ["backlog", "ready", "in_progress", "review", "done"]
+        );
+        assert!(reset.column_colors.is_empty());
+    }
 
-        let parsed_display =
-            queries::parse_task_ref("LATTICE-42").expect("display key should parse");
-        match parsed_display {
-            queries::TaskRef::DisplayKey { slug, task_number } => {
-                assert_eq!(slug, "LATTICE");
-                assert_eq!(task_number, 42);
-            }
-            _ => panic!("expected display-key task ref"),
+    #[tokio::test]
+    async fn facets_count_labels_actors_and_priorities() {
+        let (_temp_dir, pool) = setup_db("facets").await;
+        queries::create_project_with_slug(&pool, "Facets", "goal", "FAC")
+            .await
+            .expect("project should be created");
+        for (title, priority, labels, created_by) in [
+            ("one", "high", vec!["api", "ui"], "ana"),
+            ("two", "high", vec!["api"], "ana"),
+            ("three", "low", vec![], "bot"),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "FAC",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: priority.to_string(),
+                    review_state: "ready".to_string(),
+                    labels: labels.into_iter().map(str::to_string).collect(),
+                    created_by: created_by.to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
         }
-    }
 
-    #[test]
-    fn parse_task_ref_rejects_invalid_display_key() {
-        let result = queries::parse_task_ref("lattice-01");
-        assert!(result.is_err());
+        let facets = queries::project_facets(&pool, "FAC", 1, 0)
+            .await
+            .expect("facets should load");
+        assert_eq!(facets.labels.total, 2);
+        assert_eq!(facets.labels.items.len(), 1);
+        assert_eq!(
+            (
+                facets.labels.items[0].value.as_str(),
+                facets.labels.items[0].count
+            ),
+            ("api", 2)
+        );
+        assert_eq!(facets.actors.total, 2);
+        assert_eq!(facets.actors.items[0].value, "ana");
+        let priorities: Vec<(&str, i64)> = facets
+            .priorities
+            .iter()
+            .map(|facet| (facet.value.as_str(), facet.count))
+            .collect();
+        assert_eq!(
+            priorities,
+            [("low", 1), ("medium", 0), ("high", 2), ("critical", 0)]
+        );
+
+        let next = queries::project_facets(&pool, "FAC", 1, 1)
+            .await
+            .expect("facets should load");
+        assert_eq!(next.labels.items[0].value, "ui");
+        assert_eq!(next.actors.items[0].value, "bot");
     }
 
-    #[test]
-    fn normalize_slug_rejects_lowercase_and_symbols() {
-        let normalized =
-            queries::normalize_slug(" lattice-v1 ").expect("slug normalization should succeed");
-        assert_eq!(normalized, "LATTICE-V1");
+    #[tokio::test]
+    async fn renaming_a_label_merges_it_and_records_one_event() {
+        let (_temp_dir, pool) = setup_db("rename_label").await;
+        for slug in ["LAB", "OTHER"] {
+            queries::create_project_with_slug(&pool, slug, "goal", slug)
+                .await
+                .expect("project should be created");
+        }
+        for (project, labels) in [
+            ("LAB", vec!["bug"]),
+            ("LAB", vec!["bug", "defect"]),
+            ("LAB", vec!["ui"]),
+            ("OTHER", vec!["bug"]),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                project,
+                queries::NewTaskInput {
+                    title: "task".to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: labels.into_iter().map(str::to_string).collect(),
+                    created_by: "ana".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+
+        let affected = queries::rename_label(&pool, "LAB", " bug ", "defect", "ana")
+            .await
+            .expect("label should be renamed");
+        assert_eq!(affected, 2);
+        let facets = queries::project_facets(&pool, "LAB", 10, 0)
+            .await
+            .expect("facets should load");
+        let labels: Vec<(&str, i64)> = facets
+            .labels
+            .items
+            .iter()
+            .map(|facet| (facet.value.as_str(), facet.count))
+            .collect();
+        assert_eq!(labels, [("defect", 2), ("ui", 1)]);
+        let other = queries::project_facets(&pool, "OTHER", 10, 0)
+            .await
+            .expect("facets should load");
+        assert_eq!(
+            other.labels.items[0].value, "bug",
+            "other projects keep theirs"
+        );
 
-        assert!(queries::normalize_slug("bad_slug").is_err());
-        assert!(queries::normalize_slug("-BAD").is_err());
+        let events = queries::list_recent_system_events(&pool, "LAB", 10)
+            .await
+            .expect("events should load");
+        let renames: Vec<&str> = events
+            .iter()
+            .filter(|event| event.action == "labels.renamed")
+            .map(|event| event.detail.as_str())
+            .collect();
+        assert_eq!(renames.len(), 1);
+        let detail: Value = serde_json::from_str(renames[0]).expect("detail should parse");
+        assert_eq!(detail["affected"], 2);
+
+        let again = queries::rename_label(&pool, "LAB", "bug", "defect", "ana")
+            .await
+            .expect("renaming a missing label is a no-op");
+        assert_eq!(again, 0);
+        assert!(matches!(
+            queries::rename_label(&pool, "LAB", "ui", " ", "ana").await,
+            Err(AppError::BadRequest(_))
+        ));
     }
 
-    async fn setup_db(db_name: &str) -> (tempfile::TempDir, AnyPool) {
-        let temp_dir = tempdir().expect("tempdir should be created");
-        let db_path = temp_dir.path().join(format!("{db_name}.db"));
-        let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
-
-        let config = Config {
-            port: 7400,
-            db_url,
-            token: None,
-            log_level: "info".to_string(),
-            storage_dir: temp_dir.path().join("storage"),
-            max_file_size: 10 * 1024 * 1024,
-            rate_limits: RateLimitConfig::default(),
+    #[tokio::test]
+    async fn cached_project_ids_are_dropped_when_a_slug_is_freed() {
+        let (_temp_dir, pool) = setup_db("slug_cache").await;
+        let task = |title: &str| queries::NewTaskInput {
+            title: title.to_string(),
+            description: String::new(),
+            status: "backlog".to_string(),
+            priority: "medium".to_string(),
+            review_state: "ready".to_string(),
+            labels: Vec::new(),
+            created_by: "human".to_string(),
+            assignee: None,
+            due_at: None,
         };
 
-        let pool = db::connect_and_migrate(&config)
+        queries::create_project_with_slug(&pool, "Scratch", "goal", "SCRATCH")
             .await
-            .expect("database should initialize");
+            .expect("project should be created");
+        queries::create_task(&pool, &FieldLimitConfig::default(), "SCRATCH", task("old"))
+            .await
+            .expect("task should be created");
+        queries::delete_project(&pool, "SCRATCH", "human", None)
+            .await
+            .expect("project should be deleted");
+        assert!(matches!(
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "SCRATCH",
+                task("orphan")
+            )
+            .await,
+            Err(AppError::NotFound(_))
+        ));
 
-        (temp_dir, pool)
+        queries::create_project_with_slug(&pool, "Scratch", "goal", "SCRATCH")
+            .await
+            .expect("slug should be free again");
+        let created =
+            queries::create_task(&pool, &FieldLimitConfig::default(), "SCRATCH", task("new"))
+                .await
+                .expect("task should land in the new project");
+        assert_eq!(created.task_number, 1);
     }
 
     #[tokio::test]
-    async fn create_task_allocates_incrementing_numbers() {
-        let (_temp_dir, pool) = setup_db("lattice-test").await;
-
-        let project = queries::create_project_with_slug(&pool, "lattice", "goal", "LATTICE")
+    async fn stored_board_snapshots_keep_the_board_of_their_day() {
+        let (_temp_dir, pool) = setup_db("board_snapshots").await;
+        queries::create_project_with_slug(&pool, "Snap", "goal", "SNAP")
             .await
-            .expect("project creation should succeed");
-
-        let first_task = queries::create_task(
-            &pool,
-            &project.project.slug,
-            queries::NewTaskInput {
-                title: "first".to_string(),
-                description: String::new(),
-                status: "backlog".to_string(),
-                priority: "medium".to_string(),
-                review_state: "ready".to_string(),
-                labels: Vec::new(),
-                created_by: "human".to_string(),
-            },
-        )
-        .await
-        .expect("first task should be created");
+            .expect("project should be created");
+        for title in ["first", "second"] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "SNAP",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: "ready".to_string(),
+                    priority: "medium".to_string(),
+                    review_state: "ready".to_string(),
+                    labels: Vec::new(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
 
-        let second_task = queries::create_task(
+        let date = "2026-01-05";
+        assert_eq!(
+            queries::list_projects_missing_board_snapshot(&pool, date)
+                .await
+                .expect("missing snapshots should list"),
+            vec!["SNAP".to_string()]
+        );
+        queries::store_board_snapshot(&pool, "SNAP", date)
+            .await
+            .expect("snapshot should be stored");
+        queries::move_task(
             &pool,
-            &project.project.slug,
-            queries::NewTaskInput {
-                title: "second".to_string(),
-                description: String::new(),
-                status: "backlog".to_string(),
-                priority: "medium".to_string(),
-                review_state: "ready".to_string(),
-                labels: Vec::new(),
-                created_by: "human".to_string(),
+            "SNAP",
+            "SNAP-1",
+            queries::MoveTaskInput {
+                status: "done".to_string(),
+                sort_order: None,
+                actor: "human".to_string(),
+                mcp_origin: false,
             },
         )
         .await
-        .expect("second task should be created");
+        .expect("task should move");
+        queries::store_board_snapshot(&pool, "SNAP", date)
+            .await
+            .expect("a second store should be a no-op");
+        assert!(queries::list_projects_missing_board_snapshot(&pool, date)
+            .await
+            .expect("missing snapshots should list")
+            .is_empty());
 
-        assert_eq!(first_task.task_number, 1);
-        assert_eq!(second_task.task_number, 2);
+        let stored = queries::get_board_snapshot(&pool, "SNAP", date)
+            .await
+            .expect("stored snapshot should load");
+        let column = |snapshot: &Value, status: &str| {
+            snapshot["columns"]
+                .as_array()
+                .expect("columns should be an array")
+                .iter()
+                .find(|column| column["status"] == status)
+                .expect("column should exist")
+                .clone()
+        };
+        assert_eq!(stored["total"], 2);
         assert_eq!(
-            queries::display_key(&project.project.slug, second_task.task_number),
-            "LATTICE-2"
+            column(&stored, "ready")["tasks"].as_array().map(Vec::len),
+            Some(2)
         );
+        assert_eq!(column(&stored, "done")["total"], 0);
+
+        let live = serde_json::to_value(
+            queries::board_snapshot(&pool, "SNAP")
+                .await
+                .expect("live snapshot should load"),
+        )
+        .expect("snapshot should serialize");
+        assert_eq!(column(&live, "done")["tasks"][0]["display_key"], "SNAP-1");
+
+        assert!(matches!(
+            queries::get_board_snapshot(&pool, "SNAP", "2026-01-06").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            queries::get_board_snapshot(&pool, "SNAP", "last monday").await,
+            Err(AppError::BadRequest(_))
+        ));
     }
 
     #[tokio::test]
-    async fn update_spec_section_creates_revision() {
-        let (_temp_dir, pool) = setup_db("spec-test").await;
-        let project = queries::create_project_with_slug(&pool, "phase3spec", "goal", "PHASE3SPEC")
+    async fn board_lanes_split_columns_by_priority_and_label() {
+        let (_temp_dir, pool) = setup_db("board_lanes").await;
+        queries::create_project_with_slug(&pool, "Lanes", "goal", "LANES")
             .await
             .expect("project should be created");
+        for (title, status, priority, labels) in [
+            ("api", "ready", "high", vec!["backend", "api"]),
+            ("docs", "ready", "low", vec![]),
+            ("deploy", "done", "high", vec!["backend"]),
+        ] {
+            queries::create_task(
+                &pool,
+                &FieldLimitConfig::default(),
+                "LANES",
+                queries::NewTaskInput {
+                    title: title.to_string(),
+                    description: String::new(),
+                    status: status.to_string(),
+                    priority: priority.to_string(),
+                    review_state: "ready".to_string(),
+                    labels: labels.into_iter().map(str::to_string).collect(),
+                    created_by: "human".to_string(),
+                    assignee: None,
+                    due_at: None,
+                },
+            )
+            .await
+            .expect("task should be created");
+        }
+        let count = |lane: &crate::db::models::BoardLane, status: &str| {
+            lane.columns
+                .iter()
+                .find(|column| column.status == status)
+                .map(|column| (column.total, column.tasks.len()))
+        };
 
-        let updated = queries::update_spec_section(
-            &pool,
-            &project.project.slug,
-            "overview",
-            "# Overview",
-            "human",
-        )
-        .await
-        .expect("section update should succeed");
-        assert_eq!(updated.section, "overview");
-        assert_eq!(updated.content, "# Overview");
-
-        let history = queries::list_spec_history(&pool, &project.project.slug, "overview", 50, 0)
+        let by_priority = queries::get_board_lanes(&pool, "LANES", queries::Swimlanes::Priority, 1)
             .await
-            .expect("history should be listed");
-        assert_eq!(history.len(), 1);
-        assert_eq!(history[0].content, "# Overview");
-        assert_eq!(history[0].edited_by, "human");
+            .expect("priority lanes should load");
+        let keys: Vec<Option<&str>> = by_priority.iter().map(|lane| lane.key.as_deref()).collect();
+        assert_eq!(keys, [Some("high"), Some("low")]);
+        assert_eq!(count(&by_priority[0], "ready"), Some((1, 1)));
+        assert_eq!(count(&by_priority[0], "done"), Some((1, 1)));
+        assert_eq!(by_priority[0].columns.len(), 5);
+
+        let by_label = queries::get_board_lanes(&pool, "LANES", queries::Swimlanes::Label, 100)
+            .await
+            .expect("label lanes should load");
+        let keys: Vec<Option<&str>> = by_label.iter().map(|lane| lane.key.as_deref()).collect();
+        assert_eq!(keys, [Some("api"), Some("backend"), None]);
+        assert_eq!(count(&by_label[1], "ready"), Some((1, 1)));
+        assert_eq!(count(&by_label[1], "done"), Some((1, 1)));
+        assert_eq!(by_label[2].columns[1].tasks[0].title, "docs");
+
+        assert!(matches!(
+            queries::Swimlanes::parse("assignee"),
+            Err(AppError::BadRequest(_))
+        ));
     }
 
     #[tokio::test]
-    async fn open_question_can_be_created_and_resolved() {
-        let (_temp_dir, pool) = setup_db("questions-test").await;
-        let project =
-            queries::create_project_with_slug(&pool, "phase3questions", "goal", "PHASE3QUESTIONS")
-                .await
-                .expect("project should be created");
-
-        let task = queries::create_task(
+    async fn recurring_tasks_are_copied_once_per_due_run() {
+        let (_temp_dir, pool) = setup_db("recurrence-test").await;
+        queries::create_project_with_slug(&pool, "ops", "goal", "OPS")
+            .await
+            .expect("project should be created");
+        queries::create_task(
             &pool,
-            &project.project.slug,
+            &FieldLimitConfig::default(),
+            "OPS",
             queries::NewTaskInput {
-                title: "question task".to_string(),
-                description: String::new(),
-                status: "backlog".to_string(),
-                priority: "medium".to_string(),
+                title: "Rotate keys".to_string(),
+                description: "Follow the runbook.".to_string(),
+                status: "done".to_string(),
+                priority: "high".to_string(),
                 review_state: "ready".to_string(),
-                labels: Vec::new(),
+                labels: vec!["security".to_string()],
                 created_by: "human".to_string(),
+                assignee: Some("sam".to_string()),
+                due_at: None,
             },
         )
         .await
         .expect("task should be created");
 
-        let task_ref = queries::display_key(&project.project.slug, task.task_number);
-        let created = queries::create_open_question(
-            &pool,
-            &project.project.slug,
-            &task_ref,
-            "Use SSE?",
-            "Need realtime notifications.",
-            "human",
-        )
-        .await
-        .expect("open question should be created");
-        assert_eq!(created.status, "open");
-
-        let open_questions =
-            queries::list_project_open_questions(&pool, &project.project.slug, 50, 0)
+        assert!(matches!(
+            queries::set_task_recurrence(&pool, "OPS", "OPS-1", "every monday", "human").await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            queries::set_task_recurrence(&pool, "OPS", "OPS-1", "0 0 30 2 *", "human").await,
+            Err(AppError::BadRequest(_))
+        ));
+        let recurrence =
+            queries::set_task_recurrence(&pool, "OPS", "OPS-1", "0 9 * * MON", "human")
                 .await
-                .expect("open question list should succeed");
-        assert_eq!(open_questions.len(), 1);
-
-        let resolved = queries::answer_open_question(
-            &pool,
-            &project.project.slug,
-            &task_ref,
-            &created.id,
-            "Yes",
-            "human",
-        )
-        .await
-        .expect("open question should be resolved");
-        assert_eq!(resolved.status, "resolved");
-        assert_eq!(resolved.answer.as_deref(), Some("Yes"));
+                .expect("recurrence should be set");
+        assert_eq!(recurrence.task_number, 1);
+        assert!(recurrence.next_run_at.is_some());
+        assert!(
+            queries::list_due_recurrences(&pool, &queries::now_timestamp())
+                .await
+                .expect("due recurrences should load")
+                .is_empty()
+        );
 
-        let remaining = queries::list_project_open_questions(&pool, &project.project.slug, 50, 0)
+        sqlx::query("UPDATE task_recurrences SET next_run_at = '2026-01-05T09:00:00.000Z'")
+            .execute(&pool)
             .await
-            .expect("remaining open question list should succeed");
-        assert!(remaining.is_empty());
-    }
-
-    #[tokio::test]
-    async fn spec_update_writes_system_event() {
-        let (_temp_dir, pool) = setup_db("spec-event-test").await;
-        let project =
-            queries::create_project_with_slug(&pool, "spec events", "goal", "SPEC-EVENTS")
+            .expect("next run should be moved back");
+        let due = queries::list_due_recurrences(&pool, &queries::now_timestamp())
+            .await
+            .expect("due recurrences should load");
+        assert_eq!(due.len(), 1);
+
+        // The copy fails after the claim, and the claim is rolled back with it.
+        let mut unreachable = due[0].clone();
+        unreachable.project_slug = "GONE".to_string();
+        assert!(matches!(
+            queries::run_task_recurrence(
+                &pool,
+                &FieldLimitConfig::default(),
+                &unreachable,
+                Some("2999-01-07T09:00:00.000Z")
+            )
+            .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert_eq!(
+            queries::list_due_recurrences(&pool, &queries::now_timestamp())
                 .await
-                .expect("project should be created");
+                .expect("due recurrences should load")
+                .len(),
+            1,
+            "a failed copy leaves the run due"
+        );
 
-        queries::update_spec_section(
+        let copy = queries::run_task_recurrence(
             &pool,
-            &project.project.slug,
-            "architecture",
-            "## architecture",
-            "human",
+            &FieldLimitConfig::default(),
+            &due[0],
+            Some("2999-01-07T09:00:00.000Z"),
         )
         .await
-        .expect("spec update should succeed");
+        .expect("recurrence should run")
+        .expect("the run should be claimed");
+        assert_eq!(copy.task_number, 2);
+        assert_eq!(copy.title, "Rotate keys");
+        assert_eq!(copy.description, "Follow the runbook.");
+        assert_eq!(copy.status, "ready");
+        assert_eq!(copy.priority, "high");
+        assert_eq!(copy.assignee.as_deref(), Some("sam"));
+        let labels = queries::labels_by_task(&pool, std::slice::from_ref(&copy.id))
+            .await
+            .expect("labels should load");
+        assert_eq!(labels[&copy.id], ["security"]);
+        assert!(
+            queries::run_task_recurrence(
+                &pool,
+                &FieldLimitConfig::default(),
+                &due[0],
+                Some("2999-01-07T09:00:00.000Z")
+            )
+            .await
+            .expect("a second run should not fail")
+            .is_none(),
+            "a claimed run is not copied twice"
+        );
 
-        let events = queries::list_system_events(
-            &pool,
-            std::slice::from_ref(&project.project.slug),
-            None,
-            None,
-            50,
-        )
-        .await
-        .expect("events should be listed");
+        let recurrence = queries::get_task_recurrence(&pool, "OPS", "OPS-1")
+            .await
+            .expect("recurrence should load");
+        assert_eq!(recurrence.last_task_id.as_deref(), Some(copy.id.as_str()));
+        assert_eq!(
+            recurrence.next_run_at.as_deref(),
+            Some("2999-01-07T09:00:00.000Z")
+        );
+        let history = queries::get_task_details(&pool, "OPS", "OPS-2")
+            .await
+            .expect("copy should load")
+            .history;
+        let created = history
+            .iter()
+            .find(|entry| entry.action == "task.created")
+            .expect("copy should record its creation");
+        assert_eq!(created.actor, "system");
+
+        queries::archive_task(&pool, "OPS", "OPS-1", "human")
+            .await
+            .expect("task should archive");
+        sqlx::query("UPDATE task_recurrences SET next_run_at = '2026-01-05T09:00:00.000Z'")
+            .execute(&pool)
+            .await
+            .expect("next run should be moved back");
+        assert!(
+            queries::list_due_recurrences(&pool, &queries::now_timestamp())
+                .await
+                .expect("due recurrences should load")
+                .is_empty()
+        );
+        assert!(matches!(
+            queries::set_task_recurrence(&pool, "OPS", "OPS-1", "@daily", "human").await,
+            Err(AppError::Conflict(_))
+        ));
 
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].action, "spec.updated");
-        assert!(events[0].task_id.is_none());
-        assert!(events[0].task_number.is_none());
+        queries::delete_task_recurrence(&pool, "OPS", "OPS-1")
+            .await
+            .expect("recurrence should be removed");
+        assert!(queries::list_task_recurrences(&pool, "OPS")
+            .await
+            .expect("recurrences should list")
+            .is_empty());
+        assert!(matches!(
+            queries::delete_task_recurrence(&pool, "OPS", "OPS-1").await,
+            Err(AppError::NotFound(_))
+        ));
     }
 
     #[tokio::test]
-    async fn goal_update_writes_system_event() {
-        let (_temp_dir, pool) = setup_db("goal-event-test").await;
-        let project =
-            queries::create_project_with_slug(&pool, "goal events", "old goal", "GOAL-EVENTS")
-                .await
-                .expect("project should be created");
-
-        queries::update_project(
+    async fn spec_lint_checks_sections_against_project_tasks() {
+        let (_temp_dir, pool) = setup_db("spec-lint-test").await;
+        queries::create_project_with_slug(&pool, "lint", "goal", "LINT")
+            .await
+            .expect("project should be created");
+        queries::create_task(
             &pool,
-            &project.project.slug,
-            None,
-            Some("new goal".to_string()),
-            "human",
+            &FieldLimitConfig::default(),
+            "LINT",
+            queries::NewTaskInput {
+                title: "exists".to_string(),
+                description: String::new(),
+                status: "ready".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
         )
         .await
-        .expect("goal update should succeed");
-
-        let events = queries::list_system_events(
+        .expect("task should be created");
+        queries::update_spec_section(
             &pool,
-            std::slice::from_ref(&project.project.slug),
-            None,
-            None,
-            50,
+            &FieldLimitConfig::default(),
+            "LINT",
+            "requirements",
+            "# Requirements\n### Login\nCovered by LINT-1, blocked on LINT-7.\n",
+            "human",
+            false,
         )
         .await
-        .expect("events should be listed");
+        .expect("spec should update");
 
-        assert_eq!(events.len(), 1);
-        assert_eq!(events[0].action, "goal.updated");
-        assert!(events[0].task_id.is_none());
-        assert!(events[0].task_number.is_none());
-        assert!(events[0].detail.contains("\"from_goal\":\"old goal\""));
-        assert!(events[0].detail.contains("\"to_goal\":\"new goal\""));
+        let report = queries::lint_spec(&pool, "LINT", None)
+            .await
+            .expect("spec should lint");
+        assert_eq!(report.sections_checked, 6);
+        let findings: Vec<(&str, usize, &str, &str)> = report
+            .findings
+            .iter()
+            .map(|finding| {
+                (
+                    finding.section.as_str(),
+                    finding.line,
+                    finding.rule,
+                    finding.text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            findings,
+            [
+                ("requirements", 2, "heading_level_skip", "### Login"),
+                ("requirements", 3, "broken_task_ref", "LINT-7"),
+            ]
+        );
+
+        let overview = queries::lint_spec(&pool, "LINT", Some("overview"))
+            .await
+            .expect("one section should lint");
+        assert_eq!(overview.sections_checked, 1);
+        assert!(overview.findings.is_empty());
+        assert!(matches!(
+            queries::lint_spec(&pool, "LINT", Some("appendix")).await,
+            Err(AppError::BadRequest(_))
+        ));
     }
 }
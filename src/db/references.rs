@@ -0,0 +1,86 @@
+//! Finds `@actor` mentions and `SLUG-42` task references in free text.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// `@name` at the start of the text or after a character that cannot be part of an email
+/// address, so `dev@example.com` is not a mention.
+static MENTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^A-Za-z0-9_.@/-])@([A-Za-z0-9](?:[A-Za-z0-9._-]*[A-Za-z0-9])?)")
+        .expect("mention pattern should compile")
+});
+
+/// A project slug (uppercase letters, digits, single dashes) followed by `-<number>`.
+static TASK_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b([A-Z0-9]+(?:-[A-Z0-9]+)*)-([0-9]+)\b").expect("task ref pattern should compile")
+});
+
+/// Mentioned actor names in order of first appearance, without duplicates.
+pub fn parse_mentions(text: &str) -> Vec<String> {
+    let mut mentions: Vec<String> = Vec::new();
+    for captures in MENTION.captures_iter(text) {
+        let name = captures[1].to_string();
+        if !mentions.contains(&name) {
+            mentions.push(name);
+        }
+    }
+    mentions
+}
+
+/// Referenced `(project slug, task number)` pairs in order of first appearance, without
+/// duplicates. Whether the task exists is up to the caller.
+pub fn parse_task_refs(text: &str) -> Vec<(String, i64)> {
+    let mut refs: Vec<(String, i64)> = Vec::new();
+    for captures in TASK_REF.captures_iter(text) {
+        let Ok(number) = captures[2].parse::<i64>() else {
+            continue;
+        };
+        let reference = (captures[1].to_string(), number);
+        if !refs.contains(&reference) {
+            refs.push(reference);
+        }
+    }
+    refs
+}
+
+/// Every task reference with its byte offset, in order and with repeats, as
+/// `(offset, matched text, project slug, task number)`.
+pub fn task_ref_spans(text: &str) -> Vec<(usize, &str, &str, i64)> {
+    TASK_REF
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let whole = captures.get(0)?;
+            let number = captures[2].parse::<i64>().ok()?;
+            let slug = captures.get(1)?.as_str();
+            Some((whole.start(), whole.as_str(), slug, number))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_mentions, parse_task_refs};
+
+    #[test]
+    fn finds_mentions_but_not_email_addresses() {
+        assert_eq!(
+            parse_mentions("@alice can you pair with @build-bot? cc @alice, mail dev@example.com."),
+            vec!["alice".to_string(), "build-bot".to_string()]
+        );
+        assert_eq!(
+            parse_mentions("ping (@ops.team)."),
+            vec!["ops.team".to_string()]
+        );
+        assert!(parse_mentions("just an @ sign").is_empty());
+    }
+
+    #[test]
+    fn finds_task_refs_with_dashed_slugs() {
+        assert_eq!(
+            parse_task_refs("see LATTICE-7 and LATTICE-DEMO-12, again LATTICE-7; not lattice-3"),
+            vec![("LATTICE".to_string(), 7), ("LATTICE-DEMO".to_string(), 12)]
+        );
+        assert!(parse_task_refs("no refs in here-1").is_empty());
+    }
+}
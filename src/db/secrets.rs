@@ -0,0 +1,251 @@
+//! Envelope encryption for credentials stored in the database, such as webhook and inbound
+//! hook secrets.
+//!
+//! Each value is encrypted with its own random data key, and the data key is wrapped with a
+//! key derived from `LATTICE_SECRET_KEY`. Rotating the key only rewraps data keys, so stored
+//! ciphertext never has to be re-encrypted. Without a configured key values are stored as
+//! given, and values written before encryption was turned on are read as plaintext.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::config::SecretKeyConfig;
+use crate::error::{AppError, AppResult};
+
+/// Marks an encrypted value: `enc:v1:<key id>:<wrapped data key>:<ciphertext>`.
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+struct MasterKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl MasterKey {
+    /// Any string works as a key; it is stretched to 256 bits with SHA-256. The id is a short
+    /// hash of the derived key, so stored values can name the key they need.
+    fn derive(secret: &str) -> Self {
+        let derived = Sha256::digest(secret.trim().as_bytes());
+        let id = Sha256::digest(derived)
+            .iter()
+            .take(4)
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Self {
+            id,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&derived)),
+        }
+    }
+}
+
+/// The configured keys. `AppState` holds the one built from `Config::secret_keys`.
+#[derive(Default)]
+pub struct Keyring {
+    current: Option<MasterKey>,
+    previous: Vec<MasterKey>,
+}
+
+impl Keyring {
+    pub fn from_config(config: &SecretKeyConfig) -> Self {
+        let usable = |value: &&String| !value.trim().is_empty();
+        Self {
+            current: config
+                .secret_key
+                .iter()
+                .find(usable)
+                .map(|value| MasterKey::derive(value)),
+            previous: config
+                .previous_secret_keys
+                .iter()
+                .filter(usable)
+                .map(|value| MasterKey::derive(value))
+                .collect(),
+        }
+    }
+
+    /// Encrypts `plaintext` under the current key, or returns it unchanged when none is set.
+    pub fn seal(&self, plaintext: &str) -> AppResult<String> {
+        let Some(key) = &self.current else {
+            return Ok(plaintext.to_string());
+        };
+
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let ciphertext = encrypt(&Aes256Gcm::new(&data_key), plaintext.as_bytes())?;
+        let wrapped = encrypt(&key.cipher, data_key.as_slice())?;
+        Ok(format!(
+            "{PREFIX}{}:{}:{}",
+            key.id,
+            STANDARD_NO_PAD.encode(wrapped),
+            STANDARD_NO_PAD.encode(ciphertext)
+        ))
+    }
+
+    /// Decrypts a stored value. Values without the `enc:v1:` prefix are returned unchanged.
+    pub fn open(&self, stored: &str) -> AppResult<String> {
+        let Some(envelope) = Envelope::parse(stored)? else {
+            return Ok(stored.to_string());
+        };
+
+        let data_key = self.unwrap_data_key(&envelope)?;
+        let plaintext = decrypt(&Aes256Gcm::new(&data_key), &envelope.ciphertext)?;
+        String::from_utf8(plaintext).map_err(|_| {
+            tracing::error!("decrypted secret is not valid UTF-8");
+            AppError::Internal
+        })
+    }
+
+    /// Re-encrypts a stored value under the current key. `None` means it is already current,
+    /// or there is no current key to move it to.
+    pub fn rewrap(&self, stored: &str) -> AppResult<Option<String>> {
+        let Some(current) = &self.current else {
+            return Ok(None);
+        };
+        let Some(envelope) = Envelope::parse(stored)? else {
+            return self.seal(stored).map(Some);
+        };
+        if envelope.key_id == current.id {
+            return Ok(None);
+        }
+
+        let data_key = self.unwrap_data_key(&envelope)?;
+        let wrapped = encrypt(&current.cipher, data_key.as_slice())?;
+        Ok(Some(format!(
+            "{PREFIX}{}:{}:{}",
+            current.id,
+            STANDARD_NO_PAD.encode(wrapped),
+            STANDARD_NO_PAD.encode(&envelope.ciphertext)
+        )))
+    }
+
+    fn unwrap_data_key(&self, envelope: &Envelope) -> AppResult<Key<Aes256Gcm>> {
+        let key = self
+            .current
+            .iter()
+            .chain(&self.previous)
+            .find(|key| key.id == envelope.key_id)
+            .ok_or_else(|| {
+                tracing::error!(
+                    key_id = %envelope.key_id,
+                    "secret is encrypted with a key that is not in LATTICE_SECRET_KEY or LATTICE_SECRET_KEY_PREVIOUS"
+                );
+                AppError::Internal
+            })?;
+        let data_key = decrypt(&key.cipher, &envelope.wrapped_key)?;
+        if data_key.len() != 32 {
+            tracing::error!("wrapped data key has the wrong length");
+            return Err(AppError::Internal);
+        }
+        Ok(*Key::<Aes256Gcm>::from_slice(&data_key))
+    }
+}
+
+struct Envelope {
+    key_id: String,
+    wrapped_key: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Envelope {
+    fn parse(stored: &str) -> AppResult<Option<Self>> {
+        let Some(rest) = stored.strip_prefix(PREFIX) else {
+            return Ok(None);
+        };
+
+        let malformed = || {
+            tracing::error!("stored secret has a malformed encryption envelope");
+            AppError::Internal
+        };
+        let mut parts = rest.split(':');
+        let (Some(key_id), Some(wrapped_key), Some(ciphertext), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+        Ok(Some(Self {
+            key_id: key_id.to_string(),
+            wrapped_key: STANDARD_NO_PAD
+                .decode(wrapped_key)
+                .map_err(|_| malformed())?,
+            ciphertext: STANDARD_NO_PAD
+                .decode(ciphertext)
+                .map_err(|_| malformed())?,
+        }))
+    }
+}
+
+/// The nonce followed by the ciphertext.
+fn encrypt(cipher: &Aes256Gcm, plaintext: &[u8]) -> AppResult<Vec<u8>> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| {
+        tracing::error!("failed to encrypt secret");
+        AppError::Internal
+    })?;
+    let mut sealed = nonce.to_vec();
+    sealed.extend(ciphertext);
+    Ok(sealed)
+}
+
+fn decrypt(cipher: &Aes256Gcm, sealed: &[u8]) -> AppResult<Vec<u8>> {
+    if sealed.len() < NONCE_LEN {
+        tracing::error!("encrypted secret is too short");
+        return Err(AppError::Internal);
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| {
+            tracing::error!("failed to decrypt secret, the key does not match");
+            AppError::Internal
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Keyring;
+    use crate::config::SecretKeyConfig;
+
+    fn keyring(current: Option<&str>, previous: &[&str]) -> Keyring {
+        Keyring::from_config(&SecretKeyConfig {
+            secret_key: current.map(ToOwned::to_owned),
+            previous_secret_keys: previous.iter().map(|key| key.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn secrets_round_trip_and_survive_key_rotation() {
+        let plain = keyring(None, &[]);
+        assert_eq!(plain.seal("hunter2").unwrap(), "hunter2");
+        assert_eq!(plain.rewrap("hunter2").unwrap(), None);
+
+        let old = keyring(Some("old key"), &[]);
+        let sealed = old.seal("hunter2").unwrap();
+        assert!(sealed.starts_with("enc:v1:") && !sealed.contains("hunter2"));
+        assert_ne!(sealed, old.seal("hunter2").unwrap());
+        assert_eq!(old.open(&sealed).unwrap(), "hunter2");
+        assert_eq!(old.open("legacy plaintext").unwrap(), "legacy plaintext");
+        assert_eq!(old.rewrap(&sealed).unwrap(), None);
+
+        let rotated = keyring(Some("new key"), &["old key"]);
+        assert_eq!(rotated.open(&sealed).unwrap(), "hunter2");
+        let rewrapped = rotated
+            .rewrap(&sealed)
+            .unwrap()
+            .expect("old key should rewrap");
+        assert_eq!(
+            rewrapped.rsplit(':').next(),
+            sealed.rsplit(':').next(),
+            "rotation keeps the ciphertext"
+        );
+        assert_eq!(
+            keyring(Some("new key"), &[]).open(&rewrapped).unwrap(),
+            "hunter2"
+        );
+        assert!(keyring(Some("new key"), &[]).open(&sealed).is_err());
+
+        let adopted = rotated.rewrap("legacy plaintext").unwrap().unwrap();
+        assert_eq!(rotated.open(&adopted).unwrap(), "legacy plaintext");
+    }
+}
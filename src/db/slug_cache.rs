@@ -0,0 +1,65 @@
+//! Remembers which project id a slug belongs to, so the queries that start from a slug skip
+//! a `SELECT` on the hot path.
+//!
+//! Entries are kept per database, since tests and tools may open several pools in one process.
+//! Anything that frees or moves a slug (deleting or renaming a project, restoring a backup)
+//! calls [`invalidate`] once its transaction has committed.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use sqlx::AnyPool;
+
+/// Entries kept before the cache starts over; far more projects than an instance serves.
+const MAX_ENTRIES: usize = 4096;
+
+static CACHE: LazyLock<Mutex<SlugCache>> = LazyLock::new(Mutex::default);
+
+#[derive(Debug, Default)]
+struct SlugCache {
+    generation: u64,
+    ids: HashMap<(String, String), String>,
+}
+
+/// Taken before reading a slug from the database and handed back to [`insert`], so a lookup
+/// that raced with an invalidation is not cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Generation(u64);
+
+pub fn get(pool: &AnyPool, slug: &str) -> Option<String> {
+    lock().ids.get(&(database(pool), slug.to_string())).cloned()
+}
+
+pub fn generation() -> Generation {
+    Generation(lock().generation)
+}
+
+pub fn insert(pool: &AnyPool, slug: &str, project_id: &str, read_at: Generation) {
+    let mut cache = lock();
+    if cache.generation != read_at.0 {
+        return;
+    }
+    if cache.ids.len() >= MAX_ENTRIES {
+        cache.ids.clear();
+    }
+    cache
+        .ids
+        .insert((database(pool), slug.to_string()), project_id.to_string());
+}
+
+/// Forgets every cached slug.
+pub fn invalidate() {
+    let mut cache = lock();
+    cache.generation += 1;
+    cache.ids.clear();
+}
+
+fn database(pool: &AnyPool) -> String {
+    pool.connect_options().database_url.to_string()
+}
+
+fn lock() -> MutexGuard<'static, SlugCache> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
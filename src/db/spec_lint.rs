@@ -0,0 +1,254 @@
+//! Checks spec sections for task references and attachment links that lead nowhere, and for
+//! Markdown heading structure problems. Fenced code blocks are skipped.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::db::models::SpecLintFinding;
+use crate::db::references;
+
+pub const BROKEN_TASK_REF: &str = "broken_task_ref";
+pub const OUTDATED_TASK_REF: &str = "outdated_task_ref";
+pub const DANGLING_FILE_LINK: &str = "dangling_file_link";
+pub const HEADING_LEVEL_SKIP: &str = "heading_level_skip";
+pub const DUPLICATE_HEADING: &str = "duplicate_heading";
+pub const EMPTY_HEADING: &str = "empty_heading";
+pub const HEADING_MISSING_SPACE: &str = "heading_missing_space";
+
+/// A link to the canonical attachment route, such as `/api/v1/files/<id>` or `/files/<id>/preview`.
+static FILE_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:/api/v1)?/files/([A-Za-z0-9-]+)").expect("file link pattern should compile")
+});
+
+/// What a project's spec references are checked against.
+#[derive(Debug, Default)]
+pub struct LintContext {
+    pub project_slug: String,
+    /// Slugs the project had before a rename; references to them still resolve.
+    pub old_slugs: HashSet<String>,
+    pub task_numbers: HashSet<i64>,
+    pub attachment_ids: HashSet<String>,
+}
+
+/// Findings for one section, in line and column order.
+pub fn lint_section(section: &str, content: &str, context: &LintContext) -> Vec<SpecLintFinding> {
+    let mut findings = Vec::new();
+    let mut finding = |line: usize, column: usize, rule, message, text: &str, fix| {
+        findings.push(SpecLintFinding {
+            section: section.to_string(),
+            line,
+            column,
+            rule,
+            message,
+            text: text.to_string(),
+            fix,
+        });
+    };
+
+    let mut in_code_block = false;
+    let mut previous_level: Option<usize> = None;
+    let mut headings: HashSet<(usize, String)> = HashSet::new();
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let column = |offset: usize| line[..offset].chars().count() + 1;
+
+        for (offset, text, slug, number) in references::task_ref_spans(line) {
+            let current = slug == context.project_slug;
+            if !current && !context.old_slugs.contains(slug) {
+                continue;
+            }
+            if !context.task_numbers.contains(&number) {
+                finding(
+                    line_number,
+                    column(offset),
+                    BROKEN_TASK_REF,
+                    format!("task {text} does not exist"),
+                    text,
+                    None,
+                );
+            } else if !current {
+                finding(
+                    line_number,
+                    column(offset),
+                    OUTDATED_TASK_REF,
+                    format!("{slug} was renamed to {}", context.project_slug),
+                    text,
+                    Some(format!("{}-{number}", context.project_slug)),
+                );
+            }
+        }
+
+        for captures in FILE_LINK.captures_iter(line) {
+            let (Some(link), Some(id)) = (captures.get(0), captures.get(1)) else {
+                continue;
+            };
+            if !context.attachment_ids.contains(id.as_str()) {
+                finding(
+                    line_number,
+                    column(link.start()),
+                    DANGLING_FILE_LINK,
+                    format!(
+                        "attachment '{}' does not exist in this project",
+                        id.as_str()
+                    ),
+                    link.as_str(),
+                    None,
+                );
+            }
+        }
+
+        let Some((level, heading)) = heading(line) else {
+            continue;
+        };
+        let Some(heading) = heading else {
+            finding(
+                line_number,
+                1,
+                HEADING_MISSING_SPACE,
+                "heading marker needs a space before its text".to_string(),
+                line,
+                Some(format!("{} {}", &line[..level], &line[level..])),
+            );
+            continue;
+        };
+        if heading.is_empty() {
+            finding(
+                line_number,
+                1,
+                EMPTY_HEADING,
+                "heading has no text".to_string(),
+                line,
+                None,
+            );
+            continue;
+        }
+
+        // Track the corrected level, so applying every fix leaves no skips behind.
+        let mut level_after_fix = level;
+        if let Some(previous) = previous_level {
+            if level > previous + 1 {
+                level_after_fix = previous + 1;
+                finding(
+                    line_number,
+                    1,
+                    HEADING_LEVEL_SKIP,
+                    format!("heading jumps from level {previous} to level {level}"),
+                    line,
+                    Some(format!("{} {heading}", "#".repeat(level_after_fix))),
+                );
+            }
+        }
+        previous_level = Some(level_after_fix);
+
+        if !headings.insert((level_after_fix, heading.to_lowercase())) {
+            finding(
+                line_number,
+                1,
+                DUPLICATE_HEADING,
+                format!("heading '{heading}' already appears at this level"),
+                line,
+                None,
+            );
+        }
+    }
+
+    findings.sort_by_key(|finding| (finding.line, finding.column));
+    findings
+}
+
+/// The level and text of an ATX heading line. The text is `None` when the `#` marker runs
+/// straight into a word, as in `##Goals`, and empty for a bare marker.
+fn heading(line: &str) -> Option<(usize, Option<String>)> {
+    let line = line.trim_end();
+    let text = line.trim_start_matches('#');
+    let level = line.len() - text.len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    if text.starts_with(|character: char| character.is_alphabetic()) {
+        return Some((level, None));
+    }
+    if !(text.is_empty() || text.starts_with(' ')) {
+        return None;
+    }
+
+    let text = text.trim();
+    let closed = text.trim_end_matches('#');
+    let text = if closed.is_empty() || closed.ends_with(' ') {
+        closed.trim_end()
+    } else {
+        text
+    };
+    Some((level, Some(text.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{lint_section, LintContext};
+
+    fn context() -> LintContext {
+        LintContext {
+            project_slug: "CORE".to_string(),
+            old_slugs: HashSet::from(["OLD".to_string()]),
+            task_numbers: HashSet::from([1, 2]),
+            attachment_ids: HashSet::from(["a1b2".to_string()]),
+        }
+    }
+
+    fn rules(content: &str) -> Vec<(usize, usize, &'static str, Option<String>)> {
+        lint_section("overview", content, &context())
+            .into_iter()
+            .map(|finding| (finding.line, finding.column, finding.rule, finding.fix))
+            .collect()
+    }
+
+    #[test]
+    fn flags_references_that_lead_nowhere() {
+        let content = "See CORE-1, CORE-9, and OLD-2 (not UTF-8 or OTHER-5).\n\
+                       ![log](/api/v1/files/a1b2) and [trace](/api/v1/files/zz99/preview)\n\
+                       ```\n\
+                       CORE-404 in a code block is an example\n\
+                       ```\n";
+        assert_eq!(
+            rules(content),
+            vec![
+                (1, 13, "broken_task_ref", None),
+                (1, 25, "outdated_task_ref", Some("CORE-2".to_string())),
+                (2, 40, "dangling_file_link", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_heading_structure_problems() {
+        // Once `#### Scope` is fixed to `## Scope`, `### Risks` is no longer a skip.
+        let content = "# Goals\n\
+                       #### Scope\n\
+                       ### Risks\n\
+                       ## Scope ##\n\
+                       ##\n\
+                       ##Notes\n\
+                       #1 priority is tagged, not a heading\n\
+                       ## C#\n";
+        assert_eq!(
+            rules(content),
+            vec![
+                (2, 1, "heading_level_skip", Some("## Scope".to_string())),
+                (4, 1, "duplicate_heading", None),
+                (5, 1, "empty_heading", None),
+                (6, 1, "heading_missing_space", Some("## Notes".to_string())),
+            ]
+        );
+    }
+}
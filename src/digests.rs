@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::{DateTime, Datelike, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use tokio::time::MissedTickBehavior;
+
+use crate::db::models::{DigestScheduleRecord, ProjectDigest, TaskRecord};
+use crate::db::queries;
+use crate::event_kind::EventKind;
+use crate::state::AppState;
+use crate::webhooks::{self, WebhookPayload};
+
+const DIGEST_SCAN_INTERVAL_SECS: u64 = 60;
+
+/// Periodically posts due project digests to webhooks subscribed to the `digest` event.
+///
+/// Runs alongside the webhook dispatcher; a missed slot (e.g. during downtime) is sent once
+/// on the next scan and covers everything since the previous digest.
+pub fn spawn_digest_scheduler(state: AppState) {
+    tokio::spawn(async move {
+        if let Err(error) = run_scheduler(state).await {
+            tracing::error!(error = ?error, "digest scheduler terminated");
+        }
+    });
+}
+
+async fn run_scheduler(state: AppState) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .context("failed to build digest client")?;
+    let mut interval = tokio::time::interval(Duration::from_secs(DIGEST_SCAN_INTERVAL_SECS));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let schedules = match queries::list_digest_schedules(&state.db).await {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(error = ?error, "failed to load digest schedules");
+                continue;
+            }
+        };
+
+        let now = Utc::now();
+        for schedule in schedules {
+            let Some(since) = due_since(&schedule, now) else {
+                continue;
+            };
+
+            if let Err(error) = send_digest(&state, &client, &schedule, &since, now).await {
+                tracing::error!(
+                    error = ?error,
+                    project = %schedule.project_slug,
+                    "failed to send project digest"
+                );
+            }
+        }
+    }
+}
+
+async fn send_digest(
+    state: &AppState,
+    client: &reqwest::Client,
+    schedule: &DigestScheduleRecord,
+    since: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let until = now.to_rfc3339_opts(SecondsFormat::Millis, true);
+    let stale_before = state
+        .config
+        .stale_threshold_days()
+        .map(queries::timestamp_days_ago);
+    let digest = queries::build_project_digest(
+        &state.db,
+        &schedule.project_slug,
+        since,
+        &until,
+        stale_before.as_deref(),
+    )
+    .await?;
+
+    let payload = WebhookPayload {
+        event_id: uuid::Uuid::new_v4().to_string(),
+        event: EventKind::Digest.to_string(),
+        project: schedule.project_slug.clone(),
+        task_id: None,
+        task_number: None,
+        task_display_key: None,
+        actor: "system".to_string(),
+        actor_display_name: None,
+        detail: digest_detail(&schedule.frequency, &digest),
+        created_at: until.clone(),
+        task: None,
+    };
+    let delivered = webhooks::deliver_to_project(state, client, &payload).await?;
+    tracing::info!(project = %schedule.project_slug, delivered, "sent project digest");
+
+    // Mark the slot as sent even with no subscribers so it is not retried every scan.
+    queries::mark_digest_sent(&state.db, &schedule.project_id, &until).await?;
+    Ok(())
+}
+
+/// Start of the digest window if a scheduled slot has passed since the last digest.
+fn due_since(schedule: &DigestScheduleRecord, now: DateTime<Utc>) -> Option<String> {
+    let slot = latest_slot(schedule, now)?;
+    let last = schedule
+        .last_sent_at
+        .as_deref()
+        .unwrap_or(&schedule.created_at);
+    let last = DateTime::parse_from_rfc3339(last).ok()?.with_timezone(&Utc);
+    if slot <= last {
+        return None;
+    }
+
+    let since = match schedule.last_sent_at {
+        Some(_) => last,
+        None => slot - period(schedule),
+    };
+    Some(since.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// The most recent scheduled time at or before `now`.
+fn latest_slot(schedule: &DigestScheduleRecord, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let hour = u32::try_from(schedule.hour).ok()?;
+    let mut slot = now.date_naive().and_hms_opt(hour, 0, 0)?.and_utc();
+    if slot > now {
+        slot -= chrono::Duration::days(1);
+    }
+
+    if schedule.frequency == "weekly" {
+        let weekday = u32::try_from(schedule.weekday).ok()?;
+        while slot.weekday().number_from_monday() != weekday {
+            slot -= chrono::Duration::days(1);
+        }
+    }
+
+    Some(slot)
+}
+
+fn period(schedule: &DigestScheduleRecord) -> chrono::Duration {
+    if schedule.frequency == "weekly" {
+        chrono::Duration::days(7)
+    } else {
+        chrono::Duration::days(1)
+    }
+}
+
+fn digest_detail(frequency: &str, digest: &ProjectDigest) -> Value {
+    let task_line = |task: &TaskRecord| {
+        format!(
+            "{} {}",
+            queries::display_key(&digest.project_slug, task.task_number),
+            task.title
+        )
+    };
+
+    json!({
+        "frequency": frequency,
+        "summary": format!(
+            "{} completed, {} new questions, {} stalled",
+            digest.completed.len(),
+            digest.new_questions.len(),
+            digest.stalled.len()
+        ),
+        "since": digest.since,
+        "until": digest.until,
+        "completed": digest.completed.iter().map(task_line).collect::<Vec<_>>(),
+        "new_questions": digest
+            .new_questions
+            .iter()
+            .map(|question| format!(
+                "{}: {}",
+                queries::display_key(&digest.project_slug, question.task_number),
+                question.question
+            ))
+            .collect::<Vec<_>>(),
+        "stalled": digest.stalled.iter().map(task_line).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use crate::db::models::DigestScheduleRecord;
+
+    fn schedule(
+        frequency: &str,
+        hour: i64,
+        weekday: i64,
+        last_sent_at: Option<&str>,
+    ) -> DigestScheduleRecord {
+        DigestScheduleRecord {
+            project_id: "project".to_string(),
+            project_slug: "DIGEST".to_string(),
+            frequency: frequency.to_string(),
+            hour,
+            weekday,
+            last_sent_at: last_sent_at.map(ToOwned::to_owned),
+            created_at: "2026-03-01T00:00:00Z".to_string(),
+            updated_at: "2026-03-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("timestamp should parse")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn digests_are_due_once_per_slot() {
+        // 2026-03-04 is a Wednesday.
+        let daily = schedule("daily", 9, 1, Some("2026-03-03T09:00:30Z"));
+        assert_eq!(super::due_since(&daily, at("2026-03-04T08:59:00Z")), None);
+        assert_eq!(
+            super::due_since(&daily, at("2026-03-04T09:00:10Z")).as_deref(),
+            Some("2026-03-03T09:00:30.000Z")
+        );
+
+        let sent = schedule("daily", 9, 1, Some("2026-03-04T09:00:10Z"));
+        assert_eq!(super::due_since(&sent, at("2026-03-04T17:00:00Z")), None);
+
+        let weekly = schedule("weekly", 9, 1, None);
+        assert_eq!(
+            super::due_since(&weekly, at("2026-03-04T12:00:00Z")).as_deref(),
+            Some("2026-02-23T09:00:00.000Z"),
+            "first weekly digest covers the week before Monday's slot"
+        );
+        let weekly_sent = schedule("weekly", 9, 1, Some("2026-03-02T09:00:05Z"));
+        assert_eq!(
+            super::due_since(&weekly_sent, at("2026-03-08T23:00:00Z")),
+            None
+        );
+    }
+}
@@ -4,6 +4,8 @@ use axum::Json;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::redact;
+
 pub type AppResult<T> = Result<T, AppError>;
 
 #[derive(Debug, Error)]
@@ -14,12 +16,22 @@ pub enum AppError {
     #[error("unauthorized")]
     Unauthorized,
 
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
     #[error("not found: {0}")]
     NotFound(String),
 
     #[error("conflict: {0}")]
     Conflict(String),
 
+    /// A conflict over a project slug, with free slugs the caller could use instead.
+    #[error("conflict: {message}")]
+    SlugTaken {
+        message: String,
+        suggestions: Vec<String>,
+    },
+
     #[error("internal server error")]
     Internal,
 }
@@ -28,10 +40,16 @@ pub enum AppError {
 struct ErrorBody {
     error: String,
     message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
+impl AppError {
+    /// The status, error code, and message a client is shown.
+    ///
+    /// Messages are passed through as written. Errors that may quote an upstream url or
+    /// credential are logged through `redact::text` and reach the client as `Internal`.
+    pub fn public_parts(self) -> (StatusCode, &'static str, String) {
         let (status, error, message) = match self {
             Self::BadRequest(message) => (StatusCode::BAD_REQUEST, "bad_request", message),
             Self::Unauthorized => (
@@ -39,18 +57,32 @@ impl IntoResponse for AppError {
                 "unauthorized",
                 "missing or invalid bearer token".to_string(),
             ),
+            Self::Forbidden(message) => (StatusCode::FORBIDDEN, "forbidden", message),
             Self::NotFound(message) => (StatusCode::NOT_FOUND, "not_found", message),
-            Self::Conflict(message) => (StatusCode::CONFLICT, "conflict", message),
+            Self::Conflict(message) | Self::SlugTaken { message, .. } => {
+                (StatusCode::CONFLICT, "conflict", message)
+            }
             Self::Internal => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "internal",
                 "unexpected error".to_string(),
             ),
         };
+        (status, error, message)
+    }
+}
 
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let suggestions = match &self {
+            Self::SlugTaken { suggestions, .. } => suggestions.clone(),
+            _ => Vec::new(),
+        };
+        let (status, error, message) = self.public_parts();
         let body = Json(ErrorBody {
             error: error.to_string(),
             message,
+            suggestions,
         });
 
         (status, body).into_response()
@@ -80,7 +112,7 @@ impl From<sqlx::Error> for AppError {
 
 impl From<anyhow::Error> for AppError {
     fn from(error: anyhow::Error) -> Self {
-        tracing::error!(error = ?error, "unexpected error");
+        tracing::error!(error = %redact::text(&format!("{error:?}")), "unexpected error");
         Self::Internal
     }
 }
@@ -0,0 +1,343 @@
+//! The actions lattice records as system events and delivers to SSE streams and webhooks.
+//!
+//! Events are stored and sent as their dotted names (`task.moved`); this registry is the one
+//! list of those names, what they mean, and whether webhooks may subscribe to them.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    TaskCreated,
+    TaskUpdated,
+    TaskMentioned,
+    TaskMoved,
+    TaskDeleted,
+    TaskArchived,
+    TaskUnarchived,
+    TaskTransferred,
+    SubtaskCreated,
+    SubtaskUpdated,
+    SubtaskDeleted,
+    SubtasksUpdated,
+    AttachmentCreated,
+    AttachmentDeleted,
+    TaskReviewStateChanged,
+    TaskPriorityChanged,
+    TaskLabelsChanged,
+    TaskAssigned,
+    TaskDueDateChanged,
+    TaskDependencyAdded,
+    TaskDependencyRemoved,
+    TaskParentChanged,
+    TaskMilestoneChanged,
+    LabelsRenamed,
+    TaskUndone,
+    TaskStale,
+    SlaBreached,
+    ReviewRequested,
+    ReviewCompleted,
+    QuestionCreated,
+    QuestionResolved,
+    QuestionAccepted,
+    QuestionDisputed,
+    QuestionsResolved,
+    SpecUpdated,
+    GoalUpdated,
+    ProjectRenamed,
+    WebhookCreated,
+    WebhookUpdated,
+    WebhookDeleted,
+    Digest,
+    ReviewItemUpdated,
+    TasksReordered,
+    EventsHeld,
+    Test,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 45] = [
+        EventKind::TaskCreated,
+        EventKind::TaskUpdated,
+        EventKind::TaskMentioned,
+        EventKind::TaskMoved,
+        EventKind::TaskDeleted,
+        EventKind::TaskArchived,
+        EventKind::TaskUnarchived,
+        EventKind::TaskTransferred,
+        EventKind::SubtaskCreated,
+        EventKind::SubtaskUpdated,
+        EventKind::SubtaskDeleted,
+        EventKind::SubtasksUpdated,
+        EventKind::AttachmentCreated,
+        EventKind::AttachmentDeleted,
+        EventKind::TaskReviewStateChanged,
+        EventKind::TaskPriorityChanged,
+        EventKind::TaskLabelsChanged,
+        EventKind::TaskAssigned,
+        EventKind::TaskDueDateChanged,
+        EventKind::TaskDependencyAdded,
+        EventKind::TaskDependencyRemoved,
+        EventKind::TaskParentChanged,
+        EventKind::TaskMilestoneChanged,
+        EventKind::LabelsRenamed,
+        EventKind::TaskUndone,
+        EventKind::TaskStale,
+        EventKind::SlaBreached,
+        EventKind::ReviewRequested,
+        EventKind::ReviewCompleted,
+        EventKind::QuestionCreated,
+        EventKind::QuestionResolved,
+        EventKind::QuestionAccepted,
+        EventKind::QuestionDisputed,
+        EventKind::QuestionsResolved,
+        EventKind::SpecUpdated,
+        EventKind::GoalUpdated,
+        EventKind::ProjectRenamed,
+        EventKind::WebhookCreated,
+        EventKind::WebhookUpdated,
+        EventKind::WebhookDeleted,
+        EventKind::Digest,
+        EventKind::ReviewItemUpdated,
+        EventKind::TasksReordered,
+        EventKind::EventsHeld,
+        EventKind::Test,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::TaskCreated => "task.created",
+            EventKind::TaskUpdated => "task.updated",
+            EventKind::TaskMentioned => "task.mentioned",
+            EventKind::TaskMoved => "task.moved",
+            EventKind::TaskDeleted => "task.deleted",
+            EventKind::TaskArchived => "task.archived",
+            EventKind::TaskUnarchived => "task.unarchived",
+            EventKind::TaskTransferred => "task.transferred",
+            EventKind::SubtaskCreated => "subtask.created",
+            EventKind::SubtaskUpdated => "subtask.updated",
+            EventKind::SubtaskDeleted => "subtask.deleted",
+            EventKind::SubtasksUpdated => "subtasks.updated",
+            EventKind::AttachmentCreated => "attachment.created",
+            EventKind::AttachmentDeleted => "attachment.deleted",
+            EventKind::TaskReviewStateChanged => "task.review_state_changed",
+            EventKind::TaskPriorityChanged => "task.priority_changed",
+            EventKind::TaskLabelsChanged => "task.labels_changed",
+            EventKind::TaskAssigned => "task.assigned",
+            EventKind::TaskDueDateChanged => "task.due_date_changed",
+            EventKind::TaskDependencyAdded => "task.dependency_added",
+            EventKind::TaskDependencyRemoved => "task.dependency_removed",
+            EventKind::TaskParentChanged => "task.parent_changed",
+            EventKind::TaskMilestoneChanged => "task.milestone_changed",
+            EventKind::LabelsRenamed => "labels.renamed",
+            EventKind::TaskUndone => "task.undone",
+            EventKind::TaskStale => "task.stale",
+            EventKind::SlaBreached => "sla.breached",
+            EventKind::ReviewRequested => "review.requested",
+            EventKind::ReviewCompleted => "review.completed",
+            EventKind::QuestionCreated => "question.created",
+            EventKind::QuestionResolved => "question.resolved",
+            EventKind::QuestionAccepted => "question.accepted",
+            EventKind::QuestionDisputed => "question.disputed",
+            EventKind::QuestionsResolved => "questions.resolved",
+            EventKind::SpecUpdated => "spec.updated",
+            EventKind::GoalUpdated => "goal.updated",
+            EventKind::ProjectRenamed => "project.renamed",
+            EventKind::WebhookCreated => "webhook.created",
+            EventKind::WebhookUpdated => "webhook.updated",
+            EventKind::WebhookDeleted => "webhook.deleted",
+            EventKind::Digest => "digest",
+            EventKind::ReviewItemUpdated => "review.item_updated",
+            EventKind::TasksReordered => "tasks.reordered",
+            EventKind::EventsHeld => "events.held",
+            EventKind::Test => "test",
+        }
+    }
+
+    /// Whether a webhook may list the event in its `events`. The rest are either internal
+    /// history (`review.item_updated`, `tasks.reordered`) or sent to every webhook regardless
+    /// (`events.held`, `test`).
+    pub fn subscribable(self) -> bool {
+        !matches!(
+            self,
+            EventKind::ReviewItemUpdated
+                | EventKind::TasksReordered
+                | EventKind::EventsHeld
+                | EventKind::Test
+        )
+    }
+
+    /// `task` for `task.moved`; an undotted name is its own namespace.
+    pub fn namespace(self) -> &'static str {
+        let name = self.as_str();
+        name.split_once('.')
+            .map_or(name, |(namespace, _)| namespace)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            EventKind::TaskCreated => "A task was created.",
+            EventKind::TaskUpdated => {
+                "A task's title, description, priority, or labels were edited."
+            }
+            EventKind::TaskMentioned => "An actor was @mentioned in a task.",
+            EventKind::TaskMoved => "A task moved to another status column.",
+            EventKind::TaskDeleted => "A task was deleted.",
+            EventKind::TaskArchived => "A task was archived off the board.",
+            EventKind::TaskUnarchived => "An archived task was restored to the board.",
+            EventKind::TaskTransferred => "A task moved to another project.",
+            EventKind::SubtaskCreated => "A subtask was added.",
+            EventKind::SubtaskUpdated => "A subtask was edited or checked off.",
+            EventKind::SubtaskDeleted => "A subtask was removed.",
+            EventKind::SubtasksUpdated => "Several subtasks were edited in one request.",
+            EventKind::AttachmentCreated => "A file was attached to a task.",
+            EventKind::AttachmentDeleted => "An attachment was removed.",
+            EventKind::TaskReviewStateChanged => "A task's review state changed.",
+            EventKind::TaskPriorityChanged => "A task's priority changed.",
+            EventKind::TaskLabelsChanged => "Labels were added to or removed from a task.",
+            EventKind::TaskAssigned => "A task was assigned to someone else or unassigned.",
+            EventKind::TaskDueDateChanged => "A task's due date was set, moved, or cleared.",
+            EventKind::TaskDependencyAdded => "A task was marked as blocked by another task.",
+            EventKind::TaskDependencyRemoved => "A task is no longer blocked by another task.",
+            EventKind::TaskParentChanged => {
+                "A task was moved under a parent task or detached from one."
+            }
+            EventKind::TaskMilestoneChanged => {
+                "A task was attached to a milestone or removed from one."
+            }
+            EventKind::LabelsRenamed => "A label was renamed across the project's tasks.",
+            EventKind::TaskUndone => "A task change was undone.",
+            EventKind::TaskStale => "A task has had no updates for the stale threshold.",
+            EventKind::SlaBreached => "A task stayed in a status longer than an SLA policy allows.",
+            EventKind::ReviewRequested => "A review was requested.",
+            EventKind::ReviewCompleted => "A reviewer approved or requested changes.",
+            EventKind::QuestionCreated => "An open question was asked.",
+            EventKind::QuestionResolved => "An open question was answered.",
+            EventKind::QuestionAccepted => "An answer was accepted.",
+            EventKind::QuestionDisputed => "An answer was disputed.",
+            EventKind::QuestionsResolved => "Several questions were answered in one request.",
+            EventKind::SpecUpdated => "A spec section was saved.",
+            EventKind::GoalUpdated => "The project goal changed.",
+            EventKind::ProjectRenamed => "The project slug changed.",
+            EventKind::WebhookCreated => "A webhook was added.",
+            EventKind::WebhookUpdated => "A webhook's settings changed.",
+            EventKind::WebhookDeleted => "A webhook was removed.",
+            EventKind::Digest => "A scheduled project digest.",
+            EventKind::ReviewItemUpdated => "A review checklist item was checked or unchecked.",
+            EventKind::TasksReordered => "Tasks in a column were reordered.",
+            EventKind::EventsHeld => {
+                "A summary of events held back by quiet hours or a delivery cap."
+            }
+            EventKind::Test => "A test delivery sent from the webhook settings.",
+        }
+    }
+
+    /// Embed color in Discord messages.
+    pub fn discord_color(self) -> u32 {
+        match self {
+            EventKind::TaskCreated => 0x7A3FFF,
+            EventKind::TaskMentioned => 0x5C9EE6,
+            EventKind::TaskMoved => 0x4F9DFF,
+            EventKind::TaskDeleted => 0xC94C4C,
+            EventKind::TaskArchived => 0x7D8B99,
+            EventKind::TaskUnarchived => 0x5FA37A,
+            EventKind::TaskTransferred => 0x3FA7A0,
+            EventKind::SubtaskCreated | EventKind::SubtasksUpdated => 0x8FB8DE,
+            EventKind::SubtaskUpdated => 0x6FBF8E,
+            EventKind::SubtaskDeleted | EventKind::AttachmentDeleted => 0xB5838D,
+            EventKind::AttachmentCreated => 0xC4A35A,
+            EventKind::TaskReviewStateChanged => 0xE0A341,
+            EventKind::TaskPriorityChanged => 0xD9644A,
+            EventKind::TaskLabelsChanged | EventKind::LabelsRenamed => 0x8C9F5B,
+            EventKind::TaskAssigned => 0x3FB8AF,
+            EventKind::TaskDueDateChanged => 0xC9A227,
+            EventKind::TaskDependencyAdded => 0xB8644F,
+            EventKind::TaskDependencyRemoved => 0x6F9F7A,
+            EventKind::TaskParentChanged => 0x8E7CC3,
+            EventKind::TaskMilestoneChanged => 0x4F86A8,
+            EventKind::TaskUndone => 0xB07CFF,
+            EventKind::TaskStale => 0xD98E3A,
+            EventKind::SlaBreached => 0xE0533D,
+            EventKind::ReviewRequested => 0x5FB3B3,
+            EventKind::ReviewCompleted => 0x3FA66B,
+            EventKind::QuestionCreated => 0xF0C54A,
+            EventKind::QuestionResolved | EventKind::QuestionsResolved => 0x4BB47B,
+            EventKind::QuestionAccepted => 0x2E9E5B,
+            EventKind::QuestionDisputed => 0xE07B3D,
+            EventKind::SpecUpdated => 0x9A65C7,
+            EventKind::GoalUpdated => 0x74BBD6,
+            EventKind::ProjectRenamed => 0x5E8FB0,
+            EventKind::WebhookCreated | EventKind::WebhookUpdated => 0x6E7F99,
+            EventKind::WebhookDeleted => 0xA35A5A,
+            EventKind::Digest => 0x6C8EBF,
+            EventKind::TaskUpdated
+            | EventKind::ReviewItemUpdated
+            | EventKind::TasksReordered
+            | EventKind::EventsHeld
+            | EventKind::Test => 0x8A8A8A,
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = UnknownEventKind;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        EventKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| UnknownEventKind(value.to_string()))
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("unknown event type '{0}'")]
+pub struct UnknownEventKind(pub String);
+
+impl Serialize for EventKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EventKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EventKind;
+
+    #[test]
+    fn names_round_trip_and_are_unique() {
+        for kind in EventKind::ALL {
+            assert_eq!(kind.as_str().parse::<EventKind>().ok(), Some(kind));
+            assert_eq!(
+                serde_json::to_value(kind).expect("kind should serialize"),
+                kind.as_str()
+            );
+            assert!(!kind.description().is_empty());
+        }
+        let mut names: Vec<&str> = EventKind::ALL.iter().map(|kind| kind.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), EventKind::ALL.len());
+
+        assert_eq!(EventKind::TaskLabelsChanged.namespace(), "task");
+        assert_eq!(EventKind::Digest.namespace(), "digest");
+        assert!("task.unknown".parse::<EventKind>().is_err());
+        assert!(serde_json::from_str::<EventKind>("\"nope\"").is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use tokio::net::TcpListener;
+use tracing::warn;
+
+/// The first descriptor a service manager passes under the socket activation protocol.
+#[cfg(unix)]
+const LISTEN_FDS_START: std::os::fd::RawFd = 3;
+
+/// Returns the listening socket handed over by systemd (or `systemfd` during development)
+/// through `LISTEN_PID`/`LISTEN_FDS`, or `None` when the process was started normally.
+pub fn inherited_listener() -> std::io::Result<Option<TcpListener>> {
+    let listen_pid = std::env::var("LISTEN_PID").ok();
+    let listen_fds = std::env::var("LISTEN_FDS").ok();
+    let Some(count) = passed_fd_count(
+        listen_pid.as_deref(),
+        listen_fds.as_deref(),
+        std::process::id(),
+    ) else {
+        return Ok(None);
+    };
+    if count > 1 {
+        warn!(
+            count,
+            "service manager passed several sockets, only the first is used"
+        );
+    }
+
+    from_first_fd()
+}
+
+#[cfg(unix)]
+fn from_first_fd() -> std::io::Result<Option<TcpListener>> {
+    use std::os::fd::FromRawFd;
+
+    // SAFETY: under the socket activation protocol the service manager transfers ownership of
+    // descriptors starting at 3 to this process, and nothing else in lattice opens or uses it.
+    let listener = unsafe { std::net::TcpListener::from_raw_fd(LISTEN_FDS_START) };
+    // Fails with ENOTSOCK when the descriptor is not a socket, e.g. a stale environment.
+    listener.local_addr()?;
+    listener.set_nonblocking(true)?;
+    TcpListener::from_std(listener).map(Some)
+}
+
+#[cfg(not(unix))]
+fn from_first_fd() -> std::io::Result<Option<TcpListener>> {
+    warn!("LISTEN_FDS is only supported on unix, binding normally");
+    Ok(None)
+}
+
+/// How many sockets were passed to the process `pid`. The variables are ignored when
+/// `LISTEN_PID` names another process, since they were meant for a parent; tools such as
+/// `systemfd --no-pid` leave it unset.
+fn passed_fd_count(listen_pid: Option<&str>, listen_fds: Option<&str>, pid: u32) -> Option<u32> {
+    if let Some(listen_pid) = listen_pid {
+        if listen_pid.trim().parse::<u32>().ok()? != pid {
+            return None;
+        }
+    }
+
+    listen_fds?
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|count| *count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::passed_fd_count;
+
+    #[test]
+    fn only_sockets_passed_to_this_process_count() {
+        assert_eq!(passed_fd_count(Some("42"), Some("1"), 42), Some(1));
+        assert_eq!(passed_fd_count(Some("42"), Some("2"), 42), Some(2));
+        assert_eq!(passed_fd_count(Some("41"), Some("1"), 42), None);
+        assert_eq!(passed_fd_count(None, Some("1"), 42), Some(1));
+        assert_eq!(passed_fd_count(Some("42"), Some("0"), 42), None);
+        assert_eq!(passed_fd_count(Some("42"), Some("x"), 42), None);
+        assert_eq!(passed_fd_count(Some("42"), None, 42), None);
+    }
+}
@@ -1,9 +1,21 @@
+mod access_log;
 mod api;
+mod auto_archive;
+mod board_snapshots;
+mod client_ip;
 mod config;
 mod db;
+mod digests;
 mod error;
+mod event_kind;
+mod listener;
 mod mcp;
+mod metrics;
 mod rate_limit;
+mod recurrence;
+mod redact;
+mod sla;
+mod stale;
 mod state;
 mod static_files;
 mod webhooks;
@@ -11,10 +23,11 @@ mod webhooks;
 use std::net::SocketAddr;
 
 use anyhow::Context;
-use axum::extract::DefaultBodyLimit;
+use axum::extract::{DefaultBodyLimit, Request};
 use axum::middleware;
 use axum::routing::get;
-use axum::Router;
+use axum::{Router, ServiceExt};
+use tower::Layer;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
@@ -35,17 +48,45 @@ async fn main() -> anyhow::Result<()> {
         .await
         .context("failed to initialize database")?;
 
+    let listener = match listener::inherited_listener()
+        .context("failed to use the socket passed in LISTEN_FDS")?
+    {
+        Some(listener) => listener,
+        None => {
+            let addr = SocketAddr::new(config.bind_addr, config.port);
+            tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("failed to bind to {addr}"))?
+        }
+    };
+    let addr = listener
+        .local_addr()
+        .context("failed to read listening address")?;
+
     let state = AppState::new(config.clone(), pool);
+    state.set_bound_addr(addr);
     webhooks::spawn_dispatcher(state.clone());
+    stale::spawn_stale_detector(state.clone());
+    auto_archive::spawn_auto_archiver(state.clone());
+    board_snapshots::spawn_board_snapshotter(state.clone());
+    digests::spawn_digest_scheduler(state.clone());
+    sla::spawn_sla_evaluator(state.clone());
+    recurrence::spawn_recurrence_scheduler(state.clone());
     let mcp_service = mcp::service(state.clone());
     let max_request_body_bytes = state.config.rate_limits.max_request_body_bytes;
 
-    let app = Router::new()
+    let mut app = Router::new()
         .nest_service("/mcp", mcp_service)
         .nest("/api/v1", api::router())
         .route("/healthz", get(api::healthz))
+        .route("/readyz", get(api::readyz))
         .fallback(get(static_files::serve_embedded_asset))
         .layer(DefaultBodyLimit::max(max_request_body_bytes))
+        // Inside auth so a former slug only redirects callers allowed to see the project.
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::projects::redirect_former_addresses,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             api::auth::require_auth,
@@ -54,18 +95,23 @@ async fn main() -> anyhow::Result<()> {
         .layer(middleware::from_fn_with_state(
             state.clone(),
             rate_limit::enforce_limits,
-        ))
-        .with_state(state);
-
-    let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .with_context(|| format!("failed to bind to {addr}"))?;
+        ));
+    if state.config.access_log {
+        app = access_log::layer(app);
+    }
+    let app = app.with_state(state);
+    // Scoped `/api/v1/w/{workspace}/...` URLs must be rewritten before the router matches.
+    let app = middleware::from_fn(api::workspaces::rewrite_scoped_path).layer(app);
 
     info!(%addr, "lattice server listening");
-    axum::serve(listener, app)
-        .await
-        .context("axum server error")?;
+    // A plain, filter-independent line for scripts that start lattice with `LATTICE_PORT=0`.
+    println!("lattice listening on http://{addr}");
+    axum::serve(
+        listener,
+        ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(app),
+    )
+    .await
+    .context("axum server error")?;
 
     Ok(())
 }
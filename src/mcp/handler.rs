@@ -1,55 +1,303 @@
+use std::sync::{Arc, Mutex};
+
 use axum::http::request::Parts;
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
-    model::{Extensions, ServerCapabilities, ServerInfo},
-    schemars, tool, tool_handler, tool_router, ErrorData, Json, ServerHandler,
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
+    model::{
+        CallToolRequestParams, CallToolResult, CompleteRequestParams, CompleteResult,
+        CompletionInfo, Extensions, ListToolsResult, LoggingLevel, LoggingMessageNotificationParam,
+        Meta, PaginatedRequestParams, ProgressNotificationParam, ProgressToken, ServerCapabilities,
+        ServerInfo, SetLevelRequestParams,
+    },
+    schemars,
+    service::RequestContext,
+    tool, tool_router, ErrorData, Json, Peer, RoleServer, ServerHandler,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::AnyPool;
 
+use crate::api::attachments::read_preview;
+use crate::api::auth::{AccessScope, McpIdentity};
+use crate::client_ip::ClientIp;
+use crate::config::Config;
 use crate::db::models::{
-    OpenQuestionRecord, ProjectActivityRecord, ProjectQuestionRecord, ProjectSummary,
-    SpecRevisionRecord, SpecSectionRecord, SubtaskRecord, TaskDetails, TaskRecord,
+    BoardColumn, OpenQuestionRecord, ProjectActivityRecord, ProjectQuestionRecord, ProjectSummary,
+    SpecRevisionRecord, SpecSectionRecord, SubtaskRecord, TaskDependencyRecord, TaskDetails,
+    TaskHistoryRecord, TaskRecord, TaskReviewItemRecord, TaskReviewRecord,
 };
 use crate::db::queries;
 use crate::db::queries::{
-    MoveTaskInput, NewTaskInput, TaskFilters, UpdateSubtaskInput, UpdateTaskInput,
+    MoveTaskInput, NewTaskInput, TaskFilters, TaskHistoryFilters, UpdateSubtaskInput,
+    UpdateTaskInput,
 };
 use crate::error::{AppError, AppResult};
+use crate::rate_limit::{self, RateBudget, RateLimiter};
 
 const DEFAULT_LIMIT: i64 = 50;
 const MAX_LIMIT: i64 = 100;
 const DEFAULT_OFFSET: i64 = 0;
 const DEFAULT_RECENT_LIMIT: i64 = 10;
+const DEFAULT_COLUMN_LIMIT: i64 = 25;
 const MAX_RECENT_LIMIT: i64 = 50;
 const MAX_BULK_TASKS: usize = 100;
+const BULK_PROGRESS_EVERY: usize = 10;
 
 #[derive(Debug, Clone)]
 pub struct LatticeMcpServer {
     db: AnyPool,
+    config: Arc<Config>,
+    rate_limiter: RateLimiter,
+    /// Minimum level for `notifications/message`, set by the client via `logging/setLevel`.
+    log_level: Arc<Mutex<LoggingLevel>>,
     tool_router: ToolRouter<Self>,
 }
 
 impl LatticeMcpServer {
-    pub fn new(db: AnyPool) -> Self {
+    pub fn new(db: AnyPool, config: Arc<Config>, rate_limiter: RateLimiter) -> Self {
         Self {
             db,
+            config,
+            rate_limiter,
+            log_level: Arc::new(Mutex::new(LoggingLevel::Info)),
             tool_router: Self::tool_router(),
         }
     }
+
+    /// Normalizes a project slug, follows renames, and hides projects outside the caller's
+    /// workspace.
+    async fn scoped_project_slug(
+        &self,
+        extensions: &Extensions,
+        project: &str,
+    ) -> Result<String, ErrorData> {
+        let slug = normalize_project_slug(project)?;
+        let slug =
+            map_to_mcp(queries::renamed_project_slug(&self.db, &slug).await)?.unwrap_or(slug);
+        if let Some(workspace_id) = scope_from_extensions(extensions).workspace_id() {
+            let owner = map_to_mcp(queries::project_workspace_id(&self.db, &slug).await)?;
+            if owner.as_deref() != Some(workspace_id) {
+                return Err(map_error(AppError::NotFound(format!(
+                    "project '{slug}' not found"
+                ))));
+            }
+        }
+        Ok(slug)
+    }
+
+    /// Rejects top-level tool arguments that are not declared in the tool's input schema.
+    fn reject_unknown_arguments(&self, request: &CallToolRequestParams) -> Result<(), ErrorData> {
+        let Some(arguments) = request.arguments.as_ref() else {
+            return Ok(());
+        };
+        let Some(tool) = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .find(|tool| tool.name == request.name)
+        else {
+            return Ok(());
+        };
+
+        let known = tool
+            .input_schema
+            .get("properties")
+            .and_then(serde_json::Value::as_object);
+        let unknown = arguments
+            .keys()
+            .filter(|key| !known.is_some_and(|properties| properties.contains_key(*key)))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        Err(ErrorData::invalid_params(
+            format!(
+                "unknown argument(s) for {}: {}",
+                request.name,
+                unknown.join(", ")
+            ),
+            None,
+        ))
+    }
+
+    /// Suggests values for `project`, `section`, and `task_ref` arguments; anything else gets none.
+    async fn complete_argument(
+        &self,
+        request: &CompleteRequestParams,
+        extensions: &Extensions,
+    ) -> Result<Vec<String>, ErrorData> {
+        let value = request.argument.value.as_str();
+        let limit = CompletionInfo::MAX_VALUES as i64 + 1;
+        match request.argument.name.as_str() {
+            "project" => map_to_mcp(
+                queries::complete_project_slugs(
+                    &self.db,
+                    scope_from_extensions(extensions).workspace_id(),
+                    value,
+                    limit,
+                )
+                .await,
+            ),
+            "section" => Ok(queries::complete_spec_sections(value)),
+            "task_ref" => {
+                let context_project = request
+                    .context
+                    .as_ref()
+                    .and_then(|context| context.arguments.as_ref())
+                    .and_then(|arguments| arguments.get("project"));
+                let (project, number_prefix) = match (context_project, value.rsplit_once('-')) {
+                    (_, Some((project, number))) => (project, number),
+                    (Some(project), None) => (project.as_str(), value),
+                    (None, None) => return Ok(Vec::new()),
+                };
+                let Ok(slug) = self.scoped_project_slug(extensions, project).await else {
+                    return Ok(Vec::new());
+                };
+                map_to_mcp(queries::complete_task_refs(&self.db, &slug, number_prefix, limit).await)
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Sends a log notification to this session's client if it is at or above the requested level.
+    async fn log_to_client(&self, peer: &Peer<RoleServer>, level: LoggingLevel, message: String) {
+        let threshold = *self
+            .log_level
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if logging_severity(level) < logging_severity(threshold) {
+            return;
+        }
+
+        let notification = LoggingMessageNotificationParam {
+            level,
+            logger: Some("lattice".to_string()),
+            data: serde_json::Value::String(message),
+        };
+        if let Err(error) = peer.notify_logging_message(notification).await {
+            tracing::debug!(error = ?error, "failed to send MCP log notification");
+        }
+    }
+
+    /// Reports bulk progress as a log message and, when the caller sent a `progressToken`,
+    /// as a progress notification on the call's own stream.
+    async fn report_progress(
+        &self,
+        peer: &Peer<RoleServer>,
+        progress_token: Option<&ProgressToken>,
+        done: usize,
+        total: usize,
+        message: String,
+    ) {
+        if let Some(progress_token) = progress_token {
+            let notification = ProgressNotificationParam {
+                progress_token: progress_token.clone(),
+                progress: done as f64,
+                total: Some(total as f64),
+                message: Some(message.clone()),
+            };
+            if let Err(error) = peer.notify_progress(notification).await {
+                tracing::debug!(error = ?error, "failed to send MCP progress notification");
+            }
+        }
+        self.log_to_client(peer, LoggingLevel::Info, message).await;
+    }
+
+    fn stale_cutoff(&self, stale: Option<bool>) -> Result<Option<String>, ErrorData> {
+        if stale != Some(true) {
+            return Ok(None);
+        }
+
+        let Some(days) = self.config.stale_threshold_days() else {
+            return Err(ErrorData::invalid_params(
+                "stale detection is disabled on this server",
+                None,
+            ));
+        };
+        Ok(Some(queries::timestamp_days_ago(days)))
+    }
 }
 
-#[tool_handler(router = self.tool_router)]
 impl ServerHandler for LatticeMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
                 "Lattice MCP server for project, spec, task, and question workflows.".to_string(),
             ),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_completions()
+                .enable_logging()
+                .build(),
             ..Default::default()
         }
     }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if self.config.strict_json {
+            self.reject_unknown_arguments(&request)?;
+        }
+
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, ErrorData> {
+        let mut values = self
+            .complete_argument(&request, &context.extensions)
+            .await?;
+        let has_more = values.len() > CompletionInfo::MAX_VALUES;
+        values.truncate(CompletionInfo::MAX_VALUES);
+        Ok(CompleteResult {
+            completion: CompletionInfo {
+                values,
+                total: None,
+                has_more: Some(has_more),
+            },
+        })
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        *self
+            .log_level
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = request.level;
+        Ok(())
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .filter(|tool| {
+                !(self.config.mcp_disable_project_delete && tool.name == "lattice_delete_project")
+            })
+            .collect();
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
 }
 
 #[tool_router(router = tool_router)]
@@ -61,9 +309,21 @@ impl LatticeMcpServer {
     async fn lattice_list_projects(
         &self,
         Parameters(params): Parameters<ListProjectsInput>,
+        extensions: Extensions,
     ) -> Result<Json<ListProjectsOutput>, ErrorData> {
         let (limit, offset) = normalize_limit_offset(params.limit, params.offset)?;
-        let projects = map_to_mcp(queries::list_projects(&self.db, limit, offset).await)?;
+        let actor = actor_from_extensions(&extensions);
+        let starred_by = params.starred.unwrap_or(false).then_some(actor.as_str());
+        let projects = map_to_mcp(
+            queries::list_projects(
+                &self.db,
+                scope_from_extensions(&extensions).workspace_id(),
+                starred_by,
+                limit,
+                offset,
+            )
+            .await,
+        )?;
         let results = projects.into_iter().map(map_project_summary).collect();
         Ok(Json(ListProjectsOutput { projects: results }))
     }
@@ -75,8 +335,11 @@ impl LatticeMcpServer {
     async fn lattice_get_project(
         &self,
         Parameters(params): Parameters<ProjectInput>,
+        extensions: Extensions,
     ) -> Result<Json<ProjectSummaryOutput>, ErrorData> {
-        let project_slug = normalize_project_slug(&params.project)?;
+        let project_slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let project = map_to_mcp(queries::get_project(&self.db, &project_slug).await)?;
         Ok(Json(map_project_summary(project)))
     }
@@ -101,12 +364,34 @@ impl LatticeMcpServer {
         let goal = params.goal.unwrap_or_default();
         let actor = actor_from_extensions(&extensions);
 
-        map_to_mcp(queries::create_project_with_slug(&self.db, &params.name, &goal, &slug).await)?;
+        let created = match scope_from_extensions(&extensions).workspace_id() {
+            Some(workspace_id) => {
+                queries::create_project_in_workspace(
+                    &self.db,
+                    workspace_id,
+                    &params.name,
+                    &goal,
+                    &slug,
+                )
+                .await
+            }
+            None => queries::create_project_with_slug(&self.db, &params.name, &goal, &slug).await,
+        };
+        map_to_mcp(created)?;
 
         if let Some(initial_spec) = params.initial_spec {
             for (section, content) in initial_spec.into_sections() {
                 map_to_mcp(
-                    queries::update_spec_section(&self.db, &slug, section, &content, &actor).await,
+                    queries::update_spec_section(
+                        &self.db,
+                        &self.config.field_limits,
+                        &slug,
+                        section,
+                        &content,
+                        &actor,
+                        false,
+                    )
+                    .await,
                 )?;
             }
         }
@@ -115,6 +400,40 @@ impl LatticeMcpServer {
         Ok(Json(map_project_summary(project)))
     }
 
+    #[tool(
+        name = "lattice_delete_project",
+        description = "Permanently delete a project with all its tasks, spec, and history. confirm_slug must repeat the project slug exactly."
+    )]
+    async fn lattice_delete_project(
+        &self,
+        Parameters(params): Parameters<DeleteProjectInput>,
+        extensions: Extensions,
+    ) -> Result<Json<DeleteOutput>, ErrorData> {
+        if self.config.mcp_disable_project_delete {
+            return Err(ErrorData::invalid_request(
+                "project deletion is disabled for MCP on this server",
+                None,
+            ));
+        }
+
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        if params.confirm_slug.trim() != slug {
+            return Err(ErrorData::invalid_params(
+                format!("confirm_slug must be '{slug}' to delete this project"),
+                None,
+            ));
+        }
+
+        let actor = actor_from_extensions(&extensions);
+        let client_ip = client_ip_from_extensions(&extensions);
+        map_to_mcp(
+            queries::delete_project(&self.db, &slug, &actor, client_ip.address().as_deref()).await,
+        )?;
+        Ok(Json(DeleteOutput { deleted: true }))
+    }
+
     #[tool(
         name = "lattice_update_goal",
         description = "Update a project's goal text."
@@ -124,7 +443,9 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<UpdateGoalInput>,
         extensions: Extensions,
     ) -> Result<Json<ProjectSummaryOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let updated = map_to_mcp(
             queries::update_project(&self.db, &slug, None, Some(params.goal), &actor).await,
@@ -139,9 +460,14 @@ impl LatticeMcpServer {
     async fn lattice_get_spec(
         &self,
         Parameters(params): Parameters<ProjectInput>,
+        extensions: Extensions,
     ) -> Result<Json<GetSpecOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
-        let sections = map_to_mcp(queries::list_spec_sections(&self.db, &slug).await)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let sections = map_to_mcp(
+            queries::list_spec_sections(&self.db, &self.config.field_limits, &slug).await,
+        )?;
         Ok(Json(GetSpecOutput {
             sections: sections.into_iter().map(map_spec_section).collect(),
         }))
@@ -154,31 +480,115 @@ impl LatticeMcpServer {
     async fn lattice_get_spec_section(
         &self,
         Parameters(params): Parameters<GetSpecSectionInput>,
+        extensions: Extensions,
     ) -> Result<Json<SpecSectionOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
-        let section =
-            map_to_mcp(queries::get_spec_section(&self.db, &slug, &params.section).await)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let section = map_to_mcp(
+            queries::get_spec_section(&self.db, &self.config.field_limits, &slug, &params.section)
+                .await,
+        )?;
         Ok(Json(map_spec_section(section)))
     }
 
+    #[tool(
+        name = "lattice_lint_spec",
+        description = "Check spec sections for missing task refs, dangling /files/ links, and heading problems. Each finding gives the exact text at its line and column, and a fix to replace it with when one is clear."
+    )]
+    async fn lattice_lint_spec(
+        &self,
+        Parameters(params): Parameters<LintSpecInput>,
+        extensions: Extensions,
+    ) -> Result<Json<SpecLintOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let report =
+            map_to_mcp(queries::lint_spec(&self.db, &slug, params.section.as_deref()).await)?;
+        Ok(Json(SpecLintOutput {
+            sections_checked: report.sections_checked,
+            findings: report
+                .findings
+                .into_iter()
+                .map(|finding| SpecLintFindingOutput {
+                    section: finding.section,
+                    line: finding.line,
+                    column: finding.column,
+                    rule: finding.rule.to_string(),
+                    message: finding.message,
+                    text: finding.text,
+                    fix: finding.fix,
+                })
+                .collect(),
+        }))
+    }
+
     #[tool(
         name = "lattice_update_spec_section",
-        description = "Update one spec section and append a revision."
+        description = "Update one spec section and append a revision. Fails while another actor holds the section's lock unless force is true."
     )]
     async fn lattice_update_spec_section(
         &self,
         Parameters(params): Parameters<UpdateSpecSectionInput>,
         extensions: Extensions,
     ) -> Result<Json<SpecSectionOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let section = map_to_mcp(
-            queries::update_spec_section(&self.db, &slug, &params.section, &params.content, &actor)
-                .await,
+            queries::update_spec_section(
+                &self.db,
+                &self.config.field_limits,
+                &slug,
+                &params.section,
+                &params.content,
+                &actor,
+                params.force.unwrap_or(false),
+            )
+            .await,
         )?;
         Ok(Json(map_spec_section(section)))
     }
 
+    #[tool(
+        name = "lattice_lock_spec_section",
+        description = "Take, renew, or release a soft edit lock on a spec section so other actors' updates are refused while you edit. Locks expire after ttl_secs (default 300, max 3600)."
+    )]
+    async fn lattice_lock_spec_section(
+        &self,
+        Parameters(params): Parameters<LockSpecSectionInput>,
+        extensions: Extensions,
+    ) -> Result<Json<SpecSectionOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let section = if params.release.unwrap_or(false) {
+            queries::unlock_spec_section(
+                &self.db,
+                &self.config.field_limits,
+                &slug,
+                &params.section,
+                &actor,
+                false,
+            )
+            .await
+        } else {
+            queries::lock_spec_section(
+                &self.db,
+                &self.config.field_limits,
+                &slug,
+                &params.section,
+                &actor,
+                params.ttl_secs,
+            )
+            .await
+        };
+        Ok(Json(map_spec_section(map_to_mcp(section)?)))
+    }
+
     #[tool(
         name = "lattice_get_spec_history",
         description = "Get revision history for one spec section."
@@ -186,11 +596,22 @@ impl LatticeMcpServer {
     async fn lattice_get_spec_history(
         &self,
         Parameters(params): Parameters<GetSpecHistoryInput>,
+        extensions: Extensions,
     ) -> Result<Json<GetSpecHistoryOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let (limit, offset) = normalize_limit_offset(params.limit, params.offset)?;
         let revisions = map_to_mcp(
-            queries::list_spec_history(&self.db, &slug, &params.section, limit, offset).await,
+            queries::list_spec_history(
+                &self.db,
+                &slug,
+                &params.section,
+                limit,
+                offset,
+                params.include_content.unwrap_or(true),
+            )
+            .await,
         )?;
         Ok(Json(GetSpecHistoryOutput {
             revisions: revisions.into_iter().map(map_spec_revision).collect(),
@@ -199,22 +620,43 @@ impl LatticeMcpServer {
 
     #[tool(
         name = "lattice_list_tasks",
-        description = "List tasks by project, with optional status/label/review filters."
+        description = "List tasks by project. Filters take comma-separated values and support exclusions, priority ranges, creator, and created/updated date ranges."
     )]
     async fn lattice_list_tasks(
         &self,
         Parameters(params): Parameters<ListTasksInput>,
+        extensions: Extensions,
     ) -> Result<Json<ListTasksOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let (limit, offset) = normalize_limit_offset(params.limit, params.offset)?;
         let tasks = map_to_mcp(
             queries::list_tasks(
                 &self.db,
                 &slug,
                 TaskFilters {
-                    status: params.status,
-                    label: params.label,
+                    statuses: queries::parse_filter_list(params.status.as_deref()),
+                    exclude_statuses: queries::parse_filter_list(params.exclude_status.as_deref()),
+                    labels: queries::parse_filter_list(params.label.as_deref()),
+                    exclude_labels: queries::parse_filter_list(params.exclude_label.as_deref()),
+                    priorities: queries::parse_filter_list(params.priority.as_deref()),
+                    min_priority: params.min_priority,
+                    max_priority: params.max_priority,
                     review_state: params.review_state,
+                    created_by: params.created_by,
+                    assignees: queries::parse_filter_list(params.assignee.as_deref()),
+                    due_before: params.due_before,
+                    overdue: params.overdue,
+                    parent: params.parent,
+                    created_after: params.created_after,
+                    created_before: params.created_before,
+                    updated_after: params.updated_after,
+                    updated_before: params.updated_before,
+                    stale_before: self.stale_cutoff(params.stale)?,
+                    archived: params.archived,
+                    include_archived: params.include_archived.unwrap_or(false),
+                    sort: params.sort,
                 },
                 limit,
                 offset,
@@ -235,13 +677,109 @@ impl LatticeMcpServer {
     async fn lattice_get_task(
         &self,
         Parameters(params): Parameters<TaskRefInput>,
+        extensions: Extensions,
     ) -> Result<Json<TaskDetailsOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let details =
             map_to_mcp(queries::get_task_details(&self.db, &slug, &params.task_ref).await)?;
         Ok(Json(map_task_details(&slug, details)))
     }
 
+    #[tool(
+        name = "lattice_get_tasks",
+        description = "Fetch up to 100 tasks by UUID or display key in one call. Unknown refs are returned in missing."
+    )]
+    async fn lattice_get_tasks(
+        &self,
+        Parameters(params): Parameters<GetTasksInput>,
+        extensions: Extensions,
+    ) -> Result<Json<GetTasksOutput>, ErrorData> {
+        if params.task_refs.len() > MAX_BULK_TASKS {
+            return Err(ErrorData::invalid_params(
+                "too many task refs in one call, max is 100",
+                None,
+            ));
+        }
+
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let (tasks, missing) =
+            map_to_mcp(queries::get_tasks_by_refs(&self.db, &slug, &params.task_refs).await)?;
+        Ok(Json(GetTasksOutput {
+            tasks: tasks
+                .into_iter()
+                .map(|task| map_task(&slug, task))
+                .collect(),
+            missing,
+        }))
+    }
+
+    #[tool(
+        name = "lattice_get_task_history",
+        description = "Get one task's history newest-first, filterable by action/actor. Pass next_cursor back as cursor for older entries."
+    )]
+    async fn lattice_get_task_history(
+        &self,
+        Parameters(params): Parameters<TaskHistoryInput>,
+        extensions: Extensions,
+    ) -> Result<Json<TaskHistoryPageOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let (limit, _) = normalize_limit_offset(params.limit, None)?;
+        let page = map_to_mcp(
+            queries::list_task_history(
+                &self.db,
+                &slug,
+                &params.task_ref,
+                TaskHistoryFilters {
+                    action: params.action,
+                    actor: params.actor,
+                },
+                limit,
+                params.cursor.as_deref(),
+            )
+            .await,
+        )?;
+        Ok(Json(TaskHistoryPageOutput {
+            entries: page.entries.into_iter().map(map_task_history).collect(),
+            next_cursor: page.next_cursor,
+        }))
+    }
+
+    #[tool(
+        name = "lattice_preview_attachment",
+        description = "Read the start of a text attachment (logs, CSV, JSON) with a syntax hint instead of downloading it. max_kb defaults to 16, max 256."
+    )]
+    async fn lattice_preview_attachment(
+        &self,
+        Parameters(params): Parameters<PreviewAttachmentInput>,
+        extensions: Extensions,
+    ) -> Result<Json<AttachmentPreviewOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let attachment = map_to_mcp(
+            queries::get_task_attachment(&self.db, &slug, &params.task_ref, &params.attachment_id)
+                .await,
+        )?;
+        let preview =
+            map_to_mcp(read_preview(&self.config.storage_dir, attachment, params.max_kb).await)?;
+        Ok(Json(AttachmentPreviewOutput {
+            id: preview.id,
+            filename: preview.filename,
+            content_type: preview.content_type,
+            size_bytes: preview.size_bytes,
+            syntax: preview.syntax.to_string(),
+            content: preview.content,
+            preview_bytes: preview.preview_bytes,
+            truncated: preview.truncated,
+        }))
+    }
+
     #[tool(
         name = "lattice_create_task",
         description = "Create a task and return its display key."
@@ -251,11 +789,14 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<CreateTaskInput>,
         extensions: Extensions,
     ) -> Result<Json<TaskOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let task = map_to_mcp(
             queries::create_task(
                 &self.db,
+                &self.config.field_limits,
                 &slug,
                 NewTaskInput {
                     title: params.title,
@@ -265,6 +806,8 @@ impl LatticeMcpServer {
                     review_state: params.review_state.unwrap_or_else(|| "ready".to_string()),
                     labels: params.labels,
                     created_by: actor,
+                    assignee: params.assignee,
+                    due_at: params.due_at,
                 },
             )
             .await,
@@ -274,11 +817,13 @@ impl LatticeMcpServer {
 
     #[tool(
         name = "lattice_create_tasks_bulk",
-        description = "Create multiple tasks in one call."
+        description = "Create multiple tasks in one call. Progress is reported through MCP log notifications."
     )]
     async fn lattice_create_tasks_bulk(
         &self,
         Parameters(params): Parameters<CreateTasksBulkInput>,
+        peer: Peer<RoleServer>,
+        meta: Meta,
         extensions: Extensions,
     ) -> Result<Json<ListTasksOutput>, ErrorData> {
         if params.tasks.is_empty() {
@@ -291,13 +836,17 @@ impl LatticeMcpServer {
             ));
         }
 
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
-        let mut created = Vec::with_capacity(params.tasks.len());
+        let total = params.tasks.len();
+        let mut created = Vec::with_capacity(total);
         for task in params.tasks {
             let item = map_to_mcp(
                 queries::create_task(
                     &self.db,
+                    &self.config.field_limits,
                     &slug,
                     NewTaskInput {
                         title: task.title,
@@ -307,11 +856,25 @@ impl LatticeMcpServer {
                         review_state: task.review_state.unwrap_or_else(|| "ready".to_string()),
                         labels: task.labels,
                         created_by: actor.clone(),
+                        assignee: task.assignee,
+                        due_at: task.due_at,
                     },
                 )
                 .await,
             )?;
             created.push(map_task(&slug, item));
+
+            let done = created.len();
+            if done.is_multiple_of(BULK_PROGRESS_EVERY) || done == total {
+                self.report_progress(
+                    &peer,
+                    meta.get_progress_token().as_ref(),
+                    done,
+                    total,
+                    format!("created {done}/{total} tasks in {slug}"),
+                )
+                .await;
+            }
         }
 
         Ok(Json(ListTasksOutput { tasks: created }))
@@ -332,6 +895,8 @@ impl LatticeMcpServer {
             && params.priority.is_none()
             && params.review_state.is_none()
             && params.labels.is_none()
+            && params.assignee.is_none()
+            && params.due_at.is_none()
         {
             return Err(ErrorData::invalid_params(
                 "at least one task field must be provided",
@@ -339,11 +904,14 @@ impl LatticeMcpServer {
             ));
         }
 
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let updated = map_to_mcp(
             queries::update_task(
                 &self.db,
+                &self.config.field_limits,
                 &slug,
                 &params.task_ref,
                 UpdateTaskInput {
@@ -353,6 +921,8 @@ impl LatticeMcpServer {
                     priority: params.priority,
                     review_state: params.review_state,
                     labels: params.labels,
+                    assignee: params.assignee,
+                    due_at: params.due_at,
                     actor,
                 },
             )
@@ -361,6 +931,65 @@ impl LatticeMcpServer {
         Ok(Json(map_task(&slug, updated)))
     }
 
+    #[tool(
+        name = "lattice_update_tasks_bulk",
+        description = "Update up to 100 tasks in one transaction. Each entry is {task_ref, changes}; if any entry fails, no task is changed."
+    )]
+    async fn lattice_update_tasks_bulk(
+        &self,
+        Parameters(params): Parameters<UpdateTasksBulkInput>,
+        extensions: Extensions,
+    ) -> Result<Json<ListTasksOutput>, ErrorData> {
+        if let Some(empty) = params
+            .updates
+            .iter()
+            .find(|update| update.changes.is_empty())
+        {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "changes for '{}' must set at least one task field",
+                    empty.task_ref
+                ),
+                None,
+            ));
+        }
+
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let updates = params
+            .updates
+            .into_iter()
+            .map(|update| {
+                let changes = update.changes;
+                (
+                    update.task_ref,
+                    UpdateTaskInput {
+                        title: changes.title,
+                        description: changes.description,
+                        status: changes.status,
+                        priority: changes.priority,
+                        review_state: changes.review_state,
+                        labels: changes.labels,
+                        assignee: changes.assignee,
+                        due_at: changes.due_at,
+                        actor: actor.clone(),
+                    },
+                )
+            })
+            .collect();
+        let updated = map_to_mcp(
+            queries::update_tasks_bulk(&self.db, &self.config.field_limits, &slug, updates).await,
+        )?;
+        Ok(Json(ListTasksOutput {
+            tasks: updated
+                .into_iter()
+                .map(|task| map_task(&slug, task))
+                .collect(),
+        }))
+    }
+
     #[tool(
         name = "lattice_move_task",
         description = "Move a task to another board status and optional sort position."
@@ -370,7 +999,9 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<MoveTaskToolInput>,
         extensions: Extensions,
     ) -> Result<Json<TaskOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let moved = map_to_mcp(
             queries::move_task(
@@ -391,17 +1022,32 @@ impl LatticeMcpServer {
 
     #[tool(
         name = "lattice_delete_task",
-        description = "Delete a task by UUID or display key."
+        description = "Delete a task by UUID or display key. Deleting archives the task: it leaves the board and lists but keeps its history, and can be restored over the REST API. Without confirm=true this only previews what would be deleted; show the preview to the user, then call again with confirm=true."
     )]
     async fn lattice_delete_task(
         &self,
-        Parameters(params): Parameters<TaskRefInput>,
+        Parameters(params): Parameters<DeleteTaskInput>,
         extensions: Extensions,
-    ) -> Result<Json<DeleteOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
-        let actor = actor_from_extensions(&extensions);
-        map_to_mcp(queries::delete_task(&self.db, &slug, &params.task_ref, &actor).await)?;
-        Ok(Json(DeleteOutput { deleted: true }))
+    ) -> Result<Json<DeleteTaskOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let details =
+            map_to_mcp(queries::get_task_details(&self.db, &slug, &params.task_ref).await)?;
+        let confirmed = params.confirm == Some(true);
+        if confirmed {
+            let actor = actor_from_extensions(&extensions);
+            map_to_mcp(queries::archive_task(&self.db, &slug, &details.task.id, &actor).await)?;
+        }
+
+        Ok(Json(DeleteTaskOutput {
+            deleted: confirmed,
+            confirmation_required: !confirmed,
+            subtask_count: details.subtasks.len(),
+            attachment_count: details.attachments.len(),
+            open_question_count: details.open_questions.len(),
+            task: map_task(&slug, details.task),
+        }))
     }
 
     #[tool(name = "lattice_add_subtask", description = "Add a subtask to a task.")]
@@ -410,10 +1056,20 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<AddSubtaskInput>,
         extensions: Extensions,
     ) -> Result<Json<SubtaskOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let subtask = map_to_mcp(
-            queries::add_subtask(&self.db, &slug, &params.task_ref, &params.title, &actor).await,
+            queries::add_subtask(
+                &self.db,
+                &self.config.field_limits,
+                &slug,
+                &params.task_ref,
+                &params.title,
+                &actor,
+            )
+            .await,
         )?;
         Ok(Json(map_subtask(subtask)))
     }
@@ -434,11 +1090,14 @@ impl LatticeMcpServer {
             ));
         }
 
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let subtask = map_to_mcp(
             queries::update_subtask(
                 &self.db,
+                &self.config.field_limits,
                 &slug,
                 &params.task_ref,
                 &params.subtask_id,
@@ -463,7 +1122,9 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<DeleteSubtaskInput>,
         extensions: Extensions,
     ) -> Result<Json<DeleteOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         map_to_mcp(
             queries::delete_subtask(
@@ -485,8 +1146,11 @@ impl LatticeMcpServer {
     async fn lattice_list_open_questions(
         &self,
         Parameters(params): Parameters<ListOpenQuestionsInput>,
+        extensions: Extensions,
     ) -> Result<Json<ListOpenQuestionsOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let (limit, offset) = normalize_limit_offset(params.limit, params.offset)?;
         let questions =
             map_to_mcp(queries::list_project_open_questions(&self.db, &slug, limit, offset).await)?;
@@ -506,11 +1170,14 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<AskQuestionInput>,
         extensions: Extensions,
     ) -> Result<Json<TaskOpenQuestionOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let question = map_to_mcp(
             queries::create_open_question(
                 &self.db,
+                &self.config.field_limits,
                 &slug,
                 &params.task_ref,
                 &params.question,
@@ -531,11 +1198,14 @@ impl LatticeMcpServer {
         Parameters(params): Parameters<AnswerQuestionInput>,
         extensions: Extensions,
     ) -> Result<Json<TaskOpenQuestionOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
         let actor = actor_from_extensions(&extensions);
         let answered = map_to_mcp(
             queries::answer_open_question(
                 &self.db,
+                &self.config.field_limits,
                 &slug,
                 &params.task_ref,
                 &params.question_id,
@@ -548,29 +1218,189 @@ impl LatticeMcpServer {
     }
 
     #[tool(
-        name = "lattice_board_summary",
-        description = "Return a compact board summary with counts and recent activity."
+        name = "lattice_review_answer",
+        description = "Accept the answer to a question you asked, or dispute it with a reason. Disputing reopens the question. Emits question.accepted or question.disputed."
     )]
-    async fn lattice_board_summary(
+    async fn lattice_review_answer(
         &self,
-        Parameters(params): Parameters<BoardSummaryInput>,
-    ) -> Result<Json<BoardSummaryOutput>, ErrorData> {
-        let slug = normalize_project_slug(&params.project)?;
-        let recent_limit = normalize_recent_limit(params.recent_limit)?;
-        let project = map_to_mcp(queries::get_project(&self.db, &slug).await)?;
-        let activity =
-            map_to_mcp(queries::list_recent_project_activity(&self.db, &slug, recent_limit).await)?;
+        Parameters(params): Parameters<ReviewAnswerInput>,
+        extensions: Extensions,
+    ) -> Result<Json<TaskOpenQuestionOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let reviewed = map_to_mcp(
+            queries::review_question_answer(
+                &self.db,
+                &self.config.field_limits,
+                &slug,
+                &params.task_ref,
+                &params.question_id,
+                queries::ReviewAnswerInput {
+                    verdict: params.verdict,
+                    reason: params.reason,
+                    actor,
+                },
+            )
+            .await,
+        )?;
+        Ok(Json(map_task_open_question(reviewed)))
+    }
 
-        Ok(Json(BoardSummaryOutput {
-            project: map_project(project.project),
-            counts: BoardCountsOutput {
-                backlog: project.backlog_count,
+    #[tool(
+        name = "lattice_request_review",
+        description = "Ask a named actor to review a task. Emits review.requested."
+    )]
+    async fn lattice_request_review(
+        &self,
+        Parameters(params): Parameters<RequestReviewInput>,
+        extensions: Extensions,
+    ) -> Result<Json<TaskReviewOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let review = map_to_mcp(
+            queries::request_review(&self.db, &slug, &params.task_ref, &params.reviewer, &actor)
+                .await,
+        )?;
+        Ok(Json(map_task_review(review)))
+    }
+
+    #[tool(
+        name = "lattice_submit_review",
+        description = "Record an approved or changes_requested verdict on a pending review, with an optional comment. Emits review.completed."
+    )]
+    async fn lattice_submit_review(
+        &self,
+        Parameters(params): Parameters<SubmitReviewInput>,
+        extensions: Extensions,
+    ) -> Result<Json<TaskReviewOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let review = map_to_mcp(
+            queries::complete_review(
+                &self.db,
+                &slug,
+                &params.task_ref,
+                &params.review_id,
+                &params.verdict,
+                params.comment,
+                &actor,
+            )
+            .await,
+        )?;
+        Ok(Json(map_task_review(review)))
+    }
+
+    #[tool(
+        name = "lattice_update_review_item",
+        description = "Check or uncheck a task's review checklist item. Open items block moving to done unless the project disables it."
+    )]
+    async fn lattice_update_review_item(
+        &self,
+        Parameters(params): Parameters<UpdateReviewItemInput>,
+        extensions: Extensions,
+    ) -> Result<Json<ReviewItemOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let actor = actor_from_extensions(&extensions);
+        let item = map_to_mcp(
+            queries::set_review_item_done(
+                &self.db,
+                &slug,
+                &params.task_ref,
+                &params.item_id,
+                params.done,
+                &actor,
+            )
+            .await,
+        )?;
+        Ok(Json(map_review_item(item)))
+    }
+
+    #[tool(
+        name = "lattice_get_board",
+        description = "Return every board column, in the project's configured order and with its color, with its tasks in board order (key, title, priority, labels, review_state), up to column_limit tasks per column. Pass group_by (priority or label) to also get swimlanes, each with its own columns."
+    )]
+    async fn lattice_get_board(
+        &self,
+        Parameters(params): Parameters<GetBoardInput>,
+        extensions: Extensions,
+    ) -> Result<Json<BoardOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let column_limit = params.column_limit.unwrap_or(DEFAULT_COLUMN_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&column_limit) {
+            return Err(ErrorData::invalid_params(
+                "column_limit must be between 1 and 100",
+                None,
+            ));
+        }
+
+        let swimlanes = map_to_mcp(
+            params
+                .group_by
+                .as_deref()
+                .map(queries::Swimlanes::parse)
+                .transpose(),
+        )?;
+
+        let columns = map_to_mcp(queries::get_board(&self.db, &slug, column_limit).await)?;
+        let lanes = match swimlanes {
+            Some(swimlanes) => Some(map_to_mcp(
+                queries::get_board_lanes(&self.db, &slug, swimlanes, column_limit).await,
+            )?),
+            None => None,
+        };
+        Ok(Json(BoardOutput {
+            project: slug,
+            columns: columns.into_iter().map(map_board_column).collect(),
+            lanes: lanes.map(|lanes| {
+                lanes
+                    .into_iter()
+                    .map(|lane| BoardLaneOutput {
+                        key: lane.key,
+                        columns: lane.columns.into_iter().map(map_board_column).collect(),
+                    })
+                    .collect()
+            }),
+        }))
+    }
+
+    #[tool(
+        name = "lattice_board_summary",
+        description = "Return a compact board summary with counts and recent activity."
+    )]
+    async fn lattice_board_summary(
+        &self,
+        Parameters(params): Parameters<BoardSummaryInput>,
+        extensions: Extensions,
+    ) -> Result<Json<BoardSummaryOutput>, ErrorData> {
+        let slug = self
+            .scoped_project_slug(&extensions, &params.project)
+            .await?;
+        let recent_limit = normalize_recent_limit(params.recent_limit)?;
+        let project = map_to_mcp(queries::get_project(&self.db, &slug).await)?;
+        let activity =
+            map_to_mcp(queries::list_recent_project_activity(&self.db, &slug, recent_limit).await)?;
+
+        Ok(Json(BoardSummaryOutput {
+            project: map_project(project.project),
+            counts: BoardCountsOutput {
+                backlog: project.backlog_count,
                 ready: project.ready_count,
                 in_progress: project.in_progress_count,
                 review: project.review_count,
                 done: project.done_count,
             },
             open_question_count: project.open_question_count,
+            disputed_question_count: project.disputed_question_count,
             not_ready_count: project.not_ready_count,
             recent_activity: activity
                 .into_iter()
@@ -578,10 +1408,32 @@ impl LatticeMcpServer {
                 .collect(),
         }))
     }
+
+    #[tool(
+        name = "lattice_get_limits",
+        description = "Report the caller's remaining rate limit budget per scope, plus in-flight and SSE stream usage. Checking does not spend write budget; it counts as one MCP read."
+    )]
+    async fn lattice_get_limits(
+        &self,
+        extensions: Extensions,
+    ) -> Result<Json<LimitsOutput>, ErrorData> {
+        let headers = extensions
+            .get::<Parts>()
+            .map(|parts| parts.headers.clone())
+            .unwrap_or_default();
+        let identity = rate_limit::request_identity(
+            &headers,
+            client_ip_from_extensions(&extensions).0,
+            self.config.auth_enabled(),
+        );
+        Ok(Json(map_rate_budget(self.rate_limiter.budget(&identity))))
+    }
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct ListProjectsInput {
+    /// Only projects starred by this MCP key or client.
+    starred: Option<bool>,
     limit: Option<i64>,
     offset: Option<i64>,
 }
@@ -600,6 +1452,13 @@ struct CreateProjectInput {
     initial_spec: Option<InitialSpecInput>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct DeleteProjectInput {
+    project: String,
+    /// Must repeat the project slug exactly, e.g. "ROADMAP".
+    confirm_slug: String,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct InitialSpecInput {
     overview: Option<String>,
@@ -647,11 +1506,30 @@ struct GetSpecSectionInput {
     section: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct LintSpecInput {
+    project: String,
+    /// Lint only this section; all sections when omitted.
+    section: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct UpdateSpecSectionInput {
     project: String,
     section: String,
     content: String,
+    /// Overwrite even if another actor holds the section's lock.
+    force: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct LockSpecSectionInput {
+    project: String,
+    section: String,
+    /// Seconds until the lock expires; default 300, max 3600.
+    ttl_secs: Option<i64>,
+    /// Release your lock instead of taking it.
+    release: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -660,14 +1538,53 @@ struct GetSpecHistoryInput {
     section: String,
     limit: Option<i64>,
     offset: Option<i64>,
+    /// Set to false to get only each revision's change summary, without its full text.
+    include_content: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct ListTasksInput {
     project: String,
+    /// One status or a comma-separated list, e.g. "ready,in_progress".
     status: Option<String>,
+    /// Comma-separated statuses to leave out.
+    exclude_status: Option<String>,
+    /// Matches tasks carrying any of the comma-separated labels.
     label: Option<String>,
+    /// Leaves out tasks carrying any of the comma-separated labels.
+    exclude_label: Option<String>,
+    /// Comma-separated priorities.
+    priority: Option<String>,
+    /// Inclusive lower bound, e.g. "high" matches high and critical.
+    min_priority: Option<String>,
+    /// Inclusive upper bound.
+    max_priority: Option<String>,
     review_state: Option<String>,
+    created_by: Option<String>,
+    /// Comma-separated actors; "none" matches unassigned tasks.
+    assignee: Option<String>,
+    /// RFC3339 timestamp or YYYY-MM-DD, exclusive.
+    due_before: Option<String>,
+    /// true keeps only unfinished tasks past their due date; false leaves them out.
+    overdue: Option<bool>,
+    /// Task ref whose children to list; "none" matches tasks without a parent.
+    parent: Option<String>,
+    /// RFC3339 timestamp or YYYY-MM-DD, inclusive.
+    created_after: Option<String>,
+    /// RFC3339 timestamp or YYYY-MM-DD, exclusive.
+    created_before: Option<String>,
+    /// RFC3339 timestamp or YYYY-MM-DD, inclusive.
+    updated_after: Option<String>,
+    /// RFC3339 timestamp or YYYY-MM-DD, exclusive.
+    updated_before: Option<String>,
+    /// Only in-progress tasks untouched for the server's staleness threshold.
+    stale: Option<bool>,
+    /// true lists only archived tasks; by default they are left out.
+    archived: Option<bool>,
+    /// true lists archived tasks alongside the rest.
+    include_archived: Option<bool>,
+    /// priority, created_at, updated_at, or task_number, optionally suffixed with :asc or :desc.
+    sort: Option<String>,
     limit: Option<i64>,
     offset: Option<i64>,
 }
@@ -678,6 +1595,39 @@ struct TaskRefInput {
     task_ref: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct DeleteTaskInput {
+    project: String,
+    task_ref: String,
+    /// Must be true to actually delete; otherwise the call only returns a preview.
+    confirm: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct GetTasksInput {
+    project: String,
+    /// Task UUIDs or display keys such as "LATTICE-3".
+    task_refs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct TaskHistoryInput {
+    project: String,
+    task_ref: String,
+    action: Option<String>,
+    actor: Option<String>,
+    limit: Option<i64>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct PreviewAttachmentInput {
+    project: String,
+    task_ref: String,
+    attachment_id: String,
+    max_kb: Option<usize>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct CreateTaskInput {
     project: String,
@@ -688,6 +1638,10 @@ struct CreateTaskInput {
     review_state: Option<String>,
     #[serde(default)]
     labels: Vec<String>,
+    /// Actor responsible for the task.
+    assignee: Option<String>,
+    /// RFC3339 timestamp, e.g. "2026-05-01T17:00:00Z".
+    due_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -705,6 +1659,8 @@ struct CreateTaskBulkItem {
     review_state: Option<String>,
     #[serde(default)]
     labels: Vec<String>,
+    assignee: Option<String>,
+    due_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -717,6 +1673,49 @@ struct UpdateTaskToolInput {
     priority: Option<String>,
     review_state: Option<String>,
     labels: Option<Vec<String>>,
+    /// Actor responsible for the task; an empty string unassigns it.
+    assignee: Option<String>,
+    /// RFC3339 timestamp; an empty string clears the due date.
+    due_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct UpdateTasksBulkInput {
+    project: String,
+    updates: Vec<BulkTaskUpdate>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct BulkTaskUpdate {
+    task_ref: String,
+    changes: TaskChangesInput,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct TaskChangesInput {
+    title: Option<String>,
+    description: Option<String>,
+    status: Option<String>,
+    priority: Option<String>,
+    review_state: Option<String>,
+    labels: Option<Vec<String>>,
+    /// An empty string unassigns the task.
+    assignee: Option<String>,
+    /// An empty string clears the due date.
+    due_at: Option<String>,
+}
+
+impl TaskChangesInput {
+    fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.status.is_none()
+            && self.priority.is_none()
+            && self.review_state.is_none()
+            && self.labels.is_none()
+            && self.assignee.is_none()
+            && self.due_at.is_none()
+    }
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -774,17 +1773,109 @@ struct AnswerQuestionInput {
     answer: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct ReviewAnswerInput {
+    project: String,
+    task_ref: String,
+    question_id: String,
+    /// "accepted" or "disputed".
+    verdict: String,
+    /// Why the answer does not settle the question; required when disputing.
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct RequestReviewInput {
+    project: String,
+    task_ref: String,
+    /// Actor id of the reviewer, e.g. "human" or an agent's MCP-Client name.
+    reviewer: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct SubmitReviewInput {
+    project: String,
+    task_ref: String,
+    review_id: String,
+    /// "approved" or "changes_requested".
+    verdict: String,
+    comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct UpdateReviewItemInput {
+    project: String,
+    task_ref: String,
+    item_id: String,
+    done: bool,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 struct BoardSummaryInput {
     project: String,
     recent_limit: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct GetBoardInput {
+    project: String,
+    /// Tasks returned per column, 1-100 (default 25). Column totals are always exact.
+    column_limit: Option<i64>,
+    /// `priority` or `label` to also return the board split into swimlanes.
+    group_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct BoardOutput {
+    project: String,
+    columns: Vec<BoardColumnOutput>,
+    /// Present with `group_by`: each lane's tasks in the same columns, with lane totals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lanes: Option<Vec<BoardLaneOutput>>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct BoardLaneOutput {
+    /// The lane's priority or label; null for unlabeled tasks.
+    key: Option<String>,
+    columns: Vec<BoardColumnOutput>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct BoardColumnOutput {
+    status: String,
+    /// `#rrggbb`, when the project sets one for this column.
+    color: Option<String>,
+    total: i64,
+    truncated: bool,
+    tasks: Vec<BoardCardOutput>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct BoardCardOutput {
+    id: String,
+    display_key: String,
+    title: String,
+    priority: String,
+    review_state: String,
+    labels: Vec<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct DeleteOutput {
     deleted: bool,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct DeleteTaskOutput {
+    deleted: bool,
+    confirmation_required: bool,
+    task: TaskOutput,
+    subtask_count: usize,
+    attachment_count: usize,
+    open_question_count: usize,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct ListProjectsOutput {
     projects: Vec<ProjectSummaryOutput>,
@@ -799,6 +1890,7 @@ struct ProjectSummaryOutput {
     review_count: i64,
     done_count: i64,
     open_question_count: i64,
+    disputed_question_count: i64,
     not_ready_count: i64,
 }
 
@@ -824,6 +1916,30 @@ struct SpecSectionOutput {
     section: String,
     content: String,
     updated_at: String,
+    locked_by: Option<String>,
+    lock_expires_at: Option<String>,
+    word_count: usize,
+    char_count: usize,
+    max_length: usize,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct SpecLintOutput {
+    sections_checked: usize,
+    findings: Vec<SpecLintFindingOutput>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct SpecLintFindingOutput {
+    section: String,
+    /// 1-based line and column (in characters) where `text` starts.
+    line: usize,
+    column: usize,
+    rule: String,
+    message: String,
+    text: String,
+    /// Replacement for `text`, when the fix is unambiguous.
+    fix: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -835,9 +1951,13 @@ struct GetSpecHistoryOutput {
 struct SpecRevisionOutput {
     id: String,
     section: String,
-    content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
     edited_by: String,
     created_at: String,
+    bytes_added: i64,
+    bytes_removed: i64,
+    headings_touched: Vec<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -845,6 +1965,12 @@ struct ListTasksOutput {
     tasks: Vec<TaskOutput>,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct GetTasksOutput {
+    tasks: Vec<TaskOutput>,
+    missing: Vec<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct TaskOutput {
     id: String,
@@ -859,6 +1985,11 @@ struct TaskOutput {
     created_by: String,
     created_at: String,
     updated_at: String,
+    archived_at: Option<String>,
+    assignee: Option<String>,
+    due_at: Option<String>,
+    parent_task_id: Option<String>,
+    milestone_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -867,8 +1998,64 @@ struct TaskDetailsOutput {
     labels: Vec<String>,
     subtasks: Vec<SubtaskOutput>,
     open_questions: Vec<TaskOpenQuestionOutput>,
+    reviews: Vec<TaskReviewOutput>,
+    review_checklist: Vec<ReviewItemOutput>,
     attachments: Vec<AttachmentOutput>,
     history: Vec<TaskHistoryOutput>,
+    backlinks: Vec<TaskBacklinkOutput>,
+    mentions: Vec<TaskMentionOutput>,
+    /// Tasks that must be done before this one.
+    blocked_by: Vec<TaskDependencyOutput>,
+    /// Tasks waiting on this one.
+    blocks: Vec<TaskDependencyOutput>,
+    /// Tasks filed under this one, with how many are done.
+    children: TaskChildrenOutput,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskChildrenOutput {
+    total: i64,
+    done: i64,
+    tasks: Vec<ChildTaskOutput>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ChildTaskOutput {
+    task_id: String,
+    display_key: String,
+    title: String,
+    status: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskDependencyOutput {
+    task_id: String,
+    display_key: String,
+    title: String,
+    status: String,
+    created_by: String,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskBacklinkOutput {
+    task_id: String,
+    display_key: String,
+    title: String,
+    status: String,
+    source_kind: String,
+    source_id: String,
+    created_by: String,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskMentionOutput {
+    actor: String,
+    source_kind: String,
+    source_id: String,
+    mentioned_by: String,
+    created_at: String,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -889,12 +2076,37 @@ struct TaskOpenQuestionOutput {
     context: String,
     answer: Option<String>,
     status: String,
+    acceptance: String,
+    dispute_reason: Option<String>,
     asked_by: String,
     resolved_by: Option<String>,
     created_at: String,
     resolved_at: Option<String>,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskReviewOutput {
+    id: String,
+    task_id: String,
+    reviewer: String,
+    requested_by: String,
+    status: String,
+    comment: Option<String>,
+    completed_by: Option<String>,
+    created_at: String,
+    completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ReviewItemOutput {
+    id: String,
+    task_id: String,
+    title: String,
+    done: bool,
+    completed_by: Option<String>,
+    completed_at: Option<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct ProjectOpenQuestionOutput {
     id: String,
@@ -905,6 +2117,8 @@ struct ProjectOpenQuestionOutput {
     context: String,
     answer: Option<String>,
     status: String,
+    acceptance: String,
+    dispute_reason: Option<String>,
     asked_by: String,
     resolved_by: Option<String>,
     created_at: String,
@@ -926,24 +2140,74 @@ struct AttachmentOutput {
     created_at: String,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct AttachmentPreviewOutput {
+    id: String,
+    filename: String,
+    content_type: String,
+    size_bytes: i64,
+    /// Highlighting hint such as `json`, `csv`, `log`, or `plaintext`.
+    syntax: String,
+    content: String,
+    preview_bytes: usize,
+    /// True when the file goes on past `content`.
+    truncated: bool,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct TaskHistoryOutput {
     id: String,
     actor: String,
+    actor_display_name: Option<String>,
     action: String,
     detail: String,
     created_at: String,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct TaskHistoryPageOutput {
+    entries: Vec<TaskHistoryOutput>,
+    next_cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct BoardSummaryOutput {
     project: ProjectOutput,
     counts: BoardCountsOutput,
     open_question_count: i64,
+    disputed_question_count: i64,
     not_ready_count: i64,
     recent_activity: Vec<RecentActivityOutput>,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct LimitsOutput {
+    /// `token:<hash prefix>` when auth is on, otherwise `ip:<client ip>`. Callers sharing it share a budget.
+    identity: String,
+    scopes: Vec<ScopeLimitOutput>,
+    in_flight: CapacityOutput,
+    sse_streams: CapacityOutput,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct ScopeLimitOutput {
+    /// read, write, attachment, webhook_test, mcp_read, mcp_write, or sse_connect.
+    scope: String,
+    /// Requests refilled per minute.
+    limit_per_minute: u32,
+    burst: u32,
+    /// Requests that can be made right now before a 429.
+    remaining: u32,
+    /// Seconds until the budget is full again.
+    reset_after_secs: u64,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+struct CapacityOutput {
+    current: u32,
+    max: u32,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 struct BoardCountsOutput {
     backlog: i64,
@@ -960,6 +2224,7 @@ struct RecentActivityOutput {
     task_display_key: String,
     action: String,
     actor: String,
+    actor_display_name: Option<String>,
     created_at: String,
 }
 
@@ -972,11 +2237,32 @@ fn map_error(error: AppError) -> ErrorData {
         AppError::BadRequest(message) => ErrorData::invalid_params(message, None),
         AppError::NotFound(message) => ErrorData::resource_not_found(message, None),
         AppError::Conflict(message) => ErrorData::invalid_request(message, None),
+        AppError::SlugTaken {
+            message,
+            suggestions,
+        } => ErrorData::invalid_request(
+            message,
+            Some(serde_json::json!({ "suggestions": suggestions })),
+        ),
         AppError::Unauthorized => ErrorData::invalid_request("unauthorized", None),
+        AppError::Forbidden(message) => ErrorData::invalid_request(message, None),
         AppError::Internal => ErrorData::internal_error("unexpected error", None),
     }
 }
 
+fn logging_severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
 fn normalize_project_slug(project: &str) -> Result<String, ErrorData> {
     queries::normalize_slug(project).map_err(map_error)
 }
@@ -1013,9 +2299,29 @@ fn normalize_recent_limit(limit: Option<i64>) -> Result<i64, ErrorData> {
     Ok(normalized)
 }
 
-fn actor_from_extensions(extensions: &Extensions) -> String {
+fn scope_from_extensions(extensions: &Extensions) -> AccessScope {
+    extensions
+        .get::<Parts>()
+        .and_then(|parts| parts.extensions.get::<AccessScope>())
+        .cloned()
+        .unwrap_or(AccessScope::Instance)
+}
+
+fn client_ip_from_extensions(extensions: &Extensions) -> ClientIp {
     extensions
         .get::<Parts>()
+        .and_then(|parts| parts.extensions.get::<ClientIp>())
+        .copied()
+        .unwrap_or_default()
+}
+
+fn actor_from_extensions(extensions: &Extensions) -> String {
+    let parts = extensions.get::<Parts>();
+    if let Some(McpIdentity(name)) = parts.and_then(|parts| parts.extensions.get::<McpIdentity>()) {
+        return name.clone();
+    }
+
+    parts
         .and_then(|parts| parts.headers.get("MCP-Client"))
         .and_then(|value| value.to_str().ok())
         .map(str::trim)
@@ -1024,6 +2330,52 @@ fn actor_from_extensions(extensions: &Extensions) -> String {
         .unwrap_or_else(|| "agent".to_string())
 }
 
+fn map_rate_budget(value: RateBudget) -> LimitsOutput {
+    LimitsOutput {
+        identity: value.identity,
+        scopes: value
+            .scopes
+            .into_iter()
+            .map(|scope| ScopeLimitOutput {
+                scope: scope.scope.as_str().to_string(),
+                limit_per_minute: scope.limit,
+                burst: scope.burst,
+                remaining: scope.remaining,
+                reset_after_secs: scope.reset_after_secs,
+            })
+            .collect(),
+        in_flight: CapacityOutput {
+            current: value.in_flight,
+            max: value.max_in_flight,
+        },
+        sse_streams: CapacityOutput {
+            current: value.sse_streams,
+            max: value.max_sse_streams,
+        },
+    }
+}
+
+fn map_board_column(value: BoardColumn) -> BoardColumnOutput {
+    BoardColumnOutput {
+        truncated: value.total > value.tasks.len() as i64,
+        status: value.status,
+        color: value.color,
+        total: value.total,
+        tasks: value
+            .tasks
+            .into_iter()
+            .map(|card| BoardCardOutput {
+                id: card.id,
+                display_key: card.display_key,
+                title: card.title,
+                priority: card.priority,
+                review_state: card.review_state,
+                labels: card.labels,
+            })
+            .collect(),
+    }
+}
+
 fn map_project_summary(value: ProjectSummary) -> ProjectSummaryOutput {
     ProjectSummaryOutput {
         project: map_project(value.project),
@@ -1033,6 +2385,7 @@ fn map_project_summary(value: ProjectSummary) -> ProjectSummaryOutput {
         review_count: value.review_count,
         done_count: value.done_count,
         open_question_count: value.open_question_count,
+        disputed_question_count: value.disputed_question_count,
         not_ready_count: value.not_ready_count,
     }
 }
@@ -1055,6 +2408,11 @@ fn map_spec_section(value: SpecSectionRecord) -> SpecSectionOutput {
         section: value.section,
         content: value.content,
         updated_at: value.updated_at,
+        locked_by: value.locked_by,
+        lock_expires_at: value.lock_expires_at,
+        word_count: value.word_count,
+        char_count: value.char_count,
+        max_length: value.max_length,
     }
 }
 
@@ -1065,6 +2423,9 @@ fn map_spec_revision(value: SpecRevisionRecord) -> SpecRevisionOutput {
         content: value.content,
         edited_by: value.edited_by,
         created_at: value.created_at,
+        bytes_added: value.bytes_added,
+        bytes_removed: value.bytes_removed,
+        headings_touched: value.headings_touched,
     }
 }
 
@@ -1082,6 +2443,11 @@ fn map_task(project_slug: &str, value: TaskRecord) -> TaskOutput {
         created_by: value.created_by,
         created_at: value.created_at,
         updated_at: value.updated_at,
+        archived_at: value.archived_at,
+        assignee: value.assignee,
+        due_at: value.due_at,
+        parent_task_id: value.parent_task_id,
+        milestone_id: value.milestone_id,
     }
 }
 
@@ -1095,6 +2461,12 @@ fn map_task_details(project_slug: &str, value: TaskDetails) -> TaskDetailsOutput
             .into_iter()
             .map(map_task_open_question)
             .collect(),
+        reviews: value.reviews.into_iter().map(map_task_review).collect(),
+        review_checklist: value
+            .review_checklist
+            .into_iter()
+            .map(map_review_item)
+            .collect(),
         attachments: value
             .attachments
             .into_iter()
@@ -1107,17 +2479,70 @@ fn map_task_details(project_slug: &str, value: TaskDetails) -> TaskDetailsOutput
                 created_at: attachment.created_at,
             })
             .collect(),
-        history: value
-            .history
+        history: value.history.into_iter().map(map_task_history).collect(),
+        backlinks: value
+            .backlinks
+            .into_iter()
+            .map(|backlink| TaskBacklinkOutput {
+                task_id: backlink.task_id,
+                display_key: backlink.display_key,
+                title: backlink.title,
+                status: backlink.status,
+                source_kind: backlink.source_kind,
+                source_id: backlink.source_id,
+                created_by: backlink.created_by,
+                created_at: backlink.created_at,
+            })
+            .collect(),
+        mentions: value
+            .mentions
             .into_iter()
-            .map(|history| TaskHistoryOutput {
-                id: history.id,
-                actor: history.actor,
-                action: history.action,
-                detail: history.detail,
-                created_at: history.created_at,
+            .map(|mention| TaskMentionOutput {
+                actor: mention.actor,
+                source_kind: mention.source_kind,
+                source_id: mention.source_id,
+                mentioned_by: mention.mentioned_by,
+                created_at: mention.created_at,
             })
             .collect(),
+        blocked_by: value
+            .dependencies
+            .blocked_by
+            .into_iter()
+            .map(map_task_dependency)
+            .collect(),
+        blocks: value
+            .dependencies
+            .blocks
+            .into_iter()
+            .map(map_task_dependency)
+            .collect(),
+        children: TaskChildrenOutput {
+            total: value.children.total,
+            done: value.children.done,
+            tasks: value
+                .children
+                .tasks
+                .into_iter()
+                .map(|child| ChildTaskOutput {
+                    task_id: child.task_id,
+                    display_key: child.display_key,
+                    title: child.title,
+                    status: child.status,
+                })
+                .collect(),
+        },
+    }
+}
+
+fn map_task_dependency(value: TaskDependencyRecord) -> TaskDependencyOutput {
+    TaskDependencyOutput {
+        task_id: value.task_id,
+        display_key: value.display_key,
+        title: value.title,
+        status: value.status,
+        created_by: value.created_by,
+        created_at: value.created_at,
     }
 }
 
@@ -1140,6 +2565,8 @@ fn map_task_open_question(value: OpenQuestionRecord) -> TaskOpenQuestionOutput {
         context: value.context,
         answer: value.answer,
         status: value.status,
+        acceptance: value.acceptance,
+        dispute_reason: value.dispute_reason,
         asked_by: value.asked_by,
         resolved_by: value.resolved_by,
         created_at: value.created_at,
@@ -1147,6 +2574,31 @@ fn map_task_open_question(value: OpenQuestionRecord) -> TaskOpenQuestionOutput {
     }
 }
 
+fn map_task_review(value: TaskReviewRecord) -> TaskReviewOutput {
+    TaskReviewOutput {
+        id: value.id,
+        task_id: value.task_id,
+        reviewer: value.reviewer,
+        requested_by: value.requested_by,
+        status: value.status,
+        comment: value.comment,
+        completed_by: value.completed_by,
+        created_at: value.created_at,
+        completed_at: value.completed_at,
+    }
+}
+
+fn map_review_item(value: TaskReviewItemRecord) -> ReviewItemOutput {
+    ReviewItemOutput {
+        id: value.id,
+        task_id: value.task_id,
+        title: value.title,
+        done: value.done == 1,
+        completed_by: value.completed_by,
+        completed_at: value.completed_at,
+    }
+}
+
 fn map_project_open_question(
     project_slug: &str,
     value: ProjectQuestionRecord,
@@ -1160,6 +2612,8 @@ fn map_project_open_question(
         context: value.context,
         answer: value.answer,
         status: value.status,
+        acceptance: value.acceptance,
+        dispute_reason: value.dispute_reason,
         asked_by: value.asked_by,
         resolved_by: value.resolved_by,
         created_at: value.created_at,
@@ -1167,6 +2621,17 @@ fn map_project_open_question(
     }
 }
 
+fn map_task_history(value: TaskHistoryRecord) -> TaskHistoryOutput {
+    TaskHistoryOutput {
+        id: value.id,
+        actor: value.actor,
+        actor_display_name: value.actor_display_name,
+        action: value.action,
+        detail: value.detail,
+        created_at: value.created_at,
+    }
+}
+
 fn map_recent_activity(project_slug: &str, value: ProjectActivityRecord) -> RecentActivityOutput {
     RecentActivityOutput {
         task_id: value.task_id,
@@ -1174,6 +2639,7 @@ fn map_recent_activity(project_slug: &str, value: ProjectActivityRecord) -> Rece
         task_display_key: queries::display_key(project_slug, value.task_number),
         action: value.action,
         actor: value.actor,
+        actor_display_name: value.actor_display_name,
         created_at: value.created_at,
     }
 }
@@ -1191,12 +2657,18 @@ mod tests {
     use tempfile::tempdir;
 
     use crate::api;
-    use crate::config::{Config, RateLimitConfig};
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
     use crate::db;
     use crate::db::queries;
+    use crate::db::queries::NewTaskInput;
+    use crate::error::AppError;
     use crate::mcp;
     use crate::state::AppState;
 
+    use super::*;
+
     #[tokio::test]
     async fn streamable_http_mcp_tools_list_and_call_work() {
         let temp_dir = tempdir().expect("tempdir should be created");
@@ -1204,13 +2676,23 @@ mod tests {
         let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
 
         let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
             port: 0,
             db_url,
             token: None,
             log_level: "info".to_string(),
             storage_dir: temp_dir.path().join("storage"),
             max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
             rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
         };
         let pool = db::connect_and_migrate(&config)
             .await
@@ -1385,4 +2867,449 @@ mod tests {
 
         server.abort();
     }
+
+    #[tokio::test]
+    async fn bulk_task_creation_streams_progress_notifications() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("mcp_bulk_progress_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Bulk", "", "BULK")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool);
+        let app = Router::new()
+            .nest_service("/mcp", mcp::service(state.clone()))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let base = format!("http://{addr}/mcp");
+        let rpc = |body: serde_json::Value| {
+            client
+                .post(&base)
+                .header(ACCEPT, "application/json, text/event-stream")
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string())
+        };
+
+        let init = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "bulk-e2e", "version": "0.1.0" }
+            }
+        }))
+        .send()
+        .await
+        .expect("initialize should succeed");
+        assert_eq!(init.status(), StatusCode::OK);
+        let session_id = init
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .expect("session header should exist");
+        let initialized = rpc(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .header("Mcp-Session-Id", &session_id)
+            .send()
+            .await
+            .expect("initialized notification should succeed");
+        assert_eq!(initialized.status(), StatusCode::ACCEPTED);
+
+        let tasks = (1..=12)
+            .map(|index| json!({ "title": format!("bulk {index}") }))
+            .collect::<Vec<_>>();
+        let bulk_body = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "lattice_create_tasks_bulk",
+                "arguments": { "project": "BULK", "tasks": tasks },
+                "_meta": { "progressToken": "bulk-1" }
+            }
+        }))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("bulk tools/call should succeed")
+        .text()
+        .await
+        .expect("bulk body should be readable");
+        for progress in ["created 10/12 tasks in BULK", "created 12/12 tasks in BULK"] {
+            assert!(
+                bulk_body.contains(progress),
+                "bulk create should stream '{progress}' as a progress notification"
+            );
+        }
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn delete_task_previews_until_confirmed() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("mcp_delete_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Cleanup", "", "CLEAN")
+            .await
+            .expect("project should be created");
+        let task = queries::create_task(
+            &pool,
+            &FieldLimitConfig::default(),
+            "CLEAN",
+            NewTaskInput {
+                title: "keep me".to_string(),
+                description: String::new(),
+                status: "backlog".to_string(),
+                priority: "medium".to_string(),
+                review_state: "ready".to_string(),
+                labels: Vec::new(),
+                created_by: "human".to_string(),
+                assignee: None,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("task should be created");
+        queries::add_subtask(
+            &pool,
+            &FieldLimitConfig::default(),
+            "CLEAN",
+            &task.id,
+            "step",
+            "human",
+        )
+        .await
+        .expect("subtask should be added");
+
+        let rate_limiter = RateLimiter::new(config.rate_limits.clone());
+        let server = LatticeMcpServer::new(pool.clone(), Arc::new(config), rate_limiter);
+        let request = |confirm| DeleteTaskInput {
+            project: "CLEAN".to_string(),
+            task_ref: "CLEAN-1".to_string(),
+            confirm,
+        };
+
+        let Json(preview) = server
+            .lattice_delete_task(Parameters(request(None)), Extensions::new())
+            .await
+            .expect("preview should succeed");
+        assert!(!preview.deleted);
+        assert!(preview.confirmation_required);
+        assert_eq!(preview.subtask_count, 1);
+        queries::get_task_details(&pool, "CLEAN", "CLEAN-1")
+            .await
+            .expect("task should survive the preview");
+
+        let Json(deleted) = server
+            .lattice_delete_task(Parameters(request(Some(true))), Extensions::new())
+            .await
+            .expect("confirmed delete should succeed");
+        assert!(deleted.deleted);
+        let archived = queries::get_task_details(&pool, "CLEAN", "CLEAN-1")
+            .await
+            .expect("a deleted task is archived, not removed");
+        assert!(archived.task.archived_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn mcp_keys_are_required_and_become_the_actor() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("mcp_keys_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: Some("instance-token".to_string()),
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: true,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Keys", "", "KEYS")
+            .await
+            .expect("project should be created");
+
+        let state = AppState::new(config, pool);
+        let app = Router::new()
+            .nest_service("/mcp", mcp::service(state.clone()))
+            .nest("/api/v1", api::router())
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let base = format!("http://{addr}/mcp");
+        let anonymous_mint = client
+            .post(format!("http://{addr}/api/v1/mcp-keys"))
+            .json(&json!({ "name": "intruder" }))
+            .send()
+            .await
+            .expect("key mint should complete");
+        assert_eq!(anonymous_mint.status(), StatusCode::UNAUTHORIZED);
+        let rejection: serde_json::Value = anonymous_mint
+            .json()
+            .await
+            .expect("rejection body should parse");
+        assert_eq!(rejection["message"], "missing or invalid bearer token");
+        let minted: serde_json::Value = client
+            .post(format!("http://{addr}/api/v1/mcp-keys"))
+            .bearer_auth("instance-token")
+            .json(&json!({ "name": "build-bot" }))
+            .send()
+            .await
+            .expect("key mint should succeed")
+            .json()
+            .await
+            .expect("key body should parse");
+        let key = minted["token"].as_str().expect("token should be returned");
+
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "keys-e2e", "version": "0.1.0" }
+            }
+        })
+        .to_string();
+        let rpc = |body: String| {
+            client
+                .post(&base)
+                .header(ACCEPT, "application/json, text/event-stream")
+                .header(CONTENT_TYPE, "application/json")
+                .header("MCP-Client", "someone-else")
+                .body(body)
+        };
+
+        let unauthenticated = rpc(initialize.clone())
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(unauthenticated.status(), StatusCode::UNAUTHORIZED);
+        let instance_token = rpc(initialize.clone())
+            .bearer_auth("instance-token")
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(
+            instance_token.status(),
+            StatusCode::UNAUTHORIZED,
+            "the instance token is not an MCP key"
+        );
+
+        let init = rpc(initialize)
+            .bearer_auth(key)
+            .send()
+            .await
+            .expect("initialize should succeed");
+        assert_eq!(init.status(), StatusCode::OK);
+        let session_id = init
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .expect("session header should exist");
+        let initialized =
+            rpc(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }).to_string())
+                .bearer_auth(key)
+                .header("Mcp-Session-Id", &session_id)
+                .send()
+                .await
+                .expect("initialized notification should succeed");
+        assert_eq!(initialized.status(), StatusCode::ACCEPTED);
+
+        let call_body = rpc(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "lattice_create_task",
+                "arguments": { "project": "KEYS", "title": "attributed" }
+            }
+        })
+        .to_string())
+        .bearer_auth(key)
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("tools/call should succeed")
+        .text()
+        .await
+        .expect("call body should be readable");
+        assert!(
+            call_body.contains(r#""created_by":"build-bot""#),
+            "the key name should be recorded as the actor"
+        );
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn delete_project_requires_matching_slug_and_is_audited() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("mcp_delete_project_test.db");
+        let mut config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: true,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Scratch", "", "SCRATCH")
+            .await
+            .expect("project should be created");
+        let request = |confirm_slug: &str| DeleteProjectInput {
+            project: "scratch".to_string(),
+            confirm_slug: confirm_slug.to_string(),
+        };
+
+        let rate_limiter = RateLimiter::new(config.rate_limits.clone());
+        let disabled =
+            LatticeMcpServer::new(pool.clone(), Arc::new(config.clone()), rate_limiter.clone());
+        assert!(disabled
+            .lattice_delete_project(Parameters(request("SCRATCH")), Extensions::new())
+            .await
+            .is_err());
+
+        config.mcp_disable_project_delete = false;
+        let server = LatticeMcpServer::new(pool.clone(), Arc::new(config), rate_limiter);
+        assert!(server
+            .lattice_delete_project(Parameters(request("SCRATC")), Extensions::new())
+            .await
+            .is_err());
+        queries::get_project(&pool, "SCRATCH")
+            .await
+            .expect("a mismatched slug should not delete");
+
+        server
+            .lattice_delete_project(Parameters(request("SCRATCH")), Extensions::new())
+            .await
+            .expect("delete should succeed");
+        assert!(matches!(
+            queries::get_project(&pool, "SCRATCH").await,
+            Err(AppError::NotFound(_))
+        ));
+        let audit = queries::list_audit_events(&pool, 10, 0)
+            .await
+            .expect("audit events should load");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "project.deleted");
+        assert_eq!(audit[0].subject, "SCRATCH");
+        assert_eq!(audit[0].actor, "agent");
+    }
+
+    #[test]
+    fn read_only_tools_name_registered_tools() {
+        let tools: Vec<String> = LatticeMcpServer::tool_router()
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        for name in mcp::READ_ONLY_TOOLS {
+            assert!(tools.iter().any(|tool| tool == name), "{name}");
+        }
+        assert!(!mcp::READ_ONLY_TOOLS.contains(&"lattice_update_task"));
+    }
 }
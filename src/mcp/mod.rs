@@ -1,15 +1,54 @@
 mod handler;
+mod sessions;
+
+use std::sync::Arc;
 
 use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
 
 use crate::state::AppState;
 use handler::LatticeMcpServer;
+use sessions::PersistentSessionManager;
+
+/// Tools that never change lattice data. Calls to any other tool are rate limited against the
+/// MCP write budget.
+pub const READ_ONLY_TOOLS: &[&str] = &[
+    "lattice_list_projects",
+    "lattice_get_project",
+    "lattice_get_spec",
+    "lattice_get_spec_section",
+    "lattice_get_spec_history",
+    "lattice_lint_spec",
+    "lattice_list_tasks",
+    "lattice_get_task",
+    "lattice_get_tasks",
+    "lattice_get_task_history",
+    "lattice_preview_attachment",
+    "lattice_list_open_questions",
+    "lattice_get_board",
+    "lattice_board_summary",
+    "lattice_get_limits",
+];
 
-pub fn service(state: AppState) -> StreamableHttpService<LatticeMcpServer> {
+pub fn service(
+    state: AppState,
+) -> StreamableHttpService<LatticeMcpServer, PersistentSessionManager> {
     let db = state.db.clone();
+    let config = state.config.clone();
+    let rate_limiter = state.rate_limiter.clone();
+    let sessions = Arc::new(PersistentSessionManager::new(
+        db.clone(),
+        config.clone(),
+        rate_limiter.clone(),
+    ));
     StreamableHttpService::new(
-        move || Ok(LatticeMcpServer::new(db.clone())),
-        Default::default(),
+        move || {
+            Ok(LatticeMcpServer::new(
+                db.clone(),
+                config.clone(),
+                rate_limiter.clone(),
+            ))
+        },
+        sessions,
         StreamableHttpServerConfig::default(),
     )
 }
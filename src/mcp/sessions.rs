@@ -0,0 +1,348 @@
+use std::sync::Arc;
+
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::transport::streamable_http_server::session::local::{
+    create_local_session, LocalSessionManager, LocalSessionManagerError, LocalSessionWorker,
+};
+use rmcp::transport::streamable_http_server::session::ServerSseMessage;
+use rmcp::transport::streamable_http_server::{SessionId, SessionManager};
+use rmcp::transport::WorkerTransport;
+use rmcp::ServiceExt;
+use sqlx::AnyPool;
+use tokio::sync::Mutex;
+use tokio_stream::Stream;
+
+use crate::config::Config;
+use crate::db::queries;
+use crate::error::AppError;
+use crate::rate_limit::RateLimiter;
+
+use super::handler::LatticeMcpServer;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionStoreError {
+    #[error(transparent)]
+    Local(#[from] LocalSessionManagerError),
+    #[error("mcp session store: {0}")]
+    Store(#[from] AppError),
+}
+
+/// In-memory MCP sessions backed by the database, so `Mcp-Session-Id`s survive restarts.
+///
+/// Each session's `initialize` request is stored when the session starts. A request that
+/// names a session this process has never seen is served by replaying that handshake
+/// into a fresh local session under the same id. Per-session state that lives only in
+/// memory (such as the logging level) starts over from its defaults.
+pub struct PersistentSessionManager {
+    inner: Arc<LocalSessionManager>,
+    db: AnyPool,
+    config: Arc<Config>,
+    rate_limiter: RateLimiter,
+    restoring: Mutex<()>,
+}
+
+impl PersistentSessionManager {
+    pub fn new(db: AnyPool, config: Arc<Config>, rate_limiter: RateLimiter) -> Self {
+        Self {
+            inner: Arc::new(LocalSessionManager::default()),
+            db,
+            config,
+            rate_limiter,
+            restoring: Mutex::new(()),
+        }
+    }
+
+    async fn restore(&self, id: &SessionId) -> Result<bool, SessionStoreError> {
+        let _guard = self.restoring.lock().await;
+        if self.inner.has_session(id).await? {
+            return Ok(true);
+        }
+
+        let Some(stored) = queries::mcp_session_initialize(&self.db, id).await? else {
+            return Ok(false);
+        };
+        let initialize = match serde_json::from_str::<ClientJsonRpcMessage>(&stored) {
+            Ok(message) => message,
+            Err(error) => {
+                tracing::warn!(error = ?error, session_id = %id, "discarding unreadable mcp session");
+                queries::delete_mcp_session(&self.db, id).await?;
+                return Ok(false);
+            }
+        };
+
+        let (handle, worker) = create_local_session(id.clone(), self.inner.session_config.clone());
+        self.inner.sessions.write().await.insert(id.clone(), handle);
+        self.spawn_server(id.clone(), WorkerTransport::spawn(worker));
+        self.inner.initialize_session(id, initialize).await?;
+        self.inner
+            .accept_message(id, initialized_notification())
+            .await?;
+
+        tracing::info!(session_id = %id, "restored mcp session");
+        Ok(true)
+    }
+
+    fn spawn_server(&self, id: SessionId, transport: WorkerTransport<LocalSessionWorker>) {
+        let server = LatticeMcpServer::new(
+            self.db.clone(),
+            self.config.clone(),
+            self.rate_limiter.clone(),
+        );
+        let inner = self.inner.clone();
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            match server.serve(transport).await {
+                Ok(running) => {
+                    let _ = running.waiting().await;
+                }
+                Err(error) => {
+                    tracing::error!(error = ?error, session_id = %id, "failed to restore mcp session");
+                }
+            }
+            if let Err(error) = close(&inner, &db, &id).await {
+                tracing::error!(error = ?error, session_id = %id, "failed to close mcp session");
+            }
+        });
+    }
+}
+
+impl SessionManager for PersistentSessionManager {
+    type Error = SessionStoreError;
+    type Transport = WorkerTransport<LocalSessionWorker>;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        Ok(self.inner.create_session().await?)
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        let stored = serde_json::to_string(&message).map_err(|error| {
+            tracing::error!(error = ?error, "failed to serialize mcp initialize request");
+            AppError::Internal
+        })?;
+        let response = self.inner.initialize_session(id, message).await?;
+        queries::save_mcp_session(&self.db, id, &stored).await?;
+        Ok(response)
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        if self.inner.has_session(id).await? {
+            return Ok(true);
+        }
+        self.restore(id).await
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        close(&self.inner, &self.db, id).await
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        Ok(self.inner.create_stream(id, message).await?)
+    }
+
+    async fn accept_message(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<(), Self::Error> {
+        Ok(self.inner.accept_message(id, message).await?)
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        Ok(self.inner.create_standalone_stream(id).await?)
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        Ok(self.inner.resume(id, last_event_id).await?)
+    }
+}
+
+async fn close(
+    inner: &LocalSessionManager,
+    db: &AnyPool,
+    id: &SessionId,
+) -> Result<(), SessionStoreError> {
+    inner.close_session(id).await?;
+    queries::delete_mcp_session(db, id).await?;
+    Ok(())
+}
+
+fn initialized_notification() -> ClientJsonRpcMessage {
+    serde_json::from_value(serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/initialized"
+    }))
+    .expect("initialized notification should deserialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::middleware;
+    use axum::Router;
+    use reqwest::header::{ACCEPT, CONTENT_TYPE};
+    use reqwest::StatusCode;
+    use serde_json::json;
+    use sqlx::AnyPool;
+    use tempfile::tempdir;
+
+    use crate::api;
+    use crate::config::{
+        Config, FieldLimitConfig, RateLimitConfig, SecretKeyConfig, WebhookDeliveryConfig,
+    };
+    use crate::db;
+    use crate::db::queries;
+    use crate::mcp;
+    use crate::state::AppState;
+
+    async fn start_server(config: Config, pool: AnyPool) -> (String, tokio::task::JoinHandle<()>) {
+        let state = AppState::new(config, pool);
+        let app = Router::new()
+            .nest_service("/mcp", mcp::service(state.clone()))
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::require_auth,
+            ))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let addr = listener
+            .local_addr()
+            .expect("listener addr should be readable");
+        let server = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{addr}/mcp"), server)
+    }
+
+    #[tokio::test]
+    async fn sessions_survive_a_server_restart() {
+        let temp_dir = tempdir().expect("tempdir should be created");
+        let db_path = temp_dir.path().join("mcp_sessions_test.db");
+        let config = Config {
+            bind_addr: std::net::Ipv4Addr::LOCALHOST.into(),
+            port: 0,
+            db_url: format!("sqlite://{}?mode=rwc", db_path.display()),
+            token: None,
+            log_level: "info".to_string(),
+            storage_dir: temp_dir.path().join("storage"),
+            max_file_size: 10 * 1024 * 1024,
+            stale_after_days: 7,
+            strict_json: false,
+            mcp_require_key: false,
+            mcp_disable_project_delete: false,
+            access_log: false,
+            public_url: None,
+            rate_limits: RateLimitConfig::default(),
+            field_limits: FieldLimitConfig::default(),
+            secret_keys: SecretKeyConfig::default(),
+            webhook_delivery: WebhookDeliveryConfig::default(),
+        };
+        let pool = db::connect_and_migrate(&config)
+            .await
+            .expect("database should initialize");
+        queries::create_project_with_slug(&pool, "Restart", "", "RESTART")
+            .await
+            .expect("project should be created");
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("client should build");
+        let post = |base: &str, session_id: Option<&str>, body: serde_json::Value| {
+            let mut request = client
+                .post(base)
+                .header(ACCEPT, "application/json, text/event-stream")
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+            if let Some(session_id) = session_id {
+                request = request.header("Mcp-Session-Id", session_id);
+            }
+            request.send()
+        };
+
+        let (base, first) = start_server(config.clone(), pool.clone()).await;
+        let init = post(
+            &base,
+            None,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "restart-e2e", "version": "0.1.0" }
+                }
+            }),
+        )
+        .await
+        .expect("initialize should succeed");
+        let session_id = init
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned)
+            .expect("session header should exist");
+        let _ = init.text().await;
+        let initialized = post(
+            &base,
+            Some(&session_id),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await
+        .expect("initialized notification should succeed");
+        assert_eq!(initialized.status(), StatusCode::ACCEPTED);
+        first.abort();
+
+        let (base, second) = start_server(config, pool).await;
+        let call = post(
+            &base,
+            Some(&session_id),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": {
+                    "name": "lattice_get_project",
+                    "arguments": { "project": "RESTART" }
+                }
+            }),
+        )
+        .await
+        .expect("tools/call should succeed");
+        assert_eq!(call.status(), StatusCode::OK);
+        let body = call.text().await.expect("call body should be readable");
+        assert!(
+            body.contains(r#""slug":"RESTART""#),
+            "the restored session should serve tool calls: {body}"
+        );
+
+        let unknown = post(
+            &base,
+            Some("not-a-session"),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list", "params": {} }),
+        )
+        .await
+        .expect("request should complete");
+        assert_eq!(unknown.status(), StatusCode::UNAUTHORIZED);
+
+        second.abort();
+    }
+}
@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Upper bounds, in seconds, of the webhook latency histogram buckets. Deliveries time out
+/// after five seconds by default; anything slower than ten lands in `+Inf`.
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// In-process counters rendered in the Prometheus text format by `GET /api/v1/metrics`.
+///
+/// Values reset when the server restarts; Prometheus handles that for counters.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    webhooks: Arc<Mutex<BTreeMap<WebhookKey, DeliveryStats>>>,
+    sse: Arc<Mutex<SseStats>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct WebhookKey {
+    platform: String,
+    webhook_id: String,
+}
+
+#[derive(Debug, Default)]
+struct DeliveryStats {
+    succeeded: u64,
+    failed: u64,
+    retries: u64,
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+    latency_count: u64,
+}
+
+#[derive(Debug, Default)]
+struct SseStats {
+    next_connection: u64,
+    connections: BTreeMap<u64, SseConnectionStats>,
+    slow_consumer_disconnects: u64,
+}
+
+#[derive(Debug, Default)]
+struct SseConnectionStats {
+    /// When each event handed to the stream but not yet written to the client was queued.
+    buffered: VecDeque<Instant>,
+    sent: u64,
+    last_sent_id: Option<String>,
+}
+
+/// One live SSE stream's entry in the metrics, removed when the last clone is dropped.
+#[derive(Debug)]
+pub struct SseConnectionMetrics {
+    id: u64,
+    sse: Arc<Mutex<SseStats>>,
+}
+
+impl Metrics {
+    /// Records one delivery attempt. `retry` marks a re-send of an earlier failed delivery.
+    pub fn record_webhook_delivery(
+        &self,
+        platform: &str,
+        webhook_id: &str,
+        succeeded: bool,
+        retry: bool,
+        elapsed: Duration,
+    ) {
+        let mut webhooks = self.webhooks();
+        let stats = webhooks
+            .entry(WebhookKey {
+                platform: platform.to_string(),
+                webhook_id: webhook_id.to_string(),
+            })
+            .or_default();
+
+        if succeeded {
+            stats.succeeded += 1;
+        } else {
+            stats.failed += 1;
+        }
+        if retry {
+            stats.retries += 1;
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            stats.buckets[index] += 1;
+        }
+        stats.latency_sum += seconds;
+        stats.latency_count += 1;
+    }
+
+    pub fn open_sse_connection(&self) -> Arc<SseConnectionMetrics> {
+        let mut sse = lock(&self.sse);
+        sse.next_connection += 1;
+        let id = sse.next_connection;
+        sse.connections.insert(id, SseConnectionStats::default());
+        Arc::new(SseConnectionMetrics {
+            id,
+            sse: self.sse.clone(),
+        })
+    }
+
+    /// Events written so far to the SSE stream labelled `connection`, if it is still open.
+    pub fn sse_events_sent(&self, connection: u64) -> Option<u64> {
+        lock(&self.sse)
+            .connections
+            .get(&connection)
+            .map(|stats| stats.sent)
+    }
+
+    pub fn render_prometheus(&self) -> String {
+        let webhooks = self.webhooks();
+        let mut output = String::new();
+
+        write_header(
+            &mut output,
+            "lattice_webhook_deliveries_total",
+            "counter",
+            "Webhook delivery attempts by outcome.",
+        );
+        for (key, stats) in webhooks.iter() {
+            for (outcome, value) in [("success", stats.succeeded), ("failure", stats.failed)] {
+                let _ = writeln!(
+                    output,
+                    "lattice_webhook_deliveries_total{{{},outcome=\"{outcome}\"}} {value}",
+                    key.labels()
+                );
+            }
+        }
+
+        write_header(
+            &mut output,
+            "lattice_webhook_retries_total",
+            "counter",
+            "Webhook delivery attempts that retried an earlier failure.",
+        );
+        for (key, stats) in webhooks.iter() {
+            let _ = writeln!(
+                output,
+                "lattice_webhook_retries_total{{{}}} {}",
+                key.labels(),
+                stats.retries
+            );
+        }
+
+        write_header(
+            &mut output,
+            "lattice_webhook_delivery_duration_seconds",
+            "histogram",
+            "Webhook delivery latency, including failed attempts.",
+        );
+        for (key, stats) in webhooks.iter() {
+            let labels = key.labels();
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(stats.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "lattice_webhook_delivery_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                output,
+                "lattice_webhook_delivery_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}",
+                stats.latency_count
+            );
+            let _ = writeln!(
+                output,
+                "lattice_webhook_delivery_duration_seconds_sum{{{labels}}} {}",
+                stats.latency_sum
+            );
+            let _ = writeln!(
+                output,
+                "lattice_webhook_delivery_duration_seconds_count{{{labels}}} {}",
+                stats.latency_count
+            );
+        }
+
+        self.render_sse(&mut output);
+        output
+    }
+
+    fn render_sse(&self, output: &mut String) {
+        let sse = lock(&self.sse);
+        let now = Instant::now();
+
+        write_header(
+            output,
+            "lattice_sse_connections",
+            "gauge",
+            "Open SSE event streams.",
+        );
+        let _ = writeln!(output, "lattice_sse_connections {}", sse.connections.len());
+
+        write_header(
+            output,
+            "lattice_sse_slow_consumer_disconnects_total",
+            "counter",
+            "SSE streams closed because the client stopped reading.",
+        );
+        let _ = writeln!(
+            output,
+            "lattice_sse_slow_consumer_disconnects_total {}",
+            sse.slow_consumer_disconnects
+        );
+
+        write_header(
+            output,
+            "lattice_sse_buffered_events",
+            "gauge",
+            "Events queued for an SSE stream but not yet written to the client.",
+        );
+        for (id, stats) in &sse.connections {
+            let _ = writeln!(
+                output,
+                "lattice_sse_buffered_events{{connection=\"{id}\"}} {}",
+                stats.buffered.len()
+            );
+        }
+
+        write_header(
+            output,
+            "lattice_sse_lag_seconds",
+            "gauge",
+            "How long the oldest buffered event of an SSE stream has waited.",
+        );
+        for (id, stats) in &sse.connections {
+            let lag = stats.buffered.front().map_or(0.0, |queued| {
+                now.saturating_duration_since(*queued).as_secs_f64()
+            });
+            let _ = writeln!(
+                output,
+                "lattice_sse_lag_seconds{{connection=\"{id}\"}} {lag}"
+            );
+        }
+
+        write_header(
+            output,
+            "lattice_sse_events_sent_total",
+            "counter",
+            "Events written to an SSE stream.",
+        );
+        for (id, stats) in &sse.connections {
+            let _ = writeln!(
+                output,
+                "lattice_sse_events_sent_total{{connection=\"{id}\"}} {}",
+                stats.sent
+            );
+        }
+    }
+
+    fn webhooks(&self) -> MutexGuard<'_, BTreeMap<WebhookKey, DeliveryStats>> {
+        lock(&self.webhooks)
+    }
+}
+
+impl SseConnectionMetrics {
+    /// The label of this stream's series in `GET /metrics`.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn queued(&self) {
+        self.with_stats(|stats| stats.buffered.push_back(Instant::now()));
+    }
+
+    pub fn sent(&self, event_id: Option<&str>) {
+        self.with_stats(|stats| {
+            stats.buffered.pop_front();
+            stats.sent += 1;
+            if let Some(event_id) = event_id {
+                stats.last_sent_id = Some(event_id.to_string());
+            }
+        });
+    }
+
+    /// The last event the client actually received, which is where it should resume.
+    pub fn last_sent_id(&self) -> Option<String> {
+        lock(&self.sse)
+            .connections
+            .get(&self.id)
+            .and_then(|stats| stats.last_sent_id.clone())
+    }
+
+    pub fn disconnected_slow_consumer(&self) {
+        lock(&self.sse).slow_consumer_disconnects += 1;
+    }
+
+    fn with_stats(&self, apply: impl FnOnce(&mut SseConnectionStats)) {
+        if let Some(stats) = lock(&self.sse).connections.get_mut(&self.id) {
+            apply(stats);
+        }
+    }
+}
+
+impl Drop for SseConnectionMetrics {
+    fn drop(&mut self) {
+        lock(&self.sse).connections.remove(&self.id);
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+impl WebhookKey {
+    fn labels(&self) -> String {
+        format!(
+            "platform=\"{}\",webhook_id=\"{}\"",
+            escape_label(&self.platform),
+            escape_label(&self.webhook_id)
+        )
+    }
+}
+
+fn write_header(output: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} {kind}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Metrics;
+
+    #[test]
+    fn renders_webhook_counters_and_histogram() {
+        let metrics = Metrics::default();
+        metrics.record_webhook_delivery("slack", "hook-1", false, false, Duration::from_millis(80));
+        metrics.record_webhook_delivery("slack", "hook-1", true, true, Duration::from_millis(300));
+        metrics.record_webhook_delivery("generic", "hook-2", true, false, Duration::from_secs(12));
+
+        let output = metrics.render_prometheus();
+        for line in [
+            "# TYPE lattice_webhook_deliveries_total counter",
+            "lattice_webhook_deliveries_total{platform=\"slack\",webhook_id=\"hook-1\",outcome=\"success\"} 1",
+            "lattice_webhook_deliveries_total{platform=\"slack\",webhook_id=\"hook-1\",outcome=\"failure\"} 1",
+            "lattice_webhook_retries_total{platform=\"slack\",webhook_id=\"hook-1\"} 1",
+            "lattice_webhook_retries_total{platform=\"generic\",webhook_id=\"hook-2\"} 0",
+            "lattice_webhook_delivery_duration_seconds_bucket{platform=\"slack\",webhook_id=\"hook-1\",le=\"0.1\"} 1",
+            "lattice_webhook_delivery_duration_seconds_bucket{platform=\"slack\",webhook_id=\"hook-1\",le=\"0.5\"} 2",
+            "lattice_webhook_delivery_duration_seconds_bucket{platform=\"generic\",webhook_id=\"hook-2\",le=\"10\"} 0",
+            "lattice_webhook_delivery_duration_seconds_bucket{platform=\"generic\",webhook_id=\"hook-2\",le=\"+Inf\"} 1",
+            "lattice_webhook_delivery_duration_seconds_count{platform=\"slack\",webhook_id=\"hook-1\"} 2",
+        ] {
+            assert!(output.lines().any(|candidate| candidate == line), "missing line: {line}");
+        }
+    }
+
+    #[test]
+    fn renders_sse_connection_lag() {
+        let metrics = Metrics::default();
+        let connection = metrics.open_sse_connection();
+        connection.queued();
+        connection.queued();
+        connection.sent(Some("event-1"));
+        assert_eq!(connection.last_sent_id().as_deref(), Some("event-1"));
+        connection.disconnected_slow_consumer();
+
+        let id = connection.id();
+        let output = metrics.render_prometheus();
+        for line in [
+            "lattice_sse_connections 1".to_string(),
+            "lattice_sse_slow_consumer_disconnects_total 1".to_string(),
+            format!("lattice_sse_buffered_events{{connection=\"{id}\"}} 1"),
+            format!("lattice_sse_events_sent_total{{connection=\"{id}\"}} 1"),
+        ] {
+            assert!(
+                output.lines().any(|candidate| candidate == line),
+                "missing line: {line}"
+            );
+        }
+        assert!(output.contains(&format!("lattice_sse_lag_seconds{{connection=\"{id}\"}}")));
+
+        drop(connection);
+        let output = metrics.render_prometheus();
+        assert!(output
+            .lines()
+            .any(|line| line == "lattice_sse_connections 0"));
+        assert!(!output.contains("lattice_sse_buffered_events{"));
+    }
+}
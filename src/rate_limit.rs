@@ -1,24 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write as _;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
 
-use axum::body::Body;
-use axum::extract::State;
+use axum::body::{Body, Bytes};
+use axum::extract::{ConnectInfo, State};
 use axum::http::header::{AUTHORIZATION, RETRY_AFTER};
 use axum::http::{HeaderMap, HeaderValue, Method, Request, StatusCode};
 use axum::middleware::Next;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
+use chrono::{DateTime, Utc};
+use http_body::{Frame, SizeHint};
 use serde::Serialize;
+use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
+use crate::client_ip::{self, ClientIp};
 use crate::config::RateLimitConfig;
+use crate::mcp;
 use crate::state::AppState;
 
 const CLEANUP_INTERVAL: Duration = Duration::from_secs(300);
 const STALE_BUCKET_AGE: Duration = Duration::from_secs(3600);
 const SSE_CAP_RETRY_AFTER_SECS: u64 = 10;
+const IN_FLIGHT_RETRY_AFTER_SECS: u64 = 1;
 
 #[derive(Clone, Debug)]
 pub struct RateLimiter {
@@ -74,7 +83,60 @@ impl RateLimiter {
         })
     }
 
-    pub fn try_acquire_sse_slot(&self, identity: &str) -> Result<SseConnectionLease, SseCapDenied> {
+    /// What `identity` has left in every scope, without spending any of it.
+    pub fn budget(&self, identity: &str) -> RateBudget {
+        self.budget_with_now(identity, Instant::now())
+    }
+
+    fn budget_with_now(&self, identity: &str, now: Instant) -> RateBudget {
+        self.with_inner(|inner| {
+            let scopes = RateScope::ALL
+                .into_iter()
+                .map(|scope| {
+                    let settings = bucket_settings(&self.settings, scope);
+                    let tokens = inner
+                        .buckets
+                        .get(&(scope, identity.to_string()))
+                        .map_or(settings.burst as f64, |bucket| {
+                            bucket.available(settings.per_minute, settings.burst, now)
+                        });
+                    ScopeBudget {
+                        scope,
+                        limit: settings.per_minute,
+                        burst: settings.burst,
+                        remaining: tokens.floor().clamp(0.0, u32::MAX as f64) as u32,
+                        reset_after_secs: reset_after_seconds(
+                            tokens,
+                            settings.per_minute,
+                            settings.burst,
+                        ),
+                    }
+                })
+                .collect();
+
+            RateBudget {
+                identity: identity.to_string(),
+                scopes,
+                in_flight: inner
+                    .in_flight_by_identity
+                    .get(identity)
+                    .copied()
+                    .unwrap_or(0),
+                max_in_flight: self.settings.max_in_flight_per_identity,
+                sse_streams: inner
+                    .sse_active_by_identity
+                    .get(identity)
+                    .copied()
+                    .unwrap_or(0),
+                max_sse_streams: self.settings.sse_max_per_identity,
+            }
+        })
+    }
+
+    pub fn try_acquire_sse_slot(
+        &self,
+        identity: &str,
+    ) -> Result<SseConnectionLease, CapacityDenied> {
         self.with_inner(|inner| {
             let current_for_identity = inner
                 .sse_active_by_identity
@@ -82,7 +144,7 @@ impl RateLimiter {
                 .copied()
                 .unwrap_or(0);
             if current_for_identity >= self.settings.sse_max_per_identity {
-                return Err(SseCapDenied {
+                return Err(CapacityDenied {
                     limit: self.settings.sse_max_per_identity,
                     retry_after_secs: SSE_CAP_RETRY_AFTER_SECS,
                     message: "too many active SSE streams for this client identity".to_string(),
@@ -90,7 +152,7 @@ impl RateLimiter {
             }
 
             if inner.sse_active_global >= self.settings.sse_max_global {
-                return Err(SseCapDenied {
+                return Err(CapacityDenied {
                     limit: self.settings.sse_max_global,
                     retry_after_secs: SSE_CAP_RETRY_AFTER_SECS,
                     message: "SSE stream capacity reached for this instance".to_string(),
@@ -103,17 +165,75 @@ impl RateLimiter {
                 .entry(identity.to_string())
                 .and_modify(|count| *count += 1)
                 .or_insert(1);
+            inner.next_sse_stream += 1;
+            let id = inner.next_sse_stream;
+            inner.sse_streams.insert(
+                id,
+                SseStreamEntry {
+                    identity: identity.to_string(),
+                    connected_at: Utc::now(),
+                    connected: Instant::now(),
+                    workspace_id: None,
+                    projects: Vec::new(),
+                    metrics_connection: None,
+                },
+            );
 
             Ok(SseConnectionLease {
                 limiter: self.clone(),
+                id,
                 identity: identity.to_string(),
                 released: false,
             })
         })
     }
 
-    fn release_sse_slot(&self, identity: &str) {
+    /// Records what an open stream listens to, once its handler has parsed the request.
+    pub fn describe_sse_stream(
+        &self,
+        stream: SseStreamId,
+        workspace_id: Option<&str>,
+        projects: &[String],
+        metrics_connection: u64,
+    ) {
         self.with_inner(|inner| {
+            if let Some(entry) = inner.sse_streams.get_mut(&stream.0) {
+                entry.workspace_id = workspace_id.map(ToOwned::to_owned);
+                entry.projects = projects.to_vec();
+                entry.metrics_connection = Some(metrics_connection);
+            }
+        });
+    }
+
+    /// The streams holding SSE slots, oldest first.
+    pub fn sse_streams(&self) -> Vec<SseStreamSnapshot> {
+        self.with_inner(|inner| {
+            inner
+                .sse_streams
+                .iter()
+                .map(|(id, entry)| SseStreamSnapshot {
+                    id: *id,
+                    identity: entry.identity.clone(),
+                    connected_at: entry.connected_at,
+                    connected_for: entry.connected.elapsed(),
+                    workspace_id: entry.workspace_id.clone(),
+                    projects: entry.projects.clone(),
+                    metrics_connection: entry.metrics_connection,
+                })
+                .collect()
+        })
+    }
+
+    pub fn sse_limits(&self) -> (u32, u32) {
+        (
+            self.settings.sse_max_global,
+            self.settings.sse_max_per_identity,
+        )
+    }
+
+    fn release_sse_slot(&self, id: u64, identity: &str) {
+        self.with_inner(|inner| {
+            inner.sse_streams.remove(&id);
             inner.sse_active_global = inner.sse_active_global.saturating_sub(1);
             match inner.sse_active_by_identity.get_mut(identity) {
                 Some(count) if *count > 1 => {
@@ -127,6 +247,46 @@ impl RateLimiter {
         });
     }
 
+    pub fn try_acquire_in_flight_slot(
+        &self,
+        identity: &str,
+    ) -> Result<InFlightLease, CapacityDenied> {
+        self.with_inner(|inner| {
+            let limit = self.settings.max_in_flight_per_identity;
+            let in_flight = inner
+                .in_flight_by_identity
+                .entry(identity.to_string())
+                .or_insert(0);
+            if *in_flight >= limit {
+                return Err(CapacityDenied {
+                    limit,
+                    retry_after_secs: IN_FLIGHT_RETRY_AFTER_SECS,
+                    message: "too many requests in flight for this client identity".to_string(),
+                });
+            }
+            *in_flight += 1;
+
+            Ok(InFlightLease {
+                limiter: self.clone(),
+                identity: identity.to_string(),
+            })
+        })
+    }
+
+    fn release_in_flight_slot(&self, identity: &str) {
+        self.with_inner(
+            |inner| match inner.in_flight_by_identity.get_mut(identity) {
+                Some(count) if *count > 1 => {
+                    *count -= 1;
+                }
+                Some(_) => {
+                    inner.in_flight_by_identity.remove(identity);
+                }
+                None => {}
+            },
+        );
+    }
+
     fn with_inner<F, T>(&self, f: F) -> T
     where
         F: FnOnce(&mut RateLimiterInner) -> T,
@@ -141,11 +301,43 @@ pub async fn enforce_limits(
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    let Some(scope) = classify_scope(request.method(), request.uri().path()) else {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client = client_ip::client_ip(
+        request.headers(),
+        peer,
+        &state.config.rate_limits.trusted_proxies,
+        state.config.rate_limits.trusted_proxy_hops,
+    );
+    let mut request = request;
+    request.extensions_mut().insert(ClientIp(client));
+
+    let Some(mut scope) = classify_scope(request.method(), request.uri().path()) else {
         return next.run(request).await;
     };
 
-    let identity = request_identity(request.headers(), state.config.auth_enabled());
+    // MCP traffic is all `POST /mcp`, so reads and writes are told apart by the tools called.
+    let mut mcp_body = None;
+    if scope == RateScope::McpRead && request.method() == Method::POST {
+        let (parts, body) = request.into_parts();
+        let limit = state.config.rate_limits.max_request_body_bytes;
+        let Ok(bytes) = axum::body::to_bytes(body, limit).await else {
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "request body exceeds the configured limit",
+            )
+                .into_response();
+        };
+        if calls_write_tool(&bytes) {
+            scope = RateScope::McpWrite;
+        }
+        request = Request::from_parts(parts, Body::from(bytes.clone()));
+        mcp_body = Some(bytes);
+    }
+
+    let identity = request_identity(request.headers(), client, state.config.auth_enabled());
     let decision = state.rate_limiter.check(scope, &identity);
 
     let allowance = match decision {
@@ -157,28 +349,66 @@ pub async fn enforce_limits(
                 retry_after_secs = denial.retry_after_secs,
                 "request denied by rate limiter"
             );
+            if let Some(body) = mcp_body {
+                return mcp_rate_limited_response(&body, denial);
+            }
             return rate_limited_response(denial);
         }
     };
 
     let sse_lease = if scope == RateScope::Sse {
         match state.rate_limiter.try_acquire_sse_slot(&identity) {
-            Ok(lease) => Some(lease),
+            Ok(lease) => {
+                request.extensions_mut().insert(SseStreamId(lease.id));
+                Some(lease)
+            }
             Err(denial) => {
                 tracing::warn!(identity = %identity, "request denied by sse stream capacity");
-                return sse_capacity_response(denial);
+                return capacity_response(denial);
             }
         }
     } else {
         None
     };
 
+    // SSE streams, including an MCP session's `GET /mcp` stream, stay open indefinitely and
+    // are capped by the SSE limits instead.
+    let in_flight_lease = if scope == RateScope::Sse
+        || (scope == RateScope::McpRead && request.method() == Method::GET)
+    {
+        None
+    } else {
+        match state.rate_limiter.try_acquire_in_flight_slot(&identity) {
+            Ok(lease) => Some(lease),
+            Err(denial) => {
+                tracing::warn!(identity = %identity, "request denied by in-flight request cap");
+                return capacity_response(denial);
+            }
+        }
+    };
+
     let mut response = next.run(request).await;
     set_rate_limit_headers(&mut response, &allowance);
+    if let Some(lease) = in_flight_lease {
+        // MCP tool calls keep running while their response streams, so hold the slot until
+        // the body is done.
+        response = response.map(|inner| {
+            Body::new(LeasedBody {
+                inner,
+                _lease: lease,
+            })
+        });
+    }
 
+    // The stream holds its slot for as long as its body is open.
     if response.status().is_success() {
         if let Some(lease) = sse_lease {
-            response.extensions_mut().insert(lease);
+            response = response.map(|inner| {
+                Body::new(LeasedBody {
+                    inner,
+                    _lease: lease,
+                })
+            });
         }
     }
 
@@ -191,26 +421,52 @@ pub enum RateScope {
     Write,
     Attachment,
     WebhookTest,
-    Mcp,
+    McpRead,
+    McpWrite,
     Sse,
 }
 
 impl RateScope {
+    pub const ALL: [RateScope; 7] = [
+        Self::Read,
+        Self::Write,
+        Self::Attachment,
+        Self::WebhookTest,
+        Self::McpRead,
+        Self::McpWrite,
+        Self::Sse,
+    ];
+
+    /// The scope's name in budget reports.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Attachment => "attachment",
+            Self::WebhookTest => "webhook_test",
+            Self::McpRead => "mcp_read",
+            Self::McpWrite => "mcp_write",
+            Self::Sse => "sse_connect",
+        }
+    }
+
     fn description(self) -> &'static str {
         match self {
             Self::Read => "read requests",
             Self::Write => "write requests",
             Self::Attachment => "attachment requests",
             Self::WebhookTest => "webhook test requests",
-            Self::Mcp => "mcp requests",
+            Self::McpRead => "mcp read requests",
+            Self::McpWrite => "mcp write requests",
             Self::Sse => "sse connect requests",
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct SseConnectionLease {
     limiter: RateLimiter,
+    id: u64,
     identity: String,
     released: bool,
 }
@@ -221,7 +477,74 @@ impl Drop for SseConnectionLease {
             return;
         }
         self.released = true;
-        self.limiter.release_sse_slot(&self.identity);
+        self.limiter.release_sse_slot(self.id, &self.identity);
+    }
+}
+
+/// Identifies an SSE request's lease, so its handler can describe the stream.
+#[derive(Debug, Clone, Copy)]
+pub struct SseStreamId(u64);
+
+#[derive(Debug)]
+struct SseStreamEntry {
+    identity: String,
+    connected_at: DateTime<Utc>,
+    connected: Instant,
+    workspace_id: Option<String>,
+    projects: Vec<String>,
+    metrics_connection: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SseStreamSnapshot {
+    pub id: u64,
+    /// `token:<hash prefix>` or `ip:<client ip>`, the same identity the limits apply to.
+    pub identity: String,
+    pub connected_at: DateTime<Utc>,
+    pub connected_for: Duration,
+    pub workspace_id: Option<String>,
+    /// Empty when the stream follows every project it can see.
+    pub projects: Vec<String>,
+    /// The stream's `connection` label in `GET /metrics`.
+    pub metrics_connection: Option<u64>,
+}
+
+/// One request's share of `max_in_flight_per_identity`, released when dropped.
+#[derive(Debug)]
+pub struct InFlightLease {
+    limiter: RateLimiter,
+    identity: String,
+}
+
+impl Drop for InFlightLease {
+    fn drop(&mut self) {
+        self.limiter.release_in_flight_slot(&self.identity);
+    }
+}
+
+/// A response body that holds its request's slot until it is sent or dropped.
+struct LeasedBody<L> {
+    inner: Body,
+    _lease: L,
+}
+
+impl<L: Send + Unpin + 'static> http_body::Body for LeasedBody<L> {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        Pin::new(&mut self.inner).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
     }
 }
 
@@ -247,8 +570,30 @@ pub struct RateDenial {
     pub message: String,
 }
 
+/// A client identity's standing against every limit, as reported by `lattice_get_limits`.
+#[derive(Debug, Clone)]
+pub struct RateBudget {
+    pub identity: String,
+    pub scopes: Vec<ScopeBudget>,
+    pub in_flight: u32,
+    pub max_in_flight: u32,
+    pub sse_streams: u32,
+    pub max_sse_streams: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScopeBudget {
+    pub scope: RateScope,
+    /// Tokens refilled per minute.
+    pub limit: u32,
+    pub burst: u32,
+    pub remaining: u32,
+    /// Seconds until the bucket is full again.
+    pub reset_after_secs: u64,
+}
+
 #[derive(Debug)]
-pub struct SseCapDenied {
+pub struct CapacityDenied {
     pub limit: u32,
     pub retry_after_secs: u64,
     pub message: String,
@@ -278,10 +623,14 @@ fn bucket_settings(settings: &RateLimitConfig, scope: RateScope) -> BucketSettin
             per_minute: settings.webhook_test_per_min,
             burst: settings.webhook_test_burst,
         },
-        RateScope::Mcp => BucketSettings {
+        RateScope::McpRead => BucketSettings {
             per_minute: settings.mcp_per_min,
             burst: settings.mcp_burst,
         },
+        RateScope::McpWrite => BucketSettings {
+            per_minute: settings.mcp_write_per_min,
+            burst: settings.mcp_write_burst,
+        },
         RateScope::Sse => BucketSettings {
             per_minute: settings.sse_connect_per_min,
             burst: settings.sse_connect_burst,
@@ -305,6 +654,14 @@ impl RateBucket {
         }
     }
 
+    /// The tokens a refill at `now` would leave, without touching the bucket.
+    fn available(&self, per_minute: u32, burst: u32, now: Instant) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        (self.tokens + elapsed * per_minute as f64 / 60.0).min(burst as f64)
+    }
+
     fn refill(&mut self, per_minute: u32, burst: u32, now: Instant) {
         self.last_seen = now;
         if per_minute == 0 {
@@ -329,6 +686,9 @@ struct RateLimiterInner {
     buckets: HashMap<(RateScope, String), RateBucket>,
     sse_active_by_identity: HashMap<String, u32>,
     sse_active_global: u32,
+    sse_streams: BTreeMap<u64, SseStreamEntry>,
+    next_sse_stream: u64,
+    in_flight_by_identity: HashMap<String, u32>,
     last_cleanup: Option<Instant>,
 }
 
@@ -350,7 +710,7 @@ impl RateLimiterInner {
 
 fn classify_scope(method: &Method, path: &str) -> Option<RateScope> {
     if path.starts_with("/mcp") {
-        return Some(RateScope::Mcp);
+        return Some(RateScope::McpRead);
     }
 
     if !path.starts_with("/api/v1") {
@@ -379,10 +739,12 @@ fn classify_scope(method: &Method, path: &str) -> Option<RateScope> {
 fn is_sse_route(path: &str) -> bool {
     let normalized = path.trim_end_matches('/');
     normalized == "/api/v1/events"
+        || normalized == "/api/v1/notifications/stream"
         || (normalized.starts_with("/api/v1/projects/") && normalized.ends_with("/events"))
 }
 
-fn request_identity(headers: &HeaderMap, auth_enabled: bool) -> String {
+/// Who a request counts against: its bearer token when auth is on, otherwise its client IP.
+pub fn request_identity(headers: &HeaderMap, client: Option<IpAddr>, auth_enabled: bool) -> String {
     if auth_enabled {
         if let Some(token) = headers
             .get(AUTHORIZATION)
@@ -395,11 +757,10 @@ fn request_identity(headers: &HeaderMap, auth_enabled: bool) -> String {
         return "token:missing".to_string();
     }
 
-    if let Some(ip) = first_forwarded_ip(headers) {
-        return format!("ip:{ip}");
+    match client {
+        Some(ip) => format!("ip:{ip}"),
+        None => "ip:anonymous".to_string(),
     }
-
-    "ip:anonymous".to_string()
 }
 
 fn parse_bearer_token(value: &str) -> Option<&str> {
@@ -418,20 +779,6 @@ fn parse_bearer_token(value: &str) -> Option<&str> {
     Some(token)
 }
 
-fn first_forwarded_ip(headers: &HeaderMap) -> Option<String> {
-    extract_first_ip(headers, "x-forwarded-for").or_else(|| extract_first_ip(headers, "x-real-ip"))
-}
-
-fn extract_first_ip(headers: &HeaderMap, header_name: &'static str) -> Option<String> {
-    let value = headers.get(header_name)?.to_str().ok()?;
-    let first = value
-        .split(',')
-        .next()
-        .map(str::trim)
-        .filter(|ip| !ip.is_empty())?;
-    Some(first.to_string())
-}
-
 fn hash_prefix(input: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(input.as_bytes());
@@ -482,12 +829,18 @@ fn reset_after_seconds(tokens: f64, per_minute: u32, burst: u32) -> u64 {
 struct RateLimitBody {
     error: &'static str,
     message: String,
+    retry_after_secs: u64,
 }
 
+/// JSON-RPC error code for a throttled MCP request, in the implementation-defined server
+/// error range.
+const MCP_RATE_LIMITED_CODE: i64 = -32029;
+
 fn rate_limited_response(denial: RateDenial) -> Response {
     let body = Json(RateLimitBody {
         error: "rate_limited",
         message: denial.message,
+        retry_after_secs: denial.retry_after_secs,
     });
     let mut response = (StatusCode::TOO_MANY_REQUESTS, body).into_response();
 
@@ -504,10 +857,84 @@ fn rate_limited_response(denial: RateDenial) -> Response {
     response
 }
 
-fn sse_capacity_response(denial: SseCapDenied) -> Response {
+/// Answers a throttled MCP call with a JSON-RPC error for each request `id` in the body, so
+/// the agent sees a tool error carrying `retry_after_secs` rather than an HTTP failure.
+/// Notifications and unreadable bodies get the plain 429.
+fn mcp_rate_limited_response(body: &[u8], denial: RateDenial) -> Response {
+    let ids = jsonrpc_request_ids(body);
+    if ids.is_empty() {
+        return rate_limited_response(denial);
+    }
+
+    let error = |id: Value| {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": MCP_RATE_LIMITED_CODE,
+                "message": format!(
+                    "{} Wait {}s before calling lattice again.",
+                    denial.message, denial.retry_after_secs
+                ),
+                "data": {
+                    "error": "rate_limited",
+                    "retry_after_secs": denial.retry_after_secs,
+                    "limit": denial.limit,
+                    "reset_after_secs": denial.reset_after_secs,
+                },
+            },
+        })
+    };
+    let body = match <[Value; 1]>::try_from(ids) {
+        Ok([id]) => error(id),
+        Err(ids) => Value::Array(ids.into_iter().map(error).collect()),
+    };
+
+    let mut response = Json(body).into_response();
+    set_rate_limit_headers(
+        &mut response,
+        &RateAllowance {
+            limit: denial.limit,
+            remaining: denial.remaining,
+            reset_after_secs: denial.reset_after_secs,
+        },
+    );
+    set_header_u64(&mut response, RETRY_AFTER.as_str(), denial.retry_after_secs);
+    response
+}
+
+/// The `id` of a JSON-RPC request, or of every request in a batch; notifications have none.
+fn jsonrpc_request_ids(body: &[u8]) -> Vec<Value> {
+    let id = |message: &Value| message.get("id").filter(|id| !id.is_null()).cloned();
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(messages)) => messages.iter().filter_map(id).collect(),
+        Ok(message) => id(&message).into_iter().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Whether a JSON-RPC message, or any message in a batch, calls a tool outside
+/// `mcp::READ_ONLY_TOOLS`.
+fn calls_write_tool(body: &[u8]) -> bool {
+    let writes = |message: &Value| {
+        message.get("method").and_then(Value::as_str) == Some("tools/call")
+            && message
+                .pointer("/params/name")
+                .and_then(Value::as_str)
+                .is_none_or(|name| !mcp::READ_ONLY_TOOLS.contains(&name))
+    };
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(messages)) => messages.iter().any(writes),
+        Ok(message) => writes(&message),
+        Err(_) => false,
+    }
+}
+
+fn capacity_response(denial: CapacityDenied) -> Response {
     let body = Json(RateLimitBody {
         error: "rate_limited",
         message: denial.message,
+        retry_after_secs: denial.retry_after_secs,
     });
     let mut response = (StatusCode::TOO_MANY_REQUESTS, body).into_response();
     set_header_u64(&mut response, "x-ratelimit-limit", denial.limit as u64);
@@ -559,10 +986,32 @@ mod tests {
             classify_scope(&Method::GET, "/api/v1/projects/ROADMAP/events"),
             Some(RateScope::Sse)
         );
-        assert_eq!(classify_scope(&Method::POST, "/mcp"), Some(RateScope::Mcp));
+        assert_eq!(
+            classify_scope(&Method::POST, "/mcp"),
+            Some(RateScope::McpRead)
+        );
         assert_eq!(classify_scope(&Method::GET, "/"), None);
     }
 
+    #[test]
+    fn notification_streams_are_capped_as_sse_instead_of_in_flight_requests() {
+        // Open notification streams must not use up the in-flight slots other requests need.
+        for path in [
+            "/api/v1/notifications/stream",
+            "/api/v1/notifications/stream/",
+        ] {
+            assert_eq!(
+                classify_scope(&Method::GET, path),
+                Some(RateScope::Sse),
+                "{path}"
+            );
+        }
+        assert_eq!(
+            classify_scope(&Method::GET, "/api/v1/notifications"),
+            Some(RateScope::Read)
+        );
+    }
+
     #[test]
     fn token_bucket_denies_after_burst_and_recovers() {
         let write_burst = 10;
@@ -592,6 +1041,48 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn budget_reports_remaining_tokens_without_spending_them() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            mcp_write_per_min: 60,
+            mcp_write_burst: 5,
+            ..RateLimitConfig::default()
+        });
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.check_with_now(RateScope::McpWrite, "token:a", start);
+        }
+        let _slot = limiter
+            .try_acquire_in_flight_slot("token:a")
+            .expect("slot should be available");
+
+        let scope = |budget: &RateBudget, scope: RateScope| {
+            budget
+                .scopes
+                .iter()
+                .find(|entry| entry.scope == scope)
+                .cloned()
+                .expect("every scope should be reported")
+        };
+        let budget = limiter.budget_with_now("token:a", start);
+        assert_eq!(budget.scopes.len(), RateScope::ALL.len());
+        let writes = scope(&budget, RateScope::McpWrite);
+        assert_eq!((writes.limit, writes.burst), (60, 5));
+        assert_eq!(writes.remaining, 2);
+        assert_eq!(writes.reset_after_secs, 3);
+        assert_eq!(budget.in_flight, 1);
+
+        let again = limiter.budget_with_now("token:a", start);
+        assert_eq!(scope(&again, RateScope::McpWrite).remaining, 2);
+        let later = limiter.budget_with_now("token:a", start + Duration::from_secs(2));
+        assert_eq!(scope(&later, RateScope::McpWrite).remaining, 4);
+
+        let fresh = limiter.budget_with_now("token:b", start);
+        assert_eq!(scope(&fresh, RateScope::McpWrite).remaining, 5);
+        assert_eq!(scope(&fresh, RateScope::McpWrite).reset_after_secs, 0);
+        assert_eq!(fresh.in_flight, 0);
+    }
+
     #[test]
     fn sse_connection_slots_release_on_drop() {
         let sse_max_per_identity = 5;
@@ -621,4 +1112,135 @@ mod tests {
             "slot should become available after drop"
         );
     }
+
+    #[tokio::test]
+    async fn in_flight_slots_are_held_until_the_response_body_is_sent() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            max_in_flight_per_identity: 2,
+            ..RateLimitConfig::default()
+        });
+        let first = limiter
+            .try_acquire_in_flight_slot("token:a")
+            .expect("first slot should be available");
+        let second = limiter
+            .try_acquire_in_flight_slot("token:a")
+            .expect("second slot should be available");
+        let denied = limiter
+            .try_acquire_in_flight_slot("token:a")
+            .expect_err("third concurrent request should be denied");
+        assert_eq!(denied.limit, 2);
+        assert!(limiter.try_acquire_in_flight_slot("token:b").is_ok());
+
+        drop(first);
+        let body = Body::new(LeasedBody {
+            inner: Body::from("done"),
+            _lease: second,
+        });
+        let third = limiter
+            .try_acquire_in_flight_slot("token:a")
+            .expect("a finished request frees its slot");
+        assert!(limiter.try_acquire_in_flight_slot("token:a").is_err());
+
+        let bytes = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .expect("body should read");
+        assert_eq!(&bytes[..], b"done");
+        assert!(limiter.try_acquire_in_flight_slot("token:a").is_ok());
+        drop(third);
+    }
+
+    #[test]
+    fn identity_is_the_token_hash_or_the_client_ip() {
+        let mut headers = HeaderMap::new();
+        let client: IpAddr = "198.51.100.4".parse().expect("valid ip");
+        assert_eq!(
+            request_identity(&headers, Some(client), false),
+            "ip:198.51.100.4"
+        );
+        assert_eq!(request_identity(&headers, None, false), "ip:anonymous");
+        assert_eq!(
+            request_identity(&headers, Some(client), true),
+            "token:missing"
+        );
+
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        assert!(request_identity(&headers, Some(client), true).starts_with("token:"));
+    }
+
+    #[tokio::test]
+    async fn throttled_mcp_calls_get_a_jsonrpc_error_with_retry_after() {
+        let denial = || RateDenial {
+            limit: 80,
+            remaining: 0,
+            reset_after_secs: 15,
+            retry_after_secs: 3,
+            message: "rate limit exceeded for mcp write requests".to_string(),
+        };
+
+        let response = mcp_rate_limited_response(
+            br#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{}}"#,
+            denial(),
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[RETRY_AFTER], "3");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body should read");
+        let body: Value = serde_json::from_slice(&bytes).expect("body should be json");
+        assert_eq!(body["id"], 7);
+        assert_eq!(body["error"]["code"], MCP_RATE_LIMITED_CODE);
+        assert_eq!(body["error"]["data"]["retry_after_secs"], 3);
+
+        let notification = mcp_rate_limited_response(
+            br#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            denial(),
+        );
+        assert_eq!(notification.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn mcp_tool_calls_are_split_into_read_and_write_scopes() {
+        let call = |name: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": {} },
+            })
+        };
+        let bytes = |value: Value| serde_json::to_vec(&value).expect("json should serialize");
+
+        assert!(!calls_write_tool(&bytes(call("lattice_get_board"))));
+        assert!(calls_write_tool(&bytes(call("lattice_move_task"))));
+        assert!(calls_write_tool(&bytes(json!([
+            call("lattice_list_tasks"),
+            call("lattice_create_task"),
+        ]))));
+        assert!(!calls_write_tool(&bytes(json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/list",
+        }))));
+        assert!(!calls_write_tool(b"not json"));
+
+        let limiter = RateLimiter::new(RateLimitConfig {
+            mcp_burst: 1,
+            mcp_write_burst: 1,
+            ..RateLimitConfig::default()
+        });
+        let now = Instant::now();
+        assert!(matches!(
+            limiter.check_with_now(RateScope::McpRead, "agent", now),
+            RateDecision::Allow(_)
+        ));
+        assert!(matches!(
+            limiter.check_with_now(RateScope::McpRead, "agent", now),
+            RateDecision::Deny(_)
+        ));
+        // A chatty reader does not use up the write budget.
+        assert!(matches!(
+            limiter.check_with_now(RateScope::McpWrite, "agent", now),
+            RateDecision::Allow(_)
+        ));
+    }
 }
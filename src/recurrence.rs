@@ -0,0 +1,321 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveDate, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use sqlx::AnyPool;
+use tokio::time::MissedTickBehavior;
+
+use crate::config::FieldLimitConfig;
+use crate::db::models::TaskRecurrenceRecord;
+use crate::db::queries;
+use crate::error::{AppError, AppResult};
+use crate::state::AppState;
+
+const RECURRENCE_SCAN_INTERVAL_SECS: u64 = 60;
+/// How far ahead to look for a run; long enough for schedules that only fire on 29 February.
+const MAX_LOOKAHEAD_DAYS: u32 = 366 * 8;
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Periodically copies tasks whose recurrence is due, emitting `task.created` for each copy.
+///
+/// A run missed during downtime produces one copy on the next scan, not one per missed slot.
+pub fn spawn_recurrence_scheduler(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(RECURRENCE_SCAN_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            run_due_recurrences(&state.db, &state.config.field_limits, Utc::now()).await;
+        }
+    });
+}
+
+async fn run_due_recurrences(pool: &AnyPool, limits: &FieldLimitConfig, now: DateTime<Utc>) {
+    let due = match queries::list_due_recurrences(pool, &timestamp(now)).await {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(error = ?error, "failed to load due recurrences");
+            return;
+        }
+    };
+
+    for recurrence in due {
+        if let Err(error) = run_recurrence(pool, limits, &recurrence, now).await {
+            tracing::error!(
+                error = ?error,
+                task_id = %recurrence.task_id,
+                "failed to create recurring task"
+            );
+        }
+    }
+}
+
+async fn run_recurrence(
+    pool: &AnyPool,
+    limits: &FieldLimitConfig,
+    recurrence: &TaskRecurrenceRecord,
+    now: DateTime<Utc>,
+) -> AppResult<()> {
+    let schedule = Schedule::parse(&recurrence.schedule)?;
+    let timezone = queries::project_timezone(pool, &recurrence.project_slug).await?;
+    let next_run_at = schedule.next_after(now, timezone).map(timestamp);
+    if let Some(task) =
+        queries::run_task_recurrence(pool, limits, recurrence, next_run_at.as_deref()).await?
+    {
+        tracing::info!(
+            project = %recurrence.project_slug,
+            task_number = task.task_number,
+            "created recurring task"
+        );
+    }
+    Ok(())
+}
+
+pub fn timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// A five-field cron expression (minute, hour, day of month, month, day of week) or one of
+/// `@hourly`, `@daily`, `@weekly`, `@monthly`, and `@yearly`.
+///
+/// Fields take `*`, numbers, `a-b` ranges, `,` lists, and `/n` steps. Months and weekdays also
+/// take three-letter names, and both 0 and 7 mean Sunday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// When both day fields are restricted, cron fires on a day matching either one.
+    either_day: bool,
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> AppResult<Self> {
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let invalid = |reason: String| {
+            AppError::BadRequest(format!("invalid schedule '{expression}': {reason}"))
+        };
+        let fields = expanded.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(invalid(
+                "expected five fields: minute hour day-of-month month day-of-week".to_string(),
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, WEEKDAY_NAMES).map_err(invalid)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[]).map_err(invalid)?,
+            hours: parse_field(hour, 0, 23, &[]).map_err(invalid)?,
+            days_of_month: parse_field(day_of_month, 1, 31, &[]).map_err(invalid)?,
+            months: parse_field(month, 1, 12, MONTH_NAMES).map_err(invalid)?,
+            days_of_week,
+            either_day: !day_of_month.starts_with('*') && !day_of_week.starts_with('*'),
+        })
+    }
+
+    /// The first run strictly after `after`, reading the schedule as local time in `timezone`.
+    /// Local times skipped by a daylight saving change are passed over; repeated ones run once.
+    pub fn next_after(&self, after: DateTime<Utc>, timezone: Tz) -> Option<DateTime<Utc>> {
+        let mut date = after.with_timezone(&timezone).date_naive();
+        for _ in 0..MAX_LOOKAHEAD_DAYS {
+            if self.matches_day(date) {
+                for hour in (0..24).filter(|hour| has(self.hours, *hour)) {
+                    for minute in (0..60).filter(|minute| has(self.minutes, *minute)) {
+                        let Some(local) = date.and_hms_opt(hour, minute, 0) else {
+                            continue;
+                        };
+                        let Some(run) = timezone.from_local_datetime(&local).earliest() else {
+                            continue;
+                        };
+                        let run = run.with_timezone(&Utc);
+                        if run > after {
+                            return Some(run);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.either_day {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn has(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parses one cron field into a bit set of the values it allows.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let value = |token: &str| -> Result<u32, String> {
+        let parsed = match names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(token))
+        {
+            Some(index) => min + index as u32,
+            None => token
+                .parse::<u32>()
+                .map_err(|_| format!("'{token}' is not a number"))?,
+        };
+        if (min..=max).contains(&parsed) {
+            Ok(parsed)
+        } else {
+            Err(format!("{parsed} is outside {min}-{max}"))
+        }
+    };
+
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step in '{part}'"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else if step > 1 {
+            (value(range)?, max)
+        } else {
+            let single = value(range)?;
+            (single, single)
+        };
+        if start > end {
+            return Err(format!("range '{range}' runs backwards"));
+        }
+
+        for allowed in (start..=end).step_by(step as usize) {
+            bits |= 1 << allowed;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::Schedule;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .expect("timestamp should parse")
+            .with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str, timezone: Tz) -> Option<String> {
+        Schedule::parse(expression)
+            .expect("schedule should parse")
+            .next_after(at(after), timezone)
+            .map(super::timestamp)
+    }
+
+    #[test]
+    fn schedules_find_the_next_matching_minute() {
+        // 2026-03-04 is a Wednesday.
+        assert_eq!(
+            next("0 9 * * MON", "2026-03-04T10:00:00Z", Tz::UTC).as_deref(),
+            Some("2026-03-09T09:00:00.000Z")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-03-04T10:15:00Z", Tz::UTC).as_deref(),
+            Some("2026-03-04T10:30:00.000Z"),
+            "a run exactly at `after` is not repeated"
+        );
+        assert_eq!(
+            next("30 8 1,15 * *", "2026-03-04T00:00:00Z", Tz::UTC).as_deref(),
+            Some("2026-03-15T08:30:00.000Z")
+        );
+        assert_eq!(
+            next("@monthly", "2026-12-31T23:59:00Z", Tz::UTC).as_deref(),
+            Some("2027-01-01T00:00:00.000Z")
+        );
+        assert_eq!(
+            next("0 0 29 feb *", "2026-03-01T00:00:00Z", Tz::UTC).as_deref(),
+            Some("2028-02-29T00:00:00.000Z")
+        );
+        assert_eq!(
+            next("0 12 13 * 5", "2026-03-04T00:00:00Z", Tz::UTC).as_deref(),
+            Some("2026-03-06T12:00:00.000Z"),
+            "restricting both day fields fires on either"
+        );
+        assert_eq!(
+            next("0 6 * * 7", "2026-03-04T00:00:00Z", Tz::UTC).as_deref(),
+            Some("2026-03-08T06:00:00.000Z"),
+            "7 is Sunday"
+        );
+        assert_eq!(next("0 0 31 2 *", "2026-03-04T00:00:00Z", Tz::UTC), None);
+    }
+
+    #[test]
+    fn schedules_follow_the_project_timezone() {
+        let berlin: Tz = "Europe/Berlin".parse().expect("timezone should parse");
+        assert_eq!(
+            next("0 9 * * *", "2026-03-04T10:00:00Z", berlin).as_deref(),
+            Some("2026-03-05T08:00:00.000Z")
+        );
+        // Clocks jump from 02:00 to 03:00 on 2026-03-29, so that day has no 02:30.
+        assert_eq!(
+            next("30 2 * * *", "2026-03-28T02:00:00Z", berlin).as_deref(),
+            Some("2026-03-30T00:30:00.000Z")
+        );
+    }
+
+    #[test]
+    fn malformed_schedules_are_rejected() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * * funday",
+            "@fortnightly",
+        ] {
+            assert!(
+                Schedule::parse(expression).is_err(),
+                "'{expression}' should be rejected"
+            );
+        }
+    }
+}
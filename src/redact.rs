@@ -0,0 +1,127 @@
+//! Strips credentials from text before it reaches logs or error responses.
+//!
+//! Chat webhook urls carry their token in the path (`https://hooks.slack.com/services/…`),
+//! other services take one in the query string or userinfo, and database urls embed a
+//! password. Anything that may echo such a value goes through here first.
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex};
+
+/// Redacted text stands in for.
+const HIDDEN: &str = "…";
+
+static URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b[A-Za-z][A-Za-z0-9+.-]*://[^\s"'<>()\[\]{}]+"#).expect("url regex is valid")
+});
+
+/// A credential after `Authorization:`, or a token-shaped value after a bare `Bearer`/`Basic`,
+/// so prose such as "invalid bearer token" is left alone.
+static BEARER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)(\bauthorization:\s*(?:bearer|basic)\s+)[^\s,;]+|\b((?:bearer|basic)\s+)[A-Za-z0-9._~+/-]{20,}=*",
+    )
+    .expect("auth regex is valid")
+});
+
+/// Keeps only the scheme, host, and port of `value`:
+/// `https://hooks.slack.com/services/T0/B0/xyz` becomes `https://hooks.slack.com/…`.
+pub fn url(value: &str) -> String {
+    let Ok(parsed) = reqwest::Url::parse(value.trim()) else {
+        return HIDDEN.to_string();
+    };
+    let Some(host) = parsed.host_str() else {
+        return format!("{}:{HIDDEN}", parsed.scheme());
+    };
+
+    let mut redacted = format!("{}://{host}", parsed.scheme());
+    if let Some(port) = parsed.port() {
+        redacted.push_str(&format!(":{port}"));
+    }
+    let has_more = !matches!(parsed.path(), "" | "/")
+        || parsed.query().is_some()
+        || parsed.fragment().is_some();
+    if has_more {
+        redacted.push('/');
+        redacted.push_str(HIDDEN);
+    }
+    redacted
+}
+
+/// Masks the password of a connection string and keeps the rest, since a database url's path
+/// and options help to debug it: `postgres://app:pw@db/lattice` becomes
+/// `postgres://app:…@db/lattice`.
+pub fn credentials(value: &str) -> String {
+    let Some((scheme, rest)) = value.split_once("://") else {
+        return value.to_string();
+    };
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let Some(at) = rest[..authority_end].rfind('@') else {
+        return value.to_string();
+    };
+    match rest[..at].split_once(':') {
+        Some((user, _)) => format!("{scheme}://{user}:{HIDDEN}{}", &rest[at..]),
+        None => value.to_string(),
+    }
+}
+
+/// Redacts every url in free-form text, such as an error chain, along with `Bearer` and
+/// `Basic` credentials.
+pub fn text(value: &str) -> String {
+    let without_urls = URL.replace_all(value, |captures: &Captures| url(&captures[0]));
+    BEARER
+        .replace_all(&without_urls, |captures: &Captures| {
+            let scheme = captures.get(1).or_else(|| captures.get(2));
+            format!("{}{HIDDEN}", scheme.map_or("", |scheme| scheme.as_str()))
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{credentials, text, url};
+
+    #[test]
+    fn urls_keep_only_their_origin() {
+        assert_eq!(
+            url("https://hooks.slack.com/services/T0/B0/xyz"),
+            "https://hooks.slack.com/…"
+        );
+        assert_eq!(
+            url("https://user:pw@example.com:8443/?token=abc"),
+            "https://example.com:8443/…"
+        );
+        assert_eq!(url("https://example.com/"), "https://example.com");
+        assert_eq!(url("not a url"), "…");
+
+        assert_eq!(
+            credentials("postgres://app:s3cret@db:5432/lattice?sslmode=require"),
+            "postgres://app:…@db:5432/lattice?sslmode=require"
+        );
+        assert_eq!(
+            credentials("sqlite://./lattice.db?mode=rwc"),
+            "sqlite://./lattice.db?mode=rwc"
+        );
+
+        let error = "error sending request for url (https://discord.com/api/webhooks/1/tok): \
+                     Authorization: Bearer abc.def";
+        assert_eq!(
+            text(error),
+            "error sending request for url (https://discord.com/…): Authorization: Bearer …"
+        );
+        assert_eq!(
+            text("upstream sent basic dXNlcjpodW50ZXIyLWh1bnRlcjI="),
+            "upstream sent basic …"
+        );
+    }
+
+    #[test]
+    fn prose_about_credentials_is_left_alone() {
+        for message in [
+            "missing or invalid bearer token",
+            "use Basic auth, or a Bearer token for the api",
+        ] {
+            assert_eq!(text(message), message);
+        }
+    }
+}
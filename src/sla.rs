@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::{SecondsFormat, Utc};
+use tokio::time::MissedTickBehavior;
+
+use crate::db::models::{SlaPolicyRecord, TaskRecord};
+use crate::db::queries;
+use crate::event_kind::EventKind;
+use crate::state::AppState;
+use crate::webhooks::{self, WebhookPayload};
+
+const SLA_SCAN_INTERVAL_SECS: u64 = 60;
+
+/// Periodically checks SLA policies and emits `sla.breached` for tasks that overstayed.
+///
+/// Each task breaches a policy at most once per stay in the policy's status.
+pub fn spawn_sla_evaluator(state: AppState) {
+    tokio::spawn(async move {
+        if let Err(error) = run_evaluator(state).await {
+            tracing::error!(error = ?error, "sla evaluator terminated");
+        }
+    });
+}
+
+async fn run_evaluator(state: AppState) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .context("failed to build sla webhook client")?;
+    let mut interval = tokio::time::interval(Duration::from_secs(SLA_SCAN_INTERVAL_SECS));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        evaluate_policies(&state, &client).await;
+    }
+}
+
+async fn evaluate_policies(state: &AppState, client: &reqwest::Client) {
+    let policies = match queries::list_all_sla_policies(&state.db).await {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(error = ?error, "failed to load sla policies");
+            return;
+        }
+    };
+
+    for policy in policies {
+        let cutoff = (Utc::now() - chrono::Duration::hours(policy.max_hours))
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        let tasks = match queries::list_sla_breach_candidates(&state.db, &policy, &cutoff).await {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(error = ?error, policy_id = %policy.id, "failed to query sla breaches");
+                continue;
+            }
+        };
+
+        for task in tasks {
+            match queries::record_sla_breach(&state.db, &policy, &task).await {
+                Ok((task, detail)) => {
+                    notify_escalation_webhook(state, client, &policy, task, detail).await;
+                }
+                Err(error) => {
+                    tracing::error!(error = ?error, task_id = %task.id, "failed to record sla breach");
+                }
+            }
+        }
+    }
+}
+
+async fn notify_escalation_webhook(
+    state: &AppState,
+    client: &reqwest::Client,
+    policy: &SlaPolicyRecord,
+    task: TaskRecord,
+    detail: serde_json::Value,
+) {
+    let Some(webhook_id) = policy.webhook_id.as_deref() else {
+        return;
+    };
+
+    let webhook =
+        match queries::get_project_webhook(&state.db, &policy.project_slug, webhook_id).await {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::warn!(error = ?error, policy_id = %policy.id, "sla webhook not found");
+                return;
+            }
+        };
+
+    let payload = WebhookPayload {
+        event_id: uuid::Uuid::new_v4().to_string(),
+        event: EventKind::SlaBreached.to_string(),
+        project: policy.project_slug.clone(),
+        task_id: Some(task.id),
+        task_number: Some(task.task_number),
+        task_display_key: Some(queries::display_key(&policy.project_slug, task.task_number)),
+        actor: "system".to_string(),
+        actor_display_name: None,
+        detail,
+        created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        task: None,
+    };
+
+    if let Err(error) = webhooks::deliver_to_webhook(state, client, &webhook, &payload).await {
+        tracing::warn!(
+            error = ?error,
+            webhook_id = %webhook.id,
+            policy_id = %policy.id,
+            "sla escalation webhook delivery failed"
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use tokio::time::MissedTickBehavior;
+
+use crate::db::queries;
+use crate::state::AppState;
+
+const STALE_SCAN_INTERVAL_SECS: u64 = 300;
+
+/// Periodically emits `task.stale` for in-progress tasks nobody has touched in a while.
+///
+/// Each task is flagged once per quiet period; any update resets it.
+pub fn spawn_stale_detector(state: AppState) {
+    let Some(stale_after_days) = state.config.stale_threshold_days() else {
+        tracing::info!("stale task detection disabled");
+        return;
+    };
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(STALE_SCAN_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            scan_for_stale_tasks(&state, stale_after_days).await;
+        }
+    });
+}
+
+async fn scan_for_stale_tasks(state: &AppState, stale_after_days: u32) {
+    let cutoff = queries::timestamp_days_ago(stale_after_days);
+    let tasks = match queries::list_newly_stale_tasks(&state.db, &cutoff).await {
+        Ok(value) => value,
+        Err(error) => {
+            tracing::error!(error = ?error, "failed to query stale tasks");
+            return;
+        }
+    };
+
+    for task in tasks {
+        if let Err(error) = queries::mark_task_stale(&state.db, &task, stale_after_days).await {
+            tracing::error!(error = ?error, task_id = %task.id, "failed to flag stale task");
+        }
+    }
+}
@@ -1,24 +1,51 @@
-use std::sync::Arc;
+use std::net::SocketAddr;
+use std::sync::{Arc, OnceLock};
 
 use sqlx::AnyPool;
 
+use crate::api::attachments::UploadLocks;
 use crate::config::Config;
+use crate::db::secrets::Keyring;
+use crate::metrics::Metrics;
 use crate::rate_limit::RateLimiter;
+use crate::webhooks::health::DispatcherHealth;
 
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<Config>,
     pub db: AnyPool,
+    /// Seals and opens the webhook and inbound hook secrets stored in `db`.
+    pub keyring: Arc<Keyring>,
     pub rate_limiter: RateLimiter,
+    pub metrics: Metrics,
+    pub dispatcher: DispatcherHealth,
+    pub upload_locks: UploadLocks,
+    bound_addr: Arc<OnceLock<SocketAddr>>,
 }
 
 impl AppState {
     pub fn new(config: Config, db: AnyPool) -> Self {
         let rate_limiter = RateLimiter::new(config.rate_limits.clone());
+        let keyring = Arc::new(Keyring::from_config(&config.secret_keys));
         Self {
             config: Arc::new(config),
             db,
+            keyring,
             rate_limiter,
+            metrics: Metrics::default(),
+            dispatcher: DispatcherHealth::default(),
+            upload_locks: UploadLocks::default(),
+            bound_addr: Arc::new(OnceLock::new()),
         }
     }
+
+    /// Records the address the server actually listens on, which differs from the config
+    /// when the port is `0` or the socket was inherited.
+    pub fn set_bound_addr(&self, addr: SocketAddr) {
+        let _ = self.bound_addr.set(addr);
+    }
+
+    pub fn bound_addr(&self) -> Option<SocketAddr> {
+        self.bound_addr.get().copied()
+    }
 }
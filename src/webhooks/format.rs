@@ -0,0 +1,398 @@
+//! One-line descriptions of events for chat platforms, built from the event's detail.
+
+use serde_json::{Map, Value};
+
+use crate::event_kind::EventKind;
+
+/// Longest quoted user text (question, goal, comment) kept in a message.
+const MAX_QUOTE_CHARS: usize = 280;
+
+/// A readable sentence for `event`, or `None` when the action is unknown or its detail lacks
+/// the fields the sentence needs, in which case callers fall back to the raw detail.
+pub fn describe(event: &str, detail: &Value) -> Option<String> {
+    let text = |key: &str| detail.get(key).and_then(Value::as_str);
+    let number = |key: &str| detail.get(key).and_then(Value::as_i64);
+
+    let sentence = match event.parse::<EventKind>().ok()? {
+        EventKind::TaskCreated => format!(
+            "Created in {} with {} priority",
+            label(text("status")?),
+            text("priority")?
+        ),
+        EventKind::TaskUpdated => {
+            describe_update(detail.get("changes").and_then(Value::as_object)?)
+        }
+        EventKind::TaskMoved => {
+            let (from, to) = (text("from_status")?, text("to_status")?);
+            if from == to {
+                format!("Reordered within {}", label(to))
+            } else {
+                format!("Moved from {} → {}", label(from), label(to))
+            }
+        }
+        EventKind::TaskDeleted => "Deleted".to_string(),
+        EventKind::TaskArchived => match number("auto_archive_after_days") {
+            Some(days) => format!("Archived after {days} days in done"),
+            None => "Archived".to_string(),
+        },
+        EventKind::TaskUnarchived => "Restored from the archive".to_string(),
+        EventKind::TaskTransferred => format!(
+            "Transferred from {} to {}",
+            text("from_key")?,
+            text("to_key")?
+        ),
+        EventKind::TaskReviewStateChanged => format!(
+            "Review state {} → {}",
+            label(text("from_review_state")?),
+            label(text("to_review_state")?)
+        ),
+        EventKind::TaskPriorityChanged => format!(
+            "Priority {} → {}",
+            text("from_priority")?,
+            text("to_priority")?
+        ),
+        EventKind::TaskLabelsChanged => describe_labels(detail)?,
+        EventKind::TaskAssigned => match (text("from"), text("to")) {
+            (_, Some(to)) => format!("Assigned to {to}"),
+            (Some(from), None) => format!("Unassigned from {from}"),
+            (None, None) => return None,
+        },
+        EventKind::TaskDueDateChanged => match (text("from_due_at"), text("to_due_at")) {
+            (None, Some(to)) => format!("Due date set to {to}"),
+            (Some(from), Some(to)) => format!("Due date moved from {from} to {to}"),
+            (Some(from), None) => format!("Due date cleared (was {from})"),
+            (None, None) => return None,
+        },
+        EventKind::TaskDependencyAdded => format!("Blocked by {}", text("blocker_key")?),
+        EventKind::TaskDependencyRemoved => {
+            format!("No longer blocked by {}", text("blocker_key")?)
+        }
+        EventKind::TaskParentChanged => match (text("from_parent_key"), text("to_parent_key")) {
+            (_, Some(to)) => format!("Moved under {to}"),
+            (Some(from), None) => format!("Detached from {from}"),
+            (None, None) => return None,
+        },
+        EventKind::TaskMilestoneChanged => match (text("from_milestone"), text("to_milestone")) {
+            (_, Some(to)) => format!("Added to milestone \"{to}\""),
+            (Some(from), None) => format!("Removed from milestone \"{from}\""),
+            (None, None) => return None,
+        },
+        EventKind::TaskUndone => format!("Undid {}", text("undone_action")?),
+        EventKind::TaskStale => format!(
+            "No updates for {} days while {}",
+            number("stale_after_days")?,
+            label(text("status")?)
+        ),
+        EventKind::TaskMentioned => format!("Mentioned @{}", text("mentioned")?),
+        EventKind::SlaBreached => {
+            let mut sentence = format!(
+                "Breached SLA \"{}\": over {}h in {}",
+                text("policy")?,
+                number("max_hours")?,
+                label(text("status")?)
+            );
+            if let Some(priority) = detail.get("priority") {
+                let from = priority.get("from").and_then(Value::as_str)?;
+                let to = priority.get("to").and_then(Value::as_str)?;
+                sentence.push_str(&format!(", priority raised {from} → {to}"));
+            }
+            sentence
+        }
+        EventKind::ReviewRequested => format!("Review requested from {}", text("reviewer")?),
+        EventKind::ReviewCompleted => {
+            let verdict = match text("verdict")? {
+                "approved" => "approved",
+                "changes_requested" => "requested changes",
+                other => other,
+            };
+            match text("comment").filter(|comment| !comment.trim().is_empty()) {
+                Some(comment) => format!("{} {verdict}: {}", text("reviewer")?, quote(comment)),
+                None => format!("{} {verdict}", text("reviewer")?),
+            }
+        }
+        EventKind::QuestionCreated => format!("Question asked: {}", quote(text("question")?)),
+        EventKind::QuestionResolved => "Question resolved".to_string(),
+        EventKind::QuestionAccepted => "Answer accepted".to_string(),
+        EventKind::QuestionsResolved => format!("Resolved {} questions", number("count")?),
+        EventKind::QuestionDisputed => format!("Answer disputed: {}", quote(text("reason")?)),
+        EventKind::SpecUpdated => format!("Spec section {} updated", label(text("section")?)),
+        EventKind::GoalUpdated => format!("Goal changed to: {}", quote(text("to_goal")?)),
+        EventKind::LabelsRenamed => {
+            let affected = number("affected")?;
+            let tasks = if affected == 1 { "task" } else { "tasks" };
+            format!(
+                "Renamed label {} to {} on {affected} {tasks}",
+                text("from")?,
+                text("to")?
+            )
+        }
+        EventKind::ProjectRenamed => format!(
+            "Renamed from {} to {}",
+            text("from_slug")?,
+            text("to_slug")?
+        ),
+        EventKind::WebhookCreated => {
+            format!("Added {} webhook \"{}\"", text("platform")?, text("name")?)
+        }
+        EventKind::WebhookUpdated => describe_webhook_update(detail)?,
+        EventKind::WebhookDeleted => format!("Removed webhook \"{}\"", text("name")?),
+        EventKind::SubtaskCreated => format!("Added subtask \"{}\"", text("title")?),
+        EventKind::SubtaskUpdated => {
+            let verb = if detail.get("done").and_then(Value::as_bool)? {
+                "Completed"
+            } else {
+                "Updated"
+            };
+            match text("title") {
+                Some(title) => format!("{verb} subtask \"{title}\""),
+                None => format!("{verb} a subtask"),
+            }
+        }
+        EventKind::SubtaskDeleted => match text("title") {
+            Some(title) => format!("Removed subtask \"{title}\""),
+            None => "Removed a subtask".to_string(),
+        },
+        EventKind::SubtasksUpdated => {
+            let count = detail.get("subtask_ids").and_then(Value::as_array)?.len();
+            format!("Updated {count} subtasks")
+        }
+        EventKind::AttachmentCreated => format!(
+            "Attached {} ({})",
+            text("filename")?,
+            format_size(number("size_bytes")?)
+        ),
+        EventKind::AttachmentDeleted => format!("Removed attachment {}", text("filename")?),
+        EventKind::EventsHeld => describe_held(detail)?,
+        EventKind::Digest => describe_digest(detail)?,
+        EventKind::Test => text("message")?.to_string(),
+        EventKind::ReviewItemUpdated | EventKind::TasksReordered => return None,
+    };
+    Some(sentence)
+}
+
+/// `Updated status (ready → in_progress), description` from a `task.updated` change set.
+fn describe_update(changes: &Map<String, Value>) -> String {
+    let parts: Vec<String> = changes
+        .iter()
+        .map(|(field, change)| {
+            let from = change.get("from");
+            let to = change.get("to");
+            match (field.as_str(), from, to) {
+                ("labels", Some(from), Some(to)) => {
+                    format!("labels ({} → {})", label_list(from), label_list(to))
+                }
+                ("title", _, Some(Value::String(to))) => format!("title (\"{to}\")"),
+                (_, Some(Value::String(from)), Some(Value::String(to))) => {
+                    format!("{field} ({} → {})", label(from), label(to))
+                }
+                _ => field.clone(),
+            }
+        })
+        .collect();
+
+    if parts.is_empty() {
+        "Updated".to_string()
+    } else {
+        format!("Updated {}", parts.join(", "))
+    }
+}
+
+/// `Labels added api, ui; removed bug` from a `task.labels_changed` delta.
+fn describe_labels(detail: &Value) -> Option<String> {
+    let mut parts = Vec::new();
+    for key in ["added", "removed"] {
+        let labels = detail.get(key)?;
+        if labels.as_array().is_some_and(|labels| !labels.is_empty()) {
+            parts.push(format!("{key} {}", label_list(labels)));
+        }
+    }
+    Some(format!("Labels {}", parts.join("; ")))
+}
+
+fn describe_digest(detail: &Value) -> Option<String> {
+    let mut lines = vec![detail.get("summary")?.as_str()?.to_string()];
+    for (key, heading) in [
+        ("completed", "Completed"),
+        ("new_questions", "New questions"),
+        ("stalled", "Stalled"),
+    ] {
+        let items: Vec<&str> = detail
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        if !items.is_empty() {
+            lines.push(format!("{heading}: {}", items.join("; ")));
+        }
+    }
+    Some(lines.join("\n"))
+}
+
+/// `Held 14 events during quiet hours: task.moved ×10, task.created ×4`.
+fn describe_held(detail: &Value) -> Option<String> {
+    let count = detail.get("count")?.as_u64()?;
+    let reason = match detail.get("reason")?.as_str()? {
+        "quiet_hours" => "during quiet hours",
+        _ => "over the delivery limit",
+    };
+    let mut events: Vec<(&String, u64)> = detail
+        .get("events")?
+        .as_object()?
+        .iter()
+        .filter_map(|(event, count)| Some((event, count.as_u64()?)))
+        .collect();
+    events.sort_by(|left, right| right.1.cmp(&left.1).then_with(|| left.0.cmp(right.0)));
+    let breakdown: Vec<String> = events
+        .iter()
+        .map(|(event, count)| format!("{event} ×{count}"))
+        .collect();
+    let noun = if count == 1 { "event" } else { "events" };
+    Some(format!(
+        "Held {count} {noun} {reason}: {}",
+        breakdown.join(", ")
+    ))
+}
+
+/// `Updated webhook "alerts": events, url, secret` from a `webhook.updated` detail.
+fn describe_webhook_update(detail: &Value) -> Option<String> {
+    let name = detail.get("name").and_then(Value::as_str)?;
+    let mut fields: Vec<&str> = detail
+        .get("changes")
+        .and_then(Value::as_object)?
+        .keys()
+        .map(String::as_str)
+        .filter(|field| *field != "has_secret")
+        .collect();
+    if detail.get("secret_changed").and_then(Value::as_bool)? {
+        fields.push("secret");
+    }
+    if fields.is_empty() {
+        return Some(format!("Updated webhook \"{name}\""));
+    }
+    Some(format!("Updated webhook \"{name}\": {}", fields.join(", ")))
+}
+
+/// `in_progress` reads as `in progress`.
+fn label(value: &str) -> String {
+    value.replace('_', " ")
+}
+
+fn label_list(value: &Value) -> String {
+    let labels: Vec<&str> = value
+        .as_array()
+        .map(|labels| labels.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if labels.is_empty() {
+        "none".to_string()
+    } else {
+        labels.join(", ")
+    }
+}
+
+/// User text in quotes, cut at `MAX_QUOTE_CHARS`.
+fn quote(value: &str) -> String {
+    let value = value.trim();
+    match value.char_indices().nth(MAX_QUOTE_CHARS) {
+        Some((cut, _)) => format!("\"{}…\"", &value[..cut]),
+        None => format!("\"{value}\""),
+    }
+}
+
+fn format_size(bytes: i64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::describe;
+
+    #[test]
+    fn common_actions_read_as_sentences() {
+        let cases = [
+            (
+                "task.moved",
+                json!({ "from_status": "ready", "to_status": "in_progress" }),
+                "Moved from ready → in progress",
+            ),
+            (
+                "question.created",
+                json!({ "question_id": "q-1", "question": "Which region?" }),
+                "Question asked: \"Which region?\"",
+            ),
+            (
+                "task.updated",
+                json!({ "changes": {
+                    "priority": { "from": "low", "to": "high" },
+                    "labels": { "from": [], "to": ["api"] },
+                    "description": { "from_sha256": "a", "to_sha256": "b", "diff": [] },
+                } }),
+                "Updated description, labels (none → api), priority (low → high)",
+            ),
+            (
+                "task.priority_changed",
+                json!({ "from_priority": "medium", "to_priority": "critical" }),
+                "Priority medium → critical",
+            ),
+            (
+                "task.labels_changed",
+                json!({ "added": ["api", "ui"], "removed": ["bug"], "labels": ["api", "ui"] }),
+                "Labels added api, ui; removed bug",
+            ),
+            (
+                "labels.renamed",
+                json!({ "from": "bug", "to": "defect", "affected": 3 }),
+                "Renamed label bug to defect on 3 tasks",
+            ),
+            (
+                "webhook.updated",
+                json!({
+                    "webhook_id": "w-1",
+                    "name": "alerts",
+                    "changes": {
+                        "has_secret": { "from": false, "to": true },
+                        "url": { "from": "https://a.example/…", "to": "https://b.example/…" },
+                    },
+                    "secret_changed": true,
+                }),
+                "Updated webhook \"alerts\": url, secret",
+            ),
+            (
+                "events.held",
+                json!({
+                    "reason": "quiet_hours",
+                    "count": 14,
+                    "events": { "task.created": 4, "task.moved": 10 },
+                }),
+                "Held 14 events during quiet hours: task.moved ×10, task.created ×4",
+            ),
+            (
+                "review.completed",
+                json!({ "reviewer": "ana", "verdict": "changes_requested", "comment": "" }),
+                "ana requested changes",
+            ),
+        ];
+        for (event, detail, expected) in cases {
+            assert_eq!(
+                describe(event, &detail).as_deref(),
+                Some(expected),
+                "{event}"
+            );
+        }
+
+        assert_eq!(describe("task.moved", &json!({})), None);
+        assert_eq!(describe("custom.event", &json!({ "a": 1 })), None);
+    }
+}
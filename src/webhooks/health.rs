@@ -0,0 +1,168 @@
+//! Liveness of the webhook dispatcher, shared between the dispatcher task and the readiness
+//! and admin endpoints.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::redact;
+
+/// A dispatcher that has not finished a poll for this long is reported as stalled. Deliveries
+/// are sent one after another, so a poll can legitimately take a few slow timeouts.
+const STALL_AFTER: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Debug, Default)]
+pub struct DispatcherHealth {
+    inner: Arc<Mutex<DispatcherState>>,
+}
+
+#[derive(Debug, Default)]
+struct DispatcherState {
+    running: bool,
+    /// When the dispatcher last started, handed out an event, or finished a poll.
+    heartbeat: Option<Instant>,
+    last_poll_at: Option<DateTime<Utc>>,
+    /// The last system event handed to webhooks, so a restart resumes after it.
+    cursor: Option<(String, String)>,
+    pending_retries: usize,
+    restarts: u32,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+}
+
+/// What `/readyz` and `GET /api/v1/admin/stats` report about the dispatcher.
+#[derive(Debug, Serialize)]
+pub struct DispatcherReport {
+    /// `running`, `stalled`, `restarting`, or `not_started`.
+    pub status: &'static str,
+    pub healthy: bool,
+    pub last_poll_at: Option<String>,
+    /// `created_at` of the last event handed to webhooks.
+    pub last_event_at: Option<String>,
+    /// Seconds between that event and the newest one, `0` when caught up.
+    pub lag_secs: i64,
+    pub pending_retries: usize,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+}
+
+impl DispatcherHealth {
+    pub fn started(&self) {
+        let mut state = self.state();
+        state.running = true;
+        state.heartbeat = Some(Instant::now());
+    }
+
+    /// Records the last event handed to webhooks. Called per event, so a restart in the
+    /// middle of a batch does not deliver the earlier events twice.
+    pub fn advanced(&self, created_at: &str, event_id: &str) {
+        let mut state = self.state();
+        state.heartbeat = Some(Instant::now());
+        state.cursor = Some((created_at.to_string(), event_id.to_string()));
+    }
+
+    pub fn polled(&self, pending_retries: usize) {
+        let mut state = self.state();
+        state.heartbeat = Some(Instant::now());
+        state.last_poll_at = Some(Utc::now());
+        state.pending_retries = pending_retries;
+    }
+
+    pub fn stopped(&self, error: &anyhow::Error) {
+        let mut state = self.state();
+        state.running = false;
+        state.restarts += 1;
+        state.pending_retries = 0;
+        state.last_error = Some(redact::text(&format!("{error:#}")));
+        state.last_error_at = Some(Utc::now());
+    }
+
+    pub fn cursor(&self) -> Option<(String, String)> {
+        self.state().cursor.clone()
+    }
+
+    /// `latest_event_at` is the newest system event's `created_at`, for the lag.
+    pub fn report(&self, latest_event_at: Option<&str>) -> DispatcherReport {
+        let state = self.state();
+        let stalled = state
+            .heartbeat
+            .is_none_or(|heartbeat| heartbeat.elapsed() > STALL_AFTER);
+        let status = match (state.running, stalled) {
+            (true, false) => "running",
+            (true, true) => "stalled",
+            (false, _) if state.restarts > 0 => "restarting",
+            (false, _) => "not_started",
+        };
+        let last_event_at = state.cursor.as_ref().map(|(created_at, _)| created_at);
+
+        DispatcherReport {
+            status,
+            healthy: status == "running",
+            last_poll_at: state.last_poll_at.map(timestamp),
+            last_event_at: last_event_at.cloned(),
+            lag_secs: lag_secs(last_event_at.map(String::as_str), latest_event_at),
+            pending_retries: state.pending_retries,
+            restarts: state.restarts,
+            last_error: state.last_error.clone(),
+            last_error_at: state.last_error_at.map(timestamp),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, DispatcherState> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn lag_secs(dispatched: Option<&str>, latest: Option<&str>) -> i64 {
+    let parse =
+        |value: Option<&str>| value.and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+    match (parse(dispatched), parse(latest)) {
+        (Some(dispatched), Some(latest)) => (latest - dispatched).num_seconds().max(0),
+        _ => 0,
+    }
+}
+
+fn timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DispatcherHealth;
+
+    #[test]
+    fn report_tracks_polls_lag_and_restarts() {
+        let health = DispatcherHealth::default();
+        let report = health.report(None);
+        assert_eq!((report.status, report.healthy), ("not_started", false));
+
+        health.started();
+        health.advanced("2026-03-01T10:00:00Z", "event-1");
+        health.polled(2);
+        let report = health.report(Some("2026-03-01T10:00:45Z"));
+        assert_eq!((report.status, report.healthy), ("running", true));
+        assert_eq!(report.lag_secs, 45);
+        assert_eq!(report.pending_retries, 2);
+
+        health.stopped(&anyhow::anyhow!(
+            "request failed for https://hooks.slack.com/services/T0/B0/secret"
+        ));
+        let report = health.report(None);
+        assert_eq!((report.status, report.healthy), ("restarting", false));
+        assert_eq!(report.restarts, 1);
+        assert_eq!(
+            report.last_error.as_deref(),
+            Some("request failed for https://hooks.slack.com/…")
+        );
+        assert_eq!(
+            health.cursor().map(|(_, id)| id).as_deref(),
+            Some("event-1"),
+            "a restart resumes after the last dispatched event"
+        );
+    }
+}
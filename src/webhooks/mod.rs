@@ -1,3 +1,7 @@
+mod format;
+pub mod health;
+mod throttle;
+
 use std::fmt::Write;
 use std::time::{Duration, Instant};
 
@@ -8,43 +12,160 @@ use serde_json::{json, Value};
 use sha2::Sha256;
 use tokio::time::MissedTickBehavior;
 
+use crate::config::{WebhookDeliveryConfig, MAX_WEBHOOK_RETRY_BACKOFF_SECS};
 use crate::db::models::{SystemEventRecord, WebhookRecord};
 use crate::db::queries;
+use crate::db::secrets::Keyring;
 use crate::error::AppResult;
+use crate::event_kind::EventKind;
+use crate::redact;
 use crate::state::AppState;
 
+use self::health::DispatcherReport;
+use self::throttle::DeliveryGates;
+
 const DISPATCH_POLL_INTERVAL_MS: u64 = 1000;
-const RETRY_DELAY_SECONDS: u64 = 30;
 const MAX_RETRY_QUEUE: usize = 512;
 const DISPATCH_BATCH_SIZE: i64 = 100;
+const RESTART_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// A dispatcher that ran this long before failing restarts without the accumulated backoff.
+const RESTART_BACKOFF_RESET_AFTER: Duration = Duration::from_secs(600);
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WebhookPayload {
+    /// Stable across retries; used as the CloudEvents `id`.
+    #[serde(skip)]
+    pub event_id: String,
     pub event: String,
     pub project: String,
     pub task_id: Option<String>,
     pub task_number: Option<i64>,
     pub task_display_key: Option<String>,
     pub actor: String,
+    pub actor_display_name: Option<String>,
     pub detail: Value,
     pub created_at: String,
+    /// The task as it is when the event is delivered, for webhooks with
+    /// `include_task_snapshot` set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task: Option<TaskSnapshot>,
+}
+
+/// A compact view of the task an event is about, so consumers need not call back for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSnapshot {
+    pub title: String,
+    pub status: String,
+    pub priority: String,
+    pub labels: Vec<String>,
+    /// The task in the web UI; a path unless `LATTICE_PUBLIC_URL` is set.
+    pub url: String,
+}
+
+/// `WebhookPayload` as a generic webhook receives it, shaped by the webhook's pinned
+/// `schema_version`.
+#[derive(Debug, Serialize)]
+struct VersionedPayload<'a> {
+    schema_version: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a str>,
+    #[serde(flatten)]
+    payload: &'a WebhookPayload,
+}
+
+impl<'a> VersionedPayload<'a> {
+    fn new(payload: &'a WebhookPayload, schema_version: i64) -> Self {
+        Self {
+            schema_version,
+            id: (schema_version >= 2).then_some(payload.event_id.as_str()),
+            payload,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct PendingDelivery {
     webhook: WebhookRecord,
     payload: WebhookPayload,
+    /// Which retry this is, from 1.
+    attempt: u32,
     due_at: Instant,
 }
 
+/// A webhook's timeout and retry policy: its own overrides, or the server defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DeliveryPolicy {
+    timeout: Duration,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl DeliveryPolicy {
+    fn for_webhook(webhook: &WebhookRecord, defaults: &WebhookDeliveryConfig) -> Self {
+        let seconds = |value: i64| Duration::from_secs(u64::try_from(value).unwrap_or(1));
+        Self {
+            timeout: seconds(
+                webhook
+                    .timeout_secs
+                    .unwrap_or(defaults.webhook_timeout_secs),
+            ),
+            max_retries: u32::try_from(webhook.max_retries.unwrap_or(defaults.webhook_max_retries))
+                .unwrap_or(0),
+            backoff: seconds(
+                webhook
+                    .retry_backoff_secs
+                    .unwrap_or(defaults.webhook_retry_backoff_secs),
+            ),
+        }
+    }
+
+    /// The wait before retry `attempt`: the backoff, doubled for each retry before it, and
+    /// never more than `MAX_WEBHOOK_RETRY_BACKOFF_SECS`.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let cap = Duration::from_secs(MAX_WEBHOOK_RETRY_BACKOFF_SECS.unsigned_abs());
+        self.backoff
+            .checked_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .map_or(cap, |delay| delay.min(cap))
+    }
+}
+
+/// Runs the dispatcher under a supervisor that restarts it with backoff when it fails or
+/// panics, so deliveries do not silently stop. Its liveness is tracked in `state.dispatcher`.
 pub fn spawn_dispatcher(state: AppState) {
     tokio::spawn(async move {
-        if let Err(error) = run_dispatcher(state).await {
-            tracing::error!(error = ?error, "webhook dispatcher terminated");
+        let mut backoff = RESTART_BACKOFF_MIN;
+        loop {
+            let started = Instant::now();
+            let error = match tokio::spawn(run_dispatcher(state.clone())).await {
+                Ok(Ok(())) => anyhow!("webhook dispatcher exited"),
+                Ok(Err(error)) => error,
+                Err(error) => anyhow!("webhook dispatcher panicked: {error}"),
+            };
+            if started.elapsed() >= RESTART_BACKOFF_RESET_AFTER {
+                backoff = RESTART_BACKOFF_MIN;
+            }
+
+            state.dispatcher.stopped(&error);
+            tracing::error!(
+                error = %redact::text(&format!("{error:#}")),
+                restart_in_secs = backoff.as_secs(),
+                "webhook dispatcher terminated, restarting"
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
         }
     });
 }
 
+/// The dispatcher's liveness, with its lag behind the newest system event.
+pub async fn dispatcher_report(state: &AppState) -> AppResult<DispatcherReport> {
+    let latest = queries::latest_system_event_cursor(&state.db, None, &[]).await?;
+    Ok(state
+        .dispatcher
+        .report(latest.as_ref().map(|(created_at, _)| created_at.as_str())))
+}
+
 pub async fn send_test_webhook(
     state: &AppState,
     project_slug: &str,
@@ -52,51 +173,105 @@ pub async fn send_test_webhook(
 ) -> AppResult<()> {
     let webhook = queries::get_project_webhook(&state.db, project_slug, webhook_id).await?;
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(5))
         .build()
         .context("failed to build webhook client")?;
     let payload = WebhookPayload {
-        event: "test".to_string(),
+        event_id: uuid::Uuid::new_v4().to_string(),
+        event: EventKind::Test.to_string(),
         project: project_slug.to_string(),
         task_id: None,
         task_number: None,
         task_display_key: None,
         actor: "system".to_string(),
+        actor_display_name: None,
         detail: json!({ "message": "test webhook from lattice" }),
-        created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        created_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        task: None,
     };
 
-    deliver_webhook(&client, &webhook, &payload)
+    deliver_webhook(state, &client, &webhook, &payload, false)
         .await
         .context("failed to deliver test webhook")?;
     Ok(())
 }
 
+/// Posts a payload that is not backed by a system event (such as a digest) to every active
+/// project webhook subscribed to `payload.event`. Failed deliveries are logged, not retried.
+pub async fn deliver_to_project(
+    state: &AppState,
+    client: &reqwest::Client,
+    payload: &WebhookPayload,
+) -> AppResult<usize> {
+    let webhooks = queries::list_active_project_webhooks(&state.db, &payload.project).await?;
+    let mut delivered = 0;
+
+    for webhook in webhooks {
+        if !webhook_subscribed_to_event(&webhook, &payload.event) {
+            continue;
+        }
+
+        match deliver_webhook(state, client, &webhook, payload, false).await {
+            Ok(()) => delivered += 1,
+            Err(error) => tracing::warn!(
+                error = ?error,
+                webhook_id = %webhook.id,
+                event = %payload.event,
+                "webhook delivery failed"
+            ),
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Posts `payload` to one specific webhook, e.g. an SLA policy's escalation target.
+///
+/// Skipped when the webhook is inactive or already subscribed to the event, since the
+/// dispatcher delivers it in that case. Returns whether a delivery was attempted.
+pub async fn deliver_to_webhook(
+    state: &AppState,
+    client: &reqwest::Client,
+    webhook: &WebhookRecord,
+    payload: &WebhookPayload,
+) -> anyhow::Result<bool> {
+    if webhook.active != 1 || webhook_subscribed_to_event(webhook, &payload.event) {
+        return Ok(false);
+    }
+
+    deliver_webhook(state, client, webhook, payload, false).await?;
+    Ok(true)
+}
+
 async fn run_dispatcher(state: AppState) -> anyhow::Result<()> {
     let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(5))
         .build()
         .context("failed to build webhook client")?;
 
-    let (mut last_created_at, mut last_event_id) =
-        match queries::latest_system_event_cursor(&state.db, &[]).await {
-            Ok(Some((created_at, event_id))) => (Some(created_at), Some(event_id)),
-            Ok(None) => (None, None),
-            Err(error) => {
-                tracing::error!(error = ?error, "failed to initialize webhook dispatcher cursor");
-                (None, None)
-            }
-        };
+    // A restart resumes after the last event it handed out; a fresh start skips the backlog.
+    let cursor = match state.dispatcher.cursor() {
+        Some(cursor) => Some(cursor),
+        None => queries::latest_system_event_cursor(&state.db, None, &[])
+            .await
+            .context("failed to initialize webhook dispatcher cursor")?,
+    };
+    let (mut last_created_at, mut last_event_id) = cursor.unzip();
+    if let (Some(created_at), Some(event_id)) = (&last_created_at, &last_event_id) {
+        state.dispatcher.advanced(created_at, event_id);
+    }
+    state.dispatcher.started();
     let mut retry_queue: Vec<PendingDelivery> = Vec::new();
+    let mut gates = DeliveryGates::default();
     let mut interval = tokio::time::interval(Duration::from_millis(DISPATCH_POLL_INTERVAL_MS));
     interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     loop {
         interval.tick().await;
-        process_retry_queue(&client, &mut retry_queue).await;
+        process_retry_queue(&state, &client, &mut retry_queue).await;
+        deliver_held_summaries(&state, &client, &mut gates).await;
 
         let events = match queries::list_system_events(
             &state.db,
+            None,
             &[],
             last_created_at.as_deref(),
             last_event_id.as_deref(),
@@ -114,8 +289,10 @@ async fn run_dispatcher(state: AppState) -> anyhow::Result<()> {
         for event in events {
             last_created_at = Some(event.created_at.clone());
             last_event_id = Some(event.id.clone());
-            dispatch_event(&state, &client, &mut retry_queue, event).await;
+            state.dispatcher.advanced(&event.created_at, &event.id);
+            dispatch_event(&state, &client, &mut retry_queue, &mut gates, event).await;
         }
+        state.dispatcher.polled(retry_queue.len());
     }
 }
 
@@ -123,6 +300,7 @@ async fn dispatch_event(
     state: &AppState,
     client: &reqwest::Client,
     retry_queue: &mut Vec<PendingDelivery>,
+    gates: &mut DeliveryGates,
     event: SystemEventRecord,
 ) {
     let payload = payload_from_system_event(event);
@@ -133,25 +311,69 @@ async fn dispatch_event(
             return;
         }
     };
+    let (now, instant) = (chrono::Utc::now(), Instant::now());
+    let webhooks: Vec<_> = webhooks
+        .into_iter()
+        .filter(|webhook| webhook_subscribed_to_event(webhook, &payload.event))
+        .filter(|webhook| gates.admit(webhook, &payload, now, instant))
+        .collect();
 
-    for webhook in webhooks {
-        if !webhook_subscribed_to_event(&webhook, &payload.event) {
-            continue;
-        }
+    let with_snapshot = if webhooks
+        .iter()
+        .any(|webhook| webhook.include_task_snapshot == 1)
+    {
+        let mut enriched = payload.clone();
+        enriched.task = task_snapshot(state, &payload).await;
+        Some(enriched)
+    } else {
+        None
+    };
 
-        if let Err(error) = deliver_webhook(client, &webhook, &payload).await {
+    for webhook in webhooks {
+        let payload = match &with_snapshot {
+            Some(enriched) if webhook.include_task_snapshot == 1 => enriched,
+            _ => &payload,
+        };
+        if let Err(error) = deliver_webhook(state, client, &webhook, payload, false).await {
             tracing::warn!(
                 error = ?error,
                 webhook_id = %webhook.id,
                 event = %payload.event,
-                "webhook delivery failed, scheduling one retry"
+                "webhook delivery failed"
             );
-            schedule_retry(retry_queue, webhook, payload.clone());
+            schedule_retry(state, retry_queue, webhook, payload.clone(), 1);
         }
     }
 }
 
-async fn process_retry_queue(client: &reqwest::Client, retry_queue: &mut Vec<PendingDelivery>) {
+async fn task_snapshot(state: &AppState, payload: &WebhookPayload) -> Option<TaskSnapshot> {
+    let task_id = payload.task_id.as_deref()?;
+    let (task, labels) = match queries::find_task_with_labels(&state.db, task_id).await {
+        Ok(value) => value?,
+        Err(error) => {
+            tracing::warn!(error = ?error, task_id, "failed to load task snapshot for webhook");
+            return None;
+        }
+    };
+
+    Some(TaskSnapshot {
+        url: task_url(
+            state.config.public_base_url().unwrap_or_default(),
+            &payload.project,
+            &queries::display_key(&payload.project, task.task_number),
+        ),
+        title: task.title,
+        status: task.status,
+        priority: task.priority,
+        labels,
+    })
+}
+
+async fn process_retry_queue(
+    state: &AppState,
+    client: &reqwest::Client,
+    retry_queue: &mut Vec<PendingDelivery>,
+) {
     let now = Instant::now();
     let mut still_pending = Vec::new();
 
@@ -161,12 +383,22 @@ async fn process_retry_queue(client: &reqwest::Client, retry_queue: &mut Vec<Pen
             continue;
         }
 
-        if let Err(error) = deliver_webhook(client, &pending.webhook, &pending.payload).await {
+        if let Err(error) =
+            deliver_webhook(state, client, &pending.webhook, &pending.payload, true).await
+        {
             tracing::warn!(
                 error = ?error,
                 webhook_id = %pending.webhook.id,
                 event = %pending.payload.event,
-                "webhook retry delivery failed and will be dropped"
+                attempt = pending.attempt,
+                "webhook retry delivery failed"
+            );
+            schedule_retry(
+                state,
+                &mut still_pending,
+                pending.webhook,
+                pending.payload,
+                pending.attempt + 1,
             );
         }
     }
@@ -174,11 +406,63 @@ async fn process_retry_queue(client: &reqwest::Client, retry_queue: &mut Vec<Pen
     *retry_queue = still_pending;
 }
 
+/// Sends the summaries of events held back by quiet hours or a delivery cap. Like digests,
+/// a failed summary is logged rather than retried.
+///
+/// The gates hold a copy of the webhook from when its events were held, so each one is
+/// reloaded first: summaries for webhooks deleted or deactivated since then are dropped.
+async fn deliver_held_summaries(
+    state: &AppState,
+    client: &reqwest::Client,
+    gates: &mut DeliveryGates,
+) {
+    for (held_for, payload) in gates.take_due_summaries(chrono::Utc::now(), Instant::now()) {
+        let webhook = match queries::find_webhook(&state.db, &held_for.id).await {
+            Ok(Some(webhook)) if webhook.active == 1 => webhook,
+            Ok(_) => {
+                tracing::debug!(
+                    webhook_id = %held_for.id,
+                    "dropping held events summary for a removed or inactive webhook"
+                );
+                continue;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    error = ?error,
+                    webhook_id = %held_for.id,
+                    "failed to reload webhook for held events summary"
+                );
+                continue;
+            }
+        };
+        if let Err(error) = deliver_webhook(state, client, &webhook, &payload, false).await {
+            tracing::warn!(
+                error = ?error,
+                webhook_id = %webhook.id,
+                "held events summary delivery failed"
+            );
+        }
+    }
+}
+
+/// Queues retry `attempt` of a failed delivery, unless the webhook's policy allows no more.
 fn schedule_retry(
+    state: &AppState,
     retry_queue: &mut Vec<PendingDelivery>,
     webhook: WebhookRecord,
     payload: WebhookPayload,
+    attempt: u32,
 ) {
+    let policy = DeliveryPolicy::for_webhook(&webhook, &state.config.webhook_delivery);
+    if attempt > policy.max_retries {
+        tracing::warn!(
+            webhook_id = %webhook.id,
+            event = %payload.event,
+            retries = policy.max_retries,
+            "webhook delivery dropped after its last retry"
+        );
+        return;
+    }
     if retry_queue.len() >= MAX_RETRY_QUEUE {
         tracing::warn!(
             webhook_id = %webhook.id,
@@ -191,7 +475,8 @@ fn schedule_retry(
     retry_queue.push(PendingDelivery {
         webhook,
         payload,
-        due_at: Instant::now() + Duration::from_secs(RETRY_DELAY_SECONDS),
+        attempt,
+        due_at: Instant::now() + policy.retry_delay(attempt),
     });
 }
 
@@ -199,6 +484,7 @@ fn payload_from_system_event(event: SystemEventRecord) -> WebhookPayload {
     let detail = serde_json::from_str::<Value>(&event.detail)
         .unwrap_or_else(|_| Value::String(event.detail.clone()));
     WebhookPayload {
+        event_id: event.id,
         event: event.action,
         project: event.project_slug.clone(),
         task_id: event.task_id,
@@ -207,14 +493,16 @@ fn payload_from_system_event(event: SystemEventRecord) -> WebhookPayload {
             .task_number
             .map(|task_number| queries::display_key(&event.project_slug, task_number)),
         actor: event.actor,
+        actor_display_name: event.actor_display_name,
         detail,
         created_at: event.created_at,
+        task: None,
     }
 }
 
 fn webhook_subscribed_to_event(webhook: &WebhookRecord, event: &str) -> bool {
     match queries::parse_webhook_events(&webhook.events) {
-        Ok(events) => events.iter().any(|candidate| candidate == event),
+        Ok(events) => queries::webhook_subscribes_to(&events, event),
         Err(error) => {
             tracing::warn!(
                 error = ?error,
@@ -226,16 +514,56 @@ fn webhook_subscribed_to_event(webhook: &WebhookRecord, event: &str) -> bool {
     }
 }
 
+/// Sends one delivery attempt and records its outcome and latency in `metrics`.
 async fn deliver_webhook(
+    state: &AppState,
+    client: &reqwest::Client,
+    webhook: &WebhookRecord,
+    payload: &WebhookPayload,
+    retry: bool,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    let policy = DeliveryPolicy::for_webhook(webhook, &state.config.webhook_delivery);
+    let public_url = state.config.public_base_url();
+    let result = send_webhook(
+        client,
+        &state.keyring,
+        webhook,
+        payload,
+        public_url,
+        policy.timeout,
+    )
+    .await
+    .map_err(|error| anyhow!(redact::text(&format!("{error:#}"))));
+    state.metrics.record_webhook_delivery(
+        &webhook.platform,
+        &webhook.id,
+        result.is_ok(),
+        retry,
+        started.elapsed(),
+    );
+    result
+}
+
+async fn send_webhook(
     client: &reqwest::Client,
+    keyring: &Keyring,
     webhook: &WebhookRecord,
     payload: &WebhookPayload,
+    public_url: Option<&str>,
+    timeout: Duration,
 ) -> anyhow::Result<()> {
-    let body = webhook_body(webhook, payload)?;
+    let body = webhook_body(webhook, payload, public_url)?;
+    let content_type = if webhook.payload_format == "cloudevents" {
+        "application/cloudevents+json"
+    } else {
+        "application/json"
+    };
 
     let mut request = client
         .post(&webhook.url)
-        .header("Content-Type", "application/json")
+        .timeout(timeout)
+        .header("Content-Type", content_type)
         .body(body.clone());
 
     if webhook.platform == "generic" {
@@ -244,13 +572,17 @@ async fn deliver_webhook(
             .as_deref()
             .filter(|value| !value.trim().is_empty())
         {
-            request = request.header("X-Lattice-Signature", hmac_signature(secret, &body)?);
+            let secret = keyring
+                .open(secret)
+                .map_err(|_| anyhow!("cannot decrypt the secret of webhook '{}'", webhook.id))?;
+            request = request.header("X-Lattice-Signature", hmac_signature(&secret, &body)?);
         }
     }
 
     let response = request
         .send()
         .await
+        .map_err(reqwest::Error::without_url)
         .with_context(|| format!("request failed for webhook '{}'", webhook.id))?;
 
     if response.status().is_success() {
@@ -264,24 +596,63 @@ async fn deliver_webhook(
     ))
 }
 
-fn webhook_body(webhook: &WebhookRecord, payload: &WebhookPayload) -> anyhow::Result<Vec<u8>> {
+fn webhook_body(
+    webhook: &WebhookRecord,
+    payload: &WebhookPayload,
+    public_url: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
+    let link = public_url.map(|base| event_link(base, payload));
     let body = match webhook.platform.as_str() {
-        "slack" => slack_payload(payload),
-        "discord" => discord_payload(payload),
+        "slack" => slack_payload(payload, link.as_deref()),
+        "discord" => discord_payload(payload, link.as_deref()),
         _ => {
-            serde_json::to_value(payload).context("failed to serialize generic webhook payload")?
+            let data = serde_json::to_value(VersionedPayload::new(payload, webhook.schema_version))
+                .context("failed to serialize generic webhook payload")?;
+            if webhook.payload_format == "cloudevents" {
+                cloudevent(payload, data)
+            } else {
+                data
+            }
         }
     };
 
     serde_json::to_vec(&body).context("failed to encode webhook payload")
 }
 
-fn slack_payload(payload: &WebhookPayload) -> Value {
-    let task_label = payload
-        .task_display_key
-        .as_ref()
-        .map_or("task".to_string(), ToOwned::to_owned);
-    let detail = compact_json(&payload.detail);
+/// Wraps `data` in a structured-mode CloudEvents 1.0 envelope.
+fn cloudevent(payload: &WebhookPayload, data: Value) -> Value {
+    let mut envelope = json!({
+        "specversion": "1.0",
+        "id": payload.event_id,
+        "source": format!("/lattice/projects/{}", payload.project),
+        "type": format!("lattice.{}", payload.event),
+        "time": payload.created_at,
+        "datacontenttype": "application/json",
+        "data": data,
+    });
+    if let Some(display_key) = &payload.task_display_key {
+        envelope["subject"] = Value::from(display_key.as_str());
+    }
+    envelope
+}
+
+fn slack_payload(payload: &WebhookPayload, link: Option<&str>) -> Value {
+    let task_label = task_label(payload);
+    let detail = format::describe(&payload.event, &payload.detail)
+        .map_or_else(|| compact_json(&payload.detail), |text| slack_escape(&text));
+    let headline = match link {
+        Some(url) => format!(
+            "*{}* <{}|{}> in *{}*",
+            payload.event,
+            url,
+            slack_escape(&task_label),
+            payload.project
+        ),
+        None => format!(
+            "*{}* `{}` in *{}*",
+            payload.event, task_label, payload.project
+        ),
+    };
 
     json!({
         "text": format!("[{}] {} {}", payload.project, payload.event, task_label),
@@ -290,7 +661,7 @@ fn slack_payload(payload: &WebhookPayload) -> Value {
                 "type": "section",
                 "text": {
                     "type": "mrkdwn",
-                    "text": format!("*{}* `{}` in *{}*", payload.event, task_label, payload.project)
+                    "text": headline
                 }
             },
             {
@@ -298,7 +669,7 @@ fn slack_payload(payload: &WebhookPayload) -> Value {
                 "elements": [
                     {
                         "type": "mrkdwn",
-                        "text": format!("actor: {} • {}", payload.actor, payload.created_at)
+                        "text": format!("actor: {} • {}", actor_label(payload), payload.created_at)
                     }
                 ]
             },
@@ -313,25 +684,68 @@ fn slack_payload(payload: &WebhookPayload) -> Value {
     })
 }
 
-fn discord_payload(payload: &WebhookPayload) -> Value {
-    let task_label = payload
-        .task_display_key
-        .as_ref()
-        .map_or("task".to_string(), ToOwned::to_owned);
+fn discord_payload(payload: &WebhookPayload, link: Option<&str>) -> Value {
+    let task_label = task_label(payload);
 
-    json!({
-        "embeds": [
-            {
-                "title": format!("{} • {}", payload.event, task_label),
-                "description": compact_json(&payload.detail),
-                "color": discord_color_for_event(&payload.event),
-                "footer": {
-                    "text": format!("{} • {}", payload.project, payload.actor),
-                },
-                "timestamp": payload.created_at,
-            }
-        ]
-    })
+    let mut embed = json!({
+        "title": format!("{} • {}", payload.event, task_label),
+        "description": format::describe(&payload.event, &payload.detail)
+            .unwrap_or_else(|| compact_json(&payload.detail)),
+        "color": discord_color_for_event(&payload.event),
+        "footer": {
+            "text": format!("{} • {}", payload.project, actor_label(payload)),
+        },
+        "timestamp": payload.created_at,
+    });
+    if let Some(url) = link {
+        // Discord makes the embed title a link to `url`.
+        embed["url"] = Value::from(url);
+    }
+
+    json!({ "embeds": [embed] })
+}
+
+/// Where an event points in the web UI: its task, the edited spec section, or the board.
+fn event_link(base: &str, payload: &WebhookPayload) -> String {
+    if let Some(display_key) = &payload.task_display_key {
+        return task_url(base, &payload.project, display_key);
+    }
+    if payload.event.starts_with("spec.") {
+        return match payload.detail.get("section").and_then(Value::as_str) {
+            Some(section) => format!("{base}/{}/spec?section={section}", payload.project),
+            None => format!("{base}/{}/spec", payload.project),
+        };
+    }
+    format!("{base}/{}", payload.project)
+}
+
+/// The board URL that opens `display_key`, relative to the UI root when `base` is empty.
+fn task_url(base: &str, project: &str, display_key: &str) -> String {
+    format!("{base}/{project}?task={display_key}")
+}
+
+/// Escapes the characters Slack treats as markup inside link text.
+fn slack_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The task's display key, followed by its title when a snapshot is attached.
+fn task_label(payload: &WebhookPayload) -> String {
+    let key = payload.task_display_key.as_deref().unwrap_or("task");
+    match &payload.task {
+        Some(task) => format!("{key} {}", task.title),
+        None => key.to_string(),
+    }
+}
+
+fn actor_label(payload: &WebhookPayload) -> &str {
+    payload
+        .actor_display_name
+        .as_deref()
+        .unwrap_or(&payload.actor)
 }
 
 fn compact_json(value: &Value) -> String {
@@ -346,17 +760,9 @@ fn compact_json(value: &Value) -> String {
 }
 
 fn discord_color_for_event(event: &str) -> u32 {
-    match event {
-        "task.created" => 0x7A3FFF,
-        "task.moved" => 0x4F9DFF,
-        "task.deleted" => 0xC94C4C,
-        "task.review_state_changed" => 0xE0A341,
-        "question.created" => 0xF0C54A,
-        "question.resolved" => 0x4BB47B,
-        "spec.updated" => 0x9A65C7,
-        "goal.updated" => 0x74BBD6,
-        _ => 0x8A8A8A,
-    }
+    event
+        .parse::<EventKind>()
+        .map_or(0x8A8A8A, EventKind::discord_color)
 }
 
 fn hmac_signature(secret: &str, body: &[u8]) -> anyhow::Result<String> {
@@ -372,3 +778,350 @@ fn hmac_signature(secret: &str, body: &[u8]) -> anyhow::Result<String> {
     }
     Ok(format!("sha256={encoded}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use std::time::Duration;
+
+    use super::{webhook_body, DeliveryPolicy, TaskSnapshot, WebhookPayload};
+    use crate::config::WebhookDeliveryConfig;
+    use crate::db::models::WebhookRecord;
+
+    #[test]
+    fn delivery_policy_prefers_webhook_overrides_and_doubles_backoff() {
+        let mut webhook = WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "slow receiver".to_string(),
+            url: "https://internal.example/hook".to_string(),
+            platform: "generic".to_string(),
+            events: "[\"task.created\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "lattice".to_string(),
+            schema_version: 2,
+            include_task_snapshot: 0,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let defaults = WebhookDeliveryConfig::default();
+        assert_eq!(
+            DeliveryPolicy::for_webhook(&webhook, &defaults),
+            DeliveryPolicy {
+                timeout: Duration::from_secs(5),
+                max_retries: 1,
+                backoff: Duration::from_secs(30),
+            }
+        );
+
+        webhook.timeout_secs = Some(60);
+        webhook.max_retries = Some(4);
+        webhook.retry_backoff_secs = Some(900);
+        let policy = DeliveryPolicy::for_webhook(&webhook, &defaults);
+        assert_eq!(policy.timeout, Duration::from_secs(60));
+        assert_eq!(policy.max_retries, 4);
+        let delays: Vec<u64> = (1..=4)
+            .map(|attempt| policy.retry_delay(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [900, 1800, 3600, 3600]);
+    }
+
+    #[test]
+    fn chat_platforms_summarize_subtask_and_attachment_events() {
+        let webhook = |platform: &str| WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "chat".to_string(),
+            url: "https://example.com/chat".to_string(),
+            platform: platform.to_string(),
+            events: "[\"attachment.created\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "lattice".to_string(),
+            schema_version: 1,
+            include_task_snapshot: 0,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let payload = |event: &str, detail: Value| WebhookPayload {
+            event_id: "event-1".to_string(),
+            event: event.to_string(),
+            project: "DEMO".to_string(),
+            task_id: Some("task-1".to_string()),
+            task_number: Some(7),
+            task_display_key: Some("DEMO-7".to_string()),
+            actor: "human".to_string(),
+            actor_display_name: None,
+            detail,
+            created_at: "2026-01-02T03:04:05Z".to_string(),
+            task: None,
+        };
+
+        let attached = payload(
+            "attachment.created",
+            json!({ "attachment_id": "a-1", "filename": "plan.pdf", "size_bytes": 1536 }),
+        );
+        let body = webhook_body(&webhook("slack"), &attached, None).expect("body should encode");
+        let slack: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(
+            slack["blocks"][2]["text"]["text"],
+            "Attached plan.pdf (1.5 KiB)"
+        );
+
+        let completed = payload(
+            "subtask.updated",
+            json!({ "subtask_id": "s-1", "title": "Write tests", "done": true }),
+        );
+        let body = webhook_body(&webhook("discord"), &completed, None).expect("body should encode");
+        let discord: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(
+            discord["embeds"][0]["description"],
+            "Completed subtask \"Write tests\""
+        );
+        assert_eq!(discord["embeds"][0]["color"], 0x6FBF8E);
+    }
+
+    #[test]
+    fn chat_messages_link_into_the_ui_when_a_public_url_is_set() {
+        let mut webhook = WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "chat".to_string(),
+            url: "https://example.com/chat".to_string(),
+            platform: "slack".to_string(),
+            events: "[\"task.moved\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "lattice".to_string(),
+            schema_version: 1,
+            include_task_snapshot: 0,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let mut payload = WebhookPayload {
+            event_id: "event-1".to_string(),
+            event: "task.moved".to_string(),
+            project: "DEMO".to_string(),
+            task_id: Some("task-1".to_string()),
+            task_number: Some(7),
+            task_display_key: Some("DEMO-7".to_string()),
+            actor: "human".to_string(),
+            actor_display_name: None,
+            detail: json!({ "from": "ready", "to": "in_progress" }),
+            created_at: "2026-01-02T03:04:05Z".to_string(),
+            task: None,
+        };
+        let base = Some("https://lattice.example.com");
+
+        let body = webhook_body(&webhook, &payload, base).expect("body should encode");
+        let slack: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(
+            slack["blocks"][0]["text"]["text"],
+            "*task.moved* <https://lattice.example.com/DEMO?task=DEMO-7|DEMO-7> in *DEMO*"
+        );
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let unlinked: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(
+            unlinked["blocks"][0]["text"]["text"],
+            "*task.moved* `DEMO-7` in *DEMO*"
+        );
+
+        webhook.platform = "discord".to_string();
+        payload.event = "spec.updated".to_string();
+        payload.task_id = None;
+        payload.task_number = None;
+        payload.task_display_key = None;
+        payload.detail = json!({ "section": "architecture" });
+        let body = webhook_body(&webhook, &payload, base).expect("body should encode");
+        let discord: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(
+            discord["embeds"][0]["url"],
+            "https://lattice.example.com/DEMO/spec?section=architecture"
+        );
+    }
+
+    #[test]
+    fn task_snapshots_are_embedded_only_when_attached() {
+        let mut webhook = WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "consumer".to_string(),
+            url: "https://example.com/hook".to_string(),
+            platform: "generic".to_string(),
+            events: "[\"task.moved\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "lattice".to_string(),
+            schema_version: 2,
+            include_task_snapshot: 1,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let mut payload = WebhookPayload {
+            event_id: "event-1".to_string(),
+            event: "task.moved".to_string(),
+            project: "DEMO".to_string(),
+            task_id: Some("task-1".to_string()),
+            task_number: Some(7),
+            task_display_key: Some("DEMO-7".to_string()),
+            actor: "human".to_string(),
+            actor_display_name: None,
+            detail: json!({ "from": "ready", "to": "in_progress" }),
+            created_at: "2026-01-02T03:04:05Z".to_string(),
+            task: None,
+        };
+
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let plain: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert!(plain.get("task").is_none());
+
+        payload.task = Some(TaskSnapshot {
+            title: "Ship it".to_string(),
+            status: "in_progress".to_string(),
+            priority: "high".to_string(),
+            labels: vec!["release".to_string()],
+            url: "/DEMO?task=DEMO-7".to_string(),
+        });
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let enriched: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(enriched["task"]["title"], "Ship it");
+        assert_eq!(enriched["task"]["labels"], json!(["release"]));
+        assert_eq!(enriched["task"]["url"], "/DEMO?task=DEMO-7");
+
+        webhook.platform = "slack".to_string();
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let slack: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(slack["text"], "[DEMO] task.moved DEMO-7 Ship it");
+    }
+
+    #[test]
+    fn cloudevents_format_wraps_the_generic_payload() {
+        let mut webhook = WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "knative".to_string(),
+            url: "https://example.com/events".to_string(),
+            platform: "generic".to_string(),
+            events: "[\"task.created\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "cloudevents".to_string(),
+            schema_version: 1,
+            include_task_snapshot: 0,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let payload = WebhookPayload {
+            event_id: "event-1".to_string(),
+            event: "task.created".to_string(),
+            project: "DEMO".to_string(),
+            task_id: Some("task-1".to_string()),
+            task_number: Some(7),
+            task_display_key: Some("DEMO-7".to_string()),
+            actor: "human".to_string(),
+            actor_display_name: None,
+            detail: json!({ "title": "Ship it" }),
+            created_at: "2026-01-02T03:04:05Z".to_string(),
+            task: None,
+        };
+
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let envelope: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(envelope["specversion"], "1.0");
+        assert_eq!(envelope["id"], "event-1");
+        assert_eq!(envelope["source"], "/lattice/projects/DEMO");
+        assert_eq!(envelope["type"], "lattice.task.created");
+        assert_eq!(envelope["subject"], "DEMO-7");
+        assert_eq!(envelope["time"], "2026-01-02T03:04:05Z");
+        assert_eq!(envelope["data"]["detail"]["title"], "Ship it");
+        assert!(envelope["data"].get("event_id").is_none());
+
+        webhook.payload_format = "lattice".to_string();
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let plain: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(plain["event"], "task.created");
+        assert!(plain.get("specversion").is_none());
+    }
+
+    #[test]
+    fn generic_payloads_follow_the_pinned_schema_version() {
+        let mut webhook = WebhookRecord {
+            id: "hook-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "consumer".to_string(),
+            url: "https://example.com/hook".to_string(),
+            platform: "generic".to_string(),
+            events: "[\"task.created\"]".to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "lattice".to_string(),
+            schema_version: 1,
+            include_task_snapshot: 0,
+            quiet_hours: None,
+            quiet_hours_timezone: "UTC".to_string(),
+            max_deliveries_per_minute: 0,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        };
+        let payload = WebhookPayload {
+            event_id: "event-1".to_string(),
+            event: "task.created".to_string(),
+            project: "DEMO".to_string(),
+            task_id: None,
+            task_number: None,
+            task_display_key: None,
+            actor: "human".to_string(),
+            actor_display_name: None,
+            detail: json!({}),
+            created_at: "2026-01-02T03:04:05Z".to_string(),
+            task: None,
+        };
+
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let v1: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(v1["schema_version"], 1);
+        assert!(v1.get("id").is_none());
+
+        webhook.schema_version = 2;
+        let body = webhook_body(&webhook, &payload, None).expect("body should encode");
+        let v2: Value = serde_json::from_slice(&body).expect("body should be json");
+        assert_eq!(v2["schema_version"], 2);
+        assert_eq!(v2["id"], "event-1");
+        assert_eq!(v2["event"], "task.created");
+    }
+}
@@ -0,0 +1,318 @@
+//! Per-webhook quiet hours and delivery caps for the dispatcher.
+//!
+//! Events that arrive while a webhook is quiet or over its per-minute cap are not sent; they
+//! are counted and later delivered as a single `events.held` summary.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Timelike, Utc};
+use serde_json::json;
+
+use super::WebhookPayload;
+use crate::db::models::WebhookRecord;
+use crate::db::queries;
+use crate::event_kind::EventKind;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HoldReason {
+    QuietHours,
+    RateLimit,
+}
+
+impl HoldReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::QuietHours => "quiet_hours",
+            Self::RateLimit => "rate_limit",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Gate {
+    webhook: WebhookRecord,
+    project: String,
+    window_start: Instant,
+    sent: i64,
+    held: BTreeMap<String, u64>,
+    reason: HoldReason,
+    first_held_at: String,
+    last_held_at: String,
+}
+
+impl Gate {
+    fn roll_window(&mut self, now: Instant) {
+        if now.duration_since(self.window_start) >= RATE_WINDOW {
+            self.window_start = now;
+            self.sent = 0;
+        }
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.webhook.max_deliveries_per_minute <= 0
+            || self.sent < self.webhook.max_deliveries_per_minute
+    }
+
+    fn hold(&mut self, payload: &WebhookPayload, reason: HoldReason) {
+        if self.held.is_empty() {
+            self.first_held_at = payload.created_at.clone();
+            self.reason = reason;
+        } else if reason == HoldReason::QuietHours {
+            self.reason = reason;
+        }
+        self.last_held_at = payload.created_at.clone();
+        *self.held.entry(payload.event.clone()).or_default() += 1;
+    }
+}
+
+/// Delivery state for every webhook the dispatcher has seen, owned by the dispatcher loop.
+#[derive(Debug, Default)]
+pub struct DeliveryGates {
+    gates: HashMap<String, Gate>,
+}
+
+impl DeliveryGates {
+    /// Whether `payload` may be sent to `webhook` now. When it may not, the event is counted
+    /// towards the webhook's next summary.
+    pub fn admit(
+        &mut self,
+        webhook: &WebhookRecord,
+        payload: &WebhookPayload,
+        now: DateTime<Utc>,
+        instant: Instant,
+    ) -> bool {
+        if webhook.quiet_hours.is_none() && webhook.max_deliveries_per_minute <= 0 {
+            self.gates.remove(&webhook.id);
+            return true;
+        }
+
+        let gate = self
+            .gates
+            .entry(webhook.id.clone())
+            .or_insert_with(|| Gate {
+                webhook: webhook.clone(),
+                project: payload.project.clone(),
+                window_start: instant,
+                sent: 0,
+                held: BTreeMap::new(),
+                reason: HoldReason::RateLimit,
+                first_held_at: String::new(),
+                last_held_at: String::new(),
+            });
+        gate.webhook = webhook.clone();
+        gate.project = payload.project.clone();
+        gate.roll_window(instant);
+
+        if in_quiet_hours(webhook, now) {
+            gate.hold(payload, HoldReason::QuietHours);
+            return false;
+        }
+        if !gate.held.is_empty() || !gate.has_capacity() {
+            gate.hold(payload, HoldReason::RateLimit);
+            return false;
+        }
+        gate.sent += 1;
+        true
+    }
+
+    /// Summaries for webhooks whose held events may now be delivered. Each summary counts as
+    /// one delivery against the webhook's cap.
+    pub fn take_due_summaries(
+        &mut self,
+        now: DateTime<Utc>,
+        instant: Instant,
+    ) -> Vec<(WebhookRecord, WebhookPayload)> {
+        let mut due = Vec::new();
+
+        for gate in self.gates.values_mut() {
+            gate.roll_window(instant);
+            if gate.held.is_empty() || in_quiet_hours(&gate.webhook, now) || !gate.has_capacity() {
+                continue;
+            }
+
+            gate.sent += 1;
+            let held = std::mem::take(&mut gate.held);
+            let count: u64 = held.values().sum();
+            due.push((
+                gate.webhook.clone(),
+                WebhookPayload {
+                    event_id: uuid::Uuid::new_v4().to_string(),
+                    event: EventKind::EventsHeld.to_string(),
+                    project: gate.project.clone(),
+                    task_id: None,
+                    task_number: None,
+                    task_display_key: None,
+                    actor: "system".to_string(),
+                    actor_display_name: None,
+                    detail: json!({
+                        "reason": gate.reason.as_str(),
+                        "count": count,
+                        "events": held,
+                        "since": gate.first_held_at,
+                        "until": gate.last_held_at,
+                    }),
+                    created_at: now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                    task: None,
+                },
+            ));
+        }
+
+        self.gates.retain(|_, gate| {
+            !gate.held.is_empty() || instant.duration_since(gate.window_start) < RATE_WINDOW
+        });
+        due
+    }
+}
+
+fn in_quiet_hours(webhook: &WebhookRecord, now: DateTime<Utc>) -> bool {
+    let Some((start, end)) = webhook
+        .quiet_hours
+        .as_deref()
+        .and_then(queries::parse_quiet_hours)
+    else {
+        return false;
+    };
+    let timezone = webhook
+        .quiet_hours_timezone
+        .parse::<chrono_tz::Tz>()
+        .unwrap_or(chrono_tz::UTC);
+    let local = now.with_timezone(&timezone);
+    let minute = local.hour() * 60 + local.minute();
+
+    if start < end {
+        (start..end).contains(&minute)
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde_json::{json, Value};
+
+    use super::{in_quiet_hours, DeliveryGates};
+    use crate::db::models::WebhookRecord;
+    use crate::event_kind::EventKind;
+    use crate::webhooks::WebhookPayload;
+
+    fn webhook(quiet_hours: Option<&str>, timezone: &str, per_minute: i64) -> WebhookRecord {
+        WebhookRecord {
+            id: "wh-1".to_string(),
+            project_id: "project-1".to_string(),
+            name: "team".to_string(),
+            platform: "slack".to_string(),
+            url: "https://example.test/hook".to_string(),
+            events: r#"["task.moved"]"#.to_string(),
+            secret: None,
+            active: 1,
+            payload_format: "platform".to_string(),
+            schema_version: 2,
+            include_task_snapshot: 0,
+            quiet_hours: quiet_hours.map(str::to_string),
+            quiet_hours_timezone: timezone.to_string(),
+            max_deliveries_per_minute: per_minute,
+            timeout_secs: None,
+            max_retries: None,
+            retry_backoff_secs: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn event(name: &str, at: DateTime<Utc>) -> WebhookPayload {
+        WebhookPayload {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            event: name.to_string(),
+            project: "alpha".to_string(),
+            task_id: None,
+            task_number: None,
+            task_display_key: None,
+            actor: "agent".to_string(),
+            actor_display_name: None,
+            detail: Value::Null,
+            created_at: at.to_rfc3339(),
+            task: None,
+        }
+    }
+
+    #[test]
+    fn quiet_hours_follow_the_webhook_timezone_and_wrap_midnight() {
+        let overnight = webhook(Some("22:00-07:00"), "Europe/Berlin", 0);
+        // 01:30 UTC in January is 02:30 in Berlin.
+        let night = Utc.with_ymd_and_hms(2026, 1, 15, 1, 30, 0).unwrap();
+        // 06:30 UTC is 07:30 in Berlin.
+        let morning = Utc.with_ymd_and_hms(2026, 1, 15, 6, 30, 0).unwrap();
+        assert!(in_quiet_hours(&overnight, night));
+        assert!(!in_quiet_hours(&overnight, morning));
+
+        let lunch = webhook(Some("12:00-13:00"), "UTC", 0);
+        assert!(in_quiet_hours(
+            &lunch,
+            Utc.with_ymd_and_hms(2026, 1, 15, 12, 59, 0).unwrap()
+        ));
+        assert!(!in_quiet_hours(
+            &lunch,
+            Utc.with_ymd_and_hms(2026, 1, 15, 13, 0, 0).unwrap()
+        ));
+    }
+
+    #[test]
+    fn held_events_collapse_into_one_summary() {
+        let mut gates = DeliveryGates::default();
+        let quiet = webhook(Some("00:00-07:00"), "UTC", 0);
+        let start = Instant::now();
+        let night = Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap();
+
+        for name in ["task.moved", "task.moved", "task.created"] {
+            assert!(!gates.admit(&quiet, &event(name, night), night, start));
+        }
+        assert!(gates.take_due_summaries(night, start).is_empty());
+
+        let morning = Utc.with_ymd_and_hms(2026, 1, 15, 7, 0, 0).unwrap();
+        let due = gates.take_due_summaries(morning, start);
+        assert_eq!(due.len(), 1);
+        let (target, summary) = &due[0];
+        assert_eq!(target.id, "wh-1");
+        assert_eq!(summary.event, EventKind::EventsHeld.as_str());
+        assert_eq!(summary.project, "alpha");
+        assert_eq!(summary.detail["reason"], "quiet_hours");
+        assert_eq!(summary.detail["count"], 3);
+        assert_eq!(
+            summary.detail["events"],
+            json!({ "task.created": 1, "task.moved": 2 })
+        );
+
+        assert!(gates.take_due_summaries(morning, start).is_empty());
+        assert!(gates.admit(&quiet, &event("task.moved", morning), morning, start));
+    }
+
+    #[test]
+    fn deliveries_over_the_cap_wait_for_the_next_window() {
+        let mut gates = DeliveryGates::default();
+        let capped = webhook(None, "UTC", 2);
+        let start = Instant::now();
+        let now = Utc.with_ymd_and_hms(2026, 1, 15, 3, 0, 0).unwrap();
+
+        let admitted: Vec<bool> = (0..5)
+            .map(|_| gates.admit(&capped, &event("task.moved", now), now, start))
+            .collect();
+        assert_eq!(admitted, [true, true, false, false, false]);
+        assert!(gates.take_due_summaries(now, start).is_empty());
+
+        let next_window = start + Duration::from_secs(60);
+        let due = gates.take_due_summaries(now, next_window);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.detail["reason"], "rate_limit");
+        assert_eq!(due[0].1.detail["count"], 3);
+
+        // The summary used one of this window's two deliveries.
+        assert!(gates.admit(&capped, &event("task.moved", now), now, next_window));
+        assert!(!gates.admit(&capped, &event("task.moved", now), now, next_window));
+    }
+}
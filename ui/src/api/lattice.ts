@@ -3,6 +3,7 @@ const API_BASE = '/api/v1';
 interface ApiErrorBody {
   error: string;
   message: string;
+  suggestions?: string[];
 }
 
 export type TaskStatus = 'backlog' | 'ready' | 'in_progress' | 'review' | 'done';
@@ -27,6 +28,7 @@ export interface ProjectSummary {
   review_count: number;
   done_count: number;
   open_question_count: number;
+  disputed_question_count: number;
   not_ready_count: number;
 }
 
@@ -43,6 +45,20 @@ export interface TaskResponse {
   created_by: string;
   created_at: string;
   updated_at: string;
+  assignee: string | null;
+  due_at: string | null;
+  parent_task_id: string | null;
+  milestone_id: string | null;
+  local: LocalTimes;
+}
+
+/** A task's timestamps in its project's timezone, with offsets. */
+export interface LocalTimes {
+  timezone: string;
+  created_at: string;
+  updated_at: string;
+  archived_at: string | null;
+  due_at: string | null;
 }
 
 export interface SubtaskRecord {
@@ -61,6 +77,8 @@ export interface OpenQuestionRecord {
   context: string;
   answer: string | null;
   status: 'open' | 'resolved';
+  acceptance: 'pending' | 'accepted' | 'disputed';
+  dispute_reason: string | null;
   asked_by: string;
   resolved_by: string | null;
   created_at: string;
@@ -78,10 +96,24 @@ export interface AttachmentRecord {
   created_at: string;
 }
 
+export interface AttachmentPreview {
+  id: string;
+  filename: string;
+  content_type: string;
+  size_bytes: number;
+  syntax: string;
+  content: string;
+  preview_bytes: number;
+  truncated: boolean;
+}
+
 export interface TaskHistoryRecord {
   id: string;
   task_id: string;
   actor: string;
+  actor_display_name: string | null;
+  actor_kind: string | null;
+  actor_avatar_url: string | null;
   action: string;
   detail: string;
   created_at: string;
@@ -95,17 +127,71 @@ export interface TaskEventPayload {
   task_display_key: string | null;
   action: string;
   actor: string;
+  actor_display_name: string | null;
+  actor_kind: string | null;
+  actor_avatar_url: string | null;
   detail: unknown;
   created_at: string;
 }
 
+export interface TaskReviewRecord {
+  id: string;
+  task_id: string;
+  reviewer: string;
+  requested_by: string;
+  status: 'pending' | 'approved' | 'changes_requested';
+  comment: string | null;
+  completed_by: string | null;
+  created_at: string;
+  completed_at: string | null;
+}
+
+export interface TaskReviewItemRecord {
+  id: string;
+  task_id: string;
+  title: string;
+  done: number;
+  sort_order: number;
+  completed_by: string | null;
+  completed_at: string | null;
+  created_at: string;
+}
+
 export interface TaskDetailsResponse {
   task: TaskResponse;
   labels: string[];
   subtasks: SubtaskRecord[];
   open_questions: OpenQuestionRecord[];
+  reviews: TaskReviewRecord[];
+  review_checklist: TaskReviewItemRecord[];
   attachments: AttachmentRecord[];
   history: TaskHistoryRecord[];
+  blocked_by: TaskDependencyRecord[];
+  blocks: TaskDependencyRecord[];
+  children: TaskChildren;
+}
+
+/** Tasks filed under a parent, with how many of them are done. */
+export interface TaskChildren {
+  total: number;
+  done: number;
+  tasks: ChildTaskRecord[];
+}
+
+export interface ChildTaskRecord {
+  task_id: string;
+  display_key: string;
+  title: string;
+  status: TaskStatus;
+}
+
+export interface TaskDependencyRecord {
+  task_id: string;
+  display_key: string;
+  title: string;
+  status: TaskStatus;
+  created_by: string;
+  created_at: string;
 }
 
 export interface ProjectOpenQuestionResponse {
@@ -117,6 +203,8 @@ export interface ProjectOpenQuestionResponse {
   context: string;
   answer: string | null;
   status: 'open' | 'resolved';
+  acceptance: 'pending' | 'accepted' | 'disputed';
+  dispute_reason: string | null;
   asked_by: string;
   resolved_by: string | null;
   created_at: string;
@@ -129,6 +217,11 @@ export interface SpecSectionRecord {
   section: 'overview' | 'requirements' | 'architecture' | 'technical_design' | 'open_decisions' | 'references';
   content: string;
   updated_at: string;
+  locked_by: string | null;
+  lock_expires_at: string | null;
+  word_count: number;
+  char_count: number;
+  max_length: number;
 }
 
 export interface SpecRevisionRecord {
@@ -138,9 +231,13 @@ export interface SpecRevisionRecord {
   content: string;
   edited_by: string;
   created_at: string;
+  bytes_added: number;
+  bytes_removed: number;
+  headings_touched: string[];
 }
 
 export type WebhookPlatform = 'slack' | 'discord' | 'generic';
+export type WebhookPayloadFormat = 'lattice' | 'cloudevents';
 
 export interface WebhookResponse {
   id: string;
@@ -149,6 +246,15 @@ export interface WebhookResponse {
   platform: WebhookPlatform;
   events: string[];
   active: boolean;
+  payload_format: WebhookPayloadFormat;
+  schema_version: number;
+  include_task_snapshot: boolean;
+  quiet_hours: string | null;
+  quiet_hours_timezone: string;
+  max_deliveries_per_minute: number;
+  timeout_secs: number | null;
+  max_retries: number | null;
+  retry_backoff_secs: number | null;
   has_secret: boolean;
   created_at: string;
   updated_at: string;
@@ -158,6 +264,8 @@ export interface ListTaskFilters {
   status?: TaskStatus;
   label?: string;
   review_state?: ReviewState;
+  assignee?: string;
+  overdue?: boolean;
   limit?: number;
   offset?: number;
 }
@@ -169,6 +277,10 @@ export interface UpdateTaskPayload {
   priority?: TaskPriority;
   review_state?: ReviewState;
   labels?: string[];
+  /** An empty string unassigns the task. */
+  assignee?: string;
+  /** RFC3339; an empty string clears the due date. */
+  due_at?: string;
 }
 
 export interface UpdateSubtaskPayload {
@@ -188,6 +300,9 @@ async function request<T>(path: string, init: RequestInit = {}): Promise<T> {
       if (typeof body.message === 'string' && body.message.length > 0) {
         message = body.message;
       }
+      if (body.suggestions && body.suggestions.length > 0) {
+        message = `${message} (try ${body.suggestions.join(', ')})`;
+      }
     } else {
       const text = await response.text();
       if (text.trim().length > 0) {
@@ -231,8 +346,14 @@ function withJsonBody(body: unknown): RequestInit {
   };
 }
 
-export async function listProjects(limit = 50, offset = 0): Promise<ProjectSummary[]> {
-  return request<ProjectSummary[]>(`/projects${toQuery({ limit, offset })}`);
+export async function listProjects(limit = 50, offset = 0, starred = false): Promise<ProjectSummary[]> {
+  return request<ProjectSummary[]>(`/projects${toQuery({ starred: starred ? 'true' : undefined, limit, offset })}`);
+}
+
+export async function setProjectStarred(project: string, starred: boolean): Promise<void> {
+  await request<{ project: string; starred: boolean }>(`/projects/${encodeURIComponent(project)}/star`, {
+    method: starred ? 'PUT' : 'DELETE',
+  });
 }
 
 export interface CreateProjectPayload {
@@ -248,12 +369,49 @@ export async function createProject(payload: CreateProjectPayload): Promise<Proj
   });
 }
 
+export interface ProjectSettings {
+  project_id: string;
+  review_checklist_required: boolean;
+  review_gate: string;
+  review_gate_scope: string;
+  auto_archive_after_days: number;
+  column_order: TaskStatus[];
+  column_colors: Partial<Record<TaskStatus, string>>;
+  enforce_dependencies: boolean;
+  timezone: string;
+  updated_at: string | null;
+}
+
+export async function getProjectSettings(project: string): Promise<ProjectSettings> {
+  return request<ProjectSettings>(`/projects/${encodeURIComponent(project)}/settings`);
+}
+
+export interface FacetValue {
+  value: string;
+  count: number;
+  display_name?: string;
+}
+
+export interface ProjectFacets {
+  labels: { items: FacetValue[]; total: number };
+  actors: { items: FacetValue[]; total: number };
+  priorities: FacetValue[];
+  limit: number;
+  offset: number;
+}
+
+export async function getProjectFacets(project: string, limit = 50, offset = 0): Promise<ProjectFacets> {
+  return request<ProjectFacets>(`/projects/${encodeURIComponent(project)}/facets${toQuery({ limit, offset })}`);
+}
+
 export async function listTasks(project: string, filters: ListTaskFilters = {}): Promise<TaskResponse[]> {
   return request<TaskResponse[]>(
     `/projects/${encodeURIComponent(project)}/tasks${toQuery({
       status: filters.status,
       label: filters.label,
       review_state: filters.review_state,
+      assignee: filters.assignee,
+      overdue: filters.overdue,
       limit: filters.limit ?? 100,
       offset: filters.offset ?? 0,
     })}`,
@@ -382,6 +540,39 @@ export function projectEventsPath(project: string): string {
   return `${API_BASE}/projects/${encodeURIComponent(project)}/events`;
 }
 
+export interface NotificationRecord {
+  id: string;
+  project: string;
+  task_id: string | null;
+  task_number: number | null;
+  task_display_key: string | null;
+  action: string;
+  actor: string;
+  reason: 'watching' | 'question' | 'review_requested' | 'review_completed' | 'assigned' | string;
+  detail: unknown;
+  read: boolean;
+  read_at: string | null;
+  created_at: string;
+}
+
+export async function listNotifications(unread = false, limit = 50, offset = 0): Promise<NotificationRecord[]> {
+  return request<NotificationRecord[]>(`/notifications${toQuery({ unread: unread ? 'true' : undefined, limit, offset })}`);
+}
+
+export async function getUnreadNotificationCount(): Promise<number> {
+  const response = await request<{ unread: number }>('/notifications/unread-count');
+  return response.unread;
+}
+
+export async function markAllNotificationsRead(): Promise<number> {
+  const response = await request<{ marked: number }>('/notifications/read-all', { method: 'POST' });
+  return response.marked;
+}
+
+export function notificationStreamPath(): string {
+  return `${API_BASE}/notifications/stream`;
+}
+
 export async function uploadAttachment(project: string, taskRef: string, file: File): Promise<AttachmentRecord> {
   const body = new FormData();
   body.append('file', file, file.name);
@@ -404,6 +595,22 @@ export async function deleteAttachment(project: string, taskRef: string, attachm
   );
 }
 
+export async function previewAttachment(attachmentId: string, maxKb?: number): Promise<AttachmentPreview> {
+  const query = maxKb === undefined ? '' : `?max_kb=${maxKb}`;
+  return request<AttachmentPreview>(`/files/${encodeURIComponent(attachmentId)}/preview${query}`);
+}
+
+export interface EventType {
+  name: string;
+  namespace: string;
+  description: string;
+  subscribable: boolean;
+}
+
+export async function listEventTypes(): Promise<EventType[]> {
+  return request<EventType[]>('/event-types');
+}
+
 export async function listWebhooks(project: string): Promise<WebhookResponse[]> {
   return request<WebhookResponse[]>(`/projects/${encodeURIComponent(project)}/webhooks`);
 }
@@ -415,6 +622,15 @@ export interface CreateWebhookPayload {
   events: string[];
   secret?: string;
   active?: boolean;
+  payload_format?: WebhookPayloadFormat;
+  schema_version?: number;
+  include_task_snapshot?: boolean;
+  quiet_hours?: string;
+  quiet_hours_timezone?: string;
+  max_deliveries_per_minute?: number;
+  timeout_secs?: number;
+  max_retries?: number;
+  retry_backoff_secs?: number;
 }
 
 export async function createWebhook(project: string, payload: CreateWebhookPayload): Promise<WebhookResponse> {
@@ -431,6 +647,15 @@ export interface UpdateWebhookPayload {
   events?: string[];
   secret?: string;
   active?: boolean;
+  payload_format?: WebhookPayloadFormat;
+  schema_version?: number;
+  include_task_snapshot?: boolean;
+  quiet_hours?: string;
+  quiet_hours_timezone?: string;
+  max_deliveries_per_minute?: number;
+  timeout_secs?: number | null;
+  max_retries?: number | null;
+  retry_backoff_secs?: number | null;
 }
 
 export async function updateWebhook(